@@ -19,6 +19,7 @@ extern crate proc_macro;
 mod attr;
 mod data;
 mod lens;
+mod persistent;
 
 use proc_macro::TokenStream;
 use syn::parse_macro_input;
@@ -43,3 +44,18 @@ pub fn derive_lens(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+/// Derives `Serialize` and `Deserialize` for a type that also derives
+/// `Data`, so that it can be saved with `druid::persist`.
+///
+/// Fields marked `#[data(ignore)]` are not saved; they are set to their
+/// `Default` value when the type is loaded. The expansion refers to
+/// `::serde`, so the crate using this attribute needs `serde` as a
+/// dependency.
+#[proc_macro_attribute]
+pub fn persistent(args: TokenStream, input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    persistent::persistent_impl(args.into(), input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
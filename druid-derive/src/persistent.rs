@@ -0,0 +1,59 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The implementation for #[persistent]
+
+use crate::attr::Field;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, spanned::Spanned, Data, Error};
+
+pub(crate) fn persistent_impl(
+    args: TokenStream,
+    mut input: syn::DeriveInput,
+) -> Result<TokenStream, Error> {
+    if !args.is_empty() {
+        return Err(Error::new(args.span(), "persistent takes no arguments"));
+    }
+
+    let fields: Vec<&mut syn::Field> = match &mut input.data {
+        Data::Struct(s) => s.fields.iter_mut().collect(),
+        Data::Enum(e) => e
+            .variants
+            .iter_mut()
+            .flat_map(|variant| variant.fields.iter_mut())
+            .collect(),
+        Data::Union(u) => {
+            return Err(Error::new(
+                u.union_token.span(),
+                "persistent cannot be used on unions",
+            ))
+        }
+    };
+
+    // a field that doesn't take part in `Data` doesn't belong in the file
+    // either.
+    for (i, field) in fields.into_iter().enumerate() {
+        if Field::parse_ast(field, i)?.ignore {
+            field.attrs.push(parse_quote!(#[serde(skip)]));
+        }
+    }
+
+    input.attrs.insert(
+        0,
+        parse_quote!(#[derive(::serde::Serialize, ::serde::Deserialize)]),
+    );
+    Ok(quote!(#input))
+}
@@ -15,6 +15,7 @@
 //! The top-level application type.
 
 use crate::clipboard::Clipboard;
+use crate::error::Error;
 use crate::platform::application as platform;
 
 /// A top-level handler that is not associated with any window.
@@ -36,6 +37,31 @@ pub trait AppHandler {
     fn command(&mut self, id: u32) {}
 }
 
+/// Whether the user prefers light or dark window contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorScheme {
+    /// Dark text on a light background.
+    Light,
+    /// Light text on a dark background.
+    Dark,
+}
+
+/// A sound that the system plays to get the user's attention.
+///
+/// Platforms without a distinct sound for each kind play their usual
+/// alert sound for all of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SystemSound {
+    /// The plain alert sound, or bell.
+    Beep,
+    /// The sound for an informational message, such as a finished task.
+    Information,
+    /// The sound for a warning.
+    Warning,
+    /// The sound for an error.
+    Error,
+}
+
 //TODO: we may want to make the user create an instance of this (Application::global()?)
 //but for now I'd like to keep changes minimal.
 /// The top level application object.
@@ -84,4 +110,35 @@ impl Application {
     pub fn get_locale() -> String {
         platform::Application::get_locale()
     }
+
+    /// Returns the color scheme the user has chosen for the system.
+    ///
+    /// Platforms that don't expose this setting report [`ColorScheme::Light`].
+    /// When the setting changes, open windows are notified through
+    /// [`WinHandler::color_scheme_changed`].
+    ///
+    /// [`ColorScheme::Light`]: enum.ColorScheme.html#variant.Light
+    /// [`WinHandler::color_scheme_changed`]: trait.WinHandler.html#method.color_scheme_changed
+    pub fn color_scheme() -> ColorScheme {
+        platform::Application::color_scheme()
+    }
+
+    /// Ask the system to play `sound`.
+    ///
+    /// This returns immediately; the sound is played in the background, and
+    /// may not be played at all if the user has turned off alert sounds.
+    pub fn play_sound(sound: SystemSound) {
+        platform::Application::play_sound(sound)
+    }
+
+    /// Make the font in `data`, the contents of a TrueType or OpenType font
+    /// file, available to this process by its family name.
+    ///
+    /// This should be called before any text is drawn, as text systems may
+    /// not look for new fonts once they have started. Fonts can't be loaded
+    /// on Windows yet, where this always returns an error, as the text
+    /// there only finds fonts that are installed.
+    pub fn load_font(data: &[u8]) -> Result<(), Error> {
+        platform::Application::load_font(data)
+    }
 }
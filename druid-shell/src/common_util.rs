@@ -39,6 +39,19 @@ pub fn strip_access_key(raw_menu_text: &str) -> String {
     result
 }
 
+/// Write font `data` to a new file in the temporary directory, for platforms
+/// that can only load a font from a file.
+///
+/// The file is left in place, as the font is read from it while it is used.
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+pub(crate) fn write_font_file(data: &[u8]) -> std::io::Result<std::path::PathBuf> {
+    static FONT_FILES: Counter = Counter::new();
+    let name = format!("druid-font-{}-{}", std::process::id(), FONT_FILES.next());
+    let path = std::env::temp_dir().join(name);
+    std::fs::write(&path, data)?;
+    Ok(path)
+}
+
 /// A trait for implementing the boxed callback hack.
 pub(crate) trait IdleCallback: Send {
     fn call(self: Box<Self>, a: &dyn Any);
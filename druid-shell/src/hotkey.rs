@@ -58,6 +58,7 @@ use crate::keycodes::KeyCode;
 pub struct HotKey {
     pub(crate) mods: RawMods,
     pub(crate) key: KeyCompare,
+    repeats: bool,
 }
 
 /// Something that can be compared with a keyboard key.
@@ -99,10 +100,30 @@ impl HotKey {
         HotKey {
             mods: mods.into().unwrap_or(RawMods::None),
             key: key.into(),
+            repeats: true,
         }
         .warn_if_needed()
     }
 
+    /// Builder-style method to only match the first press of the key, and
+    /// not the repeats sent while it is held down.
+    ///
+    /// # Examples
+    /// ```
+    /// use druid_shell::{HotKey, KeyEvent, KeyCode, RawMods};
+    ///
+    /// let toggle = HotKey::new(None, KeyCode::Space).ignoring_repeats();
+    /// let mut event = KeyEvent::for_test(RawMods::None, " ", KeyCode::Space);
+    /// assert!(toggle.matches(event));
+    ///
+    /// event.is_repeat = true;
+    /// assert!(!toggle.matches(event));
+    /// ```
+    pub fn ignoring_repeats(mut self) -> Self {
+        self.repeats = false;
+        self
+    }
+
     //TODO: figure out if we need to be normalizing case or something? This requires
     //correctly documenting the expected behaviour of `unmod_text`.
     fn warn_if_needed(self) -> Self {
@@ -125,6 +146,7 @@ impl HotKey {
     pub fn matches(&self, event: impl Borrow<KeyEvent>) -> bool {
         let event = event.borrow();
         self.mods == event.mods
+            && (self.repeats || !event.is_repeat)
             && match self.key {
                 KeyCompare::Code(code) => code == event.key_code,
                 KeyCompare::Text(text) => Some(text) == event.text(),
@@ -22,7 +22,14 @@ use std::fmt;
 pub struct KeyEvent {
     /// The platform independent keycode.
     pub key_code: KeyCode,
-    /// Whether or not this event is a repeat (the key was held down)
+    /// Whether or not this event is a repeat (the key was held down).
+    ///
+    /// While a key is held, the platform sends further key down events at
+    /// the rate the user has configured, with this set. Widgets that act
+    /// once per press, like a checkbox, should ignore these; see also
+    /// [`HotKey::ignoring_repeats`].
+    ///
+    /// [`HotKey::ignoring_repeats`]: struct.HotKey.html#method.ignoring_repeats
     pub is_repeat: bool,
     /// The modifiers for this event.
     pub mods: KeyModifiers,
@@ -84,6 +91,26 @@ impl KeyEvent {
         }
     }
 
+    /// Create a `KeyEvent` from its parts, such as when replaying a
+    /// recorded event.
+    ///
+    /// Text longer than 15 bytes is truncated.
+    pub fn from_parts(
+        key_code: KeyCode,
+        is_repeat: bool,
+        mods: KeyModifiers,
+        text: &str,
+        unmodified_text: &str,
+    ) -> Self {
+        KeyEvent {
+            key_code,
+            is_repeat,
+            mods,
+            text: TinyStr::truncating(text),
+            unmodified_text: TinyStr::truncating(unmodified_text),
+        }
+    }
+
     /// For creating `KeyEvent`s during testing.
     #[doc(hidden)]
     pub fn for_test(mods: impl Into<KeyModifiers>, text: &'static str, code: KeyCode) -> Self {
@@ -155,6 +182,15 @@ impl TinyStr {
         }
     }
 
+    /// Like `new`, but silently truncates strings that don't fit.
+    fn truncating(s: &str) -> Self {
+        let mut len = s.len().min(TINY_STR_CAPACITY);
+        while !s.is_char_boundary(len) {
+            len -= 1;
+        }
+        TinyStr::new(&s[..len])
+    }
+
     fn as_str(&self) -> &str {
         unsafe { std::str::from_utf8_unchecked(&self.buf[..self.len as usize]) }
     }
@@ -164,4 +164,123 @@ impl KeyCode {
             _ => false,
         }
     }
+
+    /// Look up a key code by name, the inverse of its `Debug` representation.
+    ///
+    /// Unknown codes are written as `Unknown(n)`, where `n` is the raw code
+    /// of the current platform; they are only meaningful on that platform.
+    pub fn from_name(name: &str) -> Option<KeyCode> {
+        use KeyCode::*;
+        let code = match name {
+            "Escape" => Escape,
+            "Backtick" => Backtick,
+            "Key0" => Key0,
+            "Key1" => Key1,
+            "Key2" => Key2,
+            "Key3" => Key3,
+            "Key4" => Key4,
+            "Key5" => Key5,
+            "Key6" => Key6,
+            "Key7" => Key7,
+            "Key8" => Key8,
+            "Key9" => Key9,
+            "Minus" => Minus,
+            "Equals" => Equals,
+            "Backspace" => Backspace,
+            "Tab" => Tab,
+            "KeyQ" => KeyQ,
+            "KeyW" => KeyW,
+            "KeyE" => KeyE,
+            "KeyR" => KeyR,
+            "KeyT" => KeyT,
+            "KeyY" => KeyY,
+            "KeyU" => KeyU,
+            "KeyI" => KeyI,
+            "KeyO" => KeyO,
+            "KeyP" => KeyP,
+            "LeftBracket" => LeftBracket,
+            "RightBracket" => RightBracket,
+            "Return" => Return,
+            "KeyA" => KeyA,
+            "KeyS" => KeyS,
+            "KeyD" => KeyD,
+            "KeyF" => KeyF,
+            "KeyG" => KeyG,
+            "KeyH" => KeyH,
+            "KeyJ" => KeyJ,
+            "KeyK" => KeyK,
+            "KeyL" => KeyL,
+            "Semicolon" => Semicolon,
+            "Quote" => Quote,
+            "Backslash" => Backslash,
+            "KeyZ" => KeyZ,
+            "KeyX" => KeyX,
+            "KeyC" => KeyC,
+            "KeyV" => KeyV,
+            "KeyB" => KeyB,
+            "KeyN" => KeyN,
+            "KeyM" => KeyM,
+            "Comma" => Comma,
+            "Period" => Period,
+            "Slash" => Slash,
+            "LeftControl" => LeftControl,
+            "RightControl" => RightControl,
+            "LeftAlt" => LeftAlt,
+            "RightAlt" => RightAlt,
+            "LeftShift" => LeftShift,
+            "RightShift" => RightShift,
+            "LeftMeta" => LeftMeta,
+            "RightMeta" => RightMeta,
+            "Space" => Space,
+            "CapsLock" => CapsLock,
+            "F1" => F1,
+            "F2" => F2,
+            "F3" => F3,
+            "F4" => F4,
+            "F5" => F5,
+            "F6" => F6,
+            "F7" => F7,
+            "F8" => F8,
+            "F9" => F9,
+            "F10" => F10,
+            "F11" => F11,
+            "F12" => F12,
+            "PrintScreen" => PrintScreen,
+            "ScrollLock" => ScrollLock,
+            "Pause" => Pause,
+            "Insert" => Insert,
+            "Delete" => Delete,
+            "Home" => Home,
+            "End" => End,
+            "PageUp" => PageUp,
+            "PageDown" => PageDown,
+            "Numpad0" => Numpad0,
+            "Numpad1" => Numpad1,
+            "Numpad2" => Numpad2,
+            "Numpad3" => Numpad3,
+            "Numpad4" => Numpad4,
+            "Numpad5" => Numpad5,
+            "Numpad6" => Numpad6,
+            "Numpad7" => Numpad7,
+            "Numpad8" => Numpad8,
+            "Numpad9" => Numpad9,
+            "NumpadEquals" => NumpadEquals,
+            "NumpadSubtract" => NumpadSubtract,
+            "NumpadAdd" => NumpadAdd,
+            "NumpadDecimal" => NumpadDecimal,
+            "NumpadMultiply" => NumpadMultiply,
+            "NumpadDivide" => NumpadDivide,
+            "NumLock" => NumLock,
+            "NumpadEnter" => NumpadEnter,
+            "ArrowUp" => ArrowUp,
+            "ArrowDown" => ArrowDown,
+            "ArrowLeft" => ArrowLeft,
+            "ArrowRight" => ArrowRight,
+            _ => {
+                let raw = name.strip_prefix("Unknown(")?.strip_suffix(')')?;
+                Unknown(raw.parse().ok()?)
+            }
+        };
+        Some(code)
+    }
 }
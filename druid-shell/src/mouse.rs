@@ -79,4 +79,14 @@ pub enum Cursor {
     NotAllowed,
     ResizeLeftRight,
     ResizeUpDown,
+    /// A diagonal resize cursor, for the top left and bottom right corners.
+    ///
+    /// macOS has no public diagonal cursor; a private one is used where it
+    /// exists, and a crosshair otherwise.
+    ResizeUpLeftDownRight,
+    /// A diagonal resize cursor, for the top right and bottom left corners.
+    ///
+    /// macOS has no public diagonal cursor; a private one is used where it
+    /// exists, and a crosshair otherwise.
+    ResizeUpRightDownLeft,
 }
@@ -15,14 +15,26 @@
 //! GTK implementation of features at the application scope.
 
 use std::cell::RefCell;
+use std::ffi::{c_void, CString};
+use std::os::raw::{c_char, c_int};
+use std::os::unix::ffi::OsStringExt;
+use std::ptr;
 
 use gio::prelude::ApplicationExtManual;
 use gio::{ApplicationExt, ApplicationFlags, Cancellable};
-use gtk::{Application as GtkApplication, GtkApplicationExt};
+use gtk::{Application as GtkApplication, GtkApplicationExt, SettingsExt};
 
 use super::clipboard::Clipboard;
 use super::util;
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme, SystemSound};
+use crate::common_util;
+use crate::error::Error;
+
+// cairo and pango find their fonts with fontconfig.
+#[link(name = "fontconfig")]
+extern "C" {
+    fn FcConfigAppFontAddFile(config: *mut c_void, file: *const c_char) -> c_int;
+}
 
 // XXX: The application needs to be global because WindowBuilder::build wants
 // to construct an ApplicationWindow, which needs the application, but
@@ -94,6 +106,46 @@ impl Application {
         //TODO ahem
         "en-US".into()
     }
+
+    pub fn color_scheme() -> ColorScheme {
+        let settings = match gtk::Settings::get_default() {
+            Some(settings) => settings,
+            None => return ColorScheme::Light,
+        };
+        let prefers_dark = settings.get_property_gtk_application_prefer_dark_theme();
+        let theme_is_dark = settings
+            .get_property_gtk_theme_name()
+            .map(|name| name.to_lowercase().ends_with("-dark"))
+            .unwrap_or(false);
+        if prefers_dark || theme_is_dark {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
+
+    /// GTK only has the bell, so every sound is the same.
+    pub fn play_sound(_sound: SystemSound) {
+        match gdk::Display::get_default() {
+            Some(display) => display.beep(),
+            None => log::warn!("failed to beep: no default display"),
+        }
+    }
+
+    /// Fontconfig can only add a font from a file, so the font is written
+    /// to a temporary one first.
+    pub fn load_font(data: &[u8]) -> Result<(), Error> {
+        let file = common_util::write_font_file(data)
+            .map_err(|_| Error::Other("failed to write the font to a file"))?;
+        let path = CString::new(file.clone().into_os_string().into_vec())
+            .map_err(|_| Error::Other("the font's file name has a nul byte"))?;
+        // a null config is the one fontconfig is currently using.
+        if unsafe { FcConfigAppFontAddFile(ptr::null_mut(), path.as_ptr()) } == 0 {
+            let _ = std::fs::remove_file(&file);
+            return Err(Error::Other("fontconfig failed to load the font"));
+        }
+        Ok(())
+    }
 }
 
 #[inline]
@@ -21,19 +21,22 @@ use std::ffi::c_void;
 use std::ffi::OsString;
 use std::os::raw::{c_int, c_uint};
 use std::ptr;
+use std::rc::Rc;
 use std::slice;
 use std::sync::{Arc, Mutex, Weak};
 use std::time::Instant;
 
-use gdk::{EventKey, EventMask, ModifierType, ScrollDirection, WindowExt};
+use gdk::{
+    EventKey, EventMask, ModifierType, ScrollDirection, WindowExt, WindowState as GdkWindowState,
+};
 use gio::ApplicationExt;
 use gtk::prelude::*;
 use gtk::{AccelGroup, ApplicationWindow};
 
-use crate::kurbo::{Point, Size, Vec2};
+use crate::kurbo::{Point, Rect, Size, Vec2};
 use crate::piet::{Piet, RenderContext};
 
-use super::application::with_application;
+use super::application::{with_application, Application};
 use super::dialog;
 use super::menu::Menu;
 use super::util::assert_main_thread;
@@ -87,6 +90,7 @@ pub struct WindowBuilder {
     menu: Option<Menu>,
     size: Size,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     resizable: bool,
     show_titlebar: bool,
 }
@@ -108,6 +112,9 @@ pub(crate) struct WindowState {
     pub(crate) handler: RefCell<Box<dyn WinHandler>>,
     idle_queue: Arc<Mutex<Vec<IdleKind>>>,
     current_keyval: RefCell<Option<u32>>,
+    /// Gestures aren't owned by the widget they're attached to.
+    gestures: RefCell<Vec<gtk::Gesture>>,
+    drawing_area: gtk::DrawingArea,
 }
 
 impl WindowBuilder {
@@ -118,6 +125,7 @@ impl WindowBuilder {
             menu: None,
             size: Size::new(500.0, 400.0),
             min_size: None,
+            max_size: None,
             resizable: true,
             show_titlebar: true,
         }
@@ -135,6 +143,10 @@ impl WindowBuilder {
         self.min_size = Some(size);
     }
 
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = Some(size);
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -181,11 +193,15 @@ impl WindowBuilder {
         let vbox = gtk::Box::new(gtk::Orientation::Vertical, 0);
         window.add(&vbox);
 
+        let drawing_area = gtk::DrawingArea::new();
+
         let win_state = Arc::new(WindowState {
             window,
             handler: RefCell::new(handler),
             idle_queue: Arc::new(Mutex::new(vec![])),
             current_keyval: RefCell::new(None),
+            gestures: RefCell::new(Vec::new()),
+            drawing_area: drawing_area.clone(),
         });
 
         with_application(|app| {
@@ -201,13 +217,13 @@ impl WindowBuilder {
             state: Arc::downgrade(&win_state),
         };
 
+        let mut menu_height = 0;
         if let Some(menu) = self.menu {
             let menu = menu.into_gtk_menubar(&handle, &accel_group);
             vbox.pack_start(&menu, false, false, 0);
+            menu_height = menu.get_preferred_height().1;
         }
 
-        let drawing_area = gtk::DrawingArea::new();
-
         drawing_area.set_events(
             EventMask::EXPOSURE_MASK
                 | EventMask::POINTER_MOTION_MASK
@@ -218,7 +234,8 @@ impl WindowBuilder {
                 | EventMask::ENTER_NOTIFY_MASK
                 | EventMask::KEY_RELEASE_MASK
                 | EventMask::SCROLL_MASK
-                | EventMask::SMOOTH_SCROLL_MASK,
+                | EventMask::SMOOTH_SCROLL_MASK
+                | EventMask::TOUCH_MASK,
         );
 
         drawing_area.set_can_focus(true);
@@ -237,18 +254,44 @@ impl WindowBuilder {
             );
         }
 
+        if let Some(max_size) = self.max_size {
+            // GTK ignores the geometry widget, so the hints are for the whole
+            // window, and the maximum also has to fit the menu bar.
+            let geometry = gdk::Geometry {
+                min_width: -1,
+                min_height: -1,
+                max_width: (max_size.width * dpi_scale) as i32,
+                max_height: (max_size.height * dpi_scale) as i32 + menu_height,
+                base_width: -1,
+                base_height: -1,
+                width_inc: -1,
+                height_inc: -1,
+                min_aspect: 0.0,
+                max_aspect: 0.0,
+                win_gravity: gdk::Gravity::NorthWest,
+            };
+            win_state.window.set_geometry_hints(
+                None::<&gtk::Widget>,
+                Some(&geometry),
+                gdk::WindowHints::MAX_SIZE,
+            );
+        }
+
         let last_size = Cell::new((0, 0));
 
         drawing_area.connect_draw(clone!(handle => move |widget, context| {
             if let Some(state) = handle.state.upgrade() {
 
                 let extents = context.clip_extents();
+                let invalid_rect = Rect::new(extents.0, extents.1, extents.2, extents.3);
                 let dpi_scale = state.window.get_window()
                     .map(|w| w.get_display().get_default_screen().get_resolution())
                     .unwrap_or(96.0) / 96.0;
+                // the clip may only cover part of the window, so we use the
+                // allocation to determine the size.
                 let size = (
-                    ((extents.2 - extents.0) * dpi_scale) as u32,
-                    ((extents.3 - extents.1) * dpi_scale) as u32,
+                    (f64::from(widget.get_allocated_width()) * dpi_scale) as u32,
+                    (f64::from(widget.get_allocated_height()) * dpi_scale) as u32,
                 );
 
                 if last_size.get() != size {
@@ -262,7 +305,7 @@ impl WindowBuilder {
 
                 if let Ok(mut handler_borrow) = state.handler.try_borrow_mut() {
                     let anim = handler_borrow
-                        .paint(&mut piet_context);
+                        .paint_rect(&mut piet_context, invalid_rect);
                     if let Err(e) = piet_context.finish() {
                         eprintln!("piet error on render: {:?}", e);
                     }
@@ -401,6 +444,29 @@ impl WindowBuilder {
             Inhibit(true)
         }));
 
+        // gtk reports the scale since the start of the gesture, where the
+        // handler wants the change since the last event.
+        let zoom = gtk::GestureZoom::new(&drawing_area);
+        let last_zoom = Rc::new(Cell::new(None));
+        zoom.connect_begin(clone!(last_zoom => move |gesture, _| {
+            let center = gesture.get_bounding_box_center().map(Point::from);
+            last_zoom.set(center.map(|center| (1.0, center)));
+        }));
+        zoom.connect_scale_changed(clone!(handle, last_zoom => move |gesture, scale| {
+            if let Some(state) = handle.state.upgrade() {
+                let center = gesture.get_bounding_box_center().map(Point::from);
+                if let (Some((last_scale, last_center)), Some(center)) = (last_zoom.get(), center) {
+                    let mut handler = state.handler.borrow_mut();
+                    if center != last_center {
+                        handler.pan(center - last_center, center);
+                    }
+                    handler.pinch(scale / last_scale, center);
+                    last_zoom.set(Some((scale, center)));
+                }
+            }
+        }));
+        win_state.gestures.borrow_mut().push(zoom.upcast());
+
         drawing_area.connect_key_press_event(clone!(handle => move |_widget, key| {
             if let Some(state) = handle.state.upgrade() {
 
@@ -428,12 +494,72 @@ impl WindowBuilder {
             Inhibit(true)
         }));
 
+        win_state
+            .window
+            .connect_window_state_event(clone!(handle => move |_widget, event| {
+                if let Some(state) = handle.state.upgrade() {
+                    let iconified = event.get_new_window_state().contains(GdkWindowState::ICONIFIED);
+                    state.handler.borrow_mut().visibility_changed(!iconified);
+                }
+                Inhibit(false)
+            }));
+
+        win_state
+            .window
+            .connect_focus_in_event(clone!(handle => move |_widget, _event| {
+                if let Some(state) = handle.state.upgrade() {
+                    state.handler.borrow_mut().got_focus();
+                }
+                Inhibit(false)
+            }));
+
+        win_state
+            .window
+            .connect_focus_out_event(clone!(handle => move |_widget, _event| {
+                if let Some(state) = handle.state.upgrade() {
+                    state.handler.borrow_mut().lost_focus();
+                }
+                Inhibit(false)
+            }));
+
+        win_state
+            .window
+            .connect_delete_event(clone!(handle => move |_widget, _event| {
+                match handle.state.upgrade() {
+                    Some(state) => Inhibit(!state.handler.borrow_mut().request_close()),
+                    None => Inhibit(false),
+                }
+            }));
+
         drawing_area.connect_destroy(clone!(handle => move |_widget| {
             if let Some(state) = handle.state.upgrade() {
                 state.handler.borrow_mut().destroy();
             }
         }));
 
+        // the color scheme follows these settings, which the user can change
+        // while we run.
+        if let Some(settings) = gtk::Settings::get_default() {
+            let handle = handle.clone();
+            let notify = move |_settings: &gtk::Settings| {
+                if let Some(state) = handle.state.upgrade() {
+                    if let Ok(mut handler) = state.handler.try_borrow_mut() {
+                        handler.color_scheme_changed(Application::color_scheme());
+                    }
+                }
+            };
+            let signals = Cell::new(vec![
+                settings.connect_property_gtk_application_prefer_dark_theme_notify(notify.clone()),
+                settings.connect_property_gtk_theme_name_notify(notify),
+            ]);
+            // the settings outlive the window.
+            win_state.window.connect_destroy(move |_widget| {
+                for signal in signals.take() {
+                    settings.disconnect(signal);
+                }
+            });
+        }
+
         vbox.pack_end(&drawing_area, true, true, 0);
         drawing_area.realize();
         drawing_area
@@ -463,6 +589,27 @@ impl WindowHandle {
         }
     }
 
+    #[cfg(feature = "platform-ext")]
+    pub fn raw_gtk_window(&self) -> Option<ApplicationWindow> {
+        self.state.upgrade().map(|state| state.window.clone())
+    }
+
+    pub fn set_size(&self, size: Size) {
+        if let Some(state) = self.state.upgrade() {
+            let scale = f64::from(self.get_dpi()) / 96.0;
+            let mut width = (size.width * scale) as i32;
+            let mut height = (size.height * scale) as i32;
+            if let Some(window) = state.window.get_window() {
+                if let Some(monitor) = window.get_display().get_monitor_at_window(&window) {
+                    let area = monitor.get_workarea();
+                    width = width.min(area.width);
+                    height = height.min(area.height);
+                }
+            }
+            state.window.resize(width, height);
+        }
+    }
+
     pub fn show_titlebar(&self, show_titlebar: bool) {
         if let Some(state) = self.state.upgrade() {
             state.window.set_decorated(show_titlebar)
@@ -491,6 +638,19 @@ impl WindowHandle {
         }
     }
 
+    pub fn invalidate_rect(&self, rect: Rect) {
+        if let Some(state) = self.state.upgrade() {
+            // the drawing area starts below the menu bar, as our coordinates do.
+            let rect = rect.expand();
+            state.drawing_area.queue_draw_area(
+                rect.x0 as i32,
+                rect.y0 as i32,
+                rect.width() as i32,
+                rect.height() as i32,
+            );
+        }
+    }
+
     pub fn text(&self) -> Text {
         Text::new()
     }
@@ -711,6 +871,8 @@ fn make_gdk_cursor(cursor: &Cursor, gdk_window: &gdk::Window) -> Option<gdk::Cur
             Cursor::NotAllowed => "not-allowed",
             Cursor::ResizeLeftRight => "ew-resize",
             Cursor::ResizeUpDown => "ns-resize",
+            Cursor::ResizeUpLeftDownRight => "nwse-resize",
+            Cursor::ResizeUpRightDownLeft => "nesw-resize",
         },
     )
 }
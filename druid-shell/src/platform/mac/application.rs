@@ -20,16 +20,32 @@ use std::ffi::c_void;
 
 use super::clipboard::Clipboard;
 use super::util;
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme, SystemSound};
+use crate::common_util;
+use crate::error::Error;
 
 use cocoa::appkit::{NSApp, NSApplication, NSApplicationActivationPolicyRegular};
-use cocoa::base::{id, nil, YES};
+use cocoa::base::{id, nil, BOOL, YES};
 use cocoa::foundation::NSAutoreleasePool;
 use objc::declare::ClassDecl;
 use objc::runtime::{Class, Object, Sel};
 
 static APP_HANDLER_IVAR: &str = "druidAppHandler";
 
+#[link(name = "AppKit", kind = "framework")]
+extern "C" {
+    fn NSBeep();
+}
+
+// the font is only registered for this process.
+const CT_FONT_MANAGER_SCOPE_PROCESS: u32 = 1;
+
+#[link(name = "CoreText", kind = "framework")]
+extern "C" {
+    // the URL is a CFURLRef, which an NSURL is bridged to.
+    fn CTFontManagerRegisterFontsForURL(font_url: id, scope: u32, error: *mut id) -> u8;
+}
+
 pub struct Application {
     ns_app: id,
 }
@@ -96,6 +112,77 @@ impl Application {
             locale
         }
     }
+
+    pub fn color_scheme() -> ColorScheme {
+        unsafe {
+            // the app's appearance follows the system's from macOS 10.14, and
+            // is the one that windows are notified about when it changes.
+            let app = NSApp();
+            let has_appearance: BOOL =
+                msg_send![app, respondsToSelector: sel!(effectiveAppearance)];
+            if has_appearance == YES {
+                let appearance: id = msg_send![app, effectiveAppearance];
+                let name: id = msg_send![appearance, name];
+                return if name != nil && util::from_nsstring(name).contains("Dark") {
+                    ColorScheme::Dark
+                } else {
+                    ColorScheme::Light
+                };
+            }
+            let defaults: id = msg_send![class!(NSUserDefaults), standardUserDefaults];
+            let key = util::make_nsstring("AppleInterfaceStyle");
+            let style: id = msg_send![defaults, stringForKey: key];
+            if style != nil && util::from_nsstring(style) == "Dark" {
+                ColorScheme::Dark
+            } else {
+                ColorScheme::Light
+            }
+        }
+    }
+
+    pub fn play_sound(sound: SystemSound) {
+        // these ship with every version of macOS.
+        let name = match sound {
+            SystemSound::Beep => None,
+            SystemSound::Information => Some("Glass"),
+            SystemSound::Warning => Some("Funk"),
+            SystemSound::Error => Some("Basso"),
+        };
+        unsafe {
+            if let Some(name) = name {
+                let sound: id = msg_send![class!(NSSound), soundNamed: util::make_nsstring(name)];
+                if sound != nil {
+                    let _: BOOL = msg_send![sound, play];
+                    return;
+                }
+            }
+            NSBeep();
+        }
+    }
+
+    /// Core Text can register a font from a file, which cairo then finds
+    /// by name, so the font is written to a temporary one first.
+    pub fn load_font(data: &[u8]) -> Result<(), Error> {
+        let file = common_util::write_font_file(data)
+            .map_err(|_| Error::Other("failed to write the font to a file"))?;
+        let path = file
+            .to_str()
+            .ok_or(Error::Other("the font's file name isn't UTF-8"))?;
+        unsafe {
+            let _pool = NSAutoreleasePool::new(nil);
+            let url: id = msg_send![class!(NSURL), fileURLWithPath: util::make_nsstring(path)];
+            let registered = CTFontManagerRegisterFontsForURL(
+                url,
+                CT_FONT_MANAGER_SCOPE_PROCESS,
+                std::ptr::null_mut(),
+            );
+            if registered == 0 {
+                let _ = std::fs::remove_file(&file);
+                return Err(Error::Other("Core Text failed to register the font"));
+            }
+        }
+        Ok(())
+    }
 }
 
 struct DelegateState {
@@ -24,7 +24,7 @@ use std::time::Instant;
 
 use cocoa::appkit::{
     CGFloat, NSApp, NSApplication, NSAutoresizingMaskOptions, NSBackingStoreBuffered, NSEvent,
-    NSEventModifierFlags, NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindow,
+    NSEventModifierFlags, NSScreen, NSView, NSViewHeightSizable, NSViewWidthSizable, NSWindow,
     NSWindowStyleMask,
 };
 use cocoa::base::{id, nil, BOOL, NO, YES};
@@ -36,9 +36,10 @@ use objc::runtime::{Class, Object, Sel};
 use cairo::{Context, QuartzSurface};
 use log::{error, info};
 
-use crate::kurbo::{Point, Size, Vec2};
+use crate::kurbo::{Point, Rect, Size, Vec2};
 use crate::piet::{Piet, RenderContext};
 
+use super::application::Application;
 use super::dialog;
 use super::menu::Menu;
 use super::util::{assert_main_thread, make_nsstring};
@@ -53,6 +54,9 @@ use crate::Error;
 #[allow(non_upper_case_globals)]
 const NSWindowDidBecomeKeyNotification: &str = "NSWindowDidBecomeKeyNotification";
 
+/// The key path of `NSApp` that changes with the system's color scheme.
+const APPEARANCE_KEY_PATH: &str = "effectiveAppearance";
+
 #[derive(Clone)]
 pub(crate) struct WindowHandle {
     /// This is an NSView, as our concept of "window" is more the top-level container holding
@@ -77,6 +81,7 @@ pub(crate) struct WindowBuilder {
     menu: Option<Menu>,
     size: Size,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     resizable: bool,
     show_titlebar: bool,
 }
@@ -109,6 +114,7 @@ impl WindowBuilder {
             menu: None,
             size: Size::new(500.0, 400.0),
             min_size: None,
+            max_size: None,
             resizable: true,
             show_titlebar: true,
         }
@@ -126,6 +132,10 @@ impl WindowBuilder {
         self.min_size = Some(size);
     }
 
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = Some(size);
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -171,6 +181,11 @@ impl WindowBuilder {
                 window.setContentMinSize_(size);
             }
 
+            if let Some(max_size) = self.max_size {
+                let size = NSSize::new(max_size.width, max_size.height);
+                window.setContentMaxSize_(size);
+            }
+
             window.cascadeTopLeftFromPoint_(NSPoint::new(20.0, 20.0));
             window.setTitle_(make_nsstring(&self.title));
             // TODO: this should probably be a tracking area instead
@@ -188,6 +203,7 @@ impl WindowBuilder {
             }
 
             content_view.addSubview_(view);
+            observe_appearance(view, true);
             let view_state: *mut c_void = *(*view).get_ivar("viewState");
             let view_state = &mut *(view_state as *mut ViewState);
             let handle = WindowHandle {
@@ -240,6 +256,14 @@ lazy_static! {
             sel!(windowDidBecomeKey:),
             window_did_become_key as extern "C" fn(&mut Object, Sel, id),
         );
+        decl.add_method(
+            sel!(windowDidChangeOcclusionState:),
+            window_did_change_occlusion_state as extern "C" fn(&mut Object, Sel, id),
+        );
+        decl.add_method(
+            sel!(windowDidResignKey:),
+            window_did_resign_key as extern "C" fn(&mut Object, Sel, id),
+        );
         decl.add_method(
             sel!(setFrameSize:),
             set_frame_size as extern "C" fn(&mut Object, Sel, NSSize),
@@ -303,10 +327,18 @@ lazy_static! {
             sel!(showContextMenu:),
             show_context_menu as extern "C" fn(&mut Object, Sel, id),
         );
+        decl.add_method(
+            sel!(windowShouldClose:),
+            window_should_close as extern "C" fn(&mut Object, Sel, id) -> BOOL,
+        );
         decl.add_method(
             sel!(windowWillClose:),
             window_will_close as extern "C" fn(&mut Object, Sel, id),
         );
+        decl.add_method(
+            sel!(observeValueForKeyPath:ofObject:change:context:),
+            observe_value as extern "C" fn(&mut Object, Sel, id, id, id, *mut c_void),
+        );
         ViewClass(decl.register())
     };
 }
@@ -452,7 +484,10 @@ extern "C" fn pinch_event(this: &mut Object, _: Sel, nsevent: id) {
         let view_state = &mut *(view_state as *mut ViewState);
 
         let delta: CGFloat = msg_send![nsevent, magnification];
-        (*view_state).handler.zoom(delta as f64);
+        let point = nsevent.locationInWindow();
+        let view_point = (this as id).convertPoint_fromView_(point, nil);
+        let center = Point::new(view_point.x as f64, view_point.y as f64);
+        (*view_state).handler.pinch(1.0 + delta as f64, center);
     }
 }
 
@@ -509,7 +544,14 @@ extern "C" fn draw_rect(this: &mut Object, _: Sel, dirtyRect: NSRect) {
         let mut piet_ctx = Piet::new(&mut cairo_ctx);
         let view_state: *mut c_void = *this.get_ivar("viewState");
         let view_state = &mut *(view_state as *mut ViewState);
-        let anim = (*view_state).handler.paint(&mut piet_ctx);
+        // our view is flipped, so the dirty rect's origin is the top left.
+        let invalid_rect = Rect::from_origin_size(
+            (dirtyRect.origin.x, dirtyRect.origin.y),
+            (dirtyRect.size.width, dirtyRect.size.height),
+        );
+        let anim = (*view_state)
+            .handler
+            .paint_rect(&mut piet_ctx, invalid_rect);
         if let Err(e) = piet_ctx.finish() {
             error!("{}", e)
         }
@@ -590,14 +632,87 @@ extern "C" fn window_did_become_key(this: &mut Object, _: Sel, _notification: id
     }
 }
 
+extern "C" fn window_did_change_occlusion_state(this: &mut Object, _: Sel, _notification: id) {
+    // NSWindowOcclusionStateVisible
+    const OCCLUSION_STATE_VISIBLE: u64 = 1 << 1;
+    unsafe {
+        let window: id = msg_send![this as *const _, window];
+        let state: u64 = msg_send![window, occlusionState];
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        (*view_state)
+            .handler
+            .visibility_changed(state & OCCLUSION_STATE_VISIBLE != 0);
+    }
+}
+
+extern "C" fn window_did_resign_key(this: &mut Object, _: Sel, _notification: id) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        (*view_state).handler.lost_focus();
+    }
+}
+
+extern "C" fn window_should_close(this: &mut Object, _: Sel, _window: id) -> BOOL {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        if (*view_state).handler.request_close() {
+            YES
+        } else {
+            NO
+        }
+    }
+}
+
 extern "C" fn window_will_close(this: &mut Object, _: Sel, _window: id) {
     unsafe {
+        observe_appearance(this, false);
         let view_state: *mut c_void = *this.get_ivar("viewState");
         let view_state = &mut *(view_state as *mut ViewState);
         (*view_state).handler.destroy();
     }
 }
 
+/// Start or stop observing the app's appearance, which changes when the
+/// user switches between the light and dark color schemes.
+///
+/// Before macOS 10.14 there is no appearance to observe, and the system
+/// doesn't have a dark color scheme.
+unsafe fn observe_appearance(view: *mut Object, observe: bool) {
+    let app = NSApp();
+    let has_appearance: BOOL = msg_send![app, respondsToSelector: sel!(effectiveAppearance)];
+    if has_appearance == NO {
+        return;
+    }
+    let key_path = make_nsstring(APPEARANCE_KEY_PATH);
+    if observe {
+        let options: u64 = 0;
+        let () =
+            msg_send![app, addObserver: view forKeyPath: key_path options: options context: nil];
+    } else {
+        let () = msg_send![app, removeObserver: view forKeyPath: key_path];
+    }
+}
+
+extern "C" fn observe_value(
+    this: &mut Object,
+    _: Sel,
+    _key_path: id,
+    _object: id,
+    _change: id,
+    _context: *mut c_void,
+) {
+    unsafe {
+        let view_state: *mut c_void = *this.get_ivar("viewState");
+        let view_state = &mut *(view_state as *mut ViewState);
+        (*view_state)
+            .handler
+            .color_scheme_changed(Application::color_scheme());
+    }
+}
+
 impl WindowHandle {
     pub fn show(&self) {
         unsafe {
@@ -637,6 +752,16 @@ impl WindowHandle {
         }
     }
 
+    pub fn invalidate_rect(&self, rect: Rect) {
+        let rect = NSRect::new(
+            NSPoint::new(rect.x0, rect.y0),
+            NSSize::new(rect.width(), rect.height()),
+        );
+        unsafe {
+            let () = msg_send![*self.nsview.load(), setNeedsDisplayInRect: rect];
+        }
+    }
+
     pub fn set_cursor(&mut self, cursor: &Cursor) {
         unsafe {
             let nscursor = class!(NSCursor);
@@ -648,6 +773,12 @@ impl WindowHandle {
                 Cursor::NotAllowed => msg_send![nscursor, operationNotAllowedCursor],
                 Cursor::ResizeLeftRight => msg_send![nscursor, resizeLeftRightCursor],
                 Cursor::ResizeUpDown => msg_send![nscursor, resizeUpDownCursor],
+                Cursor::ResizeUpLeftDownRight => {
+                    diagonal_cursor(sel!(_windowResizeNorthWestSouthEastCursor))
+                }
+                Cursor::ResizeUpRightDownLeft => {
+                    diagonal_cursor(sel!(_windowResizeNorthEastSouthWestCursor))
+                }
             };
             let () = msg_send![cursor, set];
         }
@@ -708,6 +839,31 @@ impl WindowHandle {
         }
     }
 
+    #[cfg(feature = "platform-ext")]
+    pub fn raw_ns_view(&self) -> Option<id> {
+        // the window owns the view, so it outlives this strong reference.
+        let view = *self.nsview.load();
+        if view == nil {
+            None
+        } else {
+            Some(view)
+        }
+    }
+
+    pub fn set_size(&self, size: Size) {
+        unsafe {
+            let window: id = msg_send![*self.nsview.load(), window];
+            let mut size = NSSize::new(size.width, size.height);
+            let screen = window.screen();
+            if screen != nil {
+                let visible = NSScreen::visibleFrame(screen);
+                size.width = size.width.min(visible.size.width);
+                size.height = size.height.min(visible.size.height);
+            }
+            window.setContentSize_(size);
+        }
+    }
+
     pub fn set_menu(&self, menu: Menu) {
         unsafe {
             NSApp().setMainMenu_(menu.menu);
@@ -792,6 +948,18 @@ impl IdleHandle {
 /// of seconds from now.
 ///
 /// This may lose some precision for multi-month durations.
+/// One of `NSCursor`'s private diagonal resize cursors, or a crosshair if
+/// this version of AppKit doesn't have it.
+unsafe fn diagonal_cursor(selector: Sel) -> id {
+    let nscursor = class!(NSCursor);
+    let exists: BOOL = msg_send![nscursor, respondsToSelector: selector];
+    if exists == YES {
+        msg_send![nscursor, performSelector: selector]
+    } else {
+        msg_send![nscursor, crosshairCursor]
+    }
+}
+
 fn time_interval_from_deadline(deadline: std::time::Instant) -> f64 {
     let now = Instant::now();
     if now >= deadline {
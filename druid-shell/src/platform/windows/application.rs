@@ -17,17 +17,22 @@
 use std::mem;
 use std::ptr;
 
-use winapi::shared::minwindef::HINSTANCE;
+use winapi::shared::minwindef::{DWORD, HINSTANCE};
 use winapi::shared::ntdef::LPCWSTR;
 use winapi::shared::windef::HCURSOR;
-use winapi::um::shellscalingapi::PROCESS_SYSTEM_DPI_AWARE;
+use winapi::shared::winerror::ERROR_SUCCESS;
+use winapi::um::errhandlingapi::GetLastError;
+use winapi::um::shellscalingapi::PROCESS_PER_MONITOR_DPI_AWARE;
 use winapi::um::wingdi::CreateSolidBrush;
+use winapi::um::winreg::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD};
 use winapi::um::winuser::{
-    DispatchMessageW, GetAncestor, GetMessageW, LoadIconW, PostQuitMessage, RegisterClassW,
-    TranslateAcceleratorW, TranslateMessage, GA_ROOT, IDI_APPLICATION, MSG, WNDCLASSW,
+    DispatchMessageW, GetAncestor, GetMessageW, LoadIconW, MessageBeep, PostQuitMessage,
+    RegisterClassW, TranslateAcceleratorW, TranslateMessage, GA_ROOT, IDI_APPLICATION,
+    MB_ICONASTERISK, MB_ICONEXCLAMATION, MB_ICONHAND, MB_OK, MSG, WNDCLASSW,
 };
 
-use crate::application::AppHandler;
+use crate::application::{AppHandler, ColorScheme, SystemSound};
+use crate::error::Error;
 
 use super::accels;
 use super::clipboard::Clipboard;
@@ -71,7 +76,9 @@ impl Application {
         if let Some(func) = OPTIONAL_FUNCTIONS.SetProcessDpiAwareness {
             // This function is only supported on windows 10
             unsafe {
-                func(PROCESS_SYSTEM_DPI_AWARE); // TODO: per monitor (much harder)
+                // windows are told when they move to a monitor with a
+                // different dpi, with WM_DPICHANGED.
+                func(PROCESS_PER_MONITOR_DPI_AWARE);
             }
         }
 
@@ -112,4 +119,50 @@ impl Application {
         //TODO ahem
         "en-US".into()
     }
+
+    pub fn color_scheme() -> ColorScheme {
+        let key = "Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize".to_wide();
+        let value = "AppsUseLightTheme".to_wide();
+        let mut data: DWORD = 1;
+        let mut size = mem::size_of::<DWORD>() as DWORD;
+        let res = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                key.as_ptr(),
+                value.as_ptr(),
+                RRF_RT_REG_DWORD,
+                ptr::null_mut(),
+                &mut data as *mut DWORD as *mut _,
+                &mut size,
+            )
+        };
+        // the value is missing before Windows 10 1809, which had no dark mode.
+        if res == ERROR_SUCCESS as i32 && data == 0 {
+            ColorScheme::Dark
+        } else {
+            ColorScheme::Light
+        }
+    }
+
+    pub fn play_sound(sound: SystemSound) {
+        let kind = match sound {
+            SystemSound::Beep => MB_OK,
+            SystemSound::Information => MB_ICONASTERISK,
+            SystemSound::Warning => MB_ICONEXCLAMATION,
+            SystemSound::Error => MB_ICONHAND,
+        };
+        if unsafe { MessageBeep(kind) } == 0 {
+            log::warn!("MessageBeep failed, error code {}", unsafe {
+                GetLastError()
+            });
+        }
+    }
+
+    /// DirectWrite only finds fonts in the system font collection, and
+    /// fonts added with GDI aren't in it.
+    pub fn load_font(_data: &[u8]) -> Result<(), Error> {
+        Err(Error::Other(
+            "loading fonts is not supported on Windows yet",
+        ))
+    }
 }
@@ -45,6 +45,7 @@ use super::util::as_result;
 pub(crate) unsafe fn create_render_target(
     d2d_factory: &D2DFactory,
     hwnd: HWND,
+    dpi: f32,
 ) -> Result<DeviceContext, Error> {
     let mut rect: RECT = mem::zeroed();
     if GetClientRect(hwnd, &mut rect) == 0 {
@@ -58,8 +59,12 @@ pub(crate) unsafe fn create_render_target(
         if let Err(ref e) = res {
             error!("Creating hwnd render target failed: {:?}", e);
         }
-        res.map(|hrt| cast_to_device_context(&hrt).expect("removethis"))
-            .map_err(|_| Error::D2Error)
+        let rt = res
+            .map(|hrt| cast_to_device_context(&hrt).expect("removethis"))
+            .map_err(|_| Error::D2Error)?;
+        // the window's dpi may not be the system's, which is the default.
+        rt.get_comptr().SetDpi(dpi, dpi);
+        Ok(rt)
     }
 }
 
@@ -43,10 +43,11 @@ use piet_common::dwrite::DwriteFactory;
 
 use crate::platform::windows::HwndRenderTarget;
 
-use crate::kurbo::{Point, Size, Vec2};
+use crate::kurbo::{Point, Rect, Size, Vec2};
 use crate::piet::{Piet, RenderContext};
 
 use super::accels::register_accel;
+use super::application::Application;
 use super::dcomp::{D3D11Device, DCompositionDevice, DCompositionTarget, DCompositionVisual};
 use super::dialog::get_file_dialog_path;
 use super::error::Error;
@@ -76,6 +77,7 @@ pub struct WindowBuilder {
     show_titlebar: bool,
     size: Size,
     min_size: Option<Size>,
+    max_size: Option<Size>,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -162,6 +164,7 @@ struct WndState {
     dcomp_state: Option<DCompState>,
     dpi: f32,
     min_size: Option<Size>,
+    max_size: Option<Size>,
     /// The `KeyCode` of the last `WM_KEYDOWN` event. We stash this so we can
     /// include it when handling `WM_CHAR` events.
     stashed_key_code: KeyCode,
@@ -186,6 +189,13 @@ struct DCompState {
     sizing: bool,
 }
 
+/// The bit of a key message's `lparam` that is set if the key was already
+/// down, which is how auto-repeated presses are told apart from the first.
+///
+/// Bits 0-15 are the repeat count, which is at least 1 even for the first
+/// press: <https://docs.microsoft.com/en-ca/windows/desktop/inputdev/wm-keydown>
+const KEY_WAS_DOWN: LPARAM = 1 << 30;
+
 /// Message indicating there are idle tasks to run.
 const XI_RUN_IDLE: UINT = WM_USER;
 
@@ -201,6 +211,13 @@ const XI_RUN_IDLE: UINT = WM_USER;
 /// time it is handled, we can successfully borrow the handler.
 const XI_REQUEST_DESTROY: UINT = WM_USER + 1;
 
+/// Message relaying a request to resize the window's client area.
+///
+/// Like `XI_REQUEST_DESTROY`, this is so that the `WM_SIZE` message that
+/// `SetWindowPos` sends doesn't arrive while the handler is borrowed. The
+/// width and height, in pixels, are the `wparam` and `lparam`.
+const XI_REQUEST_RESIZE: UINT = WM_USER + 2;
+
 impl Default for PresentStrategy {
     fn default() -> PresentStrategy {
         // We probably want to change this, but we need GDI to work. Too bad about
@@ -241,11 +258,15 @@ impl WndState {
     // Renders but does not present.
     fn render(&mut self, d2d: &D2DFactory, dw: &DwriteFactory, handle: &RefCell<WindowHandle>) {
         let rt = self.render_target.as_mut().unwrap();
+        // The swap chain does not preserve the contents of the previous
+        // frame, so we always repaint the whole window.
+        let size = unsafe { rt.get_comptr().GetSize() };
+        let invalid_rect = Rect::new(0.0, 0.0, f64::from(size.width), f64::from(size.height));
         rt.begin_draw();
         let anim;
         {
             let mut piet_ctx = Piet::new(d2d, dw, rt);
-            anim = self.handler.paint(&mut piet_ctx);
+            anim = self.handler.paint_rect(&mut piet_ctx, invalid_rect);
             if let Err(e) = piet_ctx.finish() {
                 error!("piet error on render: {:?}", e);
             }
@@ -336,6 +357,35 @@ impl WndProc for MyWndProc {
                 Some(0)
             }
             WM_ERASEBKGND => Some(0),
+            WM_DPICHANGED => unsafe {
+                let dpi = f32::from(HIWORD(wparam as u32));
+                if let Some(state) = self.handle.borrow().state.upgrade() {
+                    state.dpi.set(dpi);
+                }
+                if let Ok(mut s) = self.state.try_borrow_mut() {
+                    let s = s.as_mut().unwrap();
+                    s.dpi = dpi;
+                    if let Some(rt) = s.render_target.as_mut() {
+                        rt.get_comptr().SetDpi(dpi, dpi);
+                    }
+                    s.handler.scale_changed(f64::from(dpi) / 96.0);
+                } else {
+                    self.log_dropped_msg(hwnd, msg, wparam, lparam);
+                }
+                // the suggested rect keeps the window's size in display points;
+                // resizing to it sends us WM_SIZE, so the state can't be borrowed.
+                let rect = &*(lparam as *const RECT);
+                SetWindowPos(
+                    hwnd,
+                    null_mut(),
+                    rect.left,
+                    rect.top,
+                    rect.right - rect.left,
+                    rect.bottom - rect.top,
+                    SWP_NOZORDER | SWP_NOACTIVATE,
+                );
+                Some(0)
+            },
             WM_SETFOCUS => {
                 if let Ok(mut s) = self.state.try_borrow_mut() {
                     let s = s.as_mut().unwrap();
@@ -345,11 +395,20 @@ impl WndProc for MyWndProc {
                 }
                 Some(0)
             }
+            WM_KILLFOCUS => {
+                if let Ok(mut s) = self.state.try_borrow_mut() {
+                    let s = s.as_mut().unwrap();
+                    s.handler.lost_focus();
+                } else {
+                    self.log_dropped_msg(hwnd, msg, wparam, lparam);
+                }
+                Some(0)
+            }
             WM_PAINT => unsafe {
                 if let Ok(mut s) = self.state.try_borrow_mut() {
                     let s = s.as_mut().unwrap();
                     if s.render_target.is_none() {
-                        let rt = paint::create_render_target(&self.d2d_factory, hwnd);
+                        let rt = paint::create_render_target(&self.d2d_factory, hwnd, s.dpi);
                         s.render_target = rt.ok();
                     }
                     s.handler.rebuild_resources();
@@ -370,7 +429,7 @@ impl WndProc for MyWndProc {
                 if let Ok(mut s) = self.state.try_borrow_mut() {
                     let s = s.as_mut().unwrap();
                     if s.dcomp_state.is_some() {
-                        let rt = paint::create_render_target(&self.d2d_factory, hwnd);
+                        let rt = paint::create_render_target(&self.d2d_factory, hwnd, s.dpi);
                         s.render_target = rt.ok();
                         {
                             s.handler.rebuild_resources();
@@ -433,6 +492,7 @@ impl WndProc for MyWndProc {
             WM_SIZE => unsafe {
                 if let Ok(mut s) = self.state.try_borrow_mut() {
                     let s = s.as_mut().unwrap();
+                    s.handler.visibility_changed(wparam != SIZE_MINIMIZED);
                     let width = LOWORD(lparam as u32) as u32;
                     let height = HIWORD(lparam as u32) as u32;
                     s.handler.size(width, height);
@@ -505,7 +565,7 @@ impl WndProc for MyWndProc {
                     };
 
                     let modifiers = get_mod_state();
-                    let is_repeat = (lparam & 0xFFFF) > 0;
+                    let is_repeat = lparam & KEY_WAS_DOWN != 0;
                     let event = KeyEvent::new(key_code, is_repeat, modifiers, text, text);
 
                     if s.handler.key_down(event) {
@@ -530,9 +590,7 @@ impl WndProc for MyWndProc {
                     }
 
                     let modifiers = get_mod_state();
-                    // bits 0-15 of iparam are the repeat count:
-                    // https://docs.microsoft.com/en-ca/windows/desktop/inputdev/wm-keydown
-                    let is_repeat = (lparam & 0xFFFF) > 0;
+                    let is_repeat = lparam & KEY_WAS_DOWN != 0;
                     let event = KeyEvent::new(key_code, is_repeat, modifiers, "", "");
 
                     if s.handler.key_down(event) {
@@ -679,6 +737,24 @@ impl WndProc for MyWndProc {
                 }
                 Some(0)
             }
+            XI_REQUEST_RESIZE => {
+                unsafe {
+                    resize_client_area(hwnd, wparam as i32, lparam as i32);
+                }
+                Some(0)
+            }
+            WM_CLOSE => {
+                if let Ok(mut s) = self.state.try_borrow_mut() {
+                    let s = s.as_mut().unwrap();
+                    if !s.handler.request_close() {
+                        return Some(0);
+                    }
+                } else {
+                    self.log_dropped_msg(hwnd, msg, wparam, lparam);
+                }
+                // let DefWindowProc destroy the window
+                None
+            }
             WM_DESTROY => {
                 if let Ok(mut s) = self.state.try_borrow_mut() {
                     let s = s.as_mut().unwrap();
@@ -701,6 +777,19 @@ impl WndProc for MyWndProc {
                 }
                 Some(1)
             }
+            WM_SETTINGCHANGE => {
+                // the color scheme is reported as a change to this "area".
+                let area = lparam as LPWSTR;
+                if !area.is_null() && area.from_wide().as_deref() == Some("ImmersiveColorSet") {
+                    if let Ok(mut s) = self.state.try_borrow_mut() {
+                        let s = s.as_mut().unwrap();
+                        s.handler.color_scheme_changed(Application::color_scheme());
+                    } else {
+                        self.log_dropped_msg(hwnd, msg, wparam, lparam);
+                    }
+                }
+                None
+            }
             WM_CAPTURECHANGED => {
                 if let Ok(mut s) = self.state.try_borrow_mut() {
                     let s = s.as_mut().unwrap();
@@ -712,11 +801,21 @@ impl WndProc for MyWndProc {
                 let min_max_info = unsafe { &mut *(lparam as *mut MINMAXINFO) };
                 if let Ok(s) = self.state.try_borrow() {
                     let s = s.as_ref().unwrap();
-                    if let Some(min_size) = s.min_size {
-                        min_max_info.ptMinTrackSize.x =
-                            (min_size.width * (f64::from(s.dpi) / 96.0)) as i32;
-                        min_max_info.ptMinTrackSize.y =
-                            (min_size.height * (f64::from(s.dpi) / 96.0)) as i32;
+                    let scale = f64::from(s.dpi) / 96.0;
+                    // the limits are for the client area, but the track sizes
+                    // include the frame and the menu bar.
+                    let track_size = |size: Size| unsafe {
+                        let width = (size.width * scale) as i32;
+                        let height = (size.height * scale) as i32;
+                        window_size_for_client(hwnd, width, height)
+                    };
+                    if let Some((x, y)) = s.min_size.and_then(track_size) {
+                        min_max_info.ptMinTrackSize.x = x;
+                        min_max_info.ptMinTrackSize.y = y;
+                    }
+                    if let Some((x, y)) = s.max_size.and_then(track_size) {
+                        min_max_info.ptMaxTrackSize.x = x;
+                        min_max_info.ptMaxTrackSize.y = y;
                     }
                 } else {
                     self.log_dropped_msg(hwnd, msg, wparam, lparam);
@@ -763,6 +862,7 @@ impl WindowBuilder {
             present_strategy: Default::default(),
             size: Size::new(500.0, 400.0),
             min_size: None,
+            max_size: None,
         }
     }
 
@@ -779,6 +879,10 @@ impl WindowBuilder {
         self.min_size = Some(size);
     }
 
+    pub fn set_max_size(&mut self, size: Size) {
+        self.max_size = Some(size);
+    }
+
     pub fn resizable(&mut self, resizable: bool) {
         self.resizable = resizable;
     }
@@ -842,6 +946,7 @@ impl WindowBuilder {
                 dcomp_state: None,
                 dpi,
                 min_size: self.min_size,
+                max_size: self.max_size,
                 stashed_key_code: KeyCode::Unknown(0),
                 stashed_char: None,
                 captured_mouse_buttons: 0,
@@ -894,6 +999,53 @@ impl WindowBuilder {
     }
 }
 
+/// The size of the whole window, with its frame and menu bar, when its client
+/// area is `width` by `height` pixels.
+unsafe fn window_size_for_client(hwnd: HWND, width: i32, height: i32) -> Option<(i32, i32)> {
+    let style = GetWindowLongPtrW(hwnd, GWL_STYLE) as DWORD;
+    let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as DWORD;
+    let has_menu = !GetMenu(hwnd).is_null();
+    let mut rect = RECT {
+        left: 0,
+        top: 0,
+        right: width,
+        bottom: height,
+    };
+    if AdjustWindowRectEx(&mut rect, style, has_menu as BOOL, ex_style) == 0 {
+        warn!(
+            "failed to compute the window size: {}",
+            Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+        );
+        return None;
+    }
+    Some((rect.right - rect.left, rect.bottom - rect.top))
+}
+
+/// Resize the window so that its client area is `width` by `height` pixels,
+/// or as close to that as fits in the work area of its monitor.
+unsafe fn resize_client_area(hwnd: HWND, width: i32, height: i32) {
+    let (mut width, mut height) = match window_size_for_client(hwnd, width, height) {
+        Some(size) => size,
+        None => return,
+    };
+
+    let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+    let mut info: MONITORINFO = mem::zeroed();
+    info.cbSize = mem::size_of::<MONITORINFO>() as DWORD;
+    if GetMonitorInfoW(monitor, &mut info) != 0 {
+        width = width.min(info.rcWork.right - info.rcWork.left);
+        height = height.min(info.rcWork.bottom - info.rcWork.top);
+    }
+
+    let flags = SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE;
+    if SetWindowPos(hwnd, null_mut(), 0, 0, width, height, flags) == 0 {
+        warn!(
+            "failed to resize the window: {}",
+            Error::Hr(HRESULT_FROM_WIN32(GetLastError()))
+        );
+    }
+}
+
 /// Choose an adapter. Here the heuristic is to choose the adapter with the
 /// largest video memory, which will generally be the discrete adapter. It's
 /// possible that on some systems the integrated adapter might be a better
@@ -1078,6 +1230,8 @@ impl Cursor {
             Cursor::NotAllowed => IDC_NO,
             Cursor::ResizeLeftRight => IDC_SIZEWE,
             Cursor::ResizeUpDown => IDC_SIZENS,
+            Cursor::ResizeUpLeftDownRight => IDC_SIZENWSE,
+            Cursor::ResizeUpRightDownLeft => IDC_SIZENESW,
         }
     }
 }
@@ -1113,6 +1267,28 @@ impl WindowHandle {
         }
     }
 
+    #[cfg(feature = "platform-ext")]
+    pub fn raw_hwnd(&self) -> Option<HWND> {
+        let hwnd = self.state.upgrade()?.hwnd.get();
+        if hwnd.is_null() {
+            None
+        } else {
+            Some(hwnd)
+        }
+    }
+
+    pub fn set_size(&self, size: Size) {
+        if let Some(w) = self.state.upgrade() {
+            let hwnd = w.hwnd.get();
+            let scale = f64::from(w.dpi.get()) / 96.0;
+            let width = (size.width * scale) as WPARAM;
+            let height = (size.height * scale) as LPARAM;
+            unsafe {
+                PostMessageW(hwnd, XI_REQUEST_RESIZE, width, height);
+            }
+        }
+    }
+
     /// Bring this window to the front of the window stack and give it focus.
     pub fn bring_to_front_and_focus(&self) {
         //FIXME: implementation goes here
@@ -1128,6 +1304,22 @@ impl WindowHandle {
         }
     }
 
+    pub fn invalidate_rect(&self, rect: Rect) {
+        if let Some(w) = self.state.upgrade() {
+            let hwnd = w.hwnd.get();
+            let scale = f64::from(w.dpi.get()) / 96.0;
+            let rect = RECT {
+                left: (rect.x0 * scale).floor() as i32,
+                top: (rect.y0 * scale).floor() as i32,
+                right: (rect.x1 * scale).ceil() as i32,
+                bottom: (rect.y1 * scale).ceil() as i32,
+            };
+            unsafe {
+                InvalidateRect(hwnd, &rect, FALSE);
+            }
+        }
+    }
+
     /// Set the title for this menu.
     pub fn set_title(&self, title: &str) {
         if let Some(w) = self.state.upgrade() {
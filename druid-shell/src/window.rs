@@ -16,11 +16,12 @@
 
 use std::any::Any;
 
+use crate::application::ColorScheme;
 use crate::common_util::Counter;
 use crate::dialog::{FileDialogOptions, FileInfo};
 use crate::error::Error;
 use crate::keyboard::{KeyEvent, KeyModifiers};
-use crate::kurbo::{Point, Size, Vec2};
+use crate::kurbo::{Point, Rect, Size, Vec2};
 use crate::menu::Menu;
 use crate::mouse::{Cursor, MouseEvent};
 use crate::platform::window as platform;
@@ -115,6 +116,18 @@ impl WindowHandle {
         self.0.resizable(resizable)
     }
 
+    /// Resize the window, so that its content area has the given size in
+    /// display points.
+    ///
+    /// The window may end up smaller than this, to fit on its screen. The
+    /// handler's [`size`] is called with the new size as usual, which on
+    /// some platforms happens before this returns.
+    ///
+    /// [`size`]: trait.WinHandler.html#method.size
+    pub fn set_size(&self, size: Size) {
+        self.0.set_size(size)
+    }
+
     /// Set whether the window should show titlebar
     pub fn show_titlebar(&self, show_titlebar: bool) {
         self.0.show_titlebar(show_titlebar)
@@ -130,6 +143,30 @@ impl WindowHandle {
         self.0.invalidate()
     }
 
+    /// Request invalidation of a region of the window.
+    ///
+    /// The rect is in display points, relative to the top left of the
+    /// window's content area. The next call to [`WinHandler::paint_rect`]
+    /// will include this rect in its `invalid_rect`.
+    ///
+    /// This only invalidates part of the window with the
+    /// `partial-invalidation` feature; without it, it is the same as
+    /// [`invalidate`]. With the feature, macOS and GTK repaint only the
+    /// invalid part of the window. Windows always repaints and presents the
+    /// whole window, because its swap chain doesn't keep the previous frame.
+    ///
+    /// [`WinHandler::paint_rect`]: trait.WinHandler.html#method.paint_rect
+    /// [`invalidate`]: #method.invalidate
+    pub fn invalidate_rect(&self, rect: Rect) {
+        #[cfg(feature = "partial-invalidation")]
+        self.0.invalidate_rect(rect);
+        #[cfg(not(feature = "partial-invalidation"))]
+        {
+            let _ = rect;
+            self.0.invalidate();
+        }
+    }
+
     /// Set the title for this menu.
     pub fn set_title(&self, title: &str) {
         self.0.set_title(title)
@@ -201,6 +238,38 @@ impl WindowHandle {
     }
 }
 
+/// Access to the native window, for platform features that druid-shell
+/// doesn't wrap, such as vibrancy on macOS.
+///
+/// This is an escape hatch, and is only available with the `platform-ext`
+/// feature. druid-shell owns the native window throughout, and assumes it
+/// is the only thing changing its state: resizing it, changing its style
+/// or drawing into it behind druid-shell's back may confuse it. Properties
+/// that druid-shell doesn't use are safe to change. The native window may
+/// only be used on the main thread, and not after the window is closed.
+#[cfg(feature = "platform-ext")]
+impl WindowHandle {
+    /// The window's `HWND`, or `None` if it has been closed.
+    #[cfg(all(target_os = "windows", not(feature = "use_gtk")))]
+    pub fn raw_hwnd(&self) -> Option<winapi::shared::windef::HWND> {
+        self.0.raw_hwnd()
+    }
+
+    /// The window's content `NSView`, or `None` if it has been closed.
+    ///
+    /// The `NSWindow` is the view's `window`.
+    #[cfg(all(target_os = "macos", not(feature = "use_gtk")))]
+    pub fn raw_ns_view(&self) -> Option<cocoa::base::id> {
+        self.0.raw_ns_view()
+    }
+
+    /// The window's `GtkApplicationWindow`, or `None` if it has been closed.
+    #[cfg(any(feature = "use_gtk", target_os = "linux"))]
+    pub fn raw_gtk_window(&self) -> Option<gtk::ApplicationWindow> {
+        self.0.raw_gtk_window()
+    }
+}
+
 /// A builder type for creating new windows.
 pub struct WindowBuilder(platform::WindowBuilder);
 
@@ -223,11 +292,16 @@ impl WindowBuilder {
         self.0.set_size(size)
     }
 
-    /// Set the window's initial size.
+    /// Set the smallest size the user can resize the window to.
     pub fn set_min_size(&mut self, size: Size) {
         self.0.set_min_size(size)
     }
 
+    /// Set the largest size the user can resize the window to.
+    pub fn set_max_size(&mut self, size: Size) {
+        self.0.set_max_size(size)
+    }
+
     /// Set whether the window should be resizable
     pub fn resizable(&mut self, resizable: bool) {
         self.0.resizable(resizable)
@@ -280,6 +354,19 @@ pub trait WinHandler {
     /// should be scheduled for the next animation frame.
     fn paint(&mut self, piet: &mut piet_common::Piet) -> bool;
 
+    /// Request the handler to paint the part of the window inside
+    /// `invalid_rect`, in display points. Painting outside of this rect may
+    /// have no effect. The return value is the same as for [`paint`].
+    ///
+    /// This is what the platform calls; the default implementation ignores
+    /// the rect and paints everything with [`paint`].
+    ///
+    /// [`paint`]: #tymethod.paint
+    fn paint_rect(&mut self, piet: &mut piet_common::Piet, invalid_rect: Rect) -> bool {
+        let _ = invalid_rect;
+        self.paint(piet)
+    }
+
     /// Called when the resources need to be rebuilt.
     ///
     /// Discussion: this function is mostly motivated by using
@@ -321,6 +408,34 @@ pub trait WinHandler {
     #[allow(unused_variables)]
     fn zoom(&mut self, delta: f64) {}
 
+    /// Called when the user pinches on a touch screen or trackpad.
+    ///
+    /// `scale` is the change in size since the previous call, where `1.0`
+    /// is no change, and `center` is the point between the fingers, in
+    /// the window's coordinate space.
+    ///
+    /// The default implementation calls [`zoom`] with the change in scale.
+    /// This is called on macOS and on Linux; Windows touch gestures are not
+    /// supported yet.
+    ///
+    /// [`zoom`]: #method.zoom
+    #[allow(unused_variables)]
+    fn pinch(&mut self, scale: f64, center: Point) {
+        self.zoom(scale - 1.0);
+    }
+
+    /// Called when the user moves two or more fingers together across a
+    /// touch screen.
+    ///
+    /// `delta` is the movement since the previous call, and `center` is
+    /// the point between the fingers, in the window's coordinate space.
+    /// Panning on a trackpad is reported as a [`wheel`] event instead.
+    /// This is only called on Linux.
+    ///
+    /// [`wheel`]: #method.wheel
+    #[allow(unused_variables)]
+    fn pan(&mut self, delta: Vec2, center: Point) {}
+
     /// Called when the mouse moves.
     #[allow(unused_variables)]
     fn mouse_move(&mut self, event: &MouseEvent) {}
@@ -347,6 +462,48 @@ pub trait WinHandler {
     #[allow(unused_variables)]
     fn got_focus(&mut self) {}
 
+    /// Called when this window stops being the focused window, because
+    /// another window, or another application, was focused.
+    fn lost_focus(&mut self) {}
+
+    /// Called when the window becomes visible or hidden, because it was
+    /// minimized or restored, or (on macOS) covered by other windows.
+    ///
+    /// This may be called more than once with the same value.
+    #[allow(unused_variables)]
+    fn visibility_changed(&mut self, visible: bool) {}
+
+    /// Called when the window's scale factor changes, such as when it is
+    /// moved to a monitor with a different pixel density.
+    ///
+    /// The scale is the number of pixels per display point, which is the
+    /// same as [`get_dpi`] divided by 96. If the window's size in pixels
+    /// changes too, `size` is called afterwards.
+    ///
+    /// This is currently only reported on Windows.
+    ///
+    /// [`get_dpi`]: struct.WindowHandle.html#method.get_dpi
+    #[allow(unused_variables)]
+    fn scale_changed(&mut self, scale: f64) {}
+
+    /// Called when the user changes the system color scheme.
+    ///
+    /// On GTK this follows the `gtk-application-prefer-dark-theme` and
+    /// `gtk-theme-name` settings; macOS reports it from 10.14.
+    #[allow(unused_variables)]
+    fn color_scheme_changed(&mut self, scheme: ColorScheme) {}
+
+    /// Called when the user asks to close the window, for example with its
+    /// close button or the system's close shortcut.
+    ///
+    /// Return `false` to keep the window open. This is not called when the
+    /// window is closed with [`WindowHandle::close`].
+    ///
+    /// [`WindowHandle::close`]: struct.WindowHandle.html#method.close
+    fn request_close(&mut self) -> bool {
+        true
+    }
+
     /// Called when the window is being destroyed. Note that this happens
     /// earlier in the sequence than drop (at WM_DESTROY, while the latter is
     /// WM_NCDESTROY).
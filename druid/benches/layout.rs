@@ -0,0 +1,73 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarks for layout and paint of common container widgets.
+//!
+//! Run with `cargo bench -p druid --features test-harness`.
+
+use std::sync::Arc;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use druid::tests::Harness;
+use druid::widget::{Flex, Label, List};
+use druid::{BoxConstraints, Size, Widget, WidgetExt};
+
+const ITEM_COUNT: u32 = 200;
+
+fn flex_column() -> impl Widget<()> {
+    let mut column = Flex::column();
+    for i in 0..ITEM_COUNT {
+        column.add_child(Label::new(format!("row {}", i)).padding(4.0));
+    }
+    column
+}
+
+fn list() -> impl Widget<Arc<Vec<u32>>> {
+    List::new(|| Label::new(|item: &u32, _env: &_| format!("item {}", item)).padding(4.0))
+}
+
+/// Alternating between two widths means every pass sees new constraints,
+/// so no widget can reuse its cached layout.
+fn alternating_constraints() -> impl Iterator<Item = BoxConstraints> {
+    let narrow = BoxConstraints::new(Size::ZERO, Size::new(300., 10_000.));
+    let wide = BoxConstraints::new(Size::ZERO, Size::new(400., 10_000.));
+    vec![narrow, wide].into_iter().cycle()
+}
+
+fn bench_flex(c: &mut Criterion) {
+    Harness::create((), flex_column(), |harness| {
+        harness.send_initial_events();
+        let mut constraints = alternating_constraints();
+        c.bench_function("flex column layout", |b| {
+            b.iter(|| harness.layout_with_constraints(constraints.next().unwrap()))
+        });
+        c.bench_function("flex column paint", |b| b.iter(|| harness.paint()));
+    });
+}
+
+fn bench_list(c: &mut Criterion) {
+    let data = Arc::new((0..ITEM_COUNT).collect::<Vec<_>>());
+    Harness::create(data, list(), |harness| {
+        harness.send_initial_events();
+        let mut constraints = alternating_constraints();
+        c.bench_function("list layout", |b| {
+            b.iter(|| harness.layout_with_constraints(constraints.next().unwrap()))
+        });
+        c.bench_function("list paint", |b| b.iter(|| harness.paint()));
+    });
+}
+
+criterion_group!(benches, bench_flex, bench_list);
+criterion_main!(benches);
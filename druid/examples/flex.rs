@@ -142,6 +142,7 @@ fn make_control_row() -> impl Widget<AppState> {
                         ("Start", CrossAxisAlignment::Start),
                         ("Center", CrossAxisAlignment::Center),
                         ("End", CrossAxisAlignment::End),
+                        ("Fill", CrossAxisAlignment::Fill),
                     ])
                     .lens(Params::cross_alignment),
                 ),
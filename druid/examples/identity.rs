@@ -105,15 +105,11 @@ impl Widget<OurData> for ColorWell {
                 self.token = ctx.request_timer(Instant::now() + CYCLE_DURATION);
             }
 
-            Event::Command(cmd) if cmd.selector == FREEZE_COLOR => {
-                self.frozen = cmd
-                    .get_object::<Color>()
-                    .ok()
-                    .cloned()
-                    .expect("payload is always a Color")
-                    .into();
+            Event::Command(cmd) if cmd.is(FREEZE_COLOR) => {
+                let color = cmd.get::<Color>(FREEZE_COLOR);
+                self.frozen = Some(color.cloned().expect("payload is always a Color"));
             }
-            Event::Command(cmd) if cmd.selector == UNFREEZE_COLOR => self.frozen = None,
+            Event::Command(cmd) if cmd.is(UNFREEZE_COLOR) => self.frozen = None,
             _ => (),
         }
     }
@@ -157,9 +153,11 @@ fn main() {
 const ID_ONE: WidgetId = WidgetId::reserved(1);
 
 fn make_ui() -> impl Widget<OurData> {
-    // two ids generated at runtime
+    // an id generated at runtime
     let id_two = WidgetId::next();
-    let id_three = WidgetId::next();
+    // an id allocated by wrapping the widget, and read back afterwards
+    let well_three = ColorWell::new(false).with_new_id();
+    let id_three = well_three.widget_id();
 
     Flex::column()
         .with_flex_child(ColorWell::new(true), 1.0)
@@ -203,7 +201,7 @@ fn make_ui() -> impl Widget<OurData> {
         .with_flex_child(
             Flex::row()
                 .cross_axis_alignment(CrossAxisAlignment::Center)
-                .with_flex_child(ColorWell::new(false).with_id(id_three), 1.)
+                .with_flex_child(well_three, 1.)
                 .with_spacer(10.0)
                 .with_child(
                     Button::<OurData>::new("freeze").on_click(move |ctx, data, _env| {
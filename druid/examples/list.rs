@@ -63,16 +63,19 @@ fn ui_builder() -> impl Widget<AppData> {
 
     let mut lists = Flex::row().cross_axis_alignment(CrossAxisAlignment::Start);
 
-    // Build a simple list
+    // Build a simple list, whose items can be dragged by their handles
     lists.add_flex_child(
-        Scroll::new(List::new(|| {
-            Label::new(|item: &u32, _env: &_| format!("List item #{}", item))
-                .align_vertical(UnitPoint::LEFT)
-                .padding(10.0)
-                .expand()
-                .height(50.0)
-                .background(Color::rgb(0.5, 0.5, 0.5))
-        }))
+        Scroll::new(
+            List::new(|| {
+                Label::new(|item: &u32, _env: &_| format!("List item #{}", item))
+                    .align_vertical(UnitPoint::LEFT)
+                    .padding(10.0)
+                    .expand()
+                    .height(50.0)
+                    .background(Color::rgb(0.5, 0.5, 0.5))
+            })
+            .reorderable(),
+        )
         .vertical()
         .lens(AppData::left),
         1.0,
@@ -14,11 +14,11 @@
 
 //! An example of a timer.
 
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
-use druid::kurbo::Line;
+use druid::kurbo::{Line, Point, Vec2};
 use druid::widget::prelude::*;
-use druid::{AppLauncher, Color, LocalizedString, TimerToken, WindowDesc};
+use druid::{theme, AppLauncher, LocalizedString, TimerToken, WindowDesc};
 
 struct TimerWidget {
     timer_id: TimerToken,
@@ -31,14 +31,14 @@ impl Widget<u32> for TimerWidget {
             Event::MouseDown(_) => {
                 self.on = !self.on;
                 ctx.request_paint();
-                let deadline = Instant::now() + Duration::from_millis(500);
+                let deadline = ctx.now() + Duration::from_millis(500);
                 self.timer_id = ctx.request_timer(deadline);
             }
             Event::Timer(id) => {
                 if *id == self.timer_id {
                     self.on = !self.on;
                     ctx.request_paint();
-                    let deadline = Instant::now() + Duration::from_millis(500);
+                    let deadline = ctx.now() + Duration::from_millis(500);
                     self.timer_id = ctx.request_timer(deadline);
                 }
             }
@@ -60,9 +60,17 @@ impl Widget<u32> for TimerWidget {
         bc.constrain((100.0, 100.0))
     }
 
-    fn paint(&mut self, ctx: &mut PaintCtx, _data: &u32, _env: &Env) {
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &u32, env: &Env) {
         if self.on {
-            ctx.stroke(Line::new((10.0, 10.0), (10.0, 50.0)), &Color::WHITE, 1.0);
+            // the label color contrasts with the window in both the light
+            // and the dark theme.
+            let color = env.get(theme::LABEL_COLOR);
+            // a line centered on a pixel boundary would be blurred across
+            // the pixels on either side of it.
+            let width = ctx.snap_stroke_width(1.0);
+            let top = ctx.snap_to_pixel((10.0, 10.0)) + Vec2::new(width / 2.0, 0.0);
+            let bottom = Point::new(top.x, 50.0);
+            ctx.stroke(Line::new(top, bottom), &color, width);
         }
     }
 }
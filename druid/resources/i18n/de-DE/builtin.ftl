@@ -39,3 +39,29 @@ common-menu-copy = Kopieren
 common-menu-paste = Einfügen
 common-menu-undo = Rückgängig
 common-menu-redo = Wiederherstellen
+
+# the date picker's calendar.
+calendar-month-january = Januar
+calendar-month-february = Februar
+calendar-month-march = März
+calendar-month-april = April
+calendar-month-may = Mai
+calendar-month-june = Juni
+calendar-month-july = Juli
+calendar-month-august = August
+calendar-month-september = September
+calendar-month-october = Oktober
+calendar-month-november = November
+calendar-month-december = Dezember
+
+# the calendar's column headings, one or two letters long.
+calendar-weekday-monday = Mo
+calendar-weekday-tuesday = Di
+calendar-weekday-wednesday = Mi
+calendar-weekday-thursday = Do
+calendar-weekday-friday = Fr
+calendar-weekday-saturday = Sa
+calendar-weekday-sunday = So
+
+# the search row of a searchable dropdown, while nothing has been typed.
+dropdown-search-placeholder = Suchen
@@ -43,3 +43,29 @@ common-menu-copy = Copy
 common-menu-paste = Paste
 common-menu-undo = Undo
 common-menu-redo = Redo
+
+# the date picker's calendar.
+calendar-month-january = January
+calendar-month-february = February
+calendar-month-march = March
+calendar-month-april = April
+calendar-month-may = May
+calendar-month-june = June
+calendar-month-july = July
+calendar-month-august = August
+calendar-month-september = September
+calendar-month-october = October
+calendar-month-november = November
+calendar-month-december = December
+
+# the calendar's column headings, one or two letters long.
+calendar-weekday-monday = Mo
+calendar-weekday-tuesday = Tu
+calendar-weekday-wednesday = We
+calendar-weekday-thursday = Th
+calendar-weekday-friday = Fr
+calendar-weekday-saturday = Sa
+calendar-weekday-sunday = Su
+
+# the search row of a searchable dropdown, while nothing has been typed.
+dropdown-search-placeholder = Search
@@ -39,3 +39,29 @@ common-menu-copy = Copier
 common-menu-paste = Coller
 common-menu-undo = Annuler
 common-menu-redo = Rétablir
+
+# the date picker's calendar.
+calendar-month-january = janvier
+calendar-month-february = février
+calendar-month-march = mars
+calendar-month-april = avril
+calendar-month-may = mai
+calendar-month-june = juin
+calendar-month-july = juillet
+calendar-month-august = août
+calendar-month-september = septembre
+calendar-month-october = octobre
+calendar-month-november = novembre
+calendar-month-december = décembre
+
+# the calendar's column headings, one or two letters long.
+calendar-weekday-monday = lu
+calendar-weekday-tuesday = ma
+calendar-weekday-wednesday = me
+calendar-weekday-thursday = je
+calendar-weekday-friday = ve
+calendar-weekday-saturday = sa
+calendar-weekday-sunday = di
+
+# the search row of a searchable dropdown, while nothing has been typed.
+dropdown-search-placeholder = Rechercher
@@ -15,23 +15,35 @@
 //! Window building and app lifecycle.
 
 use crate::ext_event::{ExtEventHost, ExtEventSink};
+use std::path::PathBuf;
+use std::rc::Rc;
+
 use crate::kurbo::Size;
-use crate::shell::{Application, Error as PlatformError, WindowBuilder, WindowHandle};
+use crate::piet::Color;
+use crate::recording::EventLog;
+use crate::shell::{Application, ColorScheme, Error as PlatformError, WindowBuilder, WindowHandle};
+use crate::task;
 use crate::win_handler::{AppHandler, AppState};
 use crate::window::WindowId;
 use crate::{
-    theme, AppDelegate, Data, DruidHandler, Env, LocalizedString, MenuDesc, Widget, WidgetExt,
+    theme, AppDelegate, Data, DruidHandler, Env, Executor, KeyOrValue, LocalizedString, MenuDesc,
+    Widget, WidgetExt,
 };
 
 /// A function that modifies the initial environment.
-type EnvSetupFn<T> = dyn FnOnce(&mut Env, &T);
+type EnvSetupFn<T> = dyn Fn(&mut Env, &T);
 
 /// Handles initial setup of an application, and starts the runloop.
 pub struct AppLauncher<T> {
     windows: Vec<WindowDesc<T>>,
     env_setup: Option<Box<EnvSetupFn<T>>>,
+    color_scheme: Option<ColorScheme>,
     delegate: Option<Box<dyn AppDelegate<T>>>,
     ext_event_host: ExtEventHost,
+    max_fps: Option<f64>,
+    executor: Option<Rc<dyn Executor>>,
+    log_unhandled_commands: bool,
+    fonts: Vec<Vec<u8>>,
 }
 
 /// A description of a window to be instantiated.
@@ -43,9 +55,14 @@ pub struct WindowDesc<T> {
     pub(crate) title: LocalizedString<T>,
     pub(crate) size: Option<Size>,
     pub(crate) min_size: Option<Size>,
+    pub(crate) max_size: Option<Size>,
+    pub(crate) auto_size: bool,
     pub(crate) menu: Option<MenuDesc<T>>,
     pub(crate) resizable: bool,
     pub(crate) show_titlebar: bool,
+    pub(crate) background: Option<KeyOrValue<Color>>,
+    pub(crate) record_path: Option<PathBuf>,
+    pub(crate) replay: Option<EventLog>,
     /// The `WindowId` that will be assigned to this window.
     ///
     /// This can be used to track a window from when it is launched and when
@@ -59,20 +76,43 @@ impl<T: Data> AppLauncher<T> {
         AppLauncher {
             windows: vec![window],
             env_setup: None,
+            color_scheme: None,
             delegate: None,
             ext_event_host: ExtEventHost::new(),
+            max_fps: None,
+            executor: None,
+            log_unhandled_commands: false,
+            fonts: Vec::new(),
         }
     }
 
     /// Provide an optional closure that will be given mutable access to
     /// the environment and immutable access to the app state before launch.
     ///
-    /// This can be used to set or override theme values.
+    /// This can be used to set or override theme values. The closure is
+    /// called again, on a fresh theme, whenever the system color scheme
+    /// changes.
     pub fn configure_env(mut self, f: impl Fn(&mut Env, &T) + 'static) -> Self {
         self.env_setup = Some(Box::new(f));
         self
     }
 
+    /// Always use the theme for `scheme`, instead of following the system.
+    ///
+    /// By default, the launcher uses [`theme::init`] or [`theme::init_light`]
+    /// depending on the system color scheme, and switches between them
+    /// when the user changes that setting. With a fixed scheme, the
+    /// [`COLOR_SCHEME_CHANGED`] command is still sent, but the environment
+    /// is left alone.
+    ///
+    /// [`theme::init`]: theme/fn.init.html
+    /// [`theme::init_light`]: theme/fn.init_light.html
+    /// [`COLOR_SCHEME_CHANGED`]: commands/constant.COLOR_SCHEME_CHANGED.html
+    pub fn color_scheme(mut self, scheme: ColorScheme) -> Self {
+        self.color_scheme = Some(scheme);
+        self
+    }
+
     /// Set the [`AppDelegate`].
     ///
     /// [`AppDelegate`]: trait.AppDelegate.html
@@ -81,14 +121,153 @@ impl<T: Data> AppLauncher<T> {
         self
     }
 
+    /// Deliver at most `fps` animation frames per second.
+    ///
+    /// By default, a widget that keeps requesting animation frames gets one
+    /// for every frame the display shows; capping the rate saves power when
+    /// animations don't need to be smooth.
+    ///
+    /// Frames are still shown in step with the display's refresh (vsync),
+    /// so this is a maximum: each frame is requested a timer's length after
+    /// the last, then waits for the next refresh. On a 60Hz display, a cap
+    /// of 30 gives about 30 frames per second, while a cap of 45 gives
+    /// something between 30 and 45. A cap at or above the refresh rate has
+    /// no effect. Other repaints, such as after an event, are not affected.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fps` is not positive.
+    pub fn with_max_fps(mut self, fps: f64) -> Self {
+        assert!(fps > 0.0, "max fps must be positive");
+        self.max_fps = Some(fps);
+        self
+    }
+
+    /// Set the [`Executor`] that runs the futures started with
+    /// [`EventCtx::spawn_future`].
+    ///
+    /// Without one, each is run on a pooled thread that sleeps while the
+    /// future waits, or, with druid's `futures-executor` feature, on the
+    /// `futures` crate's thread pool.
+    ///
+    /// [`Executor`]: trait.Executor.html
+    /// [`EventCtx::spawn_future`]: struct.EventCtx.html#method.spawn_future
+    pub fn with_executor(mut self, executor: impl Executor + 'static) -> Self {
+        self.executor = Some(Rc::new(executor));
+        self
+    }
+
+    /// Log a warning for every command sent to a window or widget that
+    /// none of its widgets handled.
+    ///
+    /// A command counts as handled once a widget calls
+    /// [`EventCtx::set_handled`] for it, or once the [`AppDelegate`] or the
+    /// window itself acts on it. The warning names the selector, the
+    /// target, and the widget that submitted the command, if it came from
+    /// one. This helps catch a misspelled selector or a command sent to the
+    /// wrong target, but a widget that acts on a command without calling
+    /// `set_handled` also shows up. Broadcasts are never logged.
+    ///
+    /// The warnings go through the [`log`] crate, so a logger must be set
+    /// up, for instance with [`use_simple_logger`].
+    ///
+    /// [`EventCtx::set_handled`]: struct.EventCtx.html#method.set_handled
+    /// [`AppDelegate`]: trait.AppDelegate.html
+    /// [`log`]: https://docs.rs/log
+    /// [`use_simple_logger`]: #method.use_simple_logger
+    pub fn log_unhandled_commands(mut self) -> Self {
+        self.log_unhandled_commands = true;
+        self
+    }
+
+    /// Load a font that the app ships with, such as one embedded with
+    /// `include_bytes!`, so that it can be used by its family name, as with
+    /// [`Label::with_font`].
+    ///
+    /// `data` is the contents of a TrueType or OpenType font file. Fonts are
+    /// loaded when the app is launched; if one can't be, a warning is logged
+    /// and text that asks for it is drawn in a fallback font instead. Fonts
+    /// can't be loaded on Windows yet.
+    ///
+    /// [`Label::with_font`]: widget/struct.Label.html#method.with_font
+    pub fn load_font(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.fonts.push(data.into());
+        self
+    }
+
     /// Initialize a minimal logger for printing logs out to stderr.
     ///
-    /// Meant for use during development only.
+    /// Meant for use during development only. This prints everything; to
+    /// choose what is printed, use [`log_to`] with a logger of your own.
+    ///
+    /// [`log_to`]: #method.log_to
     pub fn use_simple_logger(self) -> Self {
         simple_logger::init().ok();
         self
     }
 
+    /// Send log messages to `logger`.
+    ///
+    /// This installs `logger` as the global logger of the [`log`] crate, and
+    /// lets it decide what to keep, so it sees messages at every level. If a
+    /// logger has already been installed, the new one is ignored.
+    ///
+    /// Druid's messages use the following targets, which a logger can
+    /// filter on:
+    ///
+    /// - `druid::event`: event handling and focus changes.
+    /// - `druid::update`: updating widgets when their data changes.
+    /// - `druid::layout`: layout, such as bad constraints or widgets with
+    ///   an infinite size.
+    /// - `druid::paint`: painting, including loading fonts.
+    /// - `druid::command`: routing commands, and commands with unexpected
+    ///   arguments.
+    /// - `druid::window`: creating windows, and other window-level work.
+    ///
+    /// Other messages use the path of the module they come from, such as
+    /// `druid::localization`.
+    ///
+    /// # Examples
+    ///
+    /// A logger that prints only what happens to commands:
+    ///
+    /// ```no_run
+    /// use druid::widget::Label;
+    /// use druid::{AppLauncher, WindowDesc};
+    /// use log::{Log, Metadata, Record};
+    ///
+    /// struct CommandLogger;
+    ///
+    /// impl Log for CommandLogger {
+    ///     fn enabled(&self, metadata: &Metadata) -> bool {
+    ///         metadata.target() == "druid::command"
+    ///     }
+    ///
+    ///     fn log(&self, record: &Record) {
+    ///         if self.enabled(record.metadata()) {
+    ///             eprintln!("{}: {}", record.level(), record.args());
+    ///         }
+    ///     }
+    ///
+    ///     fn flush(&self) {}
+    /// }
+    ///
+    /// let window = WindowDesc::new(|| Label::new("Hello"));
+    /// AppLauncher::with_window(window)
+    ///     .log_to(CommandLogger)
+    ///     .log_unhandled_commands()
+    ///     .launch(())
+    ///     .expect("launch failed");
+    /// ```
+    ///
+    /// [`log`]: https://docs.rs/log
+    pub fn log_to(self, logger: impl log::Log + 'static) -> Self {
+        if log::set_boxed_logger(Box::new(logger)).is_ok() {
+            log::set_max_level(log::LevelFilter::Trace);
+        }
+        self
+    }
+
     /// Returns an [`ExtEventSink`] that can be moved between threads,
     /// and can be used to submit events back to the application.
     ///
@@ -110,15 +289,30 @@ impl<T: Data> AppLauncher<T> {
     /// Returns an error if a window cannot be instantiated. This is usually
     /// a fatal error.
     pub fn launch(mut self, data: T) -> Result<(), PlatformError> {
-        let mut env = theme::init();
-        if let Some(f) = self.env_setup.take() {
-            f(&mut env, &data);
-        }
-
-        let mut state = AppState::new(data, env, self.delegate.take(), self.ext_event_host);
+        let mut state = AppState::new(
+            data,
+            Env::default(),
+            self.delegate.take(),
+            self.ext_event_host,
+        );
+        state.set_max_fps(self.max_fps);
+        state.set_executor(self.executor.or_else(task::default_executor));
+        state.set_log_unhandled_commands(self.log_unhandled_commands);
         let handler = AppHandler::new(state.clone());
 
         let mut app = Application::new(Some(Box::new(handler)));
+        // before any text is laid out, so that the text system sees them.
+        for font in &self.fonts {
+            if let Err(e) = Application::load_font(font) {
+                log::warn!("failed to load a font: {}", e);
+            }
+        }
+        // the system color scheme can't be queried before the platform is
+        // initialized, so the real env is only built now.
+        state.set_env_source(EnvSource {
+            fixed_scheme: self.color_scheme,
+            setup: self.env_setup.take(),
+        });
         for desc in self.windows {
             let window = desc.build_native(&mut state)?;
             window.show();
@@ -129,6 +323,26 @@ impl<T: Data> AppLauncher<T> {
     }
 }
 
+/// Builds the app's [`Env`], initially and again whenever the system color
+/// scheme changes.
+///
+/// [`Env`]: struct.Env.html
+pub(crate) struct EnvSource<T> {
+    /// If set, this scheme is used regardless of the system setting.
+    pub(crate) fixed_scheme: Option<ColorScheme>,
+    setup: Option<Box<EnvSetupFn<T>>>,
+}
+
+impl<T> EnvSource<T> {
+    pub(crate) fn build(&self, system_scheme: ColorScheme, data: &T) -> Env {
+        let mut env = theme::init_for_scheme(self.fixed_scheme.unwrap_or(system_scheme));
+        if let Some(f) = self.setup.as_ref() {
+            f(&mut env, data);
+        }
+        env
+    }
+}
+
 impl<T: Data> WindowDesc<T> {
     /// Create a new `WindowDesc`, taking a funciton that will generate the root
     /// [`Widget`] for this window.
@@ -148,9 +362,14 @@ impl<T: Data> WindowDesc<T> {
             title: LocalizedString::new("app-name"),
             size: None,
             min_size: None,
+            max_size: None,
+            auto_size: false,
             menu: MenuDesc::platform_default(),
             resizable: true,
             show_titlebar: true,
+            background: None,
+            record_path: None,
+            replay: None,
             id: WindowId::next(),
         }
     }
@@ -184,6 +403,10 @@ impl<T: Data> WindowDesc<T> {
 
     /// Set the minimum window size.
     ///
+    /// The user can't resize the window to be smaller than this, and the
+    /// root widget is never laid out with a smaller size, even if the
+    /// platform doesn't enforce the limit.
+    ///
     /// To  set the initial window size, see [`window_size`].
     ///
     /// [`window_size`]: struct.WindowDesc.html#method.window_size
@@ -192,6 +415,31 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Set the maximum window size.
+    ///
+    /// This is the counterpart of [`with_min_size`]. The initial size set
+    /// with [`window_size`] is clamped to these limits.
+    ///
+    /// [`with_min_size`]: struct.WindowDesc.html#method.with_min_size
+    /// [`window_size`]: struct.WindowDesc.html#method.window_size
+    pub fn with_max_size(mut self, size: impl Into<Size>) -> Self {
+        self.max_size = Some(size.into());
+        self
+    }
+
+    /// Size the window to fit its content when it opens.
+    ///
+    /// This replaces the size set with [`window_size`] once the root widget
+    /// has been laid out; see [`SIZE_WINDOW_TO_CONTENT`] for how the size is
+    /// chosen, and for resizing again later.
+    ///
+    /// [`window_size`]: struct.WindowDesc.html#method.window_size
+    /// [`SIZE_WINDOW_TO_CONTENT`]: commands/constant.SIZE_WINDOW_TO_CONTENT.html
+    pub fn auto_size(mut self) -> Self {
+        self.auto_size = true;
+        self
+    }
+
     pub fn resizable(mut self, resizable: bool) -> Self {
         self.resizable = resizable;
         self
@@ -202,6 +450,40 @@ impl<T: Data> WindowDesc<T> {
         self
     }
 
+    /// Builder-style method to fill this window with `color`, instead of
+    /// [`theme::WINDOW_BACKGROUND_COLOR`], before its widgets are painted.
+    ///
+    /// `color` can be a key, such as [`theme::BACKGROUND_DARK`], to follow
+    /// the color scheme.
+    ///
+    /// [`theme::WINDOW_BACKGROUND_COLOR`]: theme/constant.WINDOW_BACKGROUND_COLOR.html
+    /// [`theme::BACKGROUND_DARK`]: theme/constant.BACKGROUND_DARK.html
+    pub fn background(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.background = Some(color.into());
+        self
+    }
+
+    /// Record the input events this window receives to the file at `path`.
+    ///
+    /// Each event is written as it arrives, so the recording is complete
+    /// even if the application crashes. See the [`recording`] module for
+    /// the format.
+    ///
+    /// [`recording`]: recording/index.html
+    pub fn record_events(mut self, path: impl Into<PathBuf>) -> Self {
+        self.record_path = Some(path.into());
+        self
+    }
+
+    /// Replay the recorded events in `log` into this window, starting when
+    /// it is connected, at the pace at which they were recorded.
+    ///
+    /// Input from the user is still handled during the replay.
+    pub fn replay_events(mut self, log: EventLog) -> Self {
+        self.replay = Some(log);
+        self
+    }
+
     /// Attempt to create a platform window from this `WindowDesc`.
     pub(crate) fn build_native(
         mut self,
@@ -222,11 +504,14 @@ impl<T: Data> WindowDesc<T> {
 
         builder.set_handler(Box::new(handler));
         if let Some(size) = self.size {
-            builder.set_size(size);
+            builder.set_size(clamp_window_size(size, self.min_size, self.max_size));
         }
         if let Some(min_size) = self.min_size {
             builder.set_min_size(min_size);
         }
+        if let Some(max_size) = self.max_size {
+            builder.set_max_size(max_size);
+        }
 
         builder.set_title(self.title.localized_str());
         if let Some(menu) = platform_menu {
@@ -237,9 +522,24 @@ impl<T: Data> WindowDesc<T> {
         let mut window = WindowDesc::new(|| root);
         window.title = self.title;
         window.menu = self.menu;
+        window.min_size = self.min_size;
+        window.max_size = self.max_size;
+        window.auto_size = self.auto_size;
+        window.background = self.background;
+        window.record_path = self.record_path;
+        window.replay = self.replay;
 
         state.add_window(self.id, window);
 
         builder.build()
     }
 }
+
+/// Clamp `size` to the optional limits, with the minimum taking precedence
+/// if they conflict.
+pub(crate) fn clamp_window_size(size: Size, min: Option<Size>, max: Option<Size>) -> Size {
+    let max = max.unwrap_or_else(|| Size::new(f64::INFINITY, f64::INFINITY));
+    let min = min.unwrap_or(Size::ZERO);
+    let size = Size::new(size.width.min(max.width), size.height.min(max.height));
+    Size::new(size.width.max(min.width), size.height.max(min.height))
+}
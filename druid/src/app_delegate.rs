@@ -50,9 +50,76 @@ impl<'a> DelegateCtx<'a> {
 /// The `AppDelegate` is a trait that is allowed to handle and modify
 /// events before they are passed down the widget tree.
 ///
-/// It is a natural place for things like window and menu management.
+/// It is a natural place for things like window and menu management, and
+/// for commands that concern the whole app rather than a particular widget.
 ///
-/// You customize the `AppDelegate` by implementing its methods on your own type.
+/// You customize the `AppDelegate` by implementing its methods on your own type,
+/// and install it with [`AppLauncher::delegate`].
+///
+/// # Ordering
+///
+/// The delegate always runs before any widget:
+///
+/// - [`event`] is called with each event before it is sent to the window's
+///   widgets;
+/// - [`command`] is called with every command, including the built-in
+///   ones in [`commands`], before druid or any widget acts on it;
+/// - [`window_added`] is called once a window's platform handle exists,
+///   before the window's widgets receive [`Event::WindowConnected`];
+/// - [`window_should_close`] is called when the user asks to close a
+///   window, and can keep it open;
+/// - [`window_removed`] is called when the platform closes a window, before
+///   its widgets are dropped.
+///
+/// Changes the delegate makes to the data are seen by widgets in the
+/// [`update`] pass that follows, as with changes made by widgets.
+///
+/// # Examples
+///
+/// Handling the commands of a stopwatch in one place:
+///
+/// ```
+/// use druid::{AppDelegate, Command, Data, DelegateCtx, Env, Selector, Target};
+///
+/// const START: Selector = Selector::new("stopwatch.start");
+/// const STOP: Selector = Selector::new("stopwatch.stop");
+///
+/// #[derive(Clone, Data)]
+/// struct Stopwatch {
+///     running: bool,
+/// }
+///
+/// struct Delegate;
+///
+/// impl AppDelegate<Stopwatch> for Delegate {
+///     fn command(
+///         &mut self,
+///         _ctx: &mut DelegateCtx,
+///         _target: &Target,
+///         cmd: &Command,
+///         data: &mut Stopwatch,
+///         _env: &Env,
+///     ) -> bool {
+///         match cmd.selector {
+///             START => data.running = true,
+///             STOP => data.running = false,
+///             // let everything else through to the widgets
+///             _ => return true,
+///         }
+///         false
+///     }
+/// }
+/// ```
+///
+/// [`AppLauncher::delegate`]: struct.AppLauncher.html#method.delegate
+/// [`event`]: #method.event
+/// [`command`]: #method.command
+/// [`window_added`]: #method.window_added
+/// [`window_should_close`]: #method.window_should_close
+/// [`window_removed`]: #method.window_removed
+/// [`commands`]: commands/index.html
+/// [`Event::WindowConnected`]: enum.Event.html#variant.WindowConnected
+/// [`update`]: trait.Widget.html#tymethod.update
 #[allow(unused)]
 pub trait AppDelegate<T: Data> {
     /// The `AppDelegate`'s event handler. This function receives all
@@ -79,14 +146,17 @@ pub trait AppDelegate<T: Data> {
     /// This function is called with each ([`Target`], [`Command`]) pair before
     /// they are sent down the tree.
     ///
-    /// If your implementation returns `true`, the command will be sent down
-    /// the widget tree. Otherwise it will not.
+    /// If your implementation returns `true`, the command will be handled as
+    /// usual: built-in commands such as [`QUIT_APP`] are acted on by druid,
+    /// and others are sent down the widget tree. Otherwise the command is
+    /// dropped.
     ///
     /// To do anything fancier than this, you can submit arbitary commands
     /// via [`DelegateCtx::submit_command`].
     ///
     /// [`Target`]: enum.Target.html
     /// [`Command`]: struct.Command.html
+    /// [`QUIT_APP`]: commands/constant.QUIT_APP.html
     /// [`DelegateCtx::submit_command`]: struct.DelegateCtx.html#method.submit_command
     fn command(
         &mut self,
@@ -107,4 +177,27 @@ pub trait AppDelegate<T: Data> {
     /// The handler for window deletion events.
     /// This function is called after a window has been removed.
     fn window_removed(&mut self, id: WindowId, data: &mut T, env: &Env, ctx: &mut DelegateCtx) {}
+
+    /// Called when the user asks to close a window, with its close button or
+    /// with a [`CLOSE_WINDOW`] command, and for each window when the
+    /// application is asked to quit with [`QUIT_APP`].
+    ///
+    /// Return `false` to keep the window open, for instance to ask the user
+    /// to confirm first. Once they have, submit [`FORCE_CLOSE_WINDOW`], or
+    /// close the window from a widget with [`EventCtx::close_window`];
+    /// neither calls this method again.
+    ///
+    /// [`CLOSE_WINDOW`]: commands/constant.CLOSE_WINDOW.html
+    /// [`FORCE_CLOSE_WINDOW`]: commands/constant.FORCE_CLOSE_WINDOW.html
+    /// [`QUIT_APP`]: commands/constant.QUIT_APP.html
+    /// [`EventCtx::close_window`]: struct.EventCtx.html#method.close_window
+    fn window_should_close(
+        &mut self,
+        id: WindowId,
+        data: &mut T,
+        env: &Env,
+        ctx: &mut DelegateCtx,
+    ) -> bool {
+        true
+    }
 }
@@ -0,0 +1,62 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Playing short sounds, such as an alert when a timer finishes.
+//!
+//! The sounds are the system's own alert sounds, listed in [`SystemSound`];
+//! they follow the user's sound settings, and are silent if alert sounds
+//! are turned off. Where the platform has no separate sound for a kind of
+//! alert (on Linux, only the bell is available), its usual bell is played.
+//! Druid does not bundle or decode sound files.
+//!
+//! Sounds are played with [`play`], from the main thread, or by submitting
+//! [`commands::PLAY_SOUND`], which also works from other threads through an
+//! [`ExtEventSink`].
+//!
+//! This module requires the `audio` feature.
+//!
+//! # Latency
+//!
+//! The system decides when a sound starts, and it is not told when the
+//! sound ends. Expect a delay of a few tens of milliseconds, and up to
+//! about a tenth of a second on a busy system; this is fine for a
+//! notification, but sounds should not be used to keep time, or to give
+//! feedback on every keypress.
+//!
+//! # Examples
+//!
+//! ```
+//! use druid::audio::{self, SystemSound};
+//!
+//! fn countdown_finished() {
+//!     audio::play(SystemSound::Information);
+//! }
+//! ```
+//!
+//! [`SystemSound`]: enum.SystemSound.html
+//! [`play`]: fn.play.html
+//! [`commands::PLAY_SOUND`]: ../commands/constant.PLAY_SOUND.html
+//! [`ExtEventSink`]: ../struct.ExtEventSink.html
+
+use crate::shell::Application;
+
+pub use crate::shell::SystemSound;
+
+/// Ask the system to play `sound`, returning immediately.
+///
+/// This must be called on the main thread, such as from a widget's event
+/// handler.
+pub fn play(sound: SystemSound) {
+    Application::play_sound(sound)
+}
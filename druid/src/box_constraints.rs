@@ -35,7 +35,7 @@ use log;
 /// [`layout`]: trait.Widget.html#tymethod.layout
 /// [Flutter BoxConstraints]: https://api.flutter.dev/flutter/rendering/BoxConstraints-class.html
 /// [rounded away from zero]: struct.Size.html#method.expand
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct BoxConstraints {
     min: Size,
     max: Size,
@@ -124,16 +124,24 @@ impl BoxConstraints {
             && self.min.expand() == self.min
             && self.max.expand() == self.max)
         {
-            log::warn!("Bad BoxConstraints passed to {}:", name);
-            log::warn!("{:?}", self);
+            log::warn!(target: "druid::layout", "Bad BoxConstraints passed to {}:", name);
+            log::warn!(target: "druid::layout", "{:?}", self);
         }
 
         if self.min.width.is_infinite() {
-            log::warn!("Infinite minimum width constraint passed to {}:", name);
+            log::warn!(
+                target: "druid::layout",
+                "Infinite minimum width constraint passed to {}:",
+                name
+            );
         }
 
         if self.min.height.is_infinite() {
-            log::warn!("Infinite minimum height constraint passed to {}:", name);
+            log::warn!(
+                target: "druid::layout",
+                "Infinite minimum height constraint passed to {}:",
+                name
+            );
         }
     }
 
@@ -0,0 +1,32 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Where a window gets the time, and its timers, from.
+
+use std::time::Instant;
+
+use crate::TimerToken;
+
+/// A replacement for the system clock and the platform's timers.
+///
+/// Windows normally use `Instant::now` and their `WindowHandle`; the test
+/// harness installs a virtual clock instead, which only moves forward when
+/// a test advances it.
+pub(crate) trait Clock {
+    /// The current time.
+    fn now(&self) -> Instant;
+
+    /// Ask for an `Event::Timer` at `deadline`, returning its token.
+    fn request_timer(&self, deadline: Instant) -> TimerToken;
+}
@@ -26,7 +26,7 @@ use crate::{WidgetId, WindowId};
 /// [`druid::commands`] module.
 ///
 /// [`druid::commands`]: commands/index.html
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Selector(&'static str);
 
 /// An arbitrary command.
@@ -46,25 +46,83 @@ pub struct Selector(&'static str);
 /// used once; an example would be if you have some resource that cannot be
 /// cloned, and you wish to send it to another widget.
 ///
-/// # Examples
+/// # Delivery order
+///
+/// A command submitted while handling an event is not delivered right away.
+/// It is queued, and the event first finishes propagating to every widget
+/// that will receive it. The queue is then drained in submission order
+/// (first in, first out), and [`update`] is called. Commands submitted while
+/// handling a queued command join the back of the queue, so they are
+/// delivered after everything that was submitted before them, in another
+/// round followed by its own `update`. All of this happens before the next
+/// layout and paint; if commands keep submitting commands for more than a
+/// few dozen rounds, the rest are delivered later instead.
+///
+/// Each event is therefore handled in this order: the event itself, then
+/// the commands it submitted, then `update`, and only then layout and paint;
+/// a change a command makes to the data is visible in the very next frame.
+/// Commands submitted during an animation frame, which happens as part of
+/// painting, are delivered straight after that frame, and are followed by
+/// their own `update`.
+///
+/// # Submitting outside of events
+///
+/// Commands can also be submitted from [`update`], with
+/// [`UpdateCtx::submit_command`], and from [`lifecycle`], with
+/// [`LifeCycleCtx::submit_command`]. This suits a widget that reacts to a
+/// change in its data, such as a countdown reaching zero, rather than to
+/// input. These commands are delivered once the current pass over the
+/// widget tree ends, and are followed by another `update`; a widget that
+/// submits a command in every `update` will never stop. Layout and paint
+/// cannot submit commands.
+///
+/// # Unknown targets
+///
+/// Submitting a command can't fail, because its target is only looked up
+/// when it is delivered. A command for a window or widget that can't be
+/// found then is dropped, and a warning naming its selector is logged.
+/// A [`Target::Window`] that never belonged to the app is a bug, and also
+/// panics in debug builds; a window that has recently closed is not.
+///
+/// # Handling commands
+///
+/// A widget can check what a command is with [`is`], and get its argument
+/// with [`get`], which also checks the selector:
+///
 /// ```
 /// use druid::{Command, Selector};
 ///
-/// let selector = Selector::new("process_rows");
+/// const PROCESS_ROWS: Selector = Selector::new("process_rows");
 /// let rows = vec![1, 3, 10, 12];
-/// let command = Command::new(selector, rows);
+/// let command = Command::new(PROCESS_ROWS, rows);
 ///
-/// assert_eq!(command.get_object(), Ok(&vec![1, 3, 10, 12]));
+/// assert!(command.is(PROCESS_ROWS));
+/// assert_eq!(command.get::<Vec<i32>>(PROCESS_ROWS), Some(&vec![1, 3, 10, 12]));
+/// assert_eq!(command.get::<Vec<i32>>(Selector::new("other")), None);
 /// ```
 ///
+/// Use [`try_get`] instead to find out why the argument isn't available.
+///
 /// [`Command::new`]: #method.new
 /// [`Command::get_object`]: #method.get_object
+/// [`is`]: #method.is
+/// [`get`]: #method.get
+/// [`try_get`]: #method.try_get
 /// [`Selector`]: struct.Selector.html
+/// [`update`]: trait.Widget.html#tymethod.update
+/// [`lifecycle`]: trait.Widget.html#tymethod.lifecycle
+/// [`UpdateCtx::submit_command`]: struct.UpdateCtx.html#method.submit_command
+/// [`LifeCycleCtx::submit_command`]: struct.LifeCycleCtx.html#method.submit_command
+/// [`Target::Window`]: enum.Target.html#variant.Window
 #[derive(Debug, Clone)]
 pub struct Command {
     /// The command's `Selector`.
     pub selector: Selector,
     object: Option<Arg>,
+    /// Set once the command is delivered.
+    target: Option<Target>,
+    /// The widget that submitted the command, if it came from one.
+    source: Option<WidgetId>,
 }
 
 #[derive(Debug, Clone)]
@@ -84,18 +142,55 @@ pub enum ArgumentError {
     IncorrectType,
     /// The one-shot argument has already been taken.
     Consumed,
+    /// The command has a different selector.
+    WrongSelector,
 }
 
 /// The target of a command.
+///
+/// # Broadcasts
+///
+/// Commands sent to [`Global`] or [`Type`] are broadcasts. They are
+/// delivered to every window, and within each window to every widget that
+/// its parent passes the event on to; calling [`EventCtx::set_handled`]
+/// does not stop them. Within a window, widgets receive a broadcast
+/// depth-first, each parent before its children, with siblings in the order
+/// their container forwards events (for [`Flex`], the order they were
+/// added). The order in which windows are visited is unspecified.
+///
+/// [`Global`]: #variant.Global
+/// [`Type`]: #variant.Type
+/// [`EventCtx::set_handled`]: struct.EventCtx.html#method.set_handled
+/// [`Flex`]: widget/struct.Flex.html
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Target {
     /// The target is the top-level application.
+    ///
+    /// The command is offered to the [`AppDelegate`], and then broadcast to
+    /// every widget in every window.
+    ///
+    /// [`AppDelegate`]: trait.AppDelegate.html
     Global,
     /// The target is a window; the event will be delivered to all
     /// widgets in that window.
     Window(WindowId),
     /// The target is a specific widget.
     Widget(WidgetId),
+    /// The target is every widget of a given type, in every window.
+    ///
+    /// The value is the widget's [`Widget::type_name`]; create it with
+    /// [`Target::of_type`]. Only widgets that are the direct child of a
+    /// [`WidgetPod`] are matched, so a widget wrapped in another widget
+    /// without a pod of its own, such as an [`IdentityWrapper`], is not
+    /// found. As with [`Widget`] targets, a matching widget receives an
+    /// `Event::Command`, which it may pass on to its own children.
+    ///
+    /// [`Widget::type_name`]: trait.Widget.html#method.type_name
+    /// [`Target::of_type`]: #method.of_type
+    /// [`WidgetPod`]: struct.WidgetPod.html
+    /// [`IdentityWrapper`]: widget/struct.IdentityWrapper.html
+    /// [`Widget`]: #variant.Widget
+    Type(&'static str),
 }
 
 /// Commands with special meaning, defined by druid.
@@ -107,6 +202,11 @@ pub mod sys {
     use super::Selector;
 
     /// Quit the running application. This command is handled by the druid library.
+    ///
+    /// Each window is asked first, with [`AppDelegate::window_should_close`],
+    /// and the application keeps running if any of them says no.
+    ///
+    /// [`AppDelegate::window_should_close`]: ../trait.AppDelegate.html#method.window_should_close
     pub const QUIT_APP: Selector = Selector::new("druid-builtin.quit-app");
 
     /// Hide the application. (mac only?)
@@ -120,8 +220,24 @@ pub mod sys {
 
     /// The selector for a command to close a window. The command's argument
     /// should be the id of the window to close.
+    ///
+    /// The [`AppDelegate`] can keep the window open, by returning `false`
+    /// from [`window_should_close`].
+    ///
+    /// [`AppDelegate`]: ../trait.AppDelegate.html
+    /// [`window_should_close`]: ../trait.AppDelegate.html#method.window_should_close
     pub const CLOSE_WINDOW: Selector = Selector::new("druid-builtin.close-window");
 
+    /// Close a window without asking the [`AppDelegate`] first. The command's
+    /// argument should be the id of the window to close.
+    ///
+    /// Submit this once the user has confirmed a close that
+    /// [`window_should_close`] kept from happening.
+    ///
+    /// [`AppDelegate`]: ../trait.AppDelegate.html
+    /// [`window_should_close`]: ../trait.AppDelegate.html#method.window_should_close
+    pub const FORCE_CLOSE_WINDOW: Selector = Selector::new("druid-builtin.force-close-window");
+
     /// The selector for a command to bring a window to the front, and give it focus.
     ///
     /// The command's argument should be the id of the target window.
@@ -139,6 +255,117 @@ pub mod sys {
     /// [`MenuDesc`]: ../struct.MenuDesc.html
     pub const SET_MENU: Selector = Selector::new("druid-builtin.set-menu");
 
+    /// Play a sound; the argument must be a [`SystemSound`].
+    ///
+    /// See the [`audio`] module for details.
+    ///
+    /// [`SystemSound`]: ../audio/enum.SystemSound.html
+    /// [`audio`]: ../audio/index.html
+    #[cfg(feature = "audio")]
+    pub const PLAY_SOUND: Selector = Selector::new("druid-builtin.play-sound");
+
+    /// Print the window's widget tree to the log.
+    ///
+    /// See the [`inspect`] module for details.
+    ///
+    /// [`inspect`]: ../inspect/index.html
+    pub const DUMP_WIDGET_TREE: Selector = Selector::new("druid-builtin.dump-widget-tree");
+
+    /// Show a [`Toast`] in the window.
+    ///
+    /// The command must carry a `Toast` as its argument.
+    ///
+    /// [`Toast`]: ../struct.Toast.html
+    pub const SHOW_TOAST: Selector = Selector::new("druid-builtin.show-toast");
+
+    /// Repaint the whole window.
+    ///
+    /// Widgets are repainted when the data or [`Env`] they are given
+    /// changes, or when they ask for it, so this is rarely needed. It is for
+    /// when what is painted depends on something druid can't see, such as
+    /// an image file that has been reloaded, or state shared outside the
+    /// data. Sent with [`Target::Global`], it repaints every window.
+    ///
+    /// Like any command, this passes through the [`AppDelegate`] first.
+    ///
+    /// [`Env`]: ../struct.Env.html
+    /// [`Target::Global`]: ../enum.Target.html#variant.Global
+    /// [`AppDelegate`]: ../trait.AppDelegate.html
+    pub const REQUEST_PAINT: Selector = Selector::new("druid-builtin.request-paint");
+
+    /// Lay out, and repaint, the whole window.
+    ///
+    /// This discards every widget's cached layout. As with [`REQUEST_PAINT`],
+    /// changes to the data or `Env` already cause a layout where it is
+    /// needed; use this when sizes depend on something else, such as fonts
+    /// that were installed while the app is running.
+    ///
+    /// [`REQUEST_PAINT`]: constant.REQUEST_PAINT.html
+    pub const REQUEST_LAYOUT: Selector = Selector::new("druid-builtin.request-layout");
+
+    /// Resize the window to fit the size its root widget prefers.
+    ///
+    /// The preferred size comes from laying out the root widget with only
+    /// the window's minimum and maximum size as constraints, so a root that
+    /// expands to fill its space has none, and is left alone. The platform
+    /// may shrink the window further to fit it on the screen.
+    ///
+    /// Windows can do this when they open, with [`WindowDesc::auto_size`];
+    /// send this command to do it again after the content changes.
+    ///
+    /// [`WindowDesc::auto_size`]: ../struct.WindowDesc.html#method.auto_size
+    pub const SIZE_WINDOW_TO_CONTENT: Selector =
+        Selector::new("druid-builtin.size-window-to-content");
+
+    /// Sent to a widget when the user presses its access key.
+    ///
+    /// Widgets register an access key with
+    /// [`LifeCycleCtx::register_access_key`]; pressing alt and that key
+    /// focuses the widget and sends it this command. A widget that acts
+    /// when it is clicked should act on this too, as [`Click`] does.
+    ///
+    /// [`LifeCycleCtx::register_access_key`]: ../struct.LifeCycleCtx.html#method.register_access_key
+    /// [`Click`]: ../widget/struct.Click.html
+    pub const ACTIVATE_ACCESS_KEY: Selector = Selector::new("druid-builtin.activate-access-key");
+
+    /// Sent to all windows when the user changes the system color scheme.
+    ///
+    /// The argument is the new [`ColorScheme`]. By the time this is
+    /// received, the environment has already been rebuilt with the matching
+    /// theme, unless the app chose a fixed scheme with
+    /// [`AppLauncher::color_scheme`].
+    ///
+    /// [`ColorScheme`]: ../enum.ColorScheme.html
+    /// [`AppLauncher::color_scheme`]: ../struct.AppLauncher.html#method.color_scheme
+    pub const COLOR_SCHEME_CHANGED: Selector = Selector::new("druid-builtin.color-scheme-changed");
+
+    /// Scroll an item of a [`List`] into view.
+    ///
+    /// The argument is a [`ScrollToItem`], saying which item and where in
+    /// the view it should end up. Send this to the list's id; a list that
+    /// isn't in a [`Scroll`] ignores it.
+    ///
+    /// [`List`]: ../widget/struct.List.html
+    /// [`ScrollToItem`]: ../widget/struct.ScrollToItem.html
+    /// [`Scroll`]: ../widget/struct.Scroll.html
+    pub const SCROLL_TO_ITEM: Selector = Selector::new("druid-builtin.scroll-to-item");
+
+    /// Push a view onto the nearest enclosing [`Navigator`].
+    ///
+    /// The argument is the name the view was added with, a `&'static str`.
+    ///
+    /// [`Navigator`]: ../widget/struct.Navigator.html
+    pub const PUSH_VIEW: Selector = Selector::new("druid-builtin.push-view");
+
+    /// Pop the current view of the nearest enclosing [`Navigator`], going
+    /// back to the one under it.
+    ///
+    /// A navigator's first view can't be popped; the command is then left
+    /// for a navigator further out, if there is one.
+    ///
+    /// [`Navigator`]: ../widget/struct.Navigator.html
+    pub const POP_VIEW: Selector = Selector::new("druid-builtin.pop-view");
+
     /// Show the application preferences.
     pub const SHOW_PREFERENCES: Selector = Selector::new("druid-builtin.menu-show-preferences");
 
@@ -224,6 +451,8 @@ impl Command {
         Command {
             selector,
             object: Some(Arg::Reusable(Arc::new(arg))),
+            target: None,
+            source: None,
         }
     }
 
@@ -238,6 +467,8 @@ impl Command {
         Command {
             selector,
             object: Some(Arg::OneShot(Arc::new(Mutex::new(Some(Box::new(arg)))))),
+            target: None,
+            source: None,
         }
     }
 
@@ -245,7 +476,66 @@ impl Command {
     pub(crate) fn from_ext(selector: Selector, object: Option<Box<dyn Any + Send>>) -> Self {
         let object: Option<Box<dyn Any>> = object.map(|obj| obj as Box<dyn Any>);
         let object = object.map(|o| Arg::Reusable(o.into()));
-        Command { selector, object }
+        Command {
+            selector,
+            object,
+            target: None,
+            source: None,
+        }
+    }
+
+    /// Record which widget submitted this command.
+    pub(crate) fn submitted_by(mut self, id: WidgetId) -> Self {
+        self.source = Some(id);
+        self
+    }
+
+    /// A description of where this command came from, for logging.
+    pub(crate) fn source_for_log(&self) -> String {
+        match self.source {
+            Some(id) => format!("{:?}", id),
+            None => "outside the widget tree".into(),
+        }
+    }
+
+    /// Record where this command is being delivered.
+    pub(crate) fn delivered_to(mut self, target: Target) -> Self {
+        self.target = Some(target);
+        self
+    }
+
+    /// Returns `true` if this command has the given selector.
+    pub fn is(&self, selector: Selector) -> bool {
+        self.selector == selector
+    }
+
+    /// Return this command's argument, if it has the given selector and a
+    /// reusable argument of type `T`.
+    ///
+    /// This is shorthand for checking the selector and then calling
+    /// [`get_object`]; use [`try_get`] to find out why it failed.
+    ///
+    /// [`get_object`]: #method.get_object
+    /// [`try_get`]: #method.try_get
+    pub fn get<T: Any>(&self, selector: Selector) -> Option<&T> {
+        self.try_get(selector).ok()
+    }
+
+    /// Return this command's argument, if it has the given selector and a
+    /// reusable argument of type `T`, or the reason it couldn't.
+    pub fn try_get<T: Any>(&self, selector: Selector) -> Result<&T, ArgumentError> {
+        if !self.is(selector) {
+            return Err(ArgumentError::WrongSelector);
+        }
+        self.get_object()
+    }
+
+    /// The target this command was submitted to.
+    ///
+    /// This is `None` until the command is delivered. A command submitted
+    /// without a target reports the window it was submitted from.
+    pub fn target(&self) -> Option<Target> {
+        self.target
     }
 
     /// Return a reference to this `Command`'s object, if it has one.
@@ -292,6 +582,8 @@ impl From<Selector> for Command {
         Command {
             selector,
             object: None,
+            target: None,
+            source: None,
         }
     }
 }
@@ -308,6 +600,7 @@ impl std::fmt::Display for ArgumentError {
             ArgumentError::NoArgument => write!(f, "Command has no argument"),
             ArgumentError::IncorrectType => write!(f, "Downcast failed: wrong concrete type"),
             ArgumentError::Consumed => write!(f, "One-shot command arguemnt already consumed"),
+            ArgumentError::WrongSelector => write!(f, "Command has a different selector"),
             ArgumentError::WrongVariant => write!(
                 f,
                 "Incorrect access method for argument type; \
@@ -319,6 +612,22 @@ impl std::fmt::Display for ArgumentError {
 
 impl std::error::Error for ArgumentError {}
 
+impl Target {
+    /// A target for every widget of type `W`.
+    ///
+    /// The type must be written out in full, including any type parameters.
+    ///
+    /// ```
+    /// use druid::widget::Label;
+    /// use druid::Target;
+    ///
+    /// let target = Target::of_type::<Label<u32>>();
+    /// ```
+    pub fn of_type<W: ?Sized>() -> Target {
+        Target::Type(std::any::type_name::<W>())
+    }
+}
+
 impl From<WindowId> for Target {
     fn from(id: WindowId) -> Target {
         Target::Window(id)
@@ -353,4 +662,20 @@ mod tests {
         let command = Command::new(sel, objs);
         assert_eq!(command.get_object(), Ok(&vec![0, 1, 2]));
     }
+
+    #[test]
+    fn get_checks_selector() {
+        let sel = Selector::new("my-selector");
+        let command = Command::new(sel, 42u32);
+        assert_eq!(command.try_get::<u32>(sel), Ok(&42));
+        assert_eq!(command.get::<u32>(Selector::NOOP), None);
+        assert_eq!(
+            command.try_get::<u32>(Selector::NOOP),
+            Err(ArgumentError::WrongSelector)
+        );
+        assert_eq!(
+            command.try_get::<String>(sel),
+            Err(ArgumentError::IncorrectType)
+        );
+    }
 }
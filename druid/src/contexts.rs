@@ -14,16 +14,24 @@
 
 //! The context types that are passed into various widget methods.
 
+use std::any::Any;
+use std::cell::RefCell;
+use std::future::Future;
 use std::ops::{Deref, DerefMut};
 use std::time::Instant;
 
 use log;
 
+use crate::clock::Clock;
 use crate::core::{BaseState, CommandQueue, FocusChange};
+use crate::debug_overlay::DebugBox;
+use crate::inspect::WidgetTree;
 use crate::piet::Piet;
 use crate::piet::RenderContext;
+use crate::task::TaskHost;
 use crate::{
-    Affine, Command, Cursor, Insets, Rect, Size, Target, Text, TimerToken, WidgetId, WindowHandle,
+    Affine, Application, Command, Cursor, HotKey, Insets, KeyCode, KeyEvent, Point, Rect, Selector,
+    Size, SysMods, Target, TaskCtx, TaskHandle, Text, TimerToken, Vec2, WidgetId, WindowHandle,
     WindowId,
 };
 
@@ -41,11 +49,30 @@ pub struct EventCtx<'a> {
     pub(crate) command_queue: &'a mut CommandQueue,
     pub(crate) window_id: WindowId,
     pub(crate) window: &'a WindowHandle,
+    /// Set when running in the test harness, which handles timers itself.
+    pub(crate) clock: Option<&'a dyn Clock>,
+    /// Set when running in the test harness, which keeps copied text.
+    pub(crate) mock_clipboard: Option<&'a RefCell<Option<String>>>,
+    pub(crate) tasks: &'a mut TaskHost,
     pub(crate) base_state: &'a mut BaseState,
     pub(crate) focus_widget: Option<WidgetId>,
     pub(crate) had_active: bool,
     pub(crate) is_handled: bool,
+    /// The part of a wheel event's delta that a nested [`Scroll`] could not
+    /// use, and that should be passed on to the one containing it.
+    ///
+    /// [`Scroll`]: widget/struct.Scroll.html
+    pub(crate) scroll_remainder: Option<Vec2>,
+    /// Set while delivering a broadcast command, which can't be handled.
+    pub(crate) is_broadcast: bool,
+    /// Set once a command for a [`Target::Widget`] has found that widget.
+    ///
+    /// [`Target::Widget`]: enum.Target.html#variant.Widget
+    pub(crate) reached_target: bool,
     pub(crate) is_root: bool,
+    pub(crate) window_visible: bool,
+    /// The window's widgets as of its last layout and paint.
+    pub(crate) widget_tree: Option<&'a WidgetTree>,
 }
 
 /// A mutable context provided to the [`lifecycle`] method on widgets.
@@ -61,6 +88,9 @@ pub struct LifeCycleCtx<'a> {
     pub(crate) command_queue: &'a mut CommandQueue,
     pub(crate) base_state: &'a mut BaseState,
     pub(crate) window_id: WindowId,
+    pub(crate) window_visible: bool,
+    /// The window's widgets as of its last layout and paint.
+    pub(crate) widget_tree: Option<&'a WidgetTree>,
 }
 
 /// A mutable context provided to data update methods of widgets.
@@ -70,13 +100,17 @@ pub struct LifeCycleCtx<'a> {
 ///
 /// [`request_paint`]: #method.request_paint
 pub struct UpdateCtx<'a> {
+    pub(crate) command_queue: &'a mut CommandQueue,
     pub(crate) window: &'a WindowHandle,
+    /// Set when running in the test harness, which handles timers itself.
+    pub(crate) clock: Option<&'a dyn Clock>,
     // Discussion: we probably want to propagate more fine-grained
     // invalidations, which would mean a structure very much like
     // `EventCtx` (and possibly using the same structure). But for
     // now keep it super-simple.
     pub(crate) window_id: WindowId,
     pub(crate) base_state: &'a mut BaseState,
+    pub(crate) window_visible: bool,
 }
 
 /// A context provided to layout handling methods of widgets.
@@ -88,6 +122,16 @@ pub struct LayoutCtx<'a, 'b: 'a> {
     pub(crate) text_factory: &'a mut Text<'b>,
     pub(crate) paint_insets: Insets,
     pub(crate) window_id: WindowId,
+    pub(crate) cache_layout: bool,
+    /// Set by a widget whose children's viewport changed during layout.
+    pub(crate) viewport_changed: bool,
+    /// How far content moved during layout, for the enclosing `Scroll`.
+    pub(crate) scroll_shift: Vec2,
+    /// The size of the window, for widgets that draw outside their parents.
+    pub(crate) window_size: Size,
+    /// Set when a widget is laid out to answer an intrinsic size query, so
+    /// that its `WidgetPod` knows to lay it out again for real.
+    pub(crate) probed_layout: bool,
 }
 
 /// Z-order paint operations with transformations.
@@ -110,10 +154,21 @@ pub struct PaintCtx<'a, 'b: 'a> {
     pub window_id: WindowId,
     /// The z-order paint operations.
     pub(crate) z_ops: Vec<ZOrderPaintOp>,
+    /// Widget bounds for the debug overlay; empty unless `Env::DEBUG_PAINT`
+    /// is set somewhere, or `record_paint` is.
+    pub(crate) debug_boxes: Vec<DebugBox>,
+    /// Set by the test harness, to record the painted widgets in
+    /// `debug_boxes` even without the overlay.
+    pub(crate) record_paint: bool,
     /// The currently visible region.
     pub(crate) region: Region,
     pub(crate) base_state: &'a BaseState,
     pub(crate) focus_widget: Option<WidgetId>,
+    /// The pixels per display point that are not part of the render
+    /// context's transform, such as the window's dpi on Windows.
+    pub(crate) window_scale: f64,
+    /// Whether alt is held, so access keys should be underlined.
+    pub(crate) show_access_keys: bool,
 }
 
 /// A region of a widget, generally used to describe what needs to be drawn.
@@ -128,14 +183,32 @@ impl<'a> EventCtx<'a> {
         // invalidation, we'll want to compute the invalidation region, and
         // that needs to be propagated (with, likely, special handling for
         // scrolling).
-        self.base_state.needs_inval = true;
+        self.base_state.invalidate_all();
     }
 
     /// Request a [`paint`] pass.
     ///
+    /// This does not cause layout to be recomputed; if only the appearance
+    /// of your widget has changed, and not its size, this is the method
+    /// to call.
+    ///
     /// [`paint`]: trait.Widget.html#tymethod.paint
     pub fn request_paint(&mut self) {
+        self.base_state.invalidate_all();
+    }
+
+    /// Request a [`paint`] pass, for only part of this widget.
+    ///
+    /// The rect is in this widget's coordinate space. This is useful for
+    /// widgets that change frequently but only in a small area, such as a
+    /// text cursor. With the `partial-invalidation` feature, only the
+    /// invalidated part of the window is repainted on macOS and GTK; otherwise
+    /// this is the same as `request_paint`.
+    ///
+    /// [`paint`]: trait.Widget.html#tymethod.paint
+    pub fn request_paint_rect(&mut self, rect: Rect) {
         self.base_state.needs_inval = true;
+        self.base_state.invalid.add_rect(rect);
     }
 
     /// Request a layout pass.
@@ -147,17 +220,27 @@ impl<'a> EventCtx<'a> {
     /// (such as if it would like to change the layout of children in
     /// response to some event) it must call this method.
     ///
+    /// Only widgets that have requested layout (and their ancestors) are
+    /// guaranteed to have their [`layout`] method called; a widget that has
+    /// not requested layout and that receives the same constraints as in the
+    /// previous pass will keep its previous size. This also requests a paint.
+    ///
     /// [`layout`]: trait.Widget.html#tymethod.layout
     pub fn request_layout(&mut self) {
         self.base_state.needs_layout = true;
-        self.base_state.needs_inval = true;
+        self.base_state.invalidate_all();
     }
 
     /// Indicate that your children have changed.
     ///
     /// Widgets must call this method after adding a new child.
+    ///
+    /// This also requests a layout pass, so that new children are laid out
+    /// and removed children no longer take up space.
     pub fn children_changed(&mut self) {
         self.base_state.children_changed = true;
+        self.base_state.needs_layout = true;
+        self.base_state.invalidate_all();
     }
 
     /// Get an object which can create text layouts.
@@ -215,7 +298,9 @@ impl<'a> EventCtx<'a> {
     /// down and then up.
     ///
     /// When a widget is active, it gets mouse events even when the mouse
-    /// is dragged away.
+    /// is dragged away, and other widgets outside of it don't get them. The
+    /// active widget's own children get mouse events as usual, when the
+    /// mouse is over them.
     ///
     /// [`set_active`]: struct.EventCtx.html#method.set_active
     pub fn is_active(&self) -> bool {
@@ -227,10 +312,35 @@ impl<'a> EventCtx<'a> {
         &self.window
     }
 
+    /// Close the window this widget is in.
+    ///
+    /// Unlike the [`CLOSE_WINDOW`] command, this does not ask
+    /// [`AppDelegate::window_should_close`] first; use it to close the
+    /// window once the user has confirmed that they want to. Code outside a
+    /// widget can do the same with [`FORCE_CLOSE_WINDOW`].
+    ///
+    /// [`CLOSE_WINDOW`]: commands/constant.CLOSE_WINDOW.html
+    /// [`FORCE_CLOSE_WINDOW`]: commands/constant.FORCE_CLOSE_WINDOW.html
+    /// [`AppDelegate::window_should_close`]: trait.AppDelegate.html#method.window_should_close
+    pub fn close_window(&mut self) {
+        self.window.close();
+    }
+
     /// Set the event as "handled", which stops its propagation to other
     /// widgets.
+    ///
+    /// Siblings that have not yet seen the event will not receive it, and
+    /// ancestors can check [`is_handled`] before acting on it themselves.
+    ///
+    /// This has no effect on broadcast commands (see [`Target`]), which are
+    /// always delivered to every widget.
+    ///
+    /// [`Target`]: enum.Target.html
+    /// [`is_handled`]: #method.is_handled
     pub fn set_handled(&mut self) {
-        self.is_handled = true;
+        if !self.is_broadcast {
+            self.is_handled = true;
+        }
     }
 
     /// Determine whether the event has been handled by some other widget.
@@ -278,6 +388,17 @@ impl<'a> EventCtx<'a> {
         self.base_state.request_focus = Some(FocusChange::Focus(self.widget_id()));
     }
 
+    /// Give keyboard focus to another widget, such as one of this widget's
+    /// descendants.
+    ///
+    /// The target should be registered for focus; see
+    /// [`LifeCycleCtx::register_for_focus`].
+    ///
+    /// [`LifeCycleCtx::register_for_focus`]: struct.LifeCycleCtx.html#method.register_for_focus
+    pub fn set_focus(&mut self, target: WidgetId) {
+        self.base_state.request_focus = Some(FocusChange::Focus(target));
+    }
+
     /// Transfer focus to the next focusable widget.
     ///
     /// This should only be called by a widget that currently has focus.
@@ -285,7 +406,10 @@ impl<'a> EventCtx<'a> {
         if self.focus_widget == Some(self.widget_id()) {
             self.base_state.request_focus = Some(FocusChange::Next);
         } else {
-            log::warn!("focus_next can only be called by the currently focused widget");
+            log::warn!(
+                target: "druid::event",
+                "focus_next can only be called by the currently focused widget"
+            );
         }
     }
 
@@ -296,7 +420,27 @@ impl<'a> EventCtx<'a> {
         if self.focus_widget == Some(self.widget_id()) {
             self.base_state.request_focus = Some(FocusChange::Previous);
         } else {
-            log::warn!("focus_prev can only be called by the currently focused widget");
+            log::warn!(
+                target: "druid::event",
+                "focus_prev can only be called by the currently focused widget"
+            );
+        }
+    }
+
+    /// Move focus for Tab or Shift+Tab, returning `true` if `key` was one
+    /// of them.
+    ///
+    /// This is for widgets that take focus, to call with the `KeyDown`
+    /// events they get while focused.
+    pub(crate) fn handle_focus_key(&mut self, key: &KeyEvent) -> bool {
+        if HotKey::new(None, KeyCode::Tab).matches(key) {
+            self.focus_next();
+            true
+        } else if HotKey::new(SysMods::Shift, KeyCode::Tab).matches(key) {
+            self.focus_prev();
+            true
+        } else {
+            false
         }
     }
 
@@ -307,23 +451,140 @@ impl<'a> EventCtx<'a> {
         if self.focus_widget == Some(self.widget_id()) {
             self.base_state.request_focus = Some(FocusChange::Resign);
         } else {
-            log::warn!("resign_focus can only be called by the currently focused widget");
+            log::warn!(
+                target: "druid::event",
+                "resign_focus can only be called by the currently focused widget"
+            );
+        }
+    }
+
+    /// Give up focus if this widget or one of its descendants has it.
+    ///
+    /// This is for containers that are about to stop showing the focused
+    /// widget, so that keys don't go to something that can't be seen.
+    pub(crate) fn resign_focus_within(&mut self) {
+        if self.has_focus() {
+            self.base_state.request_focus = Some(FocusChange::Resign);
         }
     }
 
     /// Request an animation frame.
     pub fn request_anim_frame(&mut self) {
         self.base_state.request_anim = true;
-        self.base_state.needs_inval = true;
+        self.base_state.invalidate_all();
+    }
+
+    /// The current time.
+    ///
+    /// Use this rather than `Instant::now` when computing timer deadlines or
+    /// measuring elapsed time. In the [test harness] it returns a virtual
+    /// time that only moves forwards when the test says so, which makes
+    /// time-dependent widgets testable.
+    ///
+    /// [test harness]: tests/struct.Harness.html#method.advance_time
+    pub fn now(&self) -> Instant {
+        match self.clock {
+            Some(clock) => clock.now(),
+            None => Instant::now(),
+        }
     }
 
     /// Request a timer event.
     ///
     /// The return value is a token, which can be used to associate the
-    /// request with the event.
+    /// request with the event. The deadline should be computed relative
+    /// to [`now`].
+    ///
+    /// [`now`]: #method.now
     pub fn request_timer(&mut self, deadline: Instant) -> TimerToken {
         self.base_state.request_timer = true;
-        self.window.request_timer(deadline)
+        match self.clock {
+            Some(clock) => clock.request_timer(deadline),
+            None => self.window.request_timer(deadline),
+        }
+    }
+
+    /// Run `task` on another thread, delivering what it reports to this
+    /// widget as commands.
+    ///
+    /// The task can report its progress with [`TaskCtx::report`], which
+    /// arrives as a command with the `progress` selector and the reported
+    /// value as its argument. When the task returns, its result arrives as
+    /// a command with the `done` selector.
+    ///
+    /// The task is cancelled when its window closes, or when the returned
+    /// handle is used to [`cancel`] it; after that nothing more from it is
+    /// delivered. Cancelling can't interrupt the thread, so a long task
+    /// should check [`TaskCtx::is_cancelled`] as it goes, and stop early.
+    ///
+    /// ```
+    /// # use druid::{EventCtx, Selector};
+    /// const PROGRESS: Selector = Selector::new("my-app.count-progress");
+    /// const DONE: Selector = Selector::new("my-app.count-done");
+    ///
+    /// fn start_counting(ctx: &mut EventCtx) {
+    ///     ctx.spawn(PROGRESS, DONE, |task| {
+    ///         let mut total = 0u64;
+    ///         for i in 0..100u64 {
+    ///             if task.is_cancelled() {
+    ///                 break;
+    ///             }
+    ///             total += i;
+    ///             task.report(i);
+    ///         }
+    ///         total
+    ///     });
+    /// }
+    /// ```
+    ///
+    /// [`TaskCtx::report`]: struct.TaskCtx.html#method.report
+    /// [`TaskCtx::is_cancelled`]: struct.TaskCtx.html#method.is_cancelled
+    /// [`cancel`]: struct.TaskHandle.html#method.cancel
+    pub fn spawn<P, R>(
+        &mut self,
+        progress: Selector,
+        done: Selector,
+        task: impl FnOnce(&TaskCtx<P>) -> R + Send + 'static,
+    ) -> TaskHandle
+    where
+        P: Any + Send,
+        R: Any + Send,
+    {
+        let target = Target::Widget(self.widget_id());
+        self.tasks.spawn(target, progress, done, task)
+    }
+
+    /// Run `future`, and send its output to this widget as a command with
+    /// the `done` selector once it completes.
+    ///
+    /// The future is run by the [`Executor`] given to
+    /// [`AppLauncher::with_executor`], or, if there isn't one, on a pooled
+    /// thread that sleeps while the future waits (see [`Executor`] for the
+    /// details). Like a task started with [`spawn`], it is cancelled when
+    /// its window closes, or with the returned handle.
+    ///
+    /// [`Executor`]: trait.Executor.html
+    /// [`AppLauncher::with_executor`]: struct.AppLauncher.html#method.with_executor
+    /// [`spawn`]: #method.spawn
+    pub fn spawn_future<R: Any + Send>(
+        &mut self,
+        future: impl Future<Output = R> + Send + 'static,
+        done: Selector,
+    ) -> TaskHandle {
+        let target = Target::Widget(self.widget_id());
+        self.tasks.spawn_future(target, done, future)
+    }
+
+    /// Put `text` on the system clipboard.
+    ///
+    /// In the [test harness], the text is kept by the harness instead.
+    ///
+    /// [test harness]: tests/struct.Harness.html#method.clipboard_string
+    pub fn put_clipboard_string(&mut self, text: &str) {
+        match self.mock_clipboard {
+            Some(clipboard) => *clipboard.borrow_mut() = Some(text.to_owned()),
+            None => Application::clipboard().put_string(text),
+        }
     }
 
     /// The layout size.
@@ -343,7 +604,8 @@ impl<'a> EventCtx<'a> {
     ///
     /// Commands are run in the order they are submitted; all commands
     /// submitted during the handling of an event are executed before
-    /// the [`update`] method is called.
+    /// the [`update`] method is called. See the [`Command`] docs for the
+    /// full delivery order.
     ///
     /// [`Command`]: struct.Command.html
     /// [`update`]: trait.Widget.html#tymethod.update
@@ -353,7 +615,8 @@ impl<'a> EventCtx<'a> {
         target: impl Into<Option<Target>>,
     ) {
         let target = target.into().unwrap_or_else(|| self.window_id.into());
-        self.command_queue.push_back((target, command.into()))
+        let command = command.into().submitted_by(self.base_state.id);
+        self.command_queue.push_back((target, command))
     }
 
     /// Get the window id.
@@ -361,16 +624,54 @@ impl<'a> EventCtx<'a> {
         self.window_id
     }
 
+    /// Returns `true` unless the window is minimized or, where the platform
+    /// reports it, hidden behind other windows.
+    ///
+    /// While it is not visible the window is not painted, and animation
+    /// frames are not delivered; timers still fire. Widgets are sent
+    /// [`LifeCycle::WindowVisibilityChanged`] when this changes.
+    ///
+    /// [`LifeCycle::WindowVisibilityChanged`]: enum.LifeCycle.html#variant.WindowVisibilityChanged
+    pub fn is_window_visible(&self) -> bool {
+        self.window_visible
+    }
+
     /// get the `WidgetId` of the current widget.
     pub fn widget_id(&self) -> WidgetId {
         self.base_state.id
     }
 
+    /// The innermost widget under `point`, in window coordinates, such as a
+    /// mouse event's `window_pos`.
+    ///
+    /// This is found the way [`WidgetTree::widget_id_at`] finds it, as the
+    /// window was when it was last laid out and painted; it doesn't see
+    /// changes made while handling the current event.
+    ///
+    /// [`WidgetTree::widget_id_at`]: inspect/struct.WidgetTree.html#method.widget_id_at
+    pub fn widget_id_at(&self, point: Point) -> Option<WidgetId> {
+        self.widget_tree.and_then(|tree| tree.widget_id_at(point))
+    }
+
+    /// The part of the widget with `id` that can be seen, in window
+    /// coordinates, or `None` if it is not in this window.
+    ///
+    /// Like [`widget_id_at`], this is as the window was last painted; see
+    /// [`WidgetTree::rect_of`].
+    ///
+    /// [`widget_id_at`]: #method.widget_id_at
+    /// [`WidgetTree::rect_of`]: inspect/struct.WidgetTree.html#method.rect_of
+    pub fn rect_of(&self, id: WidgetId) -> Option<Rect> {
+        self.widget_tree.and_then(|tree| tree.rect_of(id))
+    }
+
     pub(crate) fn make_lifecycle_ctx(&mut self) -> LifeCycleCtx {
         LifeCycleCtx {
             command_queue: self.command_queue,
             base_state: self.base_state,
             window_id: self.window_id,
+            window_visible: self.window_visible,
+            widget_tree: self.widget_tree,
         }
     }
 }
@@ -378,14 +679,28 @@ impl<'a> EventCtx<'a> {
 impl<'a> LifeCycleCtx<'a> {
     #[deprecated(since = "0.5.0", note = "use request_paint instead")]
     pub fn invalidate(&mut self) {
-        self.base_state.needs_inval = true;
+        self.base_state.invalidate_all();
     }
 
     /// Request a [`paint`] pass.
     ///
     /// [`paint`]: trait.Widget.html#tymethod.paint
     pub fn request_paint(&mut self) {
+        self.base_state.invalidate_all();
+    }
+
+    /// Request a [`paint`] pass, for only part of this widget.
+    ///
+    /// The rect is in this widget's coordinate space. This is useful for
+    /// widgets that change frequently but only in a small area, such as a
+    /// text cursor. With the `partial-invalidation` feature, only the
+    /// invalidated part of the window is repainted on macOS and GTK; otherwise
+    /// this is the same as `request_paint`.
+    ///
+    /// [`paint`]: trait.Widget.html#tymethod.paint
+    pub fn request_paint_rect(&mut self, rect: Rect) {
         self.base_state.needs_inval = true;
+        self.base_state.invalid.add_rect(rect);
     }
 
     /// Request layout.
@@ -395,7 +710,7 @@ impl<'a> LifeCycleCtx<'a> {
     /// [`EventCtx::request_layout`]: struct.EventCtx.html#method.request_layout
     pub fn request_layout(&mut self) {
         self.base_state.needs_layout = true;
-        self.base_state.needs_inval = true;
+        self.base_state.invalidate_all();
     }
 
     /// Returns the current widget's `WidgetId`.
@@ -418,11 +733,50 @@ impl<'a> LifeCycleCtx<'a> {
         self.base_state.focus_chain.push(self.widget_id());
     }
 
+    /// Run `f`, leaving any widgets it registers for focus out of the focus
+    /// chain.
+    ///
+    /// Containers use this to pass lifecycle events to children that can't
+    /// be seen, so that tab skips over them. The container should call
+    /// [`children_changed`] when the children become visible again.
+    ///
+    /// [`children_changed`]: #method.children_changed
+    pub(crate) fn without_focus<R>(&mut self, f: impl FnOnce(&mut LifeCycleCtx) -> R) -> R {
+        let len = self.base_state.focus_chain.len();
+        let result = f(self);
+        self.base_state.focus_chain.truncate(len);
+        result
+    }
+
+    /// Register `key` as this widget's access key.
+    ///
+    /// When the user presses alt and the key, the widget is focused, and
+    /// receives the [`ACTIVATE_ACCESS_KEY`] command. If several widgets in
+    /// the window share a key, pressing it moves focus from one to the next
+    /// instead, without activating any of them. Letters are matched without
+    /// regard to case.
+    ///
+    /// This should be called on [`LifeCycle::WidgetAdded`]. Access keys are
+    /// not used on macOS, where alt is used to type characters.
+    ///
+    /// [`ACTIVATE_ACCESS_KEY`]: commands/constant.ACTIVATE_ACCESS_KEY.html
+    /// [`LifeCycle::WidgetAdded`]: enum.LifeCycle.html#variant.WidgetAdded
+    pub fn register_access_key(&mut self, key: char) {
+        let key = key.to_lowercase().next().unwrap_or(key);
+        let id = self.widget_id();
+        self.base_state.access_keys.push((key, id));
+    }
+
     /// Indicate that your children have changed.
     ///
     /// Widgets must call this method after adding a new child.
+    ///
+    /// This also requests a layout pass, so that new children are laid out
+    /// and removed children no longer take up space.
     pub fn children_changed(&mut self) {
         self.base_state.children_changed = true;
+        self.base_state.needs_layout = true;
+        self.base_state.invalidate_all();
     }
 
     /// Request an animation frame.
@@ -430,35 +784,89 @@ impl<'a> LifeCycleCtx<'a> {
         self.base_state.request_anim = true;
     }
 
-    /// Submit a [`Command`] to be run after this event is handled.
+    /// Submit a [`Command`] to be run after this lifecycle event.
     ///
-    /// Commands are run in the order they are submitted; all commands
-    /// submitted during the handling of an event are executed before
-    /// the [`update`] method is called.
+    /// Commands are run in the order they are submitted, once the current
+    /// pass over the widget tree is over, and are followed by an [`update`].
+    /// A lifecycle event sent during an update, such as [`WidgetAdded`] for
+    /// a new child, queues its commands behind those of the update. See the
+    /// [`Command`] docs for the full delivery order.
     ///
     /// [`Command`]: struct.Command.html
     /// [`update`]: trait.Widget.html#tymethod.update
+    /// [`WidgetAdded`]: enum.LifeCycle.html#variant.WidgetAdded
     pub fn submit_command(
         &mut self,
         command: impl Into<Command>,
         target: impl Into<Option<Target>>,
     ) {
         let target = target.into().unwrap_or_else(|| self.window_id.into());
-        self.command_queue.push_back((target, command.into()))
+        let command = command.into().submitted_by(self.base_state.id);
+        self.command_queue.push_back((target, command))
+    }
+
+    /// Returns `true` unless the window is minimized or hidden.
+    ///
+    /// See [`EventCtx::is_window_visible`] for details.
+    ///
+    /// [`EventCtx::is_window_visible`]: struct.EventCtx.html#method.is_window_visible
+    pub fn is_window_visible(&self) -> bool {
+        self.window_visible
+    }
+
+    /// The layout size.
+    ///
+    /// This is the layout size as ultimately determined by the parent
+    /// container, on the previous layout pass.
+    pub fn size(&self) -> Size {
+        self.base_state.size()
+    }
+
+    /// The innermost widget under `point`, in window coordinates.
+    ///
+    /// See [`EventCtx::widget_id_at`].
+    ///
+    /// [`EventCtx::widget_id_at`]: struct.EventCtx.html#method.widget_id_at
+    pub fn widget_id_at(&self, point: Point) -> Option<WidgetId> {
+        self.widget_tree.and_then(|tree| tree.widget_id_at(point))
+    }
+
+    /// The part of the widget with `id` that can be seen, in window
+    /// coordinates.
+    ///
+    /// See [`EventCtx::rect_of`].
+    ///
+    /// [`EventCtx::rect_of`]: struct.EventCtx.html#method.rect_of
+    pub fn rect_of(&self, id: WidgetId) -> Option<Rect> {
+        self.widget_tree.and_then(|tree| tree.rect_of(id))
     }
 }
 
 impl<'a> UpdateCtx<'a> {
     #[deprecated(since = "0.5.0", note = "use request_paint instead")]
     pub fn invalidate(&mut self) {
-        self.base_state.needs_inval = true;
+        self.base_state.invalidate_all();
     }
 
     /// Request a [`paint`] pass.
     ///
     /// [`paint`]: trait.Widget.html#tymethod.paint
     pub fn request_paint(&mut self) {
+        self.base_state.invalidate_all();
+    }
+
+    /// Request a [`paint`] pass, for only part of this widget.
+    ///
+    /// The rect is in this widget's coordinate space. This is useful for
+    /// widgets that change frequently but only in a small area, such as a
+    /// text cursor. With the `partial-invalidation` feature, only the
+    /// invalidated part of the window is repainted on macOS and GTK; otherwise
+    /// this is the same as `request_paint`.
+    ///
+    /// [`paint`]: trait.Widget.html#tymethod.paint
+    pub fn request_paint_rect(&mut self, rect: Rect) {
         self.base_state.needs_inval = true;
+        self.base_state.invalid.add_rect(rect);
     }
 
     /// Request layout.
@@ -468,14 +876,71 @@ impl<'a> UpdateCtx<'a> {
     /// [`EventCtx::request_layout`]: struct.EventCtx.html#method.request_layout
     pub fn request_layout(&mut self) {
         self.base_state.needs_layout = true;
-        self.base_state.needs_inval = true;
+        self.base_state.invalidate_all();
+    }
+
+    /// Request an animation frame.
+    ///
+    /// This lets a widget start animating towards new data.
+    pub fn request_anim_frame(&mut self) {
+        self.base_state.request_anim = true;
+        self.base_state.invalidate_all();
+    }
+
+    /// The current time.
+    ///
+    /// See [`EventCtx::now`] for more information.
+    ///
+    /// [`EventCtx::now`]: struct.EventCtx.html#method.now
+    pub fn now(&self) -> Instant {
+        match self.clock {
+            Some(clock) => clock.now(),
+            None => Instant::now(),
+        }
+    }
+
+    /// Request a timer event, for reacting to new data some time later.
+    ///
+    /// See [`EventCtx::request_timer`] for more information.
+    ///
+    /// [`EventCtx::request_timer`]: struct.EventCtx.html#method.request_timer
+    pub fn request_timer(&mut self, deadline: Instant) -> TimerToken {
+        self.base_state.request_timer = true;
+        match self.clock {
+            Some(clock) => clock.request_timer(deadline),
+            None => self.window.request_timer(deadline),
+        }
     }
 
     /// Indicate that your children have changed.
     ///
     /// Widgets must call this method after adding a new child.
+    ///
+    /// This also requests a layout pass, so that new children are laid out
+    /// and removed children no longer take up space.
     pub fn children_changed(&mut self) {
         self.base_state.children_changed = true;
+        self.base_state.needs_layout = true;
+        self.base_state.invalidate_all();
+    }
+
+    /// Submit a [`Command`] to be run after this update.
+    ///
+    /// This lets a widget announce a change in its data, for instance to
+    /// an [`AppDelegate`]. The command is delivered once every widget has
+    /// been updated, and is followed by another update pass. Commands from
+    /// one update pass are delivered in the order they were submitted.
+    ///
+    /// [`Command`]: struct.Command.html
+    /// [`AppDelegate`]: trait.AppDelegate.html
+    pub fn submit_command(
+        &mut self,
+        command: impl Into<Command>,
+        target: impl Into<Option<Target>>,
+    ) {
+        let target = target.into().unwrap_or_else(|| self.window_id.into());
+        let command = command.into().submitted_by(self.base_state.id);
+        self.command_queue.push_back((target, command))
     }
 
     /// Get an object which can create text layouts.
@@ -494,6 +959,15 @@ impl<'a> UpdateCtx<'a> {
         self.window_id
     }
 
+    /// Returns `true` unless the window is minimized or hidden.
+    ///
+    /// See [`EventCtx::is_window_visible`] for details.
+    ///
+    /// [`EventCtx::is_window_visible`]: struct.EventCtx.html#method.is_window_visible
+    pub fn is_window_visible(&self) -> bool {
+        self.window_visible
+    }
+
     /// get the `WidgetId` of the current widget.
     pub fn widget_id(&self) -> WidgetId {
         self.base_state.id
@@ -525,6 +999,32 @@ impl<'a, 'b> LayoutCtx<'a, 'b> {
     pub fn set_paint_insets(&mut self, insets: impl Into<Insets>) {
         self.paint_insets = insets.into().nonnegative();
     }
+
+    /// Opt this widget out of layout caching.
+    ///
+    /// Normally, a widget that has not requested layout and that is given
+    /// the same constraints as in the previous pass is not laid out again,
+    /// and keeps its previous size. A widget whose layout depends on
+    /// something other than its constraints, data, and `Env` (such as the
+    /// current time) can call this during `layout` to ensure that its
+    /// `layout` method is called on every layout pass.
+    ///
+    /// This also disables caching for all of the widget's ancestors.
+    pub fn disable_layout_cache(&mut self) {
+        self.cache_layout = false;
+    }
+
+    /// Ask the nearest enclosing [`Scroll`] to move its offset by `delta`,
+    /// after this layout pass.
+    ///
+    /// A widget whose content moved within it, such as a list that had
+    /// items inserted above the visible ones, can use this so that what the
+    /// user was looking at stays in view.
+    ///
+    /// [`Scroll`]: widget/struct.Scroll.html
+    pub fn shift_scroll(&mut self, delta: Vec2) {
+        self.scroll_shift += delta;
+    }
 }
 
 impl<'a, 'b: 'a> PaintCtx<'a, 'b> {
@@ -575,6 +1075,17 @@ impl<'a, 'b: 'a> PaintCtx<'a, 'b> {
         is_child || self.focus_widget == Some(self.widget_id())
     }
 
+    /// Whether widgets should show which keys are their access keys, for
+    /// instance by underlining them in their labels.
+    ///
+    /// This is `true` while alt is held down; see
+    /// [`LifeCycleCtx::register_access_key`].
+    ///
+    /// [`LifeCycleCtx::register_access_key`]: struct.LifeCycleCtx.html#method.register_access_key
+    pub fn show_access_keys(&self) -> bool {
+        self.show_access_keys
+    }
+
     /// Returns the currently visible [`Region`].
     ///
     /// [`Region`]: struct.Region.html
@@ -583,6 +1094,51 @@ impl<'a, 'b: 'a> PaintCtx<'a, 'b> {
         &self.region
     }
 
+    /// Round `point`, in this widget's coordinates, to the nearest corner of
+    /// a device pixel.
+    ///
+    /// Pixel corners are found by mapping the point through the current
+    /// transform and the window's scale factor. Each device coordinate is
+    /// then rounded to the nearest whole number, with halves rounded up, so
+    /// two rects that share an edge before snapping still share it after.
+    pub fn snap_to_pixel(&mut self, point: impl Into<Point>) -> Point {
+        let to_device = self.pixel_transform();
+        let device = to_device * point.into();
+        let snapped = Point::new((device.x + 0.5).floor(), (device.y + 0.5).floor());
+        to_device.inverse() * snapped
+    }
+
+    /// Snap each edge of `rect` to the nearest pixel boundary, using the
+    /// rounding rule of [`snap_to_pixel`].
+    ///
+    /// This assumes that the current transform does not rotate or skew.
+    /// Filling the result gives hard edges; to stroke it crisply, use a
+    /// width from [`snap_stroke_width`] and inset it by half of that.
+    ///
+    /// [`snap_to_pixel`]: #method.snap_to_pixel
+    /// [`snap_stroke_width`]: #method.snap_stroke_width
+    pub fn snap_rect(&mut self, rect: Rect) -> Rect {
+        let p0 = self.snap_to_pixel((rect.x0, rect.y0));
+        let p1 = self.snap_to_pixel((rect.x1, rect.y1));
+        Rect::from_points(p0, p1)
+    }
+
+    /// Round a stroke width, in this widget's coordinates, to a whole number
+    /// of device pixels, and at least one.
+    pub fn snap_stroke_width(&mut self, width: f64) -> f64 {
+        let coeffs = self.pixel_transform().as_coeffs();
+        let scale = (coeffs[0] * coeffs[3] - coeffs[1] * coeffs[2]).abs().sqrt();
+        if scale == 0.0 {
+            return width;
+        }
+        (width * scale).round().max(1.0) / scale
+    }
+
+    /// The transform from this widget's coordinates to device pixels.
+    fn pixel_transform(&mut self) -> Affine {
+        Affine::scale(self.window_scale) * self.render_ctx.current_transform()
+    }
+
     /// Creates a temporary `PaintCtx` with a new visible region, and calls
     /// the provided function with that `PaintCtx`.
     ///
@@ -593,12 +1149,17 @@ impl<'a, 'b: 'a> PaintCtx<'a, 'b> {
             render_ctx: self.render_ctx,
             base_state: self.base_state,
             z_ops: Vec::new(),
+            debug_boxes: Vec::new(),
+            record_paint: self.record_paint,
             window_id: self.window_id,
             focus_widget: self.focus_widget,
+            show_access_keys: self.show_access_keys,
             region: region.into(),
+            window_scale: self.window_scale,
         };
         f(&mut child_ctx);
         self.z_ops.append(&mut child_ctx.z_ops);
+        self.debug_boxes.append(&mut child_ctx.debug_boxes);
     }
 
     /// Saves the current context, executes the closures, and restores the context.
@@ -624,14 +1185,14 @@ impl<'a, 'b: 'a> PaintCtx<'a, 'b> {
     /// ```
     pub fn with_save(&mut self, f: impl FnOnce(&mut PaintCtx)) {
         if let Err(e) = self.render_ctx.save() {
-            log::error!("Failed to save RenderContext: '{}'", e);
+            log::error!(target: "druid::paint", "Failed to save RenderContext: '{}'", e);
             return;
         }
 
         f(self);
 
         if let Err(e) = self.render_ctx.restore() {
-            log::error!("Failed to restore RenderContext: '{}'", e);
+            log::error!(target: "druid::paint", "Failed to restore RenderContext: '{}'", e);
         }
     }
 
@@ -653,11 +1214,36 @@ impl<'a, 'b: 'a> PaintCtx<'a, 'b> {
 }
 
 impl Region {
+    /// The empty region.
+    pub const EMPTY: Region = Region(Rect::ZERO);
+
     /// Returns the smallest `Rect` that encloses the entire region.
     pub fn to_rect(&self) -> Rect {
         self.0
     }
 
+    /// Returns `true` if this region covers no area.
+    pub fn is_empty(&self) -> bool {
+        self.0.area() == 0.
+    }
+
+    /// Adds a rect to this region.
+    pub fn add_rect(&mut self, rect: Rect) {
+        if rect.area() == 0. {
+            return;
+        }
+        self.0 = if self.is_empty() {
+            rect
+        } else {
+            self.0.union(rect)
+        };
+    }
+
+    /// Removes everything from this region.
+    pub fn clear(&mut self) {
+        self.0 = Rect::ZERO;
+    }
+
     /// Returns `true` if `self` intersects with `other`.
     #[inline]
     pub fn intersects(&self, other: Rect) -> bool {
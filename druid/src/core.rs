@@ -19,11 +19,14 @@ use std::collections::VecDeque;
 use log;
 
 use crate::bloom::Bloom;
+use crate::debug_overlay::DebugBox;
+use crate::inspect;
 use crate::kurbo::{Affine, Insets, Rect, Shape, Size};
 use crate::piet::RenderContext;
+use crate::theme;
 use crate::{
     BoxConstraints, Command, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
-    PaintCtx, Target, UpdateCtx, Widget, WidgetId,
+    PaintCtx, Region, Target, UpdateCtx, Widget, WidgetId,
 };
 
 /// Our queue type
@@ -77,12 +80,36 @@ pub(crate) struct BaseState {
     // This should become an invalidation rect.
     pub(crate) needs_inval: bool,
 
+    /// The area of this widget that needs to be repainted, in the widget's
+    /// own coordinate space. This includes the invalid areas of children.
+    pub(crate) invalid: Region,
+
     pub(crate) is_hot: bool,
 
     pub(crate) is_active: bool,
 
     pub(crate) needs_layout: bool,
 
+    /// The constraints passed to the last call to `layout`, and the size
+    /// that was returned.
+    ///
+    /// If a widget has not requested layout and receives the same constraints
+    /// again, this size is reused without calling the widget's `layout` method.
+    pub(crate) layout_cache: Option<(BoxConstraints, Size)>,
+
+    /// The size returned by the last call to the widget's `layout` method,
+    /// which its parent may not have used.
+    pub(crate) requested_size: Size,
+
+    /// Answers to intrinsic size queries, as the query, the extent on the
+    /// other axis, and the answer. They are kept until the widget needs
+    /// layout.
+    intrinsic_cache: Vec<(Intrinsic, f64, f64)>,
+
+    /// The `intrinsic_cache` was emptied in this layout pass, so answers in
+    /// it are current even though `needs_layout` is set.
+    intrinsics_fresh: bool,
+
     /// Any descendant is active.
     has_active: bool,
 
@@ -96,9 +123,30 @@ pub(crate) struct BaseState {
     pub(crate) request_timer: bool,
 
     pub(crate) focus_chain: Vec<WidgetId>,
+    /// The access keys registered by this widget and its descendants, in
+    /// focus chain order.
+    pub(crate) access_keys: Vec<(char, WidgetId)>,
     pub(crate) request_focus: Option<FocusChange>,
     pub(crate) children: Bloom<WidgetId>,
+    /// The number of descendants; unlike `children`, this is exact.
+    pub(crate) descendant_count: usize,
     pub(crate) children_changed: bool,
+
+    /// This widget, or a descendant, needs to send `ViewportChanged` after
+    /// the current layout pass.
+    pub(crate) viewport_changed: bool,
+    /// A rect, in this widget's coordinates, that an enclosing `Scroll`
+    /// should bring into view.
+    pub(crate) scroll_to: Option<Rect>,
+}
+
+/// One of the intrinsic size queries on `Widget`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Intrinsic {
+    MinWidth,
+    MaxWidth,
+    MinHeight,
+    MaxHeight,
 }
 
 /// Methods by which a widget can attempt to change focus state.
@@ -240,6 +288,46 @@ impl<T, W: Widget<T>> WidgetPod<T, W> {
     }
 }
 
+/// How far the focus ring reaches outside the widget's bounds.
+fn focus_ring_outset(env: &Env) -> f64 {
+    env.get(theme::FOCUS_RING_OFFSET) + env.get(theme::FOCUS_RING_WIDTH) * 2.0
+}
+
+/// The area covered by the focus ring of a widget of the given size.
+fn focus_ring_bounds(size: Size, env: &Env) -> Rect {
+    let outset = focus_ring_outset(env);
+    size.to_rect().inflate(outset, outset)
+}
+
+/// Draw the focus ring around the widget painting into `ctx`.
+///
+/// The ring is drawn in `theme::PRIMARY_LIGHT`; with
+/// `theme::HIGH_CONTRAST` set it uses the label color and is drawn at
+/// least twice as wide. The ring is never animated.
+fn paint_focus_ring(ctx: &mut PaintCtx, env: &Env) {
+    let (color, width) = if env.get(theme::HIGH_CONTRAST) {
+        let width = env.get(theme::FOCUS_RING_WIDTH);
+        (env.get(theme::LABEL_COLOR), (width * 2.0).max(2.0))
+    } else {
+        (
+            env.get(theme::PRIMARY_LIGHT),
+            env.get(theme::FOCUS_RING_WIDTH),
+        )
+    };
+    if width <= 0.0 {
+        return;
+    }
+    let offset = env.get(theme::FOCUS_RING_OFFSET);
+    let outset = offset + width / 2.0;
+    let radius = env.get(theme::BUTTON_BORDER_RADIUS) + outset;
+    let ring = ctx
+        .size()
+        .to_rect()
+        .inflate(outset, outset)
+        .to_rounded_rect(radius);
+    ctx.stroke(ring, &color, width);
+}
+
 impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
     /// Paint a child widget.
     ///
@@ -253,26 +341,39 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
     /// [`paint`]: trait.Widget.html#tymethod.paint
     /// [`paint_with_offset`]: #method.paint_with_offset
     pub fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        enter_span!(TRACE, "paint", id = ?self.id(), widget = self.inner.type_name());
         let mut inner_ctx = PaintCtx {
             render_ctx: ctx.render_ctx,
             window_id: ctx.window_id,
             z_ops: Vec::new(),
+            debug_boxes: Vec::new(),
+            record_paint: ctx.record_paint,
             region: ctx.region.clone(),
             base_state: &self.state,
             focus_widget: ctx.focus_widget,
+            show_access_keys: ctx.show_access_keys,
+            window_scale: ctx.window_scale,
         };
+        let outlined = env.get(Env::DEBUG_PAINT);
+        if ctx.record_paint || outlined {
+            let transform = inner_ctx.render_ctx.current_transform();
+            ctx.debug_boxes.push(DebugBox {
+                rect: transform.transform_rect_bbox(self.state.size().to_rect()),
+                id: self.id(),
+                type_name: self.inner.type_name(),
+                outlined,
+            });
+        }
         self.inner.paint(&mut inner_ctx, data, &env);
         ctx.z_ops.append(&mut inner_ctx.z_ops);
+        ctx.debug_boxes.append(&mut inner_ctx.debug_boxes);
 
-        if env.get(Env::DEBUG_PAINT) {
-            const BORDER_WIDTH: f64 = 1.0;
-            let rect = inner_ctx.size().to_rect().inset(BORDER_WIDTH / -2.0);
-            let id = self.id().to_raw();
-            let color = env.get_debug_color(id);
-            inner_ctx.stroke(rect, &color, BORDER_WIDTH);
+        if ctx.focus_widget == Some(self.id()) {
+            paint_focus_ring(&mut inner_ctx, env);
         }
 
         self.state.needs_inval = false;
+        self.state.invalid.clear();
     }
 
     /// Paint the widget, translating it by the origin of its layout rectangle.
@@ -315,7 +416,22 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
     /// Generally called by container widgets as part of their [`layout`]
     /// method.
     ///
+    /// If neither this widget nor any of its descendants have requested
+    /// layout since the last layout pass, and the constraints are the same
+    /// as last time, the widget's [`layout`] method is not called, and the
+    /// previously computed size is returned. Because this check happens
+    /// here, a container whose subtree is unchanged is skipped entirely.
+    ///
+    /// Widgets whose layout depends on their data are expected to call
+    /// [`request_layout`] from [`update`] when that data changes; a change
+    /// in the `Env` always invalidates the cached layout. Widgets that
+    /// need to be laid out on every pass can opt out of caching with
+    /// [`LayoutCtx::disable_layout_cache`].
+    ///
     /// [`layout`]: trait.Widget.html#tymethod.layout
+    /// [`update`]: trait.Widget.html#tymethod.update
+    /// [`request_layout`]: struct.UpdateCtx.html#method.request_layout
+    /// [`LayoutCtx::disable_layout_cache`]: struct.LayoutCtx.html#method.disable_layout_cache
     pub fn layout(
         &mut self,
         layout_ctx: &mut LayoutCtx,
@@ -323,24 +439,168 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
         data: &T,
         env: &Env,
     ) -> Size {
+        if !self.state.needs_layout {
+            if let Some((old_bc, size)) = self.state.layout_cache {
+                if old_bc == *bc {
+                    return size;
+                }
+            }
+        }
+
+        enter_span!(TRACE, "layout", id = ?self.id(), widget = self.inner.type_name());
+        self.discard_stale_intrinsics();
         layout_ctx.paint_insets = Insets::ZERO;
+        let parent_cache_layout = layout_ctx.cache_layout;
+        layout_ctx.cache_layout = true;
+        let parent_viewport_changed = layout_ctx.viewport_changed;
+        layout_ctx.viewport_changed = false;
         let size = self.inner.layout(layout_ctx, bc, data, &env);
+        let cache_layout = layout_ctx.cache_layout;
+        layout_ctx.cache_layout = parent_cache_layout && cache_layout;
+        self.state.viewport_changed |= layout_ctx.viewport_changed;
+        layout_ctx.viewport_changed = parent_viewport_changed || self.state.viewport_changed;
 
         if size.width.is_infinite() {
             let name = self.widget().type_name();
-            log::warn!("Widget `{}` has an infinite width.", name);
+            log::warn!(target: "druid::layout", "Widget `{}` has an infinite width.", name);
         }
 
         if size.height.is_infinite() {
             let name = self.widget().type_name();
-            log::warn!("Widget `{}` has an infinite height.", name);
+            log::warn!(target: "druid::layout", "Widget `{}` has an infinite height.", name);
         }
 
         self.state.paint_insets = layout_ctx.paint_insets;
+        if self.state.focus_chain.contains(&self.id()) {
+            // the ring is painted by the pod, outside of the widget's bounds
+            let ring = focus_ring_outset(env).max(0.0);
+            let insets = &mut self.state.paint_insets;
+            insets.x0 = insets.x0.max(ring);
+            insets.y0 = insets.y0.max(ring);
+            insets.x1 = insets.x1.max(ring);
+            insets.y1 = insets.y1.max(ring);
+        }
+        self.state.requested_size = size;
         self.state.needs_layout = false;
+        self.state.intrinsics_fresh = false;
+        self.state.layout_cache = if cache_layout {
+            Some((*bc, size))
+        } else {
+            None
+        };
         size
     }
 
+    /// Ask the widget for its [`min_intrinsic_width`] at `height`.
+    ///
+    /// As with `layout`, the answer is cached: the widget is only asked
+    /// again once it, or a descendant, has requested layout, or the `Env`
+    /// has changed.
+    ///
+    /// [`min_intrinsic_width`]: trait.Widget.html#method.min_intrinsic_width
+    pub fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MinWidth, height, data, env)
+    }
+
+    /// Ask the widget for its [`max_intrinsic_width`] at `height`.
+    ///
+    /// The answer is cached, as with [`min_intrinsic_width`].
+    ///
+    /// [`max_intrinsic_width`]: trait.Widget.html#method.max_intrinsic_width
+    /// [`min_intrinsic_width`]: #method.min_intrinsic_width
+    pub fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MaxWidth, height, data, env)
+    }
+
+    /// Ask the widget for its [`min_intrinsic_height`] at `width`.
+    ///
+    /// The answer is cached, as with [`min_intrinsic_width`].
+    ///
+    /// [`min_intrinsic_height`]: trait.Widget.html#method.min_intrinsic_height
+    /// [`min_intrinsic_width`]: #method.min_intrinsic_width
+    pub fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MinHeight, width, data, env)
+    }
+
+    /// Ask the widget for its [`max_intrinsic_height`] at `width`.
+    ///
+    /// The answer is cached, as with [`min_intrinsic_width`].
+    ///
+    /// [`max_intrinsic_height`]: trait.Widget.html#method.max_intrinsic_height
+    /// [`min_intrinsic_width`]: #method.min_intrinsic_width
+    pub fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Intrinsic::MaxHeight, width, data, env)
+    }
+
+    fn intrinsic(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        query: Intrinsic,
+        extent: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.discard_stale_intrinsics();
+        let cached = self
+            .state
+            .intrinsic_cache
+            .iter()
+            .find(|(q, e, _)| *q == query && *e == extent);
+        if let Some((_, _, answer)) = cached {
+            return *answer;
+        }
+
+        let parent_probed = std::mem::replace(&mut ctx.probed_layout, false);
+        let answer = match query {
+            Intrinsic::MinWidth => self.inner.min_intrinsic_width(ctx, extent, data, env),
+            Intrinsic::MaxWidth => self.inner.max_intrinsic_width(ctx, extent, data, env),
+            Intrinsic::MinHeight => self.inner.min_intrinsic_height(ctx, extent, data, env),
+            Intrinsic::MaxHeight => self.inner.max_intrinsic_height(ctx, extent, data, env),
+        };
+        if std::mem::replace(&mut ctx.probed_layout, parent_probed) {
+            // the widget was laid out to measure it, and now holds the
+            // results of that; its cached layout no longer describes it.
+            // The answers we have are still good, though.
+            self.state.needs_layout = true;
+            self.state.intrinsics_fresh = true;
+        }
+        self.state.intrinsic_cache.push((query, extent, answer));
+        answer
+    }
+
+    /// Empty the intrinsic size cache if the widget has changed since it
+    /// was filled.
+    fn discard_stale_intrinsics(&mut self) {
+        if self.state.needs_layout && !self.state.intrinsics_fresh {
+            self.state.intrinsic_cache.clear();
+            self.state.intrinsics_fresh = true;
+        }
+    }
+
     /// Propagate an event.
     ///
     /// Generally the [`event`] method of a container widget will call this
@@ -350,8 +610,10 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
     ///
     /// [`event`]: trait.Widget.html#tymethod.event
     pub fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        enter_span!(TRACE, "event", id = ?self.id(), widget = self.inner.type_name());
         if self.old_data.is_none() {
             log::error!(
+                target: "druid::event",
                 "widget {:?} is receiving an event without having first \
                  received WidgetAdded.",
                 ctx.widget_id()
@@ -366,16 +628,27 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             return;
         }
         let had_active = self.state.has_active;
+        // while a widget is active, mouse events only go along the path to
+        // it; from there its own children get them as usual.
+        let captured = ctx.had_active && !ctx.base_state.is_active;
         let mut child_ctx = EventCtx {
             cursor: ctx.cursor,
             command_queue: ctx.command_queue,
             window: &ctx.window,
+            clock: ctx.clock,
+            mock_clipboard: ctx.mock_clipboard,
+            tasks: ctx.tasks,
             window_id: ctx.window_id,
             base_state: &mut self.state,
             had_active,
             is_handled: false,
+            scroll_remainder: None,
+            is_broadcast: ctx.is_broadcast,
+            reached_target: false,
             is_root: false,
             focus_widget: ctx.focus_widget,
+            window_visible: ctx.window_visible,
+            widget_tree: ctx.widget_tree,
         };
         let rect = child_ctx.base_state.layout_rect;
         // Note: could also represent this as `Option<Event>`.
@@ -395,13 +668,13 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                     child_ctx.base_state.is_hot = true;
                     hot_changed = Some(true);
                 }
-                recurse = had_active || !ctx.had_active && now_hot;
+                recurse = had_active || !captured && now_hot;
                 let mut mouse_event = mouse_event.clone();
                 mouse_event.pos -= rect.origin().to_vec2();
                 Event::MouseDown(mouse_event)
             }
             Event::MouseUp(mouse_event) => {
-                recurse = had_active || !ctx.had_active && rect.winding(mouse_event.pos) != 0;
+                recurse = had_active || !captured && rect.winding(mouse_event.pos) != 0;
                 let mut mouse_event = mouse_event.clone();
                 mouse_event.pos -= rect.origin().to_vec2();
                 Event::MouseUp(mouse_event)
@@ -437,6 +710,11 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 recurse = had_active || child_ctx.base_state.is_hot;
                 Event::Zoom(*zoom)
             }
+            Event::Gesture(gesture) => {
+                let center = gesture.center();
+                recurse = had_active || !captured && rect.winding(center) != 0;
+                Event::Gesture(gesture.with_center(center - rect.origin().to_vec2()))
+            }
             Event::Timer(id) => {
                 recurse = child_ctx.base_state.request_timer;
                 Event::Timer(*id)
@@ -444,12 +722,19 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             Event::Command(cmd) => Event::Command(cmd.clone()),
             Event::TargetedCommand(target, cmd) => match target {
                 Target::Window(_) => Event::Command(cmd.clone()),
-                Target::Widget(id) if *id == child_ctx.widget_id() => Event::Command(cmd.clone()),
+                Target::Widget(id) if *id == child_ctx.widget_id() => {
+                    child_ctx.reached_target = true;
+                    Event::Command(cmd.clone())
+                }
                 Target::Widget(id) => {
                     recurse = child_ctx.base_state.children.contains(id);
                     Event::TargetedCommand(*target, cmd.clone())
                 }
-                Target::Global => panic!("Target::Global should be converted before WidgetPod"),
+                Target::Global => Event::Command(cmd.clone()),
+                Target::Type(name) if *name == self.inner.type_name() => {
+                    Event::Command(cmd.clone())
+                }
+                Target::Type(_) => Event::TargetedCommand(*target, cmd.clone()),
             },
         };
         if let Some(is_hot) = hot_changed {
@@ -466,9 +751,22 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
 
         ctx.base_state.merge_up(&child_ctx.base_state);
         ctx.is_handled |= child_ctx.is_handled;
+        ctx.reached_target |= child_ctx.reached_target;
+        ctx.scroll_remainder = ctx.scroll_remainder.or(child_ctx.scroll_remainder);
     }
 
     pub fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        enter_span!(TRACE, "lifecycle", id = ?self.id(), widget = self.inner.type_name());
+        // the viewport arrives in our parent's coordinate space.
+        let translated;
+        let event = match event {
+            LifeCycle::ViewportChanged(visible) => {
+                let origin = self.state.layout_rect.origin().to_vec2();
+                translated = LifeCycle::ViewportChanged(*visible - origin);
+                &translated
+            }
+            _ => event,
+        };
         let recurse = match event {
             LifeCycle::AnimFrame(_) => {
                 let r = self.state.request_anim;
@@ -494,13 +792,19 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 } else {
                     if self.state.children_changed {
                         self.state.children.clear();
+                        self.state.descendant_count = 0;
                         self.state.focus_chain.clear();
+                        self.state.access_keys.clear();
                     }
 
                     self.state.children_changed
                 }
             }
             LifeCycle::HotChanged(_) => false,
+            LifeCycle::ViewportChanged(_) => true,
+            LifeCycle::RouteViewportChanged => {
+                std::mem::replace(&mut self.state.viewport_changed, false)
+            }
             LifeCycle::RouteFocusChanged { old, new } => {
                 self.state.request_focus = None;
 
@@ -513,8 +817,17 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 };
 
                 if let Some(change) = this_changed {
+                    if change {
+                        self.state.scroll_to = Some(focus_ring_bounds(self.state.size(), env));
+                    }
                     let event = LifeCycle::FocusChanged(change);
                     self.inner.lifecycle(ctx, &event, data, env);
+                    // the ring is outside our paint rect, so the widget
+                    // repainting itself isn't enough to show or clear it.
+                    self.state.needs_inval = true;
+                    self.state
+                        .invalid
+                        .add_rect(focus_ring_bounds(self.state.size(), env));
                     false
                 } else {
                     old.map(|id| self.state.children.contains(&id))
@@ -526,6 +839,21 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
                 self.state.request_focus = None;
                 true
             }
+            LifeCycle::WindowFocusChanged(_)
+            | LifeCycle::WindowVisibilityChanged(_)
+            | LifeCycle::AppActiveChanged(_)
+            | LifeCycle::NavigationChanged(_) => true,
+            LifeCycle::ScaleChanged(_) => {
+                // a cached layout may depend on the old scale.
+                self.state.needs_layout = true;
+                true
+            }
+            LifeCycle::RouteRequestLayout => {
+                self.state.needs_layout = true;
+                self.state.needs_inval = true;
+                true
+            }
+            LifeCycle::RouteInspectTree(_) => true,
             #[cfg(test)]
             LifeCycle::DebugRequestState { widget, state_cell } => {
                 if *widget == self.id() {
@@ -546,20 +874,31 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             command_queue: ctx.command_queue,
             base_state: &mut self.state,
             window_id: ctx.window_id,
+            window_visible: ctx.window_visible,
+            widget_tree: ctx.widget_tree,
         };
 
-        if recurse {
+        if let Some(tree) = inspect::tree_cell(event) {
+            tree.enter(child_ctx.base_state, self.inner.type_name());
+            self.inner.lifecycle(&mut child_ctx, event, data, env);
+            tree.exit();
+        } else if recurse {
             self.inner.lifecycle(&mut child_ctx, event, data, env);
         }
 
         ctx.base_state.merge_up(&self.state);
+        if let Some(rect) = self.state.scroll_to.take() {
+            ctx.base_state.scroll_to = Some(rect + self.state.layout_rect.origin().to_vec2());
+        }
 
         // we need to (re)register children in case of one of the following events
         match event {
             LifeCycle::WidgetAdded | LifeCycle::RouteWidgetAdded => {
                 self.state.children_changed = false;
                 ctx.base_state.children = ctx.base_state.children.union(self.state.children);
+                ctx.base_state.descendant_count += self.state.descendant_count + 1;
                 ctx.base_state.focus_chain.extend(&self.state.focus_chain);
+                ctx.base_state.access_keys.extend(&self.state.access_keys);
                 ctx.register_child(self.id());
             }
             _ => (),
@@ -576,7 +915,11 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
         match (self.old_data.as_ref(), self.env.as_ref()) {
             (Some(d), Some(e)) if d.same(data) && e.same(env) => return,
             (None, _) => {
-                log::warn!("old_data missing in {:?}, skipping update", self.id());
+                log::warn!(
+                    target: "druid::update",
+                    "old_data missing in {:?}, skipping update",
+                    self.id()
+                );
                 self.old_data = Some(data.clone());
                 self.env = Some(env.clone());
                 return;
@@ -584,10 +927,20 @@ impl<T: Data, W: Widget<T>> WidgetPod<T, W> {
             _ => (),
         }
 
+        // env values (such as font sizes) commonly affect layout.
+        if !self.env.as_ref().map(|e| e.same(env)).unwrap_or(false) {
+            self.state.needs_layout = true;
+            self.state.invalidate_all();
+        }
+
+        enter_span!(TRACE, "update", id = ?self.id(), widget = self.inner.type_name());
         let mut child_ctx = UpdateCtx {
+            command_queue: ctx.command_queue,
             window: ctx.window,
+            clock: ctx.clock,
             base_state: &mut self.state,
             window_id: ctx.window_id,
+            window_visible: ctx.window_visible,
         };
 
         self.inner
@@ -616,22 +969,36 @@ impl BaseState {
             layout_rect: Rect::ZERO,
             paint_insets: Insets::ZERO,
             needs_inval: false,
+            invalid: Region::EMPTY,
             is_hot: false,
             needs_layout: false,
+            layout_cache: None,
+            requested_size: Size::ZERO,
+            intrinsic_cache: Vec::new(),
+            intrinsics_fresh: false,
             is_active: false,
             has_active: false,
             request_anim: false,
             request_timer: false,
             request_focus: None,
             focus_chain: Vec::new(),
+            access_keys: Vec::new(),
             children: Bloom::new(),
+            descendant_count: 0,
             children_changed: false,
+            viewport_changed: false,
+            scroll_to: None,
         }
     }
 
     /// Update to incorporate state changes from a child.
     fn merge_up(&mut self, child_state: &BaseState) {
         self.needs_inval |= child_state.needs_inval;
+        if !child_state.invalid.is_empty() {
+            let offset = child_state.layout_rect.origin().to_vec2();
+            self.invalid
+                .add_rect(child_state.invalid.to_rect() + offset);
+        }
         self.needs_layout |= child_state.needs_layout;
         self.request_anim |= child_state.request_anim;
         self.request_timer |= child_state.request_timer;
@@ -640,6 +1007,13 @@ impl BaseState {
         self.request_focus = self.request_focus.or(child_state.request_focus);
     }
 
+    /// Mark the entire paint rect of this widget as needing to be repainted.
+    pub(crate) fn invalidate_all(&mut self) {
+        self.needs_inval = true;
+        let paint_rect = self.paint_rect() - self.layout_rect.origin().to_vec2();
+        self.invalid.add_rect(paint_rect);
+    }
+
     #[inline]
     pub(crate) fn size(&self) -> Size {
         self.layout_rect.size()
@@ -686,6 +1060,8 @@ mod tests {
             command_queue: &mut command_queue,
             base_state: &mut state,
             window_id: WindowId::next(),
+            window_visible: true,
+            widget_tree: None,
         };
 
         let env = Env::default();
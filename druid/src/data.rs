@@ -133,6 +133,8 @@ impl_data_simple!(usize);
 impl_data_simple!(char);
 impl_data_simple!(bool);
 impl_data_simple!(String);
+impl_data_simple!(std::time::Duration);
+impl_data_simple!(std::time::Instant);
 
 impl Data for f32 {
     fn same(&self, other: &Self) -> bool {
@@ -0,0 +1,113 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! An overlay showing the bounds and id of widgets, for layout debugging.
+//!
+//! Widgets are outlined while `Env::DEBUG_PAINT` is set for them, as it is
+//! by [`WidgetExt::debug_paint_layout`]; in debug builds it can also be
+//! toggled for a whole window with Ctrl+Shift+F12 (Cmd+Shift+F12 on macOS).
+//!
+//! [`WidgetExt::debug_paint_layout`]: ../widget/trait.WidgetExt.html#method.debug_paint_layout
+
+use crate::kurbo::{Affine, Point, Rect};
+use crate::piet::{Color, FontBuilder, Piet, RenderContext, Text, TextLayout, TextLayoutBuilder};
+use crate::{theme, Env, WidgetId};
+
+#[cfg(debug_assertions)]
+use crate::{HotKey, KeyCode, KeyEvent, SysMods};
+
+const LABEL_TEXT_SIZE: f64 = 10.0;
+const LABEL_PADDING: f64 = 2.0;
+
+/// A widget's bounds, recorded while it paints.
+pub(crate) struct DebugBox {
+    /// The widget's bounds in the render context's device space.
+    pub(crate) rect: Rect,
+    pub(crate) id: WidgetId,
+    pub(crate) type_name: &'static str,
+    /// Set if `Env::DEBUG_PAINT` was set for the widget, and it should be
+    /// shown in the overlay.
+    pub(crate) outlined: bool,
+}
+
+/// Returns `true` if `event` is the hotkey that toggles the overlay.
+#[cfg(debug_assertions)]
+pub(crate) fn is_toggle_key(event: &KeyEvent) -> bool {
+    HotKey::new(SysMods::CmdShift, KeyCode::F12).matches(event)
+}
+
+/// Paint the outlined `boxes` over the window, outermost widgets first.
+///
+/// `window_transform` is the transform the window's root widget was painted
+/// with; the boxes are drawn relative to it.
+pub(crate) fn paint(piet: &mut Piet, boxes: &[DebugBox], window_transform: Affine, env: &Env) {
+    let font = match piet
+        .text()
+        .new_font_by_name(env.get(theme::FONT_NAME), LABEL_TEXT_SIZE)
+        .build()
+    {
+        Ok(font) => font,
+        Err(e) => {
+            log::warn!(target: "druid::paint", "failed to load font for debug overlay: {}", e);
+            return;
+        }
+    };
+    let to_window = window_transform.inverse();
+    let label_background = Color::rgba8(0, 0, 0, 0xa0);
+
+    for debug_box in boxes.iter().filter(|debug_box| debug_box.outlined) {
+        let rect = to_window.transform_rect_bbox(debug_box.rect);
+        let id = debug_box.id.to_raw();
+        let color = env.get_debug_color(id);
+        piet.stroke(rect.inset(-0.5), &color, 1.0);
+
+        let text = format!("#{} {}", id, short_name(debug_box.type_name));
+        if let Ok(layout) = piet.text().new_text_layout(&font, &text).build() {
+            let label = Rect::from_origin_size(
+                rect.origin(),
+                (
+                    layout.width() + LABEL_PADDING * 2.,
+                    LABEL_TEXT_SIZE * 1.2 + LABEL_PADDING * 2.,
+                ),
+            );
+            piet.fill(label, &label_background);
+            let origin = Point::new(
+                label.x0 + LABEL_PADDING,
+                label.y0 + LABEL_PADDING + LABEL_TEXT_SIZE,
+            );
+            piet.draw_text(&layout, origin, &color);
+        }
+    }
+}
+
+/// The name of a type without its module path or type parameters.
+fn short_name(type_name: &str) -> &str {
+    let base = type_name.split('<').next().unwrap_or(type_name);
+    base.rsplit("::").next().unwrap_or(base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn short_names() {
+        assert_eq!(short_name("druid::widget::flex::Flex<u32>"), "Flex");
+        assert_eq!(
+            short_name("druid::widget::label::Label<alloc::string::String>"),
+            "Label"
+        );
+        assert_eq!(short_name("Custom"), "Custom");
+    }
+}
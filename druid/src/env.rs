@@ -14,7 +14,7 @@
 
 //! An environment which is passed downward into the widget tree.
 
-use std::any;
+use std::any::{self, Any};
 use std::borrow::Borrow;
 use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
@@ -51,6 +51,28 @@ struct EnvImpl {
 /// implements [`ValueType`]. For "expensive" types, this is a reference,
 /// so the type for a string is `Key<&str>`.
 ///
+/// # Custom types
+///
+/// Values of any type that is `Send + Sync + 'static` can be stored by
+/// wrapping them in an `Arc`, and using a `Key<Arc<T>>`. Two such values
+/// are considered the same only if they point to the same allocation, so
+/// to signal a change you need to store a new `Arc`.
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::{Color, Env, Key};
+///
+/// struct Palette {
+///     accent: Color,
+/// }
+///
+/// const PALETTE: Key<Arc<Palette>> = Key::new("my-app.palette");
+///
+/// let palette = Arc::new(Palette { accent: Color::rgb8(0xff, 0x80, 0x00) });
+/// let env = Env::default().adding(PALETTE, palette);
+/// let accent = env.get(PALETTE).accent.clone();
+/// ```
+///
 /// [`ValueType`]: trait.ValueType.html
 /// [`Env`]: struct.Env.html
 pub struct Key<T> {
@@ -60,7 +82,6 @@ pub struct Key<T> {
 
 // we could do some serious deriving here: the set of types that can be stored
 // could be defined per-app
-// Also consider Box<Any> (though this would also impact debug).
 /// A dynamic type representing all values that can be stored in an environment.
 #[derive(Clone)]
 pub enum Value {
@@ -72,6 +93,8 @@ pub enum Value {
     Bool(bool),
     UnsignedInt(u64),
     String(String),
+    /// A value of some other type, stored with a `Key<Arc<T>>`.
+    Other(Arc<dyn Any + Send + Sync>),
 }
 
 /// Either a concrete `T` or a [`Key<T>`] that can be resolved in the [`Env`].
@@ -111,9 +134,28 @@ pub struct ValueTypeError {
     found: Value,
 }
 
+/// An error returned by [`Env::try_get`].
+///
+/// [`Env::try_get`]: struct.Env.html#method.try_get
+#[derive(Debug, Clone)]
+pub enum EnvError {
+    /// There is no value for this key.
+    MissingKey(&'static str),
+    /// There is a value for this key, but it has the wrong type.
+    WrongType {
+        /// The key that was requested.
+        key: &'static str,
+        /// The underlying type error.
+        error: ValueTypeError,
+    },
+}
+
 impl Env {
-    /// State for whether or not to paint colorful rectangles for layout
-    /// debugging.
+    /// State for whether or not to outline widgets and label them with their
+    /// id and type, for layout debugging.
+    ///
+    /// The outlines are painted over everything else, including popups, so
+    /// they also show widgets that are otherwise invisible.
     ///
     /// Set by the `debug_paint_layout()` method on [`WidgetExt`]'.
     ///
@@ -143,34 +185,92 @@ impl Env {
     /// [`WidgetExt::debug_widget`]: trait.WidgetExt.html#method.debug_widget
     pub const DEBUG_WIDGET: Key<bool> = Key::new("druid.built-in.debug-widget");
 
+    /// State for whether or not to show timing information for the previous
+    /// frame in the top left corner of each window.
+    ///
+    /// See the [`stats`] module for more information.
+    ///
+    /// [`stats`]: stats/index.html
+    pub const SHOW_FRAME_STATS: Key<bool> = Key::new("druid.built-in.show-frame-stats");
+
+    /// Whether the UI is laid out right-to-left.
+    ///
+    /// This starts out `true` if the system locale is written right-to-left,
+    /// such as Arabic or Hebrew. Containers such as [`Flex`] mirror their
+    /// layout when it is set; it can be overridden for a subtree with an
+    /// [`EnvScope`].
+    ///
+    /// [`Flex`]: widget/struct.Flex.html
+    /// [`EnvScope`]: widget/struct.EnvScope.html
+    pub const IS_RTL: Key<bool> = Key::new("druid.built-in.is-rtl");
+
     /// Gets a value from the environment, expecting it to be present.
     ///
     /// Note that the return value is a reference for "expensive" types such
     /// as strings, but an ordinary value for "cheap" types such as numbers
     /// and colors.
     ///
+    /// The keys in the [`theme`] module are always present in the `Env`
+    /// passed to widgets by an [`AppLauncher`], as are the built-in keys
+    /// defined on `Env` itself. Any other key is only present if the
+    /// application (or an ancestor widget) has set it; for those, consider
+    /// [`try_get`] or [`get_or`].
+    ///
     /// # Panics
     ///
     /// Panics if the key is not found, or if it is present with the wrong type.
+    ///
+    /// [`theme`]: theme/index.html
+    /// [`AppLauncher`]: struct.AppLauncher.html
+    /// [`try_get`]: #method.try_get
+    /// [`get_or`]: #method.get_or
     pub fn get<'a, V: ValueType<'a>>(&'a self, key: impl Borrow<Key<V>>) -> V {
-        let key = key.borrow();
-        if let Some(value) = self.0.map.get(key.key) {
-            value.to_inner_unchecked()
-        } else {
-            panic!("key for {} not found", key.key)
+        match self.try_get(key) {
+            Ok(value) => value,
+            Err(err) => panic!("{}", err),
         }
     }
 
     /// Gets a value from the environment.
     ///
-    /// # Panics
+    /// Returns an error if the key is not found, or if it is present with
+    /// the wrong type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use druid::{Env, EnvError, Key};
+    /// const SPACING: Key<f64> = Key::new("my-app.spacing");
+    ///
+    /// let env = Env::default();
+    /// assert!(matches!(env.try_get(SPACING), Err(EnvError::MissingKey(_))));
     ///
-    /// Panics if the value for the key is found, but has the wrong type.
-    pub fn try_get<'a, V: ValueType<'a>>(&'a self, key: impl Borrow<Key<V>>) -> Option<V> {
-        self.0
-            .map
-            .get(key.borrow().key)
-            .map(|value| value.to_inner_unchecked())
+    /// let env = env.adding(SPACING, 8.0);
+    /// assert_eq!(env.try_get(SPACING).unwrap(), 8.0);
+    /// ```
+    pub fn try_get<'a, V: ValueType<'a>>(
+        &'a self,
+        key: impl Borrow<Key<V>>,
+    ) -> Result<V, EnvError> {
+        let key = key.borrow().key;
+        let value = self.0.map.get(key).ok_or(EnvError::MissingKey(key))?;
+        V::try_from_value(value).map_err(|error| EnvError::WrongType { key, error })
+    }
+
+    /// Gets a value from the environment, or `default` if it cannot be found.
+    ///
+    /// This is useful for keys that a custom theme may not define. If the
+    /// key is present but has the wrong type, this logs a warning and
+    /// returns `default`.
+    pub fn get_or<'a, V: ValueType<'a>>(&'a self, key: impl Borrow<Key<V>>, default: V) -> V {
+        match self.try_get(key) {
+            Ok(value) => value,
+            Err(EnvError::MissingKey(_)) => default,
+            Err(err) => {
+                log::warn!("{}", err);
+                default
+            }
+        }
     }
 
     /// Adds a key/value, acting like a builder.
@@ -263,6 +363,7 @@ impl Value {
             (Bool(_), Bool(_)) => true,
             (UnsignedInt(_), UnsignedInt(_)) => true,
             (String(_), String(_)) => true,
+            (Other(o1), Other(o2)) => (**o1).type_id() == (**o2).type_id(),
             _ => false,
         }
     }
@@ -279,6 +380,7 @@ impl Debug for Value {
             Value::Bool(b) => write!(f, "Bool {}", b),
             Value::UnsignedInt(x) => write!(f, "UnsignedInt {}", x),
             Value::String(s) => write!(f, "String {:?}", s),
+            Value::Other(_) => write!(f, "Other"),
         }
     }
 }
@@ -297,6 +399,7 @@ impl Data for Value {
             (Bool(b1), Bool(b2)) => b1 == b2,
             (UnsignedInt(f1), UnsignedInt(f2)) => f1.same(&f2),
             (String(s1), String(s2)) => s1 == s2,
+            (Other(o1), Other(o2)) => Arc::ptr_eq(o1, o2),
             _ => false,
         }
     }
@@ -345,6 +448,7 @@ impl Default for Env {
             Color::rgb8(0, 0, 0),
         ];
 
+        let is_rtl = l10n.is_rtl();
         let inner = EnvImpl {
             l10n: Arc::new(l10n),
             map: HashMap::new(),
@@ -354,6 +458,8 @@ impl Default for Env {
         Env(Arc::new(inner))
             .adding(Env::DEBUG_PAINT, false)
             .adding(Env::DEBUG_WIDGET, false)
+            .adding(Env::SHOW_FRAME_STATS, false)
+            .adding(Env::IS_RTL, is_rtl)
     }
 }
 
@@ -380,6 +486,24 @@ impl std::fmt::Display for ValueTypeError {
 
 impl std::error::Error for ValueTypeError {}
 
+impl std::fmt::Display for EnvError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EnvError::MissingKey(key) => write!(f, "key for {} not found", key),
+            EnvError::WrongType { key, error } => write!(f, "key {}: {}", key, error),
+        }
+    }
+}
+
+impl std::error::Error for EnvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            EnvError::MissingKey(_) => None,
+            EnvError::WrongType { error, .. } => Some(error),
+        }
+    }
+}
+
 /// Use this macro for types which are cheap to clone (ie all `Copy` types).
 macro_rules! impl_value_type_owned {
     ($ty:ty, $var:ident) => {
@@ -432,6 +556,23 @@ impl_value_type_owned!(Point, Point);
 impl_value_type_owned!(Size, Size);
 impl_value_type_borrowed!(str, String, String);
 
+impl<'a, T: Send + Sync + 'static> ValueType<'a> for Arc<T> {
+    type Owned = Arc<T>;
+    fn try_from_value(value: &Value) -> Result<Self, ValueTypeError> {
+        let err = || ValueTypeError::new(any::type_name::<Arc<T>>(), value.clone());
+        match value {
+            Value::Other(other) => other.clone().downcast::<T>().map_err(|_| err()),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl<T: Send + Sync + 'static> From<Arc<T>> for Value {
+    fn from(src: Arc<T>) -> Value {
+        Value::Other(src)
+    }
+}
+
 impl<'a, T: ValueType<'a>> KeyOrValue<T> {
     pub fn resolve(&'a self, env: &'a Env) -> T {
         match self {
@@ -14,10 +14,11 @@
 
 //! Events.
 
-use crate::kurbo::{Rect, Shape, Size, Vec2};
+use crate::kurbo::{Affine, Point, Rect, Shape, Size, Vec2};
 
 use druid_shell::{Clipboard, KeyEvent, KeyModifiers, TimerToken};
 
+use crate::inspect::TreeCell;
 use crate::mouse::MouseEvent;
 use crate::{Command, Target, WidgetId};
 
@@ -39,11 +40,32 @@ use crate::{Command, Target, WidgetId};
 /// [`WidgetPod`] container, which is where most of the event flow logic
 /// is applied (especially the decision whether or not to propagate).
 ///
+/// # Propagation
+///
+/// A container passes each event to its children before acting on it
+/// itself. Once a widget calls [`EventCtx::set_handled`], the event is not
+/// passed to any further widgets; containers that act on an event after
+/// their children, such as [`Scroll`] with wheel events, check
+/// [`EventCtx::is_handled`] first. Which widgets are offered an event in the
+/// first place depends on its kind:
+///
+/// - mouse, wheel and zoom events go to the active widget, if any, and
+///   otherwise to the widgets under the pointer; gestures go to the active
+///   widget or to the widgets under their center;
+/// - keyboard and paste events go to the focused widget and its ancestors;
+/// - commands targeting a window go to every widget in it, in order, until
+///   one handles them; a command targeting a widget goes to that widget and
+///   its ancestors. Broadcast commands cannot be handled; see [`Target`].
+///
 /// This enum is expected to grow considerably, as there are many, many
 /// different kinds of events that are relevant in a GUI.
 ///
 /// [`event`]: trait.Widget.html#tymethod.event
 /// [`WidgetPod`]: struct.WidgetPod.html
+/// [`EventCtx::set_handled`]: struct.EventCtx.html#method.set_handled
+/// [`EventCtx::is_handled`]: struct.EventCtx.html#method.is_handled
+/// [`Scroll`]: widget/struct.Scroll.html
+/// [`Target`]: enum.Target.html
 #[derive(Debug, Clone)]
 pub enum Event {
     /// Sent to all widgets in a given window when that window is first instantiated.
@@ -103,8 +125,25 @@ pub enum Event {
     Wheel(WheelEvent),
     /// Called when the trackpad is pinched.
     ///
-    /// The value is a delta.
+    /// The value is a delta. This is sent after a pinch [`Gesture`] that no
+    /// widget handled, so that widgets that only know this event still
+    /// zoom; new widgets should handle the gesture instead.
+    ///
+    /// [`Gesture`]: #variant.Gesture
     Zoom(f64),
+    /// Called when the user pinches or pans with several fingers, on a
+    /// touch screen or trackpad.
+    ///
+    /// On macOS, trackpad pinches are reported as [`Gesture::Pinch`]; on
+    /// Linux, so are pinches and pans on a touch screen. Touch screen
+    /// gestures are not reported on Windows. Everywhere, for mice and for
+    /// trackpads whose pinches we don't see, turning the wheel with ctrl held
+    /// is reported as a pinch around the pointer, if no widget handles the
+    /// [`Wheel`] event. A zoomable widget can so handle this event alone.
+    ///
+    /// [`Gesture::Pinch`]: enum.Gesture.html#variant.Pinch
+    /// [`Wheel`]: #variant.Wheel
+    Gesture(Gesture),
     /// Called on a timer event.
     ///
     /// Request a timer event through [`EventCtx::request_timer()`]. That will
@@ -192,6 +231,73 @@ pub enum LifeCycle {
     /// See [`has_focus`](struct.EventCtx.html#method.has_focus) for
     /// discussion about the focus status.
     FocusChanged(bool),
+    /// Called when the widget's window gains or loses focus.
+    ///
+    /// A window that is not focused may still be visible, but nothing is
+    /// typing into it; widgets might stop blinking a cursor, for instance.
+    WindowFocusChanged(bool),
+    /// Called when the widget's window is minimized or restored, or, on
+    /// platforms that report it, covered or uncovered by other windows.
+    ///
+    /// While the window is hidden it is not painted and gets no animation
+    /// frames. See [`EventCtx::is_window_visible`].
+    ///
+    /// [`EventCtx::is_window_visible`]: struct.EventCtx.html#method.is_window_visible
+    WindowVisibilityChanged(bool),
+    /// Called on every widget in a window when the window's scale factor
+    /// changes, such as when it is moved to a monitor with a different pixel
+    /// density. The value is the new number of pixels per display point.
+    ///
+    /// Sizes are in display points, so most widgets need do nothing: the
+    /// window is laid out and repainted anyway, and text and strokes are
+    /// drawn at the new density. This is for widgets that keep bitmaps, or
+    /// that align what they draw to the pixel grid.
+    ///
+    /// This is also sent once the window is connected, if its scale is not 1.
+    ScaleChanged(f64),
+    /// Called on every widget in every window when the application becomes
+    /// active, because one of its windows was focused, or inactive, because
+    /// the user switched to another application.
+    ///
+    /// Widgets that animate or repaint on a timer can use this to stop while
+    /// the application is in the background.
+    AppActiveChanged(bool),
+    /// Called when the visible part of the widget changes, because a
+    /// [`Scroll`] containing it has scrolled or changed size.
+    ///
+    /// The rect is in the widget's own coordinate space, and is the viewport
+    /// of the nearest enclosing scroll, so it may extend beyond the widget,
+    /// or not overlap it at all. Widgets with a lot
+    /// of content can use it to only build, or load, what can be seen.
+    ///
+    /// This is sent to every widget inside the scroll after each change; it
+    /// is also sent once the scroll is first laid out.
+    ///
+    /// [`Scroll`]: widget/struct.Scroll.html
+    ViewportChanged(Rect),
+    /// Called on a view of a [`Navigator`], and every widget in it, when it
+    /// becomes the current view (`true`) or stops being it (`false`).
+    ///
+    /// A view is entered once it has been added, and left before it is
+    /// covered by another view or removed. A view that is left is not
+    /// painted and gets no events, but keeps its state; it is entered again
+    /// when the views above it are popped.
+    ///
+    /// [`Navigator`]: widget/struct.Navigator.html
+    NavigationChanged(bool),
+    /// Internal: used by the framework to find the widgets whose viewport
+    /// changed during layout, so that they can send `ViewportChanged`.
+    RouteViewportChanged,
+    /// Internal: used by the framework to make every widget lay out again,
+    /// for the [`REQUEST_LAYOUT`] command.
+    ///
+    /// [`REQUEST_LAYOUT`]: commands/constant.REQUEST_LAYOUT.html
+    RouteRequestLayout,
+    /// Internal: used by the framework to collect a snapshot of the widget
+    /// tree, for hit testing and the [`DUMP_WIDGET_TREE`] command.
+    ///
+    /// [`DUMP_WIDGET_TREE`]: commands/constant.DUMP_WIDGET_TREE.html
+    RouteInspectTree(TreeCell),
     /// Testing only: request the `BaseState` of a specific widget.
     ///
     /// During testing, you may wish to verify that the state of a widget
@@ -233,6 +339,59 @@ pub struct WheelEvent {
     pub mods: KeyModifiers,
 }
 
+/// A multi-finger gesture, delivered as [`Event::Gesture`].
+///
+/// Each event describes the change since the previous one, so that a
+/// zoomable widget can apply it to its current zoom and offset.
+///
+/// [`Event::Gesture`]: enum.Event.html#variant.Gesture
+#[derive(Debug, Clone, Copy)]
+pub enum Gesture {
+    /// The fingers moved apart or together.
+    Pinch(GesturePinch),
+    /// The fingers moved together across the screen.
+    Pan(GesturePan),
+}
+
+/// A change in the distance between the fingers of a gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GesturePinch {
+    /// The factor by which the content should grow; greater than `1.0`
+    /// when zooming in.
+    pub scale: f64,
+    /// The point between the fingers, in the widget's coordinate space.
+    ///
+    /// Content under this point should stay under it as it is scaled.
+    pub center: Point,
+}
+
+/// A movement of all of the fingers of a gesture.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GesturePan {
+    /// How far the fingers moved; the direction the content should move.
+    pub delta: Vec2,
+    /// The point between the fingers, in the widget's coordinate space.
+    pub center: Point,
+}
+
+impl Gesture {
+    /// The point between the fingers, in the widget's coordinate space.
+    pub fn center(&self) -> Point {
+        match self {
+            Gesture::Pinch(pinch) => pinch.center,
+            Gesture::Pan(pan) => pan.center,
+        }
+    }
+
+    /// The same gesture, with its center moved to `center`.
+    pub(crate) fn with_center(self, center: Point) -> Gesture {
+        match self {
+            Gesture::Pinch(pinch) => Gesture::Pinch(GesturePinch { center, ..pinch }),
+            Gesture::Pan(pan) => Gesture::Pan(GesturePan { center, ..pan }),
+        }
+    }
+}
+
 impl Event {
     /// Transform the event for the contents of a scrolling container.
     ///
@@ -268,9 +427,59 @@ impl Event {
                     None
                 }
             }
+            Event::Gesture(gesture) => {
+                if force || viewport.winding(gesture.center()) != 0 {
+                    Some(Event::Gesture(
+                        gesture.with_center(gesture.center() + offset),
+                    ))
+                } else {
+                    None
+                }
+            }
             _ => Some(self.clone()),
         }
     }
+
+    /// Transform the event for a child that is painted with `transform`.
+    ///
+    /// Mouse positions and gesture centers are mapped back through the
+    /// inverse of `transform`,
+    /// so that they are in the child's untransformed coordinate space. Other
+    /// events, including the delta of a wheel event, are unchanged. The
+    /// transform must be invertible.
+    pub fn transform_mouse(&self, transform: Affine) -> Event {
+        let inverse = transform.inverse();
+        let transformed = |mouse_event: &MouseEvent| {
+            let mut mouse_event = mouse_event.clone();
+            mouse_event.pos = inverse * mouse_event.pos;
+            mouse_event
+        };
+        match self {
+            Event::MouseDown(mouse_event) => Event::MouseDown(transformed(mouse_event)),
+            Event::MouseUp(mouse_event) => Event::MouseUp(transformed(mouse_event)),
+            Event::MouseMoved(mouse_event) => Event::MouseMoved(transformed(mouse_event)),
+            Event::Gesture(gesture) => {
+                Event::Gesture(gesture.with_center(inverse * gesture.center()))
+            }
+            _ => self.clone(),
+        }
+    }
+
+    /// Whether this event comes from the mouse or trackpad, and so should
+    /// go to the widget under the pointer.
+    ///
+    /// These are the mouse button and move events, wheel events and
+    /// gestures.
+    pub fn is_pointer_event(&self) -> bool {
+        matches!(
+            self,
+            Event::MouseDown(_)
+                | Event::MouseUp(_)
+                | Event::MouseMoved(_)
+                | Event::Wheel(_)
+                | Event::Gesture(_)
+        )
+    }
 }
 
 #[cfg(test)]
@@ -16,6 +16,7 @@
 
 use std::any::Any;
 use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 
 use crate::shell::IdleHandle;
@@ -24,13 +25,20 @@ use crate::{Command, Selector, Target, WindowId};
 
 pub(crate) type ExtCommand = (Selector, Option<Box<dyn Any + Send>>, Option<Target>);
 
+/// A command waiting to be delivered, with the flag that is set if the task
+/// that sent it is cancelled before then.
+struct Queued {
+    command: ExtCommand,
+    cancelled: Option<Arc<AtomicBool>>,
+}
+
 /// A thing that can move into other threads and be used to submit commands back
 /// to the running application.
 ///
 /// This API is preliminary, and may be changed or removed without warning.
 #[derive(Clone)]
 pub struct ExtEventSink {
-    queue: Arc<Mutex<VecDeque<ExtCommand>>>,
+    queue: Arc<Mutex<VecDeque<Queued>>>,
     handle: Arc<Mutex<Option<IdleHandle>>>,
 }
 
@@ -39,7 +47,7 @@ pub struct ExtEventSink {
 #[derive(Default)]
 pub(crate) struct ExtEventHost {
     /// A shared queue of items that have been sent to us.
-    queue: Arc<Mutex<VecDeque<ExtCommand>>>,
+    queue: Arc<Mutex<VecDeque<Queued>>>,
     /// This doesn't exist when the app starts and it can go away if a window
     /// closes, so we keep a reference here and can update it when needed.
     handle: Arc<Mutex<Option<IdleHandle>>>,
@@ -74,12 +82,17 @@ impl ExtEventHost {
         !self.queue.lock().unwrap().is_empty()
     }
 
+    /// The next command to deliver, skipping those from cancelled tasks.
     pub(crate) fn recv(&mut self) -> Option<(Option<Target>, Command)> {
-        self.queue
-            .lock()
-            .unwrap()
-            .pop_front()
-            .map(|(sel, obj, targ)| (targ, Command::from_ext(sel, obj)))
+        let mut queue = self.queue.lock().unwrap();
+        while let Some(Queued { command, cancelled }) = queue.pop_front() {
+            if cancelled.is_some_and(|c| c.load(Ordering::SeqCst)) {
+                continue;
+            }
+            let (sel, obj, targ) = command;
+            return Some((targ, Command::from_ext(sel, obj)));
+        }
+        None
     }
 }
 
@@ -107,15 +120,39 @@ impl ExtEventSink {
         obj: impl Into<Option<T>>,
         target: impl Into<Option<Target>>,
     ) -> Result<(), ExtEventError> {
-        let target = target.into();
-        let obj = obj.into().map(|o| Box::new(o) as Box<dyn Any + Send>);
+        self.submit(sel, obj.into(), target.into(), None)
+    }
+
+    /// Submit a command from a task, which is dropped instead of delivered
+    /// if `cancelled` is set by then.
+    pub(crate) fn submit_for_task<T: Any + Send>(
+        &self,
+        sel: Selector,
+        obj: T,
+        target: Target,
+        cancelled: &Arc<AtomicBool>,
+    ) -> Result<(), ExtEventError> {
+        self.submit(sel, Some(obj), Some(target), Some(cancelled.clone()))
+    }
+
+    fn submit<T: Any + Send>(
+        &self,
+        sel: Selector,
+        obj: Option<T>,
+        target: Option<Target>,
+        cancelled: Option<Arc<AtomicBool>>,
+    ) -> Result<(), ExtEventError> {
+        let obj = obj.map(|o| Box::new(o) as Box<dyn Any + Send>);
         if let Some(handle) = self.handle.lock().unwrap().as_mut() {
             handle.schedule_idle(EXT_EVENT_IDLE_TOKEN);
         }
         self.queue
             .lock()
             .map_err(|_| ExtEventError)?
-            .push_back((sel, obj, target));
+            .push_back(Queued {
+                command: (sel, obj, target),
+                cancelled,
+            });
         Ok(())
     }
 }
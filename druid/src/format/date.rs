@@ -0,0 +1,619 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dates and times of day as text, following the conventions of a locale.
+
+use std::fmt;
+use std::time::Duration;
+
+use crate::{Data, Env};
+
+const MINUTES_PER_DAY: i64 = 24 * 60;
+/// The number of days from 0000-03-01 to 1970-01-01.
+const UNIX_EPOCH_DAYS: i64 = 719_468;
+const DAYS_PER_ERA: i64 = 146_097;
+/// The ways of writing AM and PM, and whether each is PM; longest first.
+const PERIODS: [(&str, bool); 6] = [
+    ("a.m.", false),
+    ("p.m.", true),
+    ("am", false),
+    ("pm", true),
+    ("a", false),
+    ("p", true),
+];
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Data)]
+pub enum Weekday {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+/// A date in the Gregorian calendar.
+///
+/// Dates are ordered from earliest to latest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Data)]
+pub struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+/// A time of day, to the minute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Data)]
+pub struct Time {
+    hour: u32,
+    minute: u32,
+}
+
+/// The order of the fields in a written date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateOrder {
+    /// Day, month, year, as in `14.10.2026`.
+    DayMonthYear,
+    /// Month, day, year, as in `10/14/2026`.
+    MonthDayYear,
+    /// Year, month, day, as in `2026-10-14`.
+    YearMonthDay,
+}
+
+/// How dates and times are written, and how calendars are laid out, in a
+/// locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateConventions {
+    /// The order of the fields of a date.
+    pub order: DateOrder,
+    /// The character between the fields of a date.
+    pub separator: char,
+    /// The day that starts a week in a calendar.
+    pub first_weekday: Weekday,
+    /// Whether times use a 12-hour clock, with AM and PM.
+    pub hour12: bool,
+}
+
+/// An error from [`DateConventions::parse_date`] or
+/// [`DateConventions::parse_time`].
+///
+/// [`DateConventions::parse_date`]: struct.DateConventions.html#method.parse_date
+/// [`DateConventions::parse_time`]: struct.DateConventions.html#method.parse_time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDateTimeError {
+    /// The input was empty, or only whitespace.
+    Empty,
+    /// A number was expected, but something else was found.
+    ExpectedNumber {
+        /// The text where the number should have been.
+        found: String,
+    },
+    /// The input had the wrong number of fields for a date or time.
+    WrongFieldCount {
+        /// The number of fields found.
+        count: usize,
+    },
+    /// The fields do not name a real date or time, such as February 30th,
+    /// or 25 o'clock.
+    OutOfRange,
+}
+
+impl Weekday {
+    /// Every day of the week, starting with Monday.
+    pub const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// The number of days from Monday to this day; `0` for Monday, and `6`
+    /// for Sunday.
+    pub fn days_from_monday(self) -> u32 {
+        self as u32
+    }
+
+    /// The number of days from `start` to the next occurrence of this
+    /// day, from `0` to `6`.
+    pub fn days_since(self, start: Weekday) -> u32 {
+        (self.days_from_monday() + 7 - start.days_from_monday()) % 7
+    }
+}
+
+impl Date {
+    /// Create a date, returning `None` if there is no such day, such as
+    /// `2026-02-30`.
+    ///
+    /// Months and days are counted from `1`.
+    pub fn new(year: i32, month: u32, day: u32) -> Option<Date> {
+        if !(1..=12).contains(&month) || day < 1 || day > Date::days_in_month(year, month) {
+            return None;
+        }
+        Some(Date { year, month, day })
+    }
+
+    /// The year.
+    pub fn year(self) -> i32 {
+        self.year
+    }
+
+    /// The month, from `1` for January to `12` for December.
+    pub fn month(self) -> u32 {
+        self.month
+    }
+
+    /// The day of the month, starting from `1`.
+    pub fn day(self) -> u32 {
+        self.day
+    }
+
+    /// The number of days in a month of a year.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `month` is not from `1` to `12`.
+    pub fn days_in_month(year: i32, month: u32) -> u32 {
+        match month {
+            1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+            4 | 6 | 9 | 11 => 30,
+            2 if is_leap_year(year) => 29,
+            2 => 28,
+            _ => panic!("month {} is not from 1 to 12", month),
+        }
+    }
+
+    /// The day of the week this date falls on.
+    pub fn weekday(self) -> Weekday {
+        // 1970-01-01 was a Thursday.
+        Weekday::ALL[(self.days_since_epoch() + 3).rem_euclid(7) as usize]
+    }
+
+    /// The date `days` days later, or earlier if `days` is negative.
+    pub fn add_days(self, days: i64) -> Date {
+        Date::from_days_since_epoch(self.days_since_epoch() + days)
+    }
+
+    /// The same day `months` months later, or earlier if `months` is
+    /// negative.
+    ///
+    /// If the month is too short, this is its last day instead; one month
+    /// after January 31st is the end of February.
+    pub fn add_months(self, months: i32) -> Date {
+        let index = self.year * 12 + self.month as i32 - 1 + months;
+        let year = index.div_euclid(12);
+        let month = index.rem_euclid(12) as u32 + 1;
+        let day = self.day.min(Date::days_in_month(year, month));
+        Date { year, month, day }
+    }
+
+    /// The first day of this date's month.
+    pub fn first_of_month(self) -> Date {
+        Date { day: 1, ..self }
+    }
+
+    /// The number of days from 1970-01-01 to this date.
+    fn days_since_epoch(self) -> i64 {
+        // see http://howardhinnant.github.io/date_algorithms.html
+        let month = i64::from(self.month);
+        let year = i64::from(self.year) - if month <= 2 { 1 } else { 0 };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + i64::from(self.day) - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * DAYS_PER_ERA + day_of_era - UNIX_EPOCH_DAYS
+    }
+
+    fn from_days_since_epoch(days: i64) -> Date {
+        let days = days + UNIX_EPOCH_DAYS;
+        let era = days.div_euclid(DAYS_PER_ERA);
+        let day_of_era = days - era * DAYS_PER_ERA;
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let shifted_month = (5 * day_of_year + 2) / 153;
+        let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+        let month = if shifted_month < 10 {
+            shifted_month + 3
+        } else {
+            shifted_month - 9
+        };
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        Date {
+            year: year as i32,
+            month: month as u32,
+            day: day as u32,
+        }
+    }
+}
+
+impl Time {
+    /// Midnight, at the start of the day.
+    pub const MIDNIGHT: Time = Time { hour: 0, minute: 0 };
+
+    /// Create a time from an hour from `0` to `23` and a minute from `0` to
+    /// `59`, returning `None` if either is out of range.
+    pub fn new(hour: u32, minute: u32) -> Option<Time> {
+        if hour < 24 && minute < 60 {
+            Some(Time { hour, minute })
+        } else {
+            None
+        }
+    }
+
+    /// The hour, from `0` to `23`.
+    pub fn hour(self) -> u32 {
+        self.hour
+    }
+
+    /// The minute, from `0` to `59`.
+    pub fn minute(self) -> u32 {
+        self.minute
+    }
+
+    /// The number of minutes since midnight.
+    pub fn minutes_since_midnight(self) -> u32 {
+        self.hour * 60 + self.minute
+    }
+
+    /// The time `minutes` minutes later, or earlier if `minutes` is
+    /// negative, wrapping around midnight.
+    pub fn add_minutes(self, minutes: i64) -> Time {
+        let total =
+            (i64::from(self.minutes_since_midnight()) + minutes).rem_euclid(MINUTES_PER_DAY);
+        Time {
+            hour: (total / 60) as u32,
+            minute: (total % 60) as u32,
+        }
+    }
+
+    /// How long it is from `now` until this time next occurs.
+    ///
+    /// This is zero if `now` is this time, and less than a day otherwise;
+    /// it is what a timer should wait to go off at this time.
+    pub fn duration_after(self, now: Time) -> Duration {
+        let minutes = (i64::from(self.minutes_since_midnight())
+            - i64::from(now.minutes_since_midnight()))
+        .rem_euclid(MINUTES_PER_DAY);
+        Duration::from_secs(minutes as u64 * 60)
+    }
+}
+
+impl DateConventions {
+    /// The conventions of `en-US`, also used for locales that aren't known.
+    pub const DEFAULT: DateConventions = DateConventions {
+        order: DateOrder::MonthDayYear,
+        separator: '/',
+        first_weekday: Weekday::Sunday,
+        hour12: true,
+    };
+
+    /// The conventions of ISO 8601: `2026-10-14`, weeks starting on
+    /// Monday, and a 24-hour clock.
+    pub const ISO: DateConventions = DateConventions {
+        order: DateOrder::YearMonthDay,
+        separator: '-',
+        first_weekday: Weekday::Monday,
+        hour12: false,
+    };
+
+    /// The conventions for a locale, given as a BCP47 tag such as `"en-GB"`.
+    pub fn for_locale(tag: &str) -> DateConventions {
+        let mut parts = tag.split(&['-', '_'][..]);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts
+            .find(|part| part.len() == 2 || part.chars().all(|c| c.is_ascii_digit()))
+            .map(str::to_ascii_uppercase);
+        DateConventions::for_parts(&language, region.as_deref())
+    }
+
+    /// The conventions for the current locale.
+    pub fn for_env(env: &Env) -> DateConventions {
+        let locale = env.localization_manager().current_locale();
+        DateConventions::for_parts(locale.get_language(), locale.get_region())
+    }
+
+    fn for_parts(language: &str, region: Option<&str>) -> DateConventions {
+        use DateOrder::*;
+
+        let (order, separator) = match (language, region) {
+            ("en", None) | ("en", Some("US")) | ("en", Some("PH")) | ("es", Some("US")) => {
+                (MonthDayYear, '/')
+            }
+            ("en", Some("CA")) | ("fr", Some("CA")) | ("sv", _) | ("lt", _) => (YearMonthDay, '-'),
+            ("ja", _) | ("zh", _) => (YearMonthDay, '/'),
+            ("ko", _) | ("hu", _) => (YearMonthDay, '.'),
+            ("nl", _) => (DayMonthYear, '-'),
+            ("de", _)
+            | ("ru", _)
+            | ("pl", _)
+            | ("cs", _)
+            | ("sk", _)
+            | ("fi", _)
+            | ("nb", _)
+            | ("no", _)
+            | ("da", _)
+            | ("uk", _)
+            | ("tr", _)
+            | ("ro", _)
+            | ("hr", _)
+            | ("sl", _)
+            | ("bg", _) => (DayMonthYear, '.'),
+            ("en", _)
+            | ("fr", _)
+            | ("es", _)
+            | ("it", _)
+            | ("pt", _)
+            | ("el", _)
+            | ("id", _)
+            | ("vi", _) => (DayMonthYear, '/'),
+            _ => return DateConventions::DEFAULT,
+        };
+
+        let first_weekday = match (language, region) {
+            ("en", None) | ("ja", _) | ("ko", _) | ("he", _) => Weekday::Sunday,
+            (_, Some("US")) | (_, Some("CA")) | (_, Some("MX")) | (_, Some("BR")) => {
+                Weekday::Sunday
+            }
+            (_, Some("PH")) | (_, Some("IN")) | (_, Some("TW")) | (_, Some("IL")) => {
+                Weekday::Sunday
+            }
+            _ => Weekday::Monday,
+        };
+
+        let hour12 = match (language, region) {
+            ("en", None) | ("ko", _) | ("hi", _) => true,
+            ("en", Some(region)) => ["US", "CA", "AU", "NZ", "IN", "PH"].contains(&region),
+            _ => false,
+        };
+
+        DateConventions {
+            order,
+            separator,
+            first_weekday,
+            hour12,
+        }
+    }
+
+    /// Write `date` in these conventions, such as `"10/14/2026"`.
+    ///
+    /// Days and months always have two digits.
+    pub fn format_date(&self, date: Date) -> String {
+        let (day, month, year) = (date.day, date.month, date.year);
+        let sep = self.separator;
+        match self.order {
+            DateOrder::DayMonthYear => format!("{:02}{}{:02}{}{:04}", day, sep, month, sep, year),
+            DateOrder::MonthDayYear => format!("{:02}{}{:02}{}{:04}", month, sep, day, sep, year),
+            DateOrder::YearMonthDay => format!("{:04}{}{:02}{}{:02}", year, sep, month, sep, day),
+        }
+    }
+
+    /// Read a date written in these conventions.
+    ///
+    /// Any non-digit characters separate the fields, so `"14/10/2026"` is
+    /// read the same as `"14.10.2026"` for a day-month-year locale. Years
+    /// written with two digits are in the 2000s.
+    pub fn parse_date(&self, text: &str) -> Result<Date, ParseDateTimeError> {
+        let fields = numeric_fields(text, |c| !c.is_ascii_digit())?;
+        if fields.len() != 3 {
+            return Err(ParseDateTimeError::WrongFieldCount {
+                count: fields.len(),
+            });
+        }
+        let (day, month, year) = match self.order {
+            DateOrder::DayMonthYear => (fields[0], fields[1], fields[2]),
+            DateOrder::MonthDayYear => (fields[1], fields[0], fields[2]),
+            DateOrder::YearMonthDay => (fields[2], fields[1], fields[0]),
+        };
+        let year = if year < 100 { year + 2000 } else { year };
+        if year > i32::MAX as u32 {
+            return Err(ParseDateTimeError::OutOfRange);
+        }
+        Date::new(year as i32, month, day).ok_or(ParseDateTimeError::OutOfRange)
+    }
+
+    /// Write `time` in these conventions, such as `"3:05 PM"` or `"15:05"`.
+    pub fn format_time(&self, time: Time) -> String {
+        if self.hour12 {
+            let hour = match time.hour % 12 {
+                0 => 12,
+                hour => hour,
+            };
+            let period = if time.hour < 12 { "AM" } else { "PM" };
+            format!("{}:{:02} {}", hour, time.minute, period)
+        } else {
+            format!("{:02}:{:02}", time.hour, time.minute)
+        }
+    }
+
+    /// Read a time of day.
+    ///
+    /// Both clocks are accepted in every locale: a time ending in `AM` or
+    /// `PM` (or just `a` or `p`) uses the 12-hour clock, and any other uses
+    /// the 24-hour clock. The minutes can be left out.
+    pub fn parse_time(&self, text: &str) -> Result<Time, ParseDateTimeError> {
+        let lower = text.trim().to_ascii_lowercase();
+        let (clock, pm) = PERIODS
+            .iter()
+            .find(|(suffix, _)| lower.ends_with(suffix))
+            .map(|(suffix, pm)| (&lower[..lower.len() - suffix.len()], Some(*pm)))
+            .unwrap_or((&lower, None));
+        let fields = numeric_fields(clock, |c| c == ':' || c == '.')?;
+        let (hour, minute) = match fields.as_slice() {
+            [hour] => (*hour, 0),
+            [hour, minute] => (*hour, *minute),
+            _ => {
+                return Err(ParseDateTimeError::WrongFieldCount {
+                    count: fields.len(),
+                })
+            }
+        };
+        let hour = match pm {
+            Some(_) if !(1..=12).contains(&hour) => return Err(ParseDateTimeError::OutOfRange),
+            Some(pm) => hour % 12 + if pm { 12 } else { 0 },
+            None => hour,
+        };
+        Time::new(hour, minute).ok_or(ParseDateTimeError::OutOfRange)
+    }
+}
+
+impl Default for DateConventions {
+    fn default() -> Self {
+        DateConventions::DEFAULT
+    }
+}
+
+fn is_leap_year(year: i32) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Split `text` at the characters matching `is_separator`, and read each
+/// field as a number.
+fn numeric_fields(
+    text: &str,
+    is_separator: impl Fn(char) -> bool,
+) -> Result<Vec<u32>, ParseDateTimeError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(ParseDateTimeError::Empty);
+    }
+    text.split(is_separator)
+        .map(str::trim)
+        .filter(|field| !field.is_empty())
+        .map(|field| {
+            field
+                .parse()
+                .map_err(|_| ParseDateTimeError::ExpectedNumber {
+                    found: field.to_string(),
+                })
+        })
+        .collect()
+}
+
+impl fmt::Display for ParseDateTimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDateTimeError::Empty => write!(f, "no date or time was given"),
+            ParseDateTimeError::ExpectedNumber { found } => {
+                write!(f, "expected a number, found '{}'", found)
+            }
+            ParseDateTimeError::WrongFieldCount { count } => write!(
+                f,
+                "found {} field{}, which is not a date or a time",
+                count,
+                if *count == 1 { "" } else { "s" }
+            ),
+            ParseDateTimeError::OutOfRange => write!(f, "no such date or time"),
+        }
+    }
+}
+
+impl std::error::Error for ParseDateTimeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> Date {
+        Date::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn calendar_arithmetic() {
+        assert_eq!(date(1970, 1, 1).weekday(), Weekday::Thursday);
+        assert_eq!(date(2026, 10, 14).weekday(), Weekday::Wednesday);
+        assert_eq!(date(2000, 2, 29).weekday(), Weekday::Tuesday);
+        assert_eq!(date(2026, 12, 31).add_days(1), date(2027, 1, 1));
+        assert_eq!(date(2024, 3, 1).add_days(-1), date(2024, 2, 29));
+        assert_eq!(date(1969, 12, 31).add_days(366), date(1971, 1, 1));
+        assert_eq!(date(2026, 1, 31).add_months(1), date(2026, 2, 28));
+        assert_eq!(date(2026, 1, 15).add_months(-13), date(2024, 12, 15));
+        assert!(Date::new(2026, 2, 29).is_none());
+        assert!(Date::new(2026, 13, 1).is_none());
+    }
+
+    #[test]
+    fn time_arithmetic() {
+        let time = Time::new(23, 30).unwrap();
+        assert_eq!(time.add_minutes(45), Time::new(0, 15).unwrap());
+        assert_eq!(Time::MIDNIGHT.add_minutes(-1), Time::new(23, 59).unwrap());
+        let now = Time::new(8, 0).unwrap();
+        assert_eq!(
+            time.duration_after(now),
+            Duration::from_secs(15 * 3600 + 1800)
+        );
+        assert_eq!(
+            now.duration_after(time),
+            Duration::from_secs(8 * 3600 + 1800)
+        );
+        assert_eq!(now.duration_after(now), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn locales() {
+        let us = DateConventions::for_locale("en-US");
+        assert_eq!(us, DateConventions::DEFAULT);
+        let german = DateConventions::for_locale("de-DE");
+        assert_eq!(german.order, DateOrder::DayMonthYear);
+        assert_eq!(german.first_weekday, Weekday::Monday);
+        assert!(!german.hour12);
+        assert_eq!(
+            DateConventions::for_locale("fr_CA").order,
+            DateOrder::YearMonthDay
+        );
+        assert_eq!(DateConventions::for_locale("xx"), DateConventions::DEFAULT);
+    }
+
+    #[test]
+    fn dates_round_trip() {
+        let day = date(2026, 3, 7);
+        let german = DateConventions::for_locale("de");
+        assert_eq!(DateConventions::DEFAULT.format_date(day), "03/07/2026");
+        assert_eq!(german.format_date(day), "07.03.2026");
+        assert_eq!(DateConventions::ISO.format_date(day), "2026-03-07");
+        for conventions in &[DateConventions::DEFAULT, german, DateConventions::ISO] {
+            let text = conventions.format_date(day);
+            assert_eq!(conventions.parse_date(&text), Ok(day), "{}", text);
+        }
+        assert_eq!(german.parse_date("7/3/26"), Ok(day));
+        assert_eq!(
+            german.parse_date("7.3"),
+            Err(ParseDateTimeError::WrongFieldCount { count: 2 })
+        );
+        assert_eq!(
+            german.parse_date("30.2.2026"),
+            Err(ParseDateTimeError::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn times_round_trip() {
+        let us = DateConventions::DEFAULT;
+        let iso = DateConventions::ISO;
+        let time = Time::new(15, 5).unwrap();
+        assert_eq!(us.format_time(time), "3:05 PM");
+        assert_eq!(us.format_time(Time::MIDNIGHT), "12:00 AM");
+        assert_eq!(iso.format_time(time), "15:05");
+        assert_eq!(us.parse_time("3:05 PM"), Ok(time));
+        assert_eq!(us.parse_time("3:05p.m."), Ok(time));
+        assert_eq!(iso.parse_time("15.05"), Ok(time));
+        assert_eq!(iso.parse_time("12 am"), Ok(Time::MIDNIGHT));
+        assert_eq!(us.parse_time("13 pm"), Err(ParseDateTimeError::OutOfRange));
+        assert_eq!(iso.parse_time(" "), Err(ParseDateTimeError::Empty));
+    }
+}
@@ -0,0 +1,392 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Durations as text.
+
+use std::fmt;
+use std::time::Duration;
+
+const SECS_PER_MINUTE: u64 = 60;
+const SECS_PER_HOUR: u64 = 60 * SECS_PER_MINUTE;
+const NANOS_PER_MILLI: u32 = 1_000_000;
+
+/// The units of the human-readable format, largest first.
+const UNITS: [(&str, Unit); 4] = [
+    ("h", Unit::Hours),
+    ("m", Unit::Minutes),
+    ("s", Unit::Seconds),
+    ("ms", Unit::Millis),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Unit {
+    Hours,
+    Minutes,
+    Seconds,
+    Millis,
+}
+
+/// An error from [`parse_duration`] or [`parse_hms`].
+///
+/// [`parse_duration`]: fn.parse_duration.html
+/// [`parse_hms`]: fn.parse_hms.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDurationError {
+    /// The input was empty, or only whitespace.
+    Empty,
+    /// A number was expected, but something else was found.
+    ExpectedNumber {
+        /// The text where the number should have been.
+        found: String,
+    },
+    /// A number was not followed by a unit.
+    MissingUnit {
+        /// The number without a unit.
+        number: u64,
+    },
+    /// A unit was not one of `h`, `m`, `s` or `ms`.
+    UnknownUnit {
+        /// The unit as it appeared in the input.
+        unit: String,
+    },
+    /// A unit was repeated, or appeared after a smaller unit.
+    MisplacedUnit {
+        /// The unit as it appeared in the input.
+        unit: String,
+    },
+    /// A colon-separated input had fewer than two, or more than three, fields.
+    WrongFieldCount {
+        /// The number of fields found.
+        count: usize,
+    },
+    /// A minutes or seconds field following a larger field was 60 or more.
+    FieldOutOfRange {
+        /// The name of the field, such as `"minutes"`.
+        field: &'static str,
+        /// The value of the field.
+        value: u64,
+    },
+    /// The duration is too long to be represented.
+    Overflow,
+}
+
+/// Format a duration as a number of hours, minutes, seconds and
+/// milliseconds, such as `"1h 5m 30s"`.
+///
+/// Units with a value of zero are left out; a zero duration is `"0s"`.
+/// Precision finer than a millisecond is discarded. The result can be read
+/// back with [`parse_duration`].
+///
+/// [`parse_duration`]: fn.parse_duration.html
+pub fn duration_as_human_readable(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let fields = [
+        secs / SECS_PER_HOUR,
+        secs % SECS_PER_HOUR / SECS_PER_MINUTE,
+        secs % SECS_PER_MINUTE,
+        u64::from(duration.subsec_millis()),
+    ];
+    let parts: Vec<String> = fields
+        .iter()
+        .zip(UNITS.iter())
+        .filter(|(value, _)| **value != 0)
+        .map(|(value, (suffix, _))| format!("{}{}", value, suffix))
+        .collect();
+    if parts.is_empty() {
+        "0s".to_string()
+    } else {
+        parts.join(" ")
+    }
+}
+
+/// Format a duration as hours, minutes and seconds separated by colons,
+/// such as `"01:05:30"`.
+///
+/// Fractions of a second are discarded. The result can be read back with
+/// [`parse_hms`].
+///
+/// [`parse_hms`]: fn.parse_hms.html
+pub fn duration_as_hms(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    format!(
+        "{:02}:{:02}:{:02}",
+        secs / SECS_PER_HOUR,
+        secs % SECS_PER_HOUR / SECS_PER_MINUTE,
+        secs % SECS_PER_MINUTE
+    )
+}
+
+/// Parse a duration written as numbers with unit suffixes, such as
+/// `"1h 5m 30s"` or `"1500ms"`.
+///
+/// The units are `h`, `m`, `s` and `ms`. Each may appear once, largest
+/// first; spaces between the parts are optional. This is the inverse of
+/// [`duration_as_human_readable`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::format::parse_duration;
+///
+/// assert_eq!(parse_duration("1h 5m 30s"), Ok(Duration::from_secs(3930)));
+/// assert_eq!(parse_duration("2m30s"), Ok(Duration::from_secs(150)));
+/// assert!(parse_duration("30s 2m").is_err());
+/// ```
+///
+/// [`duration_as_human_readable`]: fn.duration_as_human_readable.html
+pub fn parse_duration(input: &str) -> Result<Duration, ParseDurationError> {
+    let mut rest = input.trim_start();
+    if rest.is_empty() {
+        return Err(ParseDurationError::Empty);
+    }
+
+    let mut secs = 0u64;
+    let mut millis = 0u64;
+    let mut last_unit = None;
+    while !rest.is_empty() {
+        let (number, after_number) = take_number(rest)?;
+        let after_number = after_number.trim_start();
+        let unit_len = after_number
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(after_number.len());
+        if unit_len == 0 {
+            return Err(ParseDurationError::MissingUnit { number });
+        }
+        let (suffix, after_unit) = after_number.split_at(unit_len);
+        let unit = match UNITS.iter().find(|(s, _)| *s == suffix) {
+            Some((_, unit)) => *unit,
+            None => {
+                return Err(ParseDurationError::UnknownUnit {
+                    unit: suffix.to_string(),
+                })
+            }
+        };
+        if last_unit.map(|last| last >= unit).unwrap_or(false) {
+            return Err(ParseDurationError::MisplacedUnit {
+                unit: suffix.to_string(),
+            });
+        }
+        last_unit = Some(unit);
+
+        let unit_secs = match unit {
+            Unit::Hours => SECS_PER_HOUR,
+            Unit::Minutes => SECS_PER_MINUTE,
+            Unit::Seconds => 1,
+            Unit::Millis => {
+                millis = number;
+                rest = after_unit.trim_start();
+                continue;
+            }
+        };
+        secs = number
+            .checked_mul(unit_secs)
+            .and_then(|s| s.checked_add(secs))
+            .ok_or(ParseDurationError::Overflow)?;
+        rest = after_unit.trim_start();
+    }
+    from_parts(secs, millis)
+}
+
+/// Parse a duration written as colon-separated fields, either
+/// `hours:minutes:seconds` or `minutes:seconds`, such as `"01:05:30"`.
+///
+/// The first field may be any size, but the fields after it must be less
+/// than 60. This is the inverse of [`duration_as_hms`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::format::parse_hms;
+///
+/// assert_eq!(parse_hms("01:05:30"), Ok(Duration::from_secs(3930)));
+/// assert_eq!(parse_hms("90:00"), Ok(Duration::from_secs(5400)));
+/// assert!(parse_hms("1:75").is_err());
+/// ```
+///
+/// [`duration_as_hms`]: fn.duration_as_hms.html
+pub fn parse_hms(input: &str) -> Result<Duration, ParseDurationError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(ParseDurationError::Empty);
+    }
+
+    let fields = input.split(':').collect::<Vec<_>>();
+    let names: &[&'static str] = match fields.len() {
+        2 => &["minutes", "seconds"],
+        3 => &["hours", "minutes", "seconds"],
+        count => return Err(ParseDurationError::WrongFieldCount { count }),
+    };
+
+    let mut secs = 0u64;
+    for (i, (field, name)) in fields.iter().zip(names.iter()).enumerate() {
+        let value = match take_number(field)? {
+            (value, "") => value,
+            (_, rest) => {
+                return Err(ParseDurationError::ExpectedNumber {
+                    found: rest.to_string(),
+                })
+            }
+        };
+        if i > 0 && value >= 60 {
+            return Err(ParseDurationError::FieldOutOfRange { field: name, value });
+        }
+        secs = secs
+            .checked_mul(60)
+            .and_then(|s| s.checked_add(value))
+            .ok_or(ParseDurationError::Overflow)?;
+    }
+    Ok(Duration::from_secs(secs))
+}
+
+/// Split a leading decimal number off `input`.
+fn take_number(input: &str) -> Result<(u64, &str), ParseDurationError> {
+    let len = input
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(input.len());
+    if len == 0 {
+        return Err(ParseDurationError::ExpectedNumber {
+            found: input.to_string(),
+        });
+    }
+    let (digits, rest) = input.split_at(len);
+    // only digits, so the only possible error is overflow.
+    let number = digits.parse().map_err(|_| ParseDurationError::Overflow)?;
+    Ok((number, rest))
+}
+
+fn from_parts(secs: u64, millis: u64) -> Result<Duration, ParseDurationError> {
+    let secs = secs
+        .checked_add(millis / 1000)
+        .ok_or(ParseDurationError::Overflow)?;
+    let nanos = (millis % 1000) as u32 * NANOS_PER_MILLI;
+    Ok(Duration::new(secs, nanos))
+}
+
+impl fmt::Display for ParseDurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseDurationError::Empty => write!(f, "no duration was given"),
+            ParseDurationError::ExpectedNumber { found } => {
+                write!(f, "expected a number, found '{}'", found)
+            }
+            ParseDurationError::MissingUnit { number } => {
+                write!(f, "'{}' needs a unit, such as 'm' or 's'", number)
+            }
+            ParseDurationError::UnknownUnit { unit } => write!(
+                f,
+                "unknown unit '{}', expected one of 'h', 'm', 's' or 'ms'",
+                unit
+            ),
+            ParseDurationError::MisplacedUnit { unit } => write!(
+                f,
+                "'{}' is repeated or out of order; units go from largest to smallest",
+                unit
+            ),
+            ParseDurationError::WrongFieldCount { count } => write!(
+                f,
+                "expected 'mm:ss' or 'hh:mm:ss', found {} field{}",
+                count,
+                if *count == 1 { "" } else { "s" }
+            ),
+            ParseDurationError::FieldOutOfRange { field, value } => {
+                write!(f, "{} must be less than 60, found {}", field, value)
+            }
+            ParseDurationError::Overflow => write!(f, "duration is too long"),
+        }
+    }
+}
+
+impl std::error::Error for ParseDurationError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_readable_round_trip() {
+        let durations = [
+            Duration::from_secs(0),
+            Duration::from_millis(1),
+            Duration::from_millis(1500),
+            Duration::from_secs(59),
+            Duration::from_secs(3930),
+            Duration::from_secs(100 * SECS_PER_HOUR + 1),
+        ];
+        for &duration in &durations {
+            let text = duration_as_human_readable(duration);
+            assert_eq!(parse_duration(&text), Ok(duration), "{}", text);
+        }
+        assert_eq!(
+            duration_as_human_readable(Duration::from_secs(3930)),
+            "1h 5m 30s"
+        );
+        assert_eq!(duration_as_human_readable(Duration::from_secs(0)), "0s");
+    }
+
+    #[test]
+    fn hms_round_trip() {
+        for &secs in &[0, 59, 60, 3930, 100 * SECS_PER_HOUR + 1] {
+            let duration = Duration::from_secs(secs);
+            let text = duration_as_hms(duration);
+            assert_eq!(parse_hms(&text), Ok(duration), "{}", text);
+        }
+        assert_eq!(duration_as_hms(Duration::from_secs(3930)), "01:05:30");
+    }
+
+    #[test]
+    fn malformed_durations() {
+        use ParseDurationError::*;
+        assert_eq!(parse_duration("  "), Err(Empty));
+        assert_eq!(parse_duration("5"), Err(MissingUnit { number: 5 }));
+        assert_eq!(
+            parse_duration("5 weeks"),
+            Err(UnknownUnit {
+                unit: "weeks".into()
+            })
+        );
+        assert_eq!(
+            parse_duration("5s 1m"),
+            Err(MisplacedUnit { unit: "m".into() })
+        );
+        assert_eq!(
+            parse_duration("1m 1m"),
+            Err(MisplacedUnit { unit: "m".into() })
+        );
+        assert_eq!(
+            parse_duration("1m -5s"),
+            Err(ExpectedNumber {
+                found: "-5s".into()
+            })
+        );
+        assert_eq!(parse_duration("99999999999999999999s"), Err(Overflow));
+        assert_eq!(parse_duration("9999999999999999999h"), Err(Overflow));
+
+        assert_eq!(parse_hms("5"), Err(WrongFieldCount { count: 1 }));
+        assert_eq!(parse_hms("1:2:3:4"), Err(WrongFieldCount { count: 4 }));
+        assert_eq!(
+            parse_hms("1:60"),
+            Err(FieldOutOfRange {
+                field: "seconds",
+                value: 60
+            })
+        );
+        assert_eq!(parse_hms("1::00"), Err(ExpectedNumber { found: "".into() }));
+        assert_eq!(
+            parse_hms("1:3x:00"),
+            Err(ExpectedNumber { found: "x".into() })
+        );
+    }
+}
@@ -0,0 +1,39 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Converting values to and from text.
+//!
+//! Numbers are formatted with a [`NumberFormatter`], which uses the decimal
+//! and group separators of the current locale.
+//!
+//! Durations can be written in two formats: a human-readable one made of
+//! numbers with unit suffixes, like `"1h 5m 30s"`, and a clock-style one with
+//! fields separated by colons, like `"01:05:30"`. Each has a function to
+//! format a `Duration` and one to parse it back.
+//!
+//! Calendar dates and times of day are written following the conventions
+//! of the current locale, given by [`DateConventions`].
+//!
+//! [`NumberFormatter`]: struct.NumberFormatter.html
+//! [`DateConventions`]: struct.DateConventions.html
+
+mod date;
+mod duration;
+mod number;
+
+pub use date::{Date, DateConventions, DateOrder, ParseDateTimeError, Time, Weekday};
+pub use duration::{
+    duration_as_hms, duration_as_human_readable, parse_duration, parse_hms, ParseDurationError,
+};
+pub use number::{NumberFormatter, NumberSymbols};
@@ -0,0 +1,313 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Numbers as text, following the conventions of a locale.
+
+use std::num::ParseFloatError;
+
+use crate::Env;
+
+/// The characters used to write numbers in a locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberSymbols {
+    /// The character between the whole and fractional parts of a number.
+    pub decimal: char,
+    /// The character between groups of three digits in the whole part.
+    pub group: char,
+}
+
+/// Formats and parses numbers, using the separators of the current locale.
+///
+/// The locale comes from the [`Env`], so a formatter can be shared between
+/// widgets; a [`Label`] whose text is produced by a formatter is updated
+/// when the environment changes.
+///
+/// Separators are known for common locales; others use those of `en-US`.
+/// Grouping is always by thousands.
+///
+/// # Examples
+///
+/// ```
+/// use druid::format::NumberFormatter;
+/// use druid::widget::Label;
+///
+/// let formatter = NumberFormatter::new().fraction_digits(0, 1);
+/// let label = Label::new(move |count: &f64, env: &_| formatter.format(*count, env));
+/// ```
+///
+/// [`Env`]: ../struct.Env.html
+/// [`Label`]: ../widget/struct.Label.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct NumberFormatter {
+    min_integer_digits: usize,
+    min_fraction_digits: usize,
+    max_fraction_digits: usize,
+    grouping: bool,
+    symbols: Option<NumberSymbols>,
+}
+
+impl NumberSymbols {
+    /// The symbols used in `en-US`, and for locales that aren't known.
+    pub const DEFAULT: NumberSymbols = NumberSymbols {
+        decimal: '.',
+        group: ',',
+    };
+
+    /// The symbols for a locale, given as a BCP47 tag such as `"de-CH"`.
+    pub fn for_locale(tag: &str) -> NumberSymbols {
+        let mut parts = tag.split(&['-', '_'][..]);
+        let language = parts.next().unwrap_or_default().to_ascii_lowercase();
+        let region = parts
+            .find(|part| part.len() == 2 || part.chars().all(|c| c.is_ascii_digit()))
+            .map(str::to_ascii_uppercase);
+        NumberSymbols::for_parts(&language, region.as_deref())
+    }
+
+    fn for_parts(language: &str, region: Option<&str>) -> NumberSymbols {
+        let (decimal, group) = match (language, region) {
+            ("de", Some("CH")) | ("it", Some("CH")) => ('.', '\u{2019}'),
+            ("es", Some("MX")) => ('.', ','),
+            ("de", _)
+            | ("es", _)
+            | ("it", _)
+            | ("nl", _)
+            | ("pt", _)
+            | ("id", _)
+            | ("tr", _)
+            | ("da", _)
+            | ("el", _)
+            | ("ro", _)
+            | ("hr", _)
+            | ("sl", _) => (',', '.'),
+            ("fr", _)
+            | ("ru", _)
+            | ("pl", _)
+            | ("cs", _)
+            | ("sk", _)
+            | ("sv", _)
+            | ("fi", _)
+            | ("nb", _)
+            | ("no", _)
+            | ("uk", _)
+            | ("hu", _)
+            | ("bg", _) => (',', '\u{a0}'),
+            _ => return NumberSymbols::DEFAULT,
+        };
+        NumberSymbols { decimal, group }
+    }
+
+    fn for_env(env: &Env) -> NumberSymbols {
+        let locale = env.localization_manager().current_locale();
+        NumberSymbols::for_parts(locale.get_language(), locale.get_region())
+    }
+}
+
+impl NumberFormatter {
+    /// Create a formatter that shows up to three fractional digits, and
+    /// groups thousands.
+    pub fn new() -> NumberFormatter {
+        NumberFormatter {
+            min_integer_digits: 1,
+            min_fraction_digits: 0,
+            max_fraction_digits: 3,
+            grouping: true,
+            symbols: None,
+        }
+    }
+
+    /// Builder-style method to set how many digits are shown after the
+    /// decimal separator.
+    ///
+    /// Numbers are rounded to `max` digits; trailing zeros are then
+    /// removed, as long as at least `min` digits remain.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min` is greater than `max`.
+    pub fn fraction_digits(mut self, min: usize, max: usize) -> Self {
+        assert!(min <= max, "min fraction digits greater than max");
+        self.min_fraction_digits = min;
+        self.max_fraction_digits = max;
+        self
+    }
+
+    /// Builder-style method to pad the whole part of numbers with zeros to
+    /// at least `min` digits, as in the minutes of `"9:05"`.
+    pub fn integer_digits(mut self, min: usize) -> Self {
+        self.min_integer_digits = min;
+        self
+    }
+
+    /// Builder-style method to set whether group separators are shown.
+    pub fn grouping(mut self, grouping: bool) -> Self {
+        self.grouping = grouping;
+        self
+    }
+
+    /// Builder-style method to use fixed symbols, instead of those of the
+    /// current locale.
+    pub fn symbols(mut self, symbols: NumberSymbols) -> Self {
+        self.symbols = Some(symbols);
+        self
+    }
+
+    /// Format `value` for the locale in `env`.
+    pub fn format(&self, value: f64, env: &Env) -> String {
+        self.format_with_symbols(value, self.symbols_for(env))
+    }
+
+    /// Parse text in the format of the locale in `env`.
+    ///
+    /// Group separators are ignored wherever they appear.
+    pub fn parse(&self, text: &str, env: &Env) -> Result<f64, ParseFloatError> {
+        self.parse_with_symbols(text, self.symbols_for(env))
+    }
+
+    /// Format `value` using `symbols`, ignoring the locale.
+    pub fn format_with_symbols(&self, value: f64, symbols: NumberSymbols) -> String {
+        if !value.is_finite() {
+            return value.to_string();
+        }
+
+        let digits = format!("{:.*}", self.max_fraction_digits, value.abs());
+        let (whole, fraction) = match digits.find('.') {
+            Some(idx) => (&digits[..idx], &digits[idx + 1..]),
+            None => (digits.as_str(), ""),
+        };
+        let mut fraction_len = fraction.len();
+        while fraction_len > self.min_fraction_digits && fraction[..fraction_len].ends_with('0') {
+            fraction_len -= 1;
+        }
+        let fraction = &fraction[..fraction_len];
+        let padded;
+        let whole = if whole.len() < self.min_integer_digits {
+            padded = format!("{:0>1$}", whole, self.min_integer_digits);
+            padded.as_str()
+        } else {
+            whole
+        };
+
+        let mut result = String::with_capacity(digits.len() + whole.len() / 3 + 1);
+        // don't show "-0" for values that round to zero.
+        if value < 0.0 && digits.bytes().any(|b| b.is_ascii_digit() && b != b'0') {
+            result.push('-');
+        }
+        for (i, c) in whole.chars().enumerate() {
+            if self.grouping && i > 0 && (whole.len() - i) % 3 == 0 {
+                result.push(symbols.group);
+            }
+            result.push(c);
+        }
+        if !fraction.is_empty() {
+            result.push(symbols.decimal);
+            result.push_str(fraction);
+        }
+        result
+    }
+
+    /// Parse text using `symbols`, ignoring the locale.
+    pub fn parse_with_symbols(
+        &self,
+        text: &str,
+        symbols: NumberSymbols,
+    ) -> Result<f64, ParseFloatError> {
+        // people type a regular space where the locale uses a special one.
+        let is_space_group = symbols.group.is_whitespace();
+        let normalized: String = text
+            .trim()
+            .chars()
+            .filter(|&c| c != symbols.group && !(is_space_group && c.is_whitespace()))
+            .map(|c| if c == symbols.decimal { '.' } else { c })
+            .collect();
+        normalized.parse()
+    }
+
+    fn symbols_for(&self, env: &Env) -> NumberSymbols {
+        self.symbols.unwrap_or_else(|| NumberSymbols::for_env(env))
+    }
+}
+
+impl Default for NumberFormatter {
+    fn default() -> Self {
+        NumberFormatter::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GERMAN: NumberSymbols = NumberSymbols {
+        decimal: ',',
+        group: '.',
+    };
+
+    #[test]
+    fn separators() {
+        let f = NumberFormatter::new().fraction_digits(0, 2);
+        assert_eq!(
+            f.format_with_symbols(1234567.891, NumberSymbols::DEFAULT),
+            "1,234,567.89"
+        );
+        assert_eq!(f.format_with_symbols(1234567.891, GERMAN), "1.234.567,89");
+        assert_eq!(f.format_with_symbols(-999.0, GERMAN), "-999");
+        assert_eq!(f.format_with_symbols(-0.001, GERMAN), "0");
+        let f = f.grouping(false);
+        assert_eq!(f.format_with_symbols(1234.5, GERMAN), "1234,5");
+    }
+
+    #[test]
+    fn fraction_digits() {
+        let f = NumberFormatter::new().fraction_digits(2, 4);
+        let format = |v| f.format_with_symbols(v, NumberSymbols::DEFAULT);
+        assert_eq!(format(1.0), "1.00");
+        assert_eq!(format(1.5), "1.50");
+        assert_eq!(format(1.23456), "1.2346");
+        let f = NumberFormatter::new().fraction_digits(0, 0);
+        assert_eq!(f.format_with_symbols(2.5001, NumberSymbols::DEFAULT), "3");
+    }
+
+    #[test]
+    fn integer_digits() {
+        let f = NumberFormatter::new().integer_digits(2);
+        let format = |v| f.format_with_symbols(v, NumberSymbols::DEFAULT);
+        assert_eq!(format(5.0), "05");
+        assert_eq!(format(-0.5), "-00.5");
+        assert_eq!(format(123.0), "123");
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let f = NumberFormatter::new();
+        let french = NumberSymbols::for_locale("fr-FR");
+        for &symbols in &[NumberSymbols::DEFAULT, GERMAN, french] {
+            for &value in &[0.0, -12.5, 1234567.125] {
+                let text = f.format_with_symbols(value, symbols);
+                assert_eq!(f.parse_with_symbols(&text, symbols), Ok(value), "{}", text);
+            }
+        }
+        assert_eq!(f.parse_with_symbols("1 234,5", french), Ok(1234.5));
+        assert_eq!(f.parse_with_symbols("1,5", GERMAN), Ok(1.5));
+        assert!(f.parse_with_symbols("abc", GERMAN).is_err());
+    }
+
+    #[test]
+    fn locales() {
+        assert_eq!(NumberSymbols::for_locale("en-US"), NumberSymbols::DEFAULT);
+        assert_eq!(NumberSymbols::for_locale("de"), GERMAN);
+        assert_eq!(NumberSymbols::for_locale("de-CH").decimal, '.');
+        assert_eq!(NumberSymbols::for_locale("de_Latn_CH").group, '\u{2019}');
+        assert_eq!(NumberSymbols::for_locale("xx-YY"), NumberSymbols::DEFAULT);
+    }
+}
@@ -0,0 +1,372 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Inspecting the live widget tree.
+//!
+//! Submitting the [`DUMP_WIDGET_TREE`] command to a window prints that
+//! window's widget tree to the log, at the `info` level: each widget's id,
+//! type, origin and size, indented under its parent. This is useful for
+//! debugging layout, and for finding the id or type to give a command's
+//! [`Target`].
+//!
+//! In tests, [`Harness::widget_tree`] returns the same information as a
+//! [`WidgetTree`], which can also map a point in the window to the widget
+//! there, and a widget to where it can be seen. Widgets can ask the same
+//! questions with [`EventCtx::widget_id_at`] and [`EventCtx::rect_of`].
+//!
+//! [`DUMP_WIDGET_TREE`]: ../commands/constant.DUMP_WIDGET_TREE.html
+//! [`Target`]: ../enum.Target.html
+//! [`Harness::widget_tree`]: ../tests/struct.Harness.html#method.widget_tree
+//! [`WidgetTree`]: struct.WidgetTree.html
+//! [`EventCtx::widget_id_at`]: ../struct.EventCtx.html#method.widget_id_at
+//! [`EventCtx::rect_of`]: ../struct.EventCtx.html#method.rect_of
+
+use std::fmt;
+
+use crate::kurbo::{Affine, Point, Rect, Shape, Size};
+use crate::{LifeCycle, WidgetId};
+
+pub use tree_cell::TreeCell;
+
+/// The tree being collected, if `event` is [`LifeCycle::RouteInspectTree`].
+///
+/// Widgets that scroll, transform, clip or reorder their children, or that
+/// paint them with a z-index, check for this before passing the event on.
+///
+/// [`LifeCycle::RouteInspectTree`]: ../enum.LifeCycle.html#variant.RouteInspectTree
+pub(crate) fn tree_cell(event: &LifeCycle) -> Option<&TreeCell> {
+    match event {
+        LifeCycle::RouteInspectTree(cell) => Some(cell),
+        _ => None,
+    }
+}
+
+/// What is known about one widget in a [`WidgetTree`].
+///
+/// [`WidgetTree`]: struct.WidgetTree.html
+#[derive(Debug, Clone)]
+pub struct WidgetInfo {
+    /// The widget's id.
+    pub id: WidgetId,
+    /// The widget's [`Widget::type_name`].
+    ///
+    /// [`Widget::type_name`]: ../trait.Widget.html#method.type_name
+    pub type_name: &'static str,
+    /// The number of widgets containing this one; the root has depth 0.
+    pub depth: usize,
+    /// The origin of the widget's layout rect, in its parent's coordinates.
+    pub origin: Point,
+    /// The origin of the widget's layout rect in the window, ignoring any
+    /// scrolling.
+    pub window_origin: Point,
+    /// The widget's size, as set by its parent.
+    pub size: Size,
+    /// The size the widget returned from its last `layout`. A parent
+    /// normally uses this, but may not.
+    pub requested_size: Size,
+    /// The transform from the widget's own coordinates to the window's,
+    /// including any scrolling or [`Transform`] around it.
+    ///
+    /// [`Transform`]: ../widget/struct.Transform.html
+    pub transform: Affine,
+    /// If an enclosing widget, such as a [`Scroll`], clips what this widget
+    /// paints, the clip in window coordinates.
+    ///
+    /// When the clip is itself rotated or skewed, this is its bounding box.
+    ///
+    /// [`Scroll`]: ../widget/struct.Scroll.html
+    pub clip: Option<Rect>,
+    /// The z-index the widget is painted with (see
+    /// [`PaintCtx::paint_with_z_index`]); 0 unless it is in a popup.
+    ///
+    /// A widget with a higher z-index than its parent is painted over
+    /// everything with a lower one, and is not clipped by its ancestors.
+    ///
+    /// [`PaintCtx::paint_with_z_index`]: ../struct.PaintCtx.html#method.paint_with_z_index
+    pub z_index: u32,
+}
+
+impl WidgetInfo {
+    /// The bounding box of the widget's layout rect, in window coordinates.
+    pub fn window_rect(&self) -> Rect {
+        self.transform.transform_rect_bbox(self.size.to_rect())
+    }
+
+    /// Whether `point`, in window coordinates, is inside the widget and
+    /// not clipped away.
+    fn contains(&self, point: Point) -> bool {
+        let local = self.transform.inverse() * point;
+        self.size.to_rect().winding(local) != 0
+            && self.clip.map(|c| c.winding(point) != 0).unwrap_or(true)
+    }
+}
+
+/// A snapshot of a window's widgets, parents before their children.
+#[derive(Debug, Clone, Default)]
+pub struct WidgetTree {
+    /// Every widget in the window, in depth-first order.
+    pub widgets: Vec<WidgetInfo>,
+}
+
+impl WidgetTree {
+    /// Find the widget with `id`.
+    pub fn find(&self, id: WidgetId) -> Option<&WidgetInfo> {
+        self.widgets.iter().find(|w| w.id == id)
+    }
+
+    /// Find every widget whose type name contains `name`.
+    pub fn find_by_type<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a WidgetInfo> {
+        self.widgets
+            .iter()
+            .filter(move |w| w.type_name.contains(name))
+    }
+
+    /// The innermost widget under `point`, in window coordinates.
+    ///
+    /// This follows the rules mouse events are routed by: a widget is only
+    /// found if the point is inside it and inside all of its ancestors, and
+    /// not clipped away. Where siblings overlap, the one painted last wins,
+    /// and widgets with a higher [`z_index`], such as popups, win over
+    /// everything below them, even outside their ancestors.
+    ///
+    /// [`z_index`]: struct.WidgetInfo.html#structfield.z_index
+    pub fn widget_id_at(&self, point: Point) -> Option<WidgetId> {
+        // whether the point is inside each of the current widget's
+        // ancestors, with their z-indices.
+        let mut ancestors: Vec<(bool, u32)> = Vec::new();
+        let mut found: Option<(WidgetId, u32)> = None;
+        for widget in &self.widgets {
+            ancestors.truncate(widget.depth);
+            let in_parent = match ancestors.last() {
+                Some((hit, z_index)) => *hit || widget.z_index > *z_index,
+                None => true,
+            };
+            let hit = in_parent && widget.contains(point);
+            if hit && found.map(|(_, z)| widget.z_index >= z).unwrap_or(true) {
+                found = Some((widget.id, widget.z_index));
+            }
+            ancestors.push((hit, widget.z_index));
+        }
+        found.map(|(id, _)| id)
+    }
+
+    /// The part of the widget with `id` that can be seen, in window
+    /// coordinates.
+    ///
+    /// This is the widget's [`window_rect`], intersected with its clip; it
+    /// has no area if the widget is scrolled out of view. Returns `None` if
+    /// there is no widget with `id`.
+    ///
+    /// [`window_rect`]: struct.WidgetInfo.html#method.window_rect
+    pub fn rect_of(&self, id: WidgetId) -> Option<Rect> {
+        self.find(id).map(|widget| match widget.clip {
+            Some(clip) => widget.window_rect().intersect(clip),
+            None => widget.window_rect(),
+        })
+    }
+}
+
+impl fmt::Display for WidgetTree {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for widget in &self.widgets {
+            write!(
+                f,
+                "{:indent$}#{} {} at ({}, {}) size {}x{}",
+                "",
+                widget.id.to_raw(),
+                widget.type_name,
+                widget.origin.x,
+                widget.origin.y,
+                widget.size.width,
+                widget.size.height,
+                indent = widget.depth * 2
+            )?;
+            if widget.requested_size != widget.size {
+                write!(
+                    f,
+                    " (requested {}x{})",
+                    widget.requested_size.width, widget.requested_size.height
+                )?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+mod tree_cell {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    use super::{WidgetInfo, WidgetTree};
+    use crate::core::BaseState;
+    use crate::kurbo::{Affine, Point, Rect};
+
+    /// Internal: collects a [`WidgetTree`] as
+    /// [`LifeCycle::RouteInspectTree`] is routed through the widgets.
+    ///
+    /// [`WidgetTree`]: struct.WidgetTree.html
+    /// [`LifeCycle::RouteInspectTree`]: ../enum.LifeCycle.html#variant.RouteInspectTree
+    #[derive(Clone, Default)]
+    pub struct TreeCell(Rc<RefCell<Builder>>);
+
+    #[derive(Default)]
+    struct Builder {
+        tree: WidgetTree,
+        /// The widgets we are currently inside.
+        ancestors: Vec<Ancestor>,
+    }
+
+    struct Ancestor {
+        window_origin: Point,
+        /// The transform from the coordinates of its children to the window.
+        child_transform: Affine,
+        /// The clip applying to its children, in window coordinates.
+        child_clip: Option<Rect>,
+        child_z_index: u32,
+    }
+
+    impl TreeCell {
+        /// Record a widget, and start recording its children.
+        pub(crate) fn enter(&self, state: &BaseState, type_name: &'static str) {
+            let mut builder = self.0.borrow_mut();
+            let origin = state.layout_rect.origin();
+            let (parent_origin, parent_transform, clip, z_index) = match builder.ancestors.last() {
+                Some(parent) => (
+                    parent.window_origin,
+                    parent.child_transform,
+                    parent.child_clip,
+                    parent.child_z_index,
+                ),
+                None => (Point::ORIGIN, Affine::default(), None, 0),
+            };
+            let window_origin = parent_origin + origin.to_vec2();
+            let transform = parent_transform * Affine::translate(origin.to_vec2());
+            let info = WidgetInfo {
+                id: state.id,
+                type_name,
+                depth: builder.ancestors.len(),
+                origin,
+                window_origin,
+                size: state.layout_rect.size(),
+                requested_size: state.requested_size,
+                transform,
+                clip,
+                z_index,
+            };
+            builder.tree.widgets.push(info);
+            builder.ancestors.push(Ancestor {
+                window_origin,
+                child_transform: transform,
+                child_clip: clip,
+                child_z_index: z_index,
+            });
+        }
+
+        /// Note that the widget entered last paints its children with
+        /// `transform`, and clipped to `clip`; both are in its own
+        /// coordinates, and the clip is applied before the transform.
+        ///
+        /// Widgets that scroll or transform their children call this before
+        /// passing the command on, so that positions are reported as painted.
+        pub(crate) fn transform_children(&self, transform: Affine, clip: Option<Rect>) {
+            let mut builder = self.0.borrow_mut();
+            let ancestor = match builder.ancestors.last_mut() {
+                Some(ancestor) => ancestor,
+                None => return,
+            };
+            let own_transform = ancestor.child_transform;
+            if let Some(clip) = clip {
+                let clip = own_transform.transform_rect_bbox(clip);
+                ancestor.child_clip = Some(match ancestor.child_clip {
+                    Some(outer) => outer.intersect(clip),
+                    None => clip,
+                });
+            }
+            ancestor.child_transform = own_transform * transform;
+        }
+
+        /// Like [`transform_children`], but only for the children entered
+        /// while `f` runs; for widgets that paint some of their children
+        /// differently from others.
+        ///
+        /// [`transform_children`]: #method.transform_children
+        pub(crate) fn with_children_transformed<R>(
+            &self,
+            transform: Affine,
+            clip: Option<Rect>,
+            f: impl FnOnce() -> R,
+        ) -> R {
+            let saved = self.save_children();
+            self.transform_children(transform, clip);
+            let result = f();
+            self.restore_children(saved);
+            result
+        }
+
+        /// Note that the children entered while `f` runs are painted with
+        /// `z_index`, over everything else and without the clips around
+        /// them, as popups are.
+        pub(crate) fn with_children_on_top<R>(&self, z_index: u32, f: impl FnOnce() -> R) -> R {
+            let saved = self.save_children();
+            if let Some(ancestor) = self.0.borrow_mut().ancestors.last_mut() {
+                ancestor.child_clip = None;
+                ancestor.child_z_index = ancestor.child_z_index.max(z_index);
+            }
+            let result = f();
+            self.restore_children(saved);
+            result
+        }
+
+        fn save_children(&self) -> Option<(Affine, Option<Rect>, u32)> {
+            self.0.borrow().ancestors.last().map(|ancestor| {
+                (
+                    ancestor.child_transform,
+                    ancestor.child_clip,
+                    ancestor.child_z_index,
+                )
+            })
+        }
+
+        fn restore_children(&self, saved: Option<(Affine, Option<Rect>, u32)>) {
+            let mut builder = self.0.borrow_mut();
+            if let (Some(ancestor), Some((transform, clip, z_index))) =
+                (builder.ancestors.last_mut(), saved)
+            {
+                ancestor.child_transform = transform;
+                ancestor.child_clip = clip;
+                ancestor.child_z_index = z_index;
+            }
+        }
+
+        /// Finish recording the widget that was entered last.
+        pub(crate) fn exit(&self) {
+            self.0.borrow_mut().ancestors.pop();
+        }
+
+        pub(crate) fn take(&self) -> WidgetTree {
+            std::mem::take(&mut self.0.borrow_mut().tree)
+        }
+    }
+
+    impl std::fmt::Debug for TreeCell {
+        fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            write!(
+                f,
+                "TreeCell({} widgets)",
+                self.0.borrow().tree.widgets.len()
+            )
+        }
+    }
+}
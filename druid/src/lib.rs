@@ -89,6 +89,17 @@
 //! }
 //! ```
 //!
+//! # Tracing
+//!
+//! With the `tracing` feature enabled, druid enters a [`tracing`] span for
+//! every `event`, `lifecycle`, `update`, `layout` and `paint` pass. Each
+//! window's pass gets a span at the `DEBUG` level, carrying the window's
+//! id, and inside it each widget the pass reaches gets one at the `TRACE`
+//! level, carrying the widget's id and [`Widget::type_name`]. A subscriber
+//! can use these to see where the time goes, or which widget asked for a
+//! repaint. Without a subscriber that wants them, the spans cost little
+//! more than checking a cached flag.
+//!
 //! [`Widget`]: trait.Widget.html
 //! [`Data`]: trait.Data.html
 //! [`Lens`]: trait.Lens.html
@@ -97,6 +108,8 @@
 //! [`druid-shell`]: https://docs.rs/druid-shell
 //! [`piet`]: https://docs.rs/piet
 //! [`druid/examples`]: https://github.com/xi-editor/druid/tree/v0.4.0/druid/examples
+//! [`tracing`]: https://docs.rs/tracing
+//! [`Widget::type_name`]: trait.Widget.html#method.type_name
 
 #![deny(intra_doc_link_resolution_failure, unsafe_code)]
 #![allow(clippy::new_ret_no_self, clippy::needless_doctest_main)]
@@ -110,25 +123,43 @@ use druid_shell as shell;
 #[doc(inline)]
 pub use druid_shell::{kurbo, piet};
 
+#[macro_use]
+mod trace;
+
 mod app;
 mod app_delegate;
+#[cfg(feature = "audio")]
+#[cfg_attr(docsrs, doc(cfg(feature = "audio")))]
+pub mod audio;
 mod bloom;
 mod box_constraints;
+mod clock;
 mod command;
 mod contexts;
 mod core;
 mod data;
+mod debug_overlay;
 mod env;
 mod event;
 mod ext_event;
+pub mod format;
+pub mod inspect;
 pub mod lens;
 mod localization;
 mod menu;
 mod mouse;
-#[cfg(test)]
-mod tests;
+#[cfg(feature = "persistence")]
+#[cfg_attr(docsrs, doc(cfg(feature = "persistence")))]
+pub mod persist;
+pub mod recording;
+pub mod stats;
+mod task;
+#[cfg(any(test, feature = "test-harness"))]
+#[cfg_attr(docsrs, doc(cfg(feature = "test-harness")))]
+pub mod tests;
 mod text;
 pub mod theme;
+mod toast;
 pub mod widget;
 mod win_handler;
 mod window;
@@ -138,9 +169,9 @@ pub use kurbo::{Affine, Insets, Point, Rect, Size, Vec2};
 pub use piet::{Color, LinearGradient, RadialGradient, RenderContext, UnitPoint};
 // these are the types from shell that we expose; others we only use internally.
 pub use shell::{
-    Application, Clipboard, ClipboardFormat, Cursor, Error as PlatformError, FileDialogOptions,
-    FileInfo, FileSpec, FormatId, HotKey, KeyCode, KeyEvent, KeyModifiers, MouseButton, RawMods,
-    SysMods, Text, TimerToken, WindowHandle,
+    Application, Clipboard, ClipboardFormat, ColorScheme, Cursor, Error as PlatformError,
+    FileDialogOptions, FileInfo, FileSpec, FormatId, HotKey, KeyCode, KeyEvent, KeyModifiers,
+    MouseButton, RawMods, SysMods, Text, TimerToken, WindowHandle,
 };
 
 pub use crate::core::WidgetPod;
@@ -150,13 +181,15 @@ pub use box_constraints::BoxConstraints;
 pub use command::{sys as commands, Command, Selector, Target};
 pub use contexts::{EventCtx, LayoutCtx, LifeCycleCtx, PaintCtx, Region, UpdateCtx};
 pub use data::Data;
-pub use env::{Env, Key, KeyOrValue, Value, ValueType};
-pub use event::{Event, LifeCycle, WheelEvent};
+pub use env::{Env, EnvError, Key, KeyOrValue, Value, ValueType, ValueTypeError};
+pub use event::{Event, Gesture, GesturePan, GesturePinch, LifeCycle, WheelEvent};
 pub use ext_event::{ExtEventError, ExtEventSink};
 pub use lens::{Lens, LensExt, LensWrap};
 pub use localization::LocalizedString;
 pub use menu::{sys as platform_menus, ContextMenu, MenuDesc, MenuItem};
-pub use mouse::MouseEvent;
+pub use mouse::{MouseEvent, MouseGesture};
+pub use task::{Executor, TaskCtx, TaskHandle};
+pub use toast::{Toast, ToastPosition};
 pub use widget::{Widget, WidgetExt, WidgetId};
 pub use win_handler::DruidHandler;
 pub use window::{Window, WindowId};
@@ -287,6 +287,19 @@ impl L10nManager {
             Some(result)
         }
     }
+
+    /// The locale strings are currently being localized for.
+    pub(crate) fn current_locale(&self) -> &LanguageIdentifier {
+        &self.current_locale
+    }
+
+    /// Whether the current locale's language is written right-to-left.
+    pub(crate) fn is_rtl(&self) -> bool {
+        const RTL_LANGUAGES: &[&str] = &[
+            "ar", "arc", "ckb", "dv", "fa", "he", "ks", "ps", "sd", "ug", "ur", "yi",
+        ];
+        RTL_LANGUAGES.contains(&self.current_locale.get_language())
+    }
     //TODO: handle locale change
 }
 
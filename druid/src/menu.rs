@@ -255,7 +255,10 @@ impl<T: Data> MenuDesc<T> {
         return None;
 
         // we want to explicitly handle all platforms; log if a platform is missing.
-        log::warn!("MenuDesc::platform_default is not implemented for this platform.");
+        log::warn!(
+            target: "druid::window",
+            "MenuDesc::platform_default is not implemented for this platform."
+        );
         None
     }
 
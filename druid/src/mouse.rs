@@ -35,6 +35,38 @@ pub struct MouseEvent {
     /// The currently pressed button in the case of a move or click event,
     /// or the released button in the case of a mouse-up event.
     pub button: MouseButton,
+    /// What this event is part of, as recognized from the movement since
+    /// the button was pressed.
+    pub gesture: MouseGesture,
+}
+
+/// What a mouse event is part of.
+///
+/// While a button is held, the mouse must move further than
+/// [`theme::DRAG_THRESHOLD`] from where it was pressed before the press
+/// becomes a drag, so that a click with a slightly unsteady hand is still a
+/// click. Once the button has been held for [`theme::DRAG_DELAY`], any
+/// movement starts a drag.
+///
+/// The gesture is recognized by the window, before the event reaches any
+/// widget, so both keys are read from the window's root `Env`, as set by
+/// [`AppLauncher::configure_env`]; setting them with an [`EnvScope`] has no
+/// effect.
+///
+/// [`AppLauncher::configure_env`]: struct.AppLauncher.html#method.configure_env
+/// [`EnvScope`]: widget/struct.EnvScope.html
+/// [`theme::DRAG_THRESHOLD`]: theme/constant.DRAG_THRESHOLD.html
+/// [`theme::DRAG_DELAY`]: theme/constant.DRAG_DELAY.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseGesture {
+    /// No button is held; the mouse is just moving.
+    Hover,
+    /// A button is held, and the mouse has not moved far enough to start a
+    /// drag. A mouse-up with this gesture ends a click.
+    Press,
+    /// A button is held, and the mouse has moved far enough to start a
+    /// drag. A mouse-up with this gesture ends the drag.
+    Drag,
 }
 
 impl From<druid_shell::MouseEvent> for MouseEvent {
@@ -51,6 +83,8 @@ impl From<druid_shell::MouseEvent> for MouseEvent {
             mods,
             count,
             button,
+            // filled in by the window, which knows about earlier events.
+            gesture: MouseGesture::Hover,
         }
     }
 }
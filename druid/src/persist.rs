@@ -0,0 +1,558 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Saving app state to disk, and loading it again on launch.
+//!
+//! App state is stored as JSON or [RON], using [serde], along with a version
+//! number.
+//! When the shape of the state changes between releases of an app, each
+//! change is described by a migration: a function that rewrites state saved
+//! by the previous version. On load, the migrations the file hasn't seen yet
+//! are run in order, before the state is deserialized.
+//!
+//! Any type that implements `Serialize` and `Deserialize` can be stored;
+//! the [`persistent`] attribute derives both for a type that derives
+//! [`Data`], leaving out the fields marked `#[data(ignore)]`. The
+//! `Duration`, `Arc` and `Rc` types commonly found in app state are
+//! supported. Fields marked `#[serde(default)]` (or a `#[serde(default)]` on
+//! the struct) may be missing from the file, which is often simpler than
+//! writing a migration for a newly added field.
+//!
+//! To keep the file up to date while the app runs, wrap the root widget in
+//! an [`AutoSave`] controller.
+//!
+//! This module requires the `persistence` feature.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use std::time::Duration;
+//!
+//! use druid::persist::{persistent, StateFile};
+//! use druid::Data;
+//!
+//! #[persistent]
+//! #[derive(Clone, Data)]
+//! struct AppState {
+//!     duration: Duration,
+//!     #[serde(default)]
+//!     sound: bool,
+//!     // not saved, and false when loaded.
+//!     #[data(ignore)]
+//!     running: bool,
+//! }
+//!
+//! // version 0 stored the duration as a number of seconds.
+//! let file = StateFile::new("timer.json").migration(|state| {
+//!     let secs = state["duration"].as_u64().unwrap_or(60);
+//!     state["duration"] = serde_json::json!({ "secs": secs, "nanos": 0 });
+//! });
+//!
+//! let state = file.load().unwrap_or_else(|e| {
+//!     eprintln!("{}", e);
+//!     None
+//! });
+//! let state = state.unwrap_or(AppState {
+//!     duration: Duration::from_secs(60),
+//!     sound: true,
+//!     running: false,
+//! });
+//! // AppLauncher::with_window(window).launch(state)
+//! ```
+//!
+//! [RON]: https://github.com/ron-rs/ron
+//! [serde]: https://serde.rs
+//! [`persistent`]: attr.persistent.html
+//! [`Data`]: ../trait.Data.html
+//! [`Lens`]: ../trait.Lens.html
+//! [`AutoSave`]: struct.AutoSave.html
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ron::ser::PrettyConfig;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::widget::Controller;
+use crate::{Data, Env, Event, EventCtx, TimerToken, UpdateCtx, Widget};
+
+pub use druid_derive::persistent;
+
+/// The default delay between a change and the following save.
+const DEFAULT_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A function that upgrades saved state by one version.
+pub type MigrateFn = dyn Fn(&mut Value);
+
+/// A file containing versioned app state.
+///
+/// The current version is the number of migrations that have been added
+/// with [`migration`]; state is saved with this version, and state saved
+/// with an earlier version is migrated when it is loaded.
+///
+/// [`migration`]: #method.migration
+pub struct StateFile {
+    path: PathBuf,
+    format: Format,
+    migrations: Vec<Box<MigrateFn>>,
+}
+
+/// The syntax a [`StateFile`] is written in.
+///
+/// [`StateFile`]: struct.StateFile.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// JSON, used unless the file has the `ron` extension.
+    Json,
+    /// [RON](https://github.com/ron-rs/ron).
+    ///
+    /// The state has the same structure as it would in JSON: structs are
+    /// written as maps and unit variants as strings, so that migrations see
+    /// the same value whichever format the file is in.
+    Ron,
+}
+
+/// Errors that can occur when loading or saving a [`StateFile`].
+///
+/// [`StateFile`]: struct.StateFile.html
+#[derive(Debug)]
+pub enum PersistError {
+    /// The file could not be read or written.
+    Io(io::Error),
+    /// The file's contents did not match the expected shape.
+    Format(serde_json::Error),
+    /// The file's contents were not valid RON, or the state could not be
+    /// written as RON.
+    Ron(ron::Error),
+    /// The file was saved by a newer version of the app.
+    UnknownVersion {
+        /// The version in the file.
+        found: u32,
+        /// The newest version this app knows about.
+        current: u32,
+    },
+}
+
+/// What is actually written to disk.
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    state: T,
+}
+
+impl StateFile {
+    /// Create a `StateFile` at `path`, with no migrations.
+    ///
+    /// The file is written in RON if `path` has the `ron` extension, and in
+    /// JSON otherwise.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let format = match path.extension() {
+            Some(ext) if ext == "ron" => Format::Ron,
+            _ => Format::Json,
+        };
+        StateFile {
+            path,
+            format,
+            migrations: Vec::new(),
+        }
+    }
+
+    /// Builder-style method to set the format, whatever the file's
+    /// extension.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Builder-style method to add a migration, which raises the current
+    /// version by one.
+    ///
+    /// The function is passed state saved with the previous version, as a
+    /// JSON value even if the file is in RON, and should rewrite it into the
+    /// shape used by the new one.
+    pub fn migration(mut self, migrate: impl Fn(&mut Value) + 'static) -> Self {
+        self.migrations.push(Box::new(migrate));
+        self
+    }
+
+    /// The path of the file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The version state is saved with.
+    pub fn version(&self) -> u32 {
+        self.migrations.len() as u32
+    }
+
+    /// Load the state, running any migrations it needs.
+    ///
+    /// Returns `Ok(None)` if the file does not exist, as it won't the first
+    /// time an app is run.
+    pub fn load<T: DeserializeOwned>(&self) -> Result<Option<T>, PersistError> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let envelope: Envelope<Value> = match self.format {
+            Format::Json => serde_json::from_str(&contents)?,
+            Format::Ron => ron::from_str(&contents)?,
+        };
+        let state = self.migrate(envelope.version, envelope.state)?;
+        Ok(Some(serde_json::from_value(state)?))
+    }
+
+    /// Save the state with the current version.
+    ///
+    /// The state is written to a temporary file next to the destination,
+    /// which then replaces it, so that a crash while saving can't leave a
+    /// partially written file behind.
+    pub fn save<T: Serialize>(&self, state: &T) -> Result<(), PersistError> {
+        let version = self.version();
+        let contents = match self.format {
+            Format::Json => serde_json::to_string_pretty(&Envelope { version, state })?,
+            Format::Ron => {
+                let state = serde_json::to_value(state)?;
+                ron::ser::to_string_pretty(&Envelope { version, state }, PrettyConfig::new())?
+            }
+        };
+
+        let mut temp_name = self.path.file_name().unwrap_or_default().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = self.path.with_file_name(temp_name);
+        fs::write(&temp_path, contents)?;
+        if let Err(e) = fs::rename(&temp_path, &self.path) {
+            let _ = fs::remove_file(&temp_path);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+
+    fn migrate(&self, version: u32, mut state: Value) -> Result<Value, PersistError> {
+        if version > self.version() {
+            return Err(PersistError::UnknownVersion {
+                found: version,
+                current: self.version(),
+            });
+        }
+        for migrate in &self.migrations[version as usize..] {
+            migrate(&mut state);
+        }
+        Ok(state)
+    }
+}
+
+/// A [`Controller`] that saves app state to a [`StateFile`] whenever it
+/// changes.
+///
+/// Saves are debounced: the state is saved once it has stopped changing for
+/// the save interval, however many changes came before. State that changes
+/// in short bursts, such as a value edited by dragging a slider, is
+/// therefore written once per burst rather than on every change. Changes
+/// that haven't been saved yet are saved when the controller is dropped,
+/// as it is when its window closes.
+///
+/// Changes are noticed in [`update`]; wrapping the root widget of a window
+/// sees every change to the app state.
+///
+/// Fields that shouldn't be saved, such as the state of a running
+/// animation, can be marked `#[serde(skip)]`, or `#[data(ignore)]` on a type
+/// using the [`persistent`] attribute; they are reset to their `Default`
+/// value when the state is loaded. Failures to save are logged.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use druid::persist::{AutoSave, StateFile};
+/// # use druid::widget::Label;
+/// # use druid::{Widget, WidgetExt};
+/// # use std::time::Duration;
+/// fn root() -> impl Widget<u64> {
+///     let file = StateFile::new("counter.json");
+///     Label::new(|count: &u64, _env: &_| format!("{}", count))
+///         .controller(AutoSave::new(file).interval(Duration::from_secs(5)))
+/// }
+/// ```
+///
+/// [`Controller`]: ../widget/trait.Controller.html
+/// [`StateFile`]: struct.StateFile.html
+/// [`update`]: ../trait.Widget.html#tymethod.update
+/// [`persistent`]: attr.persistent.html
+pub struct AutoSave<T: Serialize> {
+    file: StateFile,
+    interval: Duration,
+    /// The latest state, if it hasn't been saved yet.
+    unsaved: Option<T>,
+    /// When the state will have been unchanged for `interval`.
+    deadline: Instant,
+    timer: TimerToken,
+}
+
+impl<T: Serialize> AutoSave<T> {
+    /// Create an `AutoSave` that writes to `file`, one second after the last
+    /// change.
+    pub fn new(file: StateFile) -> Self {
+        AutoSave {
+            file,
+            interval: DEFAULT_SAVE_INTERVAL,
+            unsaved: None,
+            deadline: Instant::now(),
+            timer: TimerToken::INVALID,
+        }
+    }
+
+    /// Builder-style method to set how long the state must stay unchanged
+    /// before it is saved.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// The file state is saved to.
+    pub fn file(&self) -> &StateFile {
+        &self.file
+    }
+
+    fn save(&mut self) {
+        if let Some(state) = self.unsaved.take() {
+            if let Err(e) = self.file.save(&state) {
+                log::warn!("failed to save {}: {}", self.file.path().display(), e);
+            }
+        }
+    }
+}
+
+impl<T: Data + Serialize, W: Widget<T>> Controller<T, W> for AutoSave<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::Timer(token) if *token == self.timer => {
+                // a change since the timer was set pushed the deadline back.
+                self.timer = if ctx.now() < self.deadline {
+                    ctx.request_timer(self.deadline)
+                } else {
+                    self.save();
+                    TimerToken::INVALID
+                };
+                ctx.set_handled();
+            }
+            _ => child.event(ctx, event, data, env),
+        }
+    }
+
+    fn update(&mut self, child: &mut W, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if !old_data.same(data) {
+            self.unsaved = Some(data.clone());
+            self.deadline = ctx.now() + self.interval;
+            if self.timer == TimerToken::INVALID {
+                self.timer = ctx.request_timer(self.deadline);
+            }
+        }
+        child.update(ctx, old_data, data, env)
+    }
+}
+
+impl<T: Serialize> Drop for AutoSave<T> {
+    fn drop(&mut self) {
+        self.save();
+    }
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PersistError::Io(e) => write!(f, "failed to access saved state: {}", e),
+            PersistError::Format(e) => write!(f, "saved state is malformed: {}", e),
+            PersistError::Ron(e) => write!(f, "saved state is malformed: {}", e),
+            PersistError::UnknownVersion { found, current } => write!(
+                f,
+                "saved state has version {}, but the newest known version is {}",
+                found, current
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PersistError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PersistError::Io(e) => Some(e),
+            PersistError::Format(e) => Some(e),
+            PersistError::Ron(e) => Some(e),
+            PersistError::UnknownVersion { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for PersistError {
+    fn from(e: io::Error) -> PersistError {
+        PersistError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for PersistError {
+    fn from(e: serde_json::Error) -> PersistError {
+        PersistError::Format(e)
+    }
+}
+
+impl From<ron::Error> for PersistError {
+    fn from(e: ron::Error) -> PersistError {
+        PersistError::Ron(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Harness;
+    use crate::widget::Label;
+    use crate::Selector;
+    use crate::WidgetExt;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct State {
+        duration: Duration,
+        #[serde(default)]
+        sound: bool,
+    }
+
+    #[persistent]
+    #[derive(Clone, Data, Debug, PartialEq)]
+    struct Timer {
+        duration: Duration,
+        mode: Mode,
+        #[data(ignore)]
+        elapsed: Option<Duration>,
+    }
+
+    #[derive(Clone, Copy, Data, Debug, PartialEq, Serialize, Deserialize)]
+    enum Mode {
+        Countdown,
+        Stopwatch,
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("druid-persist-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = temp_path("round-trip.json");
+        let file = StateFile::new(&path);
+        assert!(file.load::<State>().unwrap().is_none());
+
+        let state = State {
+            duration: Duration::from_millis(1500),
+            sound: true,
+        };
+        file.save(&state).unwrap();
+        assert_eq!(file.load::<State>().unwrap(), Some(state));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn ron_round_trip_skips_ignored_fields() {
+        let path = temp_path("round-trip.ron");
+        let file = StateFile::new(&path).migration(|_| ());
+        let timer = Timer {
+            duration: Duration::from_secs(90),
+            mode: Mode::Stopwatch,
+            elapsed: Some(Duration::from_secs(3)),
+        };
+        file.save(&timer).unwrap();
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.contains("\"Stopwatch\""));
+        assert!(!contents.contains("elapsed"));
+
+        let expected = Timer {
+            elapsed: None,
+            ..timer
+        };
+        assert_eq!(file.load::<Timer>().unwrap(), Some(expected));
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn migrations_run_in_order() {
+        let path = temp_path("migrate.json");
+        fs::write(&path, r#"{ "version": 0, "state": { "secs": 90 } }"#).unwrap();
+
+        let file = StateFile::new(&path)
+            .migration(|state| state["duration"] = state["secs"].take())
+            .migration(|state| {
+                let secs = state["duration"].clone();
+                state["duration"] = serde_json::json!({ "secs": secs, "nanos": 0 });
+            });
+        assert_eq!(file.version(), 2);
+
+        let expected = State {
+            duration: Duration::from_secs(90),
+            sound: false,
+        };
+        assert_eq!(file.load::<State>().unwrap(), Some(expected));
+
+        let newer = StateFile::new(&path).migration(|_| ());
+        fs::write(&path, r#"{ "version": 2, "state": {} }"#).unwrap();
+        match newer.load::<State>() {
+            Err(PersistError::UnknownVersion {
+                found: 2,
+                current: 1,
+            }) => (),
+            other => panic!("unexpected {:?}", other),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn auto_save_waits_for_changes_to_stop() {
+        const INCREMENT: Selector = Selector::new("druid-test.increment");
+        let path = temp_path("auto-save.json");
+        let counter = Label::new("counter")
+            .capture(|_, event, data: &mut u64, _| match event {
+                Event::Command(cmd) if cmd.is(INCREMENT) => *data += 1,
+                _ => (),
+            })
+            .controller(AutoSave::new(StateFile::new(&path)).interval(Duration::from_millis(100)));
+
+        Harness::create(0u64, counter, |harness| {
+            harness.send_initial_events();
+            harness.submit_command(INCREMENT, None);
+            harness.advance_time(Duration::from_millis(50));
+            harness.submit_command(INCREMENT, None);
+            // the second change pushed the save back.
+            harness.advance_time(Duration::from_millis(50));
+            assert!(!path.exists());
+
+            harness.advance_time(Duration::from_millis(50));
+            assert_eq!(StateFile::new(&path).load::<u64>().unwrap(), Some(2));
+
+            // nothing changed, so nothing more is written.
+            fs::remove_file(&path).unwrap();
+            harness.advance_time(Duration::from_secs(1));
+            assert!(!path.exists());
+
+            // closing the window saves what is left.
+            harness.submit_command(INCREMENT, None);
+        });
+        assert_eq!(StateFile::new(&path).load::<u64>().unwrap(), Some(3));
+        fs::remove_file(&path).unwrap();
+    }
+}
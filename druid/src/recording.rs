@@ -0,0 +1,698 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Recording input events to a file, and replaying them.
+//!
+//! A recording captures the input a window receives, with the time of
+//! each event, so that a session can be replayed to reproduce a bug or to
+//! run a demo. Record a window with [`WindowDesc::record_events`], and
+//! replay a recording into a window with [`WindowDesc::replay_events`], or
+//! into a [`Harness`] with [`Harness::replay`].
+//!
+//! Only input is recorded: mouse, wheel, keyboard, zoom, gesture and resize
+//! events.
+//! Timers are not; they fire again on replay because the widgets that
+//! requested them do so again. Replaying into the harness, whose clock
+//! only moves when told to, reproduces timer-based behavior exactly.
+//!
+//! # Format
+//!
+//! A recording is a UTF-8 text file. The first line is the header,
+//! `druid-events 1`, where the number is the format version. Every other
+//! line is an event, a blank line, or a comment starting with `#`.
+//!
+//! An event line starts with its time, in seconds from the start of the
+//! recording, and its kind, followed by fields separated by spaces:
+//!
+//! ```text
+//! druid-events 1
+//! 0.000000 size 800 600
+//! 0.512000 mouse-move 120 48.5 left 0 -
+//! 0.600000 mouse-down 120 48.5 left 1 -
+//! 0.680000 mouse-up 120 48.5 left 0 -
+//! 1.250000 wheel 0 -53 shift
+//! 2.000000 key-down KeyA shift "A" "a"
+//! 2.030000 key-down KeyA shift "A" "a" repeat
+//! 2.100000 key-up KeyA shift "A" "a"
+//! 3.000000 zoom 0.1
+//! 3.500000 pinch 1.25 200 150
+//! 3.520000 pan 4 -2 202 149
+//! ```
+//!
+//! - `size <width> <height>`: the window was resized, in physical pixels.
+//! - `mouse-down`, `mouse-up` and `mouse-move` take
+//!   `<x> <y> <button> <count> <mods>`; the position is in window
+//!   coordinates, and the button is one of `left`, `middle`, `right`, `x1`
+//!   or `x2`.
+//! - `wheel <dx> <dy> <mods>`.
+//! - `key-down` and `key-up` take `<key code> <mods> <text> <unmodified
+//!   text>`, and `repeat` for repeated key presses. The key code is a
+//!   [`KeyCode`] variant name; texts are quoted, with Rust string escapes.
+//! - `zoom <delta>`.
+//! - `pinch <scale> <x> <y>` and `pan <dx> <dy> <x> <y>`, where the
+//!   position is the gesture's center, in window coordinates.
+//!
+//! Modifiers are written as `-` for none, or as some of `shift`, `alt`,
+//! `ctrl` and `meta` joined with `+`.
+//!
+//! [`WindowDesc::record_events`]: ../struct.WindowDesc.html#method.record_events
+//! [`WindowDesc::replay_events`]: ../struct.WindowDesc.html#method.replay_events
+//! [`Harness`]: ../tests/struct.Harness.html
+//! [`Harness::replay`]: ../tests/struct.Harness.html#method.replay
+//! [`KeyCode`]: ../enum.KeyCode.html
+
+use std::fmt::{self, Write as _};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use crate::kurbo::{Point, Size, Vec2};
+use crate::{
+    Event, Gesture, GesturePan, GesturePinch, KeyCode, KeyEvent, KeyModifiers, MouseButton,
+    MouseEvent, MouseGesture, TimerToken, WheelEvent,
+};
+
+/// The first line of every recording.
+pub const FORMAT_HEADER: &str = "druid-events 1";
+
+/// An input event, and when it happened.
+#[derive(Debug, Clone)]
+pub struct RecordedEvent {
+    /// The time since the start of the recording.
+    pub time: Duration,
+    /// The event.
+    pub event: Event,
+}
+
+/// A sequence of recorded input events, in the order they happened.
+#[derive(Debug, Clone, Default)]
+pub struct EventLog {
+    events: Vec<RecordedEvent>,
+}
+
+/// An error in the text of a recording.
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    /// The line of the error, starting at 1.
+    pub line: usize,
+    /// What is wrong with the line.
+    pub message: String,
+}
+
+impl EventLog {
+    /// Create an empty log.
+    pub fn new() -> Self {
+        EventLog::default()
+    }
+
+    /// Add `event`, which happened `time` after the start of the recording.
+    ///
+    /// Returns `false`, and does nothing, if the event is not one that
+    /// can be recorded.
+    pub fn push(&mut self, time: Duration, event: &Event) -> bool {
+        if !is_recordable(event) {
+            return false;
+        }
+        self.events.push(RecordedEvent {
+            time,
+            event: event.clone(),
+        });
+        true
+    }
+
+    /// The recorded events.
+    pub fn events(&self) -> &[RecordedEvent] {
+        &self.events
+    }
+
+    /// The number of recorded events.
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// `true` if nothing has been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Parse a recording in the [format described above](index.html#format).
+    pub fn parse(text: &str) -> Result<EventLog, ParseError> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header.trim() == FORMAT_HEADER => (),
+            Some((_, header)) if header.starts_with("druid-events ") => {
+                return Err(ParseError::new(1, "unsupported format version"))
+            }
+            _ => return Err(ParseError::new(1, "missing druid-events header")),
+        }
+
+        let mut log = EventLog::new();
+        for (idx, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let event = parse_line(line).map_err(|message| ParseError {
+                line: idx + 1,
+                message,
+            })?;
+            log.events.push(event);
+        }
+        Ok(log)
+    }
+
+    /// Read a recording from the file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<EventLog> {
+        let text = std::fs::read_to_string(path)?;
+        EventLog::parse(&text).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write this recording to the file at `path`.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+impl fmt::Display for EventLog {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", FORMAT_HEADER)?;
+        for recorded in &self.events {
+            // only recordable events are ever pushed.
+            if let Some(line) = format_line(recorded.time, &recorded.event) {
+                writeln!(f, "{}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for EventLog {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        EventLog::parse(s)
+    }
+}
+
+impl ParseError {
+    fn new(line: usize, message: &str) -> Self {
+        ParseError {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Where a window's events are being recorded to.
+pub(crate) enum EventRecorder {
+    /// Lines are written, and flushed, as events arrive, so that the
+    /// recording survives a crash.
+    File {
+        start: Option<Instant>,
+        out: BufWriter<File>,
+    },
+    /// Kept for the test harness.
+    #[cfg(any(test, feature = "test-harness"))]
+    Memory {
+        start: Option<Instant>,
+        log: EventLog,
+    },
+}
+
+impl EventRecorder {
+    pub(crate) fn to_file(path: &Path) -> io::Result<EventRecorder> {
+        let mut out = BufWriter::new(File::create(path)?);
+        writeln!(out, "{}", FORMAT_HEADER)?;
+        out.flush()?;
+        Ok(EventRecorder::File { start: None, out })
+    }
+
+    #[cfg(any(test, feature = "test-harness"))]
+    pub(crate) fn in_memory() -> EventRecorder {
+        EventRecorder::Memory {
+            start: None,
+            log: EventLog::new(),
+        }
+    }
+
+    /// Record `event`, arriving at `now`. The first event recorded is at
+    /// time zero.
+    pub(crate) fn record(&mut self, now: Instant, event: &Event) {
+        match self {
+            EventRecorder::File { start, out } => {
+                let time = now - *start.get_or_insert(now);
+                if let Some(line) = format_line(time, event) {
+                    if let Err(e) = writeln!(out, "{}", line).and_then(|_| out.flush()) {
+                        log::warn!(target: "druid::event", "failed to record event: {}", e);
+                    }
+                }
+            }
+            #[cfg(any(test, feature = "test-harness"))]
+            EventRecorder::Memory { start, log } => {
+                let time = now - *start.get_or_insert(now);
+                log.push(time, event);
+            }
+        }
+    }
+
+    #[cfg(any(test, feature = "test-harness"))]
+    pub(crate) fn log(&self) -> Option<&EventLog> {
+        match self {
+            EventRecorder::File { .. } => None,
+            EventRecorder::Memory { log, .. } => Some(log),
+        }
+    }
+}
+
+/// The progress of replaying a recording into a window.
+pub(crate) struct EventReplay {
+    log: EventLog,
+    next: usize,
+    start: Option<Instant>,
+    /// The timer that wakes us for the next event.
+    pub(crate) timer: Option<TimerToken>,
+}
+
+impl EventReplay {
+    pub(crate) fn new(log: EventLog) -> Self {
+        EventReplay {
+            log,
+            next: 0,
+            start: None,
+            timer: None,
+        }
+    }
+
+    /// Start the replay's clock, if it isn't running.
+    pub(crate) fn start(&mut self, now: Instant) {
+        self.start.get_or_insert(now);
+    }
+
+    /// When the next event is due, if there is one and the replay has started.
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        let start = self.start?;
+        self.log.events.get(self.next).map(|e| start + e.time)
+    }
+
+    /// Remove and return the events that are due at `now`.
+    pub(crate) fn take_due(&mut self, now: Instant) -> Vec<Event> {
+        let start = match self.start {
+            Some(start) => start,
+            None => return Vec::new(),
+        };
+        let due = self.log.events[self.next..]
+            .iter()
+            .take_while(|e| start + e.time <= now)
+            .map(|e| e.event.clone())
+            .collect::<Vec<_>>();
+        self.next += due.len();
+        due
+    }
+}
+
+fn is_recordable(event: &Event) -> bool {
+    matches!(
+        event,
+        Event::Size(_)
+            | Event::MouseDown(_)
+            | Event::MouseUp(_)
+            | Event::MouseMoved(_)
+            | Event::Wheel(_)
+            | Event::KeyDown(_)
+            | Event::KeyUp(_)
+            | Event::Zoom(_)
+            | Event::Gesture(_)
+    )
+}
+
+/// Format `event` as a line of a recording, without the newline.
+fn format_line(time: Duration, event: &Event) -> Option<String> {
+    let mut line = format!("{:.6} ", time.as_secs_f64());
+    let result = match event {
+        Event::Size(size) => write!(line, "size {} {}", size.width, size.height),
+        Event::MouseDown(mouse) => write_mouse(&mut line, "mouse-down", mouse),
+        Event::MouseUp(mouse) => write_mouse(&mut line, "mouse-up", mouse),
+        Event::MouseMoved(mouse) => write_mouse(&mut line, "mouse-move", mouse),
+        Event::Wheel(wheel) => write!(
+            line,
+            "wheel {} {} {}",
+            wheel.delta.x,
+            wheel.delta.y,
+            Mods(wheel.mods)
+        ),
+        Event::KeyDown(key) => write_key(&mut line, "key-down", key),
+        Event::KeyUp(key) => write_key(&mut line, "key-up", key),
+        Event::Zoom(delta) => write!(line, "zoom {}", delta),
+        Event::Gesture(Gesture::Pinch(pinch)) => write!(
+            line,
+            "pinch {} {} {}",
+            pinch.scale, pinch.center.x, pinch.center.y
+        ),
+        Event::Gesture(Gesture::Pan(pan)) => write!(
+            line,
+            "pan {} {} {} {}",
+            pan.delta.x, pan.delta.y, pan.center.x, pan.center.y
+        ),
+        _ => return None,
+    };
+    result.ok().map(|_| line)
+}
+
+fn write_mouse(line: &mut String, kind: &str, mouse: &MouseEvent) -> fmt::Result {
+    let button = match mouse.button {
+        MouseButton::Left => "left",
+        MouseButton::Middle => "middle",
+        MouseButton::Right => "right",
+        MouseButton::X1 => "x1",
+        MouseButton::X2 => "x2",
+    };
+    write!(
+        line,
+        "{} {} {} {} {} {}",
+        kind,
+        mouse.window_pos.x,
+        mouse.window_pos.y,
+        button,
+        mouse.count,
+        Mods(mouse.mods)
+    )
+}
+
+fn write_key(line: &mut String, kind: &str, key: &KeyEvent) -> fmt::Result {
+    write!(
+        line,
+        "{} {:?} {} {:?} {:?}",
+        kind,
+        key.key_code,
+        Mods(key.mods),
+        key.text().unwrap_or(""),
+        key.unmod_text().unwrap_or("")
+    )?;
+    if key.is_repeat {
+        line.push_str(" repeat");
+    }
+    Ok(())
+}
+
+struct Mods(KeyModifiers);
+
+impl fmt::Display for Mods {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names = [
+            (self.0.shift, "shift"),
+            (self.0.alt, "alt"),
+            (self.0.ctrl, "ctrl"),
+            (self.0.meta, "meta"),
+        ];
+        let mut any = false;
+        for (_, name) in names.iter().filter(|(set, _)| *set) {
+            if any {
+                f.write_str("+")?;
+            }
+            f.write_str(name)?;
+            any = true;
+        }
+        if !any {
+            f.write_str("-")?;
+        }
+        Ok(())
+    }
+}
+
+fn parse_line(line: &str) -> Result<RecordedEvent, String> {
+    let mut fields = tokenize(line)?.into_iter();
+
+    let time: f64 = parse_num(&field(&mut fields, "time")?, "time")?;
+    if !time.is_finite() || time < 0.0 {
+        return Err(format!("invalid time {}", time));
+    }
+    let time = Duration::from_secs_f64(time);
+
+    let kind = field(&mut fields, "event kind")?;
+    let event = match kind.as_str() {
+        "size" => {
+            let width = parse_num(&field(&mut fields, "width")?, "width")?;
+            let height = parse_num(&field(&mut fields, "height")?, "height")?;
+            Event::Size(Size::new(width, height))
+        }
+        "mouse-down" | "mouse-up" | "mouse-move" => {
+            let x = parse_num(&field(&mut fields, "x")?, "x")?;
+            let y = parse_num(&field(&mut fields, "y")?, "y")?;
+            let button = match field(&mut fields, "button")?.as_str() {
+                "left" => MouseButton::Left,
+                "middle" => MouseButton::Middle,
+                "right" => MouseButton::Right,
+                "x1" => MouseButton::X1,
+                "x2" => MouseButton::X2,
+                other => return Err(format!("unknown mouse button '{}'", other)),
+            };
+            let count = parse_num(&field(&mut fields, "count")?, "count")?;
+            let mods = parse_mods(&field(&mut fields, "modifiers")?)?;
+            let mouse = MouseEvent {
+                pos: Point::new(x, y),
+                window_pos: Point::new(x, y),
+                mods,
+                count,
+                button,
+                // recognized again when the event is replayed.
+                gesture: MouseGesture::Hover,
+            };
+            match kind.as_str() {
+                "mouse-down" => Event::MouseDown(mouse),
+                "mouse-up" => Event::MouseUp(mouse),
+                _ => Event::MouseMoved(mouse),
+            }
+        }
+        "wheel" => {
+            let dx = parse_num(&field(&mut fields, "dx")?, "dx")?;
+            let dy = parse_num(&field(&mut fields, "dy")?, "dy")?;
+            let mods = parse_mods(&field(&mut fields, "modifiers")?)?;
+            Event::Wheel(WheelEvent {
+                delta: Vec2::new(dx, dy),
+                mods,
+            })
+        }
+        "key-down" | "key-up" => {
+            let code = field(&mut fields, "key code")?;
+            let key_code =
+                KeyCode::from_name(&code).ok_or_else(|| format!("unknown key code '{}'", code))?;
+            let mods = parse_mods(&field(&mut fields, "modifiers")?)?;
+            let text = field(&mut fields, "text")?;
+            let unmod_text = field(&mut fields, "unmodified text")?;
+            let is_repeat = match fields.next().as_deref() {
+                None => false,
+                Some("repeat") => true,
+                Some(other) => return Err(format!("unexpected '{}'", other)),
+            };
+            let key = KeyEvent::from_parts(key_code, is_repeat, mods, &text, &unmod_text);
+            if kind == "key-down" {
+                Event::KeyDown(key)
+            } else {
+                Event::KeyUp(key)
+            }
+        }
+        "zoom" => Event::Zoom(parse_num(&field(&mut fields, "delta")?, "delta")?),
+        "pinch" => {
+            let scale = parse_num(&field(&mut fields, "scale")?, "scale")?;
+            let x = parse_num(&field(&mut fields, "x")?, "x")?;
+            let y = parse_num(&field(&mut fields, "y")?, "y")?;
+            Event::Gesture(Gesture::Pinch(GesturePinch {
+                scale,
+                center: Point::new(x, y),
+            }))
+        }
+        "pan" => {
+            let dx = parse_num(&field(&mut fields, "dx")?, "dx")?;
+            let dy = parse_num(&field(&mut fields, "dy")?, "dy")?;
+            let x = parse_num(&field(&mut fields, "x")?, "x")?;
+            let y = parse_num(&field(&mut fields, "y")?, "y")?;
+            Event::Gesture(Gesture::Pan(GesturePan {
+                delta: Vec2::new(dx, dy),
+                center: Point::new(x, y),
+            }))
+        }
+        other => return Err(format!("unknown event kind '{}'", other)),
+    };
+
+    if let Some(extra) = fields.next() {
+        return Err(format!("unexpected '{}'", extra));
+    }
+    Ok(RecordedEvent { time, event })
+}
+
+fn field(fields: &mut impl Iterator<Item = String>, what: &str) -> Result<String, String> {
+    fields.next().ok_or_else(|| format!("missing {}", what))
+}
+
+fn parse_num<N: FromStr>(field: &str, what: &str) -> Result<N, String> {
+    field
+        .parse()
+        .map_err(|_| format!("invalid {} '{}'", what, field))
+}
+
+fn parse_mods(field: &str) -> Result<KeyModifiers, String> {
+    let mut mods = KeyModifiers::default();
+    if field == "-" {
+        return Ok(mods);
+    }
+    for name in field.split('+') {
+        match name {
+            "shift" => mods.shift = true,
+            "alt" => mods.alt = true,
+            "ctrl" => mods.ctrl = true,
+            "meta" => mods.meta = true,
+            other => return Err(format!("unknown modifier '{}'", other)),
+        }
+    }
+    Ok(mods)
+}
+
+/// Split a line into fields at whitespace. Quoted fields may contain
+/// whitespace, and are unescaped.
+fn tokenize(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.peek().map(|c| c.is_whitespace()).unwrap_or(false) {
+            chars.next();
+        }
+        match chars.peek() {
+            None => return Ok(fields),
+            Some('"') => {
+                chars.next();
+                fields.push(unescape(&mut chars)?);
+            }
+            Some(_) => {
+                let mut field = String::new();
+                while let Some(c) = chars.peek().filter(|c| !c.is_whitespace()) {
+                    field.push(*c);
+                    chars.next();
+                }
+                fields.push(field);
+            }
+        }
+    }
+}
+
+/// Read the rest of a quoted string, up to and including its closing quote.
+fn unescape(chars: &mut impl Iterator<Item = char>) -> Result<String, String> {
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            None => return Err("unterminated string".into()),
+            Some('"') => return Ok(text),
+            Some('\\') => {
+                let c = match chars.next() {
+                    Some('n') => '\n',
+                    Some('r') => '\r',
+                    Some('t') => '\t',
+                    Some('0') => '\0',
+                    Some(c @ '\\') | Some(c @ '"') | Some(c @ '\'') => c,
+                    Some('u') => {
+                        if chars.next() != Some('{') {
+                            return Err("invalid unicode escape".into());
+                        }
+                        let hex: String = chars.by_ref().take_while(|c| *c != '}').collect();
+                        u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(std::char::from_u32)
+                            .ok_or_else(|| format!("invalid unicode escape '{}'", hex))?
+                    }
+                    _ => return Err("invalid escape".into()),
+                };
+                text.push(c);
+            }
+            Some(c) => text.push(c),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::RawMods;
+
+    #[test]
+    fn round_trip() {
+        let mut log = EventLog::new();
+        let mouse = MouseEvent {
+            pos: Point::new(10.5, 20.0),
+            window_pos: Point::new(10.5, 20.0),
+            mods: RawMods::Shift.into(),
+            count: 2,
+            button: MouseButton::Right,
+            gesture: MouseGesture::Press,
+        };
+        let key = KeyEvent::from_parts(KeyCode::Space, true, RawMods::None.into(), " ", "\"");
+        assert!(log.push(Duration::from_millis(0), &Event::Size(Size::new(80., 60.))));
+        assert!(log.push(Duration::from_millis(250), &Event::MouseDown(mouse)));
+        assert!(log.push(Duration::from_millis(1500), &Event::KeyDown(key)));
+        let pan = GesturePan {
+            delta: Vec2::new(4., -2.),
+            center: Point::new(20., 30.5),
+        };
+        assert!(log.push(
+            Duration::from_millis(1600),
+            &Event::Gesture(Gesture::Pan(pan))
+        ));
+        assert!(!log.push(Duration::from_secs(2), &Event::WindowConnected));
+
+        let text = log.to_string();
+        let parsed = EventLog::parse(&text).unwrap();
+        assert_eq!(parsed.to_string(), text);
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(parsed.events()[2].time, Duration::from_millis(1500));
+        match &parsed.events()[1].event {
+            Event::MouseDown(mouse) => {
+                assert_eq!(mouse.pos, Point::new(10.5, 20.0));
+                assert_eq!(mouse.count, 2);
+                assert_eq!(mouse.button, MouseButton::Right);
+                assert!(mouse.mods.shift && !mouse.mods.ctrl);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        match &parsed.events()[2].event {
+            Event::KeyDown(key) => {
+                assert_eq!(key.key_code, KeyCode::Space);
+                assert!(key.is_repeat);
+                assert_eq!(key.text(), Some(" "));
+                assert_eq!(key.unmod_text(), Some("\""));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        match &parsed.events()[3].event {
+            Event::Gesture(Gesture::Pan(parsed_pan)) => assert_eq!(*parsed_pan, pan),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parse_errors_name_the_line() {
+        let text = "druid-events 1\n# a comment\n\n0.1 mouse-down 1 2 left 1 -\n0.2 jump\n";
+        let err = EventLog::parse(text).unwrap_err();
+        assert_eq!(err.line, 5);
+        assert!(EventLog::parse("0.1 zoom 1").is_err());
+        assert!(EventLog::parse("druid-events 2\n").is_err());
+    }
+}
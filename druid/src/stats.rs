@@ -0,0 +1,133 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Timing information about the most recent frame painted in each window.
+//!
+//! Stats are always collected; the cost is a few calls to [`Instant::now`]
+//! per frame. To show them in the window itself, set [`Env::SHOW_FRAME_STATS`]
+//! to `true`, for instance with [`AppLauncher::configure_env`].
+//!
+//! [`Instant::now`]: https://doc.rust-lang.org/std/time/struct.Instant.html#method.now
+//! [`Env::SHOW_FRAME_STATS`]: ../struct.Env.html#associatedconstant.SHOW_FRAME_STATS
+//! [`AppLauncher::configure_env`]: ../struct.AppLauncher.html#method.configure_env
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::kurbo::{Point, Rect};
+use crate::piet::{Color, FontBuilder, Piet, RenderContext, Text, TextLayout, TextLayoutBuilder};
+use crate::{theme, Env, WindowId};
+
+const OVERLAY_TEXT_SIZE: f64 = 12.0;
+const OVERLAY_PADDING: f64 = 4.0;
+
+thread_local! {
+    static FRAME_STATS: RefCell<HashMap<WindowId, FrameStats>> = RefCell::new(HashMap::new());
+}
+
+/// Measurements taken while painting a single frame.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameStats {
+    /// The total time spent handling the paint request, including
+    /// animation, layout, and paint.
+    pub frame_time: Duration,
+    /// The time spent in layout. This is zero if no layout was needed.
+    pub layout_time: Duration,
+    /// The time spent in paint.
+    pub paint_time: Duration,
+    /// The time since the previous frame started, if there was one.
+    pub frame_interval: Option<Duration>,
+    /// The number of widgets in the window.
+    pub widget_count: usize,
+}
+
+impl FrameStats {
+    /// The frame rate implied by [`frame_interval`], in frames per second.
+    ///
+    /// [`frame_interval`]: #structfield.frame_interval
+    pub fn fps(&self) -> Option<f64> {
+        self.frame_interval
+            .map(|d| d.as_secs_f64())
+            .filter(|secs| *secs > 0.)
+            .map(|secs| secs.recip())
+    }
+
+    fn summary(&self) -> String {
+        let fps = match self.fps() {
+            Some(fps) => format!("{:.0} fps", fps),
+            None => "-- fps".to_string(),
+        };
+        format!(
+            "{}  frame {:.2}ms  layout {:.2}ms  paint {:.2}ms  {} widgets",
+            fps,
+            millis(self.frame_time),
+            millis(self.layout_time),
+            millis(self.paint_time),
+            self.widget_count
+        )
+    }
+}
+
+/// Returns the stats for the most recent frame painted in the given window.
+///
+/// Returns `None` if the window does not exist or has not been painted yet.
+/// This must be called from the main thread.
+pub fn frame_stats(window: WindowId) -> Option<FrameStats> {
+    FRAME_STATS.with(|stats| stats.borrow().get(&window).copied())
+}
+
+pub(crate) fn record(window: WindowId, frame: FrameStats) {
+    FRAME_STATS.with(|stats| stats.borrow_mut().insert(window, frame));
+}
+
+pub(crate) fn remove(window: WindowId) {
+    // this can be called during thread teardown, when the thread-local
+    // may already be gone.
+    let _ = FRAME_STATS.try_with(|stats| stats.borrow_mut().remove(&window));
+}
+
+/// Paint a summary of `frame` in the top left corner of the window.
+pub(crate) fn paint_overlay(piet: &mut Piet, frame: &FrameStats, env: &Env) {
+    let font_name = env.get(theme::FONT_NAME);
+    let text = frame.summary();
+    let layout = match piet
+        .text()
+        .new_font_by_name(font_name, OVERLAY_TEXT_SIZE)
+        .build()
+        .and_then(|font| piet.text().new_text_layout(&font, &text).build())
+    {
+        Ok(layout) => layout,
+        Err(e) => {
+            log::warn!(target: "druid::paint", "failed to lay out frame stats: {}", e);
+            return;
+        }
+    };
+
+    let width = layout.width();
+    let height = OVERLAY_TEXT_SIZE * 1.2;
+    let rect = Rect::new(
+        0.,
+        0.,
+        width + OVERLAY_PADDING * 2.,
+        height + OVERLAY_PADDING * 2.,
+    );
+    piet.fill(rect, &Color::rgba8(0, 0, 0, 0xc0));
+    let origin = Point::new(OVERLAY_PADDING, OVERLAY_PADDING + OVERLAY_TEXT_SIZE);
+    piet.draw_text(&layout, origin, &Color::WHITE);
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.
+}
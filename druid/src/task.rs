@@ -0,0 +1,401 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Running work on other threads, and delivering its results as commands.
+
+use std::any::Any;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::Duration;
+
+use crate::{ExtEventSink, Selector, Target};
+
+/// Something that runs futures, for [`EventCtx::spawn_future`].
+///
+/// druid doesn't have an async runtime of its own; this is how you connect
+/// it to the one your application uses, with [`AppLauncher::with_executor`].
+/// It is implemented for closures, so with tokio this could be:
+///
+/// ```ignore
+/// let runtime = tokio::runtime::Runtime::new()?;
+/// let handle = runtime.handle().clone();
+/// AppLauncher::with_window(window).with_executor(move |future| {
+///     handle.spawn(future);
+/// });
+/// ```
+///
+/// Without an executor, each future is run on a thread from a pool like the
+/// one that runs the tasks started with [`EventCtx::spawn`], and the thread
+/// sleeps while the future waits. This is simple, but a future that waits for a long time holds on
+/// to its thread; with druid's `futures-executor` feature the default is
+/// instead the `futures` crate's thread pool, on which waiting futures don't
+/// need a thread each.
+///
+/// [`EventCtx::spawn`]: struct.EventCtx.html#method.spawn
+/// [`EventCtx::spawn_future`]: struct.EventCtx.html#method.spawn_future
+/// [`AppLauncher::with_executor`]: struct.AppLauncher.html#method.with_executor
+pub trait Executor {
+    /// Start running `future`.
+    ///
+    /// This is called on the UI thread, and should return right away.
+    fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>);
+}
+
+impl<F: Fn(Pin<Box<dyn Future<Output = ()> + Send>>)> Executor for F {
+    fn spawn(&self, future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+        (self)(future)
+    }
+}
+
+/// A handle to a task started with [`EventCtx::spawn`] or
+/// [`EventCtx::spawn_future`].
+///
+/// [`EventCtx::spawn`]: struct.EventCtx.html#method.spawn
+/// [`EventCtx::spawn_future`]: struct.EventCtx.html#method.spawn_future
+#[derive(Debug, Clone)]
+pub struct TaskHandle {
+    cancelled: Arc<AtomicBool>,
+    /// Wakes a future, so that it sees it was cancelled.
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+/// What a task started with [`EventCtx::spawn`] is given, to report its
+/// progress and to find out if it has been cancelled.
+///
+/// [`EventCtx::spawn`]: struct.EventCtx.html#method.spawn
+pub struct TaskCtx<P> {
+    sink: ExtEventSink,
+    progress: Selector,
+    target: Target,
+    cancelled: Arc<AtomicBool>,
+    // `fn(P)` so that we are `Send` whatever `P` is.
+    progress_type: PhantomData<fn(P)>,
+}
+
+/// The tasks started by the widgets in a window.
+///
+/// They are all cancelled when this is dropped, with the window.
+#[derive(Default)]
+pub(crate) struct TaskHost {
+    sink: Option<ExtEventSink>,
+    executor: Option<Rc<dyn Executor>>,
+    pool: ThreadPool,
+    running: Vec<TaskHandle>,
+}
+
+/// How long a pool thread waits for another task before it exits.
+const IDLE_THREAD_TIMEOUT: Duration = Duration::from_secs(10);
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// The threads that run the tasks started with `spawn`.
+///
+/// A task is given a thread that has finished its last one, if there is
+/// one, and a new thread otherwise, so that a long task never holds up
+/// another; threads exit once they have been idle for a while.
+#[derive(Clone, Default)]
+struct ThreadPool(Arc<Pool>);
+
+#[derive(Default)]
+struct Pool {
+    jobs: Mutex<Jobs>,
+    job_added: Condvar,
+}
+
+#[derive(Default)]
+struct Jobs {
+    queue: VecDeque<Job>,
+    /// The number of threads waiting for a job.
+    idle: usize,
+}
+
+/// A future that finishes early, with `None`, once it is cancelled.
+struct Cancellable<R> {
+    future: Pin<Box<dyn Future<Output = R> + Send>>,
+    cancelled: Arc<AtomicBool>,
+    waker: Arc<Mutex<Option<Waker>>>,
+}
+
+impl<R> Future for Cancellable<R> {
+    type Output = Option<R>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<R>> {
+        // stored before checking, so that a cancel after the check wakes us.
+        *self.waker.lock().unwrap() = Some(cx.waker().clone());
+        if self.cancelled.load(Ordering::SeqCst) {
+            return Poll::Ready(None);
+        }
+        self.future.as_mut().poll(cx).map(Some)
+    }
+}
+
+/// The executor for apps that don't set one: the `futures` thread pool.
+#[cfg(feature = "futures-executor")]
+pub(crate) fn default_executor() -> Option<Rc<dyn Executor>> {
+    match futures_executor::ThreadPool::new() {
+        Ok(pool) => Some(Rc::new(move |future| pool.spawn_ok(future))),
+        Err(e) => {
+            log::error!(target: "druid::task", "failed to start the thread pool: {}", e);
+            None
+        }
+    }
+}
+
+/// Without the `futures-executor` feature, each future is run to completion
+/// on a thread from a pool like the one tasks run on.
+#[cfg(not(feature = "futures-executor"))]
+pub(crate) fn default_executor() -> Option<Rc<dyn Executor>> {
+    let pool = ThreadPool::default();
+    Some(Rc::new(move |future| {
+        pool.execute(move || block_on(future))
+    }))
+}
+
+/// Run `future` on this thread until it completes, sleeping while it waits.
+#[cfg(not(feature = "futures-executor"))]
+fn block_on(mut future: Pin<Box<dyn Future<Output = ()> + Send>>) {
+    let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    // a spurious wakeup just polls again.
+    while future.as_mut().poll(&mut cx).is_pending() {
+        thread::park();
+    }
+}
+
+/// Wakes the future `block_on` is running, by unparking its thread.
+#[cfg(not(feature = "futures-executor"))]
+struct ThreadWaker(thread::Thread);
+
+#[cfg(not(feature = "futures-executor"))]
+impl std::task::Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+
+    fn wake_by_ref(self: &Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+impl TaskHandle {
+    /// Ask the task to stop.
+    ///
+    /// Nothing it has reported that hasn't been delivered yet is delivered,
+    /// nor is its result. A thread keeps running until the task notices,
+    /// with [`TaskCtx::is_cancelled`]; a future is woken, and dropped when
+    /// its executor next polls it.
+    ///
+    /// [`TaskCtx::is_cancelled`]: struct.TaskCtx.html#method.is_cancelled
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+        if let Some(waker) = self.waker.lock().unwrap().take() {
+            waker.wake();
+        }
+    }
+
+    /// `true` if the task has been cancelled, either with [`cancel`] or
+    /// because its window closed.
+    ///
+    /// [`cancel`]: #method.cancel
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl<P: Any + Send> TaskCtx<P> {
+    /// Send `progress` to the widget that started the task.
+    ///
+    /// It arrives as a command with the task's progress selector, and
+    /// `progress` as the argument.
+    pub fn report(&self, progress: P) {
+        if !self.is_cancelled() {
+            let _ =
+                self.sink
+                    .submit_for_task(self.progress, progress, self.target, &self.cancelled);
+        }
+    }
+
+    /// `true` once the task should stop. A long task should check this
+    /// regularly.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+impl TaskHost {
+    pub(crate) fn set_sink(&mut self, sink: ExtEventSink) {
+        self.sink = Some(sink);
+    }
+
+    pub(crate) fn set_executor(&mut self, executor: Option<Rc<dyn Executor>>) {
+        self.executor = executor;
+    }
+
+    /// Start keeping track of a new task, returning its handle and where
+    /// it should send its commands.
+    fn start(&mut self) -> (TaskHandle, Option<ExtEventSink>) {
+        let handle = TaskHandle {
+            cancelled: Arc::new(AtomicBool::new(false)),
+            waker: Arc::new(Mutex::new(None)),
+        };
+        let sink = match self.sink.as_ref() {
+            Some(sink) => sink.clone(),
+            None => {
+                log::warn!(
+                    target: "druid::window",
+                    "can't run a task in a window that isn't connected"
+                );
+                handle.cancel();
+                return (handle, None);
+            }
+        };
+
+        // a running task holds the only other reference until it finishes.
+        self.running
+            .retain(|task| Arc::strong_count(&task.cancelled) > 1);
+        self.running.push(handle.clone());
+        (handle, Some(sink))
+    }
+
+    pub(crate) fn spawn<P, R>(
+        &mut self,
+        target: Target,
+        progress: Selector,
+        done: Selector,
+        task: impl FnOnce(&TaskCtx<P>) -> R + Send + 'static,
+    ) -> TaskHandle
+    where
+        P: Any + Send,
+        R: Any + Send,
+    {
+        let (handle, sink) = match self.start() {
+            (handle, Some(sink)) => (handle, sink),
+            (handle, None) => return handle,
+        };
+
+        let ctx = TaskCtx {
+            sink,
+            progress,
+            target,
+            cancelled: handle.cancelled.clone(),
+            progress_type: PhantomData,
+        };
+        self.pool.execute(move || {
+            let result = task(&ctx);
+            if !ctx.is_cancelled() {
+                let _ = ctx
+                    .sink
+                    .submit_for_task(done, result, target, &ctx.cancelled);
+            }
+        });
+        handle
+    }
+
+    pub(crate) fn spawn_future<R: Any + Send>(
+        &mut self,
+        target: Target,
+        done: Selector,
+        future: impl Future<Output = R> + Send + 'static,
+    ) -> TaskHandle {
+        let (handle, sink) = match self.start() {
+            (handle, Some(sink)) => (handle, sink),
+            (handle, None) => return handle,
+        };
+
+        if self.executor.is_none() {
+            self.executor = default_executor();
+        }
+        let executor = match self.executor.as_ref() {
+            Some(executor) => executor,
+            None => {
+                log::warn!(
+                    target: "druid::task",
+                    "there is no executor to run the future; set one with \
+                     AppLauncher::with_executor"
+                );
+                handle.cancel();
+                return handle;
+            }
+        };
+
+        let cancelled = handle.cancelled.clone();
+        let future = Cancellable {
+            future: Box::pin(future),
+            cancelled: cancelled.clone(),
+            waker: handle.waker.clone(),
+        };
+        let future = Box::pin(async move {
+            if let Some(result) = future.await {
+                if !cancelled.load(Ordering::SeqCst) {
+                    let _ = sink.submit_for_task(done, result, target, &cancelled);
+                }
+            }
+        });
+        executor.spawn(future);
+        handle
+    }
+}
+
+impl ThreadPool {
+    fn execute(&self, job: impl FnOnce() + Send + 'static) {
+        let mut jobs = self.0.jobs.lock().unwrap();
+        jobs.queue.push_back(Box::new(job));
+        if jobs.queue.len() > jobs.idle {
+            let pool = self.0.clone();
+            thread::spawn(move || pool.work());
+        } else {
+            self.0.job_added.notify_one();
+        }
+    }
+}
+
+impl Pool {
+    /// Run jobs until there have been none for a while.
+    fn work(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        loop {
+            if let Some(job) = jobs.queue.pop_front() {
+                drop(jobs);
+                job();
+                jobs = self.jobs.lock().unwrap();
+                continue;
+            }
+            jobs.idle += 1;
+            let (guard, wait) = self
+                .job_added
+                .wait_timeout(jobs, IDLE_THREAD_TIMEOUT)
+                .unwrap();
+            jobs = guard;
+            jobs.idle -= 1;
+            if wait.timed_out() && jobs.queue.is_empty() {
+                return;
+            }
+        }
+    }
+}
+
+impl Drop for TaskHost {
+    fn drop(&mut self) {
+        for task in &self.running {
+            task.cancel();
+        }
+    }
+}
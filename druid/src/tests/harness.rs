@@ -13,8 +13,21 @@
 // limitations under the License.
 
 //! Tools and infrastructure for testing widgets.
-use crate::core::{BaseState, CommandQueue};
-use crate::piet::{BitmapTarget, Device, Piet};
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::clock::Clock;
+#[cfg(test)]
+use crate::core::BaseState;
+use crate::core::CommandQueue;
+use crate::ext_event::ExtEventHost;
+use crate::inspect::WidgetTree;
+use crate::piet::{BitmapTarget, Device, ImageFormat, Piet};
+use crate::recording::{EventLog, EventRecorder};
+use crate::stats::FrameStats;
+use crate::tests::Snapshot;
 use crate::*;
 
 pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
@@ -37,14 +50,69 @@ pub(crate) const DEFAULT_SIZE: Size = Size::new(400., 400.);
 /// because paint is triggered by druid-shell, and there is no druid-shell here;
 ///
 /// if you want those functions run you will need to call them yourself.
+/// This also makes the harness useful for benchmarking: after a call to
+/// [`paint`], [`frame_stats`] reports how long layout and paint took, and
+/// [`layout_with_constraints`] lets you measure layout in isolation.
+///
+/// The harness has its own clock, which only moves when you call
+/// [`advance_time`]. Widgets see it through [`EventCtx::now`], and timers
+/// requested with [`EventCtx::request_timer`] fire when it passes their
+/// deadline. Animation frames sent by [`paint`] are timed with it as well.
+///
+/// Input can be simulated with methods like [`click`] and [`key_down`]; to
+/// find out where a widget ended up, use [`widget_rect`].
+///
+/// To check what happened during a test, the harness keeps a list of the
+/// commands submitted by widgets (see [`assert_command_submitted`]) and of
+/// the widgets painted by the last paint (see [`paint_log`]), and you can
+/// wrap any widget in a [`Recorder`] to see the calls it received.
 ///
-/// Also, timers don't work.  ¯\_(ツ)_/¯
+/// # Examples
+///
+/// ```no_run
+/// use druid::tests::Harness;
+/// use druid::widget::Label;
+///
+/// Harness::create((), Label::new("hello"), |harness| {
+///     harness.send_initial_events();
+///     harness.paint();
+///     let stats = harness.frame_stats();
+///     println!("layout took {:?}", stats.layout_time);
+/// });
+/// ```
+///
+/// [`paint`]: #method.paint
+/// [`frame_stats`]: #method.frame_stats
+/// [`layout_with_constraints`]: #method.layout_with_constraints
+/// [`EventCtx::now`]: ../struct.EventCtx.html#method.now
+/// [`EventCtx::request_timer`]: ../struct.EventCtx.html#method.request_timer
+/// [`advance_time`]: #method.advance_time
+/// [`click`]: #method.click
+/// [`key_down`]: #method.key_down
+/// [`widget_rect`]: #method.widget_rect
+/// [`assert_command_submitted`]: #method.assert_command_submitted
+/// [`paint_log`]: #method.paint_log
+/// [`Recorder`]: struct.Recorder.html
 pub struct Harness<'a, T> {
     piet: Piet<'a>,
     inner: Inner<T>,
     window_size: Size,
 }
 
+/// A widget that painted during a paint pass of a [`Harness`].
+///
+/// [`Harness`]: struct.Harness.html
+#[derive(Debug, Clone)]
+pub struct PaintedWidget {
+    pub id: WidgetId,
+    /// The widget's [`type_name`].
+    ///
+    /// [`type_name`]: ../trait.Widget.html#method.type_name
+    pub type_name: &'static str,
+    /// The widget's bounds, in window coordinates.
+    pub rect: Rect,
+}
+
 /// All of the state except for the `Piet` (render context). We need to pass
 /// that in to get around some lifetime issues.
 struct Inner<T> {
@@ -52,6 +120,21 @@ struct Inner<T> {
     env: Env,
     window: Window<T>,
     cmds: CommandQueue,
+    /// The harness's clock, which the window also uses.
+    timers: Rc<RefCell<MockTimerQueue>>,
+    /// Every command that has been dispatched, in order.
+    submitted: Vec<(Target, Command)>,
+    /// Where commands from other threads arrive.
+    ext_host: ExtEventHost,
+}
+
+/// The clock, and the timers requested by widgets, in a `Harness`.
+///
+/// The clock starts out at the real time when the harness is created, and
+/// after that only moves forward when the test asks it to.
+struct MockTimerQueue {
+    now: Instant,
+    pending: Vec<(Instant, TimerToken)>,
 }
 
 /// A way to clean up resources when our target goes out of scope.
@@ -72,13 +155,22 @@ impl<T: Data> Harness<'_, T> {
         let piet = target.0.as_mut().unwrap().render_context();
 
         let desc = WindowDesc::new(|| root);
-        let window = Window::new(WindowId::next(), Default::default(), desc);
+        let mut window = Window::new(WindowId::next(), Default::default(), desc);
+        let timers = Rc::new(RefCell::new(MockTimerQueue::new()));
+        window.clock = Some(timers.clone());
+        window.paint_log = Some(Vec::new());
+        window.mock_clipboard = Some(RefCell::new(None));
+        let ext_host = ExtEventHost::new();
+        window.tasks.set_sink(ext_host.make_sink());
 
         let inner = Inner {
             data,
             env: theme::init(),
             window,
             cmds: Default::default(),
+            timers,
+            submitted: Vec::new(),
+            ext_host,
         };
 
         let mut harness = Harness {
@@ -95,21 +187,69 @@ impl<T: Data> Harness<'_, T> {
         self.window_size = size;
     }
 
+    /// The window that hosts the widget.
     pub fn window(&self) -> &Window<T> {
         &self.inner.window
     }
 
-    #[allow(dead_code)]
+    /// The window that hosts the widget.
     pub fn window_mut(&mut self) -> &mut Window<T> {
         &mut self.inner.window
     }
 
-    #[allow(dead_code)]
+    /// The current data.
     pub fn data(&self) -> &T {
         &self.inner.data
     }
 
+    /// The commands that have been submitted by widgets, and their targets,
+    /// in the order they were dispatched.
+    ///
+    /// This does not include commands sent with [`submit_command`].
+    ///
+    /// [`submit_command`]: #method.submit_command
+    pub fn submitted_commands(&self) -> &[(Target, Command)] {
+        &self.inner.submitted
+    }
+
+    /// Forget about any previously submitted commands.
+    pub fn clear_submitted_commands(&mut self) {
+        self.inner.submitted.clear();
+    }
+
+    /// Panic if no command with this `selector` has been submitted.
+    pub fn assert_command_submitted(&self, selector: Selector) {
+        if !self
+            .inner
+            .submitted
+            .iter()
+            .any(|(_, cmd)| cmd.selector == selector)
+        {
+            let seen: Vec<_> = self
+                .inner
+                .submitted
+                .iter()
+                .map(|(_, c)| &c.selector)
+                .collect();
+            panic!("command {} was not submitted; saw {:?}", selector, seen);
+        }
+    }
+
+    /// Replace the `Env`, and run an update pass so that widgets see it.
+    pub fn set_env(&mut self, env: Env) {
+        self.inner.env = env;
+        self.update();
+    }
+
+    /// Timing information for the most recent call to [`paint`].
+    ///
+    /// [`paint`]: #method.paint
+    pub fn frame_stats(&self) -> FrameStats {
+        self.inner.window.frame_stats
+    }
+
     /// Retrieve a copy of this widget's `BaseState`, or die trying.
+    #[cfg(test)]
     pub(crate) fn get_state(&mut self, widget: WidgetId) -> BaseState {
         match self.try_get_state(widget) {
             Some(thing) => thing,
@@ -118,6 +258,7 @@ impl<T: Data> Harness<'_, T> {
     }
 
     /// Attempt to retrieve a copy of this widget's `BaseState`.
+    #[cfg(test)]
     pub(crate) fn try_get_state(&mut self, widget: WidgetId) -> Option<BaseState> {
         let cell = StateCell::default();
         let state_cell = cell.clone();
@@ -125,9 +266,20 @@ impl<T: Data> Harness<'_, T> {
         cell.take()
     }
 
+    /// The layout rect of the widget with this id, in window coordinates.
+    ///
+    /// Returns `None` if the widget is not in the tree. This is only
+    /// meaningful after layout, and does not account for scrolling.
+    pub fn widget_rect(&mut self, widget: WidgetId) -> Option<Rect> {
+        let tree = self.widget_tree();
+        let info = tree.find(widget)?;
+        Some(Rect::from_origin_size(info.window_origin, info.size))
+    }
+
     /// Inspect the `BaseState` of each widget in the tree.
     ///
     /// The provided closure will be called on each widget.
+    #[cfg(test)]
     pub(crate) fn inspect_state(&mut self, f: impl Fn(&BaseState) + 'static) {
         let checkfn = StateCheckFn::new(f);
         self.lifecycle(LifeCycle::DebugInspectState(checkfn))
@@ -136,10 +288,63 @@ impl<T: Data> Harness<'_, T> {
     /// Send a command to a target.
     pub fn submit_command(&mut self, cmd: impl Into<Command>, target: impl Into<Option<Target>>) {
         let target = target.into().unwrap_or_else(|| self.inner.window.id.into());
-        let event = Event::TargetedCommand(target, cmd.into());
+        let event = Event::TargetedCommand(target, cmd.into().delivered_to(target));
         self.event(event);
     }
 
+    /// Move the mouse to `pos`, in window coordinates.
+    pub fn mouse_move(&mut self, pos: impl Into<Point>) {
+        self.event(Event::MouseMoved(mouse_event(pos.into(), 0)));
+    }
+
+    /// Press the left mouse button at `pos`, in window coordinates.
+    pub fn mouse_down(&mut self, pos: impl Into<Point>) {
+        self.event(Event::MouseDown(mouse_event(pos.into(), 1)));
+    }
+
+    /// Release the left mouse button at `pos`, in window coordinates.
+    pub fn mouse_up(&mut self, pos: impl Into<Point>) {
+        self.event(Event::MouseUp(mouse_event(pos.into(), 0)));
+    }
+
+    /// Move the mouse to `pos`, and click the left button there.
+    ///
+    /// The events are routed through the widget tree using the current
+    /// layout, just as they would be in a real window.
+    pub fn click(&mut self, pos: impl Into<Point>) {
+        let pos = pos.into();
+        self.mouse_move(pos);
+        self.mouse_down(pos);
+        self.mouse_up(pos);
+    }
+
+    /// Click in the center of the part of the widget with this id that can
+    /// be seen, taking scrolling and transforms into account.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the widget is not in the tree.
+    pub fn click_widget(&mut self, widget: WidgetId) {
+        match self.widget_tree().rect_of(widget) {
+            Some(rect) => self.click(rect.center()),
+            None => panic!("click_widget failed for widget {:?}", widget),
+        }
+    }
+
+    /// Send a key down event, which will go to the focused widget.
+    ///
+    /// Use [`KeyEvent::for_test`] to construct the event.
+    ///
+    /// [`KeyEvent::for_test`]: ../struct.KeyEvent.html#method.for_test
+    pub fn key_down(&mut self, event: KeyEvent) {
+        self.event(Event::KeyDown(event));
+    }
+
+    /// Send a key up event, which will go to the focused widget.
+    pub fn key_up(&mut self, event: KeyEvent) {
+        self.event(Event::KeyUp(event));
+    }
+
     /// Send the events that would normally be sent when the app starts.
     // should we do this automatically? Also these will change regularly?
     pub fn send_initial_events(&mut self) {
@@ -150,25 +355,125 @@ impl<T: Data> Harness<'_, T> {
     /// Send an event to the widget.
     ///
     /// If this event triggers lifecycle events, they will also be dispatched,
-    /// as will any resulting commands. This will also trigger `update`.
-    ///
-    /// Commands dispatched during `update` will not be sent?
+    /// as will any resulting commands. This will also trigger `update`,
+    /// followed by any commands submitted during it.
     pub fn event(&mut self, event: Event) {
         self.inner.event(event);
         self.process_commands();
         self.update();
+        self.process_commands();
     }
 
     fn process_commands(&mut self) {
         loop {
             let cmd = self.inner.cmds.pop_front();
             match cmd {
-                Some((target, cmd)) => self.event(Event::TargetedCommand(target, cmd)),
+                Some((target, cmd)) => {
+                    let cmd = cmd.delivered_to(target);
+                    self.inner.submitted.push((target, cmd.clone()));
+                    self.event(Event::TargetedCommand(target, cmd))
+                }
                 None => break,
             }
         }
     }
 
+    /// Wait up to `timeout` for commands from other threads, such as those
+    /// from tasks started with [`EventCtx::spawn`], and deliver them.
+    ///
+    /// This returns once at least one command has been delivered, or when
+    /// the time is up.
+    ///
+    /// [`EventCtx::spawn`]: ../struct.EventCtx.html#method.spawn
+    pub fn wait_for_ext_events(&mut self, timeout: Duration) {
+        let deadline = Instant::now() + timeout;
+        while !self.inner.ext_host.has_pending_items() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+        while let Some((target, cmd)) = self.inner.ext_host.recv() {
+            let target = target.unwrap_or_else(|| self.inner.window.id.into());
+            self.event(Event::TargetedCommand(target, cmd.delivered_to(target)));
+        }
+    }
+
+    /// The text most recently put on the clipboard with
+    /// [`EventCtx::put_clipboard_string`].
+    ///
+    /// [`EventCtx::put_clipboard_string`]: ../struct.EventCtx.html#method.put_clipboard_string
+    pub fn clipboard_string(&self) -> Option<String> {
+        self.inner
+            .window
+            .mock_clipboard
+            .as_ref()
+            .and_then(|clipboard| clipboard.borrow().clone())
+    }
+
+    /// The current time on the harness's clock.
+    pub fn now(&self) -> Instant {
+        self.timers().borrow().now()
+    }
+
+    /// Move the harness's clock forward by `duration`, sending an
+    /// `Event::Timer` for each timer that expires, in deadline order.
+    ///
+    /// Each timer is delivered with the clock set to its deadline, so
+    /// widgets that request a new timer in response see the correct time.
+    /// Widgets should compute deadlines with [`EventCtx::now`]; deadlines
+    /// based on `Instant::now` will drift from the harness's clock.
+    ///
+    /// [`EventCtx::now`]: ../struct.EventCtx.html#method.now
+    pub fn advance_time(&mut self, duration: Duration) {
+        let end = self.now() + duration;
+        loop {
+            let token = self.timers().borrow_mut().pop_expired(end);
+            match token {
+                Some(token) => self.event(Event::Timer(token)),
+                None => break,
+            }
+        }
+        self.timers().borrow_mut().now = end;
+    }
+
+    /// Send the events in `log` to the window, moving the clock forward to
+    /// the time of each event before sending it.
+    ///
+    /// The log's first event is sent at the current time; timers that
+    /// expire between events are delivered in between, as they were when
+    /// the events were recorded.
+    pub fn replay(&mut self, log: &EventLog) {
+        let start = self.now();
+        for recorded in log.events() {
+            let deadline = start + recorded.time;
+            let now = self.now();
+            if deadline > now {
+                self.advance_time(deadline - now);
+            }
+            self.event(recorded.event.clone());
+        }
+    }
+
+    /// Start recording the input events sent to the window, replacing any
+    /// earlier recording.
+    ///
+    /// Get the recording with [`recorded_events`].
+    ///
+    /// [`recorded_events`]: #method.recorded_events
+    pub fn record_events(&mut self) {
+        self.inner.window.recorder = Some(EventRecorder::in_memory());
+    }
+
+    /// The events recorded since [`record_events`] was called.
+    ///
+    /// [`record_events`]: #method.record_events
+    pub fn recorded_events(&self) -> Option<&EventLog> {
+        self.inner.window.recorder.as_ref().and_then(|r| r.log())
+    }
+
+    fn timers(&self) -> &RefCell<MockTimerQueue> {
+        &self.inner.timers
+    }
+
+    #[cfg(test)]
     fn lifecycle(&mut self, event: LifeCycle) {
         self.inner.lifecycle(event)
     }
@@ -183,10 +488,114 @@ impl<T: Data> Harness<'_, T> {
         self.inner.layout(&mut self.piet)
     }
 
-    #[allow(dead_code)]
+    /// Lay out the widget with the given constraints instead of the window
+    /// size, returning the size it chose.
+    ///
+    /// As in a real window, widgets whose constraints have not changed and
+    /// that have not requested layout will reuse their previous size.
+    pub fn layout_with_constraints(&mut self, bc: BoxConstraints) -> Size {
+        self.inner.layout_with_constraints(&mut self.piet, &bc)
+    }
+
+    /// A snapshot of the widget tree, as logged by the
+    /// [`DUMP_WIDGET_TREE`] command.
+    ///
+    /// [`DUMP_WIDGET_TREE`]: ../commands/constant.DUMP_WIDGET_TREE.html
+    pub fn widget_tree(&mut self) -> WidgetTree {
+        let inner = &mut self.inner;
+        inner
+            .window
+            .widget_tree(&mut inner.cmds, &inner.data, &inner.env)
+    }
+
+    /// The innermost widget under `pos`, in window coordinates.
+    ///
+    /// See [`WidgetTree::widget_id_at`] for how this is decided.
+    ///
+    /// [`WidgetTree::widget_id_at`]: ../inspect/struct.WidgetTree.html#method.widget_id_at
+    pub fn widget_id_at(&mut self, pos: impl Into<Point>) -> Option<WidgetId> {
+        self.widget_tree().widget_id_at(pos.into())
+    }
+
+    /// Hide or show the window, as happens when the platform reports it
+    /// minimized or restored.
+    pub fn set_window_visible(&mut self, visible: bool) {
+        let inner = &mut self.inner;
+        inner
+            .window
+            .set_visible(visible, &mut inner.cmds, &inner.data, &inner.env);
+    }
+
+    /// Change the window's scale factor, as happens when it is moved to a
+    /// monitor with a different pixel density.
+    pub fn set_window_scale(&mut self, scale: f64) {
+        let inner = &mut self.inner;
+        inner
+            .window
+            .set_scale(scale, &mut inner.cmds, &inner.data, &inner.env);
+    }
+
+    /// Do a full paint pass, as would happen in response to a request
+    /// from the platform. This includes layout, if it is needed.
     pub fn paint(&mut self) {
         self.inner.paint(&mut self.piet)
     }
+
+    /// The widgets painted by the last call to [`paint`], in the order they
+    /// painted, including those painted with a z-index and those drawn from
+    /// a recording, such as the children of [`Cached`].
+    ///
+    /// Piet's drawing calls aren't recorded, only the widgets that made
+    /// them; to check what was drawn, use a [`snapshot`].
+    ///
+    /// [`paint`]: #method.paint
+    /// [`snapshot`]: #method.snapshot
+    /// [`Cached`]: ../widget/struct.Cached.html
+    pub fn paint_log(&self) -> Vec<PaintedWidget> {
+        let log = self.inner.window.paint_log.as_deref().unwrap_or_default();
+        log.iter()
+            .map(|painted| PaintedWidget {
+                id: painted.id,
+                type_name: painted.type_name,
+                rect: painted.rect,
+            })
+            .collect()
+    }
+
+    /// Paint the whole window into a new image, with `scale` pixels per
+    /// display point.
+    ///
+    /// This does a full paint pass, like [`paint`]. The result can be compared
+    /// against a stored image with [`Snapshot::assert_matches_golden`].
+    ///
+    /// [`paint`]: #method.paint
+    /// [`Snapshot::assert_matches_golden`]: struct.Snapshot.html#method.assert_matches_golden
+    pub fn snapshot(&mut self, scale: f64) -> Snapshot {
+        let size = self.inner.window.size;
+        let width = (size.width * scale).ceil().max(1.0) as usize;
+        let height = (size.height * scale).ceil().max(1.0) as usize;
+
+        let mut device = Device::new().expect("harness failed to get device");
+        let mut target = device
+            .bitmap_target(width, height, scale)
+            .expect("bitmap_target");
+        // direct2d applies the bitmap's scale as its dpi, as it does a
+        // window's, rather than as part of the transform.
+        let window_scale = self.inner.window.scale;
+        if cfg!(target_os = "windows") {
+            self.inner.window.scale = scale;
+        }
+        {
+            let mut piet = target.render_context();
+            self.inner.paint(&mut piet);
+            piet.finish().expect("failed to finish snapshot");
+        }
+        self.inner.window.scale = window_scale;
+        let pixels = target
+            .into_raw_pixels(ImageFormat::RgbaPremul)
+            .expect("failed to read snapshot pixels");
+        Snapshot::from_premul(width, height, pixels)
+    }
 }
 
 impl<T: Data> Inner<T> {
@@ -195,23 +604,84 @@ impl<T: Data> Inner<T> {
             .event(&mut self.cmds, event, &mut self.data, &self.env);
     }
 
+    #[cfg(test)]
     fn lifecycle(&mut self, event: LifeCycle) {
         self.window
             .lifecycle(&mut self.cmds, &event, &self.data, &self.env);
     }
 
     fn update(&mut self) {
-        self.window.update(&self.data, &self.env);
+        self.window.update(&mut self.cmds, &self.data, &self.env);
+        // as in a running app, children added or removed during update are
+        // registered before the next frame.
+        self.window
+            .invalidate_and_finalize(&mut self.cmds, &self.data, &self.env);
     }
 
     fn layout(&mut self, piet: &mut Piet) {
-        self.window.just_layout(piet, &self.data, &self.env);
+        self.window
+            .just_layout(piet, &mut self.cmds, &self.data, &self.env);
+    }
+
+    fn layout_with_constraints(&mut self, piet: &mut Piet, bc: &BoxConstraints) -> Size {
+        self.window
+            .layout_with_constraints(piet, bc, &self.data, &self.env)
     }
 
-    #[allow(dead_code)]
     fn paint(&mut self, piet: &mut Piet) {
+        let invalid_rect = self.window.size.to_rect();
         self.window
-            .do_paint(piet, &mut self.cmds, &self.data, &self.env);
+            .do_paint(piet, invalid_rect, &mut self.cmds, &self.data, &self.env);
+    }
+}
+
+fn mouse_event(pos: Point, count: u32) -> MouseEvent {
+    MouseEvent {
+        pos,
+        window_pos: pos,
+        mods: KeyModifiers::default(),
+        count,
+        button: MouseButton::Left,
+        gesture: MouseGesture::Hover,
+    }
+}
+
+impl MockTimerQueue {
+    fn new() -> Self {
+        MockTimerQueue {
+            now: Instant::now(),
+            pending: Vec::new(),
+        }
+    }
+
+    fn now(&self) -> Instant {
+        self.now
+    }
+
+    /// Remove and return the earliest timer with a deadline no later than
+    /// `end`, moving the clock to that deadline.
+    fn pop_expired(&mut self, end: Instant) -> Option<TimerToken> {
+        let (idx, _) = self
+            .pending
+            .iter()
+            .enumerate()
+            .filter(|(_, (deadline, _))| *deadline <= end)
+            .min_by_key(|(_, (deadline, _))| *deadline)?;
+        let (deadline, token) = self.pending.remove(idx);
+        self.now = self.now.max(deadline);
+        Some(token)
+    }
+}
+
+impl Clock for RefCell<MockTimerQueue> {
+    fn now(&self) -> Instant {
+        self.borrow().now
+    }
+
+    fn request_timer(&self, deadline: Instant) -> TimerToken {
+        let token = TimerToken::next();
+        self.borrow_mut().pending.push((deadline, token));
+        token
     }
 }
 
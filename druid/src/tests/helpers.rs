@@ -23,6 +23,7 @@ use std::rc::Rc;
 use crate::*;
 
 // taken from the matches crate; useful for the Recorder widget.
+#[cfg(test)]
 #[macro_export]
 macro_rules! assert_matches {
      ($expression:expr, $($pattern:tt)+) => {
@@ -33,12 +34,30 @@ macro_rules! assert_matches {
      }
  }
 
-pub type EventFn<S, T> = dyn FnMut(&mut S, &mut EventCtx, &Event, &T, &Env);
+/// The signature of a [`ModularWidget`]'s `event` function.
+///
+/// [`ModularWidget`]: struct.ModularWidget.html
+pub type EventFn<S, T> = dyn FnMut(&mut S, &mut EventCtx, &Event, &mut T, &Env);
+/// The signature of a [`ModularWidget`]'s `lifecycle` function.
+///
+/// [`ModularWidget`]: struct.ModularWidget.html
 pub type LifeCycleFn<S, T> = dyn FnMut(&mut S, &mut LifeCycleCtx, &LifeCycle, &T, &Env);
+/// The signature of a [`ModularWidget`]'s `update` function.
+///
+/// [`ModularWidget`]: struct.ModularWidget.html
 pub type UpdateFn<S, T> = dyn FnMut(&mut S, &mut UpdateCtx, &T, &T, &Env);
+/// The signature of a [`ModularWidget`]'s `layout` function.
+///
+/// [`ModularWidget`]: struct.ModularWidget.html
 pub type LayoutFn<S, T> = dyn FnMut(&mut S, &mut LayoutCtx, &BoxConstraints, &T, &Env) -> Size;
+/// The signature of a [`ModularWidget`]'s `paint` function.
+///
+/// [`ModularWidget`]: struct.ModularWidget.html
 pub type PaintFn<S, T> = dyn FnMut(&mut S, &mut PaintCtx, &T, &Env);
 
+/// Sent to a [`ReplaceChild`] to make it swap in a new child.
+///
+/// [`ReplaceChild`]: struct.ReplaceChild.html
 pub const REPLACE_CHILD: Selector = Selector::new("druid-test.replace-child");
 
 /// A widget that can be constructed from individual functions, builder-style.
@@ -63,13 +82,20 @@ pub struct ReplaceChild<T> {
 ///
 /// Make one like this:
 ///
-/// ```
+/// ```no_run
+/// use druid::tests::{Harness, Record, Recording, TestWidgetExt};
+/// use druid::widget::Label;
+/// use druid::{LifeCycle, WidgetExt};
+///
 /// let recording = Recording::default();
-/// let widget = Label::new().padding(4.0).record(&recording);
+/// let widget = Label::new("hello").padding(4.0).record(&recording);
 ///
 /// Harness::create((), widget, |harness| {
-///     widget.send_initial_events();
-///     assert_matches!(recording.next(), Record::L(LifeCycle::WidgetAdded));
+///     harness.send_initial_events();
+///     match recording.next() {
+///         Record::L(LifeCycle::WidgetAdded) => (),
+///         other => panic!("unexpected {:?}", other),
+///     }
 /// })
 /// ```
 pub struct Recorder<W> {
@@ -90,8 +116,11 @@ pub enum Record {
     E(Event),
     /// A `LifeCycle` event.
     L(LifeCycle),
+    /// A call to `layout`, and the size that was returned.
     Layout(Size),
+    /// A call to `update`, and whether it requested a paint.
     Update(bool),
+    /// A call to `paint`.
     Paint,
     // instead of always returning an Option<Record>, we have a none variant;
     // this would be code smell elsewhere but here I think it makes the tests
@@ -101,6 +130,9 @@ pub enum Record {
 
 /// like WidgetExt but just for this one thing
 pub trait TestWidgetExt<T: Data>: Widget<T> + Sized + 'static {
+    /// Wrap this widget in a [`Recorder`] that writes to `recording`.
+    ///
+    /// [`Recorder`]: struct.Recorder.html
     fn record(self, recording: &Recording) -> Recorder<Self> {
         Recorder {
             inner: self,
@@ -111,8 +143,9 @@ pub trait TestWidgetExt<T: Data>: Widget<T> + Sized + 'static {
 
 impl<T: Data, W: Widget<T> + 'static> TestWidgetExt<T> for W {}
 
-#[allow(dead_code)]
 impl<S, T> ModularWidget<S, T> {
+    /// Create a new widget with the given state, which does nothing until
+    /// you give it some functions.
     pub fn new(state: S) -> Self {
         ModularWidget {
             state,
@@ -126,7 +159,7 @@ impl<S, T> ModularWidget<S, T> {
 
     pub fn event_fn(
         mut self,
-        f: impl FnMut(&mut S, &mut EventCtx, &Event, &T, &Env) + 'static,
+        f: impl FnMut(&mut S, &mut EventCtx, &Event, &mut T, &Env) + 'static,
     ) -> Self {
         self.event = Some(Box::new(f));
         self
@@ -201,6 +234,8 @@ impl<S, T: Data> Widget<T> for ModularWidget<S, T> {
 }
 
 impl<T: Data> ReplaceChild<T> {
+    /// Create a new `ReplaceChild`, which will call `f` to construct its
+    /// replacement child.
     pub fn new<W: Widget<T> + 'static>(
         inner: impl Widget<T> + 'static,
         f: impl Fn() -> W + 'static,
@@ -240,17 +275,18 @@ impl<T: Data> Widget<T> for ReplaceChild<T> {
     }
 }
 
-#[allow(dead_code)]
 impl Recording {
+    /// Returns `true` if there are no recorded calls.
     pub fn is_empty(&self) -> bool {
         self.0.borrow().is_empty()
     }
 
-    #[allow(dead_code)]
+    /// The number of recorded calls.
     pub fn len(&self) -> usize {
         self.0.borrow().len()
     }
 
+    /// Discard all recorded calls.
     pub fn clear(&self) {
         self.0.borrow_mut().clear()
     }
@@ -262,6 +298,22 @@ impl Recording {
         self.0.borrow_mut().pop_front().unwrap_or(Record::None)
     }
 
+    /// Panic unless an event matching `f` has been recorded.
+    ///
+    /// Unlike [`next`], this does not consume anything.
+    ///
+    /// [`next`]: #method.next
+    pub fn assert_received(&self, f: impl Fn(&Event) -> bool) {
+        let recording = self.0.borrow();
+        let found = recording.iter().any(|record| match record {
+            Record::E(event) => f(event),
+            _ => false,
+        });
+        if !found {
+            panic!("no matching event was received; recording: {:?}", recording);
+        }
+    }
+
     fn push(&self, event: Record) {
         self.0.borrow_mut().push_back(event)
     }
@@ -275,8 +327,11 @@ impl<T: Data, W: Widget<T>> Widget<T> for Recorder<W> {
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
         let should_record = match event {
+            #[cfg(test)]
             LifeCycle::DebugRequestState { .. } => false,
+            #[cfg(test)]
             LifeCycle::DebugInspectState(_) => false,
+            LifeCycle::RouteInspectTree(_) => false,
             _ => true,
         };
 
@@ -306,14 +361,17 @@ impl<T: Data, W: Widget<T>> Widget<T> for Recorder<W> {
 }
 
 // easily make a bunch of WidgetIds
+#[doc(hidden)]
 pub fn widget_id2() -> (WidgetId, WidgetId) {
     (WidgetId::next(), WidgetId::next())
 }
 
+#[doc(hidden)]
 pub fn widget_id3() -> (WidgetId, WidgetId, WidgetId) {
     (WidgetId::next(), WidgetId::next(), WidgetId::next())
 }
 
+#[doc(hidden)]
 pub fn widget_id4() -> (WidgetId, WidgetId, WidgetId, WidgetId) {
     (
         WidgetId::next(),
@@ -323,7 +381,7 @@ pub fn widget_id4() -> (WidgetId, WidgetId, WidgetId, WidgetId) {
     )
 }
 
-#[allow(dead_code)]
+#[doc(hidden)]
 pub fn widget_id5() -> (WidgetId, WidgetId, WidgetId, WidgetId, WidgetId) {
     (
         WidgetId::next(),
@@ -334,6 +392,7 @@ pub fn widget_id5() -> (WidgetId, WidgetId, WidgetId, WidgetId, WidgetId) {
     )
 }
 
+#[doc(hidden)]
 pub fn widget_id6() -> (WidgetId, WidgetId, WidgetId, WidgetId, WidgetId, WidgetId) {
     (
         WidgetId::next(),
@@ -182,3 +182,292 @@ fn flex_paint_rect_overflow() {
         assert_eq!(state.paint_rect().size(), expected_paint_rect.size());
     })
 }
+
+/// Returns `true` if the recording contains a layout call, draining it.
+fn did_layout(recording: &Recording) -> bool {
+    let mut found = false;
+    while !recording.is_empty() {
+        if let Record::Layout(_) = recording.next() {
+            found = true;
+        }
+    }
+    found
+}
+
+#[test]
+fn request_paint_skips_layout() {
+    const PAINT: Selector = Selector::new("druid-tests.request-paint");
+    const LAYOUT: Selector = Selector::new("druid-tests.request-layout");
+
+    let (id_1, id_2) = widget_id2();
+    let left_rec = Recording::default();
+    let right_rec = Recording::default();
+
+    fn make_widget() -> impl Widget<()> {
+        ModularWidget::new(()).event_fn(|_, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == PAINT {
+                    ctx.request_paint();
+                } else if cmd.selector == LAYOUT {
+                    ctx.request_layout();
+                }
+            }
+        })
+    }
+
+    let widget = Flex::row()
+        .with_child(make_widget().fix_width(40.).record(&left_rec).with_id(id_1))
+        .with_child(
+            make_widget()
+                .fix_width(40.)
+                .record(&right_rec)
+                .with_id(id_2),
+        );
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert!(did_layout(&left_rec));
+        assert!(did_layout(&right_rec));
+
+        // nothing requested layout; nothing should be laid out.
+        harness.just_layout();
+        assert!(!did_layout(&left_rec));
+        assert!(!did_layout(&right_rec));
+
+        harness.submit_command(PAINT, id_1);
+        harness.just_layout();
+        assert!(!did_layout(&left_rec));
+        assert!(!did_layout(&right_rec));
+
+        // only the widget that requested layout is laid out again.
+        harness.submit_command(LAYOUT, id_1);
+        harness.just_layout();
+        assert!(did_layout(&left_rec));
+        assert!(!did_layout(&right_rec));
+    })
+}
+
+#[test]
+fn new_constraints_force_layout() {
+    let record = Recording::default();
+    let widget = SizedBox::empty().expand().record(&record).center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert!(did_layout(&record));
+
+        harness.event(Event::Size(Size::new(200., 200.)));
+        harness.just_layout();
+        assert!(did_layout(&record));
+    })
+}
+
+#[test]
+fn env_change_forces_layout() {
+    const TOGGLE: Selector = Selector::new("druid-tests.toggle");
+
+    let record = Recording::default();
+    let toggler = ModularWidget::new(()).event_fn(|_, _, event, data: &mut bool, _| {
+        if let Event::Command(cmd) = event {
+            if cmd.selector == TOGGLE {
+                *data = !*data;
+            }
+        }
+    });
+    let scoped = EnvScope::new(
+        |env, data: &bool| env.set(theme::TEXT_SIZE_NORMAL, if *data { 20. } else { 10. }),
+        Padding::new(0., SizedBox::empty().record(&record)),
+    );
+    let widget = Flex::column().with_child(toggler).with_child(scoped);
+
+    Harness::create(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert!(did_layout(&record));
+
+        harness.submit_command(TOGGLE, None);
+        harness.just_layout();
+        assert!(did_layout(&record));
+
+        harness.just_layout();
+        assert!(!did_layout(&record));
+    })
+}
+
+#[test]
+fn disable_layout_cache() {
+    let layouts = Rc::new(Cell::new(0));
+    let counter = layouts.clone();
+    let uncached = ModularWidget::new(counter).layout_fn(|count, ctx, bc, _, _| {
+        count.set(count.get() + 1);
+        ctx.disable_layout_cache();
+        bc.constrain(Size::new(10., 10.))
+    });
+    let widget = uncached.padding(5.).center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(layouts.get(), 1);
+
+        harness.just_layout();
+        harness.just_layout();
+        assert_eq!(layouts.get(), 3);
+    })
+}
+
+#[test]
+fn ui_scale_changes_widget_sizes() {
+    let id = WidgetId::next();
+    let widget = Flex::column().with_child(Switch::new().with_id(id));
+
+    Harness::create(true, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.widget_rect(id).unwrap().height(), 24.);
+
+        harness.set_env(theme::init().adding(theme::UI_SCALE, 2.0));
+        harness.just_layout();
+        assert_eq!(harness.widget_rect(id).unwrap().height(), 48.);
+    })
+}
+
+#[test]
+fn flex_mirrors_for_rtl() {
+    let (id1, id2, id3) = widget_id3();
+    let make_widget = |is_rtl: bool, reversed: bool| {
+        let row = if reversed {
+            Flex::row_reversed()
+        } else {
+            Flex::row()
+        };
+        let row = row
+            .with_child(SizedBox::empty().width(100.).height(20.).with_id(id1))
+            .with_child(SizedBox::empty().width(50.).height(20.).with_id(id2))
+            .must_fill_main_axis(true);
+        let column = Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(row.fix_width(400.))
+            .with_child(SizedBox::empty().width(30.).height(30.).with_id(id3))
+            .must_fill_main_axis(true)
+            .fix_width(400.);
+        EnvScope::new(move |env, _| env.set(Env::IS_RTL, is_rtl), column)
+    };
+
+    for &(is_rtl, reversed) in &[(false, false), (true, false), (false, true), (true, true)] {
+        let mirrored = is_rtl != reversed;
+        Harness::create((), make_widget(is_rtl, reversed), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            let (x1, x2) = if mirrored { (300., 250.) } else { (0., 100.) };
+            assert_eq!(
+                harness.get_state(id1).layout_rect.origin(),
+                Point::new(x1, 0.)
+            );
+            assert_eq!(
+                harness.get_state(id2).layout_rect.origin(),
+                Point::new(x2, 0.)
+            );
+            // the column mirrors with RTL, but isn't reversed.
+            let x3 = if is_rtl { 370. } else { 0. };
+            assert_eq!(
+                harness.get_state(id3).layout_rect.origin(),
+                Point::new(x3, 20.)
+            );
+        })
+    }
+}
+
+#[test]
+fn axis_padding() {
+    let (id_1, id_2) = (WidgetId::next(), WidgetId::next());
+    let widget = Flex::column()
+        .with_child(
+            SizedBox::empty()
+                .fix_size(20., 20.)
+                .with_id(id_1)
+                .padding_horizontal(5.),
+        )
+        .with_child(
+            SizedBox::empty()
+                .fix_size(20., 20.)
+                .with_id(id_2)
+                .padding_vertical(5.),
+        );
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let rect_1 = harness.get_state(id_1).layout_rect;
+        let rect_2 = harness.get_state(id_2).layout_rect;
+        assert_eq!(rect_1.origin(), Point::new(5., 0.));
+        assert_eq!(rect_2.origin(), Point::new(0., 5.));
+    })
+}
+
+#[test]
+fn flex_spacers_share_leftover_space() {
+    let (id_1, id_2, id_3) = widget_id3();
+    let widget = Flex::row()
+        .with_child(SizedBox::empty().width(20.).with_id(id_1))
+        .with_flex_spacer(1.0)
+        .with_child(SizedBox::empty().width(30.).with_id(id_2))
+        .with_flex_spacer(3.0)
+        .with_child(SizedBox::empty().width(10.).with_id(id_3))
+        .fix_width(200.);
+    // the harness root is tightly constrained; align so the width is honoured.
+    let widget = Align::left(widget);
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // 140 left over: 35 for the first spacer, 105 for the second.
+        assert_eq!(harness.get_state(id_1).layout_rect.x0, 0.);
+        assert_eq!(harness.get_state(id_2).layout_rect.x0, 55.);
+        assert_eq!(harness.get_state(id_3).layout_rect.x0, 190.);
+    })
+}
+
+#[test]
+fn window_size_limits_constrain_layout() {
+    let id = WidgetId::next();
+    let widget = SizedBox::empty().expand().with_id(id);
+
+    Harness::create((), widget, |harness| {
+        harness.window_mut().min_size = Some(Size::new(200., 100.));
+        harness.window_mut().max_size = Some(Size::new(300., 300.));
+        harness.set_initial_size(Size::new(100., 400.));
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(
+            harness.widget_rect(id).unwrap().size(),
+            Size::new(200., 300.)
+        );
+    })
+}
+
+#[test]
+fn size_window_to_content() {
+    let widget = SizedBox::empty().width(120.).height(80.);
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(commands::SIZE_WINDOW_TO_CONTENT, None);
+        harness.just_layout();
+        let size = Size::new(120., 80.);
+        assert_eq!(harness.window().size, size);
+        assert_eq!(harness.window_mut().pending_resize.take(), Some(size));
+    });
+
+    // a root that fills whatever space it has doesn't have a size to fit.
+    let widget = SizedBox::empty().expand();
+    Harness::create((), widget, |harness| {
+        harness.set_initial_size(Size::new(300., 200.));
+        harness.send_initial_events();
+        harness.submit_command(commands::SIZE_WINDOW_TO_CONTENT, None);
+        harness.just_layout();
+        assert_eq!(harness.window().size, Size::new(300., 200.));
+        assert_eq!(harness.window().pending_resize, None);
+    })
+}
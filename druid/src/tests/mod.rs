@@ -12,19 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! Additional unit tests that cross file or module boundaries.
+//! Tools for testing and benchmarking widgets without a real window.
+//!
+//! The main entry point is [`Harness`]. This module also contains additional
+//! unit tests that cross file or module boundaries.
+//!
+//! Outside of druid's own tests, this module is only available with the
+//! `test-harness` feature. It is meant for tests and benchmarks, and may
+//! change in any release.
+//!
+//! [`Harness`]: struct.Harness.html
 
 mod harness;
 mod helpers;
+#[cfg(test)]
 mod layout_tests;
+mod snapshot;
 
-use std::cell::Cell;
+pub use harness::{Harness, PaintedWidget};
+pub use helpers::*;
+pub use snapshot::{Snapshot, UPDATE_GOLDENS_VAR};
+
+#[cfg(test)]
+use std::cell::{Cell, RefCell};
+#[cfg(test)]
 use std::rc::Rc;
+#[cfg(test)]
+use std::time::Duration;
 
+#[cfg(test)]
 use crate::widget::*;
+#[cfg(test)]
 use crate::*;
+#[cfg(test)]
 use harness::*;
-use helpers::*;
 
 /// test that the first widget to request focus during an event gets it.
 #[test]
@@ -55,6 +76,7 @@ fn propogate_hot() {
             mods: KeyModifiers::default(),
             count: 0,
             button: MouseButton::Left,
+            gesture: MouseGesture::Hover,
         }
     }
     Harness::create((), widget, |harness| {
@@ -317,3 +339,3034 @@ fn register_after_adding_child() {
         assert_eq!(harness.get_state(id_5).children.entry_count(), 5);
     })
 }
+
+#[test]
+fn cached_paint() {
+    const REPAINT: Selector = Selector::new("druid-tests.repaint");
+
+    let paints = Rc::new(Cell::new(0));
+    let counter = paints.clone();
+    let widget = ModularWidget::new(counter)
+        .event_fn(|_, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == REPAINT {
+                    ctx.request_paint();
+                }
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(40., 40.)))
+        .paint_fn(|count, _, _, _| count.set(count.get() + 1))
+        .cached()
+        .center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.paint();
+        assert_eq!(paints.get(), 1);
+
+        harness.paint();
+        harness.paint();
+        assert_eq!(paints.get(), 1);
+
+        harness.submit_command(REPAINT, None);
+        harness.paint();
+        assert_eq!(paints.get(), 2);
+    })
+}
+
+#[test]
+fn hidden_window_is_not_painted() {
+    let paints = Rc::new(Cell::new(0));
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let widget = ModularWidget::new((paints.clone(), changes.clone()))
+        .lifecycle_fn(|(_, changes), ctx, event, _, _| {
+            if let LifeCycle::WindowVisibilityChanged(visible) = event {
+                assert_eq!(ctx.is_window_visible(), *visible);
+                changes.borrow_mut().push(*visible);
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(40., 40.)))
+        .paint_fn(|(count, _), _, _, _| count.set(count.get() + 1))
+        .center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.paint();
+        assert_eq!(paints.get(), 1);
+
+        harness.set_window_visible(false);
+        harness.set_window_visible(false);
+        harness.paint();
+        assert_eq!(paints.get(), 1);
+
+        harness.set_window_visible(true);
+        harness.paint();
+        assert_eq!(paints.get(), 2);
+        assert_eq!(*changes.borrow(), vec![false, true]);
+    })
+}
+
+#[test]
+fn capped_anim_frames_wait_for_a_timer() {
+    let frames = Rc::new(RefCell::new(Vec::new()));
+    let widget = ModularWidget::new(frames.clone())
+        .event_fn(|_, ctx, event, _, _| {
+            if let Event::WindowConnected = event {
+                ctx.request_anim_frame();
+            }
+        })
+        .lifecycle_fn(|frames, ctx, event, _, _| {
+            if let LifeCycle::AnimFrame(interval) = event {
+                frames.borrow_mut().push(*interval);
+                ctx.request_anim_frame();
+            }
+        });
+
+    Harness::create((), widget, |harness| {
+        harness.window_mut().set_max_fps(Some(20.));
+        harness.send_initial_events();
+        harness.paint();
+        assert_eq!(frames.borrow().len(), 1);
+        assert!(!harness.window_mut().wants_animation_frame());
+
+        harness.advance_time(Duration::from_millis(30));
+        harness.paint();
+        assert_eq!(frames.borrow().len(), 1);
+
+        harness.advance_time(Duration::from_millis(20));
+        harness.paint();
+        assert_eq!(*frames.borrow(), vec![0, 50_000_000]);
+    })
+}
+
+#[test]
+fn widget_tree_shows_nesting_and_sizes() {
+    let (row_id, box_id) = widget_id2();
+    let widget = Flex::row()
+        .with_child(SizedBox::empty().width(20.).height(10.).with_id(box_id))
+        .padding(5.)
+        .with_id(row_id);
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let tree = harness.widget_tree();
+
+        let row = tree.find(row_id).unwrap();
+        let sized = tree.find(box_id).unwrap();
+        assert!(sized.depth > row.depth);
+        assert_eq!(sized.size, Size::new(20., 10.));
+        assert_eq!(sized.requested_size, sized.size);
+        assert_eq!(sized.window_origin.x, 5.);
+        assert!(tree.find_by_type("Flex").next().is_some());
+        assert!(tree.to_string().contains(&format!("#{} ", box_id.to_raw())));
+    })
+}
+
+#[test]
+fn hit_testing_follows_scrolling() {
+    let (top_id, bottom_id) = widget_id2();
+    let content = Flex::column()
+        .with_child(SizedBox::empty().width(100.).height(300.).with_id(top_id))
+        .with_child(
+            SizedBox::empty()
+                .width(100.)
+                .height(300.)
+                .with_id(bottom_id),
+        );
+    let widget = Scroll::new(content).vertical();
+    let wheel = Event::Wheel(WheelEvent {
+        delta: Vec2::new(0., 150.),
+        mods: KeyModifiers::default(),
+    });
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(harness.widget_id_at((10., 200.)), Some(top_id));
+
+        harness.mouse_move((10., 10.));
+        harness.event(wheel.clone());
+        assert_eq!(harness.widget_id_at((10., 200.)), Some(bottom_id));
+
+        let tree = harness.widget_tree();
+        let top = tree.rect_of(top_id).unwrap();
+        assert_eq!((top.y0, top.y1), (0., 150.));
+        let bottom = tree.rect_of(bottom_id).unwrap();
+        assert_eq!((bottom.y0, bottom.y1), (150., 400.));
+    })
+}
+
+#[test]
+fn contexts_hit_test_past_closed_sections() {
+    const PROBE: Selector = Selector::new("druid-tests.probe");
+
+    let (probe_id, body_id) = widget_id2();
+    // (the widget at the probed point, the visible rect of the body)
+    let seen = Rc::new(RefCell::new(None));
+    let probe = ModularWidget::new(seen.clone())
+        .event_fn(move |seen, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == PROBE {
+                    let point = *cmd.get_object::<Point>().unwrap();
+                    *seen.borrow_mut() = Some((ctx.widget_id_at(point), ctx.rect_of(body_id)));
+                }
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(200., 20.)))
+        .with_id(probe_id);
+    let section = Collapsible::new(
+        Label::new("Advanced"),
+        SizedBox::empty().width(200.).height(100.).with_id(body_id),
+        lens::Id,
+    );
+    let widget = Flex::column().with_child(probe).with_child(section);
+
+    Harness::create(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let body_top = harness.widget_tree().find(body_id).unwrap().window_origin.y;
+        let below_header = Point::new(10., body_top + 5.);
+        harness.submit_command(Command::new(PROBE, below_header), probe_id);
+        let (hit, body) = seen.borrow_mut().take().unwrap();
+        assert_ne!(hit, Some(body_id));
+        assert_eq!(body.unwrap().area(), 0.);
+
+        // the column centers the probe.
+        let on_probe = harness.widget_rect(probe_id).unwrap().center();
+        harness.submit_command(Command::new(PROBE, on_probe), probe_id);
+        let (hit, _) = seen.borrow_mut().take().unwrap();
+        assert_eq!(hit, Some(probe_id));
+    })
+}
+
+#[test]
+fn hit_test_skips_hidden_widgets() {
+    const HIDE: Selector = Selector::new("druid-tests.hide");
+    let hidden_id = WidgetId::next();
+    let widget = SizedBox::empty()
+        .width(200.)
+        .height(100.)
+        .with_id(hidden_id)
+        .hidden_if(|hide: &bool, _| *hide)
+        .capture(|_, event, hide: &mut bool, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(HIDE) {
+                    *hide = true;
+                }
+            }
+        });
+
+    Harness::create(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let center = harness.widget_rect(hidden_id).unwrap().center();
+        assert_eq!(harness.widget_id_at(center), Some(hidden_id));
+
+        harness.submit_command(HIDE, None);
+        harness.just_layout();
+        assert_ne!(harness.widget_id_at(center), Some(hidden_id));
+    })
+}
+
+#[test]
+fn scale_change_relays_out() {
+    // (the scales seen, the number of layouts)
+    let seen = Rc::new(RefCell::new((Vec::new(), 0)));
+    let widget = ModularWidget::new(seen.clone())
+        .lifecycle_fn(|seen, _, event, _: &(), _| {
+            if let LifeCycle::ScaleChanged(scale) = event {
+                seen.borrow_mut().0.push(*scale);
+            }
+        })
+        .layout_fn(|seen, _, bc, _, _| {
+            seen.borrow_mut().1 += 1;
+            bc.constrain(Size::new(10., 10.))
+        })
+        .center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let layouts = seen.borrow().1;
+        assert!(seen.borrow().0.is_empty());
+
+        // nothing changed, so nothing is sent.
+        harness.set_window_scale(1.0);
+        harness.just_layout();
+        assert!(seen.borrow().0.is_empty());
+        assert_eq!(seen.borrow().1, layouts);
+
+        harness.set_window_scale(1.5);
+        assert_eq!(seen.borrow().0, vec![1.5]);
+        harness.just_layout();
+        assert_eq!(seen.borrow().1, layouts + 1);
+    })
+}
+
+#[test]
+fn request_layout_command_discards_cached_layouts() {
+    let layouts = Rc::new(Cell::new(0));
+    let widget = ModularWidget::<_, ()>::new(layouts.clone())
+        .layout_fn(|layouts, _, bc, _, _| {
+            layouts.set(layouts.get() + 1);
+            bc.constrain(Size::new(10., 10.))
+        })
+        .padding(5.)
+        .center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let before = layouts.get();
+
+        harness.submit_command(commands::REQUEST_PAINT, None);
+        harness.just_layout();
+        assert_eq!(layouts.get(), before);
+
+        harness.submit_command(commands::REQUEST_LAYOUT, None);
+        harness.just_layout();
+        assert_eq!(layouts.get(), before + 1);
+    })
+}
+
+#[test]
+fn fill_sizes_a_column_to_its_widest_child() {
+    let (short, long) = widget_id2();
+    let widget = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
+        .with_child(Label::new("a").with_id(short))
+        .with_child(Label::new("a much longer label").with_id(long).padding(5.))
+        .center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let short = harness.widget_rect(short).unwrap();
+        let long = harness.widget_rect(long).unwrap();
+        assert_eq!(short.width(), long.width() + 10.);
+        assert!(short.width() > 50.);
+    })
+}
+
+#[test]
+fn intrinsic_sizes_are_cached() {
+    const GROW: Selector = Selector::new("druid-tests.grow");
+
+    let id = WidgetId::next();
+    let layouts = Rc::new(Cell::new(0));
+    let counted = ModularWidget::<_, ()>::new(layouts.clone()).layout_fn(|layouts, _, bc, _, _| {
+        layouts.set(layouts.get() + 1);
+        bc.constrain(Size::new(30., 10.))
+    });
+    let growing = ModularWidget::new(10.)
+        .event_fn(|width, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == GROW {
+                    *width += 10.;
+                    ctx.request_layout();
+                }
+            }
+        })
+        .layout_fn(|width, _, bc, _, _| bc.constrain(Size::new(*width, 10.)))
+        .with_id(id);
+    let widget = Flex::column()
+        .cross_axis_alignment(CrossAxisAlignment::Fill)
+        .with_child(counted)
+        .with_child(growing)
+        .center();
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let before = layouts.get();
+
+        // the column is laid out again, but nothing in the counted widget
+        // has changed.
+        harness.submit_command(GROW, id);
+        harness.just_layout();
+        assert_eq!(layouts.get(), before);
+        assert_eq!(harness.widget_rect(id).unwrap().width(), 30.);
+    })
+}
+
+#[test]
+fn measuring_a_widget_does_not_replace_its_layout() {
+    const RELAYOUT: Selector = Selector::new("druid-tests.relayout");
+
+    // the widest constraint the child was last laid out with.
+    let last_width = Rc::new(Cell::new(0.));
+    let child = ModularWidget::<_, ()>::new(last_width.clone()).layout_fn(|last, _, bc, _, _| {
+        last.set(bc.max().width);
+        bc.constrain(Size::new(30., 10.))
+    });
+    // asks for the child's width at a new height on every layout, then lays
+    // it out with the same constraints as before.
+    let parent = ModularWidget::new((WidgetPod::new(child).boxed(), 0.))
+        .event_fn(|(child, _), ctx, event, data, env| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == RELAYOUT {
+                    ctx.request_layout();
+                }
+            }
+            child.event(ctx, event, data, env);
+        })
+        .lifecycle_fn(|(child, _), ctx, event, data, env| child.lifecycle(ctx, event, data, env))
+        .update_fn(|(child, _), ctx, _, data, env| child.update(ctx, data, env))
+        .layout_fn(|(child, height), ctx, _, data, env| {
+            *height += 1.;
+            child.max_intrinsic_width(ctx, *height, data, env);
+            let size = child.layout(ctx, &BoxConstraints::tight(Size::new(50., 10.)), data, env);
+            child.set_layout_rect(size.to_rect());
+            size
+        })
+        .paint_fn(|(child, _), ctx, data, env| child.paint(ctx, data, env));
+
+    Harness::create((), parent, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(last_width.get(), 50.);
+
+        harness.submit_command(RELAYOUT, None);
+        harness.just_layout();
+        assert_eq!(last_width.get(), 50.);
+    })
+}
+
+#[test]
+fn aspect_ratio_box_centers_its_child() {
+    let id = WidgetId::next();
+    let widget = AspectRatioBox::new(SizedBox::empty().with_id(id), 1.0);
+
+    Harness::create((), widget, |harness| {
+        harness.set_initial_size(Size::new(300., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+        let rect = harness.widget_rect(id).unwrap();
+        assert_eq!(rect.origin(), Point::new(100., 0.));
+        assert_eq!(rect.size(), Size::new(100., 100.));
+    })
+}
+
+#[test]
+fn proportional_divides_by_weight() {
+    let (header, content) = widget_id2();
+    let widget = Proportional::column()
+        .with_child(SizedBox::empty().with_id(header), 3.0)
+        .with_child(SizedBox::empty().with_id(content), 7.0)
+        .with_gap(10.);
+
+    Harness::create((), widget, |harness| {
+        harness.set_initial_size(Size::new(50., 210.));
+        harness.send_initial_events();
+        harness.just_layout();
+        let header = harness.widget_rect(header).unwrap();
+        let content = harness.widget_rect(content).unwrap();
+        assert_eq!(header.origin(), Point::ORIGIN);
+        assert_eq!(header.size(), Size::new(50., 60.));
+        assert_eq!(content.origin(), Point::new(0., 70.));
+        assert_eq!(content.size(), Size::new(50., 140.));
+    })
+}
+
+#[test]
+fn invalid_rect_merges_up() {
+    const INVALIDATE: Selector = Selector::new("druid-tests.invalidate");
+
+    let id = WidgetId::next();
+    let widget = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == INVALIDATE {
+                    ctx.request_paint_rect(Rect::new(0., 0., 5., 5.));
+                }
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(40., 40.)))
+        .padding(10.)
+        .with_id(id);
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.paint();
+        assert!(harness.get_state(id).invalid.is_empty());
+
+        harness.submit_command(INVALIDATE, None);
+        let state = harness.get_state(id);
+        assert!(state.needs_inval);
+        assert_eq!(state.invalid.to_rect().origin(), Point::new(10., 10.));
+        assert_eq!(state.invalid.to_rect().size(), Size::new(5., 5.));
+
+        harness.paint();
+        assert!(harness.get_state(id).invalid.is_empty());
+    })
+}
+
+#[test]
+fn frame_stats_recorded() {
+    let widget = Split::vertical(SizedBox::empty(), SizedBox::empty()).padding(5.0);
+
+    Harness::create((), widget, |harness| {
+        let window_id = harness.window().id;
+        assert!(stats::frame_stats(window_id).is_none());
+
+        harness.send_initial_events();
+        harness.paint();
+        let frame = stats::frame_stats(window_id).unwrap();
+        assert_eq!(frame.widget_count, 4);
+        assert!(frame.frame_interval.is_none());
+        assert!(frame.frame_time >= frame.paint_time);
+
+        harness.paint();
+        let frame = stats::frame_stats(window_id).unwrap();
+        assert!(frame.frame_interval.is_some());
+    })
+}
+
+#[test]
+fn harness_records_painted_widgets() {
+    let (top_id, bottom_id) = widget_id2();
+    let widget = Flex::column()
+        .with_child(SizedBox::empty().width(50.).height(20.).with_id(top_id))
+        .with_child(SizedBox::empty().width(50.).height(30.).with_id(bottom_id));
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        assert!(harness.paint_log().is_empty());
+        harness.paint();
+
+        let log = harness.paint_log();
+        let painted = |id| log.iter().position(|p| p.id == id).unwrap();
+        assert!(painted(top_id) < painted(bottom_id));
+        let bottom = &log[painted(bottom_id)];
+        assert_eq!(bottom.rect.size(), Size::new(50., 30.));
+        assert!(bottom.type_name.contains("SizedBox"));
+    })
+}
+
+#[test]
+fn frame_stats_count_widgets_exactly() {
+    const REMOVE: Selector = Selector::new("druid-tests.remove-items");
+
+    let widget = List::new(|| SizedBox::empty().height(10.)).capture(
+        |_, event, items: &mut std::sync::Arc<Vec<u32>>, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(REMOVE) {
+                    *items = vec![0].into();
+                }
+            }
+        },
+    );
+
+    Harness::create(std::sync::Arc::new(vec![0, 1, 2]), widget, |harness| {
+        let window_id = harness.window().id;
+        harness.send_initial_events();
+        harness.paint();
+        assert_eq!(stats::frame_stats(window_id).unwrap().widget_count, 4);
+
+        harness.submit_command(REMOVE, None);
+        harness.paint();
+        assert_eq!(stats::frame_stats(window_id).unwrap().widget_count, 2);
+    })
+}
+
+#[test]
+fn timers_fire_when_time_advances() {
+    const TIMER_FIRED: Selector = Selector::new("druid-tests.timer-fired");
+
+    let recording = Recording::default();
+    let widget = ModularWidget::new(TimerToken::INVALID)
+        .event_fn(|token, ctx, event, _, _| match event {
+            Event::WindowConnected => {
+                *token = ctx.request_timer(ctx.now() + Duration::from_millis(100));
+            }
+            Event::Timer(t) if t == token => ctx.submit_command(TIMER_FIRED, None),
+            _ => (),
+        })
+        .record(&recording);
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.advance_time(Duration::from_millis(50));
+        assert!(harness.submitted_commands().is_empty());
+
+        harness.advance_time(Duration::from_millis(50));
+        recording.assert_received(|event| matches!(event, Event::Timer(_)));
+        harness.assert_command_submitted(TIMER_FIRED);
+
+        harness.clear_submitted_commands();
+        harness.advance_time(Duration::from_secs(1));
+        assert!(harness.submitted_commands().is_empty());
+    })
+}
+
+#[test]
+fn replayed_events_reproduce_timers() {
+    const DELAY: Duration = Duration::from_millis(300);
+
+    fn widget() -> impl Widget<u32> {
+        ModularWidget::new(TimerToken::INVALID).event_fn(
+            |token, ctx, event, clicks: &mut u32, _| match event {
+                Event::MouseDown(_) => {
+                    *clicks += 1;
+                    *token = ctx.request_timer(ctx.now() + DELAY);
+                }
+                Event::Timer(t) if t == token => *clicks += 10,
+                _ => (),
+            },
+        )
+    }
+
+    let mut log = None;
+    Harness::create(0u32, widget(), |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.record_events();
+        harness.click((10., 10.));
+        harness.advance_time(Duration::from_millis(400));
+        harness.click((20., 10.));
+        harness.advance_time(Duration::from_millis(400));
+        harness.mouse_move((30., 10.));
+        assert_eq!(*harness.data(), 22);
+        log = harness.recorded_events().cloned();
+    });
+
+    let text = log.expect("events were recorded").to_string();
+    let log = recording::EventLog::parse(&text).unwrap();
+    // each click is a move, a press and a release.
+    assert_eq!(log.len(), 7);
+    assert_eq!(log.events()[6].time, Duration::from_millis(800));
+
+    Harness::create(0u32, widget(), |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.replay(&log);
+        assert_eq!(*harness.data(), 22);
+    });
+}
+
+#[test]
+fn snapshot_follows_env() {
+    const FILL: Key<Color> = Key::new("druid-tests.fill");
+
+    let widget = ModularWidget::new(()).paint_fn(|_, ctx, _, env| {
+        let rect = ctx.size().to_rect();
+        ctx.fill(rect, &env.get(FILL));
+    });
+
+    Harness::create((), widget, |harness| {
+        harness.set_initial_size(Size::new(20., 10.));
+        harness.send_initial_events();
+        harness.set_env(theme::init().adding(FILL, Color::WHITE));
+
+        let first = harness.snapshot(2.0);
+        assert_eq!((first.width, first.height), (40, 20));
+        assert_eq!(&first.pixels[..4], &[255, 255, 255, 255]);
+        assert_eq!(harness.snapshot(2.0).count_differences(&first, 0), Some(0));
+
+        harness.set_env(theme::init().adding(FILL, Color::BLACK));
+        let second = harness.snapshot(2.0);
+        assert_eq!(second.count_differences(&first, 10), Some(40 * 20));
+        assert_eq!(harness.snapshot(1.0).count_differences(&first, 0), None);
+    })
+}
+
+#[test]
+fn snapped_borders_are_crisp() {
+    let make_widget = |snap: bool| {
+        let container =
+            Container::new(SizedBox::empty().width(10.3).height(4.6)).border(Color::WHITE, 1.0);
+        let container = if snap {
+            container.snap_border_to_pixels()
+        } else {
+            container
+        };
+        Padding::new(2.3, container).align_left()
+    };
+    // whether any pixel is neither the background nor the border.
+    let is_blurry = |snapshot: &Snapshot| {
+        snapshot
+            .pixels
+            .chunks_exact(4)
+            .any(|px| px[0] != 0 && px[0] != 255)
+    };
+    let env = theme::init().adding(theme::WINDOW_BACKGROUND_COLOR, Color::BLACK);
+
+    Harness::create((), make_widget(false), |harness| {
+        harness.set_initial_size(Size::new(20., 10.));
+        harness.send_initial_events();
+        harness.set_env(env.clone());
+        assert!(is_blurry(&harness.snapshot(1.0)));
+    });
+
+    for &scale in &[1.0, 1.5, 2.0] {
+        Harness::create((), make_widget(true), |harness| {
+            harness.set_initial_size(Size::new(20., 10.));
+            harness.send_initial_events();
+            harness.set_env(env.clone());
+            assert!(!is_blurry(&harness.snapshot(scale)), "blurry at {}", scale);
+        });
+    }
+}
+
+#[test]
+fn window_background_can_be_set_per_window() {
+    let env = theme::init().adding(theme::WINDOW_BACKGROUND_COLOR, Color::WHITE);
+
+    Harness::create((), SizedBox::empty(), |harness| {
+        harness.set_initial_size(Size::new(4., 4.));
+        harness.send_initial_events();
+        harness.set_env(env.clone());
+        assert_eq!(&harness.snapshot(1.0).pixels[..4], &[255, 255, 255, 255]);
+
+        harness.window_mut().background = Some(Color::rgb8(200, 100, 0).into());
+        assert_eq!(&harness.snapshot(1.0).pixels[..4], &[200, 100, 0, 255]);
+    });
+}
+
+#[test]
+fn click_widget_by_id() {
+    let (button, padding) = widget_id2();
+    let widget = Flex::column()
+        .with_child(SizedBox::empty().height(100.))
+        .with_child(
+            Button::new("+1")
+                .on_click(|_, count: &mut u32, _| *count += 1)
+                .with_id(button)
+                .padding(10.)
+                .with_id(padding),
+        );
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let padding_rect = harness.widget_rect(padding).unwrap();
+        let button_rect = harness.widget_rect(button).unwrap();
+        assert_eq!(padding_rect.origin().y, 100.);
+        assert_eq!(
+            button_rect.origin() - padding_rect.origin(),
+            Vec2::new(10., 10.)
+        );
+
+        harness.click(Point::new(padding_rect.x0 + 1., padding_rect.y0 + 1.));
+        assert_eq!(*harness.data(), 0);
+
+        harness.click_widget(button);
+        assert_eq!(*harness.data(), 1);
+        assert!(harness.widget_rect(WidgetId::next()).is_none());
+    })
+}
+
+#[test]
+fn countdown_uses_virtual_clock() {
+    const TICK: Duration = Duration::from_secs(1);
+
+    let widget = ModularWidget::new(TimerToken::INVALID).event_fn(
+        |token, ctx, event, remaining: &mut u32, _| match event {
+            Event::WindowConnected => *token = ctx.request_timer(ctx.now() + TICK),
+            Event::Timer(t) if t == token => {
+                *remaining -= 1;
+                if *remaining > 0 {
+                    *token = ctx.request_timer(ctx.now() + TICK);
+                }
+            }
+            _ => (),
+        },
+    );
+
+    Harness::create(3u32, widget, |harness| {
+        let start = harness.now();
+        harness.send_initial_events();
+
+        harness.advance_time(Duration::from_millis(2999));
+        assert_eq!(*harness.data(), 1);
+        assert_eq!(harness.now() - start, Duration::from_millis(2999));
+
+        harness.advance_time(Duration::from_millis(1));
+        assert_eq!(*harness.data(), 0);
+
+        harness.advance_time(TICK * 5);
+        assert_eq!(*harness.data(), 0);
+    })
+}
+
+#[test]
+fn allocated_ids_are_routable() {
+    let button = Button::new("+1")
+        .on_click(|_, count: &mut u32, _| *count += 1)
+        .with_new_id();
+    let button_id = button.widget_id();
+    assert_ne!(button_id, WidgetId::reserved(0));
+    assert_ne!(button_id, WidgetId::reserved(u16::MAX));
+
+    Harness::create(0u32, button, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.click_widget(button_id);
+        assert_eq!(*harness.data(), 1);
+    })
+}
+
+#[test]
+fn broadcasts_ignore_set_handled() {
+    const PING: Selector = Selector::new("druid-test.ping");
+    let pings = Rc::new(Cell::new(0));
+    let make_pinged = || {
+        ModularWidget::new(pings.clone()).event_fn(|pings, ctx, event, _data: &mut (), _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == PING {
+                    pings.set(pings.get() + 1);
+                    ctx.set_handled();
+                }
+            }
+        })
+    };
+    let widget = Flex::column()
+        .with_child(make_pinged())
+        .with_child(make_pinged().padding(4.));
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(PING, Target::Global);
+        assert_eq!(pings.get(), 2);
+
+        // an ordinary command stops at the first widget to handle it.
+        harness.submit_command(PING, None);
+        assert_eq!(pings.get(), 3);
+    })
+}
+
+#[test]
+fn typed_broadcast() {
+    const PING: Selector = Selector::new("druid-test.ping");
+    let boxes = Recording::default();
+    let labels = Recording::default();
+    let widget = Flex::column()
+        .with_child(SizedBox::empty().record(&boxes))
+        .with_child(Label::new("label").record(&labels))
+        .with_child(SizedBox::empty().record(&boxes));
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        boxes.clear();
+        labels.clear();
+        harness.submit_command(PING, Target::of_type::<Recorder<SizedBox<()>>>());
+
+        let is_ping = |event: &Event| match event {
+            Event::Command(cmd) => cmd.selector == PING,
+            _ => false,
+        };
+        assert_eq!(boxes.len(), 2);
+        boxes.assert_received(is_ping);
+        labels.assert_received(|event| match event {
+            Event::TargetedCommand(_, cmd) => cmd.selector == PING,
+            _ => false,
+        });
+        loop {
+            match labels.next() {
+                Record::E(event) => assert!(!is_ping(&event)),
+                Record::None => break,
+                _ => (),
+            }
+        }
+    })
+}
+
+#[test]
+fn commands_are_delivered_in_submission_order() {
+    const TRIGGER: Selector = Selector::new("druid-test.trigger");
+    const FIRST: Selector = Selector::new("druid-test.first");
+    const SECOND: Selector = Selector::new("druid-test.second");
+    const FROM_FIRST: Selector = Selector::new("druid-test.from-first");
+
+    let log: Rc<RefCell<Vec<&'static str>>> = Default::default();
+
+    let submitting = ModularWidget::new(log.clone()).event_fn(|log, ctx, event, _: &mut (), _| {
+        if let Event::Command(cmd) = event {
+            let entry = match cmd.selector {
+                TRIGGER => {
+                    ctx.submit_command(FIRST, None);
+                    ctx.submit_command(SECOND, None);
+                    "trigger"
+                }
+                FIRST => {
+                    ctx.submit_command(FROM_FIRST, None);
+                    "first"
+                }
+                SECOND => "second",
+                FROM_FIRST => "from first",
+                _ => return,
+            };
+            log.borrow_mut().push(entry);
+        }
+    });
+    // a sibling that receives the trigger after the submitter does
+    let observing = ModularWidget::new(log.clone()).event_fn(|log, _, event, _: &mut (), _| {
+        if let Event::Command(cmd) = event {
+            if cmd.selector == TRIGGER {
+                log.borrow_mut().push("sibling trigger");
+            }
+        }
+    });
+    let widget = Flex::column().with_child(submitting).with_child(observing);
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(TRIGGER, None);
+        assert_eq!(
+            *log.borrow(),
+            vec![
+                "trigger",
+                "sibling trigger",
+                "first",
+                "second",
+                "from first"
+            ]
+        );
+    })
+}
+
+#[test]
+fn handled_command_skips_later_siblings() {
+    const PING: Selector = Selector::new("druid-tests.ping");
+    let seen = Rc::new(Cell::new((0, 0)));
+    let child = |first: bool| {
+        ModularWidget::new(seen.clone()).event_fn(move |seen, ctx, event, _: &mut (), _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(PING) {
+                    let (a, b) = seen.get();
+                    if first {
+                        seen.set((a + 1, b));
+                        ctx.set_handled();
+                    } else {
+                        seen.set((a, b + 1));
+                    }
+                }
+            }
+        })
+    };
+    let widget = Flex::row().with_child(child(true)).with_child(child(false));
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(PING, None);
+        assert_eq!(seen.get(), (1, 0));
+    })
+}
+
+#[test]
+fn scroll_respects_handled_wheel() {
+    // the child sees the pointer position offset by the current scroll.
+    let seen_y = Rc::new(Cell::new(0.));
+    let child = |handle_wheel: bool| {
+        ModularWidget::new(seen_y.clone())
+            .event_fn(move |seen_y, ctx, event, _: &mut (), _| match event {
+                Event::MouseMoved(mouse) => seen_y.set(mouse.pos.y),
+                Event::Wheel(_) if handle_wheel => ctx.set_handled(),
+                _ => (),
+            })
+            .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(100., 1000.)))
+    };
+    let wheel = Event::Wheel(WheelEvent {
+        delta: Vec2::new(0., 50.),
+        mods: KeyModifiers::default(),
+    });
+
+    for &handle_wheel in &[true, false] {
+        let widget = Scroll::new(child(handle_wheel)).vertical();
+        Harness::create((), widget, |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.mouse_move((10., 10.));
+            harness.event(wheel.clone());
+            harness.mouse_move((10., 10.));
+            let expected = if handle_wheel { 10. } else { 60. };
+            assert_eq!(seen_y.get(), expected);
+        })
+    }
+}
+
+#[test]
+fn capture_keeps_clicks_from_children() {
+    let widget = Button::new("+1")
+        .on_click(|_, count: &mut u32, _| *count += 1)
+        .fix_size(50., 50.)
+        .capture(|ctx, event, count: &mut u32, _| {
+            // stop accepting clicks once the count reaches two
+            if let Event::MouseDown(_) = event {
+                if *count >= 2 {
+                    ctx.set_handled();
+                }
+            }
+        });
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        for _ in 0..3 {
+            harness.click((10., 10.));
+        }
+        assert_eq!(*harness.data(), 2);
+    })
+}
+
+#[test]
+fn update_submits_commands() {
+    const FINISHED: Selector = Selector::new("druid-tests.countdown-finished");
+    const TICK: Selector = Selector::new("druid-tests.countdown-tick");
+
+    let finished = Rc::new(RefCell::new(Vec::new()));
+    let received = finished.clone();
+    let countdown = ModularWidget::new(())
+        .update_fn(|_, ctx, old_secs: &u32, secs, _| {
+            if *old_secs > 0 && *secs == 0 {
+                ctx.submit_command(Command::new(FINISHED, Duration::from_secs(90)), None);
+            }
+        })
+        .event_fn(move |_, _, event, _, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == FINISHED {
+                    received
+                        .borrow_mut()
+                        .push(*cmd.get_object::<Duration>().unwrap());
+                }
+            }
+        });
+    let widget = countdown.on_event(|_, event, secs: &mut u32| match event {
+        Event::Command(cmd) if cmd.selector == TICK => {
+            *secs -= 1;
+            true
+        }
+        _ => false,
+    });
+
+    Harness::create(2u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(TICK, None);
+        assert!(finished.borrow().is_empty());
+        harness.submit_command(TICK, None);
+        assert_eq!(*finished.borrow(), vec![Duration::from_secs(90)]);
+        harness.assert_command_submitted(FINISHED);
+    })
+}
+
+#[test]
+fn lifecycle_and_update_commands_keep_their_order() {
+    const NOTE: Selector = Selector::new("druid-tests.note");
+
+    let noting = |name: &'static str| {
+        ModularWidget::new(())
+            .lifecycle_fn(move |_, ctx, event, _: &u32, _| {
+                if let LifeCycle::WidgetAdded = event {
+                    ctx.submit_command(Command::new(NOTE, format!("{} added", name)), None);
+                }
+            })
+            .update_fn(move |_, ctx, _, _, _| {
+                ctx.submit_command(Command::new(NOTE, format!("{} updated", name)), None);
+            })
+    };
+    let notes = Rc::new(RefCell::new(Vec::new()));
+    let received = notes.clone();
+    let widget = Flex::row()
+        .with_child(noting("first"))
+        .with_child(noting("second"))
+        .on_event(move |_, event, count| match event {
+            Event::Command(cmd) if cmd.selector == NOTE => {
+                received
+                    .borrow_mut()
+                    .push(cmd.get_object::<String>().unwrap().clone());
+                true
+            }
+            Event::MouseDown(_) => {
+                *count += 1;
+                false
+            }
+            _ => false,
+        });
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        assert_eq!(*notes.borrow(), vec!["first added", "second added"]);
+        notes.borrow_mut().clear();
+
+        harness.just_layout();
+        harness.click((1., 1.));
+        // the updates that follow the notes change nothing, and submit no more.
+        assert_eq!(*notes.borrow(), vec!["first updated", "second updated"]);
+    })
+}
+
+#[test]
+fn on_event_consumes_events() {
+    let seen = Rc::new(Cell::new(0));
+    let inner_seen = seen.clone();
+    let inner = ModularWidget::new(()).event_fn(move |_, ctx, event, _: &mut u32, _| {
+        if let Event::Command(_) = event {
+            inner_seen.set(inner_seen.get() + 1);
+            ctx.set_handled();
+        }
+    });
+    const SWALLOWED: Selector = Selector::new("druid-tests.swallowed");
+    const PASSED: Selector = Selector::new("druid-tests.passed");
+    let widget = inner.on_event(|_, event, count| match event {
+        Event::Command(cmd) if cmd.selector == SWALLOWED => {
+            *count += 1;
+            true
+        }
+        _ => false,
+    });
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(SWALLOWED, None);
+        assert_eq!((*harness.data(), seen.get()), (1, 0));
+        harness.submit_command(PASSED, None);
+        assert_eq!((*harness.data(), seen.get()), (1, 1));
+    })
+}
+
+#[test]
+fn focused_buttons_respond_to_keys() {
+    let first = Button::new("+1")
+        .on_click(|_, count: &mut u32, _| *count += 1)
+        .capture(|ctx, event, _, _| {
+            if let Event::WindowConnected = event {
+                ctx.request_focus();
+            }
+        });
+    let second = Button::new("+10").on_click(|_, count: &mut u32, _| *count += 10);
+    let widget = Flex::row().with_child(first).with_child(second);
+
+    let space = || KeyEvent::for_test(RawMods::None, " ", KeyCode::Space);
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.key_down(space());
+        assert_eq!(*harness.data(), 1);
+
+        harness.key_down(KeyEvent::for_test(RawMods::None, "\t", KeyCode::Tab));
+        harness.key_down(space());
+        assert_eq!(*harness.data(), 11);
+    })
+}
+
+#[test]
+fn held_arrow_keys_step_a_stepper() {
+    let stepper = Stepper::new()
+        .with_range(0., 3.)
+        .capture(|ctx, event, _, _| {
+            if let Event::WindowConnected = event {
+                ctx.request_focus();
+            }
+        });
+    let up = |is_repeat| {
+        let mut event = KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowUp);
+        event.is_repeat = is_repeat;
+        event
+    };
+
+    Harness::create(0.0, stepper, |harness| {
+        harness.send_initial_events();
+        harness.key_down(up(false));
+        assert_eq!(*harness.data(), 1.);
+
+        // holding the key sends repeats, which keep stepping up to the limit.
+        harness.key_down(up(true));
+        harness.key_down(up(true));
+        harness.key_down(up(true));
+        assert_eq!(*harness.data(), 3.);
+
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowDown));
+        assert_eq!(*harness.data(), 2.);
+    })
+}
+
+#[test]
+fn textbox_update_modes() {
+    let key = |text: &'static str, code| KeyEvent::for_test(RawMods::None, text, code);
+    let focused = |textbox: TextBox| {
+        textbox.capture(|ctx, event, _, _| {
+            if let Event::WindowConnected = event {
+                ctx.request_focus();
+            }
+        })
+    };
+
+    let submitted = Rc::new(Cell::new(0));
+    let submitted2 = submitted.clone();
+    let textbox = TextBox::new()
+        .update_on(UpdateMode::Submit)
+        .on_submit(move |_, _, _| submitted2.set(submitted2.get() + 1));
+    Harness::create(String::new(), focused(textbox), |harness| {
+        harness.send_initial_events();
+        harness.key_down(key("4", KeyCode::Key4));
+        harness.key_down(key("2", KeyCode::Key2));
+        assert_eq!(harness.data(), "");
+
+        harness.key_down(key("\r", KeyCode::Return));
+        assert_eq!(harness.data(), "42");
+        assert_eq!(submitted.get(), 1);
+    });
+
+    let widget = Flex::column()
+        .with_child(focused(TextBox::new().update_on(UpdateMode::Blur)))
+        .with_child(TextBox::new());
+    Harness::create(String::new(), widget, |harness| {
+        harness.send_initial_events();
+        harness.key_down(key("a", KeyCode::KeyA));
+        assert_eq!(harness.data(), "");
+
+        harness.key_down(key("\t", KeyCode::Tab));
+        assert_eq!(harness.data(), "a");
+    });
+}
+
+#[test]
+fn textbox_pending_edit_yields_to_new_data() {
+    const RESET: Selector = Selector::new("druid-tests.reset");
+    let key = |text: &'static str, code| KeyEvent::for_test(RawMods::None, text, code);
+
+    let textbox =
+        TextBox::new()
+            .update_on(UpdateMode::Blur)
+            .capture(|ctx, event, data: &mut String, _| match event {
+                Event::WindowConnected => ctx.request_focus(),
+                Event::Command(cmd) if cmd.is(RESET) => *data = "reset".into(),
+                _ => (),
+            });
+    let widget = Flex::column()
+        .with_child(textbox)
+        .with_child(TextBox::new());
+    Harness::create(String::new(), widget, |harness| {
+        harness.send_initial_events();
+        harness.key_down(key("a", KeyCode::KeyA));
+        harness.submit_command(RESET, None);
+        assert_eq!(harness.data(), "reset");
+
+        // the stale edit is not written back when focus moves on.
+        harness.key_down(key("\t", KeyCode::Tab));
+        assert_eq!(harness.data(), "reset");
+    });
+}
+
+#[test]
+fn textbox_clear_button_and_placeholder() {
+    let id = WidgetId::next();
+    let textbox = TextBox::new()
+        .with_placeholder("00:05:00")
+        .with_clear_button()
+        .fix_width(200.)
+        .with_id(id)
+        // a tightly constrained text box only paints in its top part.
+        .center();
+    Harness::create("hello".to_string(), textbox, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let rect = harness.widget_tree().rect_of(id).unwrap();
+        harness.click((rect.x1 - 5., rect.center().y));
+        assert_eq!(harness.data(), "");
+
+        // with the text empty, the placeholder is not something to select
+        // or replace.
+        harness.paint();
+        harness.key_down(KeyEvent::for_test(RawMods::None, "x", KeyCode::KeyX));
+        assert_eq!(harness.data(), "x");
+    });
+}
+
+#[test]
+fn textbox_filters_typed_input() {
+    let textbox = TextBox::new()
+        .with_input_filter(|c| c.is_ascii_digit() || c == ':')
+        .capture(|ctx, event, _, _| {
+            if let Event::WindowConnected = event {
+                ctx.request_focus();
+            }
+        });
+    Harness::create(String::new(), textbox, |harness| {
+        harness.send_initial_events();
+        for (text, code) in &[
+            ("1", KeyCode::Key1),
+            ("x", KeyCode::KeyX),
+            (":", KeyCode::Semicolon),
+            (" ", KeyCode::Space),
+            ("5", KeyCode::Key5),
+        ] {
+            harness.key_down(KeyEvent::for_test(RawMods::None, text, *code));
+        }
+        assert_eq!(harness.data(), "1:5");
+    });
+}
+
+#[test]
+fn textbox_reports_hitting_max_len() {
+    let rejected = Rc::new(Cell::new(0));
+    let rejected2 = rejected.clone();
+    let textbox = TextBox::new()
+        .with_max_len(2)
+        .with_len_counter()
+        .on_max_len(move |_, _| rejected2.set(rejected2.get() + 1))
+        .capture(|ctx, event, _, _| {
+            if let Event::WindowConnected = event {
+                ctx.request_focus();
+            }
+        });
+    Harness::create(String::new(), textbox, |harness| {
+        harness.send_initial_events();
+        for (text, code) in &[
+            ("a", KeyCode::KeyA),
+            ("b", KeyCode::KeyB),
+            ("c", KeyCode::KeyC),
+        ] {
+            harness.key_down(KeyEvent::for_test(RawMods::None, text, *code));
+        }
+        assert_eq!(harness.data(), "ab");
+        assert_eq!(rejected.get(), 1);
+        harness.paint();
+    });
+}
+
+#[test]
+fn masked_textbox_hides_text_until_revealed() {
+    let id = WidgetId::next();
+    let mut hidden = None;
+    for text in &["abc", "xyz"] {
+        let textbox = TextBox::new().masked().with_id(id);
+        Harness::create(text.to_string(), textbox, |harness| {
+            harness.send_initial_events();
+            let masked = harness.snapshot(1.0);
+            match &hidden {
+                None => hidden = Some(masked.clone()),
+                Some(other) => assert_eq!(masked.count_differences(other, 0), Some(0)),
+            }
+
+            harness.submit_command(Command::new(TextBox::REVEAL_TEXT, true), id);
+            let revealed = harness.snapshot(1.0);
+            assert_ne!(revealed.count_differences(&masked, 0), Some(0));
+        });
+    }
+}
+
+#[test]
+fn held_button_repeats() {
+    let id = WidgetId::next();
+    let button = Button::new("+")
+        .on_click_repeat(Duration::from_millis(100), |_, count: &mut u32, _| {
+            *count += 1
+        })
+        .with_id(id)
+        .fix_size(50., 50.);
+    let widget = Flex::row().with_child(button);
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // the row centers the button in the window.
+        let pos = harness.widget_rect(id).unwrap().center();
+        harness.mouse_move(pos);
+        harness.mouse_down(pos);
+        assert_eq!(*harness.data(), 1);
+        harness.advance_time(DEFAULT_REPEAT_DELAY - Duration::from_millis(1));
+        assert_eq!(*harness.data(), 1);
+        harness.advance_time(Duration::from_millis(1));
+        assert_eq!(*harness.data(), 2);
+        harness.advance_time(Duration::from_millis(250));
+        assert_eq!(*harness.data(), 4);
+
+        harness.mouse_up(pos);
+        harness.advance_time(Duration::from_secs(1));
+        assert_eq!(*harness.data(), 4);
+
+        // leaving the button also stops the repeat
+        harness.mouse_down(pos);
+        harness.mouse_move((10., 10.));
+        harness.advance_time(Duration::from_secs(1));
+        assert_eq!(*harness.data(), 5);
+    })
+}
+
+#[test]
+fn boxed_widgets_of_different_types() {
+    let (label_id, button_id) = widget_id2();
+    let items: Vec<Box<dyn Widget<u32>>> = vec![
+        Label::new("count").with_id(label_id).boxed(),
+        Button::new("+")
+            .on_click(|_, count: &mut u32, _| *count += 1)
+            .with_id(button_id)
+            .boxed(),
+        SizedBox::empty().width(10.).boxed(),
+    ];
+    let mut row = Flex::row();
+    for item in items {
+        row.add_child(item);
+    }
+
+    Harness::create(0u32, row, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let label = harness.widget_rect(label_id).unwrap();
+        let button = harness.widget_rect(button_id).unwrap();
+        assert!(button.x0 >= label.x1);
+        harness.click_widget(button_id);
+        assert_eq!(*harness.data(), 1);
+    })
+}
+
+#[test]
+fn scroll_reports_viewport() {
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let child = ModularWidget::new(seen.clone())
+        .lifecycle_fn(|seen, _, event, _: &(), _| {
+            if let LifeCycle::ViewportChanged(rect) = event {
+                seen.borrow_mut().push((rect.x0, rect.y0, rect.x1, rect.y1));
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(100., 1000.)));
+    let widget = Scroll::new(Padding::new(10., child)).vertical();
+    let wheel = Event::Wheel(WheelEvent {
+        delta: Vec2::new(0., 50.),
+        mods: KeyModifiers::default(),
+    });
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        assert_eq!(seen.borrow().as_slice(), &[(-10., -10., 390., 390.)]);
+
+        harness.mouse_move((10., 10.));
+        harness.event(wheel.clone());
+        assert_eq!(seen.borrow().last(), Some(&(-10., 40., 390., 440.)));
+
+        // nothing changed, so nothing is sent.
+        let count = seen.borrow().len();
+        harness.just_layout();
+        assert_eq!(seen.borrow().len(), count);
+    })
+}
+
+#[test]
+fn nested_scroll_chains_to_parent() {
+    // the top of the outer scroll's content, relative to its viewport.
+    let seen_y = Rc::new(Cell::new(0.));
+    let make_widget = |chaining: bool| {
+        let inner = Scroll::new(SizedBox::empty().width(100.).height(1000.))
+            .vertical()
+            .with_chaining(chaining);
+        let tracker = ModularWidget::new(seen_y.clone())
+            .lifecycle_fn(|seen_y, _, event, _: &(), _| {
+                if let LifeCycle::ViewportChanged(rect) = event {
+                    seen_y.set(rect.y0);
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(100., 2000.)));
+        let content = Flex::column()
+            .with_child(SizedBox::new(inner).height(200.))
+            .with_child(tracker);
+        Scroll::new(content).vertical()
+    };
+    let wheel = Event::Wheel(WheelEvent {
+        delta: Vec2::new(0., 900.),
+        mods: KeyModifiers::default(),
+    });
+
+    for &(chaining, expected) in &[(true, -100.), (false, -200.)] {
+        Harness::create((), make_widget(chaining), |harness| {
+            harness.send_initial_events();
+            harness.just_layout();
+            assert_eq!(seen_y.get(), -200.);
+
+            // the inner scroll can only move 800 of the 900.
+            harness.mouse_move((10., 10.));
+            harness.event(wheel.clone());
+            assert_eq!(seen_y.get(), expected);
+        })
+    }
+}
+
+#[test]
+fn copy_hotkey_copies_focused_widget() {
+    const TAKE_FOCUS: Selector = Selector::new("druid-tests.copy-take-focus");
+
+    let id = WidgetId::next();
+    let countdown = ModularWidget::new(())
+        .event_fn(|_, ctx, event, _: &mut u32, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == TAKE_FOCUS {
+                    ctx.request_focus();
+                }
+            }
+        })
+        .on_copy(|secs, _| format!("00:{:02}:{:02}", secs / 60, secs % 60))
+        .with_id(id);
+    let copy = KeyEvent::for_test(RawMods::from(SysMods::Cmd), "c", KeyCode::KeyC);
+
+    Harness::create(252u32, countdown, |harness| {
+        harness.send_initial_events();
+        harness.key_down(copy);
+        assert_eq!(harness.clipboard_string(), None);
+
+        harness.submit_command(TAKE_FOCUS, id);
+        harness.key_down(copy);
+        assert_eq!(harness.clipboard_string().as_deref(), Some("00:04:12"));
+    })
+}
+
+#[test]
+fn copy_hotkey_reaches_labels_and_containers() {
+    let copy = KeyEvent::for_test(RawMods::from(SysMods::Cmd), "c", KeyCode::KeyC);
+    let tab = KeyEvent::for_test(RawMods::None, "\t", KeyCode::Tab);
+
+    // a label can't take focus by itself.
+    let label = Label::new("04:12").on_copy(|_, _| "04:12".into());
+    Harness::create((), label, |harness| {
+        harness.send_initial_events();
+        harness.key_down(tab);
+        harness.key_down(copy);
+        assert_eq!(harness.clipboard_string().as_deref(), Some("04:12"));
+    });
+
+    // the focus is inside the widget that copies.
+    let focusable = ModularWidget::new(()).lifecycle_fn(|_, ctx, event, _: &(), _| {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.register_for_focus();
+        }
+    });
+    let row = Flex::row()
+        .with_child(focusable)
+        .on_copy(|_, _| "the row".into());
+    Harness::create((), row, |harness| {
+        harness.send_initial_events();
+        harness.key_down(tab);
+        harness.key_down(copy);
+        assert_eq!(harness.clipboard_string().as_deref(), Some("the row"));
+    });
+}
+
+#[test]
+fn scroll_follows_focus() {
+    const TAKE_FOCUS: Selector = Selector::new("druid-tests.scroll-take-focus");
+    const MARGIN: f64 = 10.;
+
+    let ids: Vec<WidgetId> = (0..10).map(|_| WidgetId::next()).collect();
+    let mut column = Flex::column();
+    for id in &ids {
+        let field = ModularWidget::new(())
+            .event_fn(|_, ctx, event, _: &mut (), _| {
+                if let Event::Command(cmd) = event {
+                    if cmd.selector == TAKE_FOCUS {
+                        ctx.request_focus();
+                    }
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(100., 40.)));
+        column.add_child(field.with_id(*id));
+    }
+    let widget = column.scroll().vertical().with_focus_margin(MARGIN);
+
+    Harness::create((), widget, |harness| {
+        harness.set_initial_size(Size::new(100., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.submit_command(TAKE_FOCUS, ids[7]);
+        let rect = harness.widget_tree().rect_of(ids[7]).unwrap();
+        assert!(rect.y0 > 0. && rect.y1 <= 100. - MARGIN, "{:?}", rect);
+
+        harness.submit_command(TAKE_FOCUS, ids[0]);
+        let rect = harness.widget_tree().rect_of(ids[0]).unwrap();
+        assert_eq!(rect.y0, 0.);
+    })
+}
+
+/// A list item that is 20 high, and that reports its item when clicked.
+#[cfg(test)]
+fn clickable_item(clicked: Rc<Cell<Option<u32>>>) -> impl Widget<u32> {
+    ModularWidget::new(clicked)
+        .event_fn(|clicked, _, event, item: &mut u32, _| {
+            if let Event::MouseDown(_) = event {
+                clicked.set(Some(*item));
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(100., 20.)))
+}
+
+#[test]
+fn keyed_list_keeps_top_item_in_view() {
+    const INSERT: Selector = Selector::new("druid-tests.insert-items");
+
+    let clicked = Rc::new(Cell::new(None));
+    let item_clicked = clicked.clone();
+    let list = List::new(move || clickable_item(item_clicked.clone())).with_keys(|item| *item);
+    let widget = Scroll::new(list).vertical().capture(
+        |_, event, items: &mut std::sync::Arc<Vec<u32>>, _| {
+            if let Event::Command(cmd) = event {
+                if cmd.selector == INSERT {
+                    let mut new = vec![100, 101];
+                    new.extend(items.iter());
+                    *items = new.into();
+                }
+            }
+        },
+    );
+    let wheel = Event::Wheel(WheelEvent {
+        delta: Vec2::new(0., 50.),
+        mods: KeyModifiers::default(),
+    });
+
+    Harness::create(std::sync::Arc::new((0..20).collect()), widget, |harness| {
+        harness.set_initial_size(Size::new(100., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_move((10., 10.));
+        harness.event(wheel.clone());
+        harness.click((10., 1.));
+        assert_eq!(clicked.get(), Some(2));
+
+        harness.submit_command(INSERT, None);
+        harness.just_layout();
+        harness.click((10., 1.));
+        assert_eq!(clicked.get(), Some(2));
+    })
+}
+
+#[test]
+fn list_scrolls_to_item() {
+    let id = WidgetId::next();
+    let clicked = Rc::new(Cell::new(None));
+    let item_clicked = clicked.clone();
+    let list = List::new(move || clickable_item(item_clicked.clone())).with_keys(|item| *item);
+    let widget = Scroll::new(IdentityWrapper::wrap(list, id)).vertical();
+    let scroll_to = |request: ScrollToItem| Command::new(commands::SCROLL_TO_ITEM, request);
+
+    Harness::create(std::sync::Arc::new((0..30).collect()), widget, |harness| {
+        harness.set_initial_size(Size::new(100., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.submit_command(
+            scroll_to(ScrollToItem::index(10).align(ScrollAlign::Top)),
+            id,
+        );
+        harness.just_layout();
+        harness.click((10., 1.));
+        assert_eq!(clicked.get(), Some(10));
+
+        // a visible item stays where it is.
+        harness.submit_command(scroll_to(ScrollToItem::index(12)), id);
+        harness.just_layout();
+        harness.click((10., 1.));
+        assert_eq!(clicked.get(), Some(10));
+
+        // one below the view ends up at the bottom.
+        harness.submit_command(scroll_to(ScrollToItem::index(20)), id);
+        harness.just_layout();
+        harness.click((10., 1.));
+        assert_eq!(clicked.get(), Some(16));
+
+        harness.submit_command(
+            scroll_to(ScrollToItem::index(5).align(ScrollAlign::Center)),
+            id,
+        );
+        harness.just_layout();
+        harness.click((10., 1.));
+        assert_eq!(clicked.get(), Some(3));
+
+        harness.submit_command(
+            scroll_to(ScrollToItem::key(25u32).align(ScrollAlign::Top)),
+            id,
+        );
+        harness.just_layout();
+        harness.click((10., 1.));
+        assert_eq!(clicked.get(), Some(25));
+    })
+}
+
+#[test]
+fn list_items_reorder_by_dragging() {
+    let clicked = Rc::new(Cell::new(None));
+    let item_clicked = clicked.clone();
+    let list = List::new(move || clickable_item(item_clicked.clone()))
+        .with_keys(|item| *item)
+        .reorderable();
+
+    Harness::create(std::sync::Arc::new((0..5).collect()), list, |harness| {
+        harness.set_initial_size(Size::new(120., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        // pressing an item outside of its handle doesn't drag it.
+        harness.mouse_down((50., 10.));
+        harness.mouse_move((50., 55.));
+        harness.mouse_up((50., 55.));
+        assert_eq!(**harness.data(), vec![0, 1, 2, 3, 4]);
+
+        // nor does a press on the handle that doesn't move far enough.
+        clicked.set(None);
+        harness.mouse_down((10., 10.));
+        harness.mouse_move((10., 12.));
+        harness.mouse_up((10., 12.));
+        assert_eq!(**harness.data(), vec![0, 1, 2, 3, 4]);
+        assert_eq!(clicked.get(), None);
+
+        harness.mouse_down((10., 10.));
+        harness.mouse_move((10., 55.));
+        harness.mouse_up((10., 55.));
+        assert_eq!(**harness.data(), vec![1, 2, 0, 3, 4]);
+
+        harness.just_layout();
+        harness.click((50., 5.));
+        assert_eq!(clicked.get(), Some(1));
+        harness.click((50., 45.));
+        assert_eq!(clicked.get(), Some(0));
+    })
+}
+
+#[test]
+fn list_drag_scrolls_near_the_edge() {
+    let clicked = Rc::new(Cell::new(None));
+    let item_clicked = clicked.clone();
+    let list = List::new(move || clickable_item(item_clicked.clone())).reorderable();
+    let widget = Scroll::new(list).vertical();
+
+    Harness::create(std::sync::Arc::new((0..10).collect()), widget, |harness| {
+        harness.set_initial_size(Size::new(120., 60.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.mouse_down((10., 10.));
+        harness.mouse_move((10., 58.));
+        for _ in 0..60 {
+            harness.advance_time(Duration::from_millis(16));
+            harness.paint();
+        }
+        harness.mouse_up((10., 58.));
+        assert_eq!(**harness.data(), vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0]);
+
+        harness.just_layout();
+        harness.click((50., 5.));
+        assert_eq!(clicked.get(), Some(8));
+    })
+}
+
+#[test]
+fn selectable_list_selects_with_mouse_and_keys() {
+    let changes = Rc::new(Cell::new(0));
+    let counter = changes.clone();
+    let list = SelectableList::new(|| SizedBox::empty().width(100.).height(20.))
+        .on_selection_changed(move |_, _, _| counter.set(counter.get() + 1));
+    let data = (std::sync::Arc::new(vec![0u32; 5]), Selection::new());
+    let click = |mods: SysMods, y: f64| {
+        let pos = Point::new(10., y);
+        Event::MouseDown(MouseEvent {
+            pos,
+            window_pos: pos,
+            mods: RawMods::from(mods).into(),
+            count: 1,
+            button: MouseButton::Left,
+            gesture: MouseGesture::Hover,
+        })
+    };
+    let key = |mods: SysMods, code| KeyEvent::for_test(RawMods::from(mods), "", code);
+    let selected = |harness: &Harness<(std::sync::Arc<Vec<u32>>, Selection)>| {
+        harness.data().1.iter().collect::<Vec<_>>()
+    };
+
+    Harness::create(data, list, |harness| {
+        harness.set_initial_size(Size::new(100., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.event(click(SysMods::None, 25.));
+        assert_eq!(selected(harness), vec![1]);
+        harness.event(click(SysMods::Cmd, 65.));
+        assert_eq!(selected(harness), vec![1, 3]);
+        // ranges start from the last item clicked on its own.
+        harness.event(click(SysMods::Shift, 5.));
+        assert_eq!(selected(harness), vec![0, 1, 2, 3]);
+        harness.event(click(SysMods::Cmd, 25.));
+        assert_eq!(selected(harness), vec![0, 2, 3]);
+
+        harness.key_down(key(SysMods::None, KeyCode::ArrowDown));
+        assert_eq!(selected(harness), vec![2]);
+        harness.key_down(key(SysMods::Shift, KeyCode::ArrowDown));
+        assert_eq!(selected(harness), vec![2, 3]);
+        harness.key_down(key(SysMods::Cmd, KeyCode::ArrowUp));
+        harness.key_down(key(SysMods::Cmd, KeyCode::ArrowUp));
+        assert_eq!(selected(harness), vec![2, 3]);
+        harness.key_down(key(SysMods::None, KeyCode::Space));
+        assert_eq!(selected(harness), vec![1, 2, 3]);
+    });
+    assert_eq!(changes.get(), 7);
+}
+
+#[test]
+fn selectable_list_selection_follows_dragged_items() {
+    let list = SelectableList::new(|| SizedBox::empty().width(100.).height(20.)).reorderable();
+    let data = (std::sync::Arc::new((0..5).collect()), Selection::new());
+
+    Harness::create(data, list, |harness| {
+        harness.set_initial_size(Size::new(120., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.click((50., 5.));
+        harness.mouse_down((10., 10.));
+        harness.mouse_move((10., 55.));
+        harness.mouse_up((10., 55.));
+        assert_eq!(*harness.data().0, vec![1, 2, 0, 3, 4]);
+        assert_eq!(harness.data().1.iter().collect::<Vec<_>>(), vec![2]);
+    })
+}
+
+#[test]
+fn scroll_restores_preserved_offset() {
+    const CLEAR: Selector = Selector::new("druid-tests.clear-items");
+    const RELOAD: Selector = Selector::new("druid-tests.reload-items");
+
+    let clicked = Rc::new(Cell::new(None));
+    let make_widget = |preserve: bool| {
+        let item_clicked = clicked.clone();
+        let list = List::new(move || clickable_item(item_clicked.clone()));
+        Scroll::new(list)
+            .vertical()
+            .with_preserved_offset(preserve)
+            .capture(|_, event, items: &mut std::sync::Arc<Vec<u32>>, _| {
+                if let Event::Command(cmd) = event {
+                    if cmd.selector == CLEAR {
+                        *items = Vec::new().into();
+                    } else if cmd.selector == RELOAD {
+                        *items = (0..20).collect::<Vec<_>>().into();
+                    }
+                }
+            })
+    };
+    let wheel = Event::Wheel(WheelEvent {
+        delta: Vec2::new(0., 50.),
+        mods: KeyModifiers::default(),
+    });
+
+    for &(preserve, expected) in &[(true, 2), (false, 0)] {
+        let data = std::sync::Arc::new((0..20).collect());
+        Harness::create(data, make_widget(preserve), |harness| {
+            harness.set_initial_size(Size::new(100., 100.));
+            harness.send_initial_events();
+            harness.just_layout();
+            harness.mouse_move((10., 10.));
+            harness.event(wheel.clone());
+
+            harness.submit_command(CLEAR, None);
+            harness.just_layout();
+            harness.submit_command(RELOAD, None);
+            harness.just_layout();
+            harness.click((10., 1.));
+            assert_eq!(clicked.get(), Some(expected));
+        })
+    }
+}
+
+#[test]
+fn tabular_figures_have_equal_widths() {
+    let (ones, eights) = widget_id2();
+    let widget = Flex::column()
+        .with_child(Label::new("11:11").with_tabular_figures(true).with_id(ones))
+        .with_child(
+            Label::new("88:88")
+                .with_tabular_figures(true)
+                .with_id(eights),
+        );
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let ones = harness.widget_rect(ones).unwrap();
+        let eights = harness.widget_rect(eights).unwrap();
+        assert_eq!(ones.width(), eights.width());
+    })
+}
+
+#[test]
+fn text_shadow_extends_paint_rect() {
+    let id = WidgetId::next();
+    let label = Label::new("shadowed")
+        .with_text_shadow((2., 3.), 1., Color::BLACK)
+        .with_id(id)
+        .center();
+
+    Harness::create((), label, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let state = harness.get_state(id);
+        let layout = state.layout_rect;
+        let paint = state.paint_rect();
+        assert_eq!(paint.origin(), layout.origin());
+        assert_eq!(paint.x1 - layout.x1, 3.);
+        assert_eq!(paint.y1 - layout.y1, 4.);
+    })
+}
+
+#[test]
+fn hidden_widgets_keep_space_but_ignore_clicks() {
+    let (button, after) = widget_id2();
+    let widget = Flex::column()
+        .with_child(
+            Button::new("Stop")
+                .on_click(|_, clicks: &mut u32, _| *clicks += 1)
+                .hidden_if(|clicks, _| *clicks > 0)
+                .with_id(button),
+        )
+        .with_child(SizedBox::empty().height(10.).with_id(after));
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let below = harness.widget_rect(after).unwrap().y0;
+        harness.click_widget(button);
+        assert_eq!(*harness.data(), 1);
+
+        // now hidden: clicks are ignored, and nothing moves.
+        harness.click_widget(button);
+        harness.just_layout();
+        assert_eq!(*harness.data(), 1);
+        assert_eq!(harness.widget_rect(after).unwrap().y0, below);
+    })
+}
+
+#[test]
+fn hidden_widgets_leave_the_focus_chain() {
+    const HIDE: Selector = Selector::new("druid-tests.hide");
+    let (id_1, id_2) = widget_id2();
+    let focusable = |id| {
+        ModularWidget::new(())
+            .event_fn(|_, ctx, event, hide: &mut bool, _| {
+                if let Event::Command(cmd) = event {
+                    if cmd.is(HIDE) {
+                        *hide = !*hide;
+                        ctx.set_handled();
+                    }
+                }
+            })
+            .lifecycle_fn(|_, ctx, event, _, _| {
+                if let LifeCycle::WidgetAdded = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .with_id(id)
+    };
+    let widget = Flex::column()
+        .with_child(focusable(id_1))
+        .with_child(focusable(id_2).hidden_if(|hide, _| *hide));
+
+    Harness::create(false, widget, |harness| {
+        harness.send_initial_events();
+        assert_eq!(harness.window().focus_chain(), &[id_1, id_2]);
+        harness.submit_command(HIDE, None);
+        assert_eq!(harness.window().focus_chain(), &[id_1]);
+        // and showing it again puts it back.
+        harness.submit_command(HIDE, None);
+        assert_eq!(harness.window().focus_chain(), &[id_1, id_2]);
+    })
+}
+
+#[test]
+fn transformed_widgets_see_untransformed_mouse() {
+    let seen = Rc::new(Cell::new(None));
+    let child = ModularWidget::new(seen.clone())
+        .event_fn(|seen, _, event, _: &mut (), _| {
+            if let Event::MouseDown(mouse) = event {
+                seen.set(Some(mouse.pos));
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(100., 100.)));
+    // the harness root is tightly constrained; align so the size is honoured.
+    let widget = Align::new(
+        UnitPoint::TOP_LEFT,
+        child.transformed(Affine::scale(0.5)).fix_size(100., 100.),
+    );
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_down((20., 30.));
+        assert_eq!(seen.get(), Some(Point::new(40., 60.)));
+
+        // this is inside our layout rect, but outside the painted child.
+        seen.set(None);
+        harness.mouse_down((60., 60.));
+        assert_eq!(seen.get(), None);
+    })
+}
+
+#[test]
+fn canvas_redraws_only_when_key_changes() {
+    let draws = Rc::new(Cell::new(0));
+    let draws_clone = draws.clone();
+    let (bump_key, bump_other) = widget_id2();
+    let canvas = Canvas::new(
+        |data: &(u32, u32)| data.0,
+        move |ctx, _| {
+            draws_clone.set(draws_clone.get() + 1);
+            ctx.fill(ctx.size().to_rect(), Color::WHITE);
+        },
+    );
+    let widget = Flex::column()
+        .with_child(canvas.fix_size(50., 50.))
+        .with_child(
+            Button::new("key")
+                .on_click(|_, data: &mut (u32, u32), _| data.0 += 1)
+                .with_id(bump_key),
+        )
+        .with_child(
+            Button::new("other")
+                .on_click(|_, data: &mut (u32, u32), _| data.1 += 1)
+                .with_id(bump_other),
+        );
+
+    Harness::create((0, 0), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.paint();
+        harness.paint();
+        assert_eq!(draws.get(), 1);
+
+        harness.click_widget(bump_other);
+        harness.paint();
+        assert_eq!(draws.get(), 1);
+
+        harness.click_widget(bump_key);
+        harness.paint();
+        assert_eq!(draws.get(), 2);
+    })
+}
+
+#[test]
+fn resizable_drags_write_size_to_data() {
+    // centered, so that the window's constraints are loose.
+    let widget = Resizable::new(SizedBox::empty(), lens::Id)
+        .with_min_size(Size::new(50., 50.))
+        .with_max_size(Size::new(300., 140.))
+        .center();
+
+    Harness::create(Size::new(100., 100.), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_move((248., 248.));
+        harness.mouse_down((248., 248.));
+        harness.mouse_move((298., 268.));
+        assert_eq!(*harness.data(), Size::new(150., 120.));
+
+        // dragging past the limits clamps, and the drag outlives the bounds.
+        harness.mouse_move((548., 548.));
+        harness.mouse_up((548., 548.));
+        assert_eq!(*harness.data(), Size::new(300., 140.));
+
+        // the bottom edge only changes the height.
+        harness.just_layout();
+        harness.mouse_move((200., 268.));
+        harness.mouse_down((200., 268.));
+        harness.mouse_move((230., 218.));
+        harness.mouse_up((230., 218.));
+        assert_eq!(*harness.data(), Size::new(300., 90.));
+    })
+}
+
+#[test]
+fn resizable_drags_clamp_to_the_parent() {
+    let widget = Resizable::new(SizedBox::empty(), lens::Id).center();
+
+    Harness::create(Size::new(100., 100.), widget, |harness| {
+        harness.set_initial_size(Size::new(200., 200.));
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_move((148., 100.));
+        harness.mouse_down((148., 100.));
+        harness.mouse_move((348., 100.));
+        harness.mouse_up((348., 100.));
+        assert_eq!(*harness.data(), Size::new(200., 100.));
+
+        // the stored size is the one shown, so dragging back takes effect
+        // straight away.
+        harness.just_layout();
+        harness.mouse_move((198., 100.));
+        harness.mouse_down((198., 100.));
+        harness.mouse_move((188., 100.));
+        harness.mouse_up((188., 100.));
+        assert_eq!(*harness.data(), Size::new(190., 100.));
+
+        // dragging the left edge inwards shrinks it too.
+        harness.just_layout();
+        harness.mouse_move((6., 100.));
+        harness.mouse_down((6., 100.));
+        harness.mouse_move((16., 100.));
+        harness.mouse_up((16., 100.));
+        assert_eq!(*harness.data(), Size::new(180., 100.));
+    })
+}
+
+#[test]
+fn collapsible_animates_open_when_header_clicked() {
+    let section = WidgetId::next();
+    let widget = Collapsible::new(
+        Label::new("Advanced"),
+        SizedBox::empty().height(100.),
+        lens::Id,
+    )
+    .with_id(section)
+    .fix_width(200.);
+    // the harness root is tightly constrained; align so the section can
+    // take its own height, with the header in the top left corner.
+    let widget = Align::new(UnitPoint::TOP_LEFT, widget);
+
+    Harness::create(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let closed = harness.widget_rect(section).unwrap().height();
+        assert!(closed < 100.);
+
+        harness.click((10., 10.));
+        assert!(*harness.data());
+        // the first frame starts the clock, the second finishes the animation.
+        harness.paint();
+        harness.advance_time(DEFAULT_COLLAPSE_ANIMATION);
+        harness.paint();
+        harness.just_layout();
+        let open = harness.widget_rect(section).unwrap().height();
+        assert_eq!(open, closed + 100.);
+
+        harness.key_down(KeyEvent::for_test(RawMods::None, " ", KeyCode::Space));
+        assert!(!*harness.data());
+    })
+}
+
+#[test]
+fn accordion_opens_one_section_at_a_time() {
+    let (first, second) = widget_id2();
+    let widget = Accordion::new(lens::Id)
+        .with_section(
+            Label::new("Sounds").with_id(first),
+            SizedBox::empty().height(50.),
+        )
+        .with_section(
+            Label::new("Display").with_id(second),
+            SizedBox::empty().height(50.),
+        )
+        .with_animation_duration(Duration::from_secs(0))
+        .fix_width(200.);
+
+    Harness::create(None, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.click_widget(first);
+        assert_eq!(*harness.data(), Some(0));
+
+        harness.just_layout();
+        harness.click_widget(second);
+        assert_eq!(*harness.data(), Some(1));
+        harness.click_widget(second);
+        assert_eq!(*harness.data(), None);
+
+        // the second header has focus; move up to the first and open it.
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowUp));
+        harness.key_down(KeyEvent::for_test(RawMods::None, " ", KeyCode::Space));
+        assert_eq!(*harness.data(), Some(0));
+    })
+}
+
+#[test]
+fn accordion_bodies_leave_the_focus_chain_when_closed() {
+    let (header, body) = widget_id2();
+    let focusable_body = |id| {
+        ModularWidget::new(())
+            .lifecycle_fn(|_, ctx, event, _: &Option<usize>, _| {
+                if let LifeCycle::WidgetAdded = event {
+                    ctx.register_for_focus();
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(100., 50.)))
+            .with_id(id)
+    };
+    let widget = Accordion::new(lens::Id)
+        .with_section(Label::new("Sounds").with_id(header), focusable_body(body))
+        .with_section(Label::new("Display"), focusable_body(WidgetId::next()))
+        .with_animation_duration(Duration::from_secs(0))
+        .fix_width(200.);
+
+    Harness::create(None, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // just the two headers.
+        assert_eq!(harness.window().focus_chain().len(), 2);
+
+        harness.click_widget(header);
+        assert_eq!(*harness.data(), Some(0));
+        let chain = harness.window().focus_chain();
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain[1], body);
+
+        // closing the section takes its body out again.
+        harness.click_widget(header);
+        assert_eq!(*harness.data(), None);
+        assert_eq!(harness.window().focus_chain().len(), 2);
+    })
+}
+
+#[test]
+fn popover_opens_below_and_closes_on_outside_click() {
+    let (trigger, item) = widget_id2();
+    let picked = Rc::new(Cell::new(false));
+    let content = ModularWidget::new(picked.clone())
+        .event_fn(|picked, _, event, _: &mut bool, _| {
+            if let Event::MouseDown(_) = event {
+                picked.set(true);
+            }
+        })
+        .layout_fn(|_, _, bc, _, _| bc.constrain(Size::new(80., 60.)))
+        .with_id(item);
+    let popover = Popover::new(
+        Button::new("Presets")
+            .on_click(|_, open: &mut bool, _| *open = true)
+            .with_id(trigger),
+        content,
+        lens::Id,
+    );
+    let widget = Flex::column().with_child(popover).with_flex_spacer(1.0);
+
+    Harness::create(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.paint();
+        harness.click_widget(trigger);
+        assert!(*harness.data());
+
+        harness.paint();
+        let trigger_rect = harness.widget_rect(trigger).unwrap();
+        let item_rect = harness.widget_rect(item).unwrap();
+        assert_eq!(item_rect.y0, trigger_rect.y1);
+        harness.click_widget(item);
+        assert!(picked.get());
+        assert!(*harness.data());
+
+        harness.click((390., 390.));
+        assert!(!*harness.data());
+    })
+}
+
+#[test]
+fn popover_flips_above_when_there_is_no_room_below() {
+    let (trigger, item) = widget_id2();
+    let content = SizedBox::empty().width(80.).height(100.).with_id(item);
+    let trigger_widget = Button::new("Presets")
+        .on_click(|_, open: &mut bool, _| *open = true)
+        .with_id(trigger);
+    let popover = Popover::new(trigger_widget, content, lens::Id);
+    let widget = Flex::column()
+        .with_flex_spacer(1.0)
+        .with_child(popover)
+        .with_spacer(10.);
+
+    Harness::create(false, widget, |harness| {
+        harness.send_initial_events();
+        harness.paint();
+        harness.click_widget(trigger);
+        harness.just_layout();
+        let trigger_rect = harness.widget_rect(trigger).unwrap();
+        let item_rect = harness.widget_rect(item).unwrap();
+        assert_eq!(item_rect.y1, trigger_rect.y0);
+    })
+}
+
+#[test]
+fn dropdown_picks_with_arrows_and_type_ahead() {
+    let id = WidgetId::next();
+    let widget = Dropdown::new(vec![("Bell", 1), ("Chime", 2), ("Gong", 3)]).with_id(id);
+
+    Harness::create(1u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.click_widget(id);
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowDown));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "\r", KeyCode::Return));
+        assert_eq!(*harness.data(), 2);
+
+        // while closed, typing selects the matching option directly.
+        harness.key_down(KeyEvent::for_test(RawMods::None, "g", KeyCode::KeyG));
+        assert_eq!(*harness.data(), 3);
+    })
+}
+
+#[test]
+fn searchable_dropdown_filters_options() {
+    let id = WidgetId::next();
+    let widget = Dropdown::searchable(vec![("Bell", 1), ("Chime", 2), ("Gong", 3)]).with_id(id);
+
+    Harness::create(1u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.click_widget(id);
+        harness.key_down(KeyEvent::for_test(RawMods::None, "c", KeyCode::KeyC));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "x", KeyCode::KeyX));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::Backspace));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "h", KeyCode::KeyH));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "\r", KeyCode::Return));
+        assert_eq!(*harness.data(), 2);
+    })
+}
+
+#[test]
+fn recorded_popups_are_in_the_paint_log() {
+    let id = WidgetId::next();
+    let widget = Dropdown::new(vec![("Bell", 1), ("Chime", 2)]).with_id(id);
+    let has_list = |log: Vec<PaintedWidget>| {
+        log.iter()
+            .any(|painted| painted.type_name.contains("OptionList"))
+    };
+
+    Harness::create(1u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.click_widget(id);
+        harness.paint();
+        assert!(has_list(harness.paint_log()));
+
+        // the second paint draws the popup from its recording.
+        harness.paint();
+        assert!(has_list(harness.paint_log()));
+    })
+}
+
+#[test]
+fn date_picker_chooses_from_calendar_with_keys() {
+    let id = WidgetId::next();
+    let start = format::Date::new(2026, 10, 14).unwrap();
+    let widget = DatePicker::new().with_id(id);
+
+    Harness::create(start, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        let rect = harness.widget_rect(id).unwrap();
+        harness.click((rect.x1 - 4., rect.center().y));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowRight));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowDown));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::PageDown));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "\r", KeyCode::Return));
+        assert_eq!(*harness.data(), format::Date::new(2026, 11, 22).unwrap());
+    })
+}
+
+#[test]
+fn time_picker_steps_with_arrow_keys() {
+    let widget = TimePicker::new().with_minute_step(15);
+    let start = format::Time::new(23, 50).unwrap();
+
+    Harness::create(start, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        // focus the hour field; the clock the locale doesn't use is empty.
+        let hour_field = harness
+            .widget_tree()
+            .find_by_type("hour_field")
+            .map(|w| w.window_rect())
+            .find(|rect| rect.area() > 0.)
+            .unwrap();
+        harness.click(hour_field.center());
+        // the arrows step by the minute step, even in the hour field,
+        // carrying into the hour: 23:50 + 15 minutes is 00:05.
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowUp));
+        assert_eq!(*harness.data(), format::Time::new(0, 5).unwrap());
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowDown));
+        harness.key_down(KeyEvent::for_test(RawMods::None, "", KeyCode::ArrowDown));
+        assert_eq!(*harness.data(), format::Time::new(23, 35).unwrap());
+    })
+}
+
+#[test]
+fn toasts_stack_and_dismiss_themselves() {
+    Harness::create((), SizedBox::empty(), |harness| {
+        harness.send_initial_events();
+        let toast = Toast::new("Timer completed!").with_duration(Duration::from_secs(2));
+        harness.submit_command(Command::new(commands::SHOW_TOAST, toast), None);
+        harness.advance_time(Duration::from_secs(1));
+        let toast = Toast::new("Break is over").with_position(ToastPosition::TopRight);
+        harness.submit_command(Command::new(commands::SHOW_TOAST, toast), None);
+        assert_eq!(harness.window().toasts.len(), 2);
+        harness.paint();
+
+        // the first has slid in, waited, and slid out.
+        harness.advance_time(Duration::from_millis(1400));
+        assert_eq!(harness.window().toasts.len(), 1);
+        harness.advance_time(Duration::from_secs(4));
+        assert_eq!(harness.window().toasts.len(), 0);
+    })
+}
+
+#[test]
+fn sticky_headers_pin_and_stack() {
+    let (outer_id, inner_id) = widget_id2();
+    let next_id = WidgetId::next();
+    let header = |id| SizedBox::empty().width(100.).height(20.).with_id(id);
+    // an outer section with an inner one in its body, then one more.
+    let outer_body = Flex::column()
+        .with_child(SizedBox::empty().height(30.))
+        .with_child(Sticky::new(
+            header(inner_id),
+            SizedBox::empty().height(200.),
+        ));
+    let content = Flex::column()
+        .with_child(Sticky::new(header(outer_id), outer_body))
+        .with_child(Sticky::new(header(next_id), SizedBox::empty().height(300.)));
+    let widget = Scroll::new(content).vertical();
+    let wheel = |dy| {
+        Event::Wheel(WheelEvent {
+            delta: Vec2::new(0., dy),
+            mods: KeyModifiers::default(),
+        })
+    };
+
+    Harness::create((), widget, |harness| {
+        harness.set_initial_size(Size::new(100., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_move((10., 50.));
+
+        harness.event(wheel(100.));
+        let tree = harness.widget_tree();
+        let outer = tree.rect_of(outer_id).unwrap();
+        assert_eq!((outer.y0, outer.y1), (0., 20.));
+        let inner = tree.rect_of(inner_id).unwrap();
+        assert_eq!((inner.y0, inner.y1), (20., 40.));
+        assert_eq!(tree.widget_id_at(Point::new(10., 10.)), Some(outer_id));
+
+        // past the end of the outer section, its headers scroll away, and
+        // the next header takes their place.
+        harness.event(wheel(200.));
+        let tree = harness.widget_tree();
+        let next = tree.rect_of(next_id).unwrap();
+        assert_eq!((next.y0, next.y1), (0., 20.));
+        assert_eq!(tree.widget_id_at(Point::new(10., 10.)), Some(next_id));
+    })
+}
+
+#[test]
+fn stack_places_children_and_hits_the_top_one() {
+    let (badge_id, button_id) = widget_id2();
+    let clicks = Rc::new(Cell::new((0, 0)));
+    let counter = |top: bool| {
+        ModularWidget::<_, ()>::new(clicks.clone())
+            .event_fn(move |clicks, _, event, _, _| {
+                if let Event::MouseDown(_) = event {
+                    let (bottom, button) = clicks.get();
+                    clicks.set(if top {
+                        (bottom, button + 1)
+                    } else {
+                        (bottom + 1, button)
+                    });
+                }
+            })
+            .layout_fn(|_, _, bc, _, _| bc.max())
+    };
+    let widget = Stack::new()
+        .with_child(counter(false).fix_size(100., 100.))
+        .with_aligned_child(
+            SizedBox::empty().width(20.).height(10.).with_id(badge_id),
+            UnitPoint::TOP_RIGHT,
+        )
+        .with_positioned_child(
+            counter(true).fix_size(30., 30.).with_id(button_id),
+            Point::new(60., 60.),
+        );
+
+    Harness::create((), widget, |harness| {
+        harness.set_initial_size(Size::new(100., 100.));
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let badge = harness.widget_rect(badge_id).unwrap();
+        assert_eq!(badge.origin(), Point::new(80., 0.));
+        let button = harness.widget_rect(button_id).unwrap();
+        assert_eq!(button.origin(), Point::new(60., 60.));
+        assert_eq!(button.size(), Size::new(30., 30.));
+
+        harness.click((70., 70.));
+        assert_eq!(clicks.get(), (0, 1));
+        harness.click((10., 50.));
+        assert_eq!(clicks.get(), (1, 1));
+    })
+}
+
+#[test]
+fn badge_does_not_change_layout() {
+    let (child_id, badge_id) = widget_id2();
+    let stack_id = WidgetId::next();
+    let count = |count: &u32| Some(count.to_string());
+    let widget = Flex::row()
+        .with_child(
+            SizedBox::empty()
+                .width(40.)
+                .height(30.)
+                .with_id(child_id)
+                .with_badge(count)
+                .with_id(stack_id),
+        )
+        .with_child(
+            Stack::new()
+                .with_child(SizedBox::empty().width(40.).height(30.))
+                .with_overlay(Badge::new(count).with_id(badge_id), UnitPoint::TOP_RIGHT),
+        );
+
+    // a badge much wider than the widget it is on.
+    Harness::create(1_000_000_000, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        let child = harness.widget_rect(child_id).unwrap();
+        assert_eq!(child.size(), Size::new(40., 30.));
+        let stack = harness.widget_rect(stack_id).unwrap();
+        assert_eq!(stack.size(), Size::new(40., 30.));
+
+        let badge = harness.widget_rect(badge_id).unwrap();
+        assert!(badge.width() > 40.);
+        assert_eq!((badge.x1, badge.y0), (80., child.y0));
+    })
+}
+
+#[test]
+// macOS has no access keys.
+#[cfg(not(target_os = "macos"))]
+fn access_keys_click_and_focus_buttons() {
+    let (start_id, reset_id) = widget_id2();
+    let skip_id = WidgetId::next();
+    let button = |label, id, add: u32| {
+        Button::new(label)
+            .on_click(move |_, count: &mut u32, _| *count += add)
+            .with_id(id)
+    };
+    let widget = Flex::column()
+        .with_child(button("&Start", start_id, 1))
+        .with_child(button("Re&set", reset_id, 10))
+        .with_child(button("S&kip", skip_id, 100));
+    let alt = |text, code| KeyEvent::for_test(RawMods::Alt, text, code);
+
+    Harness::create(0, widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+
+        harness.key_down(alt("k", KeyCode::KeyK));
+        assert_eq!(*harness.data(), 100);
+        assert_eq!(harness.window().focus, Some(skip_id));
+
+        // two buttons share alt+s, so it moves focus between them.
+        harness.key_down(alt("s", KeyCode::KeyS));
+        assert_eq!(harness.window().focus, Some(start_id));
+        harness.key_down(alt("S", KeyCode::KeyS));
+        assert_eq!(harness.window().focus, Some(reset_id));
+        harness.key_down(alt("s", KeyCode::KeyS));
+        assert_eq!(harness.window().focus, Some(start_id));
+        assert_eq!(*harness.data(), 100);
+
+        // a key no one has is left alone.
+        harness.key_down(alt("q", KeyCode::KeyQ));
+        assert_eq!(harness.window().focus, Some(start_id));
+    })
+}
+
+#[test]
+fn small_moves_while_pressed_are_not_drags() {
+    let gestures = Rc::new(RefCell::new(Vec::new()));
+    let recorded = gestures.clone();
+    let widget = ModularWidget::new(()).event_fn(move |_, _, event, _: &mut (), _| match event {
+        Event::MouseDown(mouse) | Event::MouseMoved(mouse) | Event::MouseUp(mouse) => {
+            recorded.borrow_mut().push(mouse.gesture)
+        }
+        _ => (),
+    });
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_move((20., 20.));
+        harness.mouse_down((20., 20.));
+        harness.mouse_move((21., 20.));
+        harness.mouse_move((30., 20.));
+        // once a drag, always a drag.
+        harness.mouse_move((20., 20.));
+        harness.mouse_up((20., 20.));
+        assert_eq!(
+            gestures.replace(Vec::new()),
+            vec![
+                MouseGesture::Hover,
+                MouseGesture::Press,
+                MouseGesture::Press,
+                MouseGesture::Drag,
+                MouseGesture::Drag,
+                MouseGesture::Drag,
+            ]
+        );
+
+        harness.mouse_down((20., 20.));
+        harness.mouse_up((21., 20.));
+        harness.mouse_move((21., 20.));
+        assert_eq!(
+            gestures.replace(Vec::new()),
+            vec![
+                MouseGesture::Press,
+                MouseGesture::Press,
+                MouseGesture::Hover
+            ]
+        );
+
+        // after holding the button long enough, any movement is a drag.
+        harness.mouse_down((20., 20.));
+        harness.advance_time(Duration::from_millis(600));
+        harness.mouse_move((21., 20.));
+        assert_eq!(
+            gestures.replace(Vec::new()),
+            vec![MouseGesture::Press, MouseGesture::Drag]
+        );
+    })
+}
+
+#[test]
+fn ctrl_wheel_pinches_around_the_pointer() {
+    let pinches = Rc::new(RefCell::new(Vec::new()));
+    let recorded = pinches.clone();
+    let chart = ModularWidget::new(()).event_fn(move |_, ctx, event, _: &mut (), _| {
+        if let Event::Gesture(Gesture::Pinch(pinch)) = event {
+            recorded.borrow_mut().push(*pinch);
+            ctx.set_handled();
+        }
+    });
+    let widget = Padding::new(10., chart);
+    let wheel = |ctrl| {
+        Event::Wheel(WheelEvent {
+            delta: Vec2::new(0., -120.),
+            mods: KeyModifiers {
+                ctrl,
+                ..KeyModifiers::default()
+            },
+        })
+    };
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_move((30., 40.));
+        harness.event(wheel(false));
+        assert!(pinches.borrow().is_empty());
+
+        harness.event(wheel(true));
+        let pinches = pinches.borrow();
+        assert_eq!(pinches.len(), 1);
+        assert!((pinches[0].scale - 1.1).abs() < 1e-9);
+        assert_eq!(pinches[0].center, Point::new(20., 30.));
+    })
+}
+
+#[test]
+fn navigator_enters_and_leaves_views() {
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let logging = |name: &'static str, log: &Rc<RefCell<Vec<String>>>| {
+        let log = log.clone();
+        ModularWidget::new(()).lifecycle_fn(move |_, _, event, _: &u32, _| {
+            if let LifeCycle::NavigationChanged(entered) = event {
+                let change = if *entered { "entered" } else { "left" };
+                log.borrow_mut().push(format!("{} {}", name, change));
+            }
+        })
+    };
+    let settings_id = WidgetId::next();
+    let settings_log = log.clone();
+    let navigator = Navigator::new(logging("main", &log)).with_view("settings", move |_, _| {
+        Box::new(logging("settings", &settings_log).with_id(settings_id))
+    });
+    let push = || Command::new(commands::PUSH_VIEW, "settings");
+
+    Harness::create(0u32, navigator, |harness| {
+        harness.send_initial_events();
+        assert_eq!(log.replace(Vec::new()), vec!["main entered"]);
+
+        harness.submit_command(push(), None);
+        assert_eq!(
+            log.replace(Vec::new()),
+            vec!["main left", "settings entered"]
+        );
+        harness.just_layout();
+        assert_eq!(harness.widget_id_at((10., 10.)), Some(settings_id));
+
+        harness.submit_command(commands::POP_VIEW, None);
+        assert_eq!(
+            log.replace(Vec::new()),
+            vec!["settings left", "main entered"]
+        );
+        harness.just_layout();
+        assert_ne!(harness.widget_id_at((10., 10.)), Some(settings_id));
+
+        // the first view stays.
+        harness.submit_command(commands::POP_VIEW, None);
+        assert!(log.borrow().is_empty());
+    })
+}
+
+#[test]
+fn navigator_takes_focus_from_covered_views() {
+    let navigator = Navigator::new(TextBox::new())
+        .with_view("settings", |_, _| Box::new(Label::new("Settings")))
+        .with_view("search", |_, _| Box::new(TextBox::new()));
+    let key = |text: &'static str, code| KeyEvent::for_test(RawMods::None, text, code);
+
+    Harness::create(String::new(), navigator, |harness| {
+        harness.send_initial_events();
+        harness.key_down(key("\t", KeyCode::Tab));
+        harness.key_down(key("a", KeyCode::KeyA));
+        assert_eq!(harness.data(), "a");
+
+        harness.submit_command(Command::new(commands::PUSH_VIEW, "settings"), None);
+        assert_eq!(harness.window().focus, None);
+        harness.key_down(key("b", KeyCode::KeyB));
+        assert_eq!(harness.data(), "a");
+
+        // a pushed view that can take focus does.
+        harness.submit_command(Command::new(commands::PUSH_VIEW, "search"), None);
+        assert!(harness.window().focus.is_some());
+        // its caret starts at the beginning of the text.
+        harness.key_down(key("c", KeyCode::KeyC));
+        assert_eq!(harness.data(), "ca");
+    })
+}
+
+#[test]
+fn spawned_tasks_report_to_their_widget() {
+    const PROGRESS: Selector = Selector::new("druid-tests.task-progress");
+    const DONE: Selector = Selector::new("druid-tests.task-done");
+
+    let reported = Rc::new(RefCell::new(Vec::new()));
+    let result = Rc::new(Cell::new(None));
+    let running = Rc::new(RefCell::new(Vec::new()));
+    let (reported_2, result_2, running_2) = (reported.clone(), result.clone(), running.clone());
+    let widget = ModularWidget::new(()).event_fn(move |_, ctx, event, _: &mut (), _| match event {
+        Event::MouseDown(_) if running_2.borrow().is_empty() => {
+            let task = ctx.spawn(PROGRESS, DONE, |task| {
+                task.report(1u32);
+                task.report(2u32);
+                42u32
+            });
+            running_2.borrow_mut().push(task);
+        }
+        Event::MouseDown(_) => {
+            // this one only stops when it is cancelled.
+            let task = ctx.spawn(PROGRESS, DONE, |task: &TaskCtx<u32>| {
+                while !task.is_cancelled() {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                0u32
+            });
+            running_2.borrow_mut().push(task);
+        }
+        Event::Command(cmd) if cmd.selector == PROGRESS => {
+            reported_2
+                .borrow_mut()
+                .push(*cmd.get_object::<u32>().unwrap());
+        }
+        Event::Command(cmd) if cmd.selector == DONE => {
+            result_2.set(Some(*cmd.get_object::<u32>().unwrap()));
+        }
+        _ => (),
+    });
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_down((10., 10.));
+        for _ in 0..10 {
+            if result.get().is_some() {
+                break;
+            }
+            harness.wait_for_ext_events(Duration::from_secs(5));
+        }
+        assert_eq!(*reported.borrow(), vec![1, 2]);
+        assert_eq!(result.get(), Some(42));
+
+        harness.mouse_down((10., 10.));
+        assert!(!running.borrow()[1].is_cancelled());
+    });
+
+    // closing the window cancels what is still running.
+    assert!(running.borrow()[1].is_cancelled());
+}
+
+#[test]
+fn cancelled_task_reports_are_dropped() {
+    const PROGRESS: Selector = Selector::new("druid-tests.cancelled-progress");
+    const DONE: Selector = Selector::new("druid-tests.cancelled-done");
+
+    let (reported_tx, reported_rx) = std::sync::mpsc::channel();
+    let seen = Rc::new(Cell::new(0));
+    let running = Rc::new(RefCell::new(None));
+    let (seen_2, running_2) = (seen.clone(), running.clone());
+    let widget = ModularWidget::new(()).event_fn(move |_, ctx, event, _: &mut (), _| match event {
+        Event::MouseDown(_) => {
+            let reported = reported_tx.clone();
+            let task = ctx.spawn(PROGRESS, DONE, move |task| {
+                task.report(1u32);
+                let _ = reported.send(());
+                while !task.is_cancelled() {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+            });
+            *running_2.borrow_mut() = Some(task);
+        }
+        Event::Command(cmd) if cmd.selector == PROGRESS || cmd.selector == DONE => {
+            seen_2.set(seen_2.get() + 1);
+        }
+        _ => (),
+    });
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_down((10., 10.));
+        // the report is waiting to be delivered when the task is cancelled.
+        reported_rx.recv_timeout(Duration::from_secs(5)).unwrap();
+        running.borrow().as_ref().unwrap().cancel();
+        harness.wait_for_ext_events(Duration::from_millis(50));
+        assert_eq!(seen.get(), 0);
+    });
+}
+
+#[test]
+fn spawned_futures_use_the_executor() {
+    const DONE: Selector = Selector::new("druid-tests.future-done");
+
+    let result = Rc::new(Cell::new(None));
+    let result_2 = result.clone();
+    let widget = ModularWidget::new(()).event_fn(move |_, ctx, event, _: &mut (), _| match event {
+        Event::MouseDown(_) => {
+            ctx.spawn_future(async { 6u32 * 7 }, DONE);
+        }
+        Event::Command(cmd) if cmd.selector == DONE => {
+            result_2.set(Some(*cmd.get_object::<u32>().unwrap()));
+        }
+        _ => (),
+    });
+
+    let spawned = Rc::new(Cell::new(0));
+    let spawned_2 = spawned.clone();
+    let executor = move |future| {
+        spawned_2.set(spawned_2.get() + 1);
+        futures_executor::block_on(future);
+    };
+
+    Harness::create((), widget, |harness| {
+        harness
+            .window_mut()
+            .tasks
+            .set_executor(Some(Rc::new(executor.clone())));
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_down((10., 10.));
+        harness.wait_for_ext_events(Duration::from_secs(5));
+        assert_eq!(spawned.get(), 1);
+        assert_eq!(result.get(), Some(42));
+    });
+}
+
+#[test]
+fn cancelled_futures_are_dropped() {
+    const DONE: Selector = Selector::new("druid-tests.never-done");
+
+    struct DropGuard(std::sync::mpsc::Sender<()>);
+    impl Drop for DropGuard {
+        fn drop(&mut self) {
+            let _ = self.0.send(());
+        }
+    }
+
+    let (dropped_tx, dropped_rx) = std::sync::mpsc::channel();
+    let running = Rc::new(RefCell::new(None));
+    let running_2 = running.clone();
+    let widget = ModularWidget::new(()).event_fn(move |_, ctx, event, _: &mut (), _| {
+        if let Event::MouseDown(_) = event {
+            let guard = DropGuard(dropped_tx.clone());
+            let task = ctx.spawn_future(
+                async move {
+                    let _guard = guard;
+                    std::future::pending::<()>().await;
+                },
+                DONE,
+            );
+            *running_2.borrow_mut() = Some(task);
+        }
+    });
+
+    // with no executor set, the default one runs the future.
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.just_layout();
+        harness.mouse_down((10., 10.));
+        let timeout = Duration::from_millis(50);
+        assert!(dropped_rx.recv_timeout(timeout).is_err());
+
+        running.borrow().as_ref().unwrap().cancel();
+        assert!(dropped_rx.recv_timeout(Duration::from_secs(5)).is_ok());
+    });
+}
+
+#[test]
+fn throttle_and_debounce_limit_updates() {
+    const BUMP: Selector = Selector::new("druid-test.bump");
+    let bumper = ModularWidget::new(()).event_fn(|_, _, event, data: &mut u32, _| {
+        if let Event::Command(cmd) = event {
+            if cmd.selector == BUMP {
+                *data += 1;
+            }
+        }
+    });
+    let make_watched = |seen: &Rc<RefCell<Vec<u32>>>| {
+        ModularWidget::new(seen.clone()).update_fn(|seen, _, _, data: &u32, _| {
+            seen.borrow_mut().push(*data);
+        })
+    };
+    let throttled = Rc::new(RefCell::new(Vec::new()));
+    let debounced = Rc::new(RefCell::new(Vec::new()));
+    let widget = Flex::column()
+        .with_child(bumper)
+        .with_child(make_watched(&throttled).throttle(Duration::from_millis(500)))
+        .with_child(make_watched(&debounced).debounce(Duration::from_millis(200)));
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(BUMP, Target::Global);
+        harness.advance_time(Duration::from_millis(100));
+        harness.submit_command(BUMP, Target::Global);
+        harness.advance_time(Duration::from_millis(100));
+        harness.submit_command(BUMP, Target::Global);
+        // the throttle let the first change through; the debounce is waiting.
+        assert_eq!(*throttled.borrow(), vec![1]);
+        assert!(debounced.borrow().is_empty());
+
+        harness.advance_time(Duration::from_millis(200));
+        assert_eq!(*debounced.borrow(), vec![3]);
+        harness.advance_time(Duration::from_millis(100));
+        assert_eq!(*throttled.borrow(), vec![1, 3]);
+
+        // after a quiet interval, the throttle leads again.
+        harness.advance_time(Duration::from_millis(600));
+        harness.submit_command(BUMP, Target::Global);
+        assert_eq!(*throttled.borrow(), vec![1, 3, 4]);
+        assert_eq!(*debounced.borrow(), vec![3]);
+    })
+}
+
+#[test]
+fn throttled_child_changes_apply_to_current_data() {
+    const BUMP: Selector = Selector::new("druid-test.bump");
+    const JUMP: Selector = Selector::new("druid-test.jump");
+    let bumper = ModularWidget::new(()).event_fn(|_, _, event, data: &mut u32, _| {
+        if let Event::Command(cmd) = event {
+            if cmd.selector == BUMP {
+                *data += 1;
+            }
+        }
+    });
+    let jumper = ModularWidget::new(()).event_fn(|_, _, event, data: &mut u32, _| {
+        if let Event::Command(cmd) = event {
+            if cmd.selector == JUMP {
+                *data += 10;
+            }
+        }
+    });
+    let widget = Flex::column()
+        .with_child(bumper)
+        .with_child(jumper.throttle(Duration::from_millis(500)));
+
+    Harness::create(0u32, widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(BUMP, Target::Global);
+        harness.submit_command(BUMP, Target::Global);
+        // the child has only been shown 1, but its change lands on 2.
+        harness.submit_command(JUMP, Target::Global);
+        assert_eq!(*harness.data(), 12);
+    })
+}
+
+#[test]
+#[should_panic(expected = "leading and trailing")]
+fn throttle_without_edges_panics_when_added() {
+    let widget = Label::new("hi")
+        .throttle(Duration::from_millis(500))
+        .trailing(false)
+        .leading(false);
+    Harness::create((), widget, |harness| harness.send_initial_events())
+}
+
+#[test]
+fn computed_values_only_recompute_when_their_key_changes() {
+    const BUMP: Selector = Selector::new("druid-test.bump");
+    let computes = Rc::new(Cell::new(0));
+    let updates = Rc::new(Cell::new(0));
+    let counted = computes.clone();
+    let shown = ModularWidget::new(updates.clone())
+        .update_fn(|updates, _, _, _: &String, _| updates.set(updates.get() + 1))
+        .computed(move |data: &(u32, u32)| {
+            counted.set(counted.get() + 1);
+            format!("{}", data.0 / 10)
+        });
+    let bumper = ModularWidget::new(()).event_fn(|_, _, event, data: &mut (u32, u32), _| {
+        if let Event::Command(cmd) = event {
+            if cmd.selector == BUMP {
+                data.0 += 5;
+            } else {
+                data.1 += 1;
+            }
+        }
+    });
+    let widget = Flex::column()
+        .with_child(bumper)
+        .with_child(Computed::new(shown, |data: &(u32, u32)| *data).with_key(|data| data.0));
+
+    Harness::create((0, 0), widget, |harness| {
+        harness.send_initial_events();
+        harness.paint();
+        assert_eq!(computes.get(), 1);
+
+        // the key is unchanged, so nothing is recomputed.
+        harness.submit_command(Selector::new("druid-test.other"), Target::Global);
+        assert_eq!(computes.get(), 1);
+
+        // the key changed, but the shown value didn't.
+        harness.submit_command(BUMP, Target::Global);
+        assert_eq!(computes.get(), 2);
+        assert_eq!(updates.get(), 0);
+
+        harness.submit_command(BUMP, Target::Global);
+        harness.paint();
+        assert_eq!(computes.get(), 3);
+        assert_eq!(updates.get(), 1);
+    })
+}
+
+#[test]
+fn commands_know_their_target() {
+    const PING: Selector = Selector::new("druid-test.ping");
+    let id = WidgetId::next();
+    let targets = Rc::new(RefCell::new(Vec::new()));
+    let widget = ModularWidget::new(targets.clone())
+        .event_fn(|targets, _, event, _data: &mut (), _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(PING) {
+                    targets.borrow_mut().push(cmd.target());
+                }
+            }
+        })
+        .with_id(id);
+
+    Harness::create((), widget, |harness| {
+        harness.send_initial_events();
+        harness.submit_command(PING, id);
+        harness.submit_command(PING, None);
+        let window = harness.window().id;
+        assert_eq!(
+            *targets.borrow(),
+            vec![Some(Target::Widget(id)), Some(Target::Window(window))]
+        );
+    })
+}
@@ -0,0 +1,153 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Rendering widgets to images, and comparing them against stored goldens.
+
+#[cfg(feature = "image")]
+use std::path::Path;
+
+/// If this environment variable is set, [`Snapshot::assert_matches_golden`]
+/// overwrites the golden images instead of comparing against them.
+///
+/// [`Snapshot::assert_matches_golden`]: struct.Snapshot.html#method.assert_matches_golden
+pub const UPDATE_GOLDENS_VAR: &str = "DRUID_UPDATE_GOLDENS";
+
+/// The pixels of a rendered window, as produced by [`Harness::snapshot`].
+///
+/// [`Harness::snapshot`]: struct.Harness.html#method.snapshot
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    /// The width of the image, in pixels.
+    pub width: usize,
+    /// The height of the image, in pixels.
+    pub height: usize,
+    /// The image data, as unpremultiplied RGBA, one byte per channel.
+    pub pixels: Vec<u8>,
+}
+
+impl Snapshot {
+    /// Create a snapshot from premultiplied RGBA pixels.
+    pub(crate) fn from_premul(width: usize, height: usize, mut pixels: Vec<u8>) -> Snapshot {
+        for px in pixels.chunks_exact_mut(4) {
+            let alpha = px[3];
+            if alpha != 0 && alpha != 255 {
+                for c in &mut px[..3] {
+                    *c = (f64::from(*c) * 255. / f64::from(alpha)).round().min(255.) as u8;
+                }
+            }
+        }
+        Snapshot {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// The number of pixels where some channel differs from `other` by more
+    /// than `tolerance`.
+    ///
+    /// Returns `None` if the images are not the same size.
+    pub fn count_differences(&self, other: &Snapshot, tolerance: u8) -> Option<usize> {
+        if self.width != other.width || self.height != other.height {
+            return None;
+        }
+        let count = self
+            .pixels
+            .chunks_exact(4)
+            .zip(other.pixels.chunks_exact(4))
+            .filter(|(a, b)| {
+                a.iter()
+                    .zip(b.iter())
+                    .any(|(a, b)| (i16::from(*a) - i16::from(*b)).abs() > i16::from(tolerance))
+            })
+            .count();
+        Some(count)
+    }
+
+    /// Load a snapshot from a PNG file.
+    #[cfg(feature = "image")]
+    pub fn load(path: impl AsRef<Path>) -> Result<Snapshot, image::ImageError> {
+        let image = image::open(path)?.to_rgba();
+        Ok(Snapshot {
+            width: image.width() as usize,
+            height: image.height() as usize,
+            pixels: image.into_raw(),
+        })
+    }
+
+    /// Save this snapshot as a PNG file.
+    #[cfg(feature = "image")]
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), image::ImageError> {
+        image::save_buffer(
+            path,
+            &self.pixels,
+            self.width as u32,
+            self.height as u32,
+            image::ColorType::Rgba8,
+        )
+    }
+
+    /// Compare this snapshot against the PNG at `path`, panicking if they
+    /// differ.
+    ///
+    /// Channel values may differ by up to `tolerance` before a pixel is
+    /// counted as different; this absorbs small differences in
+    /// antialiasing and text rendering.
+    ///
+    /// If the [`DRUID_UPDATE_GOLDENS`] environment variable is set, the
+    /// golden is written instead, and no comparison is done. A missing
+    /// golden is an error otherwise.
+    ///
+    /// [`DRUID_UPDATE_GOLDENS`]: constant.UPDATE_GOLDENS_VAR.html
+    #[cfg(feature = "image")]
+    pub fn assert_matches_golden(&self, path: impl AsRef<Path>, tolerance: u8) {
+        let path = path.as_ref();
+        if std::env::var_os(UPDATE_GOLDENS_VAR).is_some() {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).expect("failed to create golden directory");
+            }
+            if let Err(e) = self.save(path) {
+                panic!("failed to write golden {}: {}", path.display(), e);
+            }
+            return;
+        }
+
+        let golden = match Snapshot::load(path) {
+            Ok(golden) => golden,
+            Err(e) => panic!(
+                "failed to load golden {}: {}\n(set {} to create it)",
+                path.display(),
+                e,
+                UPDATE_GOLDENS_VAR
+            ),
+        };
+
+        match self.count_differences(&golden, tolerance) {
+            Some(0) => (),
+            Some(n) => panic!(
+                "snapshot differs from golden {} in {} pixels",
+                path.display(),
+                n
+            ),
+            None => panic!(
+                "snapshot is {}x{}, but golden {} is {}x{}",
+                self.width,
+                self.height,
+                path.display(),
+                golden.width,
+                golden.height
+            ),
+        }
+    }
+}
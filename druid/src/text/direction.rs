@@ -0,0 +1,98 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Finding the direction of a paragraph of text.
+//!
+//! Only the paragraph's base direction is found here, which is what
+//! decides its alignment. Reordering mixed-direction runs within a line is
+//! left to the platform's text layout.
+
+use unicode_bidi::{bidi_class, BidiClass};
+
+use crate::Env;
+
+/// The direction a paragraph of text is written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextDirection {
+    /// Left to right, as in English.
+    LeftToRight,
+    /// Right to left, as in Arabic or Hebrew.
+    RightToLeft,
+}
+
+impl TextDirection {
+    /// The direction of `text`, falling back to the UI direction in `env`
+    /// if the text has no strongly directional characters.
+    pub fn for_text(text: &str, env: &Env) -> TextDirection {
+        base_direction(text).unwrap_or_else(|| {
+            if env.get(Env::IS_RTL) {
+                TextDirection::RightToLeft
+            } else {
+                TextDirection::LeftToRight
+            }
+        })
+    }
+
+    /// Returns `true` if this is `RightToLeft`.
+    pub fn is_rtl(self) -> bool {
+        self == TextDirection::RightToLeft
+    }
+}
+
+/// The base direction of a paragraph.
+///
+/// This follows rules P2 and P3 of the Unicode bidirectional algorithm:
+/// the direction is that of the first strongly directional character,
+/// skipping over isolates. Digits, punctuation and whitespace are neutral;
+/// `None` is returned if the text contains nothing else.
+pub fn base_direction(text: &str) -> Option<TextDirection> {
+    let mut isolates = 0usize;
+    for c in text.chars() {
+        match bidi_class(c) {
+            BidiClass::LRI | BidiClass::RLI | BidiClass::FSI => isolates += 1,
+            BidiClass::PDI => isolates = isolates.saturating_sub(1),
+            BidiClass::L if isolates == 0 => return Some(TextDirection::LeftToRight),
+            BidiClass::R | BidiClass::AL if isolates == 0 => {
+                return Some(TextDirection::RightToLeft)
+            }
+            _ => (),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_strong_character_wins() {
+        assert_eq!(base_direction("hello"), Some(TextDirection::LeftToRight));
+        assert_eq!(base_direction("שלום"), Some(TextDirection::RightToLeft));
+        assert_eq!(
+            base_direction("12:30 مرحبا timer"),
+            Some(TextDirection::RightToLeft)
+        );
+        assert_eq!(
+            base_direction("timer مرحبا"),
+            Some(TextDirection::LeftToRight)
+        );
+        assert_eq!(base_direction("12:30 ?!"), None);
+        assert_eq!(base_direction("٣٤٥"), None);
+        assert_eq!(
+            base_direction("\u{2067}hello\u{2069} שלום"),
+            Some(TextDirection::RightToLeft)
+        );
+    }
+}
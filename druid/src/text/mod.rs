@@ -23,6 +23,9 @@ pub use self::selection::Selection;
 pub mod movement;
 pub use self::movement::{movement, Movement};
 
+mod direction;
+pub use self::direction::TextDirection;
+
 pub mod backspace;
 pub use self::backspace::offset_for_delete_backwards;
 
@@ -13,11 +13,40 @@
 // limitations under the License.
 
 //! Theme keys and initial values.
+//!
+//! Every key in this module is set by both [`init`] and [`init_light`], one
+//! of which provides the environment used by [`AppLauncher`], so widgets can
+//! rely on them being present.
+//!
+//! [`init`]: fn.init.html
+//! [`init_light`]: fn.init_light.html
+//! [`AppLauncher`]: ../struct.AppLauncher.html
 
 use crate::piet::Color;
 
+use crate::shell::ColorScheme;
 use crate::{Env, Key};
 
+/// The color every window is filled with before its widgets are painted.
+///
+/// Both the light and the dark theme set this, so a window whose widgets
+/// paint no background of their own still follows the color scheme. The
+/// value is read from the app's [`Env`], so an [`EnvScope`] inside the
+/// window can't change it; set it for every window with
+/// [`AppLauncher::configure_env`], for one window with
+/// [`WindowDesc::background`], or paint it under part of a window with
+/// [`WidgetExt::background`].
+///
+/// The window is cleared to this color, alpha and all, so that it shows
+/// through a transparent window. Windows are opaque for now, though, and
+/// how their platforms show a translucent color differs; on Linux the alpha
+/// is ignored.
+///
+/// [`Env`]: ../struct.Env.html
+/// [`EnvScope`]: ../widget/struct.EnvScope.html
+/// [`AppLauncher::configure_env`]: ../struct.AppLauncher.html#method.configure_env
+/// [`WindowDesc::background`]: ../struct.WindowDesc.html#method.background
+/// [`WidgetExt::background`]: ../trait.WidgetExt.html#method.background
 pub const WINDOW_BACKGROUND_COLOR: Key<Color> = Key::new("window_background_color");
 
 pub const LABEL_COLOR: Key<Color> = Key::new("label_color");
@@ -38,6 +67,8 @@ pub const BORDER_DARK: Key<Color> = Key::new("border");
 pub const BORDER_LIGHT: Key<Color> = Key::new("border_light");
 pub const SELECTION_COLOR: Key<Color> = Key::new("selection_color");
 pub const CURSOR_COLOR: Key<Color> = Key::new("cursor_color");
+/// The color of the checkmark and indeterminate dash in a checkbox.
+pub const CHECKBOX_MARK_COLOR: Key<Color> = Key::new("druid.checkbox-mark-color");
 
 pub const FONT_NAME: Key<&str> = Key::new("font_name");
 pub const TEXT_SIZE_NORMAL: Key<f64> = Key::new("text_size_normal");
@@ -50,6 +81,28 @@ pub const BORDERED_WIDGET_HEIGHT: Key<f64> = Key::new("bordered_widget_height");
 
 pub const TEXTBOX_BORDER_RADIUS: Key<f64> = Key::new("textbox_radius");
 
+/// The width of the ring drawn around the widget with keyboard focus, in
+/// `PRIMARY_LIGHT`; a width of `0.0` disables the ring.
+pub const FOCUS_RING_WIDTH: Key<f64> = Key::new("druid.focus-ring-width");
+/// The distance between the focused widget's edge and its focus ring.
+pub const FOCUS_RING_OFFSET: Key<f64> = Key::new("druid.focus-ring-offset");
+
+/// Whether the user has asked for a high-contrast display.
+///
+/// Druid does not detect this setting; an app can set it from its own
+/// preferences. Built-in widgets respond by making state indicators such as
+/// the focus ring stand out more.
+pub const HIGH_CONTRAST: Key<bool> = Key::new("druid.high-contrast");
+
+/// Whether the user has asked for less motion on screen.
+///
+/// Like [`HIGH_CONTRAST`], this is not detected, and is up to the app to
+/// set. Built-in animations that move things, such as toasts sliding in,
+/// are skipped when it is set.
+///
+/// [`HIGH_CONTRAST`]: constant.HIGH_CONTRAST.html
+pub const REDUCED_MOTION: Key<bool> = Key::new("druid.reduced-motion");
+
 pub const SCROLLBAR_COLOR: Key<Color> = Key::new("scrollbar_color");
 pub const SCROLLBAR_BORDER_COLOR: Key<Color> = Key::new("scrollbar_border_color");
 pub const SCROLLBAR_MAX_OPACITY: Key<f64> = Key::new("scrollbar_max_opacity");
@@ -59,7 +112,54 @@ pub const SCROLLBAR_PAD: Key<f64> = Key::new("scrollbar_pad");
 pub const SCROLLBAR_RADIUS: Key<f64> = Key::new("scrollbar_radius");
 pub const SCROLLBAR_EDGE_WIDTH: Key<f64> = Key::new("scrollbar_edge_width");
 
-/// An initial theme.
+/// The fill color of a badge, such as one added with `WidgetExt::with_badge`.
+pub const BADGE_COLOR: Key<Color> = Key::new("druid.badge-color");
+/// The color of the text in a badge.
+pub const BADGE_TEXT_COLOR: Key<Color> = Key::new("druid.badge-text-color");
+/// The size of the text in a badge.
+pub const BADGE_TEXT_SIZE: Key<f64> = Key::new("druid.badge-text-size");
+
+/// How far, in display points, the mouse must move with a button held
+/// before the press becomes a drag.
+///
+/// This and [`DRAG_DELAY`] are only read from the root `Env`.
+///
+/// [`DRAG_DELAY`]: constant.DRAG_DELAY.html
+pub const DRAG_THRESHOLD: Key<f64> = Key::new("druid.drag-threshold");
+/// How long, in milliseconds, a button must be held before any movement
+/// makes the press a drag.
+pub const DRAG_DELAY: Key<u64> = Key::new("druid.drag-delay");
+
+/// A density factor for the sizes of built-in widgets.
+///
+/// Widget heights and widths from this module, as well as the padding
+/// inside widgets such as buttons and checkboxes, are multiplied by this
+/// value; text sizes are not. Use a value above `1.0` for a comfortable
+/// layout on touch screens, or below it for a more compact one. Changing
+/// it causes the affected widgets to be laid out again.
+pub const UI_SCALE: Key<f64> = Key::new("druid.ui-scale");
+
+/// Get a dimension from the environment, multiplied by [`UI_SCALE`].
+///
+/// Custom widgets can use this to follow the same density as the built-in
+/// ones.
+///
+/// [`UI_SCALE`]: constant.UI_SCALE.html
+pub fn scaled(env: &Env, key: Key<f64>) -> f64 {
+    env.get(key) * env.get(UI_SCALE)
+}
+
+/// The theme for a system [`ColorScheme`].
+///
+/// [`ColorScheme`]: ../enum.ColorScheme.html
+pub fn init_for_scheme(scheme: ColorScheme) -> Env {
+    match scheme {
+        ColorScheme::Dark => init(),
+        ColorScheme::Light => init_light(),
+    }
+}
+
+/// The default theme, with light text on a dark background.
 pub fn init() -> Env {
     let mut env = Env::default()
         .adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0x29, 0x29, 0x29))
@@ -80,12 +180,17 @@ pub fn init() -> Env {
         .adding(BORDER_LIGHT, Color::rgb8(0xa1, 0xa1, 0xa1))
         .adding(SELECTION_COLOR, Color::rgb8(0xf3, 0x00, 0x21))
         .adding(CURSOR_COLOR, Color::WHITE)
+        .adding(CHECKBOX_MARK_COLOR, Color::rgb8(0xf0, 0xf0, 0xea))
         .adding(TEXT_SIZE_NORMAL, 15.0)
         .adding(TEXT_SIZE_LARGE, 24.0)
         .adding(BASIC_WIDGET_HEIGHT, 18.0)
         .adding(WIDE_WIDGET_WIDTH, 100.)
         .adding(BORDERED_WIDGET_HEIGHT, 24.0)
         .adding(TEXTBOX_BORDER_RADIUS, 2.)
+        .adding(FOCUS_RING_WIDTH, 2.)
+        .adding(FOCUS_RING_OFFSET, 2.)
+        .adding(HIGH_CONTRAST, false)
+        .adding(REDUCED_MOTION, false)
         .adding(SCROLLBAR_COLOR, Color::rgb8(0xff, 0xff, 0xff))
         .adding(SCROLLBAR_BORDER_COLOR, Color::rgb8(0x77, 0x77, 0x77))
         .adding(SCROLLBAR_MAX_OPACITY, 0.7)
@@ -93,7 +198,13 @@ pub fn init() -> Env {
         .adding(SCROLLBAR_WIDTH, 8.)
         .adding(SCROLLBAR_PAD, 2.)
         .adding(SCROLLBAR_RADIUS, 5.)
-        .adding(SCROLLBAR_EDGE_WIDTH, 1.);
+        .adding(SCROLLBAR_EDGE_WIDTH, 1.)
+        .adding(BADGE_COLOR, Color::rgb8(0xd0, 0x30, 0x30))
+        .adding(BADGE_TEXT_COLOR, Color::WHITE)
+        .adding(BADGE_TEXT_SIZE, 11.0)
+        .adding(DRAG_THRESHOLD, 4.0)
+        .adding(DRAG_DELAY, 500u64)
+        .adding(UI_SCALE, 1.0);
 
     #[cfg(target_os = "windows")]
     {
@@ -111,3 +222,28 @@ pub fn init() -> Env {
     }
     env
 }
+
+/// The light counterpart of [`init`], with dark text on a light background.
+///
+/// Only colors differ between the two themes.
+///
+/// [`init`]: fn.init.html
+pub fn init_light() -> Env {
+    init()
+        .adding(WINDOW_BACKGROUND_COLOR, Color::rgb8(0xf0, 0xf0, 0xf0))
+        .adding(LABEL_COLOR, Color::rgb8(0x1e, 0x1e, 0x1e))
+        .adding(PLACEHOLDER_COLOR, Color::rgb8(0x8c, 0x8c, 0x8c))
+        .adding(BACKGROUND_LIGHT, Color::WHITE)
+        .adding(BACKGROUND_DARK, Color::rgb8(0xe4, 0xe4, 0xe4))
+        .adding(FOREGROUND_LIGHT, Color::rgb8(0x6e, 0x6e, 0x6e))
+        .adding(FOREGROUND_DARK, Color::rgb8(0x48, 0x48, 0x48))
+        .adding(BUTTON_DARK, Color::rgb8(0xd2, 0xd2, 0xd2))
+        .adding(BUTTON_LIGHT, Color::rgb8(0xf8, 0xf8, 0xf8))
+        .adding(BORDER_DARK, Color::rgb8(0xc4, 0xc4, 0xc4))
+        .adding(BORDER_LIGHT, Color::rgb8(0x6e, 0x6e, 0x6e))
+        .adding(SELECTION_COLOR, Color::rgb8(0xa6, 0xcc, 0xff))
+        .adding(CURSOR_COLOR, Color::BLACK)
+        .adding(CHECKBOX_MARK_COLOR, Color::rgb8(0x1e, 0x1e, 0x1e))
+        .adding(SCROLLBAR_COLOR, Color::rgb8(0x40, 0x40, 0x40))
+        .adding(SCROLLBAR_BORDER_COLOR, Color::rgb8(0xa0, 0xa0, 0xa0))
+}
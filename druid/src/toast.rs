@@ -0,0 +1,279 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Short messages shown over a window's content.
+
+use std::time::{Duration, Instant};
+
+use crate::kurbo::{Point, Rect, Size, Vec2};
+use crate::piet::{FontBuilder, Piet, RenderContext, Text, TextLayout, TextLayoutBuilder};
+use crate::{theme, Env, TimerToken};
+
+/// How long toasts take to slide in, and to slide out.
+const SLIDE_DURATION: Duration = Duration::from_millis(200);
+/// The space between toasts and the window's edges, and between toasts.
+const TOAST_MARGIN: f64 = 12.0;
+const TOAST_PADDING: f64 = 12.0;
+const TOAST_HEIGHT: f64 = 36.0;
+
+/// A short message that is shown over a window's content, and goes away
+/// by itself.
+///
+/// Show one by submitting [`SHOW_TOAST`] with the toast as its argument;
+/// it appears in the window the command is sent to. Toasts that are shown
+/// while others are still up are stacked, with the oldest nearest the edge
+/// of the window. Toasts do not take input.
+///
+/// Toasts slide in and out from the window's edge. If
+/// [`theme::REDUCED_MOTION`] is set, they appear and disappear instead.
+///
+/// # Examples
+///
+/// ```
+/// use druid::{commands, Command, EventCtx, Toast};
+///
+/// fn countdown_finished(ctx: &mut EventCtx) {
+///     let toast = Toast::new("Timer completed!");
+///     ctx.submit_command(Command::new(commands::SHOW_TOAST, toast), None);
+/// }
+/// ```
+///
+/// [`SHOW_TOAST`]: commands/constant.SHOW_TOAST.html
+/// [`theme::REDUCED_MOTION`]: theme/constant.REDUCED_MOTION.html
+#[derive(Debug, Clone, PartialEq)]
+pub struct Toast {
+    /// The text of the message.
+    pub message: String,
+    /// How long the toast is shown for, not counting the time it takes to
+    /// slide in and out.
+    pub duration: Duration,
+    /// Where in the window the toast appears.
+    pub position: ToastPosition,
+}
+
+/// Where in a window a [`Toast`] appears.
+///
+/// [`Toast`]: struct.Toast.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastPosition {
+    TopLeft,
+    TopCenter,
+    TopRight,
+    BottomLeft,
+    BottomCenter,
+    BottomRight,
+}
+
+/// The toasts currently shown in a window.
+#[derive(Default)]
+pub(crate) struct ToastLayer {
+    toasts: Vec<ShownToast>,
+    timers: Vec<TimerToken>,
+}
+
+struct ShownToast {
+    toast: Toast,
+    shown_at: Instant,
+    slide: Duration,
+}
+
+impl Toast {
+    /// How long toasts are shown for, unless they say otherwise.
+    pub const DEFAULT_DURATION: Duration = Duration::from_secs(4);
+
+    /// Create a toast showing `message`, at the bottom center of the window.
+    pub fn new(message: impl Into<String>) -> Toast {
+        Toast {
+            message: message.into(),
+            duration: Toast::DEFAULT_DURATION,
+            position: ToastPosition::BottomCenter,
+        }
+    }
+
+    /// Builder-style method to set how long the toast is shown for.
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Builder-style method to set where in the window the toast appears.
+    pub fn with_position(mut self, position: ToastPosition) -> Self {
+        self.position = position;
+        self
+    }
+}
+
+impl ToastPosition {
+    fn is_top(self) -> bool {
+        matches!(
+            self,
+            ToastPosition::TopLeft | ToastPosition::TopCenter | ToastPosition::TopRight
+        )
+    }
+
+    /// The x coordinate of a toast of width `width` in a window of width
+    /// `window_width`.
+    fn x(self, width: f64, window_width: f64) -> f64 {
+        match self {
+            ToastPosition::TopLeft | ToastPosition::BottomLeft => TOAST_MARGIN,
+            ToastPosition::TopCenter | ToastPosition::BottomCenter => (window_width - width) / 2.0,
+            ToastPosition::TopRight | ToastPosition::BottomRight => {
+                window_width - width - TOAST_MARGIN
+            }
+        }
+    }
+}
+
+impl ShownToast {
+    /// When the toast starts to slide out.
+    fn hold_end(&self) -> Instant {
+        self.shown_at + self.slide + self.toast.duration
+    }
+
+    fn end(&self) -> Instant {
+        self.hold_end() + self.slide
+    }
+
+    /// How far the toast is hidden behind the window's edge, from `0.0`
+    /// when it is fully shown to `1.0` when it is hidden.
+    fn hidden_fraction(&self, now: Instant) -> f64 {
+        let slide = self.slide.as_secs_f64();
+        if slide == 0.0 {
+            return 0.0;
+        }
+        let since_shown = now.saturating_duration_since(self.shown_at).as_secs_f64();
+        let since_hold = now.saturating_duration_since(self.hold_end()).as_secs_f64();
+        if since_shown < slide {
+            (1.0 - since_shown / slide).powi(3)
+        } else {
+            (since_hold / slide).min(1.0).powi(3)
+        }
+    }
+
+    fn is_sliding(&self, now: Instant) -> bool {
+        now < self.shown_at + self.slide || (now >= self.hold_end() && now < self.end())
+    }
+}
+
+impl ToastLayer {
+    /// Start showing `toast`, returning the times at which the window
+    /// should be woken to move it on.
+    pub(crate) fn show(&mut self, toast: Toast, now: Instant, env: &Env) -> Vec<Instant> {
+        let slide = if env.get(theme::REDUCED_MOTION) {
+            Duration::from_secs(0)
+        } else {
+            SLIDE_DURATION
+        };
+        let shown = ShownToast {
+            toast,
+            shown_at: now,
+            slide,
+        };
+        let wakeups = if slide > Duration::from_secs(0) {
+            vec![shown.hold_end(), shown.end()]
+        } else {
+            vec![shown.end()]
+        };
+        self.toasts.push(shown);
+        wakeups
+    }
+
+    /// Keep track of a timer requested for one of the times returned by
+    /// [`show`].
+    ///
+    /// [`show`]: #method.show
+    pub(crate) fn add_timer(&mut self, token: TimerToken) {
+        self.timers.push(token);
+    }
+
+    /// If `token` is one of ours, forget it, remove the toasts that have
+    /// finished, and return `true`.
+    pub(crate) fn timer_fired(&mut self, token: TimerToken, now: Instant) -> bool {
+        match self.timers.iter().position(|t| *t == token) {
+            Some(idx) => {
+                self.timers.remove(idx);
+                self.toasts.retain(|shown| shown.end() > now);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The number of toasts being shown.
+    #[cfg(test)]
+    pub(crate) fn len(&self) -> usize {
+        self.toasts.len()
+    }
+
+    /// Whether some toast is sliding in or out, and needs animation frames.
+    pub(crate) fn is_animating(&self, now: Instant) -> bool {
+        self.toasts.iter().any(|shown| shown.is_sliding(now))
+    }
+
+    pub(crate) fn paint(&self, piet: &mut Piet, window_size: Size, now: Instant, env: &Env) {
+        if self.toasts.is_empty() {
+            return;
+        }
+        let font = match piet
+            .text()
+            .new_font_by_name(env.get(theme::FONT_NAME), env.get(theme::TEXT_SIZE_NORMAL))
+            .build()
+        {
+            Ok(font) => font,
+            Err(e) => {
+                log::warn!(target: "druid::paint", "failed to load font for toasts: {}", e);
+                return;
+            }
+        };
+
+        // the distance from the edge to the next toast, for each edge.
+        let (mut top, mut bottom) = (TOAST_MARGIN, TOAST_MARGIN);
+        for shown in self.toasts.iter().filter(|shown| shown.end() > now) {
+            let layout = match piet
+                .text()
+                .new_text_layout(&font, &shown.toast.message)
+                .build()
+            {
+                Ok(layout) => layout,
+                Err(e) => {
+                    log::warn!(target: "druid::paint", "failed to lay out toast: {}", e);
+                    continue;
+                }
+            };
+            let width = layout.width() + TOAST_PADDING * 2.0;
+            let position = shown.toast.position;
+            let hidden = shown.hidden_fraction(now) * (TOAST_HEIGHT + TOAST_MARGIN);
+            let y = if position.is_top() {
+                let y = top - hidden;
+                top += TOAST_HEIGHT + TOAST_MARGIN;
+                y
+            } else {
+                let y = window_size.height - bottom - TOAST_HEIGHT + hidden;
+                bottom += TOAST_HEIGHT + TOAST_MARGIN;
+                y
+            };
+            let origin = Point::new(position.x(width, window_size.width), y);
+            let rect = Rect::from_origin_size(origin, (width, TOAST_HEIGHT));
+            let panel = rect.to_rounded_rect(4.0);
+            piet.fill(panel, &env.get(theme::BACKGROUND_LIGHT));
+            piet.stroke(panel, &env.get(theme::BORDER_LIGHT), 1.0);
+            let baseline = (TOAST_HEIGHT + env.get(theme::TEXT_SIZE_NORMAL) * 0.7) / 2.0;
+            piet.draw_text(
+                &layout,
+                origin + Vec2::new(TOAST_PADDING, baseline),
+                &env.get(theme::LABEL_COLOR),
+            );
+        }
+    }
+}
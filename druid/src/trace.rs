@@ -0,0 +1,25 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Spans for the passes over the widget tree, when the `tracing` feature
+//! is enabled.
+
+/// Enter a span at the given level, which lasts until the end of the
+/// enclosing block.
+macro_rules! enter_span {
+    ($level:ident, $name:literal, $($fields:tt)*) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::span!(tracing::Level::$level, $name, $($fields)*).entered();
+    };
+}
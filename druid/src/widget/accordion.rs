@@ -0,0 +1,197 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A stack of sections, where only one is open at a time.
+
+use std::time::Duration;
+
+use crate::kurbo::{Point, Size};
+use crate::widget::collapsible::Section;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, Lens, LifeCycle,
+    LifeCycleCtx, PaintCtx, UpdateCtx, Widget,
+};
+
+/// A stack of sections, each with a header and a body, where opening one
+/// section closes the others.
+///
+/// The index of the open section is read from, and written back to, an
+/// `Option<usize>` in the data, through a [`Lens`]; `None` means that
+/// every section is closed. Clicking the header of the open section closes
+/// it.
+///
+/// Sections open and close like a [`Collapsible`], so when the open
+/// section changes, one body closes while the other opens. The up and
+/// down arrow keys move keyboard focus between the headers, and tab skips
+/// over the bodies of closed sections.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Accordion, Label};
+/// use druid::{Data, Lens};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Preferences {
+///     open_group: Option<usize>,
+/// }
+///
+/// let preferences = Accordion::new(Preferences::open_group)
+///     .with_section(Label::new("Sounds"), Label::new("Tick while running"))
+///     .with_section(Label::new("Display"), Label::new("Show seconds"));
+/// ```
+///
+/// [`Lens`]: ../trait.Lens.html
+/// [`Collapsible`]: struct.Collapsible.html
+pub struct Accordion<T, L> {
+    sections: Vec<Section<T>>,
+    active: L,
+    animation: Duration,
+}
+
+impl<T: Data, L: Lens<T, Option<usize>>> Accordion<T, L> {
+    /// Create a new, empty `Accordion`, with the index of the open section
+    /// at `active`.
+    pub fn new(active: L) -> Self {
+        Accordion {
+            sections: Vec::new(),
+            active,
+            animation: super::DEFAULT_COLLAPSE_ANIMATION,
+        }
+    }
+
+    /// Builder-style variant of [`add_section`].
+    ///
+    /// [`add_section`]: #method.add_section
+    pub fn with_section(
+        mut self,
+        header: impl Widget<T> + 'static,
+        body: impl Widget<T> + 'static,
+    ) -> Self {
+        self.add_section(header, body);
+        self
+    }
+
+    /// Add a section at the bottom of the accordion.
+    ///
+    /// This should only be called before the accordion is added to the
+    /// widget tree.
+    pub fn add_section(
+        &mut self,
+        header: impl Widget<T> + 'static,
+        body: impl Widget<T> + 'static,
+    ) {
+        let mut section = Section::new(header, body);
+        section.set_animation(self.animation);
+        self.sections.push(section);
+    }
+
+    /// Builder-style method to set how long sections take to open or
+    /// close. `Duration::from_secs(0)` turns the animation off.
+    pub fn with_animation_duration(mut self, duration: Duration) -> Self {
+        self.animation = duration;
+        for section in &mut self.sections {
+            section.set_animation(duration);
+        }
+        self
+    }
+
+    /// The index of the section whose header has focus, if any.
+    fn focused_header(&self, ctx: &EventCtx) -> Option<usize> {
+        self.sections
+            .iter()
+            .position(|section| ctx.focus_widget == Some(section.header_id()))
+    }
+}
+
+impl<T: Data, L: Lens<T, Option<usize>>> Widget<T> for Accordion<T, L> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            if let Some(focused) = self.focused_header(ctx) {
+                let target = if HotKey::new(None, KeyCode::ArrowUp).matches(key) {
+                    focused.checked_sub(1)
+                } else if HotKey::new(None, KeyCode::ArrowDown).matches(key) {
+                    Some(focused + 1).filter(|i| *i < self.sections.len())
+                } else {
+                    None
+                };
+                if let Some(target) = target {
+                    ctx.set_focus(self.sections[target].header_id());
+                    ctx.set_handled();
+                    return;
+                }
+            }
+        }
+
+        for (i, section) in self.sections.iter_mut().enumerate() {
+            if section.event(ctx, event, data, env) {
+                self.active.with_mut(data, |active| {
+                    *active = if *active == Some(i) { None } else { Some(i) };
+                });
+            }
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let active = self.active.with(data, |active| *active);
+            for (i, section) in self.sections.iter_mut().enumerate() {
+                section.set_open(active == Some(i), false);
+            }
+        }
+        for section in &mut self.sections {
+            section.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let was_active = self.active.with(old_data, |active| *active);
+        let active = self.active.with(data, |active| *active);
+        if was_active != active {
+            for (i, section) in self.sections.iter_mut().enumerate() {
+                if section.set_open(active == Some(i), true) {
+                    ctx.request_anim_frame();
+                }
+            }
+            // closed bodies are left out of the focus chain.
+            ctx.children_changed();
+        }
+        for section in &mut self.sections {
+            section.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Accordion");
+
+        let section_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(bc.max().width, f64::INFINITY),
+        );
+        let mut width = 0.0_f64;
+        let mut y = 0.0;
+        for section in &mut self.sections {
+            let size = section.layout(ctx, &section_bc, Point::new(0.0, y), data, env);
+            width = width.max(size.width);
+            y += size.height;
+        }
+        bc.constrain(Size::new(width, y))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for section in &mut self.sections {
+            section.paint(ctx, data, env);
+        }
+    }
+}
@@ -133,6 +133,50 @@ impl<T: Data> Widget<T> for Align<T> {
         my_size
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let width = self.child.min_intrinsic_width(ctx, height, data, env);
+        width * self.width_factor.unwrap_or(1.)
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let width = self.child.max_intrinsic_width(ctx, height, data, env);
+        width * self.width_factor.unwrap_or(1.)
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let height = self.child.min_intrinsic_height(ctx, width, data, env);
+        height * self.height_factor.unwrap_or(1.)
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let height = self.child.max_intrinsic_height(ctx, width, data, env);
+        height * self.height_factor.unwrap_or(1.)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.child.paint_with_offset(ctx, data, env);
     }
@@ -0,0 +1,187 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that keeps its child at a fixed aspect ratio.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// A widget that sizes its child to a fixed aspect ratio, as large as will
+/// fit, and centers it.
+///
+/// The ratio is width divided by height, so a ratio of `2.0` makes the child
+/// twice as wide as it is tall, and `1.0` keeps it square, which suits a
+/// round [`Gauge`].
+///
+/// Where the constraints are bounded, this widget takes all of the space
+/// it is given. If they are unbounded on one axis, the child is as big as
+/// the other axis allows, and this widget is the child's size on the
+/// unbounded axis. If they are unbounded on both, the child is laid out
+/// freely, and its width decides its height.
+///
+/// [`Gauge`]: struct.Gauge.html
+pub struct AspectRatioBox<T, W> {
+    child: WidgetPod<T, W>,
+    ratio: f64,
+}
+
+impl<T, W: Widget<T>> AspectRatioBox<T, W> {
+    /// Create a new `AspectRatioBox`, keeping `child` at `ratio`, its width
+    /// divided by its height.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is not a positive, finite number.
+    pub fn new(child: W, ratio: f64) -> Self {
+        assert!(
+            ratio > 0. && ratio.is_finite(),
+            "AspectRatioBox ratio must be positive and finite, not {}",
+            ratio
+        );
+        AspectRatioBox {
+            child: WidgetPod::new(child),
+            ratio,
+        }
+    }
+}
+
+/// The largest size with width divided by height equal to `ratio` that fits
+/// within `max`, or `None` if `max` is unbounded on both axes.
+fn fit(ratio: f64, max: Size) -> Option<Size> {
+    match (max.width.is_finite(), max.height.is_finite()) {
+        (true, true) if max.width / max.height > ratio => {
+            Some(Size::new(max.height * ratio, max.height))
+        }
+        (true, _) => Some(Size::new(max.width, max.width / ratio)),
+        (false, true) => Some(Size::new(max.height * ratio, max.height)),
+        (false, false) => None,
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for AspectRatioBox<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("AspectRatioBox");
+
+        let child_size = match fit(self.ratio, bc.max()) {
+            Some(size) => size,
+            None => {
+                let free = self.child.layout(ctx, &bc.loosen(), data, env);
+                Size::new(free.width, free.width / self.ratio)
+            }
+        };
+        let child_size = self
+            .child
+            .layout(ctx, &BoxConstraints::tight(child_size), data, env);
+
+        let mut my_size = child_size;
+        if bc.is_width_bounded() {
+            my_size.width = bc.max().width;
+        }
+        if bc.is_height_bounded() {
+            my_size.height = bc.max().height;
+        }
+        let my_size = bc.constrain(my_size);
+
+        let origin = Point::new(
+            ((my_size.width - child_size.width) / 2.).round(),
+            ((my_size.height - child_size.height) / 2.).round(),
+        );
+        self.child
+            .set_layout_rect(Rect::from_origin_size(origin, child_size));
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(my_size));
+        my_size
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        if height.is_finite() {
+            height * self.ratio
+        } else {
+            self.child.max_intrinsic_width(ctx, height, data, env)
+        }
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        if width.is_finite() {
+            width / self.ratio
+        } else {
+            let width = self.child.max_intrinsic_width(ctx, width, data, env);
+            width / self.ratio
+        }
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint_with_offset(ctx, data, env);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wide_space() {
+        let size = fit(2.0, Size::new(400., 100.));
+        assert_eq!(size, Some(Size::new(200., 100.)));
+    }
+
+    #[test]
+    fn tall_space() {
+        let size = fit(2.0, Size::new(100., 400.));
+        assert_eq!(size, Some(Size::new(100., 50.)));
+    }
+
+    #[test]
+    fn unbounded_width() {
+        let size = fit(0.5, Size::new(f64::INFINITY, 100.));
+        assert_eq!(size, Some(Size::new(50., 100.)));
+    }
+
+    #[test]
+    fn unbounded_height() {
+        let size = fit(0.5, Size::new(100., f64::INFINITY));
+        assert_eq!(size, Some(Size::new(100., 200.)));
+    }
+
+    #[test]
+    fn unbounded() {
+        assert_eq!(fit(1.0, Size::new(f64::INFINITY, f64::INFINITY)), None);
+    }
+}
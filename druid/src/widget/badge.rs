@@ -0,0 +1,116 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A small label for showing a count or status over another widget.
+
+use crate::kurbo::{Point, Size};
+use crate::piet::{FontBuilder, PietText, PietTextLayout, Text, TextLayout, TextLayoutBuilder};
+use crate::theme;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    RenderContext, UpdateCtx, Widget,
+};
+
+/// Space on either side of the text.
+const BADGE_X_PADDING: f64 = 4.0;
+/// The badge's height, as a multiple of its text size.
+const BADGE_HEIGHT_FACTOR: f64 = 1.4;
+
+type TextFn<T> = dyn Fn(&T) -> Option<String>;
+
+/// A small pill with some text in it, such as a count, styled by the
+/// `BADGE_*` keys in [`theme`].
+///
+/// The text comes from a function of the data; when it returns `None`, the
+/// badge has no size and paints nothing. A badge with a single character is
+/// round.
+///
+/// You will generally want to create this with [`WidgetExt::with_badge`],
+/// which puts it in the corner of another widget.
+///
+/// [`theme`]: ../theme/index.html
+/// [`WidgetExt::with_badge`]: ../trait.WidgetExt.html#method.with_badge
+pub struct Badge<T> {
+    text_fn: Box<TextFn<T>>,
+    text: Option<String>,
+}
+
+impl<T: Data> Badge<T> {
+    /// Create a new badge, showing the text returned by `text`.
+    pub fn new(text: impl Fn(&T) -> Option<String> + 'static) -> Self {
+        Badge {
+            text_fn: Box::new(text),
+            text: None,
+        }
+    }
+}
+
+fn text_layout(t: &mut PietText, text: &str, env: &Env) -> PietTextLayout {
+    let font = t
+        .new_font_by_name(env.get(theme::FONT_NAME), env.get(theme::BADGE_TEXT_SIZE))
+        .build()
+        .unwrap();
+    t.new_text_layout(&font, text).build().unwrap()
+}
+
+impl<T: Data> Widget<T> for Badge<T> {
+    fn event(&mut self, _ctx: &mut EventCtx, _event: &Event, _data: &mut T, _env: &Env) {}
+
+    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.text = (self.text_fn)(data);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, _env: &Env) {
+        let text = (self.text_fn)(data);
+        if text != self.text {
+            self.text = text;
+            ctx.request_layout();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &T, env: &Env) -> Size {
+        bc.debug_check("Badge");
+
+        let text = match &self.text {
+            Some(text) => text,
+            None => return bc.constrain(Size::ZERO),
+        };
+        let height = (env.get(theme::BADGE_TEXT_SIZE) * BADGE_HEIGHT_FACTOR).round();
+        let text_width = text_layout(ctx.text(), text, env).width();
+        let width = (text_width + 2. * BADGE_X_PADDING).max(height);
+        bc.constrain(Size::new(width, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
+        let text = match &self.text {
+            Some(text) => text,
+            None => return,
+        };
+        let size = ctx.size();
+        let pill = size.to_rounded_rect(size.height / 2.);
+        ctx.fill(pill, &env.get(theme::BADGE_COLOR));
+
+        let text_size = env.get(theme::BADGE_TEXT_SIZE);
+        let layout = text_layout(ctx.text(), text, env);
+        // the baseline sits about four fifths down a line of text.
+        let line_height = text_size * 1.2;
+        let origin = Point::new(
+            (size.width - layout.width()) / 2.,
+            (size.height - line_height) / 2. + line_height * 0.8,
+        );
+        ctx.draw_text(&layout, origin, &env.get(theme::BADGE_TEXT_COLOR));
+    }
+}
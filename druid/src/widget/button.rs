@@ -13,9 +13,13 @@
 // limitations under the License.
 
 //! A button widget.
+use std::ops::Range;
+
 use crate::theme;
 use crate::widget::prelude::*;
-use crate::widget::{Click, ControllerHost, Label, LabelText};
+use std::time::Duration;
+
+use crate::widget::{Click, ClickRepeat, ControllerHost, Label, LabelText};
 
 use crate::{Affine, Data, Insets, LinearGradient, Point, Rect, RenderContext, UnitPoint, Widget};
 
@@ -25,9 +29,28 @@ use crate::{Affine, Data, Insets, LinearGradient, Point, Rect, RenderContext, Un
 const LABEL_INSETS: Insets = Insets::uniform_xy(8., 2.);
 
 /// A button with a text label.
+///
+/// Buttons can take keyboard focus; a focused button is clicked by pressing
+/// space or return, and passes focus on with tab and shift+tab.
+///
+/// A button can also have an access key, marked with an `&` in its label:
+/// a button labelled `"&Start"` is shown as "Start", and is focused and
+/// clicked by alt+S. The key is underlined while alt is held. Write `&&`
+/// for an ampersand. See [`LifeCycleCtx::register_access_key`] for how
+/// buttons sharing a key behave.
+///
+/// [`LifeCycleCtx::register_access_key`]: ../struct.LifeCycleCtx.html#method.register_access_key
 pub struct Button<T> {
     label: Label<T>,
     label_size: Size,
+    access_key: Option<AccessKey>,
+}
+
+/// A button's access key, and where it is in the label.
+#[derive(Debug, PartialEq)]
+struct AccessKey {
+    key: char,
+    range: Range<usize>,
 }
 
 impl<T: Data> Button<T> {
@@ -36,6 +59,9 @@ impl<T: Data> Button<T> {
     /// Use the `.on_click` method to provide a closure to be called when the
     /// button is clicked.
     ///
+    /// The access key is only read from a label that is a plain string;
+    /// localized and dynamic labels are shown as they are.
+    ///
     /// # Examples
     ///
     /// ```
@@ -46,9 +72,17 @@ impl<T: Data> Button<T> {
     /// });
     /// ```
     pub fn new(text: impl Into<LabelText<T>>) -> Button<T> {
+        let (text, access_key) = match text.into() {
+            LabelText::Specific(text) => {
+                let (text, access_key) = parse_access_key(&text);
+                (LabelText::Specific(text), access_key)
+            }
+            text => (text, None),
+        };
         Button {
             label: Label::new(text),
             label_size: Size::ZERO,
+            access_key,
         }
     }
 
@@ -59,6 +93,34 @@ impl<T: Data> Button<T> {
     ) -> ControllerHost<Self, Click<T>> {
         ControllerHost::new(self, Click::new(f))
     }
+
+    /// Provide a closure to be called when this button is pressed, and
+    /// repeatedly every `interval` while it is held down.
+    ///
+    /// Repeating starts after [`DEFAULT_REPEAT_DELAY`]; to change the delay
+    /// use a [`ClickRepeat`] as the button's controller directly.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use druid::widget::Button;
+    ///
+    /// let button = Button::new("+")
+    ///     .on_click_repeat(Duration::from_millis(100), |_ctx, data: &mut u32, _env| {
+    ///         *data += 1;
+    ///     });
+    /// ```
+    ///
+    /// [`DEFAULT_REPEAT_DELAY`]: constant.DEFAULT_REPEAT_DELAY.html
+    /// [`ClickRepeat`]: struct.ClickRepeat.html
+    pub fn on_click_repeat(
+        self,
+        interval: Duration,
+        f: impl Fn(&mut EventCtx, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, ClickRepeat<T>> {
+        ControllerHost::new(self, ClickRepeat::new(interval, f))
+    }
 }
 
 impl<T: Data> Widget<T> for Button<T> {
@@ -68,19 +130,25 @@ impl<T: Data> Widget<T> for Button<T> {
                 ctx.set_active(true);
                 ctx.request_paint();
             }
-            Event::MouseUp(_) => {
-                if ctx.is_active() {
-                    ctx.set_active(false);
-                    ctx.request_paint();
-                }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
             }
+            Event::KeyDown(key) if ctx.handle_focus_key(key) => ctx.set_handled(),
             _ => (),
         }
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
-        if let LifeCycle::HotChanged(_) = event {
-            ctx.request_paint();
+        match event {
+            LifeCycle::WidgetAdded => {
+                ctx.register_for_focus();
+                if let Some(access_key) = &self.access_key {
+                    ctx.register_access_key(access_key.key);
+                }
+            }
+            LifeCycle::HotChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
         self.label.lifecycle(ctx, event, data, env)
     }
@@ -97,12 +165,13 @@ impl<T: Data> Widget<T> for Button<T> {
         env: &Env,
     ) -> Size {
         bc.debug_check("Button");
-        let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value());
+        let scale = env.get(theme::UI_SCALE);
+        let padding = Size::new(LABEL_INSETS.x_value(), LABEL_INSETS.y_value()) * scale;
         let label_bc = bc.shrink(padding).loosen();
         self.label_size = self.label.layout(layout_ctx, &label_bc, data, env);
         // HACK: to make sure we look okay at default sizes when beside a textbox,
         // we make sure we will have at least the same height as the default textbox.
-        let min_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let min_height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
 
         bc.constrain(Size::new(
             self.label_size.width + padding.width,
@@ -147,6 +216,11 @@ impl<T: Data> Widget<T> for Button<T> {
         ctx.fill(rounded_rect, &bg_gradient);
 
         let label_offset = (size.to_vec2() - self.label_size.to_vec2()) / 2.0;
+        let underline = match &self.access_key {
+            Some(access_key) if ctx.show_access_keys() => Some(access_key.range.clone()),
+            _ => None,
+        };
+        self.label.set_underline(underline);
 
         ctx.with_save(|ctx| {
             ctx.transform(Affine::translate(label_offset));
@@ -154,3 +228,61 @@ impl<T: Data> Widget<T> for Button<T> {
         });
     }
 }
+
+/// Remove the `&` markers from a label, returning the text to show and the
+/// access key, which is the character after the first lone `&`.
+fn parse_access_key(label: &str) -> (String, Option<AccessKey>) {
+    let mut text = String::with_capacity(label.len());
+    let mut access_key = None;
+    let mut chars = label.chars();
+    while let Some(c) = chars.next() {
+        if c != '&' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some(c) if c != '&' && !c.is_whitespace() => {
+                if access_key.is_none() {
+                    let start = text.len();
+                    access_key = Some(AccessKey {
+                        key: c.to_lowercase().next().unwrap_or(c),
+                        range: start..start + c.len_utf8(),
+                    });
+                }
+                text.push(c);
+            }
+            Some(c) if c != '&' => {
+                text.push('&');
+                text.push(c);
+            }
+            _ => text.push('&'),
+        }
+    }
+    (text, access_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn access_keys_are_parsed_from_labels() {
+        let key = |key, range| Some(AccessKey { key, range });
+        assert_eq!(parse_access_key("&Start"), ("Start".into(), key('s', 0..1)));
+        assert_eq!(parse_access_key("Re&set"), ("Reset".into(), key('s', 2..3)));
+        assert_eq!(
+            parse_access_key("Save && &Quit"),
+            ("Save & Quit".into(), key('q', 7..8))
+        );
+        // only the first marker counts, and a trailing one is kept.
+        assert_eq!(parse_access_key("&a&b&"), ("ab&".into(), key('a', 0..1)));
+        assert_eq!(
+            parse_access_key("Fish & chips"),
+            ("Fish & chips".into(), None)
+        );
+        assert_eq!(
+            parse_access_key("&\u{e9}t\u{e9}"),
+            ("\u{e9}t\u{e9}".into(), key('\u{e9}', 0..2))
+        );
+    }
+}
@@ -0,0 +1,278 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that caches the painting of its child.
+
+use crate::debug_overlay::DebugBox;
+use crate::kurbo::{Affine, Point, Rect, Size};
+use crate::piet::{Device, ImageFormat, InterpolationMode, RenderContext};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// A widget that records the painting of its child into a bitmap, and
+/// draws that bitmap instead of repainting the child.
+///
+/// The child is only painted again when it (or one of its descendants)
+/// requests a paint, or when its size changes. This is useful for large
+/// subtrees that rarely change, but that share a window with something
+/// that repaints frequently, such as an animation.
+///
+/// Painting into the cache happens on the CPU, so this is a poor fit for
+/// widgets that repaint often. Widgets that paint with a z-index (see
+/// [`PaintCtx::paint_with_z_index`]) cannot be cached; if the child does so,
+/// it is painted directly on every frame.
+///
+/// You will generally want to create this with [`WidgetExt::cached`].
+///
+/// [`PaintCtx::paint_with_z_index`]: ../struct.PaintCtx.html#method.paint_with_z_index
+/// [`WidgetExt::cached`]: ../trait.WidgetExt.html#method.cached
+pub struct Cached<T, W> {
+    child: WidgetPod<T, W>,
+    resolution: f64,
+    device: Option<Device>,
+    cache: Option<PaintCache>,
+    // set if the child paints with a z-index, which we can't record, or if
+    // recording failed too often.
+    uncacheable: bool,
+    // recording failures since the last success.
+    failures: u32,
+}
+
+/// How many times in a row recording may fail before `Cached` stops trying.
+const MAX_RECORD_FAILURES: u32 = 3;
+
+/// The recorded painting of a widget.
+pub(super) struct PaintCache {
+    pub(super) rect: Rect,
+    pub(super) scale: f64,
+    width: usize,
+    height: usize,
+    /// Premultiplied RGBA.
+    pub(super) pixels: Vec<u8>,
+    /// The bounds of the widgets that were recorded for the debug overlay,
+    /// in the coordinates of `rect`.
+    debug_boxes: Vec<DebugBox>,
+    /// Whether `Env::DEBUG_PAINT` was set when recording.
+    pub(super) debug_paint: bool,
+}
+
+impl<T, W: Widget<T>> Cached<T, W> {
+    /// Create a new `Cached` widget wrapping `child`.
+    pub fn new(child: W) -> Self {
+        Cached {
+            child: WidgetPod::new(child),
+            resolution: 1.0,
+            device: None,
+            cache: None,
+            uncacheable: false,
+            failures: 0,
+        }
+    }
+
+    /// Builder-style method to set the resolution of the cached bitmap,
+    /// relative to the current transform of the render context.
+    ///
+    /// On platforms where the window's pixel density is not part of the
+    /// render context's transform, you should set this to the window's
+    /// scale factor to avoid blurry output.
+    pub fn with_resolution(mut self, resolution: f64) -> Self {
+        self.resolution = resolution;
+        self
+    }
+}
+
+/// Paint `child` into a new bitmap covering `rect`, at `scale` pixels per
+/// display point.
+///
+/// Returns `None` if the child's painting could not be recorded, because it
+/// paints with a z-index.
+pub(super) fn record<T: Data, W: Widget<T>>(
+    device: &mut Option<Device>,
+    child: &mut WidgetPod<T, W>,
+    ctx: &mut PaintCtx,
+    rect: Rect,
+    scale: f64,
+    data: &T,
+    env: &Env,
+) -> Result<Option<PaintCache>, crate::piet::Error> {
+    let width = (rect.width() * scale).ceil().max(1.0) as usize;
+    let height = (rect.height() * scale).ceil().max(1.0) as usize;
+
+    if device.is_none() {
+        *device = Some(Device::new()?);
+    }
+    let device = device.as_mut().unwrap();
+    let mut target = device.bitmap_target(width, height, scale)?;
+    let (has_z_ops, debug_boxes) = {
+        let mut piet = target.render_context();
+        piet.transform(Affine::translate(-rect.origin().to_vec2()));
+        let to_local = piet.current_transform().inverse();
+        let mut child_ctx = PaintCtx {
+            render_ctx: &mut piet,
+            window_id: ctx.window_id,
+            z_ops: Vec::new(),
+            debug_boxes: Vec::new(),
+            record_paint: ctx.record_paint,
+            region: rect.into(),
+            base_state: ctx.base_state,
+            focus_widget: ctx.focus_widget,
+            show_access_keys: ctx.show_access_keys,
+            // direct2d applies a bitmap's scale as its dpi, where cairo
+            // makes it part of the transform.
+            window_scale: if cfg!(target_os = "windows") {
+                scale
+            } else {
+                1.0
+            },
+        };
+        child.paint(&mut child_ctx, data, env);
+        let has_z_ops = !child_ctx.z_ops.is_empty();
+        let mut debug_boxes = child_ctx.debug_boxes;
+        for debug_box in &mut debug_boxes {
+            debug_box.rect = to_local.transform_rect_bbox(debug_box.rect);
+        }
+        piet.finish()?;
+        (has_z_ops, debug_boxes)
+    };
+
+    if has_z_ops {
+        return Ok(None);
+    }
+
+    let pixels = target.into_raw_pixels(ImageFormat::RgbaPremul)?;
+    Ok(Some(PaintCache {
+        rect,
+        scale,
+        width,
+        height,
+        pixels,
+        debug_boxes,
+        debug_paint: env.get(Env::DEBUG_PAINT),
+    }))
+}
+
+/// The number of bitmap pixels per display point that painting at the
+/// current transform needs, for a sharp result.
+pub(super) fn current_scale(ctx: &mut PaintCtx) -> f64 {
+    let coeffs = ctx.render_ctx.current_transform().as_coeffs();
+    (coeffs[0] * coeffs[3] - coeffs[1] * coeffs[2]).abs().sqrt()
+}
+
+impl PaintCache {
+    /// Draw the recorded painting where it was recorded from.
+    pub(super) fn draw(&self, ctx: &mut PaintCtx) {
+        let transform = ctx.render_ctx.current_transform();
+        ctx.debug_boxes
+            .extend(self.debug_boxes.iter().map(|debug_box| DebugBox {
+                rect: transform.transform_rect_bbox(debug_box.rect),
+                ..*debug_box
+            }));
+        if self.rect.area() == 0.0 {
+            return;
+        }
+        match ctx.make_image(
+            self.width,
+            self.height,
+            &self.pixels,
+            ImageFormat::RgbaPremul,
+        ) {
+            Ok(image) => ctx.draw_image(&image, self.rect, InterpolationMode::Bilinear),
+            Err(e) => log::warn!(target: "druid::paint", "failed to draw cached paint: {}", e),
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Cached<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Cached");
+
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if self.uncacheable {
+            self.child.paint(ctx, data, env);
+            return;
+        }
+
+        let rect = self.child.paint_rect();
+        let scale = current_scale(ctx) * self.resolution;
+
+        let is_valid = match self.cache.as_ref() {
+            Some(cache) => {
+                !self.child.state().needs_inval
+                    && cache.rect.origin() == rect.origin()
+                    && cache.rect.size() == rect.size()
+                    && cache.scale == scale
+                    && cache.debug_paint == env.get(Env::DEBUG_PAINT)
+            }
+            None => false,
+        };
+
+        if !is_valid {
+            self.cache = None;
+            match record(
+                &mut self.device,
+                &mut self.child,
+                ctx,
+                rect,
+                scale,
+                data,
+                env,
+            ) {
+                Ok(Some(cache)) => {
+                    self.cache = Some(cache);
+                    self.failures = 0;
+                }
+                Ok(None) => self.uncacheable = true,
+                Err(e) => {
+                    // this may be transient, such as a lost device; try again
+                    // on the next paint, but not forever.
+                    log::warn!(
+                        target: "druid::paint",
+                        "failed to cache paint, painting directly: {}",
+                        e
+                    );
+                    self.device = None;
+                    self.failures += 1;
+                    self.uncacheable = self.failures >= MAX_RECORD_FAILURES;
+                }
+            }
+            if self.cache.is_none() {
+                self.child.paint(ctx, data, env);
+                return;
+            }
+        }
+
+        self.cache.as_ref().unwrap().draw(ctx);
+    }
+}
@@ -0,0 +1,243 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that paints from a recorded list of drawing commands.
+
+use crate::kurbo::{BezPath, Shape, Size};
+use crate::piet::{PaintBrush, StrokeStyle};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    RenderContext, UpdateCtx, Widget,
+};
+
+/// The accuracy with which shapes are flattened into paths when recorded.
+const PATH_TOLERANCE: f64 = 0.1;
+
+/// A function that draws the contents of a [`Canvas`].
+///
+/// [`Canvas`]: struct.Canvas.html
+type DrawFn<T> = dyn FnMut(&mut CanvasCtx, &T);
+
+/// A function that picks out the part of the data that a [`Canvas`] draws.
+///
+/// [`Canvas`]: struct.Canvas.html
+type KeyFn<T, K> = dyn Fn(&T) -> K;
+
+/// A widget that draws with a closure, and keeps what it drew.
+///
+/// The closure draws into a [`CanvasCtx`], which records each command
+/// rather than painting it. When the widget is painted, the recorded
+/// commands are replayed, so the closure is only called again when the
+/// canvas changes size, or when the *invalidation key* changes.
+///
+/// The key is computed from the data by a function passed to
+/// [`Canvas::new`], and compared with [`Data::same`]. It should cover
+/// everything the drawing depends on; if the drawing uses the [`Env`], any
+/// value taken from it that might change belongs in the key as well.
+///
+/// This is useful when drawing is expensive, but the canvas is repainted
+/// often because of something unrelated, such as a sibling's animation.
+///
+/// # Examples
+///
+/// A sparkline of past values, only redrawn when the history changes:
+///
+/// ```
+/// use druid::kurbo::{BezPath, Point};
+/// use druid::widget::Canvas;
+/// use druid::{theme, Color};
+/// use std::sync::Arc;
+///
+/// let sparkline = Canvas::new(
+///     |history: &Arc<Vec<f64>>| history.clone(),
+///     |ctx, history| {
+///         let size = ctx.size();
+///         let step = size.width / (history.len().max(2) - 1) as f64;
+///         let mut path = BezPath::new();
+///         for (i, value) in history.iter().enumerate() {
+///             let point = Point::new(i as f64 * step, size.height * (1.0 - value));
+///             if i == 0 {
+///                 path.move_to(point);
+///             } else {
+///                 path.line_to(point);
+///             }
+///         }
+///         let color = ctx.env().get(theme::PRIMARY_LIGHT);
+///         ctx.stroke(path, color, 1.0);
+///     },
+/// );
+/// ```
+///
+/// [`CanvasCtx`]: struct.CanvasCtx.html
+/// [`Canvas::new`]: #method.new
+/// [`Data::same`]: ../trait.Data.html#tymethod.same
+/// [`Env`]: ../struct.Env.html
+pub struct Canvas<T, K> {
+    key_fn: Box<KeyFn<T, K>>,
+    draw: Box<DrawFn<T>>,
+    // the key and size the commands were recorded for.
+    recorded: Option<(K, Size)>,
+    commands: Vec<DrawCommand>,
+}
+
+/// A context for drawing into a [`Canvas`].
+///
+/// Drawing methods record a command, which is replayed whenever the canvas
+/// is painted.
+///
+/// [`Canvas`]: struct.Canvas.html
+pub struct CanvasCtx<'a> {
+    size: Size,
+    env: &'a Env,
+    commands: &'a mut Vec<DrawCommand>,
+}
+
+enum DrawCommand {
+    Fill(BezPath, PaintBrush),
+    Stroke(BezPath, PaintBrush, f64, Option<StrokeStyle>),
+}
+
+impl<T, K: Data> Canvas<T, K> {
+    /// Create a new `Canvas`.
+    ///
+    /// `key_fn` computes the invalidation key from the data, and `draw` is
+    /// called to record the drawing whenever that key or the canvas size
+    /// changes.
+    pub fn new(
+        key_fn: impl Fn(&T) -> K + 'static,
+        draw: impl FnMut(&mut CanvasCtx, &T) + 'static,
+    ) -> Self {
+        Canvas {
+            key_fn: Box::new(key_fn),
+            draw: Box::new(draw),
+            recorded: None,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Throw away the recorded drawing, so that it is drawn again the next
+    /// time the canvas is painted.
+    ///
+    /// The caller is responsible for requesting a paint.
+    pub fn invalidate(&mut self) {
+        self.recorded = None;
+    }
+
+    fn is_current(&self, key: &K, size: Size) -> bool {
+        match &self.recorded {
+            Some((old_key, old_size)) => old_key.same(key) && *old_size == size,
+            None => false,
+        }
+    }
+}
+
+impl<'a> CanvasCtx<'a> {
+    /// The size of the canvas.
+    pub fn size(&self) -> Size {
+        self.size
+    }
+
+    /// The [`Env`] the canvas is painted with.
+    ///
+    /// [`Env`]: ../struct.Env.html
+    pub fn env(&self) -> &Env {
+        self.env
+    }
+
+    /// Fill a shape.
+    pub fn fill(&mut self, shape: impl Shape, brush: impl Into<PaintBrush>) {
+        let path = shape.into_bez_path(PATH_TOLERANCE);
+        self.commands.push(DrawCommand::Fill(path, brush.into()));
+    }
+
+    /// Stroke a shape.
+    pub fn stroke(&mut self, shape: impl Shape, brush: impl Into<PaintBrush>, width: f64) {
+        let path = shape.into_bez_path(PATH_TOLERANCE);
+        self.commands
+            .push(DrawCommand::Stroke(path, brush.into(), width, None));
+    }
+
+    /// Stroke a shape, with a [`StrokeStyle`] for line joins, caps, and
+    /// dashes.
+    ///
+    /// [`StrokeStyle`]: ../piet/struct.StrokeStyle.html
+    pub fn stroke_styled(
+        &mut self,
+        shape: impl Shape,
+        brush: impl Into<PaintBrush>,
+        width: f64,
+        style: &StrokeStyle,
+    ) {
+        let path = shape.into_bez_path(PATH_TOLERANCE);
+        self.commands.push(DrawCommand::Stroke(
+            path,
+            brush.into(),
+            width,
+            Some(style.clone()),
+        ));
+    }
+}
+
+impl DrawCommand {
+    fn paint(&self, ctx: &mut PaintCtx) {
+        match self {
+            DrawCommand::Fill(path, brush) => ctx.fill(path, brush),
+            DrawCommand::Stroke(path, brush, width, None) => ctx.stroke(path, brush, *width),
+            DrawCommand::Stroke(path, brush, width, Some(style)) => {
+                ctx.stroke_styled(path, brush, *width, style)
+            }
+        }
+    }
+}
+
+impl<T: Data, K: Data> Widget<T> for Canvas<T, K> {
+    fn event(&mut self, _: &mut EventCtx, _: &Event, _: &mut T, _: &Env) {}
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, _: &LifeCycle, _: &T, _: &Env) {}
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, _: &Env) {
+        let key = (self.key_fn)(data);
+        let changed = match &self.recorded {
+            Some((old_key, _)) => !old_key.same(&key),
+            None => true,
+        };
+        if changed {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &T, _: &Env) -> Size {
+        bc.debug_check("Canvas");
+        bc.max()
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        let key = (self.key_fn)(data);
+        if !self.is_current(&key, size) {
+            self.commands.clear();
+            let mut canvas_ctx = CanvasCtx {
+                size,
+                env,
+                commands: &mut self.commands,
+            };
+            (self.draw)(&mut canvas_ctx, data);
+            self.recorded = Some((key, size));
+        }
+
+        for command in &self.commands {
+            command.paint(ctx);
+        }
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that sees events before its child.
+//!
+//! [`Controller`]: trait.Controller.html
+
+use crate::widget::Controller;
+use crate::{Env, Event, EventCtx, Widget};
+
+type CaptureFn<T> = dyn FnMut(&mut EventCtx, &Event, &mut T, &Env);
+
+/// A [`Controller`] that handles events in the capture phase, before they
+/// reach its child or any of the child's descendants.
+///
+/// By default a container passes an event on to its children and then
+/// handles it itself, if they did not (the bubble phase). A `Capture` runs
+/// its closure first instead; if the closure calls [`EventCtx::set_handled`],
+/// the child never sees the event. Broadcast commands cannot be handled, and
+/// always reach the child.
+///
+/// This is available as the [`capture`] method on [`WidgetExt`].
+///
+/// # Examples
+///
+/// While a dialog is open, keep clicks away from the controls beneath it:
+///
+/// ```
+/// use druid::widget::{Button, Flex};
+/// use druid::{Event, WidgetExt};
+///
+/// let controls = Flex::row()
+///     .with_child(Button::new("Start"))
+///     .with_child(Button::new("Stop"))
+///     .capture(|ctx, event, dialog_open: &mut bool, _env| {
+///         if let Event::MouseDown(_) | Event::MouseUp(_) = event {
+///             if *dialog_open {
+///                 ctx.set_handled();
+///             }
+///         }
+///     });
+/// ```
+///
+/// [`Controller`]: trait.Controller.html
+/// [`EventCtx::set_handled`]: ../struct.EventCtx.html#method.set_handled
+/// [`capture`]: ../trait.WidgetExt.html#method.capture
+/// [`WidgetExt`]: ../trait.WidgetExt.html
+pub struct Capture<T> {
+    handler: Box<CaptureFn<T>>,
+}
+
+impl<T> Capture<T> {
+    /// Create a new `Capture` controller that calls `handler` with each
+    /// event, before the child.
+    pub fn new(handler: impl FnMut(&mut EventCtx, &Event, &mut T, &Env) + 'static) -> Self {
+        Capture {
+            handler: Box::new(handler),
+        }
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for Capture<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        (self.handler)(ctx, event, data, env);
+        if !ctx.is_handled() {
+            child.event(ctx, event, data, env);
+        }
+    }
+}
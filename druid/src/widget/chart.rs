@@ -0,0 +1,526 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Simple line and bar charts.
+
+use std::sync::Arc;
+
+use crate::kurbo::{BezPath, Circle, Line, Point, Rect, Size};
+use crate::piet::{FontBuilder, PietText, PietTextLayout, Text, TextLayout, TextLayoutBuilder};
+use crate::theme;
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    RenderContext, UpdateCtx, Widget,
+};
+
+/// How many labelled ticks we aim for on the value axis.
+const TARGET_TICKS: f64 = 5.0;
+/// The length of a tick mark.
+const TICK_LENGTH: f64 = 4.0;
+/// Space between tick labels and the axis, and around the hover label.
+const LABEL_PADDING: f64 = 4.0;
+/// The fraction of its slot that each bar in a `BarChart` fills.
+const BAR_FILL: f64 = 0.7;
+
+/// A function that formats a value for display on a chart.
+type FormatFn = dyn Fn(f64) -> String;
+
+/// A line chart.
+///
+/// This type impls `Widget<Arc<Vec<f64>>>`; each value is a point on the
+/// line, and the points are spaced evenly from left to right.
+///
+/// The value axis is scaled to fit the data, with ticks at round numbers,
+/// unless a fixed range is set with [`with_range`]. Hovering over the chart
+/// shows the value of the nearest point.
+///
+/// [`with_range`]: #method.with_range
+pub struct LineChart {
+    chart: Chart,
+}
+
+/// A bar chart.
+///
+/// This type impls `Widget<Arc<Vec<f64>>>`, showing a bar for each value.
+///
+/// The value axis always includes zero, and is otherwise scaled to fit the
+/// data unless a fixed range is set with [`with_range`]. Hovering over a
+/// bar shows its value.
+///
+/// [`with_range`]: #method.with_range
+pub struct BarChart {
+    chart: Chart,
+}
+
+/// The state shared by the chart widgets.
+struct Chart {
+    range: Option<(f64, f64)>,
+    format: Box<FormatFn>,
+    hovered: Option<usize>,
+    // where the values were last plotted, for hit testing.
+    plot: Rect,
+}
+
+/// The value axis of a chart, fitted to some plot area.
+struct Axis {
+    min: f64,
+    max: f64,
+    plot: Rect,
+}
+
+impl LineChart {
+    /// Create a new `LineChart`.
+    pub fn new() -> LineChart {
+        LineChart {
+            chart: Chart::new(),
+        }
+    }
+
+    /// Builder-style method to fix the range of the value axis, instead of
+    /// scaling it to fit the data.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.chart.range = Some((min, max));
+        self
+    }
+
+    /// Builder-style method to set how values are formatted, on the axis
+    /// and when hovering.
+    pub fn with_formatter(mut self, format: impl Fn(f64) -> String + 'static) -> Self {
+        self.chart.format = Box::new(format);
+        self
+    }
+
+    /// The position of the point for `values[index]`.
+    fn point(axis: &Axis, len: usize, index: usize, value: f64) -> Point {
+        let x = if len > 1 {
+            axis.plot.x0 + axis.plot.width() * index as f64 / (len - 1) as f64
+        } else {
+            axis.plot.center().x
+        };
+        Point::new(x, axis.y(value))
+    }
+
+    /// The point nearest to `x`, if the chart has any.
+    fn index_at(plot: Rect, len: usize, x: f64) -> Option<usize> {
+        match len {
+            0 => None,
+            1 => Some(0),
+            _ => {
+                let step = plot.width() / (len - 1) as f64;
+                let index = ((x - plot.x0) / step).round();
+                Some(index.max(0.0).min((len - 1) as f64) as usize)
+            }
+        }
+    }
+}
+
+impl BarChart {
+    /// Create a new `BarChart`.
+    pub fn new() -> BarChart {
+        BarChart {
+            chart: Chart::new(),
+        }
+    }
+
+    /// Builder-style method to fix the range of the value axis, instead of
+    /// scaling it to fit the data.
+    pub fn with_range(mut self, min: f64, max: f64) -> Self {
+        self.chart.range = Some((min, max));
+        self
+    }
+
+    /// Builder-style method to set how values are formatted, on the axis
+    /// and when hovering.
+    pub fn with_formatter(mut self, format: impl Fn(f64) -> String + 'static) -> Self {
+        self.chart.format = Box::new(format);
+        self
+    }
+
+    /// The bar for `values[index]`.
+    fn bar(axis: &Axis, len: usize, index: usize, value: f64) -> Rect {
+        let slot = axis.plot.width() / len as f64;
+        let x0 = axis.plot.x0 + slot * (index as f64 + (1.0 - BAR_FILL) / 2.0);
+        let base = axis.y(0.0_f64.max(axis.min).min(axis.max));
+        Rect::new(x0, base, x0 + slot * BAR_FILL, axis.y(value))
+    }
+
+    /// The bar whose slot contains `x`, if any.
+    fn index_at(plot: Rect, len: usize, x: f64) -> Option<usize> {
+        if len == 0 || x < plot.x0 || x >= plot.x1 {
+            return None;
+        }
+        let slot = plot.width() / len as f64;
+        Some((((x - plot.x0) / slot) as usize).min(len - 1))
+    }
+}
+
+impl Default for LineChart {
+    fn default() -> Self {
+        LineChart::new()
+    }
+}
+
+impl Default for BarChart {
+    fn default() -> Self {
+        BarChart::new()
+    }
+}
+
+impl Chart {
+    fn new() -> Chart {
+        Chart {
+            range: None,
+            format: Box::new(default_format),
+            hovered: None,
+            plot: Rect::ZERO,
+        }
+    }
+
+    /// Track which value the mouse is over.
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        hit: impl Fn(Rect, f64) -> Option<usize>,
+    ) {
+        let hovered = match event {
+            Event::MouseMoved(mouse) if ctx.is_hot() => hit(self.plot, mouse.pos.x),
+            Event::MouseMoved(_) => None,
+            _ => return,
+        };
+        if hovered != self.hovered {
+            self.hovered = hovered;
+            ctx.request_paint();
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        if let LifeCycle::HotChanged(false) = event {
+            if self.hovered.take().is_some() {
+                ctx.request_paint();
+            }
+        }
+    }
+
+    fn layout(&self, bc: &BoxConstraints, env: &Env) -> Size {
+        bc.constrain(Size::new(
+            theme::scaled(env, theme::WIDE_WIDGET_WIDTH),
+            theme::scaled(env, theme::WIDE_WIDGET_WIDTH) / 2.0,
+        ))
+    }
+
+    fn text_layout(&self, text: &mut PietText, value: f64, env: &Env) -> PietTextLayout {
+        let font = text
+            .new_font_by_name(env.get(theme::FONT_NAME), env.get(theme::TEXT_SIZE_NORMAL))
+            .build()
+            .unwrap();
+        text.new_text_layout(&font, &(self.format)(value))
+            .build()
+            .unwrap()
+    }
+
+    /// Fit the value axis to `values`, and paint it.
+    fn paint_axis(&mut self, ctx: &mut PaintCtx, values: &[f64], zero: bool, env: &Env) -> Axis {
+        let (min, max) = self.range.unwrap_or_else(|| data_range(values, zero));
+        let (min, max, step) = match self.range {
+            Some(_) => (min, max, nice_step(max - min)),
+            None => nice_range(min, max),
+        };
+
+        let font_size = env.get(theme::TEXT_SIZE_NORMAL);
+        let ticks: Vec<(f64, PietTextLayout)> = tick_values(min, max, step)
+            .map(|value| (value, self.text_layout(ctx.text(), value, env)))
+            .collect();
+        let label_width = ticks
+            .iter()
+            .map(|(_, layout)| layout.width())
+            .fold(0.0, f64::max);
+
+        // leave room for half a line of text above the top label, and
+        // below the bottom one.
+        let size = ctx.size();
+        let plot = Rect::new(
+            label_width + LABEL_PADDING + TICK_LENGTH,
+            font_size / 2.0,
+            size.width,
+            (size.height - font_size / 2.0).max(font_size / 2.0),
+        );
+        let axis = Axis { min, max, plot };
+        self.plot = plot;
+
+        let axis_color = env.get(theme::BORDER_LIGHT);
+        let label_color = env.get(theme::LABEL_COLOR);
+        ctx.stroke(
+            Line::new((plot.x0, plot.y0), (plot.x0, plot.y1)),
+            &axis_color,
+            1.0,
+        );
+        ctx.stroke(
+            Line::new((plot.x0, plot.y1), (plot.x1, plot.y1)),
+            &axis_color,
+            1.0,
+        );
+        for (value, layout) in &ticks {
+            let y = axis.y(*value);
+            ctx.stroke(
+                Line::new((plot.x0 - TICK_LENGTH, y), (plot.x0, y)),
+                &axis_color,
+                1.0,
+            );
+            let origin = Point::new(label_width - layout.width(), y + font_size * 0.35);
+            ctx.draw_text(layout, origin, &label_color);
+        }
+        axis
+    }
+
+    /// Show `value` in a label just above `anchor`.
+    fn paint_hover(&self, ctx: &mut PaintCtx, anchor: Point, value: f64, env: &Env) {
+        let font_size = env.get(theme::TEXT_SIZE_NORMAL);
+        let layout = self.text_layout(ctx.text(), value, env);
+        let size = Size::new(
+            layout.width() + 2.0 * LABEL_PADDING,
+            font_size + 2.0 * LABEL_PADDING,
+        );
+        let bounds = ctx.size();
+        let x = (anchor.x - size.width / 2.0)
+            .min(bounds.width - size.width)
+            .max(0.0);
+        let y = (anchor.y - size.height - LABEL_PADDING).max(0.0);
+        let rect = Rect::from_origin_size((x, y), size).to_rounded_rect(LABEL_PADDING);
+
+        ctx.fill(rect, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(rect, &env.get(theme::BORDER_LIGHT), 1.0);
+        let origin = Point::new(x + LABEL_PADDING, y + LABEL_PADDING + font_size * 0.85);
+        ctx.draw_text(&layout, origin, &env.get(theme::LABEL_COLOR));
+    }
+}
+
+impl Axis {
+    /// The y coordinate of `value`.
+    fn y(&self, value: f64) -> f64 {
+        let fraction = if self.max > self.min {
+            (value - self.min) / (self.max - self.min)
+        } else {
+            0.5
+        };
+        self.plot.y1 - fraction * self.plot.height()
+    }
+}
+
+impl Widget<Arc<Vec<f64>>> for LineChart {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Arc<Vec<f64>>, _: &Env) {
+        let len = data.len();
+        self.chart
+            .event(ctx, event, |plot, x| LineChart::index_at(plot, len, x));
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _: &Arc<Vec<f64>>, _: &Env) {
+        self.chart.lifecycle(ctx, event);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old: &Arc<Vec<f64>>, data: &Arc<Vec<f64>>, _: &Env) {
+        if old != data {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _: &Arc<Vec<f64>>,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("LineChart");
+        self.chart.layout(bc, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Arc<Vec<f64>>, env: &Env) {
+        let axis = self.chart.paint_axis(ctx, data, false, env);
+        let len = data.len();
+        let color = env.get(theme::PRIMARY_LIGHT);
+
+        let mut line = BezPath::new();
+        for (i, value) in data.iter().enumerate() {
+            let point = LineChart::point(&axis, len, i, *value);
+            if i == 0 {
+                line.move_to(point);
+            } else {
+                line.line_to(point);
+            }
+        }
+        ctx.stroke(line, &color, 2.0);
+
+        if let Some(index) = self.chart.hovered.filter(|i| *i < len) {
+            let point = LineChart::point(&axis, len, index, data[index]);
+            ctx.fill(Circle::new(point, 4.0), &color);
+            self.chart.paint_hover(ctx, point, data[index], env);
+        }
+    }
+}
+
+impl Widget<Arc<Vec<f64>>> for BarChart {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Arc<Vec<f64>>, _: &Env) {
+        let len = data.len();
+        self.chart
+            .event(ctx, event, |plot, x| BarChart::index_at(plot, len, x));
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _: &Arc<Vec<f64>>, _: &Env) {
+        self.chart.lifecycle(ctx, event);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old: &Arc<Vec<f64>>, data: &Arc<Vec<f64>>, _: &Env) {
+        if old != data {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(
+        &mut self,
+        _: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        _: &Arc<Vec<f64>>,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("BarChart");
+        self.chart.layout(bc, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Arc<Vec<f64>>, env: &Env) {
+        let axis = self.chart.paint_axis(ctx, data, true, env);
+        let len = data.len();
+        let color = env.get(theme::PRIMARY_DARK);
+        let hover_color = env.get(theme::PRIMARY_LIGHT);
+
+        for (i, value) in data.iter().enumerate() {
+            let bar = BarChart::bar(&axis, len, i, *value);
+            let color = if self.chart.hovered == Some(i) {
+                &hover_color
+            } else {
+                &color
+            };
+            ctx.fill(bar, color);
+        }
+
+        if let Some(index) = self.chart.hovered.filter(|i| *i < len) {
+            let bar = BarChart::bar(&axis, len, index, data[index]);
+            let top = Point::new(bar.center().x, bar.y0.min(bar.y1));
+            self.chart.paint_hover(ctx, top, data[index], env);
+        }
+    }
+}
+
+/// The smallest and largest of `values`, widened to include zero if `zero`
+/// is set.
+fn data_range(values: &[f64], zero: bool) -> (f64, f64) {
+    let start = if zero {
+        (0.0, 0.0)
+    } else {
+        (f64::INFINITY, f64::NEG_INFINITY)
+    };
+    let (min, max) = values
+        .iter()
+        .filter(|v| v.is_finite())
+        .fold(start, |(min, max), v| (min.min(*v), max.max(*v)));
+    if min > max {
+        (0.0, 1.0)
+    } else {
+        (min, max)
+    }
+}
+
+/// A round-numbered tick spacing, giving about `TARGET_TICKS` ticks over
+/// `span`.
+fn nice_step(span: f64) -> f64 {
+    let span = if span > 0.0 { span } else { 1.0 };
+    let rough = span / TARGET_TICKS;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let normalized = rough / magnitude;
+    let nice = if normalized < 1.5 {
+        1.0
+    } else if normalized < 3.0 {
+        2.0
+    } else if normalized < 7.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+/// Widen `min..max` out to round numbers, returning the new range and the
+/// tick spacing.
+fn nice_range(min: f64, max: f64) -> (f64, f64, f64) {
+    let (min, max) = if min == max {
+        (min - 1.0, max + 1.0)
+    } else {
+        (min, max)
+    };
+    let step = nice_step(max - min);
+    (
+        (min / step).floor() * step,
+        (max / step).ceil() * step,
+        step,
+    )
+}
+
+/// The ticks from `min` to `max` inclusive, at multiples of `step`.
+fn tick_values(min: f64, max: f64, step: f64) -> impl Iterator<Item = f64> {
+    let first = (min / step).ceil() as i64;
+    let last = (max / step + 1e-9).floor() as i64;
+    (first..=last).map(move |i| i as f64 * step)
+}
+
+/// Format a value with at most two decimal places, dropping trailing zeros.
+fn default_format(value: f64) -> String {
+    let text = format!("{:.2}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    match text {
+        "-0" => "0".to_string(),
+        text => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranges_are_widened_to_round_numbers() {
+        assert_eq!(nice_range(3.0, 97.0), (0.0, 100.0, 20.0));
+        let (min, max, step) = nice_range(0.12, 0.48);
+        assert!((min - 0.1).abs() < 1e-9 && (max - 0.5).abs() < 1e-9 && (step - 0.1).abs() < 1e-9);
+        let ticks: Vec<f64> = tick_values(0.0, 100.0, 20.0).collect();
+        assert_eq!(ticks, vec![0.0, 20.0, 40.0, 60.0, 80.0, 100.0]);
+    }
+
+    #[test]
+    fn hover_finds_the_nearest_value() {
+        let plot = Rect::new(10.0, 0.0, 110.0, 50.0);
+        assert_eq!(LineChart::index_at(plot, 5, 10.0), Some(0));
+        assert_eq!(LineChart::index_at(plot, 5, 44.0), Some(1));
+        assert_eq!(LineChart::index_at(plot, 5, 200.0), Some(4));
+        assert_eq!(BarChart::index_at(plot, 4, 34.0), Some(0));
+        assert_eq!(BarChart::index_at(plot, 4, 36.0), Some(1));
+        assert_eq!(BarChart::index_at(plot, 4, 5.0), None);
+    }
+
+    #[test]
+    fn values_format_without_trailing_zeros() {
+        assert_eq!(default_format(20.0), "20");
+        assert_eq!(default_format(0.5), "0.5");
+        assert_eq!(default_format(-0.001), "0");
+    }
+}
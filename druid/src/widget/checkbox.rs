@@ -12,77 +12,136 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-//! A checkbox widget.
+//! Checkbox widgets.
 
-use crate::kurbo::{BezPath, Point, Rect, RoundedRect, Size};
+use crate::kurbo::{BezPath, Line, Point, Rect, RoundedRect, Size};
 use crate::piet::{LineCap, LineJoin, LinearGradient, RenderContext, StrokeStyle, UnitPoint};
 use crate::theme;
 use crate::widget::{Label, LabelText};
 use crate::{
-    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, UpdateCtx,
-    Widget, WidgetExt, WidgetPod,
+    BoxConstraints, Data, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, UpdateCtx, Widget, WidgetExt, WidgetPod,
 };
 
 /// A checkbox that toggles a `bool`.
+///
+/// A focused checkbox is toggled by pressing space.
 pub struct Checkbox {
-    child_label: WidgetPod<bool, Box<dyn Widget<bool>>>,
+    inner: CheckboxInner<bool>,
+}
+
+/// A checkbox with a third, indeterminate state, bound to an `Option<bool>`.
+///
+/// `None` is shown as a dash, and means "some of each"; this is what a
+/// "select all" checkbox shows when only some of the items it controls are
+/// selected. What a click does in that state is decided by the
+/// [`TristatePolicy`].
+///
+/// [`TristatePolicy`]: enum.TristatePolicy.html
+pub struct TristateCheckbox {
+    inner: CheckboxInner<Option<bool>>,
+    policy: TristatePolicy,
+}
+
+/// What clicking a [`TristateCheckbox`] does.
+///
+/// [`TristateCheckbox`]: struct.TristateCheckbox.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TristatePolicy {
+    /// An indeterminate checkbox becomes checked, and it then toggles between
+    /// checked and unchecked. Only the app can make it indeterminate again.
+    Resolve,
+    /// The checkbox goes from unchecked, to checked, to indeterminate.
+    Cycle,
+}
+
+/// The state and behaviour shared by both kinds of checkbox.
+struct CheckboxInner<T> {
+    child_label: WidgetPod<T, Box<dyn Widget<T>>>,
 }
 
 impl Checkbox {
     /// Create a new `Checkbox` with a label.
     pub fn new(label: impl Into<LabelText<bool>>) -> Checkbox {
         Checkbox {
-            child_label: WidgetPod::new(Label::new(label).boxed()),
+            inner: CheckboxInner::new(label),
         }
     }
 }
 
-impl Widget<bool> for Checkbox {
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut bool, _env: &Env) {
+impl TristateCheckbox {
+    /// Create a new `TristateCheckbox` with a label, following the
+    /// [`Resolve`] policy.
+    ///
+    /// [`Resolve`]: enum.TristatePolicy.html#variant.Resolve
+    pub fn new(label: impl Into<LabelText<Option<bool>>>) -> TristateCheckbox {
+        TristateCheckbox {
+            inner: CheckboxInner::new(label),
+            policy: TristatePolicy::Resolve,
+        }
+    }
+
+    /// Builder-style method to set what a click does.
+    pub fn with_policy(mut self, policy: TristatePolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+impl TristatePolicy {
+    /// The state after a click on a checkbox in state `state`.
+    pub fn next(self, state: Option<bool>) -> Option<bool> {
+        match (self, state) {
+            (TristatePolicy::Resolve, None) => Some(true),
+            (TristatePolicy::Resolve, Some(checked)) => Some(!checked),
+            (TristatePolicy::Cycle, Some(false)) => Some(true),
+            (TristatePolicy::Cycle, Some(true)) => None,
+            (TristatePolicy::Cycle, None) => Some(false),
+        }
+    }
+}
+
+impl<T: Data> CheckboxInner<T> {
+    fn new(label: impl Into<LabelText<T>>) -> Self {
+        CheckboxInner {
+            child_label: WidgetPod::new(Label::new(label).boxed()),
+        }
+    }
+
+    /// Handle an event, returning `true` if the checkbox was toggled.
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event) -> bool {
         match event {
             Event::MouseDown(_) => {
                 ctx.set_active(true);
                 ctx.request_paint();
             }
-            Event::MouseUp(_) => {
-                if ctx.is_active() {
-                    ctx.set_active(false);
-                    if ctx.is_hot() {
-                        if *data {
-                            *data = false;
-                        } else {
-                            *data = true;
-                        }
-                    }
-                    ctx.request_paint();
-                }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
+                return ctx.is_hot();
+            }
+            Event::KeyDown(key) if HotKey::new(None, KeyCode::Space).matches(key) => {
+                ctx.set_handled();
+                return !key.is_repeat;
             }
+            Event::KeyDown(key) if ctx.handle_focus_key(key) => ctx.set_handled(),
             _ => (),
         }
+        false
     }
 
-    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &bool, _env: &Env) {
-        if let LifeCycle::HotChanged(_) = event {
-            ctx.request_paint();
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
         }
     }
 
-    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &bool, _data: &bool, _env: &Env) {
-        ctx.request_paint();
-    }
-
-    fn layout(
-        &mut self,
-        layout_ctx: &mut LayoutCtx,
-        bc: &BoxConstraints,
-        data: &bool,
-        env: &Env,
-    ) -> Size {
-        bc.debug_check("Checkbox");
-
-        let label_size = self.child_label.layout(layout_ctx, &bc, data, env);
-        let padding = 8.0;
-        let label_x_offset = env.get(theme::BASIC_WIDGET_HEIGHT) + padding;
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let label_size = self.child_label.layout(ctx, bc, data, env);
+        let padding = 8.0 * env.get(theme::UI_SCALE);
+        let label_x_offset = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT) + padding;
         let origin = Point::new(label_x_offset, 0.0);
 
         self.child_label
@@ -90,12 +149,13 @@ impl Widget<bool> for Checkbox {
 
         bc.constrain(Size::new(
             label_x_offset + label_size.width,
-            env.get(theme::BASIC_WIDGET_HEIGHT).max(label_size.height),
+            theme::scaled(env, theme::BASIC_WIDGET_HEIGHT).max(label_size.height),
         ))
     }
 
-    fn paint(&mut self, ctx: &mut PaintCtx, data: &bool, env: &Env) {
-        let size = env.get(theme::BASIC_WIDGET_HEIGHT);
+    /// Paint the box, with a checkmark for `Some(true)` and a dash for `None`.
+    fn paint(&mut self, ctx: &mut PaintCtx, state: Option<bool>, data: &T, env: &Env) {
+        let size = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT);
 
         let rect =
             RoundedRect::from_origin_size(Point::ORIGIN, Size::new(size, size).to_vec2(), 2.);
@@ -120,21 +180,123 @@ impl Widget<bool> for Checkbox {
 
         ctx.stroke(rect, &border_color, 1.);
 
-        if *data {
-            // Paint the checkmark
-            let mut path = BezPath::new();
-            path.move_to((4.0, 9.0));
-            path.line_to((8.0, 13.0));
-            path.line_to((14.0, 5.0));
+        let mut style = StrokeStyle::new();
+        style.set_line_cap(LineCap::Round);
+        style.set_line_join(LineJoin::Round);
+        let mark_color = env.get(theme::CHECKBOX_MARK_COLOR);
 
-            let mut style = StrokeStyle::new();
-            style.set_line_cap(LineCap::Round);
-            style.set_line_join(LineJoin::Round);
-
-            ctx.stroke_styled(path, &env.get(theme::LABEL_COLOR), 2., &style);
+        // the marks are designed for an 18px box.
+        let scale = size / 18.0;
+        match state {
+            Some(true) => {
+                let mut path = BezPath::new();
+                path.move_to((4.0 * scale, 9.0 * scale));
+                path.line_to((8.0 * scale, 13.0 * scale));
+                path.line_to((14.0 * scale, 5.0 * scale));
+                ctx.stroke_styled(path, &mark_color, 2., &style);
+            }
+            None => {
+                let dash = Line::new((5.0 * scale, size / 2.0), (13.0 * scale, size / 2.0));
+                ctx.stroke_styled(dash, &mark_color, 2., &style);
+            }
+            Some(false) => (),
         }
 
         // Paint the text label
         self.child_label.paint_with_offset(ctx, data, env);
     }
 }
+
+impl Widget<bool> for Checkbox {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut bool, _env: &Env) {
+        if self.inner.event(ctx, event) {
+            *data = !*data;
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &bool, _env: &Env) {
+        self.inner.lifecycle(ctx, event)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &bool, _data: &bool, _env: &Env) {
+        ctx.request_paint();
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &bool, env: &Env) -> Size {
+        bc.debug_check("Checkbox");
+        self.inner.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &bool, env: &Env) {
+        self.inner.paint(ctx, Some(*data), data, env)
+    }
+}
+
+impl Widget<Option<bool>> for TristateCheckbox {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Option<bool>, _env: &Env) {
+        if self.inner.event(ctx, event) {
+            *data = self.policy.next(*data);
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        _data: &Option<bool>,
+        _env: &Env,
+    ) {
+        self.inner.lifecycle(ctx, event)
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        _old_data: &Option<bool>,
+        _data: &Option<bool>,
+        _env: &Env,
+    ) {
+        ctx.request_paint();
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &Option<bool>,
+        env: &Env,
+    ) -> Size {
+        bc.debug_check("TristateCheckbox");
+        self.inner.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Option<bool>, env: &Env) {
+        self.inner.paint(ctx, *data, data, env)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tristate_policies() {
+        let states = |policy: TristatePolicy, start| {
+            let mut state = start;
+            let mut seen = vec![state];
+            for _ in 0..3 {
+                state = policy.next(state);
+                seen.push(state);
+            }
+            seen
+        };
+        assert_eq!(
+            states(TristatePolicy::Resolve, None),
+            [None, Some(true), Some(false), Some(true)]
+        );
+        assert_eq!(
+            states(TristatePolicy::Cycle, Some(false)),
+            [Some(false), Some(true), None, Some(false)]
+        );
+    }
+}
@@ -16,8 +16,22 @@
 //!
 //! [`Controller`]: struct.Controller.html
 
+use std::time::Duration;
+
+use crate::command::sys as sys_cmd;
 use crate::widget::Controller;
-use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Widget};
+use crate::{Data, Env, Event, EventCtx, KeyCode, LifeCycle, LifeCycleCtx, TimerToken, Widget};
+
+/// The action performed by a [`Click`] or [`ClickRepeat`].
+///
+/// [`Click`]: struct.Click.html
+/// [`ClickRepeat`]: struct.ClickRepeat.html
+type ClickFn<T> = dyn Fn(&mut EventCtx, &mut T, &Env);
+
+/// The default delay before a held [`ClickRepeat`] starts repeating.
+///
+/// [`ClickRepeat`]: struct.ClickRepeat.html
+pub const DEFAULT_REPEAT_DELAY: Duration = Duration::from_millis(500);
 
 /// A clickable [`Controller`] widget. Pass this and a child widget to a
 /// [`ControllerHost`] to make the child interactive. More conveniently, this is
@@ -26,6 +40,9 @@ use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Widget};
 /// This is an alternative to the standard [`Button`] widget, for when you want
 /// to make an arbitrary widget clickable.
 ///
+/// If the child has keyboard focus, pressing space or return also counts
+/// as a click.
+///
 /// The child widget will also be updated on [`LifeCycle::HotChanged`] and
 /// mouse down, which can be useful for painting based on `ctx.is_active()`
 /// and `ctx.is_hot()`.
@@ -37,7 +54,7 @@ use crate::{Data, Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Widget};
 /// [`LifeCycle::HotChanged`]: ../enum.LifeCycle.html#variant.HotChanged
 pub struct Click<T> {
     /// A closure that will be invoked when the child widget is clicked.
-    action: Box<dyn Fn(&mut EventCtx, &mut T, &Env)>,
+    action: Box<ClickFn<T>>,
 }
 
 impl<T: Data> Click<T> {
@@ -65,6 +82,16 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
                     ctx.request_paint();
                 }
             }
+            Event::KeyDown(key) if ctx.is_focused() && is_activation_key(key.key_code) => {
+                if !key.is_repeat {
+                    (self.action)(ctx, data, env);
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(sys_cmd::ACTIVATE_ACCESS_KEY) => {
+                (self.action)(ctx, data, env);
+                ctx.set_handled();
+            }
             _ => {}
         }
 
@@ -86,3 +113,119 @@ impl<T: Data, W: Widget<T>> Controller<T, W> for Click<T> {
         child.lifecycle(ctx, event, data, env);
     }
 }
+
+/// A [`Controller`] that acts like [`Click`], but which keeps acting while
+/// the mouse button is held down.
+///
+/// The action is performed once when the button is pressed; if it is still
+/// held after an initial delay, the action is repeated at a fixed interval
+/// until the button is released or the pointer leaves the widget. Holding
+/// space or return while the child has keyboard focus repeats at the rate
+/// of the system's key repeat.
+///
+/// This is available as [`Button::on_click_repeat`].
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use druid::widget::{Button, ClickRepeat};
+/// # use druid::WidgetExt;
+/// let more = ClickRepeat::new(Duration::from_millis(50), |_ctx, secs: &mut u64, _env| *secs += 1)
+///     .initial_delay(Duration::from_millis(250));
+/// let button = Button::new("+").controller(more);
+/// ```
+///
+/// [`Controller`]: struct.Controller.html
+/// [`Click`]: struct.Click.html
+/// [`Button::on_click_repeat`]: struct.Button.html#method.on_click_repeat
+pub struct ClickRepeat<T> {
+    action: Box<ClickFn<T>>,
+    initial_delay: Duration,
+    interval: Duration,
+    timer: TimerToken,
+}
+
+impl<T: Data> ClickRepeat<T> {
+    /// Create a new `ClickRepeat`, repeating `action` every `interval` while
+    /// held.
+    pub fn new(interval: Duration, action: impl Fn(&mut EventCtx, &mut T, &Env) + 'static) -> Self {
+        ClickRepeat {
+            action: Box::new(action),
+            initial_delay: DEFAULT_REPEAT_DELAY,
+            interval,
+            timer: TimerToken::INVALID,
+        }
+    }
+
+    /// Builder-style method to set how long the button must be held before
+    /// the action starts repeating.
+    ///
+    /// The default is [`DEFAULT_REPEAT_DELAY`].
+    ///
+    /// [`DEFAULT_REPEAT_DELAY`]: constant.DEFAULT_REPEAT_DELAY.html
+    pub fn initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay = delay;
+        self
+    }
+
+    fn stop(&mut self, ctx: &mut EventCtx) {
+        self.timer = TimerToken::INVALID;
+        if ctx.is_active() {
+            ctx.set_active(false);
+            ctx.request_paint();
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Controller<T, W> for ClickRepeat<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(_) => {
+                ctx.set_active(true);
+                ctx.request_paint();
+                (self.action)(ctx, data, env);
+                self.timer = ctx.request_timer(ctx.now() + self.initial_delay);
+            }
+            Event::MouseUp(_) => self.stop(ctx),
+            // the pointer leaving is noticed on move, before HotChanged.
+            Event::MouseMoved(_) if ctx.is_active() && !ctx.is_hot() => self.stop(ctx),
+            Event::Timer(token) if *token == self.timer => {
+                (self.action)(ctx, data, env);
+                self.timer = ctx.request_timer(ctx.now() + self.interval);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.is_focused() && is_activation_key(key.key_code) => {
+                (self.action)(ctx, data, env);
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(sys_cmd::ACTIVATE_ACCESS_KEY) => {
+                (self.action)(ctx, data, env);
+                ctx.set_handled();
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::HotChanged(_) | LifeCycle::FocusChanged(_) = event {
+            ctx.request_paint();
+        }
+
+        child.lifecycle(ctx, event, data, env);
+    }
+}
+
+/// Whether `key` activates a focused control, as a click would.
+pub(super) fn is_activation_key(key: KeyCode) -> bool {
+    matches!(key, KeyCode::Space | KeyCode::Return | KeyCode::NumpadEnter)
+}
@@ -0,0 +1,389 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget whose body can be shown and hidden by clicking its header.
+
+use std::f64::consts::FRAC_PI_2;
+use std::time::Duration;
+
+use crate::inspect;
+use crate::kurbo::{Affine, BezPath, Point, Rect, Size};
+use crate::theme;
+use crate::widget::click::is_activation_key;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, Lens, LifeCycle, LifeCycleCtx, PaintCtx,
+    RenderContext, UpdateCtx, Widget, WidgetId, WidgetPod,
+};
+
+/// The default time a [`Collapsible`] takes to open or close.
+///
+/// [`Collapsible`]: struct.Collapsible.html
+pub const DEFAULT_COLLAPSE_ANIMATION: Duration = Duration::from_millis(150);
+
+/// A header and a body, where clicking the header shows or hides the body.
+///
+/// Whether the body is shown is read from, and written back to, a `bool`
+/// in the data, through a [`Lens`]. The header can take keyboard focus, and
+/// is toggled with space or return as well as by clicking; it is drawn with
+/// a triangle that points down when the body is shown.
+///
+/// When the `bool` changes, the body's height animates open or closed, and
+/// it is clipped to the space it has been given so far. While the body is
+/// closed, tab skips over it.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Checkbox, Collapsible, Flex, Label};
+/// use druid::{Data, Lens, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Settings {
+///     show_advanced: bool,
+///     tick_sound: bool,
+/// }
+///
+/// let advanced = Collapsible::new(
+///     Label::new("Advanced settings"),
+///     Flex::column().with_child(Checkbox::new("Tick while running").lens(Settings::tick_sound)),
+///     Settings::show_advanced,
+/// );
+/// ```
+///
+/// [`Lens`]: ../trait.Lens.html
+pub struct Collapsible<T, L> {
+    section: Section<T>,
+    expanded: L,
+}
+
+/// A header and a body, animating between open and closed.
+///
+/// This holds the parts of a [`Collapsible`] that don't depend on where
+/// the open state is stored, so that an [`Accordion`] can reuse them.
+///
+/// [`Collapsible`]: struct.Collapsible.html
+/// [`Accordion`]: struct.Accordion.html
+pub(super) struct Section<T> {
+    header: WidgetPod<T, Header<T>>,
+    body: WidgetPod<T, Box<dyn Widget<T>>>,
+    animation: Duration,
+    is_open: bool,
+    // from 0.0, closed, to 1.0, open.
+    openness: f64,
+    // where the section was last laid out, in its parent's coordinates.
+    rect: Rect,
+}
+
+/// The clickable, focusable header of a `Section`.
+struct Header<T> {
+    child: WidgetPod<T, Box<dyn Widget<T>>>,
+    openness: f64,
+    // set when the user toggles the header; cleared by the section.
+    toggled: bool,
+}
+
+impl<T: Data, L: Lens<T, bool>> Collapsible<T, L> {
+    /// Create a new `Collapsible`, open when the `bool` at `expanded` is
+    /// `true`.
+    pub fn new(
+        header: impl Widget<T> + 'static,
+        body: impl Widget<T> + 'static,
+        expanded: L,
+    ) -> Self {
+        Collapsible {
+            section: Section::new(header, body),
+            expanded,
+        }
+    }
+
+    /// Builder-style method to set how long the body takes to open or
+    /// close. `Duration::from_secs(0)` turns the animation off.
+    pub fn with_animation_duration(mut self, duration: Duration) -> Self {
+        self.section.set_animation(duration);
+        self
+    }
+}
+
+impl<T: Data> Section<T> {
+    pub(super) fn new(header: impl Widget<T> + 'static, body: impl Widget<T> + 'static) -> Self {
+        Section {
+            header: WidgetPod::new(Header {
+                child: WidgetPod::new(Box::new(header)),
+                openness: 0.0,
+                toggled: false,
+            }),
+            body: WidgetPod::new(Box::new(body)),
+            animation: DEFAULT_COLLAPSE_ANIMATION,
+            is_open: false,
+            openness: 0.0,
+            rect: Rect::ZERO,
+        }
+    }
+
+    pub(super) fn set_animation(&mut self, duration: Duration) {
+        self.animation = duration;
+    }
+
+    pub(super) fn header_id(&self) -> WidgetId {
+        self.header.id()
+    }
+
+    /// Deliver an event, returning `true` if the user toggled the header.
+    pub(super) fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut T,
+        env: &Env,
+    ) -> bool {
+        self.header.event(ctx, event, data, env);
+        let toggled = std::mem::replace(&mut self.header.widget_mut().toggled, false);
+        // a closed body can't be seen, so it shouldn't be clicked.
+        let body_hidden = !self.is_open && self.openness == 0.0;
+        if !(body_hidden && event.is_pointer_event()) {
+            self.body.event(ctx, event, data, env);
+        }
+        toggled
+    }
+
+    /// The part of the body that is shown, in our parent's coordinates.
+    fn body_clip(&self) -> Rect {
+        let top = self.header.layout_rect().y1;
+        Rect::new(self.rect.x0, top, self.rect.x1, self.rect.y1)
+    }
+
+    /// Open or close the section, animating unless `animate` is false.
+    pub(super) fn set_open(&mut self, open: bool, animate: bool) -> bool {
+        self.is_open = open;
+        let target = if open { 1.0 } else { 0.0 };
+        if !animate || self.animation == Duration::from_secs(0) {
+            self.openness = target;
+        }
+        self.header.widget_mut().openness = self.openness;
+        self.openness != target
+    }
+
+    pub(super) fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        if let LifeCycle::AnimFrame(interval) = event {
+            let target = if self.is_open { 1.0 } else { 0.0 };
+            if self.openness != target {
+                let step = *interval as f64 / self.animation.as_nanos().max(1) as f64;
+                self.openness = if self.is_open {
+                    (self.openness + step).min(1.0)
+                } else {
+                    (self.openness - step).max(0.0)
+                };
+                self.header.widget_mut().openness = self.openness;
+                if self.openness != target {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_layout();
+            }
+        }
+        self.header.lifecycle(ctx, event, data, env);
+        if let Some(tree) = inspect::tree_cell(event) {
+            let visible = self.body_clip();
+            let body = &mut self.body;
+            tree.with_children_transformed(Affine::default(), Some(visible), || {
+                body.lifecycle(ctx, event, data, env)
+            });
+            return;
+        }
+        // a closed body can't be seen, so tab shouldn't reach it.
+        if self.is_open {
+            self.body.lifecycle(ctx, event, data, env);
+        } else {
+            let body = &mut self.body;
+            ctx.without_focus(|ctx| body.lifecycle(ctx, event, data, env));
+        }
+    }
+
+    pub(super) fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        self.header.update(ctx, data, env);
+        self.body.update(ctx, data, env);
+    }
+
+    /// Lay out the section with its top left corner at `origin`.
+    pub(super) fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        origin: Point,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let child_bc = BoxConstraints::new(
+            Size::new(bc.min().width, 0.0),
+            Size::new(bc.max().width, f64::INFINITY),
+        );
+        let header_size = self.header.layout(ctx, &child_bc, data, env);
+        self.header
+            .set_layout_rect(Rect::from_origin_size(origin, header_size));
+        let body_size = self.body.layout(ctx, &child_bc, data, env);
+        self.body.set_layout_rect(Rect::from_origin_size(
+            Point::new(origin.x, origin.y + header_size.height),
+            body_size,
+        ));
+
+        // ease out: fast at first, slowing as the body settles.
+        let shown = 1.0 - (1.0 - self.openness).powi(2);
+        let size = bc.constrain(Size::new(
+            header_size.width.max(body_size.width),
+            header_size.height + body_size.height * shown,
+        ));
+        self.rect = Rect::from_origin_size(origin, size);
+        size
+    }
+
+    pub(super) fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.header.paint_with_offset(ctx, data, env);
+        if self.openness > 0.0 {
+            let visible = self.body_clip();
+            ctx.with_save(|ctx| {
+                ctx.clip(visible);
+                self.body.paint_with_offset(ctx, data, env);
+            });
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Header<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env);
+        if ctx.is_handled() {
+            return;
+        }
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                ctx.set_active(true);
+                ctx.request_focus();
+                ctx.request_paint();
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                if ctx.is_hot() {
+                    self.toggled = true;
+                }
+                ctx.request_paint();
+            }
+            Event::KeyDown(key) if ctx.is_focused() && is_activation_key(key.key_code) => {
+                self.toggled = true;
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.handle_focus_key(key) => ctx.set_handled(),
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        self.child.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Collapsible header");
+
+        let indent = triangle_size(env) * 2.0;
+        let child_bc = bc.shrink((indent, 0.0)).loosen();
+        let child_size = self.child.layout(ctx, &child_bc, data, env);
+        let height = child_size
+            .height
+            .max(theme::scaled(env, theme::BASIC_WIDGET_HEIGHT));
+        let origin = Point::new(indent, (height - child_size.height) / 2.0);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(origin, child_size));
+        bc.constrain(Size::new(child_size.width + indent, height))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let triangle = triangle_size(env);
+        let center = Point::new(triangle, ctx.size().height / 2.0);
+        let mut path = BezPath::new();
+        path.move_to((-triangle / 3.0, -triangle / 2.0));
+        path.line_to((triangle * 2.0 / 3.0, 0.0));
+        path.line_to((-triangle / 3.0, triangle / 2.0));
+        path.close_path();
+        let color = if ctx.is_hot() {
+            env.get(theme::LABEL_COLOR)
+        } else {
+            env.get(theme::FOREGROUND_DARK)
+        };
+        ctx.with_save(|ctx| {
+            ctx.transform(
+                Affine::translate(center.to_vec2()) * Affine::rotate(self.openness * FRAC_PI_2),
+            );
+            ctx.fill(path, &color);
+        });
+
+        self.child.paint_with_offset(ctx, data, env);
+    }
+}
+
+impl<T: Data, L: Lens<T, bool>> Widget<T> for Collapsible<T, L> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if self.section.event(ctx, event, data, env) {
+            self.expanded
+                .with_mut(data, |expanded| *expanded = !*expanded);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            let expanded = self.expanded.with(data, |expanded| *expanded);
+            self.section.set_open(expanded, false);
+        }
+        self.section.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let was_expanded = self.expanded.with(old_data, |expanded| *expanded);
+        let expanded = self.expanded.with(data, |expanded| *expanded);
+        if was_expanded != expanded {
+            if self.section.set_open(expanded, true) {
+                ctx.request_anim_frame();
+            }
+            // the body joins or leaves the focus chain.
+            ctx.children_changed();
+        }
+        self.section.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Collapsible");
+        self.section.layout(ctx, bc, Point::ORIGIN, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.section.paint(ctx, data, env);
+    }
+}
+
+/// The size of the disclosure triangle, which follows the text size.
+fn triangle_size(env: &Env) -> f64 {
+    env.get(theme::TEXT_SIZE_NORMAL) * 0.6
+}
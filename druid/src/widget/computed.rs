@@ -0,0 +1,130 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows its child a value derived from the data.
+
+use crate::kurbo::Size;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    UpdateCtx, Widget, WidgetId,
+};
+
+/// A function that says whether a [`Computed`]'s value is out of date,
+/// given the old and the new data.
+///
+/// [`Computed`]: struct.Computed.html
+type StaleFn<T> = dyn Fn(&T, &T) -> bool;
+
+/// A widget that computes a value from its data and shows it to its child,
+/// recomputing it only when the data changes.
+///
+/// The value is cached between calls, so `layout`, `paint` and most events
+/// don't pay for the computation. By default it is recomputed whenever the
+/// data isn't [`same`] as before; if the value only depends on part of the
+/// data, use [`with_key`] to say which part, and changes elsewhere in the
+/// data won't cause a recompute. The child is only updated when the new
+/// value differs from the old one.
+///
+/// The value is read-only: changes the child makes to it are thrown away
+/// when it is next recomputed.
+///
+/// You will generally want to create this with [`WidgetExt::computed`].
+///
+/// # Examples
+///
+/// ```
+/// use std::time::Duration;
+/// use druid::format::duration_as_human_readable;
+/// use druid::widget::{Computed, Label};
+///
+/// let elapsed = Computed::new(Label::dynamic(|text: &String, _| text.clone()), |elapsed: &u64| {
+///     duration_as_human_readable(Duration::from_secs(*elapsed))
+/// });
+/// ```
+///
+/// [`same`]: ../trait.Data.html#tymethod.same
+/// [`with_key`]: #method.with_key
+/// [`WidgetExt::computed`]: ../trait.WidgetExt.html#method.computed
+pub struct Computed<T, U, W> {
+    child: W,
+    compute: Box<dyn Fn(&T) -> U>,
+    is_stale: Box<StaleFn<T>>,
+    value: Option<U>,
+}
+
+impl<T: Data, U: Data, W: Widget<U>> Computed<T, U, W> {
+    /// Create a widget showing `child` the value `compute` returns for the
+    /// data.
+    pub fn new(child: W, compute: impl Fn(&T) -> U + 'static) -> Self {
+        Computed {
+            child,
+            compute: Box::new(compute),
+            is_stale: Box::new(|old, new| !old.same(new)),
+            value: None,
+        }
+    }
+
+    /// Builder-style method to only recompute the value when the key that
+    /// `key` returns for the data changes.
+    pub fn with_key<K: Data>(mut self, key: impl Fn(&T) -> K + 'static) -> Self {
+        self.is_stale = Box::new(move |old, new| !key(old).same(&key(new)));
+        self
+    }
+}
+
+/// The cached value, computing it first if there isn't one yet.
+fn value<'a, T, U>(value: &'a mut Option<U>, compute: &dyn Fn(&T) -> U, data: &T) -> &'a mut U {
+    value.get_or_insert_with(|| compute(data))
+}
+
+impl<T: Data, U: Data, W: Widget<U>> Widget<T> for Computed<T, U, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let value = value(&mut self.value, &*self.compute, data);
+        self.child.event(ctx, event, value, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let value = value(&mut self.value, &*self.compute, data);
+        self.child.lifecycle(ctx, event, value, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let old = match self.value.take() {
+            Some(old) if !(self.is_stale)(old_data, data) => {
+                self.value = Some(old);
+                return;
+            }
+            Some(old) => old,
+            None => (self.compute)(old_data),
+        };
+        let new = value(&mut self.value, &*self.compute, data);
+        if !old.same(new) {
+            self.child.update(ctx, &old, new, env)
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        let value = value(&mut self.value, &*self.compute, data);
+        self.child.layout(ctx, bc, value, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let value = value(&mut self.value, &*self.compute, data);
+        self.child.paint(ctx, value, env)
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.child.id()
+    }
+}
@@ -31,6 +31,7 @@ pub struct Container<T> {
     background: Option<BackgroundBrush<T>>,
     border: Option<BorderStyle>,
     corner_radius: f64,
+    snap_border: bool,
 
     inner: WidgetPod<T, Box<dyn Widget<T>>>,
 }
@@ -42,6 +43,7 @@ impl<T: Data> Container<T> {
             background: None,
             border: None,
             corner_radius: 0.0,
+            snap_border: false,
             inner: WidgetPod::new(inner).boxed(),
         }
     }
@@ -86,6 +88,27 @@ impl<T: Data> Container<T> {
         self
     }
 
+    /// Builder-style method to align the border to the pixel grid, so that
+    /// it is drawn with hard edges instead of blurring across pixels.
+    ///
+    /// The outer edge of the border is moved to the nearest pixel boundary,
+    /// and its width rounded to a whole number of pixels; see
+    /// [`PaintCtx::snap_rect`]. This may move the border by up to half a
+    /// pixel, and change its width slightly, at some scale factors.
+    ///
+    /// [`PaintCtx::snap_rect`]: ../struct.PaintCtx.html#method.snap_rect
+    pub fn snap_border_to_pixels(mut self) -> Self {
+        self.snap_border = true;
+        self
+    }
+
+    fn border_width(&self, env: &Env) -> f64 {
+        match &self.border {
+            Some(border) => border.width.resolve(env),
+            None => 0.0,
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn background_is_some(&self) -> bool {
         self.background.is_some()
@@ -114,10 +137,7 @@ impl<T: Data> Widget<T> for Container<T> {
         bc.debug_check("Container");
 
         // Shrink constraints by border offset
-        let border_width = match &self.border {
-            Some(border) => border.width.resolve(env),
-            None => 0.0,
-        };
+        let border_width = self.border_width(env);
         let child_bc = bc.shrink((2.0 * border_width, 2.0 * border_width));
         let size = self.inner.layout(ctx, &child_bc, data, env);
         let origin = Point::new(border_width, border_width);
@@ -134,6 +154,54 @@ impl<T: Data> Widget<T> for Container<T> {
         my_size
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let border = 2.0 * self.border_width(env);
+        let child_height = (height - border).max(0.);
+        self.inner.min_intrinsic_width(ctx, child_height, data, env) + border
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let border = 2.0 * self.border_width(env);
+        let child_height = (height - border).max(0.);
+        self.inner.max_intrinsic_width(ctx, child_height, data, env) + border
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let border = 2.0 * self.border_width(env);
+        let child_width = (width - border).max(0.);
+        self.inner.min_intrinsic_height(ctx, child_width, data, env) + border
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let border = 2.0 * self.border_width(env);
+        let child_width = (width - border).max(0.);
+        self.inner.max_intrinsic_height(ctx, child_width, data, env) + border
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         if let Some(background) = self.background.as_mut() {
             let panel = ctx.size().to_rounded_rect(self.corner_radius);
@@ -145,10 +213,13 @@ impl<T: Data> Widget<T> for Container<T> {
         }
 
         if let Some(border) = &self.border {
-            let border_width = border.width.resolve(env);
-            let border_rect = ctx
-                .size()
-                .to_rect()
+            let mut border_width = border.width.resolve(env);
+            let mut bounds = ctx.size().to_rect();
+            if self.snap_border {
+                border_width = ctx.snap_stroke_width(border_width);
+                bounds = ctx.snap_rect(bounds);
+            }
+            let border_rect = bounds
                 .inset(border_width / -2.0)
                 .to_rounded_rect(self.corner_radius);
             ctx.stroke(border_rect, &border.color.resolve(env), border_width);
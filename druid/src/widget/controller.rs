@@ -119,6 +119,46 @@ impl<T, W: Widget<T>, C: Controller<T, W>> Widget<T> for ControllerHost<W, C> {
         self.widget.layout(ctx, bc, data, env)
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.widget.min_intrinsic_width(ctx, height, data, env)
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.widget.max_intrinsic_width(ctx, height, data, env)
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.widget.min_intrinsic_height(ctx, width, data, env)
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.widget.max_intrinsic_height(ctx, width, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.widget.paint(ctx, data, env)
     }
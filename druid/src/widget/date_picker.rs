@@ -0,0 +1,445 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A text field for a date, with a calendar to choose from.
+
+use crate::format::{Date, DateConventions, Weekday};
+use crate::kurbo::{BezPath, Point, Rect, Size, Vec2};
+use crate::piet::TextLayout;
+use crate::theme;
+use crate::widget::dropdown::text_layouts;
+use crate::widget::popover::Popup;
+use crate::widget::{TextBox, WidgetExt};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, LifeCycle, LifeCycleCtx,
+    LocalizedString, PaintCtx, RenderContext, UpdateCtx, Widget, WidgetId,
+};
+
+/// The width of the button that opens the calendar.
+const BUTTON_WIDTH: f64 = 24.0;
+const CELL_WIDTH: f64 = 30.0;
+/// The calendar has a title, a row of weekdays, and six weeks.
+const CALENDAR_ROWS: usize = 8;
+
+/// The localization keys of the month names, with their placeholders.
+const MONTH_NAMES: [(&str, &str); 12] = [
+    ("calendar-month-january", "January"),
+    ("calendar-month-february", "February"),
+    ("calendar-month-march", "March"),
+    ("calendar-month-april", "April"),
+    ("calendar-month-may", "May"),
+    ("calendar-month-june", "June"),
+    ("calendar-month-july", "July"),
+    ("calendar-month-august", "August"),
+    ("calendar-month-september", "September"),
+    ("calendar-month-october", "October"),
+    ("calendar-month-november", "November"),
+    ("calendar-month-december", "December"),
+];
+/// The keys of the weekday initials, starting with Monday.
+const WEEKDAY_INITIALS: [(&str, &str); 7] = [
+    ("calendar-weekday-monday", "Mo"),
+    ("calendar-weekday-tuesday", "Tu"),
+    ("calendar-weekday-wednesday", "We"),
+    ("calendar-weekday-thursday", "Th"),
+    ("calendar-weekday-friday", "Fr"),
+    ("calendar-weekday-saturday", "Sa"),
+    ("calendar-weekday-sunday", "Su"),
+];
+
+/// A text field for a date, with a button that opens a calendar.
+///
+/// Dates are written and read following the conventions of the current
+/// locale (see [`DateConventions`]), and the calendar's weeks start on the
+/// locale's first day of the week.
+///
+/// The date can be typed, or chosen from the calendar, which also opens
+/// with the down arrow key. While it is open, the arrow keys move by days
+/// and weeks, page up and page down move by months, and return chooses
+/// the highlighted day.
+///
+/// [`DateConventions`]: ../format/struct.DateConventions.html
+pub struct DatePicker {
+    popup: Popup<Date, Calendar>,
+    field_id: WidgetId,
+}
+
+/// Edits the text of a date, like [`Formatted`] does for numbers.
+///
+/// [`Formatted`]: struct.Formatted.html
+struct DateField {
+    text_box: TextBox,
+    text: String,
+}
+
+/// A month of days to pick from.
+struct Calendar {
+    // the first of the month being shown.
+    shown: Date,
+    highlighted: Date,
+    // set when the user clicks a day; cleared by the picker.
+    chosen: Option<Date>,
+    first_weekday: Weekday,
+    cell_height: f64,
+    month_names: Vec<LocalizedString<()>>,
+    // starting with Monday.
+    weekday_initials: Vec<LocalizedString<()>>,
+}
+
+impl DatePicker {
+    /// Create a new `DatePicker`.
+    pub fn new() -> DatePicker {
+        let field = DateField {
+            text_box: TextBox::new(),
+            text: String::new(),
+        };
+        let field_id = WidgetId::next();
+        let field = field
+            .with_id(field_id)
+            .padding((0.0, 0.0, BUTTON_WIDTH, 0.0));
+        DatePicker {
+            popup: Popup::new(field, Calendar::new()),
+            field_id,
+        }
+    }
+
+    fn set_open(&mut self, ctx: &mut EventCtx, open: bool, data: &Date) {
+        if open {
+            self.popup.content_mut().reset(*data);
+        }
+        self.popup.set_open(open);
+        ctx.request_layout();
+    }
+
+    /// Handle a key while the calendar is open, returning `true` if it was
+    /// used.
+    fn calendar_key(&mut self, ctx: &mut EventCtx, key: KeyCode, data: &mut Date) -> bool {
+        let calendar = self.popup.content_mut();
+        match key {
+            KeyCode::ArrowLeft => calendar.move_highlight(-1, 0),
+            KeyCode::ArrowRight => calendar.move_highlight(1, 0),
+            KeyCode::ArrowUp => calendar.move_highlight(-7, 0),
+            KeyCode::ArrowDown => calendar.move_highlight(7, 0),
+            KeyCode::PageUp => calendar.move_highlight(0, -1),
+            KeyCode::PageDown => calendar.move_highlight(0, 1),
+            KeyCode::Return | KeyCode::NumpadEnter => {
+                *data = calendar.highlighted;
+                self.set_open(ctx, false, data);
+            }
+            _ => return false,
+        }
+        ctx.request_paint();
+        true
+    }
+}
+
+impl Default for DatePicker {
+    fn default() -> Self {
+        DatePicker::new()
+    }
+}
+
+impl Calendar {
+    fn new() -> Calendar {
+        let epoch = Date::new(1970, 1, 1).unwrap();
+        Calendar {
+            shown: epoch,
+            highlighted: epoch,
+            chosen: None,
+            first_weekday: Weekday::Monday,
+            cell_height: 0.0,
+            month_names: localized_strings(&MONTH_NAMES),
+            weekday_initials: localized_strings(&WEEKDAY_INITIALS),
+        }
+    }
+
+    /// Prepare to be shown, highlighting `selected`.
+    fn reset(&mut self, selected: Date) {
+        self.highlighted = selected;
+        self.shown = selected.first_of_month();
+    }
+
+    fn move_highlight(&mut self, days: i64, months: i32) {
+        self.highlighted = self.highlighted.add_days(days).add_months(months);
+        self.shown = self.highlighted.first_of_month();
+    }
+
+    /// The date in the top left corner of the grid.
+    fn first_shown(&self) -> Date {
+        let offset = self.shown.weekday().days_since(self.first_weekday);
+        self.shown.add_days(-i64::from(offset))
+    }
+
+    /// The top of the grid of days.
+    fn grid_top(&self) -> f64 {
+        self.cell_height * 2.0
+    }
+
+    fn date_at(&self, pos: Point) -> Option<Date> {
+        let row = ((pos.y - self.grid_top()) / self.cell_height).floor();
+        let column = (pos.x / CELL_WIDTH).floor();
+        if !(0.0..6.0).contains(&row) || !(0.0..7.0).contains(&column) {
+            return None;
+        }
+        Some(self.first_shown().add_days(row as i64 * 7 + column as i64))
+    }
+
+    fn cell_rect(&self, index: usize) -> Rect {
+        let origin = Point::new(
+            (index % 7) as f64 * CELL_WIDTH,
+            self.grid_top() + (index / 7) as f64 * self.cell_height,
+        );
+        Rect::from_origin_size(origin, (CELL_WIDTH, self.cell_height))
+    }
+
+    fn resolve_strings(&mut self, env: &Env) {
+        for string in self
+            .month_names
+            .iter_mut()
+            .chain(self.weekday_initials.iter_mut())
+        {
+            string.resolve(&(), env);
+        }
+        self.first_weekday = DateConventions::for_env(env).first_weekday;
+    }
+
+    fn month_name(&self) -> &str {
+        self.month_names[self.shown.month() as usize - 1].localized_str()
+    }
+
+    /// Weekday initials, starting with the first day of the week.
+    fn weekday_initials(&self) -> Vec<&str> {
+        let mut initials: Vec<&str> = self
+            .weekday_initials
+            .iter()
+            .map(LocalizedString::localized_str)
+            .collect();
+        initials.rotate_left(self.first_weekday.days_from_monday() as usize);
+        initials
+    }
+}
+
+fn localized_strings(keys: &[(&'static str, &'static str)]) -> Vec<LocalizedString<()>> {
+    keys.iter()
+        .map(|(key, placeholder)| LocalizedString::new(key).with_placeholder(*placeholder))
+        .collect()
+}
+
+impl Widget<Date> for DateField {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Date, env: &Env) {
+        self.text_box.event(ctx, event, &mut self.text, env);
+        if let Ok(date) = DateConventions::for_env(env).parse_date(&self.text) {
+            *data = date;
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Date, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.text = DateConventions::for_env(env).format_date(*data);
+        }
+        self.text_box.lifecycle(ctx, event, &self.text, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &Date, data: &Date, env: &Env) {
+        let conventions = DateConventions::for_env(env);
+        if conventions.parse_date(&self.text) == Ok(*data) {
+            return;
+        }
+        let old = std::mem::replace(&mut self.text, conventions.format_date(*data));
+        self.text_box.update(ctx, &old, &self.text, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _: &Date, env: &Env) -> Size {
+        self.text_box.layout(ctx, bc, &self.text, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &Date, env: &Env) {
+        self.text_box.paint(ctx, &self.text, env)
+    }
+}
+
+impl Widget<Date> for Calendar {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut Date, _env: &Env) {
+        match event {
+            Event::MouseMoved(mouse) => {
+                if let Some(date) = self.date_at(mouse.pos) {
+                    if date != self.highlighted {
+                        self.highlighted = date;
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseDown(_) => {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.set_handled();
+                if !ctx.is_hot() {
+                    return;
+                }
+                if mouse.pos.y < self.cell_height {
+                    let width = ctx.size().width;
+                    if mouse.pos.x < CELL_WIDTH {
+                        self.shown = self.shown.add_months(-1);
+                    } else if mouse.pos.x >= width - CELL_WIDTH {
+                        self.shown = self.shown.add_months(1);
+                    }
+                    ctx.request_paint();
+                } else {
+                    self.chosen = self.date_at(mouse.pos);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, event: &LifeCycle, _: &Date, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.resolve_strings(env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &Date, _: &Date, env: &Env) {
+        self.resolve_strings(env);
+        ctx.request_paint();
+    }
+
+    fn layout(&mut self, _: &mut LayoutCtx, bc: &BoxConstraints, _: &Date, env: &Env) -> Size {
+        bc.debug_check("Calendar");
+
+        self.cell_height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
+        bc.constrain(Size::new(
+            CELL_WIDTH * 7.0,
+            self.cell_height * CALENDAR_ROWS as f64,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Date, env: &Env) {
+        let width = ctx.size().width;
+        let font_size = env.get(theme::TEXT_SIZE_NORMAL);
+        let baseline = (self.cell_height + font_size * 0.7) / 2.0;
+        let label_color = env.get(theme::LABEL_COLOR);
+        let dim_color = env.get(theme::PLACEHOLDER_COLOR);
+
+        // the title, between arrows to the previous and next months.
+        let title = format!("{} {}", self.month_name(), self.shown.year());
+        let layout = &text_layouts(ctx.text(), std::iter::once(title.as_str()), env)[0];
+        let x = (width - layout.width()) / 2.0;
+        ctx.draw_text(layout, (x, baseline), &label_color);
+        let middle = self.cell_height / 2.0;
+        let mut arrows = BezPath::new();
+        for &(x, direction) in &[(CELL_WIDTH / 2.0, -1.0), (width - CELL_WIDTH / 2.0, 1.0)] {
+            let tip = Point::new(x + direction * 3.0, middle);
+            arrows.move_to(tip);
+            arrows.line_to(tip + Vec2::new(-direction * 6.0, -4.0));
+            arrows.line_to(tip + Vec2::new(-direction * 6.0, 4.0));
+            arrows.close_path();
+        }
+        ctx.fill(arrows, &label_color);
+
+        let initials = self.weekday_initials();
+        let layouts = text_layouts(ctx.text(), initials.into_iter(), env);
+        for (column, layout) in layouts.iter().enumerate() {
+            let x = column as f64 * CELL_WIDTH + (CELL_WIDTH - layout.width()) / 2.0;
+            ctx.draw_text(layout, (x, self.cell_height + baseline), &dim_color);
+        }
+
+        let first = self.first_shown();
+        let days: Vec<Date> = (0..42).map(|i| first.add_days(i)).collect();
+        let numbers: Vec<String> = days.iter().map(|date| date.day().to_string()).collect();
+        let layouts = text_layouts(ctx.text(), numbers.iter().map(String::as_str), env);
+        for (index, (date, layout)) in days.iter().zip(layouts.iter()).enumerate() {
+            let rect = self.cell_rect(index);
+            let cell = rect.inset(-1.0).to_rounded_rect(4.0);
+            if *date == self.highlighted {
+                ctx.fill(cell, &env.get(theme::PRIMARY_DARK));
+            }
+            if date == data {
+                ctx.stroke(cell, &env.get(theme::PRIMARY_LIGHT), 1.0);
+            }
+            let color = if date.month() == self.shown.month() {
+                &label_color
+            } else {
+                &dim_color
+            };
+            let x = rect.x0 + (CELL_WIDTH - layout.width()) / 2.0;
+            ctx.draw_text(layout, (x, rect.y0 + baseline), color);
+        }
+    }
+}
+
+impl Widget<Date> for DatePicker {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Date, env: &Env) {
+        match event {
+            Event::KeyDown(key)
+                if self.popup.is_open()
+                    && HotKey::new(None, key.key_code).matches(key)
+                    && self.calendar_key(ctx, key.key_code, data) =>
+            {
+                ctx.set_handled();
+            }
+            Event::KeyDown(key)
+                if !self.popup.is_open() && HotKey::new(None, KeyCode::ArrowDown).matches(key) =>
+            {
+                self.set_open(ctx, true, data);
+                ctx.set_handled();
+            }
+            Event::MouseDown(mouse)
+                if mouse.pos.x >= ctx.size().width - BUTTON_WIDTH && !self.popup.is_open() =>
+            {
+                // keys only reach us through the focused field.
+                ctx.set_focus(self.field_id);
+                self.set_open(ctx, true, data);
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+
+        if !ctx.is_handled() && self.popup.event(ctx, event, data, env) {
+            self.set_open(ctx, false, data);
+        }
+        if let Some(date) = self.popup.content_mut().chosen.take() {
+            *data = date;
+            self.set_open(ctx, false, data);
+        }
+        self.popup.capture_mouse(ctx);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Date, env: &Env) {
+        self.popup.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &Date, data: &Date, env: &Env) {
+        self.popup.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &Date, env: &Env) -> Size {
+        bc.debug_check("DatePicker");
+        self.popup.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Date, env: &Env) {
+        // a little calendar page, on the button.
+        let size = ctx.size();
+        let center = Point::new(size.width - BUTTON_WIDTH / 2.0, size.height / 2.0);
+        let page = Rect::from_points(center - Vec2::new(6.0, 5.5), center + Vec2::new(6.0, 5.5));
+        let color = env.get(theme::LABEL_COLOR);
+        ctx.stroke(page, &color, 1.0);
+        ctx.fill(Rect::new(page.x0, page.y0, page.x1, page.y0 + 3.0), &color);
+
+        self.popup.paint(ctx, data, env);
+    }
+}
@@ -0,0 +1,523 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget for choosing one of a list of options.
+
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use crate::kurbo::{BezPath, Circle, Line, Point, Rect, Size, Vec2};
+use crate::piet::{FontBuilder, PietText, PietTextLayout, Text, TextLayout, TextLayoutBuilder};
+use crate::theme;
+use crate::widget::popover::Popup;
+use crate::widget::{Label, WidgetExt};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, KeyCode, KeyEvent, LayoutCtx, LifeCycle,
+    LifeCycleCtx, LocalizedString, PaintCtx, RenderContext, UpdateCtx, Widget,
+};
+
+/// Space to the left of option labels.
+const LABEL_X_PADDING: f64 = 8.0;
+/// Space above and below the selected option's label.
+const LABEL_Y_PADDING: f64 = 4.0;
+/// Space on the right for the arrow.
+const ARROW_SPACE: f64 = 20.0;
+/// How long after the last keypress type-ahead starts a new search.
+const TYPE_AHEAD_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A widget that shows the selected option, and opens a list of options
+/// to choose from when clicked.
+///
+/// This type impls `Widget<T>`, where the data is the selected option's
+/// value; like [`RadioGroup`], it is created from `(label, value)` pairs,
+/// and an option is selected when the data equals its value.
+///
+/// A focused dropdown opens with space, return, or the arrow keys. In the
+/// list, the arrow keys move the highlight and return picks the highlighted
+/// option. Typing the start of a label highlights it (or, while the list is
+/// closed, selects it). A [`searchable`] dropdown instead filters the list
+/// to the options containing what has been typed.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::Dropdown;
+///
+/// #[derive(Clone, Copy, PartialEq, druid::Data)]
+/// enum Sound {
+///     Bell,
+///     Chime,
+///     Gong,
+/// }
+///
+/// let sounds = Dropdown::new(vec![
+///     ("Bell", Sound::Bell),
+///     ("Chime", Sound::Chime),
+///     ("Gong", Sound::Gong),
+/// ]);
+/// ```
+///
+/// [`RadioGroup`]: struct.RadioGroup.html
+/// [`searchable`]: #method.searchable
+pub struct Dropdown<T> {
+    popup: Popup<T, OptionList<T>>,
+    options: Rc<Vec<(String, T)>>,
+    searchable: bool,
+    type_ahead: String,
+    last_typed: Option<Instant>,
+}
+
+/// The list of options shown while a `Dropdown` is open.
+struct OptionList<T> {
+    options: Rc<Vec<(String, T)>>,
+    searchable: bool,
+    filter: String,
+    // shown in the search row while the filter is empty.
+    placeholder: LocalizedString<()>,
+    // the indices of the options that match the filter, in order.
+    visible: Vec<usize>,
+    // a position in `visible`.
+    highlighted: Option<usize>,
+    // set when the user clicks an option; cleared by the dropdown.
+    chosen: Option<usize>,
+    row_height: f64,
+}
+
+impl<T: Data + PartialEq> Dropdown<T> {
+    /// Create a new `Dropdown` from `(label, value)` pairs.
+    pub fn new(options: impl IntoIterator<Item = (impl Into<String>, T)>) -> Self {
+        Dropdown::with_search(options, false)
+    }
+
+    /// Create a new `Dropdown` whose list can be filtered by typing.
+    pub fn searchable(options: impl IntoIterator<Item = (impl Into<String>, T)>) -> Self {
+        Dropdown::with_search(options, true)
+    }
+
+    fn with_search(
+        options: impl IntoIterator<Item = (impl Into<String>, T)>,
+        searchable: bool,
+    ) -> Self {
+        let options: Rc<Vec<(String, T)>> = Rc::new(
+            options
+                .into_iter()
+                .map(|(label, value)| (label.into(), value))
+                .collect(),
+        );
+        let label_options = options.clone();
+        let label = Label::dynamic(move |data: &T, _| {
+            label_options
+                .iter()
+                .find(|(_, value)| value == data)
+                .map(|(label, _)| label.clone())
+                .unwrap_or_default()
+        })
+        .padding((
+            LABEL_X_PADDING,
+            LABEL_Y_PADDING,
+            ARROW_SPACE,
+            LABEL_Y_PADDING,
+        ));
+        let list = OptionList {
+            options: options.clone(),
+            searchable,
+            filter: String::new(),
+            placeholder: LocalizedString::new("dropdown-search-placeholder")
+                .with_placeholder("Search"),
+            visible: Vec::new(),
+            highlighted: None,
+            chosen: None,
+            row_height: 0.0,
+        };
+        Dropdown {
+            popup: Popup::new(label, list),
+            options,
+            searchable,
+            type_ahead: String::new(),
+            last_typed: None,
+        }
+    }
+
+    fn open(&mut self, ctx: &mut EventCtx, data: &T) {
+        let selected = self.options.iter().position(|(_, value)| value == data);
+        self.popup.content_mut().reset(selected);
+        self.popup.set_open(true);
+        self.type_ahead.clear();
+        ctx.request_layout();
+    }
+
+    fn close(&mut self, ctx: &mut EventCtx) {
+        self.popup.set_open(false);
+        self.type_ahead.clear();
+        ctx.request_layout();
+    }
+
+    /// The first option whose label starts with what has been typed
+    /// recently, including `text`.
+    fn type_ahead(&mut self, now: Instant, text: &str) -> Option<usize> {
+        let expired = self
+            .last_typed
+            .map(|last| now.duration_since(last) > TYPE_AHEAD_TIMEOUT)
+            .unwrap_or(true);
+        if expired {
+            self.type_ahead.clear();
+        }
+        self.type_ahead.push_str(&text.to_lowercase());
+        self.last_typed = Some(now);
+        let prefix = &self.type_ahead;
+        self.options
+            .iter()
+            .position(|(label, _)| label.to_lowercase().starts_with(prefix.as_str()))
+    }
+
+    /// Handle a key while focused, returning `true` if it was used.
+    fn key_down(&mut self, ctx: &mut EventCtx, key: &KeyEvent, data: &mut T) -> bool {
+        if ctx.handle_focus_key(key) {
+            self.close(ctx);
+            return true;
+        }
+        let text = key.text().filter(|text| is_printable(text));
+
+        if !self.popup.is_open() {
+            match key.key_code {
+                KeyCode::Space | KeyCode::Return | KeyCode::ArrowDown | KeyCode::ArrowUp => {
+                    self.open(ctx, data)
+                }
+                _ => match text {
+                    Some(text) if self.searchable => {
+                        self.open(ctx, data);
+                        self.popup.content_mut().push_filter(text);
+                    }
+                    Some(text) => {
+                        if let Some(index) = self.type_ahead(ctx.now(), text) {
+                            *data = self.options[index].1.clone();
+                        }
+                    }
+                    None => return false,
+                },
+            }
+            return true;
+        }
+
+        match key.key_code {
+            KeyCode::ArrowDown => self.popup.content_mut().move_highlight(1),
+            KeyCode::ArrowUp => self.popup.content_mut().move_highlight(-1),
+            KeyCode::Return | KeyCode::NumpadEnter => {
+                if let Some(index) = self.popup.content_mut().highlighted_option() {
+                    *data = self.options[index].1.clone();
+                }
+                self.close(ctx);
+            }
+            KeyCode::Space if !self.searchable => {
+                if let Some(index) = self.popup.content_mut().highlighted_option() {
+                    *data = self.options[index].1.clone();
+                }
+                self.close(ctx);
+            }
+            KeyCode::Escape => self.close(ctx),
+            KeyCode::Backspace if self.searchable => {
+                self.popup.content_mut().pop_filter();
+                ctx.request_layout();
+            }
+            _ => match text {
+                Some(text) if self.searchable => {
+                    self.popup.content_mut().push_filter(text);
+                    ctx.request_layout();
+                }
+                Some(text) => {
+                    if let Some(index) = self.type_ahead(ctx.now(), text) {
+                        self.popup.content_mut().highlight_option(index);
+                    }
+                }
+                None => return false,
+            },
+        }
+        ctx.request_paint();
+        true
+    }
+}
+
+impl<T: Data> OptionList<T> {
+    /// Prepare to be shown, with every option visible and `selected`
+    /// highlighted.
+    fn reset(&mut self, selected: Option<usize>) {
+        self.filter.clear();
+        self.refilter();
+        self.highlighted = selected.or(Some(0)).filter(|_| !self.visible.is_empty());
+    }
+
+    fn refilter(&mut self) {
+        let filter = self.filter.to_lowercase();
+        self.visible = (0..self.options.len())
+            .filter(|i| self.options[*i].0.to_lowercase().contains(&filter))
+            .collect();
+        self.highlighted = if self.visible.is_empty() {
+            None
+        } else {
+            Some(0)
+        };
+    }
+
+    fn push_filter(&mut self, text: &str) {
+        self.filter.push_str(text);
+        self.refilter();
+    }
+
+    fn pop_filter(&mut self) {
+        self.filter.pop();
+        self.refilter();
+    }
+
+    fn move_highlight(&mut self, delta: isize) {
+        if self.visible.is_empty() {
+            return;
+        }
+        let last = self.visible.len() as isize - 1;
+        let current = self.highlighted.map(|i| i as isize).unwrap_or(-1);
+        self.highlighted = Some((current + delta).max(0).min(last) as usize);
+    }
+
+    fn highlight_option(&mut self, index: usize) {
+        if let Some(position) = self.visible.iter().position(|i| *i == index) {
+            self.highlighted = Some(position);
+        }
+    }
+
+    fn highlighted_option(&self) -> Option<usize> {
+        self.highlighted.map(|i| self.visible[i])
+    }
+
+    /// The top of the first option's row.
+    fn rows_top(&self) -> f64 {
+        if self.searchable {
+            self.row_height
+        } else {
+            0.0
+        }
+    }
+
+    /// The position in `visible` of the row at `pos`.
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        let y = pos.y - self.rows_top();
+        if y < 0.0 || self.row_height <= 0.0 {
+            return None;
+        }
+        Some((y / self.row_height) as usize).filter(|i| *i < self.visible.len())
+    }
+}
+
+impl<T: Data> Widget<T> for OptionList<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, _data: &mut T, _env: &Env) {
+        match event {
+            Event::MouseMoved(mouse) => {
+                if let Some(row) = self.row_at(mouse.pos) {
+                    if self.highlighted != Some(row) {
+                        self.highlighted = Some(row);
+                        ctx.request_paint();
+                    }
+                }
+            }
+            Event::MouseDown(mouse) if self.row_at(mouse.pos).is_some() => {
+                ctx.set_active(true);
+                ctx.set_handled();
+            }
+            Event::MouseUp(mouse) if ctx.is_active() => {
+                ctx.set_active(false);
+                if ctx.is_hot() {
+                    self.chosen = self.row_at(mouse.pos).map(|row| self.visible[row]);
+                }
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, _: &mut LifeCycleCtx, event: &LifeCycle, _: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.placeholder.resolve(&(), env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _: &T, _: &T, env: &Env) {
+        if self.placeholder.resolve(&(), env) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _: &T, env: &Env) -> Size {
+        bc.debug_check("Dropdown list");
+
+        let labels = self.options.iter().map(|(label, _)| label.as_str());
+        let width = text_layouts(ctx.text(), labels, env)
+            .iter()
+            .map(|layout| layout.width())
+            .fold(0.0, f64::max);
+        self.row_height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
+        let rows = self.visible.len().max(1) + if self.searchable { 1 } else { 0 };
+        bc.constrain(Size::new(
+            width + LABEL_X_PADDING * 2.0,
+            self.row_height * rows as f64,
+        ))
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let width = ctx.size().width;
+        let font_size = env.get(theme::TEXT_SIZE_NORMAL);
+        let baseline = (self.row_height + font_size * 0.7) / 2.0;
+        let label_color = env.get(theme::LABEL_COLOR);
+
+        if self.searchable {
+            let (text, color) = if self.filter.is_empty() {
+                (
+                    self.placeholder.localized_str(),
+                    env.get(theme::PLACEHOLDER_COLOR),
+                )
+            } else {
+                (self.filter.as_str(), label_color.clone())
+            };
+            let layout = &text_layouts(ctx.text(), std::iter::once(text), env)[0];
+            ctx.draw_text(layout, (LABEL_X_PADDING, baseline), &color);
+            let y = self.row_height - 0.5;
+            ctx.stroke(
+                Line::new((0.0, y), (width, y)),
+                &env.get(theme::BORDER_LIGHT),
+                1.0,
+            );
+        }
+
+        let top = self.rows_top();
+        let labels = self
+            .visible
+            .iter()
+            .map(|i| self.options[*i].0.as_str())
+            .collect::<Vec<_>>();
+        let layouts = text_layouts(ctx.text(), labels.into_iter(), env);
+        for (row, layout) in layouts.iter().enumerate() {
+            let y = top + self.row_height * row as f64;
+            if self.highlighted == Some(row) {
+                let rect = Rect::new(0.0, y, width, y + self.row_height);
+                ctx.fill(rect, &env.get(theme::PRIMARY_DARK));
+            }
+            // the selected option is marked with a dot.
+            if self.options[self.visible[row]].1.same(data) {
+                let dot = Circle::new((LABEL_X_PADDING / 2.0, y + self.row_height / 2.0), 2.0);
+                ctx.fill(dot, &label_color);
+            }
+            ctx.draw_text(layout, (LABEL_X_PADDING, y + baseline), &label_color);
+        }
+    }
+}
+
+impl<T: Data + PartialEq> Widget<T> for Dropdown<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::KeyDown(key) if ctx.is_focused() && self.key_down(ctx, key, data) => {
+                ctx.set_handled();
+            }
+            Event::MouseDown(mouse) if mouse.button.is_left() && !self.popup.is_open() => {
+                self.open(ctx, data);
+                ctx.request_focus();
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+
+        if !ctx.is_handled() && self.popup.event(ctx, event, data, env) {
+            self.close(ctx);
+        }
+        if let Some(index) = self.popup.content_mut().chosen.take() {
+            *data = self.options[index].1.clone();
+            self.close(ctx);
+        }
+        self.popup.capture_mouse(ctx);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::HotChanged(_) | LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        self.popup.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        if old_data != data {
+            ctx.request_paint();
+        }
+        self.popup.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Dropdown");
+
+        // wide enough for any option, so that choosing one doesn't move things.
+        let labels = self.options.iter().map(|(label, _)| label.as_str());
+        let widest = text_layouts(ctx.text(), labels, env)
+            .iter()
+            .map(|layout| layout.width())
+            .fold(0.0, f64::max);
+        let min_width = (widest + LABEL_X_PADDING + ARROW_SPACE)
+            .max(bc.min().width)
+            .min(bc.max().width);
+        let bc = BoxConstraints::new(Size::new(min_width, bc.min().height), bc.max());
+        self.popup.layout(ctx, &bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let size = ctx.size();
+        let frame = size
+            .to_rect()
+            .inset(-0.5)
+            .to_rounded_rect(env.get(theme::TEXTBOX_BORDER_RADIUS));
+        let border = if ctx.has_focus() {
+            env.get(theme::PRIMARY_LIGHT)
+        } else if ctx.is_hot() {
+            env.get(theme::BORDER_LIGHT)
+        } else {
+            env.get(theme::BORDER_DARK)
+        };
+        ctx.fill(frame, &env.get(theme::BACKGROUND_LIGHT));
+        ctx.stroke(frame, &border, 1.0);
+
+        let center = Point::new(size.width - ARROW_SPACE / 2.0, size.height / 2.0);
+        let mut arrow = BezPath::new();
+        arrow.move_to(center + Vec2::new(-4.0, -2.0));
+        arrow.line_to(center + Vec2::new(4.0, -2.0));
+        arrow.line_to(center + Vec2::new(0.0, 3.0));
+        arrow.close_path();
+        ctx.fill(arrow, &env.get(theme::LABEL_COLOR));
+
+        self.popup.paint(ctx, data, env);
+    }
+}
+
+/// Lay out each of `labels` in the theme's font.
+pub(super) fn text_layouts<'a>(
+    text: &mut PietText,
+    labels: impl Iterator<Item = &'a str>,
+    env: &Env,
+) -> Vec<PietTextLayout> {
+    let font = text
+        .new_font_by_name(env.get(theme::FONT_NAME), env.get(theme::TEXT_SIZE_NORMAL))
+        .build()
+        .unwrap();
+    labels
+        .map(|label| text.new_text_layout(&font, label).build().unwrap())
+        .collect()
+}
+
+fn is_printable(text: &str) -> bool {
+    !text.is_empty() && text.chars().all(|c| !c.is_control())
+}
@@ -101,6 +101,14 @@ use crate::{
 ///
 /// Additional options can be set (or overridden) in the [`FlexParams`].
 ///
+/// # Right-to-left layout
+///
+/// "Start" and "end" are leading and trailing edges, not fixed directions.
+/// When [`Env::IS_RTL`] is set, a row lays out its children from right to
+/// left, and in a column `CrossAxisAlignment::Start` aligns children to the
+/// right. A row created with [`row_reversed`] runs the other way from an
+/// ordinary row, as does a column created with [`column_reversed`].
+///
 /// # Examples
 ///
 /// Construction with builder methods
@@ -134,6 +142,9 @@ use crate::{
 /// [`CrossAxisAlignment`]: enum.CrossAxisAlignment.html
 /// [`must_fill_main_axis`]: struct.Flex.html#method.must_fill_main_axis
 /// [`FlexParams`]: struct.FlexParams.html
+/// [`Env::IS_RTL`]: ../struct.Env.html#associatedconstant.IS_RTL
+/// [`row_reversed`]: #method.row_reversed
+/// [`column_reversed`]: #method.column_reversed
 /// [`WidgetExt`]: ../trait.WidgetExt.html
 /// [`expand_height`]: ../trait.WidgetExt.html#method.expand_height
 /// [`expand_width`]: ../trait.WidgetExt.html#method.expand_width
@@ -144,6 +155,7 @@ pub struct Flex<T> {
     cross_alignment: CrossAxisAlignment,
     main_alignment: MainAxisAlignment,
     fill_major_axis: bool,
+    reversed: bool,
     children: Vec<ChildWidget<T>>,
 }
 
@@ -195,7 +207,7 @@ pub struct FlexParams {
     alignment: Option<CrossAxisAlignment>,
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub(crate) enum Axis {
     Horizontal,
     Vertical,
@@ -209,16 +221,26 @@ pub(crate) enum Axis {
 pub enum CrossAxisAlignment {
     /// Top or leading.
     ///
-    /// In a vertical container, widgets are top aligned. In a horiziontal
-    /// container, their leading edges are aligned.
+    /// In a horizontal container, widgets are top aligned. In a vertical
+    /// container, their leading edges are aligned: the left edges, or the
+    /// right ones if [`Env::IS_RTL`] is set.
+    ///
+    /// [`Env::IS_RTL`]: ../struct.Env.html#associatedconstant.IS_RTL
     Start,
     /// Widgets are centered in the container.
     Center,
     /// Bottom or trailing.
     ///
-    /// In a vertical container, widgets are bottom aligned. In a horiziontal
+    /// In a horizontal container, widgets are bottom aligned. In a vertical
     /// container, their trailing edges are aligned.
     End,
+    /// Widgets are all as big on the cross axis as the biggest of them (by
+    /// its [`max intrinsic size`]), within the container's constraints.
+    ///
+    /// This is how to make every cell in a column as wide as the widest.
+    ///
+    /// [`max intrinsic size`]: ../trait.Widget.html#method.max_intrinsic_width
+    Fill,
 }
 
 /// Arrangement of children on the main axis.
@@ -286,6 +308,14 @@ impl Axis {
         }
     }
 
+    /// The other axis.
+    pub(crate) fn cross(self) -> Axis {
+        match self {
+            Axis::Horizontal => Axis::Vertical,
+            Axis::Vertical => Axis::Horizontal,
+        }
+    }
+
     pub(crate) fn minor(self, coords: Size) -> f64 {
         match self {
             Axis::Horizontal => coords.height,
@@ -300,6 +330,19 @@ impl Axis {
         }
     }
 
+    /// Replace the constraints on the minor axis with a tight `minor`.
+    fn with_tight_minor(self, bc: BoxConstraints, minor: f64) -> BoxConstraints {
+        let (min, max) = (bc.min(), bc.max());
+        match self {
+            Axis::Horizontal => {
+                BoxConstraints::new(Size::new(min.width, minor), Size::new(max.width, minor))
+            }
+            Axis::Vertical => {
+                BoxConstraints::new(Size::new(minor, min.height), Size::new(minor, max.height))
+            }
+        }
+    }
+
     /// Generate constraints with new values on the major axis.
     fn constraints(self, bc: &BoxConstraints, min_major: f64, major: f64) -> BoxConstraints {
         match self {
@@ -316,30 +359,48 @@ impl Axis {
 }
 
 impl<T: Data> Flex<T> {
-    /// Create a new horizontal stack.
-    ///
-    /// The child widgets are laid out horizontally, from left to right.
-    pub fn row() -> Self {
+    fn new(direction: Axis, reversed: bool) -> Self {
         Flex {
-            direction: Axis::Horizontal,
+            direction,
             children: Vec::new(),
             cross_alignment: CrossAxisAlignment::Center,
             main_alignment: MainAxisAlignment::Start,
             fill_major_axis: false,
+            reversed,
         }
     }
 
+    /// Create a new horizontal stack.
+    ///
+    /// The child widgets are laid out horizontally, from left to right,
+    /// or from right to left if [`Env::IS_RTL`] is set.
+    ///
+    /// [`Env::IS_RTL`]: ../struct.Env.html#associatedconstant.IS_RTL
+    pub fn row() -> Self {
+        Flex::new(Axis::Horizontal, false)
+    }
+
+    /// Create a new horizontal stack that runs the opposite way to a [`row`].
+    ///
+    /// The first child is placed at the trailing edge, and
+    /// [`MainAxisAlignment::Start`] packs children towards that edge.
+    ///
+    /// [`row`]: #method.row
+    /// [`MainAxisAlignment::Start`]: enum.MainAxisAlignment.html#variant.Start
+    pub fn row_reversed() -> Self {
+        Flex::new(Axis::Horizontal, true)
+    }
+
     /// Create a new vertical stack.
     ///
     /// The child widgets are laid out vertically, from top to bottom.
     pub fn column() -> Self {
-        Flex {
-            direction: Axis::Vertical,
-            children: Vec::new(),
-            cross_alignment: CrossAxisAlignment::Center,
-            main_alignment: MainAxisAlignment::Start,
-            fill_major_axis: false,
-        }
+        Flex::new(Axis::Vertical, false)
+    }
+
+    /// Create a new vertical stack, laid out from bottom to top.
+    pub fn column_reversed() -> Self {
+        Flex::new(Axis::Vertical, true)
     }
 
     /// Builder-style method for specifying the childrens' [`CrossAxisAlignment`].
@@ -427,6 +488,24 @@ impl<T: Data> Flex<T> {
     }
 
     /// Builder-style method for adding a `flex` spacer to the container.
+    ///
+    /// A flex spacer takes a share of the space left over after the
+    /// non-flex children are laid out, in proportion to `flex`, exactly like
+    /// a flex child would. Putting one before a child pushes that child to
+    /// the far end of the container:
+    ///
+    /// ```
+    /// # use druid::widget::{Button, Flex};
+    /// let buttons = Flex::<()>::row()
+    ///     .with_child(Button::new("Start"))
+    ///     .with_flex_spacer(1.0)
+    ///     .with_child(Button::new("Reset"));
+    /// ```
+    ///
+    /// The container must have a finite size on its main axis for this to
+    /// have any effect; see [`must_fill_main_axis`].
+    ///
+    /// [`must_fill_main_axis`]: #method.must_fill_main_axis
     pub fn with_flex_spacer(mut self, flex: f64) -> Self {
         self.add_flex_spacer(flex);
         self
@@ -514,6 +593,59 @@ impl<T: Data> Flex<T> {
     }
 }
 
+impl<T: Data> Flex<T> {
+    /// The size on the minor axis of children that use
+    /// `CrossAxisAlignment::Fill`, if there are any.
+    fn fill_minor(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> Option<f64> {
+        let cross_alignment = self.cross_alignment;
+        let has_fill = self.children.iter().any(|child| {
+            child.params.alignment.unwrap_or(cross_alignment) == CrossAxisAlignment::Fill
+        });
+        if !has_fill {
+            return None;
+        }
+        let widest = self.intrinsic(ctx, self.direction.cross(), true, f64::INFINITY, data, env);
+        let (min, max) = (
+            self.direction.minor(bc.min()),
+            self.direction.minor(bc.max()),
+        );
+        Some(widest.max(min).min(max))
+    }
+
+    /// The min or max intrinsic size on `axis`, given `extent` on the
+    /// other axis.
+    fn intrinsic(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        max: bool,
+        extent: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let sizes = self.children.iter_mut().map(|child| {
+            let child = &mut child.widget;
+            match (axis, max) {
+                (Axis::Horizontal, false) => child.min_intrinsic_width(ctx, extent, data, env),
+                (Axis::Horizontal, true) => child.max_intrinsic_width(ctx, extent, data, env),
+                (Axis::Vertical, false) => child.min_intrinsic_height(ctx, extent, data, env),
+                (Axis::Vertical, true) => child.max_intrinsic_height(ctx, extent, data, env),
+            }
+        });
+        if axis == self.direction {
+            sizes.sum()
+        } else {
+            sizes.fold(0., f64::max)
+        }
+    }
+}
+
 impl<T: Data> Widget<T> for Flex<T> {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         for child in &mut self.children {
@@ -537,23 +669,34 @@ impl<T: Data> Widget<T> for Flex<T> {
         bc.debug_check("Flex");
         // we loosen our constraints when passing to children.
         let loosened_bc = bc.loosen();
+        let fill_minor = self.fill_minor(ctx, bc, data, env);
+        let cross_alignment = self.cross_alignment;
+        let direction = self.direction;
+        let fill = |child: &ChildWidget<T>, child_bc: BoxConstraints| {
+            let alignment = child.params.alignment.unwrap_or(cross_alignment);
+            match fill_minor {
+                Some(minor) if alignment == CrossAxisAlignment::Fill => {
+                    direction.with_tight_minor(child_bc, minor)
+                }
+                _ => child_bc,
+            }
+        };
 
         // Measure non-flex children.
         let mut major_non_flex = 0.0;
         let mut minor = self.direction.minor(bc.min());
         for child in &mut self.children {
             if child.params.flex == 0.0 {
-                let child_bc = self
-                    .direction
-                    .constraints(&loosened_bc, 0., std::f64::INFINITY);
+                let child_bc = self.direction.constraints(&loosened_bc, 0., f64::INFINITY);
+                let child_bc = fill(child, child_bc);
                 let child_size = child.widget.layout(ctx, &child_bc, data, env);
 
                 if child_size.width.is_infinite() {
-                    log::warn!("A non-Flex child has an infinite width.");
+                    log::warn!(target: "druid::layout", "A non-Flex child has an infinite width.");
                 }
 
                 if child_size.height.is_infinite() {
-                    log::warn!("A non-Flex child has an infinite height.");
+                    log::warn!(target: "druid::layout", "A non-Flex child has an infinite height.");
                 }
 
                 major_non_flex += self.direction.major(child_size).expand();
@@ -581,6 +724,7 @@ impl<T: Data> Widget<T> for Flex<T> {
                 let child_bc = self
                     .direction
                     .constraints(&loosened_bc, min_major, actual_major);
+                let child_bc = fill(child, child_bc);
                 let child_size = child.widget.layout(ctx, &child_bc, data, env);
 
                 major_flex += self.direction.major(child_size).expand();
@@ -603,7 +747,6 @@ impl<T: Data> Widget<T> for Flex<T> {
         let mut spacing = Spacing::new(self.main_alignment, extra, self.children.len());
         // Finalize layout, assigning positions to each child.
         let mut major = spacing.next().unwrap_or(0.);
-        let mut child_paint_rect = Rect::ZERO;
         for child in &mut self.children {
             let rect = child.widget.layout_rect();
             let extra_minor = minor - self.direction.minor(rect.size());
@@ -612,13 +755,12 @@ impl<T: Data> Widget<T> for Flex<T> {
             let pos: Point = self.direction.pack(major, align_minor).into();
 
             child.widget.set_layout_rect(rect.with_origin(pos));
-            child_paint_rect = child_paint_rect.union(child.widget.paint_rect());
             major += self.direction.major(rect.size()).expand();
             major += spacing.next().unwrap_or(0.);
         }
 
         if flex_sum > 0.0 && total_major.is_infinite() {
-            log::warn!("A child of Flex is flex, but Flex is unbounded.")
+            log::warn!(target: "druid::layout", "A child of Flex is flex, but Flex is unbounded.")
         }
 
         if flex_sum > 0.0 {
@@ -637,12 +779,73 @@ impl<T: Data> Widget<T> for Flex<T> {
             bc.constrain(my_size)
         };
 
+        // positions so far run from the leading edge; flip them if that
+        // edge is on the right, or the bottom.
+        let is_rtl = env.get(Env::IS_RTL);
+        let (flip_x, flip_y) = match self.direction {
+            Axis::Horizontal => (self.reversed != is_rtl, false),
+            Axis::Vertical => (is_rtl, self.reversed),
+        };
+        let mut child_paint_rect = Rect::ZERO;
+        for child in &mut self.children {
+            let rect = child.widget.layout_rect();
+            let mut pos = rect.origin();
+            if flip_x {
+                pos.x = my_size.width - rect.x1;
+            }
+            if flip_y {
+                pos.y = my_size.height - rect.y1;
+            }
+            child.widget.set_layout_rect(rect.with_origin(pos));
+            child_paint_rect = child_paint_rect.union(child.widget.paint_rect());
+        }
+
         let my_bounds = Rect::ZERO.with_size(my_size);
         let insets = child_paint_rect - my_bounds;
         ctx.set_paint_insets(insets);
         my_size
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Axis::Horizontal, false, height, data, env)
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Axis::Horizontal, true, height, data, env)
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Axis::Vertical, false, width, data, env)
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Axis::Vertical, true, width, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         for child in &mut self.children {
             child.widget.paint_with_offset(ctx, data, env);
@@ -659,6 +862,7 @@ impl CrossAxisAlignment {
             CrossAxisAlignment::Start => 0.0,
             CrossAxisAlignment::Center => (val / 2.0).round(),
             CrossAxisAlignment::End => val,
+            CrossAxisAlignment::Fill => 0.0,
         }
     }
 }
@@ -0,0 +1,138 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that changes how keyboard focus is shown on its child.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::theme;
+use crate::{
+    BoxConstraints, Color, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, UpdateCtx, Widget, WidgetPod,
+};
+
+/// A function that paints a custom focus indicator.
+type FocusPaintFn = dyn Fn(&mut PaintCtx, &Env);
+
+/// How a [`FocusStyled`] widget shows that it has keyboard focus.
+///
+/// [`FocusStyled`]: struct.FocusStyled.html
+pub enum FocusStyle {
+    /// A ring around the widget, like the default one but with a different
+    /// color, width, or distance from the widget's edge.
+    Ring {
+        /// The color of the ring.
+        ///
+        /// The ring is drawn in [`theme::PRIMARY_LIGHT`], so this sets
+        /// that key for the child.
+        ///
+        /// [`theme::PRIMARY_LIGHT`]: ../theme/constant.PRIMARY_LIGHT.html
+        color: Color,
+        /// The width of the ring's stroke.
+        width: f64,
+        /// The gap between the widget's edge and the ring.
+        offset: f64,
+    },
+    /// No focus indicator. This is for widgets that show focus themselves.
+    None,
+    /// A function called after the widget paints, while it has focus.
+    ///
+    /// The function is passed the widget's paint context, so it can use
+    /// `ctx.size()` to find the widget's bounds. The painting should stay
+    /// within [`FOCUS_RING_OFFSET`] of those bounds, so that it is cleared
+    /// when the widget loses focus.
+    ///
+    /// [`FOCUS_RING_OFFSET`]: ../theme/constant.FOCUS_RING_OFFSET.html
+    Custom(Box<FocusPaintFn>),
+}
+
+/// A widget that changes how keyboard focus is shown on its child.
+///
+/// Every focused widget gets a ring drawn around it, styled by the
+/// `PRIMARY_LIGHT` and `FOCUS_RING_*` keys in [`theme`]. This widget
+/// overrides that style for its child, and for the child's descendants.
+///
+/// You will generally want to create this with [`WidgetExt::with_focus_style`].
+///
+/// [`theme`]: ../theme/index.html
+/// [`WidgetExt::with_focus_style`]: ../trait.WidgetExt.html#method.with_focus_style
+pub struct FocusStyled<T, W> {
+    child: WidgetPod<T, W>,
+    style: FocusStyle,
+}
+
+impl<T: Data, W: Widget<T>> FocusStyled<T, W> {
+    /// Create a new `FocusStyled` widget, showing focus on `child` with `style`.
+    pub fn new(child: W, style: FocusStyle) -> Self {
+        FocusStyled {
+            child: WidgetPod::new(child),
+            style,
+        }
+    }
+
+    fn child_env(&self, env: &Env) -> Env {
+        match &self.style {
+            FocusStyle::Ring {
+                color,
+                width,
+                offset,
+            } => env
+                .clone()
+                .adding(theme::PRIMARY_LIGHT, color.clone())
+                .adding(theme::FOCUS_RING_WIDTH, *width)
+                .adding(theme::FOCUS_RING_OFFSET, *offset),
+            FocusStyle::None | FocusStyle::Custom(_) => {
+                env.clone().adding(theme::FOCUS_RING_WIDTH, 0.0)
+            }
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for FocusStyled<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let env = self.child_env(env);
+        self.child.event(ctx, event, data, &env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        let env = self.child_env(env);
+        self.child.lifecycle(ctx, event, data, &env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let env = self.child_env(env);
+        self.child.update(ctx, data, &env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("FocusStyled");
+
+        let env = self.child_env(env);
+        let size = self.child.layout(ctx, bc, data, &env);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let child_env = self.child_env(env);
+        self.child.paint(ctx, data, &child_env);
+
+        if let FocusStyle::Custom(paint) = &self.style {
+            if ctx.focus_widget == Some(self.child.id()) {
+                ctx.with_save(|ctx| paint(ctx, env));
+            }
+        }
+    }
+}
@@ -0,0 +1,99 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that edits a number as localized text.
+
+use crate::format::NumberFormatter;
+use crate::kurbo::Size;
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, UpdateCtx,
+    Widget, WidgetId,
+};
+
+/// Converts between a number and the text of a `Widget<String>`, such as a
+/// [`TextBox`], using a [`NumberFormatter`].
+///
+/// This is like [`Parse`], but follows the number conventions of the
+/// current locale. Text that doesn't parse leaves the number unchanged, so
+/// the user can type freely; the text is reformatted when the number is
+/// changed elsewhere, or when the locale changes.
+///
+/// # Examples
+///
+/// ```
+/// use druid::format::NumberFormatter;
+/// use druid::widget::{Formatted, TextBox};
+///
+/// let input = Formatted::new(TextBox::new(), NumberFormatter::new().fraction_digits(2, 2));
+/// ```
+///
+/// [`TextBox`]: struct.TextBox.html
+/// [`NumberFormatter`]: ../format/struct.NumberFormatter.html
+/// [`Parse`]: struct.Parse.html
+pub struct Formatted<W> {
+    widget: W,
+    formatter: NumberFormatter,
+    text: String,
+}
+
+impl<W: Widget<String>> Formatted<W> {
+    /// Create a new `Formatted` widget, showing numbers in `widget`.
+    pub fn new(widget: W, formatter: NumberFormatter) -> Self {
+        Formatted {
+            widget,
+            formatter,
+            text: String::new(),
+        }
+    }
+}
+
+impl<W: Widget<String>> Widget<f64> for Formatted<W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut f64, env: &Env) {
+        self.widget.event(ctx, event, &mut self.text, env);
+        if let Ok(value) = self.formatter.parse(&self.text, env) {
+            *data = value;
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &f64, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.text = self.formatter.format(*data, env);
+        }
+        self.widget.lifecycle(ctx, event, &self.text, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &f64, data: &f64, env: &Env) {
+        // leave text that still means this number alone, so editing isn't
+        // interrupted by the value it produces. The child is still updated,
+        // since it may depend on more than its text.
+        if self.formatter.parse(&self.text, env) == Ok(*data) {
+            self.widget.update(ctx, &self.text, &self.text, env);
+            return;
+        }
+        let old = std::mem::replace(&mut self.text, self.formatter.format(*data, env));
+        self.widget.update(ctx, &old, &self.text, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, _data: &f64, env: &Env) -> Size {
+        self.widget.layout(ctx, bc, &self.text, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, _data: &f64, env: &Env) {
+        self.widget.paint(ctx, &self.text, env)
+    }
+
+    fn id(&self) -> Option<WidgetId> {
+        self.widget.id()
+    }
+}
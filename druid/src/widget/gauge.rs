@@ -0,0 +1,258 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A circular gauge widget.
+
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::time::Duration;
+
+use crate::kurbo::{Arc, BezPath, Rect, Size, Vec2};
+use crate::piet::{LineCap, StrokeStyle};
+use crate::theme;
+use crate::{
+    BoxConstraints, Color, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, RenderContext, UpdateCtx, Widget, WidgetPod,
+};
+
+/// The default time a [`Gauge`] takes to move to a new value.
+///
+/// [`Gauge`]: struct.Gauge.html
+pub const DEFAULT_GAUGE_ANIMATION: Duration = Duration::from_millis(200);
+
+/// A circular gauge, showing a fraction as an arc around a ring.
+///
+/// This type impls `Widget<f64>`, expecting a float in the range `0.0..1.0`.
+///
+/// By default the ring is a full circle, starting at the top and filling
+/// clockwise. When the value changes the arc animates towards it; animation
+/// frames are only requested until it gets there. If
+/// [`theme::REDUCED_MOTION`] is set, the arc jumps to the new value instead.
+///
+/// A widget can be shown in the middle of the ring with [`with_center`],
+/// such as a [`Label`] with the time remaining.
+///
+/// [`with_center`]: #method.with_center
+/// [`Label`]: struct.Label.html
+/// [`theme::REDUCED_MOTION`]: ../theme/constant.REDUCED_MOTION.html
+pub struct Gauge {
+    start_angle: f64,
+    sweep: f64,
+    thickness: KeyOrValue<f64>,
+    track_color: KeyOrValue<Color>,
+    fill_color: KeyOrValue<Color>,
+    animation: Duration,
+    center: Option<WidgetPod<f64, Box<dyn Widget<f64>>>>,
+    // the fraction currently drawn, and where the animation started.
+    shown: f64,
+    anim_from: f64,
+    anim_progress: f64,
+}
+
+impl Gauge {
+    /// Create a new `Gauge`.
+    pub fn new() -> Gauge {
+        Gauge {
+            start_angle: 0.0,
+            sweep: 2.0 * PI,
+            thickness: 6.0.into(),
+            track_color: theme::BACKGROUND_LIGHT.into(),
+            fill_color: theme::PRIMARY_LIGHT.into(),
+            animation: DEFAULT_GAUGE_ANIMATION,
+            center: None,
+            shown: 0.0,
+            anim_from: 0.0,
+            anim_progress: 1.0,
+        }
+    }
+
+    /// Builder-style method to set where the arc starts, in radians
+    /// clockwise from the top.
+    pub fn with_start_angle(mut self, radians: f64) -> Self {
+        self.start_angle = radians;
+        self
+    }
+
+    /// Builder-style method to set the angle, in radians, that a full gauge
+    /// covers.
+    ///
+    /// This is `2π`, a full circle, by default. A negative sweep fills the
+    /// gauge anticlockwise.
+    pub fn with_sweep(mut self, radians: f64) -> Self {
+        self.sweep = radians;
+        self
+    }
+
+    /// Builder-style method to set width of the ring.
+    ///
+    /// The argument can be either an `f64` or a [`Key<f64>`].
+    ///
+    /// [`Key<f64>`]: ../struct.Key.html
+    pub fn with_thickness(mut self, thickness: impl Into<KeyOrValue<f64>>) -> Self {
+        self.thickness = thickness.into();
+        self
+    }
+
+    /// Builder-style method to set the color of the unfilled part of the ring.
+    pub fn with_track_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.track_color = color.into();
+        self
+    }
+
+    /// Builder-style method to set the color of the arc showing the value.
+    pub fn with_fill_color(mut self, color: impl Into<KeyOrValue<Color>>) -> Self {
+        self.fill_color = color.into();
+        self
+    }
+
+    /// Builder-style method to set how long the arc takes to move to a new
+    /// value. `Duration::from_secs(0)` turns the animation off.
+    pub fn with_animation_duration(mut self, duration: Duration) -> Self {
+        self.animation = duration;
+        self
+    }
+
+    /// Builder-style method to show a widget in the middle of the ring.
+    pub fn with_center(mut self, center: impl Widget<f64> + 'static) -> Self {
+        self.center = Some(WidgetPod::new(Box::new(center)));
+        self
+    }
+
+    /// The arc covering `fraction` of the gauge, inside `size`.
+    fn arc(&self, size: Size, thickness: f64, fraction: f64) -> BezPath {
+        let radius = (size.width.min(size.height) - thickness).max(0.0) / 2.0;
+        let center = size.to_rect().center();
+        // kurbo measures angles clockwise from the positive x axis.
+        let start_angle = self.start_angle - FRAC_PI_2;
+        let arc = Arc {
+            center,
+            radii: Vec2::new(radius, radius),
+            start_angle,
+            sweep_angle: self.sweep * fraction,
+            x_rotation: 0.0,
+        };
+        let mut path = BezPath::new();
+        path.move_to(center + Vec2::from_angle(start_angle) * radius);
+        for el in arc.append_iter(0.1) {
+            path.push(el);
+        }
+        path
+    }
+}
+
+impl Default for Gauge {
+    fn default() -> Self {
+        Gauge::new()
+    }
+}
+
+impl Widget<f64> for Gauge {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut f64, env: &Env) {
+        if let Some(center) = self.center.as_mut() {
+            center.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &f64, env: &Env) {
+        match event {
+            LifeCycle::WidgetAdded => self.shown = data.clamp(0.0, 1.0),
+            LifeCycle::AnimFrame(interval) if self.anim_progress < 1.0 => {
+                let step = *interval as f64 / self.animation.as_nanos().max(1) as f64;
+                self.anim_progress = (self.anim_progress + step).min(1.0);
+                // ease out: fast at first, slowing as we arrive.
+                let eased = 1.0 - (1.0 - self.anim_progress).powi(2);
+                let target = data.clamp(0.0, 1.0);
+                self.shown = self.anim_from + (target - self.anim_from) * eased;
+                if self.anim_progress < 1.0 {
+                    ctx.request_anim_frame();
+                }
+                ctx.request_paint();
+            }
+            _ => (),
+        }
+        if let Some(center) = self.center.as_mut() {
+            center.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &f64, data: &f64, env: &Env) {
+        if old_data != data {
+            let target = data.clamp(0.0, 1.0);
+            if self.animation == Duration::from_secs(0) || env.get(theme::REDUCED_MOTION) {
+                self.shown = target;
+                self.anim_progress = 1.0;
+                ctx.request_paint();
+            } else if (target - self.shown).abs() > 1e-9 {
+                self.anim_from = self.shown;
+                self.anim_progress = 0.0;
+                ctx.request_anim_frame();
+            }
+        }
+        if let Some(center) = self.center.as_mut() {
+            center.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &f64, env: &Env) -> Size {
+        bc.debug_check("Gauge");
+
+        let diameter = 2.0 * theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
+        let size = bc.constrain(Size::new(diameter, diameter));
+        if let Some(center) = self.center.as_mut() {
+            let center_size = center.layout(ctx, &bc.loosen(), data, env);
+            let origin = (size.to_vec2() - center_size.to_vec2()) / 2.0;
+            center.set_layout_rect(Rect::from_origin_size(origin.to_point(), center_size));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &f64, env: &Env) {
+        let size = ctx.size();
+        let thickness = self.thickness.resolve(env);
+        let mut style = StrokeStyle::new();
+        style.set_line_cap(LineCap::Round);
+
+        let track = self.arc(size, thickness, 1.0);
+        ctx.stroke(track, &self.track_color.resolve(env), thickness);
+        if self.shown > 0.0 {
+            let fill = self.arc(size, thickness, self.shown);
+            ctx.stroke_styled(fill, &self.fill_color.resolve(env), thickness, &style);
+        }
+
+        if let Some(center) = self.center.as_mut() {
+            center.paint_with_offset(ctx, data, env);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kurbo::Point;
+
+    #[test]
+    fn arc_starts_at_the_top() {
+        let gauge = Gauge::new();
+        let arc = gauge.arc(Size::new(100., 100.), 10., 0.25);
+        let start = match arc.elements()[0] {
+            crate::kurbo::PathEl::MoveTo(p) => p,
+            _ => panic!("arc should start with a move"),
+        };
+        assert!((start - Point::new(50., 5.)).hypot() < 1e-9);
+        let end = match arc.elements().last() {
+            Some(crate::kurbo::PathEl::CurveTo(_, _, p)) => *p,
+            _ => panic!("arc should end with a curve"),
+        };
+        assert!((end - Point::new(95., 50.)).hypot() < 1e-6);
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that can hide its child, while keeping its space in the layout.
+
+use crate::inspect;
+use crate::kurbo::{Affine, Point, Rect, Size};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// A function that decides whether the child is hidden.
+type Predicate<T> = dyn Fn(&T, &Env) -> bool;
+
+/// A widget that hides its child when a predicate on the data is true.
+///
+/// A hidden child is still laid out, and takes up as much space as it would
+/// if it were visible, so nothing around it moves. It isn't painted, and
+/// doesn't receive mouse, wheel or keyboard events; it can't become hot or
+/// active, and tab skips over it. It still receives other events, such as
+/// commands.
+///
+/// To remove a widget from the layout instead, use an [`Either`] with an
+/// empty [`SizedBox`].
+///
+/// You will generally want to create this with [`WidgetExt::hidden_if`].
+///
+/// [`Either`]: struct.Either.html
+/// [`SizedBox`]: struct.SizedBox.html
+/// [`WidgetExt::hidden_if`]: ../trait.WidgetExt.html#method.hidden_if
+pub struct Hidden<T, W> {
+    child: WidgetPod<T, W>,
+    predicate: Box<Predicate<T>>,
+    hidden: bool,
+}
+
+impl<T, W: Widget<T>> Hidden<T, W> {
+    /// Create a new `Hidden` widget, hiding `child` whenever `predicate`
+    /// returns `true`.
+    pub fn new(child: W, predicate: impl Fn(&T, &Env) -> bool + 'static) -> Self {
+        Hidden {
+            child: WidgetPod::new(child),
+            predicate: Box::new(predicate),
+            hidden: false,
+        }
+    }
+
+    /// Returns `true` if the child is currently hidden.
+    pub fn is_hidden(&self) -> bool {
+        self.hidden
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Hidden<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // a hidden child can't be clicked, or typed into.
+        let is_input_event = event.is_pointer_event()
+            || matches!(
+                event,
+                Event::Zoom(_) | Event::KeyDown(_) | Event::KeyUp(_) | Event::Paste(_)
+            );
+        if !(self.hidden && is_input_event) {
+            self.child.event(ctx, event, data, env)
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.hidden = (self.predicate)(data, env);
+        }
+        if self.hidden {
+            let child = &mut self.child;
+            match inspect::tree_cell(event) {
+                // a hidden child can't be found at a point.
+                Some(tree) => {
+                    tree.with_children_transformed(Affine::default(), Some(Rect::ZERO), || {
+                        child.lifecycle(ctx, event, data, env)
+                    })
+                }
+                None => ctx.without_focus(|ctx| child.lifecycle(ctx, event, data, env)),
+            }
+        } else {
+            self.child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let hidden = (self.predicate)(data, env);
+        if hidden != self.hidden {
+            self.hidden = hidden;
+            // rebuild the focus chain, with or without the child.
+            ctx.children_changed();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Hidden");
+
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        if !self.hidden {
+            self.child.paint(ctx, data, env);
+        }
+    }
+}
@@ -31,6 +31,20 @@ impl<W> IdentityWrapper<W> {
     pub fn wrap(inner: W, id: WidgetId) -> IdentityWrapper<W> {
         IdentityWrapper { id, inner }
     }
+
+    /// Assign a newly allocated identity to a widget.
+    ///
+    /// Use [`widget_id`] to find out what it is.
+    ///
+    /// [`widget_id`]: #method.widget_id
+    pub fn new(inner: W) -> IdentityWrapper<W> {
+        IdentityWrapper::wrap(inner, WidgetId::next())
+    }
+
+    /// The id assigned to the wrapped widget.
+    pub fn widget_id(&self) -> WidgetId {
+        self.id
+    }
 }
 
 impl<T: Data, W: Widget<T>> Widget<T> for IdentityWrapper<W> {
@@ -50,6 +64,46 @@ impl<T: Data, W: Widget<T>> Widget<T> for IdentityWrapper<W> {
         self.inner.layout(ctx, bc, data, env)
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner.min_intrinsic_width(ctx, height, data, env)
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner.max_intrinsic_width(ctx, height, data, env)
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner.min_intrinsic_height(ctx, width, data, env)
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.inner.max_intrinsic_height(ctx, width, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.inner.paint(ctx, data, env);
     }
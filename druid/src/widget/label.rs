@@ -14,13 +14,17 @@
 
 //! A label widget.
 
+use std::ops::Range;
+
+use crate::kurbo::Line;
 use crate::piet::{
-    Color, FontBuilder, PietText, PietTextLayout, RenderContext, Text, TextLayout,
+    Color, FontBuilder, PietFont, PietText, PietTextLayout, RenderContext, Text, TextLayout,
     TextLayoutBuilder, UnitPoint,
 };
+use crate::text::TextDirection;
 use crate::{
-    theme, BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle,
-    LifeCycleCtx, LocalizedString, PaintCtx, Point, Size, UpdateCtx, Widget,
+    theme, BoxConstraints, Data, Env, Event, EventCtx, Insets, KeyOrValue, LayoutCtx, LifeCycle,
+    LifeCycleCtx, LocalizedString, PaintCtx, Point, Size, UpdateCtx, Vec2, Widget,
 };
 
 // a fudgey way to get an approximate line height from a font size
@@ -49,10 +53,25 @@ pub struct Dynamic<T> {
 }
 
 /// A label that displays some text.
+///
+/// If the label is wider than its text, text whose paragraph direction is
+/// right-to-left is drawn against its right edge.
 pub struct Label<T> {
     text: LabelText<T>,
     color: KeyOrValue<Color>,
     size: KeyOrValue<f64>,
+    // if `None`, the font in `theme::FONT_NAME`.
+    font: Option<String>,
+    tabular_figures: bool,
+    // the digit layouts for tabular figures, for the font they were made with.
+    digit_cells: Option<DigitCells>,
+    shadow: Option<TextShadow>,
+    outline: Option<(f64, Color)>,
+    // the width of the text at the last layout, used to avoid relayout
+    // when the text changes but its width does not.
+    text_width: Option<f64>,
+    // a byte range of the text to underline, such as a button's access key.
+    underline: Option<Range<usize>>,
 }
 
 impl<T: Data> Label<T> {
@@ -78,6 +97,13 @@ impl<T: Data> Label<T> {
             text,
             color: theme::LABEL_COLOR.into(),
             size: theme::TEXT_SIZE_NORMAL.into(),
+            font: None,
+            tabular_figures: false,
+            digit_cells: None,
+            shadow: None,
+            outline: None,
+            text_width: None,
+            underline: None,
         }
     }
 
@@ -132,6 +158,64 @@ impl<T: Data> Label<T> {
         self
     }
 
+    /// Builder-style method for setting the font, by family name.
+    ///
+    /// The font can be one installed on the system, or one the app loaded
+    /// with [`AppLauncher::load_font`]. If the font can't be built, the font
+    /// in [`theme::FONT_NAME`] is used instead, and a warning is logged; some
+    /// text systems instead pick a fallback font of their own.
+    ///
+    /// [`AppLauncher::load_font`]: ../struct.AppLauncher.html#method.load_font
+    /// [`theme::FONT_NAME`]: ../theme/constant.FONT_NAME.html
+    pub fn with_font(mut self, font: impl Into<String>) -> Self {
+        self.font = Some(font.into());
+        self
+    }
+
+    /// Builder-style method to give every digit the same width.
+    ///
+    /// Most fonts have digits of different widths, so text such as a
+    /// countdown shifts about as it changes. With this set, each ASCII digit
+    /// is centered in a space as wide as the widest digit.
+    ///
+    /// This is not the font's `tnum` OpenType feature, which piet has no way
+    /// to turn on. Instead the label lays out the ten digits once for each
+    /// font and size, and draws every digit as a separate piece, placed on
+    /// its own. Kerning and ligatures between a digit and its neighbours are
+    /// lost, and digits that are already tabular in the font are unchanged.
+    pub fn with_tabular_figures(mut self, tabular: bool) -> Self {
+        self.tabular_figures = tabular;
+        self
+    }
+
+    /// Builder-style method to draw a shadow behind the text.
+    ///
+    /// The shadow is a copy of the text in `color`, moved by `offset` and
+    /// softened by `blur`, which is roughly how far the shadow spreads out, in
+    /// display points. A shadow helps text stand out on a busy background.
+    ///
+    /// There is no real blur available for text, so the softening is done by
+    /// drawing several faint copies; large values of `blur` will look grainy.
+    pub fn with_text_shadow(
+        mut self,
+        offset: impl Into<Vec2>,
+        blur: f64,
+        color: impl Into<KeyOrValue<Color>>,
+    ) -> Self {
+        self.shadow = Some(TextShadow {
+            offset: offset.into(),
+            blur: blur.max(0.0),
+            color: color.into(),
+        });
+        self
+    }
+
+    /// Builder-style method to draw an outline of `width` around each glyph.
+    pub fn with_text_outline(mut self, width: f64, color: Color) -> Self {
+        self.outline = Some((width.max(0.0), color));
+        self
+    }
+
     /// Set a new text.
     ///
     /// Takes an already resolved string as input.
@@ -163,14 +247,232 @@ impl<T: Data> Label<T> {
         self.size = size.into();
     }
 
-    fn get_layout(&mut self, t: &mut PietText, env: &Env) -> PietTextLayout {
-        let font_name = env.get(theme::FONT_NAME);
+    /// Set the font, by family name.
+    ///
+    /// See [`with_font`] for details.
+    ///
+    /// [`with_font`]: #method.with_font
+    pub fn set_font(&mut self, font: impl Into<String>) {
+        self.font = Some(font.into());
+    }
+
+    /// Underline the text in `range`, in bytes, or remove the underline.
+    pub(crate) fn set_underline(&mut self, range: Option<Range<usize>>) {
+        self.underline = range;
+    }
+
+    /// How far a shadow or outline may paint outside our bounds.
+    fn effect_insets(&self) -> Insets {
+        let mut insets = Insets::ZERO;
+        if let Some(shadow) = &self.shadow {
+            let Vec2 { x, y } = shadow.offset;
+            let blur = shadow.blur;
+            insets = Insets::new(blur - x, blur - y, blur + x, blur + y);
+        }
+        if let Some((width, _)) = self.outline {
+            insets = Insets::new(
+                insets.x0.max(width),
+                insets.y0.max(width),
+                insets.x1.max(width),
+                insets.y1.max(width),
+            );
+        }
+        insets
+    }
+
+    fn font_name<'a>(&'a self, env: &'a Env) -> &'a str {
+        self.font
+            .as_deref()
+            .unwrap_or_else(|| env.get(theme::FONT_NAME))
+    }
+
+    fn get_font(&self, t: &mut PietText, env: &Env) -> PietFont {
+        let font_name = self.font_name(env);
         let font_size = self.size.resolve(env);
+        match t.new_font_by_name(font_name, font_size).build() {
+            Ok(font) => font,
+            Err(e) => {
+                log::warn!("failed to build font '{}': {}", font_name, e);
+                let default_font = env.get(theme::FONT_NAME);
+                t.new_font_by_name(default_font, font_size).build().unwrap()
+            }
+        }
+    }
 
+    /// Lay out the digits for tabular figures, if they are enabled.
+    ///
+    /// The layouts are kept until the font or its size changes.
+    fn prepare_digit_cells(&mut self, t: &mut PietText, font: &PietFont, env: &Env) {
+        if !self.tabular_figures {
+            return;
+        }
+        let key = (self.font_name(env), self.size.resolve(env));
+        let stale = match &self.digit_cells {
+            Some(cells) => (cells.font.0.as_str(), cells.font.1) != key,
+            None => true,
+        };
+        if stale {
+            self.digit_cells = Some(DigitCells::new(t, font, (key.0.to_string(), key.1)));
+        }
+    }
+
+    fn get_layout(&mut self, t: &mut PietText, env: &Env) -> TextPieces {
         // TODO: caching of both the format and the layout
-        let font = t.new_font_by_name(font_name, font_size).build().unwrap();
+        let font = self.get_font(t, env);
+        self.prepare_digit_cells(t, &font, env);
+        let digits = self.digit_cells.as_ref().filter(|_| self.tabular_figures);
         self.text
-            .with_display_text(|text| t.new_text_layout(&font, &text).build().unwrap())
+            .with_display_text(|text| TextPieces::new(t, &font, text, digits))
+    }
+
+    /// The horizontal extent of the byte range `range` of the text, from
+    /// the text's start.
+    fn range_extent(&mut self, t: &mut PietText, env: &Env, range: &Range<usize>) -> (f64, f64) {
+        let font = self.get_font(t, env);
+        self.prepare_digit_cells(t, &font, env);
+        let digits = self.digit_cells.as_ref().filter(|_| self.tabular_figures);
+        self.text.with_display_text(|text| {
+            let mut width = |end: usize| match text.get(..end) {
+                Some(prefix) => TextPieces::new(t, &font, prefix, digits).width(),
+                None => 0.0,
+            };
+            (width(range.start), width(range.end))
+        })
+    }
+}
+
+struct TextShadow {
+    offset: Vec2,
+    blur: f64,
+    color: KeyOrValue<Color>,
+}
+
+/// Offsets, and alpha multipliers, for the copies of the text that make up
+/// a shadow or outline spreading `radius` points in every direction.
+fn spread(radius: f64) -> Vec<(Vec2, f64)> {
+    if radius <= 0.0 {
+        return vec![(Vec2::ZERO, 1.0)];
+    }
+    let rings = (radius.ceil() as usize).min(3);
+    let count = 1 + 8 * rings;
+    let mut copies = vec![(Vec2::ZERO, 2.0 / count as f64)];
+    for ring in 1..=rings {
+        let r = radius * ring as f64 / rings as f64;
+        for step in 0..8 {
+            let angle = std::f64::consts::FRAC_PI_4 * step as f64;
+            let offset = Vec2::new(r * angle.cos(), r * angle.sin());
+            // the outer rings are fainter, so the edge fades out.
+            let alpha = 2.0 * (1.0 - (ring - 1) as f64 / rings as f64) / count as f64;
+            copies.push((offset, alpha));
+        }
+    }
+    copies
+}
+
+/// The layouts of the digits `0` to `9` in one font, for tabular figures.
+struct DigitCells {
+    /// The font name and size the digits were laid out with.
+    font: (String, f64),
+    digits: Vec<PietTextLayout>,
+    /// The width of the widest digit.
+    cell: f64,
+}
+
+impl DigitCells {
+    fn new(t: &mut PietText, font: &PietFont, key: (String, f64)) -> DigitCells {
+        let digits: Vec<PietTextLayout> = (b'0'..=b'9')
+            .map(|d| {
+                let digit = std::str::from_utf8(&[d]).unwrap().to_owned();
+                t.new_text_layout(font, &digit).build().unwrap()
+            })
+            .collect();
+        let cell = digits.iter().map(|d| d.width()).fold(0.0, f64::max);
+        DigitCells {
+            font: key,
+            digits,
+            cell,
+        }
+    }
+}
+
+/// One piece of a label's text.
+enum Piece {
+    Run(PietTextLayout),
+    /// One of the digits in `DigitCells`.
+    Digit(usize),
+}
+
+/// The laid out text of a label, possibly in several pieces.
+struct TextPieces {
+    /// Each piece, and its x offset from the start of the text.
+    pieces: Vec<(f64, Piece)>,
+    width: f64,
+}
+
+impl TextPieces {
+    fn new(
+        t: &mut PietText,
+        font: &PietFont,
+        text: &str,
+        digits: Option<&DigitCells>,
+    ) -> TextPieces {
+        if let Some(digits) = digits {
+            TextPieces::tabular(t, font, text, digits)
+        } else {
+            let layout = t.new_text_layout(font, text).build().unwrap();
+            TextPieces {
+                width: layout.width(),
+                pieces: vec![(0.0, Piece::Run(layout))],
+            }
+        }
+    }
+
+    /// Lay out `text` with each ASCII digit in a cell as wide as the widest.
+    fn tabular(t: &mut PietText, font: &PietFont, text: &str, cells: &DigitCells) -> TextPieces {
+        let mut layout = |text: &str| t.new_text_layout(font, text).build().unwrap();
+        let cell = cells.cell;
+
+        let mut pieces = Vec::new();
+        let mut x = 0.0;
+        let mut run_start = 0;
+        for (i, c) in text.char_indices() {
+            if c.is_ascii_digit() {
+                if run_start < i {
+                    let run = layout(&text[run_start..i]);
+                    let width = run.width();
+                    pieces.push((x, Piece::Run(run)));
+                    x += width;
+                }
+                let digit = (c as u8 - b'0') as usize;
+                let offset = (cell - cells.digits[digit].width()) / 2.0;
+                pieces.push((x + offset, Piece::Digit(digit)));
+                x += cell;
+                run_start = i + 1;
+            }
+        }
+        if run_start < text.len() {
+            let run = layout(&text[run_start..]);
+            let width = run.width();
+            pieces.push((x, Piece::Run(run)));
+            x += width;
+        }
+        TextPieces { pieces, width: x }
+    }
+
+    fn width(&self) -> f64 {
+        self.width
+    }
+
+    /// Draw the text; `digits` must be the cells it was laid out with.
+    fn draw(&self, ctx: &mut PaintCtx, digits: Option<&DigitCells>, origin: Point, color: &Color) {
+        for (offset, piece) in &self.pieces {
+            let layout = match (piece, digits) {
+                (Piece::Run(layout), _) => layout,
+                (Piece::Digit(digit), Some(cells)) => &cells.digits[*digit],
+                (Piece::Digit(_), None) => continue,
+            };
+            ctx.draw_text(layout, Point::new(origin.x + offset, origin.y), color);
+        }
     }
 }
 
@@ -215,9 +517,16 @@ impl<T: Data> Widget<T> for Label<T> {
         }
     }
 
-    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
-        if !old_data.same(data) && self.text.resolve(data, env) {
-            ctx.request_layout();
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        // we're also updated when the env changes, which can change the
+        // text of a localized or formatted label.
+        if self.text.resolve(data, env) {
+            let new_width = self.get_layout(&mut ctx.text(), env).width();
+            if self.text_width == Some(new_width) {
+                ctx.request_paint();
+            } else {
+                ctx.request_layout();
+            }
         }
     }
 
@@ -232,22 +541,75 @@ impl<T: Data> Widget<T> for Label<T> {
 
         let font_size = self.size.resolve(env);
         let text_layout = self.get_layout(layout_ctx.text(), env);
+        self.text_width = Some(text_layout.width());
+        layout_ctx.set_paint_insets(self.effect_insets());
         bc.constrain(Size::new(
             text_layout.width() + 2. * LABEL_X_PADDING,
             font_size * LINE_HEIGHT_FACTOR,
         ))
     }
 
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        _height: f64,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.get_layout(ctx.text(), env).width() + 2. * LABEL_X_PADDING
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        _ctx: &mut LayoutCtx,
+        _width: f64,
+        _data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.size.resolve(env) * LINE_HEIGHT_FACTOR
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, _data: &T, env: &Env) {
         let font_size = self.size.resolve(env);
         let text_layout = self.get_layout(ctx.text(), env);
         let line_height = font_size * LINE_HEIGHT_FACTOR;
 
-        // Find the origin for the text
-        let origin = Point::new(LABEL_X_PADDING, line_height * BASELINE_GUESS_FACTOR);
+        // Find the origin for the text; right-to-left text hugs the right edge
+        // if we have been given more room than it needs.
+        let is_rtl = self
+            .text
+            .with_display_text(|text| TextDirection::for_text(text, env).is_rtl());
+        let x = if is_rtl {
+            (ctx.size().width - LABEL_X_PADDING - text_layout.width()).max(LABEL_X_PADDING)
+        } else {
+            LABEL_X_PADDING
+        };
+        let origin = Point::new(x, line_height * BASELINE_GUESS_FACTOR);
         let color = self.color.resolve(env);
+        let digits = self.digit_cells.as_ref().filter(|_| self.tabular_figures);
 
-        ctx.draw_text(&text_layout, origin, &color);
+        if let Some(shadow) = &self.shadow {
+            let shadow_color = shadow.color.resolve(env);
+            let alpha = f64::from(shadow_color.as_rgba_u32() & 0xff) / 255.0;
+            for (offset, weight) in spread(shadow.blur) {
+                let copy_color = shadow_color.clone().with_alpha(alpha * weight);
+                text_layout.draw(ctx, digits, origin + shadow.offset + offset, &copy_color);
+            }
+        }
+        if let Some((width, outline_color)) = &self.outline {
+            // outline copies are drawn at full strength, for a solid edge.
+            for (offset, _) in spread(*width) {
+                text_layout.draw(ctx, digits, origin + offset, outline_color);
+            }
+        }
+        text_layout.draw(ctx, digits, origin, &color);
+
+        if let Some(range) = self.underline.clone() {
+            let (x0, x1) = self.range_extent(ctx.text(), env, &range);
+            let y = origin.y + (font_size * 0.1).max(1.0).round() + 0.5;
+            let line = Line::new((origin.x + x0, y), (origin.x + x1, y));
+            ctx.stroke(line, &color, 1.0);
+        }
     }
 }
 
@@ -15,19 +15,259 @@
 //! Simple list view widget.
 
 use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::sync::Arc;
+use std::time::Duration;
 
-use crate::kurbo::{Point, Rect, Size};
+use crate::kurbo::{Line, Point, Rect, Size, Vec2};
 
+use crate::command::sys as sys_cmd;
 use crate::{
-    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
-    UpdateCtx, Widget, WidgetPod,
+    theme, BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
+    MouseGesture, PaintCtx, RenderContext, UpdateCtx, Widget, WidgetPod,
 };
 
+type ListChild<T> = WidgetPod<T, Box<dyn Widget<T>>>;
+/// A function that gives the key identifying a list item.
+type KeyFn<T> = dyn Fn(&T) -> u64;
+
+/// The width of the drag handles of a reorderable list, before scaling.
+const HANDLE_WIDTH: f64 = 20.0;
+/// How long the other items take to make room for a dragged item.
+const GAP_ANIMATION: Duration = Duration::from_millis(150);
+/// How close to the edge of the view the pointer must be, before scaling,
+/// for a drag to scroll the list.
+const AUTO_SCROLL_MARGIN: f64 = 24.0;
+/// How fast a drag scrolls the list, in points per second, when the
+/// pointer is at the very edge of the view.
+const AUTO_SCROLL_SPEED: f64 = 600.0;
+
 /// A list widget for a variable-size collection of items.
+///
+/// By default, the children are matched to items by position: the first
+/// child shows the first item, and so on. If the items have keys (see
+/// [`with_keys`]), children are matched by key instead, so that each child
+/// keeps following its item when items are inserted, removed or reordered.
+///
+/// In a [`Scroll`], an item can be brought into view by sending the list
+/// a [`SCROLL_TO_ITEM`] command. Every item is laid out, so the item's
+/// position is exact, even if it was only just added.
+///
+/// A list made [`reorderable`] lets the user drag items to new places.
+///
+/// [`with_keys`]: #method.with_keys
+/// [`reorderable`]: #method.reorderable
+/// [`Scroll`]: struct.Scroll.html
+/// [`SCROLL_TO_ITEM`]: ../commands/constant.SCROLL_TO_ITEM.html
 pub struct List<T> {
     closure: Box<dyn Fn() -> Box<dyn Widget<T>>>,
-    children: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    children: Vec<ListChild<T>>,
+    key_fn: Option<Box<KeyFn<T>>>,
+    /// The key of each child, when keyed.
+    keys: Vec<u64>,
+    /// The visible part of the list, if it is in a `Scroll`.
+    viewport: Option<Rect>,
+    /// The key and position of the child at the top of the viewport, before
+    /// the items changed.
+    anchor: Option<(u64, f64)>,
+    /// An item to scroll into view after the next layout.
+    scroll_request: Option<ScrollToItem>,
+    reorderable: bool,
+    /// The index of the item pressed by its handle, and where it was
+    /// pressed, while the button is held.
+    grabbed: Option<(usize, f64)>,
+    /// The drag, once the press has moved far enough to become one.
+    drag: Option<Drag>,
+}
+
+/// An item being dragged to a new place in a reorderable list.
+struct Drag {
+    /// The index of the dragged item.
+    from: usize,
+    /// The top and bottom of each child when the drag started.
+    rows: Vec<(f64, f64)>,
+    /// How far below the top of the dragged child it was grabbed.
+    grab: f64,
+    /// The pointer's distance from the top of the viewport, or of the list
+    /// if it isn't in a `Scroll`.
+    pointer: f64,
+    /// How far each child is currently moved to make room.
+    offsets: Vec<f64>,
+    /// How far to scroll at the next layout.
+    scroll: f64,
+}
+
+/// Where [`SCROLL_TO_ITEM`] puts an item in the view.
+///
+/// [`SCROLL_TO_ITEM`]: ../commands/constant.SCROLL_TO_ITEM.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollAlign {
+    /// At the top of the view.
+    Top,
+    /// In the middle of the view.
+    Center,
+    /// Scroll as little as possible to show the item; an item that is
+    /// already visible doesn't move.
+    Nearest,
+}
+
+/// The argument of a [`SCROLL_TO_ITEM`] command.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{ScrollAlign, ScrollToItem};
+/// use druid::{commands, Command};
+///
+/// let request = ScrollToItem::key("Tomato").align(ScrollAlign::Center);
+/// let command = Command::new(commands::SCROLL_TO_ITEM, request);
+/// ```
+///
+/// [`SCROLL_TO_ITEM`]: ../commands/constant.SCROLL_TO_ITEM.html
+#[derive(Debug, Clone)]
+pub struct ScrollToItem {
+    item: ItemRef,
+    align: ScrollAlign,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ItemRef {
+    Index(usize),
+    Key(u64),
+}
+
+impl ScrollToItem {
+    /// Scroll to the item at `index`.
+    pub fn index(index: usize) -> Self {
+        ScrollToItem {
+            item: ItemRef::Index(index),
+            align: ScrollAlign::Nearest,
+        }
+    }
+
+    /// Scroll to the item with the given key, in a list with keys.
+    ///
+    /// The key is compared with what the list's [`with_keys`] function
+    /// returns, so it must be of the same type.
+    ///
+    /// [`with_keys`]: struct.List.html#method.with_keys
+    pub fn key(key: impl Hash) -> Self {
+        ScrollToItem {
+            item: ItemRef::Key(hash_key(key)),
+            align: ScrollAlign::Nearest,
+        }
+    }
+
+    /// Builder-style method to set where the item ends up in the view.
+    ///
+    /// The default is [`ScrollAlign::Nearest`].
+    ///
+    /// [`ScrollAlign::Nearest`]: enum.ScrollAlign.html#variant.Nearest
+    pub fn align(mut self, align: ScrollAlign) -> Self {
+        self.align = align;
+        self
+    }
+}
+
+fn handle_width(env: &Env) -> f64 {
+    HANDLE_WIDTH * env.get(theme::UI_SCALE)
+}
+
+/// Paint the grip of the drag handle beside the child at `rect`.
+fn paint_handle(ctx: &mut PaintCtx, rect: Rect, env: &Env) {
+    let scale = env.get(theme::UI_SCALE);
+    let handle = handle_width(env);
+    let (x0, x1) = (handle * 0.25, handle * 0.75);
+    let color = env.get(theme::BORDER_LIGHT);
+    for i in -1..=1 {
+        let y = rect.center().y + f64::from(i) * 4.0 * scale;
+        ctx.stroke(Line::new((x0, y), (x1, y)), &color, 1.0);
+    }
+}
+
+impl Drag {
+    fn height(&self) -> f64 {
+        let (y0, y1) = self.rows[self.from];
+        y1 - y0
+    }
+
+    /// Where the top of the dragged child is, kept within the list.
+    fn top(&self, view_top: f64) -> f64 {
+        let bottom = self.rows.last().map_or(0.0, |row| row.1);
+        (view_top + self.pointer - self.grab)
+            .min(bottom - self.height())
+            .max(0.0)
+    }
+
+    /// The index the dragged item would move to if it were dropped now.
+    fn target(&self, view_top: f64) -> usize {
+        let center = self.top(view_top) + self.height() / 2.0;
+        self.rows
+            .iter()
+            .enumerate()
+            .filter(|(i, (y0, y1))| *i != self.from && (y0 + y1) / 2.0 <= center)
+            .count()
+    }
+
+    /// How far the child at `index` moves to make room, if the dragged item
+    /// goes to `to`.
+    fn gap_offset(&self, index: usize, to: usize) -> f64 {
+        if self.from < index && index <= to {
+            -self.height()
+        } else if to <= index && index < self.from {
+            self.height()
+        } else {
+            0.0
+        }
+    }
+
+    /// Move the other children towards their places, and scroll if the
+    /// pointer is near the edge of the view.
+    ///
+    /// Returns `true` if it should be called again on the next frame.
+    fn animate(&mut self, interval: u64, viewport: Option<Rect>, env: &Env) -> bool {
+        let to = self.target(viewport.map_or(0.0, |viewport| viewport.y0));
+        let step = if env.get(theme::REDUCED_MOTION) {
+            f64::INFINITY
+        } else {
+            self.height() * interval as f64 / GAP_ANIMATION.as_nanos() as f64
+        };
+        let mut moving = false;
+        for index in 0..self.offsets.len() {
+            let target = self.gap_offset(index, to);
+            let offset = self.offsets[index];
+            self.offsets[index] = if offset < target {
+                (offset + step).min(target)
+            } else {
+                (offset - step).max(target)
+            };
+            moving |= self.offsets[index] != target;
+        }
+
+        if let Some(viewport) = viewport {
+            let margin = AUTO_SCROLL_MARGIN * env.get(theme::UI_SCALE);
+            let bottom = self.rows.last().map_or(0.0, |row| row.1);
+            let depth = if self.pointer < margin && viewport.y0 > 0.0 {
+                self.pointer - margin
+            } else if self.pointer > viewport.height() - margin && viewport.y1 < bottom {
+                self.pointer - (viewport.height() - margin)
+            } else {
+                0.0
+            };
+            let speed = (depth / margin).clamp(-1.0, 1.0) * AUTO_SCROLL_SPEED;
+            self.scroll += speed * interval as f64 / 1e9;
+            moving |= speed != 0.0;
+        }
+        moving
+    }
+}
+
+fn hash_key(key: impl Hash) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
 }
 
 impl<T: Data> List<T> {
@@ -37,9 +277,212 @@ impl<T: Data> List<T> {
         List {
             closure: Box::new(move || Box::new(closure())),
             children: Vec::new(),
+            key_fn: None,
+            keys: Vec::new(),
+            viewport: None,
+            anchor: None,
+            scroll_request: None,
+            reorderable: false,
+            grabbed: None,
+            drag: None,
         }
     }
 
+    /// Builder-style method to let the user reorder the items by dragging
+    /// them.
+    ///
+    /// A grab handle is shown at the start of each item; once an item is
+    /// pressed by its handle and the mouse has moved far enough for a drag
+    /// (see [`MouseGesture`]), the others move aside to show where it will
+    /// go, and a [`Scroll`] around the list scrolls when the pointer gets
+    /// near its edge. When the item is dropped, it is moved in the data,
+    /// and its widget moves with it, keeping its state, whether or not the
+    /// list has [keys].
+    ///
+    /// Only the list's own collection is changed: for a list of
+    /// `(shared, items)` tuples, make sure the lens writes `items` back.
+    ///
+    /// [`Scroll`]: struct.Scroll.html
+    /// [`MouseGesture`]: ../enum.MouseGesture.html
+    /// [keys]: #method.with_keys
+    pub fn reorderable(mut self) -> Self {
+        self.reorderable = true;
+        self
+    }
+
+    /// Builder-style method to match children to items by the key that
+    /// `key_fn` computes for each item, instead of by position.
+    ///
+    /// Keys should be unique within the list. When the list is inside a
+    /// [`Scroll`], the item at the top of the view is also kept in place when
+    /// items above it are added or removed, or when the whole collection is
+    /// replaced, as long as that item is still in it.
+    ///
+    /// [`Scroll`]: struct.Scroll.html
+    pub fn with_keys<K: Hash>(mut self, key_fn: impl Fn(&T) -> K + 'static) -> Self {
+        self.key_fn = Some(Box::new(move |item| hash_key(key_fn(item))));
+        self
+    }
+
+    fn item_keys(&self, data: &impl ListIter<T>) -> Vec<u64> {
+        let mut keys = Vec::with_capacity(data.data_len());
+        if let Some(key_fn) = &self.key_fn {
+            data.for_each(|item, _| keys.push(key_fn(item)));
+        }
+        keys
+    }
+
+    /// Rearrange the children to match the items' new `keys`, creating
+    /// children for new items and dropping those of removed ones.
+    ///
+    /// Returns whether each child is new.
+    fn reorder_children(&mut self, keys: Vec<u64>) -> Vec<bool> {
+        let mut old: HashMap<u64, Vec<ListChild<T>>> = HashMap::new();
+        for (key, child) in self.keys.drain(..).zip(self.children.drain(..)).rev() {
+            old.entry(key).or_default().push(child);
+        }
+        let mut is_new = Vec::with_capacity(keys.len());
+        for key in &keys {
+            match old.get_mut(key).and_then(Vec::pop) {
+                Some(child) => {
+                    self.children.push(child);
+                    is_new.push(false);
+                }
+                None => {
+                    self.children.push(WidgetPod::new((self.closure)()));
+                    is_new.push(true);
+                }
+            }
+        }
+        self.keys = keys;
+        is_new
+    }
+
+    /// The key and position of the first child that reaches below the top
+    /// of the viewport, if the list is scrolled.
+    fn find_anchor(&self) -> Option<(u64, f64)> {
+        let top = self.viewport?.y0;
+        if top <= 0.0 {
+            return None;
+        }
+        self.keys
+            .iter()
+            .zip(&self.children)
+            .map(|(key, child)| (*key, child.layout_rect()))
+            .find(|(_, rect)| rect.y1 > top)
+            .map(|(key, rect)| (key, rect.y0))
+    }
+
+    /// The layout rects of the children, in item order.
+    pub(crate) fn child_rects(&self) -> impl Iterator<Item = Rect> + '_ {
+        self.children.iter().map(|child| child.layout_rect())
+    }
+
+    /// How far the enclosing `Scroll` should move to carry out `request`.
+    fn scroll_delta(&self, request: &ScrollToItem) -> Option<f64> {
+        let visible = self.viewport?;
+        let index = match request.item {
+            ItemRef::Index(index) => index,
+            ItemRef::Key(key) => self.keys.iter().position(|k| *k == key)?,
+        };
+        let item = self.children.get(index)?.layout_rect();
+        let delta = match request.align {
+            ScrollAlign::Top => item.y0 - visible.y0,
+            ScrollAlign::Center => item.center().y - visible.center().y,
+            ScrollAlign::Nearest if item.y0 < visible.y0 => item.y0 - visible.y0,
+            ScrollAlign::Nearest if item.y1 > visible.y1 => {
+                (item.y1 - visible.y1).min(item.y0 - visible.y0)
+            }
+            ScrollAlign::Nearest => 0.0,
+        };
+        Some(delta)
+    }
+
+    /// The top of the viewport, in the list's coordinates.
+    fn view_top(&self) -> f64 {
+        self.viewport.map_or(0.0, |viewport| viewport.y0)
+    }
+
+    /// Start, continue or finish dragging an item by its handle, returning
+    /// `true` if `event` was part of a drag.
+    fn drag_event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut impl ListIter<T>,
+        env: &Env,
+    ) -> bool {
+        let view_top = self.view_top();
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if mouse.pos.x >= handle_width(env) {
+                    return false;
+                }
+                let from = match self
+                    .child_rects()
+                    .position(|rect| mouse.pos.y >= rect.y0 && mouse.pos.y < rect.y1)
+                {
+                    Some(from) => from,
+                    None => return false,
+                };
+                self.grabbed = Some((from, mouse.pos.y));
+                ctx.set_active(true);
+                ctx.set_handled();
+                return true;
+            }
+            Event::MouseMoved(mouse) => {
+                let (from, grab_y) = match self.grabbed {
+                    Some(grabbed) => grabbed,
+                    None => return false,
+                };
+                if self.drag.is_none() {
+                    // a press that hasn't become a drag leaves the items alone.
+                    if mouse.gesture != MouseGesture::Drag {
+                        ctx.set_handled();
+                        return true;
+                    }
+                    let rows: Vec<_> = self.child_rects().map(|rect| (rect.y0, rect.y1)).collect();
+                    if from >= rows.len() {
+                        self.grabbed = None;
+                        return false;
+                    }
+                    self.drag = Some(Drag {
+                        from,
+                        grab: grab_y - rows[from].0,
+                        pointer: 0.0,
+                        offsets: vec![0.0; rows.len()],
+                        rows,
+                        scroll: 0.0,
+                    });
+                }
+                if let Some(drag) = &mut self.drag {
+                    drag.pointer = mouse.pos.y - view_top;
+                    ctx.request_anim_frame();
+                }
+            }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                self.grabbed = None;
+                if let Some(drag) = self.drag.take() {
+                    let (from, to) = (drag.from, drag.target(view_top));
+                    if from != to {
+                        let child = self.children.remove(from);
+                        self.children.insert(to, child);
+                        if self.key_fn.is_some() {
+                            let key = self.keys.remove(from);
+                            self.keys.insert(to, key);
+                        }
+                        data.move_item(from, to);
+                    }
+                }
+            }
+            _ => return false,
+        }
+        ctx.request_layout();
+        ctx.set_handled();
+        true
+    }
+
     /// When the widget is created or the data changes, create or remove children as needed
     ///
     /// Returns `true` if children were added or removed.
@@ -69,6 +512,30 @@ pub trait ListIter<T>: Data {
 
     /// Return data length.
     fn data_len(&self) -> usize;
+
+    /// Move the data child at `from` so that it ends up at `to`, shifting
+    /// the ones in between.
+    ///
+    /// The default implementation clones every child and writes them back
+    /// in their new order with [`for_each_mut`]; collections that can move
+    /// a child in place should override it.
+    ///
+    /// [`for_each_mut`]: #tymethod.for_each_mut
+    fn move_item(&mut self, from: usize, to: usize)
+    where
+        T: Clone,
+    {
+        let mut items = Vec::with_capacity(self.data_len());
+        self.for_each(|item, _| items.push(item.clone()));
+        let item = items.remove(from);
+        items.insert(to, item);
+        let mut items = items.into_iter();
+        self.for_each_mut(|item, _| {
+            if let Some(moved) = items.next() {
+                *item = moved;
+            }
+        });
+    }
 }
 
 impl<T: Data> ListIter<T> for Arc<Vec<T>> {
@@ -100,6 +567,12 @@ impl<T: Data> ListIter<T> for Arc<Vec<T>> {
     fn data_len(&self) -> usize {
         self.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        let items = Arc::make_mut(self);
+        let item = items.remove(from);
+        items.insert(to, item);
+    }
 }
 
 impl<T1: Data, T: Data> ListIter<(T1, T)> for (T1, Arc<Vec<T>>) {
@@ -140,10 +613,26 @@ impl<T1: Data, T: Data> ListIter<(T1, T)> for (T1, Arc<Vec<T>>) {
     fn data_len(&self) -> usize {
         self.1.len()
     }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        self.1.move_item(from, to)
+    }
 }
 
 impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Command(cmd) = event {
+            if let Some(request) = cmd.get::<ScrollToItem>(sys_cmd::SCROLL_TO_ITEM) {
+                // the item may not have been laid out yet.
+                self.scroll_request = Some(request.clone());
+                ctx.request_layout();
+                ctx.set_handled();
+                return;
+            }
+        }
+        if self.reorderable && self.drag_event(ctx, event, data, env) {
+            return;
+        }
         let mut children = self.children.iter_mut();
         data.for_each_mut(|child_data, _| {
             if let Some(child) = children.next() {
@@ -153,10 +642,23 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
-        if let LifeCycle::WidgetAdded = event {
-            if self.update_child_count(data, env) {
-                ctx.children_changed();
+        match event {
+            LifeCycle::WidgetAdded => {
+                self.keys = self.item_keys(data);
+                if self.update_child_count(data, env) {
+                    ctx.children_changed();
+                }
+            }
+            LifeCycle::ViewportChanged(viewport) => self.viewport = Some(*viewport),
+            LifeCycle::AnimFrame(interval) => {
+                if let Some(drag) = &mut self.drag {
+                    if drag.animate(*interval, self.viewport, env) {
+                        ctx.request_anim_frame();
+                    }
+                    ctx.request_layout();
+                }
             }
+            _ => (),
         }
 
         let mut children = self.children.iter_mut();
@@ -168,6 +670,37 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
     }
 
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        // the dragged item may be gone.
+        if let Some(drag) = &self.drag {
+            if drag.rows.len() != data.data_len() {
+                self.drag = None;
+                ctx.request_layout();
+            }
+        }
+
+        if self.key_fn.is_some() {
+            let keys = self.item_keys(data);
+            if keys != self.keys {
+                self.anchor = self.find_anchor();
+                let old_len = self.children.len();
+                let is_new = self.reorder_children(keys);
+                // new children get their data with `WidgetAdded`.
+                let mut children = self.children.iter_mut().zip(is_new.iter());
+                data.for_each(|child_data, _| {
+                    if let Some((child, &false)) = children.next() {
+                        child.update(ctx, child_data, env);
+                    }
+                });
+                let reused = is_new.iter().filter(|new| !**new).count();
+                if reused < is_new.len() || reused < old_len {
+                    ctx.children_changed();
+                } else {
+                    ctx.request_layout();
+                }
+                return;
+            }
+        }
+
         // we send update to children first, before adding or removing children;
         // this way we avoid sending update to newly added children, at the cost
         // of potentially updating children that are going to be removed.
@@ -190,6 +723,11 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
         data: &T,
         env: &Env,
     ) -> Size {
+        let handle = if self.reorderable {
+            handle_width(env)
+        } else {
+            0.0
+        };
         let mut width = bc.min().width;
         let mut y = 0.0;
 
@@ -203,17 +741,55 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
                 }
             };
             let child_bc = BoxConstraints::new(
-                Size::new(bc.min().width, 0.0),
-                Size::new(bc.max().width, std::f64::INFINITY),
+                Size::new((bc.min().width - handle).max(0.0), 0.0),
+                Size::new((bc.max().width - handle).max(0.0), f64::INFINITY),
             );
             let child_size = child.layout(layout_ctx, &child_bc, child_data, env);
-            let rect = Rect::from_origin_size(Point::new(0.0, y), child_size);
+            let rect = Rect::from_origin_size(Point::new(handle, y), child_size);
             child.set_layout_rect(rect);
             paint_rect = paint_rect.union(child.paint_rect());
-            width = width.max(child_size.width);
+            width = width.max(child_size.width + handle);
             y += child_size.height;
         });
 
+        if let Some(drag) = &mut self.drag {
+            if drag.scroll != 0.0 {
+                layout_ctx.shift_scroll(Vec2::new(0.0, drag.scroll));
+            }
+            // the viewport only follows the scroll after layout.
+            let top = drag.top(self.viewport.map_or(0.0, |v| v.y0) + drag.scroll);
+            drag.scroll = 0.0;
+            for (i, child) in self.children.iter_mut().enumerate() {
+                let rect = child.layout_rect();
+                let moved = if i == drag.from {
+                    top - rect.y0
+                } else {
+                    drag.offsets.get(i).copied().unwrap_or(0.0)
+                };
+                child.set_layout_rect(rect + Vec2::new(0.0, moved));
+            }
+        }
+
+        if let Some((key, old_y)) = self.anchor.take() {
+            if let Some(idx) = self.keys.iter().position(|k| *k == key) {
+                let moved = self.children[idx].layout_rect().y0 - old_y;
+                if moved != 0.0 {
+                    layout_ctx.shift_scroll(Vec2::new(0.0, moved));
+                }
+            }
+        }
+
+        if let Some(request) = self.scroll_request.take() {
+            match self.scroll_delta(&request) {
+                Some(delta) => layout_ctx.shift_scroll(Vec2::new(0.0, delta)),
+                None => log::warn!(
+                    target: "druid::command",
+                    "can't scroll to {:?}: no such item, or the list isn't in a Scroll",
+                    request.item
+                ),
+            }
+        }
+
         let my_size = bc.constrain(Size::new(width, y));
         let insets = paint_rect - Rect::ZERO.with_size(my_size);
         layout_ctx.set_paint_insets(insets);
@@ -221,11 +797,33 @@ impl<C: Data, T: ListIter<C>> Widget<T> for List<C> {
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let width = ctx.size().width;
+        let reorderable = self.reorderable;
+        let dragged = self.drag.as_ref().map(|drag| drag.from);
         let mut children = self.children.iter_mut();
-        data.for_each(|child_data, _| {
+        data.for_each(|child_data, i| {
             if let Some(child) = children.next() {
-                child.paint_with_offset(ctx, child_data, env);
+                if Some(i) != dragged {
+                    child.paint_with_offset(ctx, child_data, env);
+                    if reorderable {
+                        paint_handle(ctx, child.layout_rect(), env);
+                    }
+                }
             }
         });
+
+        // the dragged item goes on top, hiding the ones under it.
+        if let Some(from) = dragged {
+            let child = &mut self.children[from];
+            let rect = child.layout_rect();
+            data.for_each(|child_data, i| {
+                if i == from {
+                    let background = Rect::new(0.0, rect.y0, width, rect.y1);
+                    ctx.fill(background, &env.get(theme::WINDOW_BACKGROUND_COLOR));
+                    child.paint_with_offset(ctx, child_data, env);
+                    paint_handle(ctx, rect, env);
+                }
+            });
+        }
     }
 }
@@ -14,37 +14,64 @@
 
 //! Common widgets.
 
+mod accordion;
 mod align;
+mod aspect_ratio_box;
+mod badge;
 mod button;
+mod cached;
+mod canvas;
+mod capture;
+mod chart;
 mod checkbox;
 mod click;
+mod collapsible;
 mod common;
+mod computed;
 mod container;
 mod controller;
+mod date_picker;
+mod dropdown;
 mod either;
 mod env_scope;
 mod flex;
+mod focus_style;
+mod formatted;
+mod gauge;
+mod hidden;
 mod identity_wrapper;
 #[cfg(feature = "image")]
 #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
 mod image;
 mod label;
 mod list;
+mod navigator;
+mod on_copy;
+mod opacity;
 mod padding;
 mod painter;
 mod parse;
+mod popover;
 mod progress_bar;
+mod proportional;
 mod radio;
+mod resizable;
 mod scroll;
+mod selectable_list;
 mod sized_box;
 mod slider;
 mod split;
+mod stack;
 mod stepper;
+mod sticky;
 #[cfg(feature = "svg")]
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 mod svg;
 mod switch;
 mod textbox;
+mod throttle;
+mod time_picker;
+mod transform;
 mod view_switcher;
 #[allow(clippy::module_inception)]
 mod widget;
@@ -53,34 +80,61 @@ mod widget_ext;
 #[cfg(feature = "image")]
 #[cfg_attr(docsrs, doc(cfg(feature = "image")))]
 pub use self::image::{Image, ImageData};
+pub use accordion::Accordion;
 pub use align::Align;
+pub use aspect_ratio_box::AspectRatioBox;
+pub use badge::Badge;
 pub use button::Button;
-pub use checkbox::Checkbox;
-pub use click::Click;
+pub use cached::Cached;
+pub use canvas::{Canvas, CanvasCtx};
+pub use capture::Capture;
+pub use chart::{BarChart, LineChart};
+pub use checkbox::{Checkbox, TristateCheckbox, TristatePolicy};
+pub use click::{Click, ClickRepeat, DEFAULT_REPEAT_DELAY};
+pub use collapsible::{Collapsible, DEFAULT_COLLAPSE_ANIMATION};
 pub use common::FillStrat;
+pub use computed::Computed;
 pub use container::Container;
 pub use controller::{Controller, ControllerHost};
+pub use date_picker::DatePicker;
+pub use dropdown::Dropdown;
 pub use either::Either;
 pub use env_scope::EnvScope;
 pub use flex::{CrossAxisAlignment, Flex, FlexParams, MainAxisAlignment};
+pub use focus_style::{FocusStyle, FocusStyled};
+pub use formatted::Formatted;
+pub use gauge::{Gauge, DEFAULT_GAUGE_ANIMATION};
+pub use hidden::Hidden;
 pub use identity_wrapper::IdentityWrapper;
 pub use label::{Label, LabelText};
-pub use list::{List, ListIter};
+pub use list::{List, ListIter, ScrollAlign, ScrollToItem};
+pub use navigator::Navigator;
+pub use on_copy::OnCopy;
+pub use opacity::Opacity;
 pub use padding::Padding;
 pub use painter::{BackgroundBrush, Painter};
 pub use parse::Parse;
+pub use popover::Popover;
 pub use progress_bar::ProgressBar;
+pub use proportional::Proportional;
 pub use radio::{Radio, RadioGroup};
+pub use resizable::Resizable;
 pub use scroll::Scroll;
+pub use selectable_list::{SelectableList, Selection};
 pub use sized_box::SizedBox;
 pub use slider::Slider;
 pub use split::Split;
+pub use stack::Stack;
 pub use stepper::Stepper;
+pub use sticky::Sticky;
 #[cfg(feature = "svg")]
 #[cfg_attr(docsrs, doc(cfg(feature = "svg")))]
 pub use svg::{Svg, SvgData};
 pub use switch::Switch;
-pub use textbox::TextBox;
+pub use textbox::{TextBox, UpdateMode};
+pub use throttle::Throttle;
+pub use time_picker::TimePicker;
+pub use transform::Transform;
 pub use view_switcher::ViewSwitcher;
 #[doc(hidden)]
 pub use widget::{Widget, WidgetId};
@@ -0,0 +1,324 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows one of a stack of views.
+
+use std::time::Duration;
+
+use crate::command::sys as sys_cmd;
+use crate::inspect;
+use crate::kurbo::{Affine, Point, Rect, Size};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, LifeCycle,
+    LifeCycleCtx, MouseButton, PaintCtx, RawMods, RenderContext, Selector, UpdateCtx, Widget,
+    WidgetPod,
+};
+
+type ViewBuilder<T> = dyn Fn(&T, &Env) -> Box<dyn Widget<T>>;
+
+// we send ourselves this once a pushed view has been added, as focus can
+// only be given in event.
+const FOCUS_VIEW: Selector = Selector::new("druid-builtin.navigator-focus-view");
+
+/// A widget that shows the top of a stack of views, such as a main screen
+/// with a settings screen pushed over it.
+///
+/// Views are pushed by name, with the [`PUSH_VIEW`] command, and popped with
+/// [`POP_VIEW`]. The user can also go back with the mouse's back button, or
+/// with alt and the left arrow key while a widget in the current view has
+/// focus. Commands and keys that the current view doesn't handle go to the
+/// innermost navigator first, so navigators can be nested.
+///
+/// Only the current view is laid out, painted and sent events; the views
+/// under it keep their state until they are shown again. Each view is told
+/// when it is entered and left with [`LifeCycle::NavigationChanged`]. A
+/// pushed view's first focusable widget is given focus.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Flex, Label, Navigator};
+/// use druid::{commands, Command};
+///
+/// let main = Flex::column()
+///     .with_child(Label::new("04:59"))
+///     .with_child(Button::new("Settings").on_click(|ctx, _data: &mut u32, _env| {
+///         ctx.submit_command(Command::new(commands::PUSH_VIEW, "settings"), None);
+///     }));
+/// let navigator = Navigator::new(main).with_view("settings", |_data, _env| {
+///     Box::new(Button::new("Back").on_click(|ctx, _data, _env| {
+///         ctx.submit_command(commands::POP_VIEW, None);
+///     }))
+/// });
+/// ```
+///
+/// [`PUSH_VIEW`]: ../commands/constant.PUSH_VIEW.html
+/// [`POP_VIEW`]: ../commands/constant.POP_VIEW.html
+/// [`LifeCycle::NavigationChanged`]: ../enum.LifeCycle.html#variant.NavigationChanged
+pub struct Navigator<T> {
+    views: Vec<WidgetPod<T, Box<dyn Widget<T>>>>,
+    builders: Vec<(&'static str, Box<ViewBuilder<T>>)>,
+    animation: Duration,
+    transition: Option<Transition<T>>,
+    // from 0.0, when a transition starts, to 1.0.
+    progress: f64,
+    // whether the top view has been told it was entered.
+    entered: bool,
+    // whether the top view was pushed, and should take focus once entered.
+    focus_on_enter: bool,
+}
+
+enum Transition<T> {
+    Push,
+    // the popped view, painted until it has slid away.
+    Pop(Box<WidgetPod<T, Box<dyn Widget<T>>>>),
+}
+
+impl<T: Data> Navigator<T> {
+    /// Create a new `Navigator`, showing `root` until a view is pushed.
+    pub fn new(root: impl Widget<T> + 'static) -> Self {
+        Navigator {
+            views: vec![WidgetPod::new(root).boxed()],
+            builders: Vec::new(),
+            animation: Duration::from_secs(0),
+            transition: None,
+            progress: 1.0,
+            entered: false,
+            focus_on_enter: false,
+        }
+    }
+
+    /// Builder-style method to add a view that can be pushed by `name`.
+    ///
+    /// `builder` creates a new widget each time the view is pushed.
+    pub fn with_view(
+        mut self,
+        name: &'static str,
+        builder: impl Fn(&T, &Env) -> Box<dyn Widget<T>> + 'static,
+    ) -> Self {
+        self.builders.push((name, Box::new(builder)));
+        self
+    }
+
+    /// Builder-style method to slide views in and out, taking `duration`.
+    ///
+    /// By default views change without an animation.
+    pub fn with_transition(mut self, duration: Duration) -> Self {
+        self.animation = duration;
+        self
+    }
+
+    /// The number of views on the stack, including the first.
+    pub fn depth(&self) -> usize {
+        self.views.len()
+    }
+
+    fn push(&mut self, ctx: &mut EventCtx, name: &str, data: &T, env: &Env) {
+        let builder = match self.builders.iter().find(|(n, _)| *n == name) {
+            Some((_, builder)) => builder,
+            None => {
+                log::warn!(target: "druid::command", "Navigator has no view named '{}'", name);
+                return;
+            }
+        };
+        let view = WidgetPod::new(builder(data, env));
+        self.leave_top(ctx, data, env);
+        self.views.push(view);
+        self.focus_on_enter = true;
+        self.start_transition(ctx, Transition::Push);
+    }
+
+    fn pop(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) -> bool {
+        if self.views.len() < 2 {
+            return false;
+        }
+        self.leave_top(ctx, data, env);
+        self.focus_on_enter = false;
+        let popped = self.views.pop().unwrap();
+        self.start_transition(ctx, Transition::Pop(Box::new(popped)));
+        true
+    }
+
+    fn leave_top(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        // the view is covered or gone, so it can't keep the focus.
+        ctx.resign_focus_within();
+        if self.entered {
+            let mut lc_ctx = ctx.make_lifecycle_ctx();
+            let top = self.views.last_mut().unwrap();
+            top.lifecycle(&mut lc_ctx, &LifeCycle::NavigationChanged(false), data, env);
+            self.entered = false;
+        }
+    }
+
+    fn start_transition(&mut self, ctx: &mut EventCtx, transition: Transition<T>) {
+        // the new top view is entered once it has been added.
+        ctx.children_changed();
+        if self.animation > Duration::from_secs(0) {
+            self.transition = Some(transition);
+            self.progress = 0.0;
+            ctx.request_anim_frame();
+        } else {
+            self.transition = None;
+            self.progress = 1.0;
+        }
+    }
+}
+
+fn is_back_key(event: &Event) -> bool {
+    match event {
+        Event::KeyDown(key) => HotKey::new(RawMods::Alt, KeyCode::ArrowLeft).matches(key),
+        Event::MouseDown(mouse) => mouse.button == MouseButton::X1,
+        _ => false,
+    }
+}
+
+impl<T: Data> Widget<T> for Navigator<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.views.last_mut().unwrap().event(ctx, event, data, env);
+        if ctx.is_handled() {
+            return;
+        }
+        match event {
+            Event::Command(cmd) if cmd.is(sys_cmd::PUSH_VIEW) => {
+                match cmd.get_object::<&'static str>() {
+                    Ok(name) => self.push(ctx, name, data, env),
+                    Err(e) => {
+                        log::warn!(
+                            target: "druid::command",
+                            "push-view command has the wrong argument: {}",
+                            e
+                        )
+                    }
+                }
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(sys_cmd::POP_VIEW) && self.pop(ctx, data, env) => {
+                ctx.set_handled();
+            }
+            Event::Command(cmd) if cmd.is(FOCUS_VIEW) => {
+                let top = self.views.last().unwrap();
+                if let Some(first) = top.state().focus_chain.first() {
+                    ctx.set_focus(*first);
+                }
+                ctx.set_handled();
+            }
+            _ if is_back_key(event) && self.pop(ctx, data, env) => ctx.set_handled(),
+            _ => (),
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::AnimFrame(interval) = event {
+            if self.transition.is_some() {
+                let step = *interval as f64 / self.animation.as_nanos().max(1) as f64;
+                self.progress = (self.progress + step).min(1.0);
+                if self.progress < 1.0 {
+                    ctx.request_anim_frame();
+                } else {
+                    self.transition = None;
+                }
+                ctx.request_layout();
+            }
+        }
+
+        // a sliding view is clipped to our bounds.
+        if let Some(tree) = inspect::tree_cell(event) {
+            tree.transform_children(Affine::default(), Some(ctx.size().to_rect()));
+        }
+
+        match event {
+            // the covered views are not children, so that their widgets
+            // can't be found, or focused, while they can't be seen.
+            LifeCycle::WidgetAdded
+            | LifeCycle::RouteWidgetAdded
+            | LifeCycle::RouteInspectTree(_) => {
+                let top = self.views.last_mut().unwrap();
+                top.lifecycle(ctx, event, data, env);
+            }
+            _ => {
+                for view in self.views.iter_mut() {
+                    view.lifecycle(ctx, event, data, env);
+                }
+            }
+        }
+
+        if let LifeCycle::WidgetAdded | LifeCycle::RouteWidgetAdded = event {
+            if !self.entered {
+                let top = self.views.last_mut().unwrap();
+                top.lifecycle(ctx, &LifeCycle::NavigationChanged(true), data, env);
+                self.entered = true;
+                if std::mem::replace(&mut self.focus_on_enter, false) {
+                    ctx.submit_command(FOCUS_VIEW, ctx.widget_id());
+                }
+            }
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for view in self.views.iter_mut() {
+            view.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Navigator");
+
+        let top = self.views.len() - 1;
+        let size = self.views[top].layout(ctx, bc, data, env);
+
+        // the top view slides in from the right as it is pushed, and the
+        // popped view slides out to the right.
+        let eased = 1.0 - (1.0 - self.progress).powi(2);
+        let (top_x, other_x) = match self.transition {
+            Some(Transition::Push) => ((1.0 - eased) * size.width, -eased * size.width),
+            Some(Transition::Pop(_)) => (-(1.0 - eased) * size.width, eased * size.width),
+            None => (0.0, 0.0),
+        };
+        self.views[top].set_layout_rect(Rect::from_origin_size(Point::new(top_x, 0.0), size));
+
+        let other = match self.transition.as_mut() {
+            Some(Transition::Push) if top > 0 => Some(&mut self.views[top - 1]),
+            Some(Transition::Pop(popped)) => Some(&mut **popped),
+            _ => None,
+        };
+        if let Some(other) = other {
+            let other_size = other.layout(ctx, bc, data, env);
+            other.set_layout_rect(Rect::from_origin_size(Point::new(other_x, 0.0), other_size));
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let top = self.views.len() - 1;
+        if self.transition.is_none() {
+            self.views[top].paint_with_offset(ctx, data, env);
+            return;
+        }
+
+        let bounds = ctx.size().to_rect();
+        let views = &mut self.views;
+        let transition = &mut self.transition;
+        ctx.with_save(|ctx| {
+            ctx.clip(bounds);
+            match transition {
+                Some(Transition::Push) if top > 0 => {
+                    views[top - 1].paint_with_offset(ctx, data, env)
+                }
+                Some(Transition::Pop(popped)) => popped.paint_with_offset(ctx, data, env),
+                _ => (),
+            }
+            views[top].paint_with_offset(ctx, data, env);
+        });
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A [`Controller`] that copies a representation of its widget.
+//!
+//! [`Controller`]: trait.Controller.html
+
+use crate::commands;
+use crate::widget::Controller;
+use crate::{Env, Event, EventCtx, LifeCycle, LifeCycleCtx, Target, Widget};
+
+type CopyFn<T> = dyn Fn(&T, &Env) -> String;
+
+/// A [`Controller`] that puts text on the clipboard when its widget, or one
+/// of its descendants, has focus and the user copies.
+///
+/// Copying sends [`commands::COPY`], from the Edit menu's Copy item or its
+/// hotkey; in a window without that menu item, the hotkey sends it to the
+/// focused widget directly. If a descendant handles the command, such as a
+/// [`TextBox`] that copies its selection, this controller does nothing.
+///
+/// Only a focused widget can be copied from, so if nothing in the widget can
+/// take focus, as with a [`Label`], the controller registers the widget for
+/// focus itself; it can then be tabbed to, or given focus with
+/// [`EventCtx::request_focus`].
+///
+/// This is available as the [`on_copy`] method on [`WidgetExt`].
+///
+/// # Examples
+///
+/// Copy the time that a countdown shows:
+///
+/// ```
+/// use druid::widget::Label;
+/// use druid::WidgetExt;
+///
+/// fn format(secs: &u64) -> String {
+///     format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+/// }
+///
+/// let countdown = Label::new(|secs: &u64, _: &_| format(secs))
+///     .on_copy(|secs, _env| format(secs));
+/// ```
+///
+/// [`Controller`]: trait.Controller.html
+/// [`commands::COPY`]: ../commands/constant.COPY.html
+/// [`TextBox`]: struct.TextBox.html
+/// [`Label`]: struct.Label.html
+/// [`EventCtx::request_focus`]: ../struct.EventCtx.html#method.request_focus
+/// [`on_copy`]: ../trait.WidgetExt.html#method.on_copy
+/// [`WidgetExt`]: ../trait.WidgetExt.html
+pub struct OnCopy<T> {
+    copy: Box<CopyFn<T>>,
+}
+
+impl<T> OnCopy<T> {
+    /// Create a new `OnCopy` controller that copies the text `copy` returns.
+    pub fn new(copy: impl Fn(&T, &Env) -> String + 'static) -> Self {
+        OnCopy {
+            copy: Box::new(copy),
+        }
+    }
+}
+
+impl<T, W: Widget<T>> Controller<T, W> for OnCopy<T> {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        child.event(ctx, event, data, env);
+        let cmd = match event {
+            Event::Command(cmd) => cmd,
+            // the hotkey's command, on its way to a focused descendant.
+            Event::TargetedCommand(Target::Widget(_), cmd) => cmd,
+            _ => return,
+        };
+        if cmd.is(commands::COPY) && ctx.has_focus() && !ctx.is_handled() {
+            let text = (self.copy)(data, env);
+            ctx.put_clipboard_string(&text);
+            ctx.set_handled();
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        child: &mut W,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        child.lifecycle(ctx, event, data, env);
+        if let LifeCycle::WidgetAdded | LifeCycle::RouteWidgetAdded = event {
+            if ctx.base_state.focus_chain.is_empty() {
+                ctx.register_for_focus();
+            }
+        }
+    }
+}
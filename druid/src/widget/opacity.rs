@@ -0,0 +1,176 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that paints its child partially transparent.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::piet::Device;
+use crate::widget::cached::{current_scale, record, PaintCache};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, KeyOrValue, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, UpdateCtx, Widget, WidgetPod,
+};
+
+/// A widget that paints its child, and everything in it, with a given
+/// opacity.
+///
+/// The child is painted into an offscreen bitmap, which is then drawn with
+/// the opacity applied, so overlapping parts of the child don't show through
+/// each other. At an opacity of `1.0` the child is painted normally, and at
+/// `0.0` it isn't painted at all; it is still laid out, and still receives
+/// events, either way.
+///
+/// The bitmap is kept, and only painted again when the child requests a
+/// paint or the opacity changes, as with [`Cached`].
+///
+/// Children that paint with a z-index can't be drawn into a bitmap; they
+/// are painted without the opacity, and a warning is logged.
+///
+/// [`Cached`]: struct.Cached.html
+///
+/// You will generally want to create this with [`WidgetExt::with_opacity`].
+///
+/// [`WidgetExt::with_opacity`]: ../trait.WidgetExt.html#method.with_opacity
+pub struct Opacity<T, W> {
+    child: WidgetPod<T, W>,
+    opacity: KeyOrValue<f64>,
+    // the opacity we last painted with, so we can repaint if it changes.
+    painted_opacity: Option<f64>,
+    device: Option<Device>,
+    // the child's painting with the opacity applied.
+    layer: Option<PaintCache>,
+    // set if the child paints with a z-index, and can't be recorded.
+    has_z_ops: bool,
+}
+
+impl<T, W: Widget<T>> Opacity<T, W> {
+    /// Create a new `Opacity` widget, painting `child` with `opacity`.
+    ///
+    /// The opacity can be either an `f64` between `0.0` and `1.0`, or a
+    /// [`Key<f64>`]; setting the key with an [`EnvScope`] lets it follow the
+    /// app's data.
+    ///
+    /// [`Key<f64>`]: ../struct.Key.html
+    /// [`EnvScope`]: struct.EnvScope.html
+    pub fn new(child: W, opacity: impl Into<KeyOrValue<f64>>) -> Self {
+        Opacity {
+            child: WidgetPod::new(child),
+            opacity: opacity.into(),
+            painted_opacity: None,
+            device: None,
+            layer: None,
+            has_z_ops: false,
+        }
+    }
+
+    /// Set the opacity.
+    pub fn set_opacity(&mut self, opacity: impl Into<KeyOrValue<f64>>) {
+        self.opacity = opacity.into();
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Opacity<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        self.child.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+        let opacity = self.opacity.resolve(env).clamp(0.0, 1.0);
+        if self.painted_opacity != Some(opacity) {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Opacity");
+
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let opacity = self.opacity.resolve(env).clamp(0.0, 1.0);
+        let opacity_changed = self.painted_opacity != Some(opacity);
+        self.painted_opacity = Some(opacity);
+        if opacity >= 1.0 || self.has_z_ops {
+            self.layer = None;
+            self.child.paint(ctx, data, env);
+            return;
+        }
+
+        let rect = self.child.paint_rect();
+        if opacity <= 0.0 || rect.area() == 0.0 {
+            self.layer = None;
+            return;
+        }
+
+        let scale = current_scale(ctx);
+        let is_valid = match self.layer.as_ref() {
+            Some(layer) => {
+                !opacity_changed
+                    && !self.child.state().needs_inval
+                    && layer.rect.origin() == rect.origin()
+                    && layer.rect.size() == rect.size()
+                    && layer.scale == scale
+            }
+            None => false,
+        };
+
+        if !is_valid {
+            self.layer = None;
+            match record(
+                &mut self.device,
+                &mut self.child,
+                ctx,
+                rect,
+                scale,
+                data,
+                env,
+            ) {
+                Ok(Some(mut layer)) => {
+                    // the pixels are premultiplied, so every channel is scaled.
+                    for channel in layer.pixels.iter_mut() {
+                        *channel = (f64::from(*channel) * opacity).round() as u8;
+                    }
+                    self.layer = Some(layer);
+                }
+                Ok(None) => {
+                    log::warn!(
+                        target: "druid::paint",
+                        "Opacity child paints with a z-index; ignoring opacity"
+                    );
+                    self.has_z_ops = true;
+                    self.child.paint(ctx, data, env);
+                    return;
+                }
+                Err(e) => {
+                    log::warn!(target: "druid::paint", "failed to paint with opacity: {}", e);
+                    self.device = None;
+                    self.child.paint(ctx, data, env);
+                    return;
+                }
+            }
+        }
+
+        self.layer.as_ref().unwrap().draw(ctx);
+    }
+}
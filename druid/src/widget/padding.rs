@@ -109,6 +109,54 @@ impl<T: Data> Widget<T> for Padding<T> {
         my_size
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let vpad = self.top + self.bottom;
+        let child_height = (height - vpad).max(0.);
+        self.child.min_intrinsic_width(ctx, child_height, data, env) + self.left + self.right
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let vpad = self.top + self.bottom;
+        let child_height = (height - vpad).max(0.);
+        self.child.max_intrinsic_width(ctx, child_height, data, env) + self.left + self.right
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let hpad = self.left + self.right;
+        let child_width = (width - hpad).max(0.);
+        self.child.min_intrinsic_height(ctx, child_width, data, env) + self.top + self.bottom
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let hpad = self.left + self.right;
+        let child_width = (width - hpad).max(0.);
+        self.child.max_intrinsic_height(ctx, child_width, data, env) + self.top + self.bottom
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.child.paint_with_offset(ctx, data, env);
     }
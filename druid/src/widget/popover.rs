@@ -0,0 +1,387 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that shows a popup next to its trigger.
+
+use std::rc::Rc;
+
+use crate::inspect;
+use crate::kurbo::{Affine, Point, Rect, Size};
+use crate::piet::Device;
+use crate::theme;
+use crate::widget::cached::{current_scale, record, PaintCache};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, Lens, LifeCycle,
+    LifeCycleCtx, PaintCtx, RenderContext, UpdateCtx, Widget, WidgetPod,
+};
+
+/// The z-index popups are painted at, above the rest of the window.
+const POPUP_Z_INDEX: u32 = 1 << 16;
+
+/// A trigger widget, and a popup shown beside it.
+///
+/// Whether the popup is shown is read from, and written back to, a `bool`
+/// in the data, through a [`Lens`]; the trigger will usually set it, for
+/// instance when clicked. The popup opens below the trigger, or above it
+/// with [`with_prefer_above`], and moves to the other side if it would
+/// not fit in the window. It is closed again by a click anywhere outside it,
+/// or by the escape key while keyboard focus is in the popover.
+///
+/// The popup is painted above the rest of the window. While it is open the
+/// popover holds the mouse, as an [active] widget does; only the popup and
+/// the trigger see mouse events.
+///
+/// The popup is drawn by recording it into a bitmap, like [`Cached`], which
+/// is kept until the popup's contents request a paint. Its contents should
+/// not paint with a z-index of their own.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Button, Flex, Label, Popover};
+/// use druid::{Data, Lens, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Timer {
+///     minutes: u32,
+///     choosing: bool,
+/// }
+///
+/// let presets = Flex::column()
+///     .with_child(Button::new("5 minutes").on_click(|_, timer: &mut Timer, _| {
+///         timer.minutes = 5;
+///         timer.choosing = false;
+///     }))
+///     .with_child(Button::new("25 minutes").on_click(|_, timer: &mut Timer, _| {
+///         timer.minutes = 25;
+///         timer.choosing = false;
+///     }));
+/// let trigger = Button::new("Presets").on_click(|_, timer: &mut Timer, _| timer.choosing = true);
+/// let popover = Popover::new(trigger, presets, Timer::choosing);
+/// ```
+///
+/// [`Lens`]: ../trait.Lens.html
+/// [`with_prefer_above`]: #method.with_prefer_above
+/// [active]: ../struct.EventCtx.html#method.is_active
+/// [`Cached`]: struct.Cached.html
+pub struct Popover<T, L> {
+    popup: Popup<T>,
+    open: L,
+}
+
+/// A trigger and a popup, without any notion of where the open state is
+/// stored; shared with the `Dropdown`.
+pub(super) struct Popup<T, C = Box<dyn Widget<T>>> {
+    trigger: WidgetPod<T, Box<dyn Widget<T>>>,
+    content: WidgetPod<T, C>,
+    is_open: bool,
+    prefer_above: bool,
+    // where our origin was last painted, in window coordinates.
+    window_origin: Option<Point>,
+    // the size of the window at the last layout.
+    window_size: Size,
+    device: Option<Device>,
+    // the recorded popup, shared with the z-index painting.
+    cache: Option<Rc<PaintCache>>,
+}
+
+impl<T: Data, L: Lens<T, bool>> Popover<T, L> {
+    /// Create a new `Popover`, showing `content` beside `trigger` while
+    /// the `bool` at `open` is `true`.
+    pub fn new(
+        trigger: impl Widget<T> + 'static,
+        content: impl Widget<T> + 'static,
+        open: L,
+    ) -> Self {
+        Popover {
+            popup: Popup::new(trigger, Box::new(content)),
+            open,
+        }
+    }
+
+    /// Builder-style method to open the popup above the trigger, when it
+    /// fits, rather than below.
+    pub fn with_prefer_above(mut self, prefer_above: bool) -> Self {
+        self.popup.prefer_above = prefer_above;
+        self
+    }
+}
+
+impl<T: Data, C: Widget<T>> Popup<T, C> {
+    pub(super) fn new(trigger: impl Widget<T> + 'static, content: C) -> Self {
+        Popup {
+            trigger: WidgetPod::new(Box::new(trigger)),
+            content: WidgetPod::new(content),
+            is_open: false,
+            prefer_above: false,
+            window_origin: None,
+            window_size: Size::ZERO,
+            device: None,
+            cache: None,
+        }
+    }
+
+    pub(super) fn is_open(&self) -> bool {
+        self.is_open
+    }
+
+    pub(super) fn content_mut(&mut self) -> &mut C {
+        self.content.widget_mut()
+    }
+
+    /// Show or hide the popup. The caller should request layout.
+    pub(super) fn set_open(&mut self, open: bool) {
+        self.is_open = open;
+    }
+
+    /// Hold the mouse while the popup is open, so that we see clicks
+    /// outside of it.
+    pub(super) fn capture_mouse(&self, ctx: &mut EventCtx) {
+        if self.is_open != ctx.is_active() {
+            ctx.set_active(self.is_open);
+        }
+    }
+
+    /// Deliver an event, returning `true` if the user dismissed the popup.
+    pub(super) fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut T,
+        env: &Env,
+    ) -> bool {
+        if !self.is_open {
+            self.trigger.event(ctx, event, data, env);
+            if !event.is_pointer_event() {
+                self.content.event(ctx, event, data, env);
+            }
+            return false;
+        }
+
+        let in_content = |pos: Point| self.content.layout_rect().contains(pos);
+        match event {
+            Event::MouseDown(mouse) if !in_content(mouse.pos) => {
+                ctx.set_handled();
+                return true;
+            }
+            Event::KeyDown(key) if HotKey::new(None, KeyCode::Escape).matches(key) => {
+                ctx.set_handled();
+                return true;
+            }
+            _ => (),
+        }
+
+        self.content.event(ctx, event, data, env);
+        if !(event.is_pointer_event() && self.content.is_hot()) {
+            self.trigger.event(ctx, event, data, env);
+        }
+        false
+    }
+
+    pub(super) fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &T,
+        env: &Env,
+    ) {
+        self.trigger.lifecycle(ctx, event, data, env);
+        let content = &mut self.content;
+        match inspect::tree_cell(event) {
+            Some(tree) if self.is_open => tree
+                .with_children_on_top(POPUP_Z_INDEX, || content.lifecycle(ctx, event, data, env)),
+            // closed, the content is not painted at all.
+            Some(tree) => {
+                tree.with_children_transformed(Affine::default(), Some(Rect::ZERO), || {
+                    content.lifecycle(ctx, event, data, env)
+                })
+            }
+            None => content.lifecycle(ctx, event, data, env),
+        }
+    }
+
+    pub(super) fn update(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        self.trigger.update(ctx, data, env);
+        self.content.update(ctx, data, env);
+    }
+
+    pub(super) fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        let size = self.trigger.layout(ctx, bc, data, env);
+        self.trigger
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+
+        let content_bc = BoxConstraints::new(
+            Size::new(size.width, 0.0),
+            Size::new(f64::INFINITY, f64::INFINITY),
+        );
+        let content_size = self.content.layout(ctx, &content_bc, data, env);
+        self.window_size = ctx.window_size;
+        let origin = self.content_origin(size, content_size);
+        self.content
+            .set_layout_rect(Rect::from_origin_size(origin, content_size));
+
+        let mut paint_rect = self.trigger.paint_rect().union(size.to_rect());
+        if self.is_open {
+            paint_rect = paint_rect.union(self.content.paint_rect());
+        }
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+        size
+    }
+
+    /// Where the popup goes: on the preferred side of the trigger if it
+    /// fits in the window, and otherwise on the other side if it fits
+    /// there, shifted left if it would go past the window's right edge.
+    ///
+    /// This depends on where we are in the window, which is only known once
+    /// we have been painted; `paint` moves the popup if layout guessed wrong.
+    fn content_origin(&self, size: Size, content_size: Size) -> Point {
+        let window_size = self.window_size;
+        let below = size.height;
+        let above = -content_size.height;
+        let origin = match self.window_origin {
+            Some(origin) => origin,
+            None => return Point::new(0.0, if self.prefer_above { above } else { below }),
+        };
+
+        let fits_below = origin.y + below + content_size.height <= window_size.height;
+        let fits_above = origin.y + above >= 0.0;
+        let y = match (self.prefer_above, fits_above, fits_below) {
+            (true, false, true) => below,
+            (true, _, _) => above,
+            (false, true, false) => above,
+            (false, _, _) => below,
+        };
+        let overflow = origin.x + content_size.width - window_size.width;
+        let x = -overflow.max(0.0).min(origin.x.max(0.0));
+        Point::new(x, y)
+    }
+
+    pub(super) fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.window_origin = Some(ctx.render_ctx.current_transform() * Point::ORIGIN);
+        self.trigger.paint_with_offset(ctx, data, env);
+        if !self.is_open {
+            self.cache = None;
+            return;
+        }
+
+        // now that we know where we are, check the side the popup is on.
+        let content_rect = self.content.layout_rect();
+        let placed = self.content_origin(ctx.size(), content_rect.size());
+        if placed != content_rect.origin() {
+            self.content
+                .set_layout_rect(Rect::from_origin_size(placed, content_rect.size()));
+        }
+
+        let origin = self.content.layout_rect().origin().to_vec2();
+        let rect = self.content.paint_rect() - origin;
+        let scale = current_scale(ctx);
+        let is_valid = match self.cache.as_ref() {
+            Some(cache) => {
+                !self.content.state().needs_inval
+                    && cache.rect.origin() == rect.origin()
+                    && cache.rect.size() == rect.size()
+                    && cache.scale == scale
+                    && cache.debug_paint == env.get(Env::DEBUG_PAINT)
+            }
+            None => false,
+        };
+        if !is_valid {
+            self.cache = None;
+            match record(
+                &mut self.device,
+                &mut self.content,
+                ctx,
+                rect,
+                scale,
+                data,
+                env,
+            ) {
+                Ok(Some(cache)) => self.cache = Some(Rc::new(cache)),
+                Ok(None) => {}
+                Err(e) => {
+                    log::warn!(
+                        target: "druid::paint",
+                        "failed to record popup, painting directly: {}",
+                        e
+                    );
+                    self.device = None;
+                }
+            }
+        }
+
+        match self.cache.clone() {
+            Some(cache) => {
+                let background = self.content.layout_rect() - origin;
+                let radius = env.get(theme::BUTTON_BORDER_RADIUS);
+                let fill = env.get(theme::BACKGROUND_LIGHT);
+                let border = env.get(theme::BORDER_DARK);
+                ctx.paint_with_z_index(POPUP_Z_INDEX, move |ctx| {
+                    ctx.transform(Affine::translate(origin));
+                    let panel = background.to_rounded_rect(radius);
+                    ctx.fill(panel, &fill);
+                    ctx.stroke(panel, &border, 1.0);
+                    cache.draw(ctx);
+                });
+            }
+            None => self.content.paint_with_offset(ctx, data, env),
+        }
+    }
+}
+
+impl<T: Data, L: Lens<T, bool>> Widget<T> for Popover<T, L> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if self.popup.event(ctx, event, data, env) {
+            self.open.with_mut(data, |open| *open = false);
+        }
+        // the trigger may just have opened us; start holding the mouse now,
+        // rather than waiting for the next event.
+        let open = self.open.with(data, |open| *open);
+        if open != self.popup.is_open() {
+            self.popup.set_open(open);
+            ctx.request_layout();
+        }
+        self.popup.capture_mouse(ctx);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            self.popup.set_open(self.open.with(data, |open| *open));
+        }
+        self.popup.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let open = self.open.with(data, |open| *open);
+        if open != self.popup.is_open() {
+            self.popup.set_open(open);
+            ctx.request_layout();
+        }
+        self.popup.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Popover");
+        self.popup.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.popup.paint(ctx, data, env);
+    }
+}
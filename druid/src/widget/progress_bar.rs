@@ -52,8 +52,8 @@ impl Widget<f64> for ProgressBar {
     ) -> Size {
         bc.debug_check("ProgressBar");
         bc.constrain(Size::new(
-            env.get(theme::WIDE_WIDGET_WIDTH),
-            env.get(theme::BASIC_WIDGET_HEIGHT),
+            theme::scaled(env, theme::WIDE_WIDGET_WIDTH),
+            theme::scaled(env, theme::BASIC_WIDGET_HEIGHT),
         ))
     }
 
@@ -64,7 +64,7 @@ impl Widget<f64> for ProgressBar {
             Point::ORIGIN,
             (Size {
                 width: ctx.size().width,
-                height: env.get(theme::BASIC_WIDGET_HEIGHT),
+                height: theme::scaled(env, theme::BASIC_WIDGET_HEIGHT),
             })
             .to_vec2(),
             4.,
@@ -90,7 +90,7 @@ impl Widget<f64> for ProgressBar {
             Point::ORIGIN,
             (Size {
                 width: calculated_bar_width,
-                height: env.get(theme::BASIC_WIDGET_HEIGHT),
+                height: theme::scaled(env, theme::BASIC_WIDGET_HEIGHT),
             })
             .to_vec2(),
             env.get(theme::PROGRESS_BAR_RADIUS),
@@ -0,0 +1,250 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that divides its space between its children by weight.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::widget::flex::Axis;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// A child, with its weight.
+type WeightedChild<T> = (WidgetPod<T, Box<dyn Widget<T>>>, f64);
+
+/// A container that divides its space along one axis between its children,
+/// in proportion to their weights.
+///
+/// Each child is given exactly its share of the main axis, whatever size it
+/// would choose, and the whole of the cross axis. This is a simpler and
+/// cheaper layout than a [`Flex`] whose children are all flexible, and,
+/// unlike a [`Split`], it has no handle to drag.
+///
+/// If the main axis is unbounded, there is no space to divide, so each
+/// child is given the space it needs to be at least as big as its [`max
+/// intrinsic size`] while keeping the proportions.
+///
+/// ```
+/// use druid::widget::{Label, Proportional};
+///
+/// // a header taking 30% of the height, above the content.
+/// let layout = Proportional::<()>::column()
+///     .with_child(Label::new("header"), 3.0)
+///     .with_child(Label::new("content"), 7.0)
+///     .with_gap(4.0);
+/// ```
+///
+/// [`Flex`]: struct.Flex.html
+/// [`Split`]: struct.Split.html
+/// [`max intrinsic size`]: ../trait.Widget.html#method.max_intrinsic_width
+pub struct Proportional<T> {
+    direction: Axis,
+    children: Vec<WeightedChild<T>>,
+    gap: f64,
+}
+
+impl<T: Data> Proportional<T> {
+    fn new(direction: Axis) -> Self {
+        Proportional {
+            direction,
+            children: Vec::new(),
+            gap: 0.,
+        }
+    }
+
+    /// Create a container that divides its width, from left to right.
+    pub fn row() -> Self {
+        Proportional::new(Axis::Horizontal)
+    }
+
+    /// Create a container that divides its height, from top to bottom.
+    pub fn column() -> Self {
+        Proportional::new(Axis::Vertical)
+    }
+
+    /// Builder-style variant of [`add_child`].
+    ///
+    /// [`add_child`]: #method.add_child
+    pub fn with_child(mut self, child: impl Widget<T> + 'static, weight: f64) -> Self {
+        self.add_child(child, weight);
+        self
+    }
+
+    /// Builder-style method to set the space between children.
+    pub fn with_gap(mut self, gap: f64) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Add a child, which gets `weight` shares of the space.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `weight` is negative, or not finite.
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static, weight: f64) {
+        assert!(
+            weight >= 0. && weight.is_finite(),
+            "Proportional weight must be zero or more, and finite, not {}",
+            weight
+        );
+        self.children.push((WidgetPod::new(child).boxed(), weight));
+    }
+
+    fn total_gap(&self) -> f64 {
+        self.gap * self.children.len().saturating_sub(1) as f64
+    }
+
+    fn total_weight(&self) -> f64 {
+        self.children.iter().map(|(_, weight)| weight).sum()
+    }
+
+    /// The max intrinsic size on `axis`, given `extent` on the other axis.
+    fn intrinsic(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        axis: Axis,
+        extent: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let total_weight = self.total_weight();
+        let total_gap = self.total_gap();
+        let direction = self.direction;
+        let mut size: f64 = 0.;
+        for (child, weight) in &mut self.children {
+            let child_size = match axis {
+                Axis::Horizontal => child.max_intrinsic_width(ctx, extent, data, env),
+                Axis::Vertical => child.max_intrinsic_height(ctx, extent, data, env),
+            };
+            if axis != direction {
+                size = size.max(child_size);
+            } else if *weight > 0. {
+                // the size at which this child's share is big enough.
+                size = size.max(child_size * total_weight / *weight);
+            }
+        }
+        if axis == direction {
+            size + total_gap
+        } else {
+            size
+        }
+    }
+}
+
+impl<T: Data> Widget<T> for Proportional<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        for (child, _) in &mut self.children {
+            if ctx.is_handled() {
+                break;
+            }
+            child.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for (child, _) in &mut self.children {
+            child.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for (child, _) in &mut self.children {
+            child.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Proportional");
+
+        let direction = self.direction;
+        let max_major = direction.major(bc.max());
+        let max_minor = direction.minor(bc.max());
+        let major = if max_major.is_finite() {
+            max_major
+        } else {
+            self.intrinsic(ctx, direction, max_minor, data, env)
+        };
+        let major = major.max(direction.major(bc.min()));
+
+        let total_weight = self.total_weight();
+        let available = (major - self.total_gap()).max(0.);
+        // children fill the cross axis if they can.
+        let (min_minor, max_minor) = if max_minor.is_finite() {
+            (max_minor, max_minor)
+        } else {
+            (direction.minor(bc.min()), max_minor)
+        };
+
+        let mut minor = direction.minor(bc.min());
+        let mut position = 0.;
+        let mut remainder = 0.;
+        for (child, weight) in &mut self.children {
+            let share = if total_weight > 0. {
+                available * *weight / total_weight
+            } else {
+                0.
+            };
+            let desired = share + remainder;
+            let child_major = desired.round();
+            remainder = desired - child_major;
+
+            let (min_w, min_h) = direction.pack(child_major, min_minor);
+            let (max_w, max_h) = direction.pack(child_major, max_minor);
+            let child_bc = BoxConstraints::new(Size::new(min_w, min_h), Size::new(max_w, max_h));
+            let size = child.layout(ctx, &child_bc, data, env);
+            let origin: Point = direction.pack(position, 0.).into();
+            child.set_layout_rect(Rect::from_origin_size(origin, size));
+
+            minor = minor.max(direction.minor(size));
+            position += child_major + self.gap;
+        }
+
+        let my_size: Size = direction.pack(major, minor).into();
+        let my_size = bc.constrain(my_size);
+
+        let mut paint_rect = Rect::ZERO;
+        for (child, _) in &self.children {
+            paint_rect = paint_rect.union(child.paint_rect());
+        }
+        ctx.set_paint_insets(paint_rect - my_size.to_rect());
+        my_size
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Axis::Horizontal, height, data, env)
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.intrinsic(ctx, Axis::Vertical, width, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for (child, _) in &mut self.children {
+            child.paint_with_offset(ctx, data, env);
+        }
+    }
+}
@@ -96,8 +96,8 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
         bc.debug_check("Radio");
 
         let label_size = self.child_label.layout(layout_ctx, &bc, data, env);
-        let padding = 5.0;
-        let label_x_offset = env.get(theme::BASIC_WIDGET_HEIGHT) + padding;
+        let padding = 5.0 * env.get(theme::UI_SCALE);
+        let label_x_offset = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT) + padding;
         let origin = Point::new(label_x_offset, 0.0);
 
         self.child_label
@@ -105,12 +105,12 @@ impl<T: Data + PartialEq> Widget<T> for Radio<T> {
 
         bc.constrain(Size::new(
             label_x_offset + label_size.width,
-            env.get(theme::BASIC_WIDGET_HEIGHT).max(label_size.height),
+            theme::scaled(env, theme::BASIC_WIDGET_HEIGHT).max(label_size.height),
         ))
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
-        let size = env.get(theme::BASIC_WIDGET_HEIGHT);
+        let size = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT);
 
         let circle = Circle::new((size / 2., size / 2.), 7.);
 
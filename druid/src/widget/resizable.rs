@@ -0,0 +1,289 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that the user can resize by dragging its edges.
+
+use crate::kurbo::{Line, Point, Rect, Size, Vec2};
+use crate::theme;
+use crate::{
+    BoxConstraints, Cursor, Data, Env, Event, EventCtx, LayoutCtx, Lens, LifeCycle, LifeCycleCtx,
+    PaintCtx, RenderContext, UpdateCtx, Widget, WidgetPod,
+};
+
+/// How far in from the edge a drag starts resizing, rather than reaching
+/// the child.
+const HANDLE_WIDTH: f64 = 6.0;
+
+/// A widget that lets the user resize its child by dragging its edges or
+/// corners.
+///
+/// The size is read from, and written back to, the data through a [`Lens`].
+/// The child is always laid out at that size, clamped to the minimum and
+/// maximum set on the widget and to the constraints from the parent; a drag
+/// stores the clamped size.
+///
+/// Dragging the left or top edge changes the size the same way as dragging
+/// the opposite edge the other way. Whether the widget then appears to grow
+/// towards the left or top depends on how its parent positions it.
+///
+/// While the user drags a handle the widget is [active], so the drag keeps
+/// going if the mouse leaves it.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, Resizable};
+/// use druid::{Data, Lens, Size, WidgetExt};
+///
+/// #[derive(Clone, Data, Lens)]
+/// struct Panel {
+///     size: Size,
+/// }
+///
+/// let panel = Resizable::new(Label::new("Timer").center(), Panel::size)
+///     .with_min_size(Size::new(100., 40.))
+///     .with_max_size(Size::new(400., 300.));
+/// ```
+///
+/// [`Lens`]: ../trait.Lens.html
+/// [active]: ../struct.EventCtx.html#method.is_active
+pub struct Resizable<T, L, W> {
+    child: WidgetPod<T, W>,
+    size_lens: L,
+    min_size: Size,
+    max_size: Size,
+    horizontal: bool,
+    vertical: bool,
+    // the constraints from the last layout, which the dragged size is
+    // clamped to.
+    bc: Option<BoxConstraints>,
+    // the handle being dragged, with the mouse's window position and our
+    // size at the start of the drag.
+    drag: Option<(Handle, Point, Size)>,
+}
+
+/// The edges being dragged; one or both of `x` and `y` is not `None`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Handle {
+    x: Edge,
+    y: Edge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Edge {
+    None,
+    /// The left or top edge.
+    Start,
+    /// The right or bottom edge.
+    End,
+}
+
+impl<T: Data, L: Lens<T, Size>, W: Widget<T>> Resizable<T, L, W> {
+    /// Create a new `Resizable`, with the child's size at `size_lens`.
+    pub fn new(child: W, size_lens: L) -> Self {
+        Resizable {
+            child: WidgetPod::new(child),
+            size_lens,
+            min_size: Size::new(2.0 * HANDLE_WIDTH, 2.0 * HANDLE_WIDTH),
+            max_size: Size::new(f64::INFINITY, f64::INFINITY),
+            horizontal: true,
+            vertical: true,
+            bc: None,
+            drag: None,
+        }
+    }
+
+    /// Builder-style method to set the smallest size the user can drag the
+    /// widget to.
+    pub fn with_min_size(mut self, size: Size) -> Self {
+        self.min_size = size;
+        self
+    }
+
+    /// Builder-style method to set the largest size the user can drag the
+    /// widget to.
+    pub fn with_max_size(mut self, size: Size) -> Self {
+        self.max_size = size;
+        self
+    }
+
+    /// Builder-style method to set whether the width can be changed, by
+    /// dragging the left or right edge.
+    pub fn with_horizontal_resize(mut self, resizable: bool) -> Self {
+        self.horizontal = resizable;
+        self
+    }
+
+    /// Builder-style method to set whether the height can be changed, by
+    /// dragging the top or bottom edge.
+    pub fn with_vertical_resize(mut self, resizable: bool) -> Self {
+        self.vertical = resizable;
+        self
+    }
+
+    /// The handle at `pos`, if any.
+    fn handle_at(&self, size: Size, pos: Point) -> Option<Handle> {
+        if !size.to_rect().contains(pos) {
+            return None;
+        }
+        let edge = |enabled: bool, pos: f64, len: f64| {
+            if !enabled {
+                Edge::None
+            } else if pos >= len - HANDLE_WIDTH {
+                Edge::End
+            } else if pos < HANDLE_WIDTH {
+                Edge::Start
+            } else {
+                Edge::None
+            }
+        };
+        let x = edge(self.horizontal, pos.x, size.width);
+        let y = edge(self.vertical, pos.y, size.height);
+        if x == Edge::None && y == Edge::None {
+            None
+        } else {
+            Some(Handle { x, y })
+        }
+    }
+
+    /// Clamp `size` to our limits, and to the constraints from the parent.
+    fn clamp(&self, size: Size) -> Size {
+        let max = Size::new(
+            self.max_size.width.max(self.min_size.width),
+            self.max_size.height.max(self.min_size.height),
+        );
+        let size = size.clamp(self.min_size, max);
+        match &self.bc {
+            Some(bc) => bc.constrain(size),
+            None => size,
+        }
+    }
+}
+
+impl Handle {
+    fn cursor(self) -> Cursor {
+        match (self.x, self.y) {
+            (Edge::None, _) => Cursor::ResizeUpDown,
+            (_, Edge::None) => Cursor::ResizeLeftRight,
+            (Edge::Start, Edge::Start) | (Edge::End, Edge::End) => Cursor::ResizeUpLeftDownRight,
+            _ => Cursor::ResizeUpRightDownLeft,
+        }
+    }
+
+    /// The new size after dragging by `delta` from `size`.
+    fn resize(self, size: Size, delta: Vec2) -> Size {
+        let apply = |edge: Edge, len: f64, delta: f64| match edge {
+            Edge::None => len,
+            Edge::Start => len - delta,
+            Edge::End => len + delta,
+        };
+        Size::new(
+            apply(self.x, size.width, delta.x),
+            apply(self.y, size.height, delta.y),
+        )
+    }
+}
+
+impl<T: Data, L: Lens<T, Size>, W: Widget<T>> Widget<T> for Resizable<T, L, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        match event {
+            Event::MouseDown(mouse) if mouse.button.is_left() => {
+                if let Some(handle) = self.handle_at(ctx.size(), mouse.pos) {
+                    // start from the size we are shown at, which may be
+                    // smaller than the one in the data.
+                    self.drag = Some((handle, mouse.window_pos, ctx.size()));
+                    ctx.set_active(true);
+                    ctx.set_cursor(&handle.cursor());
+                    ctx.set_handled();
+                    return;
+                }
+            }
+            Event::MouseMoved(mouse) => {
+                if let Some((handle, start, start_size)) = self.drag {
+                    // window coordinates, since our origin may move as we
+                    // change size.
+                    let delta = mouse.window_pos - start;
+                    let size = self.clamp(handle.resize(start_size, delta));
+                    self.size_lens.with_mut(data, |old| *old = size);
+                    ctx.set_cursor(&handle.cursor());
+                    ctx.request_layout();
+                    ctx.set_handled();
+                    return;
+                }
+                if ctx.is_hot() {
+                    if let Some(handle) = self.handle_at(ctx.size(), mouse.pos) {
+                        ctx.set_cursor(&handle.cursor());
+                    }
+                }
+            }
+            Event::MouseUp(mouse) if mouse.button.is_left() && self.drag.is_some() => {
+                self.drag = None;
+                ctx.set_active(false);
+                ctx.request_paint();
+                ctx.set_handled();
+                return;
+            }
+            _ => (),
+        }
+        self.child.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::HotChanged(_) = event {
+            ctx.request_paint();
+        }
+        self.child.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env) {
+        let old_size = self.size_lens.with(old_data, |size| *size);
+        let size = self.size_lens.with(data, |size| *size);
+        if old_size != size {
+            ctx.request_layout();
+        }
+        self.child.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Resizable");
+
+        self.bc = Some(*bc);
+        let size = self.size_lens.with(data, |size| *size);
+        let size = self.clamp(size);
+        let child_bc = BoxConstraints::tight(size);
+        let size = self.child.layout(ctx, &child_bc, data, env);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.child.paint(ctx, data, env);
+
+        // a small grip in the corner, while the handles can be used.
+        if (ctx.is_hot() || self.drag.is_some()) && (self.horizontal || self.vertical) {
+            let size = ctx.size();
+            let color = env.get(theme::BORDER_LIGHT);
+            for i in 1..=2 {
+                let inset = HANDLE_WIDTH * f64::from(i) / 2.0;
+                let line = Line::new(
+                    (size.width - inset, size.height - 1.0),
+                    (size.width - 1.0, size.height - inset),
+                );
+                ctx.stroke(line, &color, 1.0);
+            }
+        }
+    }
+}
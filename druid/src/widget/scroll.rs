@@ -15,8 +15,10 @@
 //! A container that scrolls its contents.
 
 use std::f64::INFINITY;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
+use crate::core::BaseState;
+use crate::inspect;
 use crate::kurbo::{Affine, Point, Rect, RoundedRect, Size, Vec2};
 use crate::theme;
 use crate::{
@@ -106,12 +108,39 @@ impl ScrollbarsState {
 /// when the child's bounds are larger than the viewport.
 ///
 /// The child is laid out with completely unconstrained layout bounds.
+///
+/// When a `Scroll` is inside another one, a wheel event that it cannot
+/// use, because it is already scrolled to the edge, is passed on to the outer
+/// one; see [`with_chaining`] to turn this off.
+///
+/// Whenever the visible part of the child changes, the child and its
+/// descendants receive [`LifeCycle::ViewportChanged`].
+///
+/// When a descendant gets keyboard focus, the scroll moves to bring it,
+/// and its focus ring, into view; see [`with_focus_margin`].
+///
+/// When the child's content changes, the offset is kept, unless the content
+/// becomes too small for it. A keyed [`List`] in the child keeps its top
+/// visible item in place instead; see [`List::with_keys`].
+///
+/// [`LifeCycle::ViewportChanged`]: ../enum.LifeCycle.html#variant.ViewportChanged
+/// [`with_chaining`]: #method.with_chaining
+/// [`with_focus_margin`]: #method.with_focus_margin
+/// [`List`]: struct.List.html
+/// [`List::with_keys`]: struct.List.html#method.with_keys
 pub struct Scroll<T, W> {
     child: WidgetPod<T, W>,
     child_size: Size,
     scroll_offset: Vec2,
     direction: ScrollDirection,
     scrollbars: ScrollbarsState,
+    chaining: bool,
+    focus_margin: f64,
+    preserve_offset: bool,
+    /// The offset we would be at, if the content were large enough.
+    wanted_offset: Option<Vec2>,
+    // the last viewport we told the child about
+    viewport: Option<Rect>,
 }
 
 impl<T, W: Widget<T>> Scroll<T, W> {
@@ -127,6 +156,11 @@ impl<T, W: Widget<T>> Scroll<T, W> {
             scroll_offset: Vec2::new(0.0, 0.0),
             direction: ScrollDirection::All,
             scrollbars: ScrollbarsState::default(),
+            chaining: true,
+            focus_margin: 0.0,
+            preserve_offset: false,
+            wanted_offset: None,
+            viewport: None,
         }
     }
 
@@ -144,6 +178,40 @@ impl<T, W: Widget<T>> Scroll<T, W> {
         self
     }
 
+    /// Builder-style method to set whether wheel events this scroll can't
+    /// use are passed on to an enclosing `Scroll`.
+    ///
+    /// This is on by default. When it is off, a wheel event over this scroll
+    /// never scrolls anything else, as long as the content is larger than the
+    /// viewport.
+    pub fn with_chaining(mut self, chaining: bool) -> Self {
+        self.chaining = chaining;
+        self
+    }
+
+    /// Builder-style method to set the space to leave around a descendant
+    /// that is scrolled into view because it got focus.
+    ///
+    /// This is zero by default, so the focused widget's focus ring just
+    /// fits in the viewport. A larger margin shows some of what is around
+    /// the widget, such as the label of a field in a form.
+    pub fn with_focus_margin(mut self, margin: f64) -> Self {
+        self.focus_margin = margin;
+        self
+    }
+
+    /// Builder-style method to set whether an offset that is lost because
+    /// the content shrank is restored when the content grows again.
+    ///
+    /// This is off by default. Turn it on when the content is replaced in
+    /// steps, such as a list that is cleared and then refilled as its items
+    /// are reloaded, so that the view returns to where it was. The offset
+    /// is forgotten as soon as the user scrolls.
+    pub fn with_preserved_offset(mut self, preserve: bool) -> Self {
+        self.preserve_offset = preserve;
+        self
+    }
+
     /// Returns a reference to the child widget.
     pub fn child(&self) -> &W {
         self.child.widget()
@@ -158,15 +226,43 @@ impl<T, W: Widget<T>> Scroll<T, W> {
     ///
     /// Returns `true` if the scroll has been updated.
     pub fn scroll(&mut self, delta: Vec2, size: Size) -> bool {
+        let remainder = self.scroll_with_remainder(delta, size);
+        (delta - remainder).hypot2() > 1e-12
+    }
+
+    /// Update the scroll, returning the part of `delta` that could not be
+    /// used because the content is already at its edge.
+    fn scroll_with_remainder(&mut self, delta: Vec2, size: Size) -> Vec2 {
         let mut offset = self.scroll_offset + delta;
         offset.x = offset.x.min(self.child_size.width - size.width).max(0.0);
         offset.y = offset.y.min(self.child_size.height - size.height).max(0.0);
-        if (offset - self.scroll_offset).hypot2() > 1e-12 {
+        let used = offset - self.scroll_offset;
+        if used.hypot2() > 1e-12 {
             self.scroll_offset = offset;
-            true
-        } else {
-            false
         }
+        if delta.hypot2() > 1e-12 {
+            self.wanted_offset = None;
+        }
+        delta - used
+    }
+
+    /// After the child's layout, move the offset by `shift`, as the child
+    /// asked, and keep it within the content.
+    fn clamp_offset(&mut self, shift: Vec2, size: Size) {
+        let wanted = self.wanted_offset.unwrap_or(self.scroll_offset) + shift;
+        let mut offset = wanted;
+        offset.x = offset.x.min(self.child_size.width - size.width).max(0.0);
+        offset.y = offset.y.min(self.child_size.height - size.height).max(0.0);
+        self.scroll_offset = offset;
+        self.wanted_offset = if self.preserve_offset && (wanted - offset).hypot2() > 1e-12 {
+            Some(wanted)
+        } else {
+            None
+        };
+    }
+
+    fn can_scroll(&self, size: Size) -> bool {
+        self.child_size.width > size.width || self.child_size.height > size.height
     }
 
     /// Makes the scrollbars visible, and resets the fade timer.
@@ -174,7 +270,7 @@ impl<T, W: Widget<T>> Scroll<T, W> {
         // Display scroll bars and schedule their disappearance
         self.scrollbars.opacity = env.get(theme::SCROLLBAR_MAX_OPACITY);
         let fade_delay = env.get(theme::SCROLLBAR_FADE_DELAY);
-        let deadline = Instant::now() + Duration::from_millis(fade_delay);
+        let deadline = ctx.now() + Duration::from_millis(fade_delay);
         self.scrollbars.timer_id = ctx.request_timer(deadline);
     }
 
@@ -183,9 +279,59 @@ impl<T, W: Widget<T>> Scroll<T, W> {
         self.scroll_offset
     }
 
+    /// Scroll as little as possible to show `rect`, in the child's
+    /// coordinates, with the focus margin around it. If it doesn't fit, its
+    /// top left corner is shown.
+    ///
+    /// Returns `true` if the scroll has been updated.
+    fn scroll_to_show(&mut self, rect: Rect, size: Size) -> bool {
+        let rect = rect.inflate(self.focus_margin, self.focus_margin);
+        let visible = self.visible_rect(size);
+        let delta_for = |start: f64, end: f64, vis_start: f64, vis_end: f64| {
+            if start < vis_start {
+                start - vis_start
+            } else if end > vis_end {
+                (end - vis_end).min(start - vis_start)
+            } else {
+                0.0
+            }
+        };
+        let delta = Vec2::new(
+            delta_for(rect.x0, rect.x1, visible.x0, visible.x1),
+            delta_for(rect.y0, rect.y1, visible.y0, visible.y1),
+        );
+        self.scroll(delta, size)
+    }
+
+    /// The part of the child that is visible, in the child's coordinates.
+    fn visible_rect(&self, size: Size) -> Rect {
+        size.to_rect().with_origin(self.scroll_offset.to_point())
+    }
+
+    /// Tell the child about the visible part, if it has changed since the
+    /// last time.
+    fn send_viewport(&mut self, ctx: &mut LifeCycleCtx, size: Size, data: &T, env: &Env)
+    where
+        T: Data,
+    {
+        let visible = self.visible_rect(size);
+        if !self.viewport_is(visible) {
+            self.viewport = Some(visible);
+            self.child
+                .lifecycle(ctx, &LifeCycle::ViewportChanged(visible), data, env);
+        }
+    }
+
+    fn viewport_is(&self, rect: Rect) -> bool {
+        match self.viewport {
+            Some(old) => old.origin() == rect.origin() && old.size() == rect.size(),
+            None => false,
+        }
+    }
+
     fn calc_vertical_bar_bounds(&self, viewport: Rect, env: &Env) -> Rect {
-        let bar_width = env.get(theme::SCROLLBAR_WIDTH);
-        let bar_pad = env.get(theme::SCROLLBAR_PAD);
+        let bar_width = theme::scaled(env, theme::SCROLLBAR_WIDTH);
+        let bar_pad = theme::scaled(env, theme::SCROLLBAR_PAD);
 
         let percent_visible = viewport.height() / self.child_size.height;
         let percent_scrolled = self.scroll_offset.y / (self.child_size.height - viewport.height());
@@ -209,8 +355,8 @@ impl<T, W: Widget<T>> Scroll<T, W> {
     }
 
     fn calc_horizontal_bar_bounds(&self, viewport: Rect, env: &Env) -> Rect {
-        let bar_width = env.get(theme::SCROLLBAR_WIDTH);
-        let bar_pad = env.get(theme::SCROLLBAR_PAD);
+        let bar_width = theme::scaled(env, theme::SCROLLBAR_WIDTH);
+        let bar_pad = theme::scaled(env, theme::SCROLLBAR_PAD);
 
         let percent_visible = viewport.width() / self.child_size.width;
         let percent_scrolled = self.scroll_offset.x / (self.child_size.width - viewport.width());
@@ -289,6 +435,14 @@ impl<T, W: Widget<T>> Scroll<T, W> {
             false
         }
     }
+
+    /// Our child's invalid region does not account for the scroll offset,
+    /// so if the child needs to be repainted, we repaint the whole viewport.
+    fn invalidate_if_child_invalid(&self, state: &mut BaseState) {
+        if self.child.state().needs_inval {
+            state.invalidate_all();
+        }
+    }
 }
 
 impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
@@ -377,7 +531,8 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
             let force_event = self.child.is_hot() || self.child.is_active();
             let child_event = event.transform_scroll(self.scroll_offset, viewport, force_event);
             if let Some(child_event) = child_event {
-                self.child.event(ctx, &child_event, data, env)
+                self.child.event(ctx, &child_event, data, env);
+                self.invalidate_if_child_invalid(ctx.base_state);
             };
 
             match event {
@@ -401,16 +556,31 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
 
         if !ctx.is_handled() {
             if let Event::Wheel(wheel) = event {
-                if self.scroll(wheel.delta, size) {
+                // a nested scroll may have used part of the delta already.
+                let delta = ctx.scroll_remainder.take().unwrap_or(wheel.delta);
+                let remainder = self.scroll_with_remainder(delta, size);
+                if (delta - remainder).hypot2() > 1e-12 {
                     ctx.request_paint();
-                    ctx.set_handled();
                     self.reset_scrollbar_fade(ctx, &env);
                 }
+                if !self.chaining && self.can_scroll(size) || remainder.hypot2() <= 1e-12 {
+                    ctx.set_handled();
+                } else {
+                    ctx.scroll_remainder = Some(remainder);
+                }
             }
         }
+
+        if self.viewport.is_some() {
+            self.send_viewport(&mut ctx.make_lifecycle_ctx(), size, data, env);
+        }
     }
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let Some(tree) = inspect::tree_cell(event) {
+            let viewport = ctx.size().to_rect();
+            tree.transform_children(Affine::translate(-self.scroll_offset), Some(viewport));
+        }
         // Guard by the timer id being invalid, otherwise the scroll bars would fade
         // immediately if some other widgeet started animating.
         if let LifeCycle::AnimFrame(interval) = event {
@@ -423,24 +593,51 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
                 }
             }
         }
-        self.child.lifecycle(ctx, event, data, env)
+        match event {
+            LifeCycle::RouteViewportChanged => {
+                let size = ctx.base_state.size();
+                self.send_viewport(ctx, size, data, env);
+            }
+            // our child's viewport is ours, not that of a scroll around us.
+            LifeCycle::ViewportChanged(_) => return,
+            _ => (),
+        }
+        self.child.lifecycle(ctx, event, data, env);
+        self.invalidate_if_child_invalid(ctx.base_state);
+
+        if let Some(rect) = ctx.base_state.scroll_to.take() {
+            let size = ctx.base_state.size();
+            if self.scroll_to_show(rect, size) {
+                ctx.request_paint();
+                self.send_viewport(ctx, size, data, env);
+            }
+            // a scroll around us should show the part that we show.
+            let shown = (rect - self.scroll_offset).intersect(size.to_rect());
+            ctx.base_state.scroll_to = Some(shown);
+        }
     }
 
     fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
         self.child.update(ctx, data, env);
+        self.invalidate_if_child_invalid(ctx.base_state);
     }
 
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
         bc.debug_check("Scroll");
 
         let child_bc = BoxConstraints::new(Size::ZERO, self.direction.max_size(bc));
+        let parent_shift = std::mem::replace(&mut ctx.scroll_shift, Vec2::ZERO);
         let size = self.child.layout(ctx, &child_bc, data, env);
+        let shift = std::mem::replace(&mut ctx.scroll_shift, parent_shift);
         log_size_warnings(size);
 
         self.child_size = size;
         self.child.set_layout_rect(size.to_rect());
         let self_size = bc.constrain(self.child_size);
-        let _ = self.scroll(Vec2::new(0.0, 0.0), self_size);
+        self.clamp_offset(shift, self_size);
+        if !self.viewport_is(self.visible_rect(self_size)) {
+            ctx.viewport_changed = true;
+        }
         self_size
     }
 
@@ -460,10 +657,10 @@ impl<T: Data, W: Widget<T>> Widget<T> for Scroll<T, W> {
 
 fn log_size_warnings(size: Size) {
     if size.width.is_infinite() {
-        log::warn!("Scroll widget's child has an infinite width.");
+        log::warn!(target: "druid::layout", "Scroll widget's child has an infinite width.");
     }
 
     if size.height.is_infinite() {
-        log::warn!("Scroll widget's child has an infinite height.");
+        log::warn!(target: "druid::layout", "Scroll widget's child has an infinite height.");
     }
 }
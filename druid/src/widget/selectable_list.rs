@@ -0,0 +1,371 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A list whose items can be selected.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::widget::{List, ListIter};
+use crate::{
+    theme, BoxConstraints, Data, Env, Event, EventCtx, HotKey, KeyCode, KeyEvent, LayoutCtx,
+    LifeCycle, LifeCycleCtx, PaintCtx, RenderContext, SysMods, UpdateCtx, Widget,
+};
+
+/// The selected items of a [`SelectableList`], by index.
+///
+/// Besides the selected indices, this remembers the item that a
+/// Shift-click or Shift-arrow selects a range from: the last one that was
+/// selected on its own or toggled.
+///
+/// [`SelectableList`]: struct.SelectableList.html
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Selection {
+    selected: Arc<BTreeSet<usize>>,
+    anchor: Option<usize>,
+}
+
+impl Selection {
+    /// An empty selection.
+    pub fn new() -> Self {
+        Selection::default()
+    }
+
+    /// Returns `true` if the item at `index` is selected.
+    pub fn contains(&self, index: usize) -> bool {
+        self.selected.contains(&index)
+    }
+
+    /// The selected indices, in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.selected.iter().copied()
+    }
+
+    /// The number of selected items.
+    pub fn len(&self) -> usize {
+        self.selected.len()
+    }
+
+    /// Returns `true` if nothing is selected.
+    pub fn is_empty(&self) -> bool {
+        self.selected.is_empty()
+    }
+
+    /// Select only the item at `index`.
+    pub fn select(&mut self, index: usize) {
+        self.selected = Arc::new(std::iter::once(index).collect());
+        self.anchor = Some(index);
+    }
+
+    /// Select the item at `index` if it isn't selected, and deselect it if
+    /// it is, leaving the other items alone.
+    pub fn toggle(&mut self, index: usize) {
+        let selected = Arc::make_mut(&mut self.selected);
+        if !selected.remove(&index) {
+            selected.insert(index);
+        }
+        self.anchor = Some(index);
+    }
+
+    /// Select every item from the last one selected with [`select`] or
+    /// [`toggle`] to `index`, and nothing else.
+    ///
+    /// [`select`]: #method.select
+    /// [`toggle`]: #method.toggle
+    pub fn extend_to(&mut self, index: usize) {
+        let anchor = self.anchor.unwrap_or(index);
+        let range = anchor.min(index)..=anchor.max(index);
+        self.selected = Arc::new(range.collect());
+        self.anchor = Some(anchor);
+    }
+
+    /// Deselect everything.
+    pub fn clear(&mut self) {
+        *self = Selection::default();
+    }
+
+    /// Keep the selection on the same items when the item at `from` moves
+    /// to `to`, shifting the ones in between, as [`ListIter::move_item`]
+    /// does.
+    ///
+    /// [`ListIter::move_item`]: trait.ListIter.html#method.move_item
+    pub fn move_item(&mut self, from: usize, to: usize) {
+        let moved = |index: usize| {
+            if index == from {
+                to
+            } else if from < index && index <= to {
+                index - 1
+            } else if to <= index && index < from {
+                index + 1
+            } else {
+                index
+            }
+        };
+        if !self.selected.is_empty() {
+            self.selected = Arc::new(self.selected.iter().map(|i| moved(*i)).collect());
+        }
+        self.anchor = self.anchor.map(moved);
+    }
+}
+
+impl Data for Selection {
+    fn same(&self, other: &Self) -> bool {
+        self.anchor == other.anchor
+            && (Arc::ptr_eq(&self.selected, &other.selected) || self.selected == other.selected)
+    }
+}
+
+/// A [`List`] whose items the user can select.
+///
+/// The data is a tuple of the items and the [`Selection`]. Clicking an
+/// item selects it alone; Cmd-clicking (Ctrl-clicking outside of macOS)
+/// toggles it, and Shift-clicking selects the range from the last item
+/// clicked. Clicks that a row handles itself, such as on a button in
+/// the row, don't change the selection.
+///
+/// The list takes keyboard focus when clicked, and the up and down arrow
+/// keys then move a cursor through the items: on their own they select the
+/// item under the cursor, with Shift they extend the selection to it, and
+/// with Cmd they leave the selection alone, so that Space can toggle the
+/// item under the cursor.
+///
+/// Selected rows are painted with [`theme::SELECTION_COLOR`] behind them,
+/// and while the list has focus, the row under the cursor is outlined with
+/// [`theme::PRIMARY_LIGHT`].
+///
+/// The selection is by index, so changes to the items can leave it
+/// pointing at different items; an app that removes items should update
+/// the selection as well. Indices past the end of the items are ignored.
+/// Items the user drags to new places in a [`reorderable`] list take their
+/// selection with them.
+///
+/// # Examples
+///
+/// ```
+/// use std::sync::Arc;
+/// use druid::widget::{Label, SelectableList, Selection};
+///
+/// let presets = SelectableList::new(|| Label::dynamic(|name: &String, _| name.clone()))
+///     .on_selection_changed(|_, selection, _| println!("{} selected", selection.len()));
+/// # let _: &dyn druid::Widget<(Arc<Vec<String>>, Selection)> = &presets;
+/// ```
+///
+/// [`List`]: struct.List.html
+/// [`Selection`]: struct.Selection.html
+/// [`reorderable`]: #method.reorderable
+/// [`theme::SELECTION_COLOR`]: ../theme/constant.SELECTION_COLOR.html
+/// [`theme::PRIMARY_LIGHT`]: ../theme/constant.PRIMARY_LIGHT.html
+pub struct SelectableList<T> {
+    list: List<T>,
+    /// The item the arrow keys move from.
+    cursor: Option<usize>,
+    on_change: Option<SelectionCallback>,
+}
+
+type SelectionCallback = Box<dyn Fn(&mut EventCtx, &Selection, &Env)>;
+
+impl<T: Data> SelectableList<T> {
+    /// Create a new list, calling `closure` to create the widget for each
+    /// item.
+    pub fn new<W: Widget<T> + 'static>(closure: impl Fn() -> W + 'static) -> Self {
+        SelectableList {
+            list: List::new(closure),
+            cursor: None,
+            on_change: None,
+        }
+    }
+
+    /// Builder-style method to call `f` whenever the user changes the
+    /// selection.
+    pub fn on_selection_changed(
+        mut self,
+        f: impl Fn(&mut EventCtx, &Selection, &Env) + 'static,
+    ) -> Self {
+        self.on_change = Some(Box::new(f));
+        self
+    }
+
+    /// Builder-style method to let the user reorder the items by dragging
+    /// them, as with [`List::reorderable`].
+    ///
+    /// [`List::reorderable`]: struct.List.html#method.reorderable
+    pub fn reorderable(mut self) -> Self {
+        self.list = self.list.reorderable();
+        self
+    }
+
+    /// The index of the row at `pos`, if there is one.
+    fn row_at(&self, pos: Point) -> Option<usize> {
+        self.list.child_rects().position(|rect| rect.contains(pos))
+    }
+
+    /// React to a key press, returning `true` if it was used.
+    fn key_down(&mut self, key: &KeyEvent, len: usize, selection: &mut Selection) -> bool {
+        if len == 0 {
+            return false;
+        }
+        if HotKey::new(None, KeyCode::Space).matches(key) {
+            if let Some(cursor) = self.cursor {
+                selection.toggle(cursor);
+            }
+            return self.cursor.is_some();
+        }
+        let up = match key.key_code {
+            KeyCode::ArrowUp => true,
+            KeyCode::ArrowDown => false,
+            _ => return false,
+        };
+        let index = match self.cursor {
+            None if up => len - 1,
+            None => 0,
+            Some(i) if up => i.saturating_sub(1),
+            Some(i) => (i + 1).min(len - 1),
+        };
+        if HotKey::new(None, key.key_code).matches(key) {
+            selection.select(index);
+        } else if HotKey::new(SysMods::Shift, key.key_code).matches(key) {
+            selection.extend_to(index);
+        } else if !HotKey::new(SysMods::Cmd, key.key_code).matches(key) {
+            return false;
+        }
+        self.cursor = Some(index);
+        true
+    }
+}
+
+impl<T: Data> Widget<(Arc<Vec<T>>, Selection)> for SelectableList<T> {
+    fn event(
+        &mut self,
+        ctx: &mut EventCtx,
+        event: &Event,
+        data: &mut (Arc<Vec<T>>, Selection),
+        env: &Env,
+    ) {
+        self.list.event(ctx, event, data, env);
+        if ctx.is_handled() {
+            return;
+        }
+
+        let old = data.1.clone();
+        match event {
+            Event::MouseDown(mouse) => {
+                if let Some(index) = self.row_at(mouse.pos) {
+                    if mouse.mods == SysMods::Cmd {
+                        data.1.toggle(index);
+                    } else if mouse.mods == SysMods::Shift {
+                        data.1.extend_to(index);
+                    } else {
+                        data.1.select(index);
+                    }
+                    self.cursor = Some(index);
+                    ctx.request_focus();
+                    ctx.request_paint();
+                }
+            }
+            Event::KeyDown(key)
+                if ctx.is_focused() && self.key_down(key, data.0.len(), &mut data.1) =>
+            {
+                ctx.request_paint();
+                ctx.set_handled();
+            }
+            _ => (),
+        }
+        if !old.same(&data.1) {
+            if let Some(on_change) = &self.on_change {
+                on_change(ctx, &data.1, env);
+            }
+        }
+    }
+
+    fn lifecycle(
+        &mut self,
+        ctx: &mut LifeCycleCtx,
+        event: &LifeCycle,
+        data: &(Arc<Vec<T>>, Selection),
+        env: &Env,
+    ) {
+        match event {
+            LifeCycle::WidgetAdded => ctx.register_for_focus(),
+            LifeCycle::FocusChanged(_) => ctx.request_paint(),
+            _ => (),
+        }
+        self.list.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(
+        &mut self,
+        ctx: &mut UpdateCtx,
+        old_data: &(Arc<Vec<T>>, Selection),
+        data: &(Arc<Vec<T>>, Selection),
+        env: &Env,
+    ) {
+        if !old_data.1.same(&data.1) {
+            ctx.request_paint();
+        }
+        self.cursor = self.cursor.filter(|cursor| *cursor < data.0.len());
+        self.list.update(ctx, old_data, data, env)
+    }
+
+    fn layout(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        bc: &BoxConstraints,
+        data: &(Arc<Vec<T>>, Selection),
+        env: &Env,
+    ) -> Size {
+        self.list.layout(ctx, bc, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &(Arc<Vec<T>>, Selection), env: &Env) {
+        let width = ctx.size().width;
+        let row = |rect: Rect| Rect::new(0.0, rect.y0, width, rect.y1);
+        let selection_color = env.get(theme::SELECTION_COLOR);
+        for (i, rect) in self.list.child_rects().enumerate() {
+            if data.1.contains(i) {
+                ctx.fill(row(rect), &selection_color);
+            }
+        }
+
+        self.list.paint(ctx, data, env);
+
+        if ctx.is_focused() {
+            let cursor = self.cursor.and_then(|i| self.list.child_rects().nth(i));
+            if let Some(rect) = cursor {
+                let line_width = env.get(theme::FOCUS_RING_WIDTH);
+                let outline = row(rect).inset(-line_width / 2.0);
+                ctx.stroke(outline, &env.get(theme::PRIMARY_LIGHT), line_width);
+            }
+        }
+    }
+}
+
+impl<T: Data> ListIter<T> for (Arc<Vec<T>>, Selection) {
+    fn for_each(&self, cb: impl FnMut(&T, usize)) {
+        self.0.for_each(cb)
+    }
+
+    fn for_each_mut(&mut self, cb: impl FnMut(&mut T, usize)) {
+        self.0.for_each_mut(cb)
+    }
+
+    fn data_len(&self) -> usize {
+        self.0.data_len()
+    }
+
+    fn move_item(&mut self, from: usize, to: usize) {
+        self.0.move_item(from, to);
+        self.1.move_item(from, to);
+    }
+}
@@ -123,6 +123,16 @@ impl<T> SizedBox<T> {
         )
     }
 
+    /// The intrinsic size on an axis with a set `extent`; an expanding box
+    /// has no size of its own.
+    fn fixed_intrinsic(extent: f64) -> f64 {
+        if extent.is_finite() {
+            extent
+        } else {
+            0.
+        }
+    }
+
     #[cfg(test)]
     pub(crate) fn width_and_height(&self) -> (Option<f64>, Option<f64>) {
         (self.width, self.height)
@@ -158,16 +168,76 @@ impl<T: Data> Widget<T> for SizedBox<T> {
         };
 
         if size.width.is_infinite() {
-            log::warn!("SizedBox is returning an infinite width.");
+            log::warn!(target: "druid::layout", "SizedBox is returning an infinite width.");
         }
 
         if size.height.is_infinite() {
-            log::warn!("SizedBox is returning an infinite height.");
+            log::warn!(target: "druid::layout", "SizedBox is returning an infinite height.");
         }
 
         size
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let height = self.height.unwrap_or(height);
+        match (self.width, self.inner.as_mut()) {
+            (Some(width), _) => Self::fixed_intrinsic(width),
+            (None, Some(inner)) => inner.min_intrinsic_width(ctx, height, data, env),
+            (None, None) => 0.,
+        }
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let height = self.height.unwrap_or(height);
+        match (self.width, self.inner.as_mut()) {
+            (Some(width), _) => Self::fixed_intrinsic(width),
+            (None, Some(inner)) => inner.max_intrinsic_width(ctx, height, data, env),
+            (None, None) => 0.,
+        }
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let width = self.width.unwrap_or(width);
+        match (self.height, self.inner.as_mut()) {
+            (Some(height), _) => Self::fixed_intrinsic(height),
+            (None, Some(inner)) => inner.min_intrinsic_height(ctx, width, data, env),
+            (None, None) => 0.,
+        }
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let width = self.width.unwrap_or(width);
+        match (self.height, self.inner.as_mut()) {
+            (Some(height), _) => Self::fixed_intrinsic(height),
+            (None, Some(inner)) => inner.max_intrinsic_height(ctx, width, data, env),
+            (None, None) => 0.,
+        }
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         if let Some(ref mut inner) = self.inner {
             inner.paint(ctx, data, env);
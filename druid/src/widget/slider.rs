@@ -18,7 +18,7 @@ use crate::kurbo::{Circle, Point, Rect, RoundedRect, Shape, Size};
 use crate::theme;
 use crate::{
     BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, LinearGradient,
-    PaintCtx, RenderContext, UnitPoint, UpdateCtx, Widget,
+    MouseGesture, PaintCtx, RenderContext, UnitPoint, UpdateCtx, Widget,
 };
 
 /// A slider, allowing interactive update of a numeric value.
@@ -76,7 +76,7 @@ impl Slider {
 
 impl Widget<f64> for Slider {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut f64, env: &Env) {
-        let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+        let knob_size = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT);
         let slider_width = ctx.size().width;
 
         match event {
@@ -93,12 +93,16 @@ impl Widget<f64> for Slider {
             Event::MouseUp(mouse) => {
                 if ctx.is_active() {
                     ctx.set_active(false);
-                    *data = self.calculate_value(mouse.pos.x, knob_size, slider_width);
+                    if mouse.gesture == MouseGesture::Drag {
+                        *data = self.calculate_value(mouse.pos.x, knob_size, slider_width);
+                    }
                     ctx.request_paint();
                 }
             }
             Event::MouseMoved(mouse) => {
-                if ctx.is_active() {
+                // small movements while pressed don't move the knob, until
+                // they add up to a drag.
+                if ctx.is_active() && mouse.gesture == MouseGesture::Drag {
                     *data = self.calculate_value(mouse.pos.x, knob_size, slider_width);
                     ctx.request_paint();
                 }
@@ -128,15 +132,15 @@ impl Widget<f64> for Slider {
         env: &Env,
     ) -> Size {
         bc.debug_check("Slider");
-        let height = env.get(theme::BASIC_WIDGET_HEIGHT);
-        let width = env.get(theme::WIDE_WIDGET_WIDTH);
+        let height = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT);
+        let width = theme::scaled(env, theme::WIDE_WIDGET_WIDTH);
         bc.constrain((width, height))
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &f64, env: &Env) {
         let clamped = self.normalize(*data);
         let rect = Rect::from_origin_size(Point::ORIGIN, ctx.size());
-        let knob_size = env.get(theme::BASIC_WIDGET_HEIGHT);
+        let knob_size = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT);
         let track_thickness = 4.;
 
         //Paint the background
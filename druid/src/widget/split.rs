@@ -292,7 +292,10 @@ impl<T: Data> Widget<T> for Split<T> {
         let (child1_bc, child2_bc) = match self.split_direction {
             Axis::Vertical => {
                 if !bc.is_width_bounded() {
-                    log::warn!("A Split widget was given an unbounded width to split.")
+                    log::warn!(
+                        target: "druid::layout",
+                        "A Split widget was given an unbounded width to split."
+                    )
                 }
                 let child1_width = (reduced_width * self.split_point).max(0.0);
                 let child2_width = (reduced_width - child1_width).max(0.0);
@@ -309,7 +312,10 @@ impl<T: Data> Widget<T> for Split<T> {
             }
             Axis::Horizontal => {
                 if !bc.is_width_bounded() {
-                    log::warn!("A Split widget was given an unbounded height to split.")
+                    log::warn!(
+                        target: "druid::layout",
+                        "A Split widget was given an unbounded height to split."
+                    )
                 }
                 let child1_height = (reduced_height * self.split_point).max(0.0);
                 let child2_height = (reduced_height - child1_height).max(0.0);
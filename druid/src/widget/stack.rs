@@ -0,0 +1,284 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A container that layers its children on top of each other.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::piet::UnitPoint;
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// A container that layers its children in the same space.
+///
+/// Children are painted in the order they were added, so each one is drawn
+/// over the ones before it. The mouse goes to the topmost child under it:
+/// once a child is hot, the children below it do not see pointer events.
+///
+/// A child is aligned, positioned, or an overlay. Aligned children are laid
+/// out with the stack's constraints, loosened (see
+/// [`with_loose_constraints`]), and the stack is as large as the largest of
+/// them; each is then placed by its [`UnitPoint`] within the stack.
+/// Positioned children are placed at a fixed offset from the stack's origin,
+/// given at most the space from there to the stack's far edges, and do not
+/// affect the stack's size.
+///
+/// Overlays are for decorations such as badges. They are laid out without
+/// constraints, aligned by their [`UnitPoint`] even if that takes them past
+/// the stack's edges, and do not affect its size. They also don't take the
+/// mouse from the children under them.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Label, Stack};
+/// use druid::{Color, UnitPoint, WidgetExt};
+///
+/// let countdown = Label::new("04:59").padding(20.0);
+/// let badge = Label::new("paused")
+///     .padding(2.0)
+///     .background(Color::rgb8(0xa0, 0x40, 0x40))
+///     .rounded(4.0);
+/// let timer: Stack<()> = Stack::new()
+///     .with_child(countdown)
+///     .with_aligned_child(badge, UnitPoint::TOP_RIGHT);
+/// ```
+///
+/// [`UnitPoint`]: ../struct.UnitPoint.html
+/// [`with_loose_constraints`]: #method.with_loose_constraints
+pub struct Stack<T> {
+    children: Vec<ChildWidget<T>>,
+    loose: bool,
+}
+
+struct ChildWidget<T> {
+    widget: WidgetPod<T, Box<dyn Widget<T>>>,
+    placement: Placement,
+}
+
+#[derive(Clone, Copy)]
+enum Placement {
+    Aligned(UnitPoint),
+    Positioned(Point),
+    Overlay(UnitPoint),
+}
+
+impl<T: Data> Stack<T> {
+    /// Create a new, empty stack.
+    pub fn new() -> Self {
+        Stack {
+            children: Vec::new(),
+            loose: true,
+        }
+    }
+
+    /// Builder-style method to set whether aligned children are laid out
+    /// with the stack's constraints loosened, which is the default, or
+    /// exactly as the stack was given them.
+    ///
+    /// Turning this off lets a stack wrap a widget without changing how it
+    /// is laid out, for instance to add an overlay.
+    pub fn with_loose_constraints(mut self, loose: bool) -> Self {
+        self.loose = loose;
+        self
+    }
+
+    /// Builder-style variant of [`add_child`].
+    ///
+    /// [`add_child`]: #method.add_child
+    pub fn with_child(mut self, child: impl Widget<T> + 'static) -> Self {
+        self.add_child(child);
+        self
+    }
+
+    /// Builder-style variant of [`add_aligned_child`].
+    ///
+    /// [`add_aligned_child`]: #method.add_aligned_child
+    pub fn with_aligned_child(mut self, child: impl Widget<T> + 'static, align: UnitPoint) -> Self {
+        self.add_aligned_child(child, align);
+        self
+    }
+
+    /// Builder-style variant of [`add_overlay`].
+    ///
+    /// [`add_overlay`]: #method.add_overlay
+    pub fn with_overlay(mut self, child: impl Widget<T> + 'static, align: UnitPoint) -> Self {
+        self.add_overlay(child, align);
+        self
+    }
+
+    /// Builder-style variant of [`add_positioned_child`].
+    ///
+    /// [`add_positioned_child`]: #method.add_positioned_child
+    pub fn with_positioned_child(mut self, child: impl Widget<T> + 'static, origin: Point) -> Self {
+        self.add_positioned_child(child, origin);
+        self
+    }
+
+    /// Add a child on top of the others, aligned to the top left.
+    pub fn add_child(&mut self, child: impl Widget<T> + 'static) {
+        self.add_aligned_child(child, UnitPoint::TOP_LEFT);
+    }
+
+    /// Add a child on top of the others, placed in the stack by `align`.
+    pub fn add_aligned_child(&mut self, child: impl Widget<T> + 'static, align: UnitPoint) {
+        self.push(child, Placement::Aligned(align));
+    }
+
+    /// Add a child on top of the others, with its origin at `origin` in the
+    /// stack's coordinates.
+    pub fn add_positioned_child(&mut self, child: impl Widget<T> + 'static, origin: Point) {
+        self.push(child, Placement::Positioned(origin));
+    }
+
+    /// Add an overlay on top of the others, placed by `align`.
+    pub fn add_overlay(&mut self, child: impl Widget<T> + 'static, align: UnitPoint) {
+        self.push(child, Placement::Overlay(align));
+    }
+
+    fn push(&mut self, child: impl Widget<T> + 'static, placement: Placement) {
+        self.children.push(ChildWidget {
+            widget: WidgetPod::new(child).boxed(),
+            placement,
+        });
+    }
+}
+
+impl<T: Data> Default for Stack<T> {
+    fn default() -> Self {
+        Stack::new()
+    }
+}
+
+impl<T: Data> Widget<T> for Stack<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // from the top down, so the child painted last gets the pointer.
+        let mut covered = false;
+        for child in self.children.iter_mut().rev() {
+            // a child holding the mouse gets it wherever it is.
+            if covered && event.is_pointer_event() && !child.widget.has_active() {
+                continue;
+            }
+            child.widget.event(ctx, event, data, env);
+            if let Placement::Overlay(_) = child.placement {
+                continue;
+            }
+            covered |= child.widget.is_hot();
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.lifecycle(ctx, event, data, env);
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Stack");
+
+        let child_bc = if self.loose { bc.loosen() } else { *bc };
+        let mut size = Size::ZERO;
+        let mut sizes = Vec::with_capacity(self.children.len());
+        for child in &mut self.children {
+            let child_size = match child.placement {
+                Placement::Aligned(_) => child.widget.layout(ctx, &child_bc, data, env),
+                Placement::Positioned(_) | Placement::Overlay(_) => Size::ZERO,
+            };
+            size.width = size.width.max(child_size.width);
+            size.height = size.height.max(child_size.height);
+            sizes.push(child_size);
+        }
+        let size = bc.constrain(size);
+
+        let mut paint_rect = size.to_rect();
+        for (child, child_size) in self.children.iter_mut().zip(sizes) {
+            let rect = match child.placement {
+                Placement::Aligned(align) => {
+                    let extra_width = (size.width - child_size.width).max(0.);
+                    let extra_height = (size.height - child_size.height).max(0.);
+                    let origin = align.resolve(Rect::new(0., 0., extra_width, extra_height));
+                    Rect::from_origin_size(origin, child_size)
+                }
+                Placement::Overlay(align) => {
+                    let child_bc =
+                        BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, f64::INFINITY));
+                    let child_size = child.widget.layout(ctx, &child_bc, data, env);
+                    let extra_width = size.width - child_size.width;
+                    let extra_height = size.height - child_size.height;
+                    let origin = align.resolve(Rect::new(0., 0., extra_width, extra_height));
+                    Rect::from_origin_size(origin, child_size)
+                }
+                Placement::Positioned(origin) => {
+                    let room = Size::new(
+                        (size.width - origin.x).max(0.),
+                        (size.height - origin.y).max(0.),
+                    );
+                    let child_bc = BoxConstraints::new(Size::ZERO, room);
+                    let child_size = child.widget.layout(ctx, &child_bc, data, env);
+                    Rect::from_origin_size(origin, child_size)
+                }
+            };
+            child.widget.set_layout_rect(rect);
+            paint_rect = paint_rect.union(child.widget.paint_rect());
+        }
+
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+        size
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let mut width = 0.0_f64;
+        for child in &mut self.children {
+            if let Placement::Aligned(_) = child.placement {
+                width = width.max(child.widget.max_intrinsic_width(ctx, height, data, env));
+            }
+        }
+        width
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let mut height = 0.0_f64;
+        for child in &mut self.children {
+            if let Placement::Aligned(_) = child.placement {
+                height = height.max(child.widget.max_intrinsic_height(ctx, width, data, env));
+            }
+        }
+        height
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        for child in &mut self.children {
+            child.widget.paint_with_offset(ctx, data, env);
+        }
+    }
+}
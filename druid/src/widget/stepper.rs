@@ -15,11 +15,11 @@
 //! A stepper widget.
 
 use crate::{
-    BoxConstraints, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx, Size,
-    TimerToken, UpdateCtx, Widget,
+    BoxConstraints, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, Size, TimerToken, UpdateCtx, Widget,
 };
 use std::f64::EPSILON;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 use crate::kurbo::{BezPath, Rect, RoundedRect};
 use crate::piet::{LinearGradient, RenderContext, UnitPoint};
@@ -33,6 +33,10 @@ const STEPPER_REPEAT_DELAY: Duration = Duration::from_millis(500);
 const STEPPER_REPEAT: Duration = Duration::from_millis(200);
 
 /// A stepper widget for step-wise increasing and decreasing a value.
+///
+/// The value can also be changed with the arrow keys while the stepper has
+/// keyboard focus; holding a key down steps repeatedly, at the rate the
+/// platform repeats keys.
 pub struct Stepper {
     max: f64,
     min: f64,
@@ -123,7 +127,7 @@ impl Widget<f64> for Stepper {
         let rounded_rect = RoundedRect::from_origin_size(Point::ORIGIN, ctx.size().to_vec2(), 4.);
 
         let height = ctx.size().height;
-        let width = env.get(theme::BASIC_WIDGET_HEIGHT);
+        let width = theme::scaled(env, theme::BASIC_WIDGET_HEIGHT);
         let button_size = Size::new(width, height / 2.);
 
         ctx.stroke(rounded_rect, &env.get(theme::BORDER_DARK), 2.0);
@@ -185,13 +189,13 @@ impl Widget<f64> for Stepper {
         env: &Env,
     ) -> Size {
         bc.constrain(Size::new(
-            env.get(theme::BASIC_WIDGET_HEIGHT),
-            env.get(theme::BORDERED_WIDGET_HEIGHT),
+            theme::scaled(env, theme::BASIC_WIDGET_HEIGHT),
+            theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT),
         ))
     }
 
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut f64, env: &Env) {
-        let height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
 
         match event {
             Event::MouseDown(mouse) => {
@@ -205,7 +209,7 @@ impl Widget<f64> for Stepper {
                     self.increment(data);
                 }
 
-                let delay = Instant::now() + STEPPER_REPEAT_DELAY;
+                let delay = ctx.now() + STEPPER_REPEAT_DELAY;
                 self.timer_id = ctx.request_timer(delay);
 
                 ctx.request_paint();
@@ -226,14 +230,33 @@ impl Widget<f64> for Stepper {
                 if self.decrease_active {
                     self.decrement(data);
                 }
-                let delay = Instant::now() + STEPPER_REPEAT;
+                let delay = ctx.now() + STEPPER_REPEAT;
                 self.timer_id = ctx.request_timer(delay);
             }
+            Event::KeyDown(key)
+                if HotKey::new(None, KeyCode::ArrowUp).matches(key)
+                    || HotKey::new(None, KeyCode::ArrowRight).matches(key) =>
+            {
+                self.increment(data);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key)
+                if HotKey::new(None, KeyCode::ArrowDown).matches(key)
+                    || HotKey::new(None, KeyCode::ArrowLeft).matches(key) =>
+            {
+                self.decrement(data);
+                ctx.set_handled();
+            }
+            Event::KeyDown(key) if ctx.handle_focus_key(key) => ctx.set_handled(),
             _ => (),
         }
     }
 
-    fn lifecycle(&mut self, _ctx: &mut LifeCycleCtx, _event: &LifeCycle, _data: &f64, _env: &Env) {}
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, _data: &f64, _env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            ctx.register_for_focus();
+        }
+    }
 
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &f64, data: &f64, _env: &Env) {
         if (*data - old_data).abs() > EPSILON {
@@ -0,0 +1,205 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A section whose header stays in view while it is scrolled.
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    UpdateCtx, Widget, WidgetPod,
+};
+
+/// A header and a body, where the header pins to the top of an enclosing
+/// [`Scroll`] while the body is scrolled past it.
+///
+/// The header is laid out above the body. Once the top of the viewport
+/// passes the top of the section, the header moves down with it, painted
+/// over the body, until it reaches the end of the section; from there it
+/// scrolls away with the rest of the section. A list of sections therefore
+/// shows the header of the topmost one, with each header pushing the last
+/// one out as it arrives.
+///
+/// Sections can be nested, in which case the headers stack: the body's
+/// viewport starts below the pinned header, so an inner section's header
+/// pins just beneath the outer one's.
+///
+/// The body shows through a header with no background of its own, so you
+/// will usually want to give it one.
+///
+/// # Examples
+///
+/// ```
+/// use druid::widget::{Flex, Label, Scroll, Sticky};
+/// use druid::{Color, WidgetExt};
+///
+/// let mut groups = Flex::column();
+/// for group in &["Warm up", "Work", "Cool down"] {
+///     let mut presets = Flex::column();
+///     for minutes in &[5, 10, 15] {
+///         presets.add_child(Label::new(format!("{} minutes", minutes)));
+///     }
+///     let header = Label::new(*group).background(Color::grey8(0x30));
+///     groups.add_child(Sticky::new(header, presets));
+/// }
+/// let list: Scroll<(), _> = Scroll::new(groups).vertical();
+/// ```
+///
+/// [`Scroll`]: struct.Scroll.html
+pub struct Sticky<T> {
+    header: WidgetPod<T, Box<dyn Widget<T>>>,
+    body: WidgetPod<T, Box<dyn Widget<T>>>,
+    header_size: Size,
+    size: Size,
+    // the last viewport we were told about, in our own coordinates.
+    viewport: Option<Rect>,
+    // how far the header is moved down from the top of the section.
+    pin: f64,
+}
+
+impl<T: Data> Sticky<T> {
+    /// Create a new sticky section, with `header` pinned above `body`.
+    pub fn new(header: impl Widget<T> + 'static, body: impl Widget<T> + 'static) -> Self {
+        Sticky {
+            header: WidgetPod::new(header).boxed(),
+            body: WidgetPod::new(body).boxed(),
+            header_size: Size::ZERO,
+            size: Size::ZERO,
+            viewport: None,
+            pin: 0.0,
+        }
+    }
+
+    /// Move the header to where the viewport puts it, returning `true` if
+    /// it moved.
+    fn place_header(&mut self) -> bool {
+        let limit = (self.size.height - self.header_size.height).max(0.0);
+        let pin = match self.viewport {
+            Some(visible) => visible.y0.max(0.0).min(limit),
+            None => 0.0,
+        };
+        self.header.set_layout_rect(Rect::from_origin_size(
+            Point::new(0.0, pin),
+            self.header_size,
+        ));
+        let moved = pin != self.pin;
+        self.pin = pin;
+        moved
+    }
+}
+
+impl<T: Data> Widget<T> for Sticky<T> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        // the header is on top, so it gets the pointer first.
+        self.header.event(ctx, event, data, env);
+        if !(event.is_pointer_event() && self.header.is_hot()) {
+            self.body.event(ctx, event, data, env);
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        match event {
+            LifeCycle::ViewportChanged(visible) => {
+                self.viewport = Some(*visible);
+                if self.place_header() {
+                    ctx.request_paint();
+                }
+                self.header.lifecycle(ctx, event, data, env);
+                // whatever is under the header can't be seen.
+                let covered = self.pin + self.header_size.height;
+                let body_visible = Rect::new(
+                    visible.x0,
+                    visible.y0.max(covered).min(visible.y1),
+                    visible.x1,
+                    visible.y1,
+                );
+                let body_event = LifeCycle::ViewportChanged(body_visible);
+                self.body.lifecycle(ctx, &body_event, data, env);
+            }
+            // the inspector expects children in the order they are painted.
+            LifeCycle::RouteInspectTree(_) => {
+                self.body.lifecycle(ctx, event, data, env);
+                self.header.lifecycle(ctx, event, data, env);
+            }
+            _ => {
+                self.header.lifecycle(ctx, event, data, env);
+                self.body.lifecycle(ctx, event, data, env);
+            }
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.header.update(ctx, data, env);
+        self.body.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Sticky");
+
+        let header_bc = BoxConstraints::new(Size::new(bc.min().width, 0.0), bc.max());
+        let header_size = self.header.layout(ctx, &header_bc, data, env);
+
+        let min_height = (bc.min().height - header_size.height).max(0.0);
+        let max_height = (bc.max().height - header_size.height).max(0.0);
+        let body_bc = BoxConstraints::new(
+            Size::new(bc.min().width, min_height),
+            Size::new(bc.max().width, max_height),
+        );
+        let body_size = self.body.layout(ctx, &body_bc, data, env);
+        let body_origin = Point::new(0.0, header_size.height);
+        self.body
+            .set_layout_rect(Rect::from_origin_size(body_origin, body_size));
+
+        let size = bc.constrain(Size::new(
+            header_size.width.max(body_size.width),
+            header_size.height + body_size.height,
+        ));
+        self.header_size = header_size;
+        self.size = size;
+        self.place_header();
+
+        // the header may be pinned anywhere in the section before we are
+        // laid out again.
+        let header_paint = size.to_rect() + self.header.paint_insets();
+        let paint_rect = self.body.paint_rect().union(header_paint);
+        ctx.set_paint_insets(paint_rect - size.to_rect());
+        size
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let header = self.header.max_intrinsic_width(ctx, height, data, env);
+        header.max(self.body.max_intrinsic_width(ctx, height, data, env))
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let header = self.header.max_intrinsic_height(ctx, width, data, env);
+        header + self.body.max_intrinsic_height(ctx, width, data, env)
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        self.body.paint_with_offset(ctx, data, env);
+        self.header.paint_with_offset(ctx, data, env);
+    }
+}
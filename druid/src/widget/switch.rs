@@ -50,7 +50,7 @@ impl Switch {
     fn paint_labels(&mut self, ctx: &mut PaintCtx, env: &Env, switch_width: f64) {
         let font_name = env.get(theme::FONT_NAME);
         let font_size = env.get(theme::TEXT_SIZE_NORMAL);
-        let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let switch_height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
         let knob_size = switch_height - 2. * SWITCH_PADDING;
 
         let font = ctx
@@ -107,7 +107,7 @@ impl Switch {
 
 impl Widget<bool> for Switch {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut bool, env: &Env) {
-        let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let switch_height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
         let switch_width = switch_height * SWITCH_WIDTH_RATIO;
         let knob_size = switch_height - 2. * SWITCH_PADDING;
         let on_pos = switch_width - knob_size / 2. - SWITCH_PADDING;
@@ -150,7 +150,7 @@ impl Widget<bool> for Switch {
 
     fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &bool, env: &Env) {
         if let LifeCycle::AnimFrame(_) = event {
-            let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+            let switch_height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
             let switch_width = switch_height * SWITCH_WIDTH_RATIO;
             let knob_size = switch_height - 2. * SWITCH_PADDING;
             let on_pos = switch_width - knob_size / 2. - SWITCH_PADDING;
@@ -183,12 +183,15 @@ impl Widget<bool> for Switch {
         _data: &bool,
         env: &Env,
     ) -> Size {
-        let width = env.get(theme::BORDERED_WIDGET_HEIGHT) * SWITCH_WIDTH_RATIO;
-        bc.constrain(Size::new(width, env.get(theme::BORDERED_WIDGET_HEIGHT)))
+        let width = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT) * SWITCH_WIDTH_RATIO;
+        bc.constrain(Size::new(
+            width,
+            theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT),
+        ))
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &bool, env: &Env) {
-        let switch_height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let switch_height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
         let switch_width = switch_height * SWITCH_WIDTH_RATIO;
         let knob_size = switch_height - 2. * SWITCH_PADDING;
         let on_pos = switch_width - knob_size / 2. - SWITCH_PADDING;
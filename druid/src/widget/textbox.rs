@@ -14,14 +14,19 @@
 
 //! A textbox widget.
 
-use std::time::{Duration, Instant};
+use std::borrow::Cow;
+use std::fmt;
+use std::rc::Rc;
+use std::time::Duration;
+
+use unicode_segmentation::UnicodeSegmentation;
 
 use crate::{
-    Application, BoxConstraints, Cursor, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx,
-    LifeCycle, LifeCycleCtx, PaintCtx, Selector, SysMods, TimerToken, UpdateCtx, Widget,
+    BoxConstraints, Cursor, Env, Event, EventCtx, HotKey, KeyCode, KeyEvent, LayoutCtx, LifeCycle,
+    LifeCycleCtx, PaintCtx, Selector, TimerToken, UpdateCtx, Widget,
 };
 
-use crate::kurbo::{Affine, Line, Point, RoundedRect, Size, Vec2};
+use crate::kurbo::{Affine, BezPath, Line, Point, Rect, RoundedRect, Size, Vec2};
 use crate::piet::{
     FontBuilder, PietText, PietTextLayout, RenderContext, Text, TextLayout, TextLayoutBuilder,
 };
@@ -29,41 +34,315 @@ use crate::theme;
 
 use crate::text::{
     movement, offset_for_delete_backwards, BasicTextInput, EditAction, EditableText, MouseAction,
-    Movement, Selection, TextInput,
+    Movement, Selection, TextDirection, TextInput,
 };
 
 const BORDER_WIDTH: f64 = 1.;
 const PADDING_TOP: f64 = 5.;
 const PADDING_LEFT: f64 = 4.;
+const CLEAR_BUTTON_WIDTH: f64 = 20.;
+const CLEAR_CROSS_SIZE: f64 = 6.;
+const MASK_CHAR: char = '\u{2022}';
 
 // we send ourselves this when we want to reset blink, which must be done in event.
 const RESET_BLINK: Selector = Selector::new("druid-builtin.reset-textbox-blink");
+// and this when we lose focus, as committing an edit also needs the data.
+const FOCUS_LOST: Selector = Selector::new("druid-builtin.textbox-focus-lost");
+
+/// When a [`TextBox`] writes what the user typed to its data.
+///
+/// In the `Blur` and `Submit` modes, an edit that hasn't been written yet is
+/// discarded if the data is changed from elsewhere in the meantime.
+///
+/// [`TextBox`]: struct.TextBox.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateMode {
+    /// On every edit. This is the default.
+    Edit,
+    /// When the text box loses focus, or Enter is pressed.
+    Blur,
+    /// Only when Enter is pressed. Edits that haven't been submitted are
+    /// discarded when the text box loses focus.
+    Submit,
+}
+
+type SubmitFn = dyn Fn(&mut EventCtx, &mut String, &Env);
+type FilterFn = dyn Fn(char) -> bool;
+type MaxLenFn = dyn Fn(&mut EventCtx, &Env);
+
+/// A closure given to one of the builder methods.
+struct Callback<F: ?Sized>(Rc<F>);
+
+impl<F: ?Sized> Clone for Callback<F> {
+    fn clone(&self) -> Self {
+        Callback(self.0.clone())
+    }
+}
+
+impl<F: ?Sized> fmt::Debug for Callback<F> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Callback")
+    }
+}
 
 /// A widget that allows user text input.
-#[derive(Debug, Clone)]
+///
+/// The text is treated as a single paragraph with one direction. A paragraph
+/// whose first strong character is right-to-left, or that has none while
+/// [`Env::IS_RTL`] is set, is aligned to the right edge, and the left arrow
+/// moves towards the end of the text. The arrow keys follow the paragraph
+/// direction only, so within a run of the opposite direction they move the
+/// wrong way visually. How the text is shaped and reordered, and which
+/// character a click lands on, is up to the platform's text layout.
+///
+/// By default the data is changed on every edit; see [`update_on`] to wait
+/// until the user has finished typing, for instance for text that is
+/// parsed, and pressing Enter calls the [`on_submit`] callback.
+///
+/// For passwords and the like, a text box can be [`masked`].
+///
+/// [`Env::IS_RTL`]: ../struct.Env.html#associatedconstant.IS_RTL
+/// [`update_on`]: #method.update_on
+/// [`on_submit`]: #method.on_submit
+/// [`masked`]: #method.masked
+#[derive(Clone)]
 pub struct TextBox {
     placeholder: String,
+    update_mode: UpdateMode,
+    /// What the user has typed, if it hasn't been written to the data yet.
+    pending: Option<String>,
+    on_submit: Option<Callback<SubmitFn>>,
+    has_clear_button: bool,
+    input_filter: Option<Callback<FilterFn>>,
+    max_len: Option<usize>,
+    on_max_len: Option<Callback<MaxLenFn>>,
+    has_len_counter: bool,
+    /// The width set aside for the counter; this depends on the font.
+    counter_width: f64,
+    masked: bool,
+    revealed: bool,
     width: f64,
+    direction: TextDirection,
     hscroll_offset: f64,
     selection: Selection,
     cursor_timer: TimerToken,
     cursor_on: bool,
 }
 
+impl fmt::Debug for TextBox {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // what is typed into a masked text box shouldn't end up in a log.
+        let pending = match &self.pending {
+            Some(_) if self.masked => Some("<masked>"),
+            Some(text) => Some(text.as_str()),
+            None => None,
+        };
+        f.debug_struct("TextBox")
+            .field("placeholder", &self.placeholder)
+            .field("update_mode", &self.update_mode)
+            .field("pending", &pending)
+            .field("has_clear_button", &self.has_clear_button)
+            .field("input_filter", &self.input_filter)
+            .field("max_len", &self.max_len)
+            .field("has_len_counter", &self.has_len_counter)
+            .field("masked", &self.masked)
+            .field("revealed", &self.revealed)
+            .field("selection", &self.selection)
+            .finish()
+    }
+}
+
 impl TextBox {
+    /// Show or hide the text of a [`masked`] text box.
+    ///
+    /// The argument is a `bool`: `true` to show the text.
+    ///
+    /// [`masked`]: #method.masked
+    pub const REVEAL_TEXT: Selector = Selector::new("druid-builtin.textbox-reveal-text");
+
     /// Create a new TextBox widget
     pub fn new() -> TextBox {
         Self {
             width: 0.0,
+            direction: TextDirection::LeftToRight,
             hscroll_offset: 0.,
             selection: Selection::caret(0),
             cursor_timer: TimerToken::INVALID,
             cursor_on: false,
             placeholder: String::new(),
+            update_mode: UpdateMode::Edit,
+            pending: None,
+            on_submit: None,
+            has_clear_button: false,
+            input_filter: None,
+            max_len: None,
+            on_max_len: None,
+            has_len_counter: false,
+            counter_width: 0.,
+            masked: false,
+            revealed: false,
+        }
+    }
+
+    /// Builder-style method to hide the text, showing a bullet for each
+    /// character instead.
+    ///
+    /// The cursor and selection work as usual, over the bullets, but the
+    /// text can't be copied or cut. It can be shown, and hidden again, by
+    /// sending the text box a [`REVEAL_TEXT`] command.
+    ///
+    /// [`REVEAL_TEXT`]: #associatedconstant.REVEAL_TEXT
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    /// Builder-style method to show a button that clears the text, at the
+    /// right end of the text box, while there is text.
+    ///
+    /// Clearing the text is an edit like any other, so it is written to the
+    /// data according to the [`update_on`] mode.
+    ///
+    /// [`update_on`]: #method.update_on
+    pub fn with_clear_button(mut self) -> Self {
+        self.has_clear_button = true;
+        self
+    }
+
+    /// Builder-style method to only accept the characters for which
+    /// `filter` returns `true`.
+    ///
+    /// Rejected characters are dropped as they are typed or pasted; the rest
+    /// of a paste is still inserted. Text that is set through the data is
+    /// not filtered.
+    ///
+    /// ```
+    /// use druid::widget::TextBox;
+    ///
+    /// // a duration, such as "05:00"
+    /// let textbox = TextBox::new().with_input_filter(|c| c.is_ascii_digit() || c == ':');
+    /// ```
+    pub fn with_input_filter(mut self, filter: impl Fn(char) -> bool + 'static) -> Self {
+        self.input_filter = Some(Callback(Rc::new(filter)));
+        self
+    }
+
+    /// Builder-style method to only accept the digits `0` to `9`.
+    ///
+    /// This replaces any [`input filter`] set before.
+    ///
+    /// [`input filter`]: #method.with_input_filter
+    pub fn numeric(self) -> Self {
+        self.with_input_filter(|c| c.is_ascii_digit())
+    }
+
+    /// Builder-style method to limit the text to `max_len` characters.
+    ///
+    /// Characters are counted as the user sees them, so an accented letter
+    /// made of several codepoints counts once. Typing is ignored once the
+    /// text is this long, and pastes are cut short. Like the [`input
+    /// filter`], this only limits what the user enters.
+    ///
+    /// [`input filter`]: #method.with_input_filter
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = Some(max_len);
+        self
+    }
+
+    /// Builder-style method to set a callback for when input is dropped
+    /// because the text has reached its [`max len`].
+    ///
+    /// [`max len`]: #method.with_max_len
+    pub fn on_max_len(mut self, f: impl Fn(&mut EventCtx, &Env) + 'static) -> Self {
+        self.on_max_len = Some(Callback(Rc::new(f)));
+        self
+    }
+
+    /// Builder-style method to show how many characters there are, out of
+    /// the [`max len`], at the right end of the text box.
+    ///
+    /// This has no effect on a text box without a max len.
+    ///
+    /// [`max len`]: #method.with_max_len
+    pub fn with_len_counter(mut self) -> Self {
+        self.has_len_counter = true;
+        self
+    }
+
+    /// Builder-style method to set when the data is updated with what the
+    /// user has typed.
+    ///
+    /// Waiting for [`UpdateMode::Submit`] suits text that is parsed, such
+    /// as a number or a duration, where the partial text while typing would
+    /// briefly produce other values, or none.
+    ///
+    /// [`UpdateMode::Submit`]: enum.UpdateMode.html#variant.Submit
+    pub fn update_on(mut self, mode: UpdateMode) -> Self {
+        self.update_mode = mode;
+        self
+    }
+
+    /// Builder-style method to set a callback for when Enter is pressed.
+    ///
+    /// The callback is passed the data, which has already been updated if
+    /// the text box waits for Enter to update it.
+    pub fn on_submit(mut self, f: impl Fn(&mut EventCtx, &mut String, &Env) + 'static) -> Self {
+        self.on_submit = Some(Callback(Rc::new(f)));
+        self
+    }
+
+    /// Whether the text is currently hidden behind bullets.
+    fn is_masked(&self) -> bool {
+        self.masked && !self.revealed
+    }
+
+    /// The text as it is shown.
+    fn shown_text<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        if self.is_masked() {
+            Cow::Owned(text.graphemes(true).map(|_| MASK_CHAR).collect())
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Convert an offset in `text` to the matching offset in what is shown.
+    fn shown_offset(&self, text: &str, offset: usize) -> usize {
+        if self.is_masked() {
+            let before = text.get(..offset).unwrap_or(text);
+            before.graphemes(true).count() * MASK_CHAR.len_utf8()
+        } else {
+            offset
+        }
+    }
+
+    /// Convert an offset in what is shown to the matching offset in `text`.
+    fn text_offset(&self, text: &str, shown_offset: usize) -> usize {
+        if self.is_masked() {
+            let n = shown_offset / MASK_CHAR.len_utf8();
+            text.grapheme_indices(true)
+                .nth(n)
+                .map(|(i, _)| i)
+                .unwrap_or_else(|| text.len())
+        } else {
+            shown_offset
+        }
+    }
+
+    /// Write any pending edit to the data.
+    fn commit(&mut self, ctx: &mut EventCtx, data: &mut String) {
+        if let Some(text) = self.pending.take() {
+            *data = text;
+            ctx.request_paint();
         }
     }
 
     /// Builder-style method to set the `TextBox`'s placeholder text.
+    ///
+    /// The placeholder is shown, in [`theme::PLACEHOLDER_COLOR`], while the
+    /// text is empty. It is only a hint: it can't be selected or copied, and
+    /// the data stays empty.
+    ///
+    /// [`theme::PLACEHOLDER_COLOR`]: ../theme/constant.PLACEHOLDER_COLOR.html
     pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
         self.placeholder = placeholder.into();
         self
@@ -110,7 +389,7 @@ impl TextBox {
     fn caret_to(&mut self, text: &mut String, to: usize) {
         match text.cursor(to) {
             Some(_) => self.selection = Selection::caret(to),
-            None => log::error!("You can't move the cursor there."),
+            None => log::error!(target: "druid::event", "You can't move the cursor there."),
         }
     }
 
@@ -120,9 +399,46 @@ impl TextBox {
         self.selection.end
     }
 
-    fn do_edit_action(&mut self, edit_action: EditAction, text: &mut String) {
+    /// The part of `new` that may be inserted into `text`, replacing the
+    /// selection, given the input filter and max len.
+    ///
+    /// The `bool` is `true` if some of `new` didn't fit.
+    fn accepted_input(&self, text: &str, new: &str) -> (String, bool) {
+        let filter = self.input_filter.as_ref();
+        let accepted: String = new
+            .chars()
+            .filter(|c| filter.map(|f| (f.0)(*c)).unwrap_or(true))
+            .collect();
+        match self.max_len {
+            Some(max_len) => {
+                let replaced = text
+                    .get(self.selection.range())
+                    .map(|s| s.graphemes(true).count())
+                    .unwrap_or(0);
+                let len = text.graphemes(true).count() - replaced;
+                let room = max_len.saturating_sub(len);
+                match accepted.grapheme_indices(true).nth(room) {
+                    Some((end, _)) => (accepted[..end].to_string(), true),
+                    None => (accepted, false),
+                }
+            }
+            None => (accepted, false),
+        }
+    }
+
+    /// Apply `edit_action` to `text`.
+    ///
+    /// Returns `true` if input was dropped because of the max len.
+    fn do_edit_action(&mut self, edit_action: EditAction, text: &mut String) -> bool {
         match edit_action {
-            EditAction::Insert(chars) | EditAction::Paste(chars) => self.insert(text, &chars),
+            EditAction::Insert(chars) | EditAction::Paste(chars) => {
+                let (chars, hit_max_len) = self.accepted_input(text, &chars);
+                // a rejected character shouldn't delete the selection.
+                if !chars.is_empty() {
+                    self.insert(text, &chars);
+                }
+                return hit_max_len;
+            }
             EditAction::Backspace => self.delete_backward(text),
             EditAction::Delete => self.delete_forward(text),
             EditAction::Move(movement) => self.move_selection(movement, text, false),
@@ -137,10 +453,19 @@ impl TextBox {
             }
             EditAction::Drag(action) => self.selection.end = action.column,
         }
+        false
     }
 
     /// Edit a selection using a `Movement`.
     fn move_selection(&mut self, mvmnt: Movement, text: &mut String, modify: bool) {
+        // movements are in logical order, which runs leftwards in RTL text.
+        let mvmnt = match (mvmnt, self.direction) {
+            (Movement::Left, TextDirection::RightToLeft) => Movement::Right,
+            (Movement::Right, TextDirection::RightToLeft) => Movement::Left,
+            (Movement::LeftOfLine, TextDirection::RightToLeft) => Movement::RightOfLine,
+            (Movement::RightOfLine, TextDirection::RightToLeft) => Movement::LeftOfLine,
+            (mvmnt, _) => mvmnt,
+        };
         // This movement function should ensure all movements are legit.
         // If they aren't, that's a problem with the movement function.
         self.selection = movement(mvmnt, self.selection, text, modify);
@@ -172,19 +497,21 @@ impl TextBox {
         }
     }
 
-    /// For a given point, returns the corresponding offset (in bytes) of
-    /// the grapheme cluster closest to that point.
-    fn offset_for_point(&self, point: Point, layout: &PietTextLayout) -> usize {
+    /// For a given point, returns the corresponding offset (in bytes) in
+    /// `text` of the grapheme cluster closest to that point.
+    fn offset_for_point(&self, point: Point, layout: &PietTextLayout, text: &str) -> usize {
         // Translating from screenspace to Piet's text layout representation.
         // We need to account for hscroll_offset state and TextBox's padding.
-        let translated_point = Point::new(point.x + self.hscroll_offset - PADDING_LEFT, point.y);
+        let translated_point =
+            Point::new(point.x + self.hscroll_offset - self.text_x(layout), point.y);
         let hit_test = layout.hit_test_point(translated_point);
-        hit_test.metrics.text_position
+        self.text_offset(text, hit_test.metrics.text_position)
     }
 
-    /// Given an offset (in bytes) of a valid grapheme cluster, return
-    /// the corresponding x coordinate of that grapheme on the screen.
-    fn x_for_offset(&self, layout: &PietTextLayout, offset: usize) -> f64 {
+    /// Given an offset (in bytes) in `text` of a valid grapheme cluster,
+    /// return the corresponding x coordinate of that grapheme on the screen.
+    fn x_for_offset(&self, layout: &PietTextLayout, text: &str, offset: usize) -> f64 {
+        let offset = self.shown_offset(text, offset);
         if let Some(position) = layout.hit_test_text_position(offset) {
             position.point.x
         } else {
@@ -193,24 +520,71 @@ impl TextBox {
         }
     }
 
+    /// The width the text is shown in, leaving out the counter and the
+    /// clear button.
+    fn text_width(&self) -> f64 {
+        (self.width - self.counter_width - self.clear_button_width()).max(0.)
+    }
+
+    fn clear_button_width(&self) -> f64 {
+        if self.has_clear_button {
+            CLEAR_BUTTON_WIDTH
+        } else {
+            0.
+        }
+    }
+
+    /// The counter's text for `text`, if it is shown.
+    fn counter_text(&self, text: &str) -> Option<String> {
+        match self.max_len {
+            Some(max_len) if self.has_len_counter => {
+                Some(format!("{}/{}", text.graphemes(true).count(), max_len))
+            }
+            _ => None,
+        }
+    }
+
+    /// The clear button's rect, if it is shown for `text`.
+    fn clear_button_rect(&self, text: &str, env: &Env) -> Option<Rect> {
+        if !self.has_clear_button || text.is_empty() {
+            return None;
+        }
+        let height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
+        let x0 = self.width - CLEAR_BUTTON_WIDTH;
+        Some(Rect::new(x0, 0., self.width, height))
+    }
+
+    /// The x position the text layout is drawn at.
+    ///
+    /// Right-to-left text that fits is drawn against the right edge.
+    fn text_x(&self, layout: &PietTextLayout) -> f64 {
+        let trailing = self.text_width() - PADDING_LEFT - layout.width();
+        if self.direction.is_rtl() && trailing > PADDING_LEFT {
+            trailing
+        } else {
+            PADDING_LEFT
+        }
+    }
+
     /// Calculate a stateful scroll offset
-    fn update_hscroll(&mut self, layout: &PietTextLayout) {
-        let cursor_x = self.x_for_offset(layout, self.cursor());
+    fn update_hscroll(&mut self, layout: &PietTextLayout, text: &str) {
+        let cursor_x = self.x_for_offset(layout, text, self.cursor());
         let overall_text_width = layout.width();
 
         let padding = PADDING_LEFT * 2.;
-        if overall_text_width < self.width {
+        let width = self.text_width();
+        if overall_text_width < width {
             // There's no offset if text is smaller than text box
             //
             // [***I*  ]
             // ^
             self.hscroll_offset = 0.;
-        } else if cursor_x > self.width + self.hscroll_offset - padding {
+        } else if cursor_x > width + self.hscroll_offset - padding {
             // If cursor goes past right side, bump the offset
             //       ->
             // **[****I]****
             //   ^
-            self.hscroll_offset = cursor_x - self.width + padding;
+            self.hscroll_offset = cursor_x - width + padding;
         } else if cursor_x < self.hscroll_offset {
             // If cursor goes past left side, match the offset
             //    <-
@@ -222,25 +596,41 @@ impl TextBox {
 
     fn reset_cursor_blink(&mut self, ctx: &mut EventCtx) {
         self.cursor_on = true;
-        let deadline = Instant::now() + Duration::from_millis(500);
+        let deadline = ctx.now() + Duration::from_millis(500);
         self.cursor_timer = ctx.request_timer(deadline);
     }
 }
 
-impl Widget<String> for TextBox {
-    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+impl TextBox {
+    /// Handle an event, editing `data`, which is the text being shown.
+    fn edit(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
         // Guard against external changes in data?
         self.selection = self.selection.constrain_to(data);
+        self.direction = TextDirection::for_text(&self.shown_text(data), env);
 
-        let mut text_layout = self.get_layout(&mut ctx.text(), &data, env);
+        let mut text_layout = self.get_layout(&mut ctx.text(), &self.shown_text(data), env);
         let mut edit_action = None;
 
+        let clear_button = self.clear_button_rect(data, env);
+        let on_clear_button = |pos| clear_button.map(|r| r.contains(pos)).unwrap_or(false);
+
         match event {
+            Event::MouseDown(mouse) if on_clear_button(mouse.pos) => {
+                ctx.request_focus();
+                data.clear();
+                self.selection = Selection::caret(0);
+                self.hscroll_offset = 0.;
+                self.reset_cursor_blink(ctx);
+                ctx.request_paint();
+            }
+            Event::MouseMoved(mouse) if on_clear_button(mouse.pos) && !ctx.is_active() => {
+                ctx.set_cursor(&Cursor::Arrow);
+            }
             Event::MouseDown(mouse) => {
                 ctx.request_focus();
                 ctx.set_active(true);
 
-                let cursor_offset = self.offset_for_point(mouse.pos, &text_layout);
+                let cursor_offset = self.offset_for_point(mouse.pos, &text_layout, data);
                 edit_action = Some(EditAction::Click(MouseAction {
                     row: 0,
                     column: cursor_offset,
@@ -252,7 +642,7 @@ impl Widget<String> for TextBox {
             Event::MouseMoved(mouse) => {
                 ctx.set_cursor(&Cursor::IBeam);
                 if ctx.is_active() {
-                    let cursor_offset = self.offset_for_point(mouse.pos, &text_layout);
+                    let cursor_offset = self.offset_for_point(mouse.pos, &text_layout, data);
                     edit_action = Some(EditAction::Drag(MouseAction {
                         row: 0,
                         column: cursor_offset,
@@ -261,34 +651,39 @@ impl Widget<String> for TextBox {
                     ctx.request_paint();
                 }
             }
-            Event::MouseUp(_) => {
-                if ctx.is_active() {
-                    ctx.set_active(false);
-                    ctx.request_paint();
-                }
+            Event::MouseUp(_) if ctx.is_active() => {
+                ctx.set_active(false);
+                ctx.request_paint();
             }
-            Event::Timer(id) => {
-                if *id == self.cursor_timer {
-                    self.cursor_on = !self.cursor_on;
+            Event::Timer(id) if *id == self.cursor_timer => {
+                self.cursor_on = !self.cursor_on;
+                ctx.request_paint();
+                let deadline = ctx.now() + Duration::from_millis(500);
+                self.cursor_timer = ctx.request_timer(deadline);
+            }
+            Event::Command(cmd) if cmd.is(TextBox::REVEAL_TEXT) => match cmd.get_object::<bool>() {
+                Ok(reveal) => {
+                    self.revealed = *reveal;
+                    text_layout = self.get_layout(&mut ctx.text(), &self.shown_text(data), env);
+                    self.update_hscroll(&text_layout, data);
                     ctx.request_paint();
-                    let deadline = Instant::now() + Duration::from_millis(500);
-                    self.cursor_timer = ctx.request_timer(deadline);
                 }
-            }
+                Err(e) => log::error!(target: "druid::command", "REVEAL_TEXT needs a bool: {}", e),
+            },
             Event::Command(ref cmd)
                 if ctx.has_focus()
-                    && (cmd.selector == crate::commands::COPY
-                        || cmd.selector == crate::commands::CUT) =>
+                    && !self.masked
+                    && (cmd.is(crate::commands::COPY) || cmd.is(crate::commands::CUT)) =>
             {
                 if let Some(text) = data.slice(self.selection.range()) {
-                    Application::clipboard().put_string(text);
+                    ctx.put_clipboard_string(&text);
                 }
-                if !self.selection.is_caret() && cmd.selector == crate::commands::CUT {
+                if !self.selection.is_caret() && cmd.is(crate::commands::CUT) {
                     edit_action = Some(EditAction::Delete);
                 }
                 ctx.set_handled();
             }
-            Event::Command(cmd) if cmd.selector == RESET_BLINK => self.reset_cursor_blink(ctx),
+            Event::Command(cmd) if cmd.is(RESET_BLINK) => self.reset_cursor_blink(ctx),
             Event::Paste(ref item) => {
                 if let Some(string) = item.get_string() {
                     edit_action = Some(EditAction::Paste(string));
@@ -296,18 +691,8 @@ impl Widget<String> for TextBox {
                 }
             }
             Event::KeyDown(key_event) => {
-                let event_handled = match key_event {
-                    // Tab and shift+tab
-                    k_e if HotKey::new(None, KeyCode::Tab).matches(k_e) => {
-                        ctx.focus_next();
-                        true
-                    }
-                    k_e if HotKey::new(SysMods::Shift, KeyCode::Tab).matches(k_e) => {
-                        ctx.focus_prev();
-                        true
-                    }
-                    _ => false,
-                };
+                // enter is handled by `event`, rather than typed.
+                let event_handled = ctx.handle_focus_key(key_event) || is_enter(key_event);
 
                 if !event_handled {
                     edit_action = BasicTextInput::new().handle_event(key_event);
@@ -325,12 +710,62 @@ impl Widget<String> for TextBox {
                 false
             };
 
-            self.do_edit_action(edit_action, data);
+            if self.do_edit_action(edit_action, data) {
+                if let Some(Callback(on_max_len)) = self.on_max_len.clone() {
+                    on_max_len(ctx, env);
+                }
+            }
             self.reset_cursor_blink(ctx);
 
+            self.direction = TextDirection::for_text(&self.shown_text(data), env);
             if !is_select_all {
-                text_layout = self.get_layout(&mut ctx.text(), &data, env);
-                self.update_hscroll(&text_layout);
+                text_layout = self.get_layout(&mut ctx.text(), &self.shown_text(data), env);
+                self.update_hscroll(&text_layout, data);
+            }
+        }
+    }
+}
+
+fn is_enter(key: &KeyEvent) -> bool {
+    HotKey::new(None, KeyCode::Return).matches(key)
+        || HotKey::new(None, KeyCode::NumpadEnter).matches(key)
+}
+
+impl Widget<String> for TextBox {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut String, env: &Env) {
+        match event {
+            Event::Command(cmd) if cmd.is(FOCUS_LOST) => {
+                match self.update_mode {
+                    UpdateMode::Edit => (),
+                    UpdateMode::Blur => self.commit(ctx, data),
+                    UpdateMode::Submit => {
+                        if self.pending.take().is_some() {
+                            ctx.request_paint();
+                        }
+                    }
+                }
+                return;
+            }
+            _ => (),
+        }
+
+        if self.update_mode == UpdateMode::Edit {
+            self.edit(ctx, event, data, env);
+        } else {
+            let mut text = self.pending.take().unwrap_or_else(|| data.clone());
+            self.edit(ctx, event, &mut text, env);
+            if text != *data {
+                self.pending = Some(text);
+            }
+        }
+
+        if let Event::KeyDown(key) = event {
+            if is_enter(key) {
+                self.commit(ctx, data);
+                if let Some(Callback(on_submit)) = self.on_submit.clone() {
+                    on_submit(ctx, data, env);
+                }
+                ctx.set_handled();
             }
         }
     }
@@ -340,41 +775,63 @@ impl Widget<String> for TextBox {
             LifeCycle::WidgetAdded => ctx.register_for_focus(),
             // an open question: should we be able to schedule timers here?
             LifeCycle::FocusChanged(true) => ctx.submit_command(RESET_BLINK, ctx.widget_id()),
+            LifeCycle::FocusChanged(false) if self.pending.is_some() => {
+                ctx.submit_command(FOCUS_LOST, ctx.widget_id())
+            }
             _ => (),
         }
     }
 
-    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &String, _data: &String, _env: &Env) {
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &String, data: &String, _env: &Env) {
+        // the data was changed by someone else; their change wins over an
+        // edit that hasn't been written yet.
+        if old_data != data && self.pending.take().is_some() {
+            self.selection = self.selection.constrain_to(data);
+        }
         ctx.request_paint();
     }
 
     fn layout(
         &mut self,
-        _layout_ctx: &mut LayoutCtx,
+        layout_ctx: &mut LayoutCtx,
         bc: &BoxConstraints,
         _data: &String,
         env: &Env,
     ) -> Size {
-        let width = env.get(theme::WIDE_WIDGET_WIDTH);
-        let height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let width = theme::scaled(env, theme::WIDE_WIDGET_WIDTH);
+        let height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
 
         let size = bc.constrain((width, height));
         self.width = size.width;
+        // leave room for the longest the counter can get.
+        self.counter_width = match self.max_len {
+            Some(max_len) if self.has_len_counter => {
+                let widest = format!("{0}/{0}", max_len);
+                let layout = self.get_layout(layout_ctx.text(), &widest, env);
+                layout.width() + PADDING_LEFT * 2.
+            }
+            _ => 0.,
+        };
         size
     }
 
     fn paint(&mut self, ctx: &mut PaintCtx, data: &String, env: &Env) {
+        let data = self.pending.as_ref().unwrap_or(data);
         // Guard against changes in data following `event`
-        let content = if data.is_empty() {
+        let showing_placeholder = data.is_empty();
+        let shown = self.shown_text(data);
+        let content = if showing_placeholder {
             &self.placeholder
         } else {
-            data
+            shown.as_ref()
         };
 
-        self.selection = self.selection.constrain_to(content);
+        // the placeholder isn't text, so there is nothing to select in it.
+        self.selection = self.selection.constrain_to(data);
+        self.direction = TextDirection::for_text(content, env);
 
         let font_size = env.get(theme::TEXT_SIZE_NORMAL);
-        let height = env.get(theme::BORDERED_WIDGET_HEIGHT);
+        let height = theme::scaled(env, theme::BORDERED_WIDGET_HEIGHT);
         let background_color = env.get(theme::BACKGROUND_LIGHT);
         let selection_color = env.get(theme::SELECTION_COLOR);
         let text_color = env.get(theme::LABEL_COLOR);
@@ -382,12 +839,8 @@ impl Widget<String> for TextBox {
         let cursor_color = env.get(theme::CURSOR_COLOR);
 
         let has_focus = ctx.has_focus();
-
-        let border_color = if has_focus {
-            env.get(theme::PRIMARY_LIGHT)
-        } else {
-            env.get(theme::BORDER_DARK)
-        };
+        // focus is shown by the ring the pod draws around us
+        let border_color = env.get(theme::BORDER_DARK);
 
         // Paint the background
         let clip_rect = RoundedRect::from_origin_size(
@@ -401,9 +854,20 @@ impl Widget<String> for TextBox {
         // Render text, selection, and cursor inside a clip
         ctx.with_save(|rc| {
             rc.clip(clip_rect);
+            if self.text_width() < self.width {
+                rc.clip(Rect::new(0., 0., self.text_width(), height));
+            }
 
             // Calculate layout
             let text_layout = self.get_layout(rc.text(), &content, env);
+            let text_x = self.text_x(&text_layout);
+            // the cursor goes where it would in empty text, not in the placeholder.
+            let empty_layout = if showing_placeholder {
+                Some(self.get_layout(rc.text(), "", env))
+            } else {
+                None
+            };
+            let caret_layout = empty_layout.as_ref().unwrap_or(&text_layout);
 
             // Shift everything inside the clip by the hscroll_offset
             rc.transform(Affine::translate((-self.hscroll_offset, 0.)));
@@ -411,12 +875,12 @@ impl Widget<String> for TextBox {
             // Draw selection rect
             if !self.selection.is_caret() {
                 let (left, right) = (self.selection.min(), self.selection.max());
-                let left_offset = self.x_for_offset(&text_layout, left);
-                let right_offset = self.x_for_offset(&text_layout, right);
+                let left_offset = self.x_for_offset(&text_layout, data, left);
+                let right_offset = self.x_for_offset(&text_layout, data, right);
 
                 let selection_width = right_offset - left_offset;
 
-                let selection_pos = Point::new(left_offset + PADDING_LEFT - 1., PADDING_TOP - 2.);
+                let selection_pos = Point::new(left_offset + text_x - 1., PADDING_TOP - 2.);
 
                 let selection_rect = RoundedRect::from_origin_size(
                     selection_pos,
@@ -428,7 +892,7 @@ impl Widget<String> for TextBox {
 
             // Layout, measure, and draw text
             let text_height = font_size * 0.8;
-            let text_pos = Point::new(0.0 + PADDING_LEFT, text_height + PADDING_TOP);
+            let text_pos = Point::new(text_x, text_height + PADDING_TOP);
             let color = if data.is_empty() {
                 &placeholder_color
             } else {
@@ -439,8 +903,9 @@ impl Widget<String> for TextBox {
 
             // Paint the cursor if focused and there's no selection
             if has_focus && self.cursor_on && self.selection.is_caret() {
-                let cursor_x = self.x_for_offset(&text_layout, self.cursor());
-                let xy = text_pos + Vec2::new(cursor_x, 2. - font_size);
+                let cursor_x = self.x_for_offset(caret_layout, data, self.cursor());
+                let caret_pos = Point::new(self.text_x(caret_layout), text_pos.y);
+                let xy = caret_pos + Vec2::new(cursor_x, 2. - font_size);
                 let x2y2 = xy + Vec2::new(0., font_size + 2.);
                 let line = Line::new(xy, x2y2);
 
@@ -448,6 +913,24 @@ impl Widget<String> for TextBox {
             }
         });
 
+        if let Some(counter) = self.counter_text(data) {
+            let layout = self.get_layout(ctx.text(), &counter, env);
+            let x = self.text_width() + self.counter_width - PADDING_LEFT - layout.width();
+            let y = font_size * 0.8 + PADDING_TOP;
+            ctx.draw_text(&layout, (x, y), &placeholder_color);
+        }
+
+        if let Some(button) = self.clear_button_rect(data, env) {
+            let center = button.center();
+            let arm = CLEAR_CROSS_SIZE / 2.;
+            let mut cross = BezPath::new();
+            cross.move_to(center + Vec2::new(-arm, -arm));
+            cross.line_to(center + Vec2::new(arm, arm));
+            cross.move_to(center + Vec2::new(arm, -arm));
+            cross.line_to(center + Vec2::new(-arm, arm));
+            ctx.stroke(cross, &placeholder_color, 1.5);
+        }
+
         // Paint the border
         ctx.stroke(clip_rect, &border_color, BORDER_WIDTH);
     }
@@ -486,6 +969,43 @@ mod tests {
         widget.insert(&mut data, "a");
     }
 
+    #[test]
+    fn input_filter_and_max_length() {
+        let mut widget = TextBox::new()
+            .with_input_filter(|c| c.is_ascii_digit() || c == ':')
+            .with_max_len(5);
+        let mut data = "".to_string();
+
+        for typed in &["1", "a", ":", "2"] {
+            widget.do_edit_action(EditAction::Insert(typed.to_string()), &mut data);
+        }
+        assert_eq!(data, "1:2");
+
+        widget.do_edit_action(EditAction::Paste("3x45".into()), &mut data);
+        assert_eq!(data, "1:234");
+        widget.do_edit_action(EditAction::Insert("6".into()), &mut data);
+        assert_eq!(data, "1:234");
+
+        // replacing the selection makes room.
+        widget.selection = Selection::new(2, 5);
+        widget.do_edit_action(EditAction::Insert("x".into()), &mut data);
+        assert_eq!(data, "1:234");
+        widget.do_edit_action(EditAction::Paste("0000".into()), &mut data);
+        assert_eq!(data, "1:000");
+    }
+
+    #[test]
+    fn max_len_counts_graphemes() {
+        let mut widget = TextBox::new().with_max_len(3);
+        let mut data = "".to_string();
+
+        // "o" with a combining stroke, and a three byte letter.
+        widget.do_edit_action(EditAction::Paste("\u{006F}\u{0337}ह".into()), &mut data);
+        assert!(!widget.do_edit_action(EditAction::Insert("a".into()), &mut data));
+        assert!(widget.do_edit_action(EditAction::Insert("b".into()), &mut data));
+        assert_eq!(data, "\u{006F}\u{0337}हa");
+    }
+
     /// Test backspace on the combo character o̷
     #[test]
     fn backspace_combining() {
@@ -520,4 +1040,20 @@ mod tests {
         widget.delete_backward(&mut data);
         assert_eq!(data, String::from(""));
     }
+
+    /// In right-to-left text the left arrow moves towards the end.
+    #[test]
+    fn arrows_follow_rtl_text() {
+        let mut widget = TextBox::new();
+        let mut data = "".to_string();
+        widget.insert(&mut data, "שלום");
+        widget.direction = TextDirection::RightToLeft;
+
+        widget.move_selection(Movement::RightOfLine, &mut data, false);
+        assert_eq!(widget.cursor(), 0);
+        widget.move_selection(Movement::Left, &mut data, false);
+        assert_eq!(widget.cursor(), "ש".len());
+        widget.move_selection(Movement::Right, &mut data, false);
+        assert_eq!(widget.cursor(), 0);
+    }
 }
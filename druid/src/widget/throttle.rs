@@ -0,0 +1,255 @@
+// Copyright 2020 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that limits how often its child sees new data.
+
+use std::time::{Duration, Instant};
+
+use crate::kurbo::{Point, Rect, Size};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    TimerToken, UpdateCtx, Widget, WidgetPod,
+};
+
+/// How a [`Throttle`] decides when to pass on changes.
+///
+/// [`Throttle`]: struct.Throttle.html
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Mode {
+    /// At most once per interval.
+    Throttle,
+    /// Once the data has stopped changing for an interval.
+    Debounce,
+}
+
+/// A widget that limits how often its child is updated with new data.
+///
+/// The child sees its own copy of the data, which only catches up with the
+/// real data when the `Throttle` lets a change through. In between, the
+/// child isn't updated, and lays itself out and paints with the data it
+/// saw last. This is useful for an expensive widget showing data that
+/// changes more often than anyone can read it.
+///
+/// A throttle lets a change through at most once per interval. A
+/// debounce waits for the data to stop changing for an interval first,
+/// which suits data that changes in bursts, such as text being typed.
+///
+/// Either can pass changes on at the *leading* edge, as soon as they arrive
+/// when nothing has been let through recently, and at the *trailing* edge,
+/// once the interval has passed; by default a throttle does both, and a
+/// debounce only the trailing edge. Without a trailing edge, a change that
+/// comes too soon is only seen with the next change after the interval.
+///
+/// Events are handled with the current data, even while the child's copy is
+/// behind, so a change the child makes is applied on top of any newer ones
+/// and passed straight on.
+///
+/// You will generally want to create this with [`WidgetExt::throttle`] or
+/// [`WidgetExt::debounce`].
+///
+/// [`WidgetExt::throttle`]: ../trait.WidgetExt.html#method.throttle
+/// [`WidgetExt::debounce`]: ../trait.WidgetExt.html#method.debounce
+pub struct Throttle<T, W> {
+    child: WidgetPod<T, W>,
+    /// The data as the child knows it.
+    shown: Option<T>,
+    mode: Mode,
+    interval: Duration,
+    leading: bool,
+    trailing: bool,
+    /// When a change was last let through.
+    last_shown: Option<Instant>,
+    /// Set when the data has changed since it was last let through.
+    pending: bool,
+    /// The timer for the end of the current interval, if there is one.
+    timer: TimerToken,
+    /// When the current interval ends.
+    deadline: Option<Instant>,
+}
+
+impl<T, W: Widget<T>> Throttle<T, W> {
+    /// Create a widget that updates `child` at most once per `interval`.
+    pub fn new(child: W, interval: Duration) -> Self {
+        Throttle::with_mode(child, interval, Mode::Throttle, true)
+    }
+
+    /// Create a widget that updates `child` once the data hasn't changed
+    /// for `interval`.
+    pub fn debounce(child: W, interval: Duration) -> Self {
+        Throttle::with_mode(child, interval, Mode::Debounce, false)
+    }
+
+    fn with_mode(child: W, interval: Duration, mode: Mode, leading: bool) -> Self {
+        Throttle {
+            child: WidgetPod::new(child),
+            shown: None,
+            mode,
+            interval,
+            leading,
+            trailing: true,
+            last_shown: None,
+            pending: false,
+            timer: TimerToken::INVALID,
+            deadline: None,
+        }
+    }
+
+    /// Builder-style method to set whether changes are passed on at the
+    /// start of an interval.
+    ///
+    /// # Panics
+    ///
+    /// The widget panics when it is added if neither edge is enabled.
+    pub fn leading(mut self, leading: bool) -> Self {
+        self.leading = leading;
+        self
+    }
+
+    /// Builder-style method to set whether changes are passed on at the
+    /// end of an interval.
+    ///
+    /// # Panics
+    ///
+    /// The widget panics when it is added if neither edge is enabled.
+    pub fn trailing(mut self, trailing: bool) -> Self {
+        self.trailing = trailing;
+        self
+    }
+}
+
+impl<T: Data, W: Widget<T>> Throttle<T, W> {
+    /// Let the current data through to the child.
+    fn show(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        self.shown = Some(data.clone());
+        self.last_shown = Some(ctx.now());
+        self.pending = false;
+        self.child.update(ctx, data, env);
+    }
+
+    /// Start a new interval, ending `interval` from now.
+    fn start_interval(&mut self, ctx: &mut UpdateCtx) {
+        let deadline = ctx.now() + self.interval;
+        self.deadline = Some(deadline);
+        self.timer = ctx.request_timer(deadline);
+    }
+
+    /// Decide what to do about new data.
+    fn data_changed(&mut self, ctx: &mut UpdateCtx, data: &T, env: &Env) {
+        let in_interval = self.deadline.is_some();
+        match self.mode {
+            Mode::Throttle => {
+                if !in_interval && self.leading {
+                    self.show(ctx, data, env);
+                    self.start_interval(ctx);
+                } else if !in_interval {
+                    self.pending = true;
+                    self.start_interval(ctx);
+                } else {
+                    self.pending = true;
+                }
+            }
+            Mode::Debounce => {
+                if !in_interval && self.leading {
+                    self.show(ctx, data, env);
+                } else {
+                    self.pending = true;
+                }
+                // every change pushes the end of the burst back.
+                self.start_interval(ctx);
+            }
+        }
+    }
+
+    /// The current interval is over.
+    fn interval_ended(&mut self, ctx: &mut EventCtx, data: &T, env: &Env) {
+        self.deadline = None;
+        self.timer = TimerToken::INVALID;
+        if !(self.pending && self.trailing) {
+            return;
+        }
+        let mut update_ctx = UpdateCtx {
+            command_queue: ctx.command_queue,
+            window: ctx.window,
+            clock: ctx.clock,
+            base_state: ctx.base_state,
+            window_id: ctx.window_id,
+            window_visible: ctx.window_visible,
+        };
+        self.show(&mut update_ctx, data, env);
+        // a throttle keeps at most one change per interval.
+        if self.mode == Mode::Throttle {
+            self.start_interval(&mut update_ctx);
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Throttle<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        if let Event::Timer(token) = event {
+            if *token == self.timer {
+                self.interval_ended(ctx, data, env);
+                ctx.set_handled();
+                return;
+            }
+        }
+
+        // the child changes the current data, not what it was last shown,
+        // so that a change it makes doesn't undo newer ones.
+        let before = data.clone();
+        self.child.event(ctx, event, data, env);
+        if !before.same(data) {
+            self.shown = Some(data.clone());
+            self.pending = false;
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let LifeCycle::WidgetAdded = event {
+            assert!(
+                self.leading || self.trailing,
+                "Throttle needs at least one of its leading and trailing edges"
+            );
+        }
+        let shown = self.shown.get_or_insert_with(|| data.clone());
+        self.child.lifecycle(ctx, event, shown, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        let changed = match self.shown.as_ref() {
+            Some(shown) => !shown.same(data),
+            None => true,
+        };
+        if changed {
+            self.data_changed(ctx, data, env);
+        } else {
+            self.pending = false;
+            self.child.update(ctx, data, env);
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Throttle");
+        let shown = self.shown.as_ref().unwrap_or(data);
+        let size = self.child.layout(ctx, bc, shown, env);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        ctx.set_paint_insets(self.child.compute_parent_paint_insets(size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let shown = self.shown.as_ref().unwrap_or(data);
+        self.child.paint(ctx, shown, env);
+    }
+}
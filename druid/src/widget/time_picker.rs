@@ -0,0 +1,214 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Fields for a time of day, with a spinner.
+
+use crate::format::{DateConventions, NumberFormatter, Time};
+use crate::kurbo::{Point, Rect, Size};
+use crate::lens::Map;
+use crate::widget::{Button, Either, Flex, Formatted, Label, Stepper, TextBox, WidgetExt};
+use crate::{
+    BoxConstraints, Env, Event, EventCtx, HotKey, KeyCode, LayoutCtx, LifeCycle, LifeCycleCtx,
+    PaintCtx, UpdateCtx, Widget, WidgetPod,
+};
+
+const MINUTES_PER_DAY: u32 = 24 * 60;
+/// Wide enough for two digits.
+const FIELD_WIDTH: f64 = 36.0;
+
+/// A widget for editing a time of day.
+///
+/// The hour and minute each have a text field, and locales that use a
+/// 12-hour clock get a button that switches between AM and PM. The hours
+/// shown follow the current locale (see [`DateConventions`]).
+///
+/// Beside the fields is a [`Stepper`] that changes the time by a number of
+/// minutes, wrapping around midnight; the up and down arrow keys do the
+/// same while a field has focus.
+///
+/// # Examples
+///
+/// Asking when a timer should go off:
+///
+/// ```
+/// use druid::widget::TimePicker;
+///
+/// let alarm = TimePicker::new().with_minute_step(15);
+/// ```
+///
+/// [`DateConventions`]: ../format/struct.DateConventions.html
+/// [`Stepper`]: struct.Stepper.html
+pub struct TimePicker {
+    minute_step: u32,
+    fields: WidgetPod<Time, Box<dyn Widget<Time>>>,
+}
+
+impl TimePicker {
+    /// Create a new `TimePicker`, stepping by one minute.
+    pub fn new() -> TimePicker {
+        TimePicker {
+            minute_step: 1,
+            fields: WidgetPod::new(fields(1)),
+        }
+    }
+
+    /// Builder-style method to set how many minutes the stepper and the
+    /// arrow keys change the time by.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `step` is zero, or at least a day.
+    pub fn with_minute_step(mut self, step: u32) -> Self {
+        assert!(
+            step > 0 && step < MINUTES_PER_DAY,
+            "minute step must be from 1 minute to a day"
+        );
+        self.minute_step = step;
+        self.fields = WidgetPod::new(fields(step));
+        self
+    }
+}
+
+impl Default for TimePicker {
+    fn default() -> Self {
+        TimePicker::new()
+    }
+}
+
+/// The fields and stepper.
+fn fields(minute_step: u32) -> Box<dyn Widget<Time>> {
+    let twelve_hour = Flex::row()
+        .with_child(hour_field(true))
+        .with_child(Label::new(":"))
+        .with_child(minute_field())
+        .with_spacer(4.0)
+        .with_child(
+            Button::new(|time: &Time, _: &Env| {
+                if time.hour() < 12 { "AM" } else { "PM" }.to_string()
+            })
+            .on_click(|_, time: &mut Time, _| *time = time.add_minutes(12 * 60)),
+        );
+    let twenty_four_hour = Flex::row()
+        .with_child(hour_field(false))
+        .with_child(Label::new(":"))
+        .with_child(minute_field());
+    let clock = Either::new(
+        |_, env| DateConventions::for_env(env).hour12,
+        twelve_hour,
+        twenty_four_hour,
+    );
+
+    let stepper = Stepper::new()
+        .with_range(0.0, f64::from(MINUTES_PER_DAY - minute_step))
+        .with_step(f64::from(minute_step))
+        .with_wraparound(true)
+        .lens(Map::new(
+            |time: &Time| f64::from(time.minutes_since_midnight()),
+            |time: &mut Time, minutes: f64| {
+                *time = Time::MIDNIGHT.add_minutes(minutes.round() as i64);
+            },
+        ));
+
+    Flex::row()
+        .with_child(clock)
+        .with_spacer(4.0)
+        .with_child(stepper)
+        .boxed()
+}
+
+fn hour_field(hour12: bool) -> impl Widget<Time> {
+    let formatter = NumberFormatter::new().fraction_digits(0, 0).grouping(false);
+    Formatted::new(TextBox::new(), formatter)
+        .fix_width(FIELD_WIDTH)
+        .lens(Map::new(
+            move |time: &Time| {
+                let hour = match (hour12, time.hour() % 12) {
+                    (true, 0) => 12,
+                    (true, hour) => hour,
+                    (false, _) => time.hour(),
+                };
+                f64::from(hour)
+            },
+            move |time: &mut Time, hour: f64| {
+                let (min, max) = if hour12 { (1.0, 12.0) } else { (0.0, 23.0) };
+                if hour.fract() != 0.0 || !(min..=max).contains(&hour) {
+                    return;
+                }
+                let hour = hour as u32;
+                let hour = match (hour12, time.hour() >= 12) {
+                    (true, true) => hour % 12 + 12,
+                    (true, false) => hour % 12,
+                    (false, _) => hour,
+                };
+                *time = Time::new(hour, time.minute()).unwrap();
+            },
+        ))
+}
+
+fn minute_field() -> impl Widget<Time> {
+    let formatter = NumberFormatter::new()
+        .fraction_digits(0, 0)
+        .integer_digits(2);
+    Formatted::new(TextBox::new(), formatter)
+        .fix_width(FIELD_WIDTH)
+        .lens(Map::new(
+            |time: &Time| f64::from(time.minute()),
+            |time: &mut Time, minute: f64| {
+                if minute.fract() == 0.0 && (0.0..60.0).contains(&minute) {
+                    *time = Time::new(time.hour(), minute as u32).unwrap();
+                }
+            },
+        ))
+}
+
+impl Widget<Time> for TimePicker {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut Time, env: &Env) {
+        if let Event::KeyDown(key) = event {
+            let step = i64::from(self.minute_step);
+            if HotKey::new(None, KeyCode::ArrowUp).matches(key) {
+                *data = data.add_minutes(step);
+                ctx.set_handled();
+                return;
+            }
+            if HotKey::new(None, KeyCode::ArrowDown).matches(key) {
+                *data = data.add_minutes(-step);
+                ctx.set_handled();
+                return;
+            }
+        }
+        self.fields.event(ctx, event, data, env);
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &Time, env: &Env) {
+        self.fields.lifecycle(ctx, event, data, env);
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &Time, data: &Time, env: &Env) {
+        self.fields.update(ctx, data, env);
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &Time, env: &Env) -> Size {
+        bc.debug_check("TimePicker");
+
+        let size = self.fields.layout(ctx, bc, data, env);
+        self.fields
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        ctx.set_paint_insets(self.fields.compute_parent_paint_insets(size));
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &Time, env: &Env) {
+        self.fields.paint(ctx, data, env);
+    }
+}
@@ -0,0 +1,154 @@
+// Copyright 2019 The xi-editor Authors.
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A widget that paints its child with an affine transform.
+
+use crate::inspect;
+use crate::kurbo::{Affine, Point, Rect, Size};
+use crate::{
+    BoxConstraints, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    RenderContext, UpdateCtx, Widget, WidgetPod,
+};
+
+/// A widget that scales, rotates, or otherwise transforms how its child is
+/// painted.
+///
+/// The transform only applies to painting, and to mouse events. The child is
+/// laid out as usual, and this widget is the size of the child's layout, not
+/// of what is painted; a child scaled up will paint over its neighbours.
+///
+/// # Events
+///
+/// The child sees mouse positions in its own, untransformed, coordinate
+/// space: the pointer is mapped back through the inverse of the transform
+/// before the child gets the event, so a click on a rotated button lands
+/// wherever the button appears to be. Whether this widget itself is hot is
+/// still decided by its untransformed layout rect, so parts of a child that
+/// are transformed outside of it don't receive events.
+///
+/// The transform is applied relative to this widget's origin; to scale or
+/// rotate around the center, combine it with translations, as
+/// [`around_center`] does.
+///
+/// You will generally want to create this with [`WidgetExt::transformed`].
+///
+/// [`around_center`]: #method.around_center
+/// [`WidgetExt::transformed`]: ../trait.WidgetExt.html#method.transformed
+pub struct Transform<T, W> {
+    child: WidgetPod<T, W>,
+    transform: Affine,
+    around_center: bool,
+}
+
+impl<T, W: Widget<T>> Transform<T, W> {
+    /// Create a new `Transform` widget, painting `child` with `transform`.
+    ///
+    /// The transform must be invertible.
+    pub fn new(child: W, transform: Affine) -> Self {
+        Transform {
+            child: WidgetPod::new(child),
+            transform,
+            around_center: false,
+        }
+    }
+
+    /// Builder-style method to apply the transform around the center of the
+    /// child, instead of its top left corner.
+    pub fn around_center(mut self) -> Self {
+        self.around_center = true;
+        self
+    }
+
+    /// Set the transform.
+    ///
+    /// This only requests a paint if it is called during an event; call it
+    /// from a [`Controller`] to animate the transform.
+    ///
+    /// [`Controller`]: trait.Controller.html
+    pub fn set_transform(&mut self, transform: Affine) {
+        self.transform = transform;
+    }
+
+    /// Returns the transform.
+    pub fn transform(&self) -> Affine {
+        self.transform
+    }
+
+    /// The transform from the child's coordinate space to ours.
+    fn effective_transform(&self, size: Size) -> Affine {
+        if self.around_center {
+            let center = size.to_vec2() / 2.0;
+            Affine::translate(center) * self.transform * Affine::translate(-center)
+        } else {
+            self.transform
+        }
+    }
+}
+
+impl<T: Data, W: Widget<T>> Widget<T> for Transform<T, W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
+        let transform = self.effective_transform(ctx.size());
+        let old_transform = transform.as_coeffs();
+        let child_event = event.transform_mouse(transform);
+        self.child.event(ctx, &child_event, data, env);
+
+        // the transform may have been changed by a controller.
+        let transform = self.effective_transform(ctx.size());
+        if transform.as_coeffs() != old_transform || self.child.state().needs_inval {
+            ctx.request_paint();
+        }
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &T, env: &Env) {
+        if let Some(tree) = inspect::tree_cell(event) {
+            tree.transform_children(self.effective_transform(ctx.size()), None);
+        }
+        self.child.lifecycle(ctx, event, data, env);
+        // our child's invalid region is in its coordinates, not ours.
+        if self.child.state().needs_inval {
+            ctx.request_paint();
+        }
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, _old_data: &T, data: &T, env: &Env) {
+        self.child.update(ctx, data, env);
+        if self.child.state().needs_inval {
+            ctx.request_paint();
+        }
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size {
+        bc.debug_check("Transform");
+
+        let size = self.child.layout(ctx, bc, data, env);
+        self.child
+            .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        let painted = self
+            .effective_transform(size)
+            .transform_rect_bbox(self.child.paint_rect());
+        ctx.set_paint_insets(painted - size.to_rect());
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
+        let transform = self.effective_transform(ctx.size());
+        let visible = transform
+            .inverse()
+            .transform_rect_bbox(ctx.region().to_rect());
+        ctx.with_save(|ctx| {
+            ctx.transform(transform);
+            ctx.with_child_ctx(visible, |ctx| self.child.paint(ctx, data, env));
+        });
+    }
+}
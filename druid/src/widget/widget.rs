@@ -84,11 +84,21 @@ pub struct WidgetId(NonZeroU64);
 /// `WidgetPod` method on all their children. The `WidgetPod` applies
 /// logic to determine whether to recurse, as needed.
 ///
+/// # Mixing widget types
+///
+/// Functions that build widgets usually return `impl Widget<T>`, but that
+/// only works when every path returns the same type. To choose between
+/// different widgets, or to keep widgets of different types in one
+/// collection, use a `Box<dyn Widget<T>>`, which is itself a `Widget<T>`
+/// and can be passed to any container. [`WidgetExt::boxed`] is a shorthand
+/// for creating one.
+///
 /// [`event`]: #tymethod.event
 /// [`update`]: #tymethod.update
 /// [`Data`]: trait.Data.html
 /// [`Env`]: struct.Env.html
 /// [`WidgetPod`]: struct.WidgetPod.html
+/// [`WidgetExt::boxed`]: trait.WidgetExt.html#method.boxed
 pub trait Widget<T> {
     /// Handle an event.
     ///
@@ -97,9 +107,20 @@ pub trait Widget<T> {
     /// requesting things from the [`EventCtx`], mutating the data, or submitting
     /// a [`Command`].
     ///
+    /// A container decides when it handles an event relative to its
+    /// children. Code that runs before the event is passed on sees it in the
+    /// capture phase, and can keep it from the children by calling
+    /// [`EventCtx::set_handled`]; code that runs afterwards sees it in the
+    /// bubble phase, and should check [`EventCtx::is_handled`] first. The
+    /// built-in containers use the bubble phase; to capture events for a
+    /// widget you didn't write, wrap it with [`WidgetExt::capture`].
+    ///
     /// [`Event`]: enum.Event.html
     /// [`EventCtx`]: struct.EventCtx.html
     /// [`Command`]: struct.Command.html
+    /// [`EventCtx::set_handled`]: struct.EventCtx.html#method.set_handled
+    /// [`EventCtx::is_handled`]: struct.EventCtx.html#method.is_handled
+    /// [`WidgetExt::capture`]: trait.WidgetExt.html#method.capture
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env);
 
     /// Handle a life cycle notification.
@@ -122,12 +143,14 @@ pub trait Widget<T> {
     ///
     /// This method is called whenever the data changes. When the appearance of
     /// the widget depends on data, call [`request_paint`] so that it's scheduled
-    /// for repaint.
+    /// for repaint. When the size of the widget depends on data, call
+    /// [`request_layout`]; otherwise, the widget will keep its previous size.
     ///
     /// The previous value of the data is provided in case the widget wants to
     /// compute a fine-grained delta.
     ///
     /// [`request_paint`]: struct.UpdateCtx.html#method.request_paint
+    /// [`request_layout`]: struct.UpdateCtx.html#method.request_layout
     fn update(&mut self, ctx: &mut UpdateCtx, old_data: &T, data: &T, env: &Env);
 
     /// Compute layout.
@@ -152,6 +175,86 @@ pub trait Widget<T> {
     /// [`set_layout_rect`]: struct.WidgetPod.html#method.set_layout_rect
     fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &T, env: &Env) -> Size;
 
+    /// The narrowest the widget can be, given `height`, without its content
+    /// being cut off or overflowing.
+    ///
+    /// This and the other intrinsic size queries let a container size its
+    /// children by their content, for instance to make every cell in a
+    /// column as wide as the widest one. `height` may be infinite. A
+    /// container should call these on its children's [`WidgetPod`]s, which
+    /// cache the answers.
+    ///
+    /// The default is the [`max_intrinsic_width`], which suits widgets
+    /// whose content doesn't wrap.
+    ///
+    /// [`WidgetPod`]: struct.WidgetPod.html
+    /// [`max_intrinsic_width`]: #method.max_intrinsic_width
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.max_intrinsic_width(ctx, height, data, env)
+    }
+
+    /// The widest the widget would want to be, given `height`, if it had as
+    /// much room as it liked.
+    ///
+    /// The default lays the widget out with an unbounded width, which is
+    /// correct but costs a full layout of the widget and its descendants;
+    /// containers should override it. The widget's `WidgetPod` then lays it
+    /// out again when it is next asked to, so the measuring layout is never
+    /// mistaken for the real one. A widget that expands to fill any
+    /// width it is given has a max intrinsic width of zero.
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(f64::INFINITY, height));
+        ctx.probed_layout = true;
+        finite_or_zero(self.layout(ctx, &bc, data, env).width)
+    }
+
+    /// The shortest the widget can be, given `width`, without its content
+    /// being cut off or overflowing.
+    ///
+    /// See [`min_intrinsic_width`]; the default is the [`max_intrinsic_height`].
+    ///
+    /// [`min_intrinsic_width`]: #method.min_intrinsic_width
+    /// [`max_intrinsic_height`]: #method.max_intrinsic_height
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.max_intrinsic_height(ctx, width, data, env)
+    }
+
+    /// The tallest the widget would want to be, given `width`, if it had as
+    /// much room as it liked.
+    ///
+    /// As with [`max_intrinsic_width`], the default lays the widget out.
+    ///
+    /// [`max_intrinsic_width`]: #method.max_intrinsic_width
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        let bc = BoxConstraints::new(Size::ZERO, Size::new(width, f64::INFINITY));
+        ctx.probed_layout = true;
+        finite_or_zero(self.layout(ctx, &bc, data, env).height)
+    }
+
     /// Paint the widget appearance.
     ///
     /// The [`PaintCtx`] derefs to something that implements the [`RenderContext`]
@@ -182,6 +285,10 @@ pub trait Widget<T> {
     }
 }
 
+/// Ids created by `WidgetId::reserved` are at or above this value; allocated
+/// ids are always below it.
+const FIRST_RESERVED_ID: u64 = u64::MAX - u16::MAX as u64;
+
 impl WidgetId {
     /// Allocate a new, unique `WidgetId`.
     ///
@@ -189,12 +296,18 @@ impl WidgetId {
     /// an explicit id if you need to know it ahead of time, for instance
     /// if you want two sibling widgets to know each others' ids.
     ///
+    /// An allocated id never collides with one created by [`reserved`].
+    ///
     /// You must ensure that a given `WidgetId` is only ever used for one
     /// widget at a time.
+    ///
+    /// [`reserved`]: #method.reserved
     pub fn next() -> WidgetId {
         use crate::shell::Counter;
         static WIDGET_ID_COUNTER: Counter = Counter::new();
-        WidgetId(WIDGET_ID_COUNTER.next_nonzero())
+        let id = WIDGET_ID_COUNTER.next_nonzero();
+        assert!(id.get() < FIRST_RESERVED_ID, "ran out of WidgetIds");
+        WidgetId(id)
     }
 
     /// Create a reserved `WidgetId`, suitable for reuse.
@@ -217,6 +330,16 @@ impl WidgetId {
     }
 }
 
+/// An intrinsic size from a layout; a widget that took all of an unbounded
+/// axis has no size of its own on it.
+fn finite_or_zero(extent: f64) -> f64 {
+    if extent.is_finite() {
+        extent
+    } else {
+        0.
+    }
+}
+
 impl<T> Widget<T> for Box<dyn Widget<T>> {
     fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut T, env: &Env) {
         self.deref_mut().event(ctx, event, data, env)
@@ -234,6 +357,46 @@ impl<T> Widget<T> for Box<dyn Widget<T>> {
         self.deref_mut().layout(ctx, bc, data, env)
     }
 
+    fn min_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut().min_intrinsic_width(ctx, height, data, env)
+    }
+
+    fn max_intrinsic_width(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        height: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut().max_intrinsic_width(ctx, height, data, env)
+    }
+
+    fn min_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut().min_intrinsic_height(ctx, width, data, env)
+    }
+
+    fn max_intrinsic_height(
+        &mut self,
+        ctx: &mut LayoutCtx,
+        width: f64,
+        data: &T,
+        env: &Env,
+    ) -> f64 {
+        self.deref_mut().max_intrinsic_height(ctx, width, data, env)
+    }
+
     fn paint(&mut self, ctx: &mut PaintCtx, data: &T, env: &Env) {
         self.deref_mut().paint(ctx, data, env);
     }
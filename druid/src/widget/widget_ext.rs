@@ -14,11 +14,17 @@
 
 //! Convenience methods for widgets.
 
+use std::time::Duration;
+
 use super::{
-    Align, BackgroundBrush, Click, Container, Controller, ControllerHost, EnvScope,
-    IdentityWrapper, Padding, Parse, SizedBox, WidgetId,
+    Align, BackgroundBrush, Badge, Cached, Capture, Click, Computed, Container, Controller,
+    ControllerHost, EnvScope, FocusStyle, FocusStyled, Hidden, IdentityWrapper, OnCopy, Opacity,
+    Padding, Parse, Scroll, SizedBox, Stack, Throttle, Transform, WidgetId,
+};
+use crate::{
+    Affine, Color, Data, Env, Event, EventCtx, Insets, KeyOrValue, Lens, LensWrap, UnitPoint,
+    Widget,
 };
-use crate::{Color, Data, Env, EventCtx, Insets, KeyOrValue, Lens, LensWrap, UnitPoint, Widget};
 
 /// A trait that provides extra methods for combining `Widget`s.
 pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
@@ -30,6 +36,48 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         Padding::new(insets, self)
     }
 
+    /// Wrap this widget in a [`Padding`] widget that adds `amount` on the
+    /// left and right.
+    ///
+    /// [`Padding`]: widget/struct.Padding.html
+    fn padding_horizontal(self, amount: f64) -> Padding<T> {
+        Padding::new(Insets::new(amount, 0.0, amount, 0.0), self)
+    }
+
+    /// Wrap this widget in a [`Padding`] widget that adds `amount` on the
+    /// top and bottom.
+    ///
+    /// [`Padding`]: widget/struct.Padding.html
+    fn padding_vertical(self, amount: f64) -> Padding<T> {
+        Padding::new(Insets::new(0.0, amount, 0.0, amount), self)
+    }
+
+    /// Add empty space around this widget, outside anything it paints.
+    ///
+    /// This is the same [`Padding`] widget as [`padding`]; the difference is
+    /// only in where it goes. Padding applied *before* a [`background`] or
+    /// [`border`] is inside it, and is filled by the background; a margin
+    /// is applied *after*, and separates the whole decorated widget from its
+    /// neighbours:
+    ///
+    /// ```
+    /// # use druid::widget::{Label, WidgetExt};
+    /// # use druid::Color;
+    /// // ten points of red around the text, then ten points of nothing.
+    /// let label = Label::<()>::new("hello")
+    ///     .padding(10.0)
+    ///     .background(Color::rgb8(0xff, 0, 0))
+    ///     .margin(10.0);
+    /// ```
+    ///
+    /// [`Padding`]: widget/struct.Padding.html
+    /// [`padding`]: #method.padding
+    /// [`background`]: #method.background
+    /// [`border`]: #method.border
+    fn margin(self, insets: impl Into<Insets>) -> Padding<T> {
+        Padding::new(insets, self)
+    }
+
     /// Wrap this widget in an [`Align`] widget, configured to center it.
     ///
     /// [`Align`]: widget/struct.Align.html
@@ -174,7 +222,69 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         ControllerHost::new(self, Click::new(f))
     }
 
-    /// Draw the [`layout`] `Rect`s of  this widget and its children.
+    /// Put the text that `f` returns on the clipboard when the user copies
+    /// while this widget, or one of its descendants, has focus, using an
+    /// [`OnCopy`] controller.
+    ///
+    /// [`OnCopy`]: widget/struct.OnCopy.html
+    fn on_copy(self, f: impl Fn(&T, &Env) -> String + 'static) -> ControllerHost<Self, OnCopy<T>> {
+        ControllerHost::new(self, OnCopy::new(f))
+    }
+
+    /// Handle events with `f` before this widget sees them, using a
+    /// [`Capture`] controller.
+    ///
+    /// If `f` calls [`EventCtx::set_handled`], the event does not reach
+    /// this widget or its children.
+    ///
+    /// [`Capture`]: widget/struct.Capture.html
+    /// [`EventCtx::set_handled`]: struct.EventCtx.html#method.set_handled
+    fn capture(
+        self,
+        f: impl FnMut(&mut EventCtx, &Event, &mut T, &Env) + 'static,
+    ) -> ControllerHost<Self, Capture<T>> {
+        ControllerHost::new(self, Capture::new(f))
+    }
+
+    /// Inspect each event with `f` before this widget sees it, using a
+    /// [`Capture`] controller.
+    ///
+    /// If `f` returns `true`, the event is handled: it reaches neither this
+    /// widget nor any widget after it. This is shorthand for [`capture`]
+    /// with a closure that calls [`EventCtx::set_handled`].
+    ///
+    /// # Examples
+    ///
+    /// Keep the escape key away from a form:
+    ///
+    /// ```
+    /// use druid::widget::TextBox;
+    /// use druid::{Event, KeyCode, WidgetExt};
+    ///
+    /// let name = TextBox::new().on_event(|_ctx, event, _data: &mut String| {
+    ///     matches!(event, Event::KeyDown(key) if key.key_code == KeyCode::Escape)
+    /// });
+    /// ```
+    ///
+    /// [`Capture`]: widget/struct.Capture.html
+    /// [`capture`]: #method.capture
+    /// [`EventCtx::set_handled`]: struct.EventCtx.html#method.set_handled
+    fn on_event(
+        self,
+        f: impl Fn(&mut EventCtx, &Event, &mut T) -> bool + 'static,
+    ) -> ControllerHost<Self, Capture<T>> {
+        self.capture(move |ctx, event, data, _env| {
+            if f(ctx, event, data) {
+                ctx.set_handled();
+            }
+        })
+    }
+
+    /// Draw the [`layout`] `Rect`s of  this widget and its children, labelled
+    /// with their ids and types.
+    ///
+    /// In debug builds, Ctrl+Shift+F12 (Cmd+Shift+F12 on macOS) does this for
+    /// a whole window.
     ///
     /// [`layout`]: trait.Widget.html#tymethod.layout
     fn debug_paint_layout(self) -> EnvScope<T, Self> {
@@ -200,6 +310,15 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         LensWrap::new(self, lens)
     }
 
+    /// Wrap this widget in a [`Computed`] widget, which shows it the value
+    /// `compute` returns for the data, and only recomputes it when the data
+    /// changes.
+    ///
+    /// [`Computed`]: widget/struct.Computed.html
+    fn computed<S: Data>(self, compute: impl Fn(&S) -> T + 'static) -> Computed<S, T, Self> {
+        Computed::new(self, compute)
+    }
+
     /// Parse a `Widget<String>`'s contents
     fn parse(self) -> Parse<Self>
     where
@@ -221,7 +340,188 @@ pub trait WidgetExt<T: Data>: Widget<T> + Sized + 'static {
         IdentityWrapper::wrap(self, id)
     }
 
-    /// Wrap this widget in a `Box`.
+    /// Assign the widget a newly allocated [`WidgetId`].
+    ///
+    /// The id can be read back with [`IdentityWrapper::widget_id`], for
+    /// instance to use as the target of a command:
+    ///
+    /// ```
+    /// use druid::widget::{Button, Flex, Label};
+    /// use druid::{Selector, WidgetExt};
+    ///
+    /// const RESET: Selector = Selector::new("example.reset");
+    ///
+    /// let label = Label::new("hello").with_new_id();
+    /// let label_id = label.widget_id();
+    /// let reset = Button::<()>::new("reset")
+    ///     .on_click(move |ctx, _, _| ctx.submit_command(RESET, label_id));
+    /// let column = Flex::column().with_child(label).with_child(reset);
+    /// ```
+    ///
+    /// [`WidgetId`]: struct.WidgetId.html
+    /// [`IdentityWrapper::widget_id`]: widget/struct.IdentityWrapper.html#method.widget_id
+    fn with_new_id(self) -> IdentityWrapper<Self> {
+        IdentityWrapper::new(self)
+    }
+
+    /// Wrap this widget in a [`Scroll`], which scrolls it in both directions.
+    ///
+    /// Use [`Scroll::vertical`] or [`Scroll::horizontal`] on the result to
+    /// scroll in only one direction.
+    ///
+    /// [`Scroll`]: widget/struct.Scroll.html
+    /// [`Scroll::vertical`]: widget/struct.Scroll.html#method.vertical
+    /// [`Scroll::horizontal`]: widget/struct.Scroll.html#method.horizontal
+    fn scroll(self) -> Scroll<T, Self> {
+        Scroll::new(self)
+    }
+
+    /// Wrap this widget in a [`Cached`] widget, which records its painting
+    /// and reuses it until the widget requests a paint.
+    ///
+    /// [`Cached`]: widget/struct.Cached.html
+    fn cached(self) -> Cached<T, Self> {
+        Cached::new(self)
+    }
+
+    /// Wrap this widget in a [`Throttle`], which updates it with new data
+    /// at most once per `interval`.
+    ///
+    /// [`Throttle`]: widget/struct.Throttle.html
+    fn throttle(self, interval: Duration) -> Throttle<T, Self> {
+        Throttle::new(self, interval)
+    }
+
+    /// Wrap this widget in a debouncing [`Throttle`], which only updates it
+    /// with new data once the data has stopped changing for `interval`.
+    ///
+    /// [`Throttle`]: widget/struct.Throttle.html
+    fn debounce(self, interval: Duration) -> Throttle<T, Self> {
+        Throttle::debounce(self, interval)
+    }
+
+    /// Wrap this widget in a [`FocusStyled`] widget, which changes how it
+    /// shows that it has keyboard focus.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use druid::widget::{Button, FocusStyle};
+    /// # use druid::{Color, Widget, WidgetExt};
+    /// fn save_button() -> impl Widget<()> {
+    ///     Button::new("Save").with_focus_style(FocusStyle::Ring {
+    ///         color: Color::rgb8(0xff, 0xa0, 0x00),
+    ///         width: 1.0,
+    ///         offset: 1.0,
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`FocusStyled`]: widget/struct.FocusStyled.html
+    fn with_focus_style(self, style: FocusStyle) -> FocusStyled<T, Self> {
+        FocusStyled::new(self, style)
+    }
+
+    /// Wrap this widget in an [`Opacity`] widget, which paints it partially
+    /// transparent.
+    ///
+    /// The argument can be either an `f64` between `0.0` and `1.0`, or a
+    /// [`Key<f64>`].
+    ///
+    /// [`Opacity`]: widget/struct.Opacity.html
+    /// [`Key<f64>`]: struct.Key.html
+    fn with_opacity(self, opacity: impl Into<KeyOrValue<f64>>) -> Opacity<T, Self> {
+        Opacity::new(self, opacity)
+    }
+
+    /// Show a [`Badge`] in the top right corner of this widget, with the
+    /// text returned by `badge`, or no badge when it returns `None`.
+    ///
+    /// The badge is an overlay in a [`Stack`], so this widget is laid out
+    /// just as it would be without it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use druid::widget::Label;
+    /// # use druid::{Widget, WidgetExt};
+    /// fn timer_icon() -> impl Widget<u32> {
+    ///     Label::new("\u{23f1}").with_badge(|sessions: &u32| match sessions {
+    ///         0 => None,
+    ///         n => Some(n.to_string()),
+    ///     })
+    /// }
+    /// ```
+    ///
+    /// [`Badge`]: widget/struct.Badge.html
+    /// [`Stack`]: widget/struct.Stack.html
+    fn with_badge(self, badge: impl Fn(&T) -> Option<String> + 'static) -> Stack<T> {
+        Stack::new()
+            .with_loose_constraints(false)
+            .with_child(self)
+            .with_overlay(Badge::new(badge), UnitPoint::TOP_RIGHT)
+    }
+
+    /// Wrap this widget in a [`Hidden`] widget, which stops painting it, and
+    /// sending it mouse events, while `predicate` returns `true`.
+    ///
+    /// The widget keeps its space in the layout while it is hidden.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use druid::widget::Button;
+    /// # use druid::{Widget, WidgetExt};
+    /// fn stop_button() -> impl Widget<bool> {
+    ///     Button::new("Stop")
+    ///         .on_click(|_, running, _| *running = false)
+    ///         .hidden_if(|running, _| !*running)
+    /// }
+    /// ```
+    ///
+    /// [`Hidden`]: widget/struct.Hidden.html
+    fn hidden_if(self, predicate: impl Fn(&T, &Env) -> bool + 'static) -> Hidden<T, Self> {
+        Hidden::new(self, predicate)
+    }
+
+    /// Wrap this widget in a [`Transform`] widget, which paints it, and maps
+    /// mouse events to it, through `transform`.
+    ///
+    /// [`Transform`]: widget/struct.Transform.html
+    fn transformed(self, transform: Affine) -> Transform<T, Self> {
+        Transform::new(self, transform)
+    }
+
+    /// Wrap this widget in a `Box`, erasing its type.
+    ///
+    /// This lets widgets of different types be stored together, or returned
+    /// from different branches of a function. A boxed widget can be used
+    /// anywhere a widget can.
+    ///
+    /// Calls to a boxed widget go through a vtable, and it is stored on the
+    /// heap; this is cheap next to the work most widgets do, and most
+    /// containers box their children internally anyway. Boxing a widget
+    /// before adding it to such a container adds a second indirection.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use druid::widget::{Button, Flex, Label, Slider};
+    /// # use druid::{Widget, WidgetExt};
+    /// fn toolbar() -> impl Widget<f64> {
+    ///     let items: Vec<Box<dyn Widget<f64>>> = vec![
+    ///         Label::new("Volume").boxed(),
+    ///         Slider::new().boxed(),
+    ///         Button::new("Mute").on_click(|_, volume, _| *volume = 0.0).boxed(),
+    ///     ];
+    ///
+    ///     let mut row = Flex::row();
+    ///     for item in items {
+    ///         row.add_child(item);
+    ///     }
+    ///     row
+    /// }
+    /// ```
     fn boxed(self) -> Box<dyn Widget<T>> {
         Box::new(self)
     }
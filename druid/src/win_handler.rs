@@ -19,20 +19,21 @@ use std::cell::RefCell;
 use std::collections::{HashMap, VecDeque};
 use std::rc::Rc;
 
-use crate::kurbo::{Size, Vec2};
+use crate::kurbo::{Point, Rect, Size, Vec2};
 use crate::piet::Piet;
 use crate::shell::{
-    Application, FileDialogOptions, IdleToken, MouseEvent, WinHandler, WindowHandle,
+    Application, ColorScheme, FileDialogOptions, IdleToken, MouseEvent, WinHandler, WindowHandle,
 };
 
+use crate::app::EnvSource;
 use crate::app_delegate::{AppDelegate, DelegateCtx};
 use crate::core::CommandQueue;
 use crate::ext_event::ExtEventHost;
 use crate::menu::ContextMenu;
 use crate::window::Window;
 use crate::{
-    Command, Data, Env, Event, KeyEvent, KeyModifiers, MenuDesc, Target, TimerToken, WheelEvent,
-    WindowDesc, WindowId,
+    Command, Data, Env, Event, Executor, Gesture, GesturePan, GesturePinch, KeyEvent, KeyModifiers,
+    LifeCycle, MenuDesc, Target, TimerToken, WheelEvent, WindowDesc, WindowId,
 };
 
 use crate::command::sys as sys_cmd;
@@ -42,6 +43,18 @@ pub(crate) const RUN_COMMANDS_TOKEN: IdleToken = IdleToken::new(1);
 /// A token we are called back with if an external event was submitted.
 pub(crate) const EXT_EVENT_IDLE_TOKEN: IdleToken = IdleToken::new(2);
 
+/// A token we are called back with after a window loses focus, to check
+/// whether any of our windows has it now.
+const APP_ACTIVE_IDLE_TOKEN: IdleToken = IdleToken::new(3);
+
+/// How many rounds of commands and updates are run for one event before the
+/// rest are left for later, in case commands keep submitting more commands.
+const MAX_COMMAND_ROUNDS: usize = 64;
+
+/// How many closed windows are remembered, so that commands that were on
+/// their way to them when they closed are not mistaken for bugs.
+const MAX_CLOSED_WINDOWS: usize = 64;
+
 /// The struct implements the druid-shell `WinHandler` trait.
 ///
 /// One `DruidHandler` exists per window.
@@ -79,6 +92,22 @@ struct Inner<T> {
     /// the application-level menu, only set on macos and only if there
     /// are no open windows.
     root_menu: Option<MenuDesc<T>>,
+    /// Rebuilds `env` when the system color scheme changes; unset if the
+    /// app was not started by an `AppLauncher`.
+    env_source: Option<EnvSource<T>>,
+    /// The most recently reported system color scheme.
+    color_scheme: ColorScheme,
+    /// The window with focus, if it is one of ours.
+    focused_window: Option<WindowId>,
+    /// Whether the app was last reported active to the widgets.
+    app_active: bool,
+    /// The cap on animation frames per second, if any.
+    max_fps: Option<f64>,
+    executor: Option<Rc<dyn Executor>>,
+    /// Whether to log targeted commands that no widget handled.
+    log_unhandled_commands: bool,
+    /// The most recently closed windows, oldest first.
+    closed_windows: VecDeque<WindowId>,
     pub(crate) env: Env,
     pub(crate) data: T,
 }
@@ -95,7 +124,7 @@ impl<T> Windows<T> {
             let win = Window::new(id, handle, pending);
             assert!(self.windows.insert(id, win).is_none(), "duplicate window");
         } else {
-            log::error!("no window for connecting handle {:?}", id);
+            log::error!(target: "druid::window", "no window for connecting handle {:?}", id);
         }
     }
 
@@ -137,6 +166,14 @@ impl<T> AppState<T> {
             delegate,
             command_queue: VecDeque::new(),
             root_menu: None,
+            env_source: None,
+            color_scheme: ColorScheme::Light,
+            focused_window: None,
+            app_active: false,
+            max_fps: None,
+            executor: None,
+            log_unhandled_commands: false,
+            closed_windows: VecDeque::new(),
             ext_event_host,
             data,
             env,
@@ -162,6 +199,18 @@ impl<T: Data> Inner<T> {
         self.command_queue.push_back((target, cmd));
     }
 
+    /// Ask to be called back on idle, to handle the queued commands.
+    fn schedule_commands(&mut self) {
+        match self
+            .windows
+            .iter_mut()
+            .find_map(|win| win.handle.get_idle_handle())
+        {
+            Some(mut idle) => idle.schedule_idle(RUN_COMMANDS_TOKEN),
+            None => log::error!(target: "druid::window", "failed to get idle handle"),
+        }
+    }
+
     /// A helper fn for setting up the `DelegateCtx`. Takes a closure with
     /// an arbitrary return type `R`, and returns `Some(R)` if an `AppDelegate`
     /// is configured.
@@ -200,6 +249,11 @@ impl<T: Data> Inner<T> {
 
     fn connect(&mut self, id: WindowId, handle: WindowHandle) {
         self.windows.connect(id, handle);
+        if let Some(win) = self.windows.get_mut(id) {
+            win.set_max_fps(self.max_fps);
+            win.tasks.set_sink(self.ext_event_host.make_sink());
+            win.tasks.set_executor(self.executor.clone());
+        }
 
         // If the external event host has no handle, it cannot wake us
         // when an event arrives.
@@ -215,6 +269,13 @@ impl<T: Data> Inner<T> {
     /// We clean up resources and notifiy the delegate, if necessary.
     fn remove_window(&mut self, window_id: WindowId) {
         self.with_delegate(|del, data, env, ctx| del.window_removed(window_id, data, env, ctx));
+        if self.focused_window == Some(window_id) {
+            self.focused_window = None;
+        }
+        if self.closed_windows.len() == MAX_CLOSED_WINDOWS {
+            self.closed_windows.pop_front();
+        }
+        self.closed_windows.push_back(window_id);
         // when closing the last window:
         if let Some(mut win) = self.windows.remove(window_id) {
             if self.windows.windows.is_empty() {
@@ -251,6 +312,19 @@ impl<T: Data> Inner<T> {
         }
     }
 
+    /// Ask the delegate whether a window may be closed.
+    fn window_should_close(&mut self, window_id: WindowId) -> bool {
+        self.with_delegate(|del, data, env, ctx| del.window_should_close(window_id, data, env, ctx))
+            .unwrap_or(true)
+    }
+
+    /// Ask about each window in turn whether it may be closed, stopping at
+    /// the first that may not.
+    fn app_should_quit(&mut self) -> bool {
+        let ids: Vec<WindowId> = self.windows.windows.keys().copied().collect();
+        ids.into_iter().all(|id| self.window_should_close(id))
+    }
+
     /// triggered by a menu item or other command.
     ///
     /// This doesn't close the window; it calls the close method on the platform
@@ -268,53 +342,113 @@ impl<T: Data> Inner<T> {
         }
     }
 
+    /// Paint the part of the window inside `invalid_rect`, or all of it.
+    ///
     /// Returns `true` if an animation frame was requested.
-    fn paint(&mut self, window_id: WindowId, piet: &mut Piet) -> bool {
+    fn paint(&mut self, window_id: WindowId, piet: &mut Piet, invalid_rect: Option<Rect>) -> bool {
         if let Some(win) = self.windows.get_mut(window_id) {
-            win.do_paint(piet, &mut self.command_queue, &self.data, &self.env);
+            let invalid_rect = invalid_rect.unwrap_or_else(|| win.size.to_rect());
+            win.do_paint(
+                piet,
+                invalid_rect,
+                &mut self.command_queue,
+                &self.data,
+                &self.env,
+            );
             win.wants_animation_frame()
         } else {
             false
         }
     }
 
-    fn dispatch_cmd(&mut self, target: Target, cmd: Command) {
-        if !self.delegate_cmd(&target, &cmd) {
-            return;
-        }
-
+    /// Send `cmd` to `target`, returning `false` if the target could not
+    /// be found and the command was dropped.
+    fn dispatch_cmd(&mut self, target: Target, cmd: Command) -> bool {
         match target {
             Target::Window(id) => {
                 // first handle special window-level events
                 match cmd.selector {
-                    sys_cmd::SET_MENU => return self.set_menu(id, &cmd),
-                    sys_cmd::SHOW_CONTEXT_MENU => return self.show_context_menu(id, &cmd),
+                    sys_cmd::SET_MENU => {
+                        self.set_menu(id, &cmd);
+                        return true;
+                    }
+                    sys_cmd::SHOW_CONTEXT_MENU => {
+                        self.show_context_menu(id, &cmd);
+                        return true;
+                    }
                     _ => (),
                 }
                 if let Some(w) = self.windows.get_mut(id) {
-                    let event = Event::Command(cmd);
-                    w.event(&mut self.command_queue, event, &mut self.data, &self.env);
+                    let event = Event::Command(cmd.clone());
+                    if !w.event(&mut self.command_queue, event, &mut self.data, &self.env) {
+                        self.log_unhandled(target, &cmd);
+                    }
+                } else {
+                    log::warn!(
+                        target: "druid::command",
+                        "dropped command '{}' for missing {:?}",
+                        cmd.selector,
+                        id
+                    );
+                    debug_assert!(
+                        self.windows.pending.contains_key(&id) || self.closed_windows.contains(&id),
+                        "command '{}' targets {:?}, which is not a window of this app",
+                        cmd.selector,
+                        id
+                    );
+                    return false;
                 }
             }
             // in this case we send it to every window that might contain
-            // this widget, breaking if the event is handled.
+            // this widget, breaking if the event is handled. The filter
+            // has false positives, so whether the widget was found is only
+            // known once a window has delivered it.
             Target::Widget(id) => {
+                let mut found = false;
+                let mut handled = false;
                 for w in self.windows.iter_mut().filter(|w| w.may_contain_widget(id)) {
                     let event = Event::TargetedCommand(id.into(), cmd.clone());
-                    if w.event(&mut self.command_queue, event, &mut self.data, &self.env) {
+                    handled = w.event(&mut self.command_queue, event, &mut self.data, &self.env);
+                    found |= w.reached_target;
+                    if handled {
                         break;
                     }
                 }
+                if !found {
+                    log::warn!(
+                        target: "druid::command",
+                        "dropped command '{}' for missing {:?}",
+                        cmd.selector,
+                        id
+                    );
+                    return false;
+                } else if !handled {
+                    self.log_unhandled(target, &cmd);
+                }
             }
-            Target::Global => {
+            // broadcasts go to every window, even once one has handled them.
+            Target::Global | Target::Type(_) => {
                 for w in self.windows.iter_mut() {
-                    let event = Event::Command(cmd.clone());
-                    if w.event(&mut self.command_queue, event, &mut self.data, &self.env) {
-                        break;
-                    }
+                    let event = Event::TargetedCommand(target, cmd.clone());
+                    w.event(&mut self.command_queue, event, &mut self.data, &self.env);
                 }
             }
         }
+        true
+    }
+
+    /// Log a command that reached its target without being handled, if
+    /// the app asked for that.
+    fn log_unhandled(&self, target: Target, cmd: &Command) {
+        if self.log_unhandled_commands {
+            log::warn!(
+                target: "druid::command",
+                "command '{}' for {:?}, submitted from {}, was not handled",
+                cmd.selector,
+                target,
+                cmd.source_for_log()
+            );
+        }
     }
 
     fn do_window_event(&mut self, source_id: WindowId, event: Event) -> bool {
@@ -342,7 +476,7 @@ impl<T: Data> Inner<T> {
         if let Some(win) = self.windows.get_mut(window_id) {
             match cmd.get_object::<MenuDesc<T>>() {
                 Ok(menu) => win.set_menu(menu.to_owned(), &self.data, &self.env),
-                Err(e) => log::warn!("set-menu object error: '{}'", e),
+                Err(e) => log::warn!(target: "druid::command", "set-menu object error: '{}'", e),
             }
         }
     }
@@ -353,7 +487,9 @@ impl<T: Data> Inner<T> {
                 Ok(ContextMenu { menu, location }) => {
                     win.show_context_menu(menu.to_owned(), *location, &self.data, &self.env)
                 }
-                Err(e) => log::warn!("show-context-menu object error: '{}'", e),
+                Err(e) => {
+                    log::warn!(target: "druid::command", "show-context-menu object error: '{}'", e)
+                }
             }
         }
     }
@@ -361,7 +497,7 @@ impl<T: Data> Inner<T> {
     fn do_update(&mut self) {
         // we send `update` to all windows, not just the active one:
         for window in self.windows.iter_mut() {
-            window.update(&self.data, &self.env);
+            window.update(&mut self.command_queue, &self.data, &self.env);
         }
         self.invalidate_and_finalize();
     }
@@ -376,14 +512,75 @@ impl<T: Data> Inner<T> {
         }
     }
 
-    #[cfg(target_os = "macos")]
     fn window_got_focus(&mut self, window_id: WindowId) {
+        #[cfg(target_os = "macos")]
+        {
+            if let Some(win) = self.windows.get_mut(window_id) {
+                win.macos_update_app_menu(&self.data, &self.env)
+            }
+        }
+        // some platforms report this more than once.
+        let old = self.focused_window.replace(window_id);
+        if old != Some(window_id) {
+            if let Some(old) = old {
+                self.window_focus_changed(old, false);
+            }
+            self.window_focus_changed(window_id, true);
+        }
+        self.update_app_active();
+    }
+
+    fn window_lost_focus(&mut self, window_id: WindowId) {
+        if self.focused_window != Some(window_id) {
+            return;
+        }
+        self.focused_window = None;
+        self.window_focus_changed(window_id, false);
+        // when focus moves between our windows, the other window is told it
+        // has focus after this one loses it; wait for that before deciding
+        // that the app is inactive.
+        let idle = self
+            .windows
+            .get_mut(window_id)
+            .and_then(|win| win.handle.get_idle_handle());
+        match idle {
+            Some(mut idle) => idle.schedule_idle(APP_ACTIVE_IDLE_TOKEN),
+            None => self.update_app_active(),
+        }
+    }
+
+    fn window_visibility_changed(&mut self, window_id: WindowId, visible: bool) {
         if let Some(win) = self.windows.get_mut(window_id) {
-            win.macos_update_app_menu(&self.data, &self.env)
+            win.set_visible(visible, &mut self.command_queue, &self.data, &self.env);
+        }
+    }
+
+    fn window_scale_changed(&mut self, window_id: WindowId, scale: f64) {
+        if let Some(win) = self.windows.get_mut(window_id) {
+            win.set_scale(scale, &mut self.command_queue, &self.data, &self.env);
+        }
+    }
+
+    fn window_focus_changed(&mut self, window_id: WindowId, focused: bool) {
+        if let Some(win) = self.windows.get_mut(window_id) {
+            let event = LifeCycle::WindowFocusChanged(focused);
+            win.lifecycle(&mut self.command_queue, &event, &self.data, &self.env);
+        }
+    }
+
+    /// Tell the widgets in every window if the app has become active or
+    /// inactive since they were last told.
+    fn update_app_active(&mut self) {
+        let active = self.focused_window.is_some();
+        if active == self.app_active {
+            return;
+        }
+        self.app_active = active;
+        let event = LifeCycle::AppActiveChanged(active);
+        for win in self.windows.iter_mut() {
+            win.lifecycle(&mut self.command_queue, &event, &self.data, &self.env);
         }
     }
-    #[cfg(not(target_os = "macos"))]
-    fn window_got_focus(&mut self, _: WindowId) {}
 }
 
 impl<T: Data> DruidHandler<T> {
@@ -406,6 +603,28 @@ impl<T: Data> AppState<T> {
         self.inner.borrow().env.clone()
     }
 
+    /// Build the env from `source`, using the current system color scheme,
+    /// and keep `source` around to rebuild it when the scheme changes.
+    pub(crate) fn set_env_source(&mut self, source: EnvSource<T>) {
+        let scheme = Application::color_scheme();
+        let mut inner = self.inner.borrow_mut();
+        inner.env = source.build(scheme, &inner.data);
+        inner.color_scheme = scheme;
+        inner.env_source = Some(source);
+    }
+
+    pub(crate) fn set_log_unhandled_commands(&mut self, log: bool) {
+        self.inner.borrow_mut().log_unhandled_commands = log;
+    }
+
+    pub(crate) fn set_max_fps(&mut self, fps: Option<f64>) {
+        self.inner.borrow_mut().max_fps = fps;
+    }
+
+    pub(crate) fn set_executor(&mut self, executor: Option<Rc<dyn Executor>>) {
+        self.inner.borrow_mut().executor = executor;
+    }
+
     pub(crate) fn add_window(&self, id: WindowId, window: WindowDesc<T>) {
         self.inner.borrow_mut().windows.add(id, window);
     }
@@ -419,7 +638,59 @@ impl<T: Data> AppState<T> {
     }
 
     fn window_got_focus(&mut self, window_id: WindowId) {
-        self.inner.borrow_mut().window_got_focus(window_id)
+        self.inner.borrow_mut().window_got_focus(window_id);
+        self.process_commands_and_update();
+    }
+
+    fn window_lost_focus(&mut self, window_id: WindowId) {
+        self.inner.borrow_mut().window_lost_focus(window_id);
+        self.process_commands_and_update();
+    }
+
+    fn window_visibility_changed(&mut self, window_id: WindowId, visible: bool) {
+        self.inner
+            .borrow_mut()
+            .window_visibility_changed(window_id, visible);
+        self.process_commands_and_update();
+    }
+
+    fn window_scale_changed(&mut self, window_id: WindowId, scale: f64) {
+        self.inner
+            .borrow_mut()
+            .window_scale_changed(window_id, scale);
+        self.process_commands_and_update();
+    }
+
+    /// Called when the user asks the platform to close a window.
+    ///
+    /// Returns `true` if the window may close.
+    fn window_should_close(&mut self, window_id: WindowId) -> bool {
+        let result = self.inner.borrow_mut().window_should_close(window_id);
+        self.process_commands_and_update();
+        result
+    }
+
+    fn color_scheme_changed(&mut self, scheme: ColorScheme) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            // every window reports the change, but we only handle it once.
+            if inner.color_scheme == scheme {
+                return;
+            }
+            inner.color_scheme = scheme;
+            let inner = &mut *inner;
+            if let Some(source) = inner.env_source.as_ref() {
+                if source.fixed_scheme.is_none() {
+                    inner.env = source.build(scheme, &inner.data);
+                    for win in inner.windows.iter_mut() {
+                        win.handle.invalidate();
+                    }
+                }
+            }
+            let cmd = Command::new(sys_cmd::COLOR_SCHEME_CHANGED, scheme);
+            inner.append_command(Target::Global, cmd);
+        }
+        self.process_commands_and_update();
     }
 
     /// Send an event to the widget hierarchy.
@@ -430,32 +701,80 @@ impl<T: Data> AppState<T> {
     /// the OS needs to know if an event was handled.
     fn do_window_event(&mut self, event: Event, window_id: WindowId) -> bool {
         let result = self.inner.borrow_mut().do_window_event(window_id, event);
-        self.process_commands();
-        self.inner.borrow_mut().do_update();
+        self.process_commands_and_update();
         result
     }
 
-    fn paint_window(&mut self, window_id: WindowId, piet: &mut Piet) -> bool {
-        self.inner.borrow_mut().paint(window_id, piet)
+    fn paint_window(
+        &mut self,
+        window_id: WindowId,
+        piet: &mut Piet,
+        invalid_rect: Option<Rect>,
+    ) -> bool {
+        let (wants_frame, resize) = {
+            let mut inner = self.inner.borrow_mut();
+            let wants_frame = inner.paint(window_id, piet, invalid_rect);
+            let resize = inner.windows.get_mut(window_id).and_then(|win| {
+                let size = win.pending_resize.take()?;
+                Some((win.handle.clone(), size))
+            });
+            (wants_frame, resize)
+        };
+        // the platform may send us the new size right away, so we can't
+        // still be borrowed.
+        if let Some((handle, size)) = resize {
+            handle.set_size(size);
+        }
+        wants_frame
     }
 
     fn idle(&mut self, token: IdleToken) {
         match token {
             RUN_COMMANDS_TOKEN => {
-                self.process_commands();
-                self.inner.borrow_mut().invalidate_and_finalize();
+                self.process_commands_and_update();
             }
             EXT_EVENT_IDLE_TOKEN => {
                 self.process_ext_events();
-                self.process_commands();
-                self.inner.borrow_mut().do_update();
+                self.process_commands_and_update();
+            }
+            APP_ACTIVE_IDLE_TOKEN => {
+                self.inner.borrow_mut().update_app_active();
+                self.process_commands_and_update();
             }
-            other => log::warn!("unexpected idle token {:?}", other),
+            other => log::warn!(target: "druid::window", "unexpected idle token {:?}", other),
         }
     }
 
+    /// Deliver every queued command, then update the widgets.
+    ///
+    /// This finishes the handling of each event, so that the widgets have
+    /// seen the commands it caused before the window is next laid out and
+    /// painted. Commands submitted while handling commands, or by the
+    /// lifecycle events sent at the end of an update, are handled the same
+    /// way in another round, up to `MAX_COMMAND_ROUNDS`; after that they are
+    /// left for an idle callback, so that commands that keep resubmitting
+    /// themselves can't hang the app.
+    fn process_commands_and_update(&mut self) {
+        for _ in 0..MAX_COMMAND_ROUNDS {
+            self.process_commands();
+            self.inner.borrow_mut().do_update();
+            if self.inner.borrow().command_queue.is_empty() {
+                return;
+            }
+        }
+        log::warn!(
+            target: "druid::command",
+            "commands were still being submitted after {} rounds; deferring the rest",
+            MAX_COMMAND_ROUNDS
+        );
+        self.inner.borrow_mut().schedule_commands();
+    }
+
+    /// Handle the commands that are queued now; those they submit wait for
+    /// the next round.
     fn process_commands(&mut self) {
-        loop {
+        let count = self.inner.borrow().command_queue.len();
+        for _ in 0..count {
             let next_cmd = self.inner.borrow_mut().command_queue.pop_front();
             match next_cmd {
                 Some((target, cmd)) => self.handle_cmd(target, cmd),
@@ -485,24 +804,34 @@ impl<T: Data> AppState<T> {
         let target = window_id.map(Into::into).unwrap_or(Target::Global);
         match cmd {
             Some(cmd) => self.inner.borrow_mut().append_command(target, cmd),
-            None => log::warn!("No command for menu id {}", cmd_id),
+            None => log::warn!(target: "druid::command", "No command for menu id {}", cmd_id),
         }
-        self.process_commands();
-        self.inner.borrow_mut().do_update();
+        self.process_commands_and_update();
     }
 
     /// Handle a command. Top level commands (e.g. for creating and destroying
     /// windows) have their logic here; other commands are passed to the window.
+    ///
+    /// The delegate sees every command first, and can veto any of them.
     fn handle_cmd(&mut self, target: Target, cmd: Command) {
         use Target as T;
+        let cmd = cmd.delivered_to(target);
+        if !self.inner.borrow_mut().delegate_cmd(&target, &cmd) {
+            return;
+        }
         match (target, &cmd.selector) {
             // these are handled the same no matter where they  come from
             (_, &sys_cmd::QUIT_APP) => self.quit(),
             (_, &sys_cmd::HIDE_APPLICATION) => self.hide_app(),
             (_, &sys_cmd::HIDE_OTHERS) => self.hide_others(),
+            #[cfg(feature = "audio")]
+            (_, &sys_cmd::PLAY_SOUND) => match cmd.get_object::<crate::audio::SystemSound>() {
+                Ok(sound) => crate::audio::play(*sound),
+                Err(e) => log::warn!(target: "druid::command", "play-sound object error: '{}'", e),
+            },
             (_, &sys_cmd::NEW_WINDOW) => {
                 if let Err(e) = self.new_window(cmd) {
-                    log::error!("failed to create window: '{}'", e);
+                    log::error!(target: "druid::window", "failed to create window: '{}'", e);
                 }
             }
             // these should come from a window
@@ -510,9 +839,12 @@ impl<T: Data> AppState<T> {
             (T::Window(id), &sys_cmd::SHOW_OPEN_PANEL) => self.show_open_panel(cmd, id),
             (T::Window(id), &sys_cmd::SHOW_SAVE_PANEL) => self.show_save_panel(cmd, id),
             (T::Window(id), &sys_cmd::CLOSE_WINDOW) => self.request_close_window(cmd, id),
+            (T::Window(id), &sys_cmd::FORCE_CLOSE_WINDOW) => self.force_close_window(cmd, id),
             (T::Window(_), &sys_cmd::SHOW_WINDOW) => self.show_window(cmd),
             (T::Window(id), &sys_cmd::PASTE) => self.do_paste(id),
-            _sel => self.inner.borrow_mut().dispatch_cmd(target, cmd),
+            _sel => {
+                self.inner.borrow_mut().dispatch_cmd(target, cmd);
+            }
         }
     }
 
@@ -534,7 +866,8 @@ impl<T: Data> AppState<T> {
         let result = handle.and_then(|mut handle| handle.open_file_sync(options));
         if let Some(info) = result {
             let cmd = Command::new(sys_cmd::OPEN_FILE, info);
-            self.inner.borrow_mut().dispatch_cmd(window_id.into(), cmd);
+            // like any other command, the delegate sees it first.
+            self.handle_cmd(window_id.into(), cmd);
         }
     }
 
@@ -552,7 +885,8 @@ impl<T: Data> AppState<T> {
         let result = handle.and_then(|mut handle| handle.save_as_sync(options));
         if let Some(info) = result {
             let cmd = Command::new(sys_cmd::SAVE_FILE, info);
-            self.inner.borrow_mut().dispatch_cmd(window_id.into(), cmd);
+            // like any other command, the delegate sees it first.
+            self.handle_cmd(window_id.into(), cmd);
         }
     }
 
@@ -564,8 +898,16 @@ impl<T: Data> AppState<T> {
     }
 
     fn request_close_window(&mut self, cmd: Command, window_id: WindowId) {
-        let id = cmd.get_object().unwrap_or(&window_id);
-        self.inner.borrow_mut().request_close_window(*id);
+        let id = *cmd.get_object().unwrap_or(&window_id);
+        let mut inner = self.inner.borrow_mut();
+        if inner.window_should_close(id) {
+            inner.request_close_window(id);
+        }
+    }
+
+    fn force_close_window(&mut self, cmd: Command, window_id: WindowId) {
+        let id = *cmd.get_object().unwrap_or(&window_id);
+        self.inner.borrow_mut().request_close_window(id);
     }
 
     fn show_window(&mut self, cmd: Command) {
@@ -580,8 +922,10 @@ impl<T: Data> AppState<T> {
         self.inner.borrow_mut().do_window_event(window_id, event);
     }
 
-    fn quit(&self) {
-        Application::quit()
+    fn quit(&mut self) {
+        if self.inner.borrow_mut().app_should_quit() {
+            Application::quit()
+        }
     }
 
     fn hide_app(&self) {
@@ -611,7 +955,12 @@ impl<T: Data> WinHandler for DruidHandler<T> {
     }
 
     fn paint(&mut self, piet: &mut Piet) -> bool {
-        self.app_state.paint_window(self.window_id, piet)
+        self.app_state.paint_window(self.window_id, piet, None)
+    }
+
+    fn paint_rect(&mut self, piet: &mut Piet, invalid_rect: Rect) -> bool {
+        self.app_state
+            .paint_window(self.window_id, piet, Some(invalid_rect))
     }
 
     fn size(&mut self, width: u32, height: u32) {
@@ -659,10 +1008,40 @@ impl<T: Data> WinHandler for DruidHandler<T> {
         self.app_state.do_window_event(event, self.window_id);
     }
 
+    fn pinch(&mut self, scale: f64, center: Point) {
+        let event = Event::Gesture(Gesture::Pinch(GesturePinch { scale, center }));
+        // widgets written before gestures existed still expect a zoom.
+        if !self.app_state.do_window_event(event, self.window_id) {
+            self.zoom(scale - 1.0);
+        }
+    }
+
+    fn pan(&mut self, delta: Vec2, center: Point) {
+        let event = Event::Gesture(Gesture::Pan(GesturePan { delta, center }));
+        self.app_state.do_window_event(event, self.window_id);
+    }
+
     fn got_focus(&mut self) {
         self.app_state.window_got_focus(self.window_id);
     }
 
+    fn lost_focus(&mut self) {
+        self.app_state.window_lost_focus(self.window_id);
+    }
+
+    fn visibility_changed(&mut self, visible: bool) {
+        self.app_state
+            .window_visibility_changed(self.window_id, visible);
+    }
+
+    fn scale_changed(&mut self, scale: f64) {
+        self.app_state.window_scale_changed(self.window_id, scale);
+    }
+
+    fn color_scheme_changed(&mut self, scheme: ColorScheme) {
+        self.app_state.color_scheme_changed(scheme);
+    }
+
     fn timer(&mut self, token: TimerToken) {
         self.app_state
             .do_window_event(Event::Timer(token), self.window_id);
@@ -676,6 +1055,10 @@ impl<T: Data> WinHandler for DruidHandler<T> {
         self
     }
 
+    fn request_close(&mut self) -> bool {
+        self.app_state.window_should_close(self.window_id)
+    }
+
     fn destroy(&mut self) {
         self.app_state.remove_window(self.window_id);
     }
@@ -689,3 +1072,219 @@ impl<T> Default for Windows<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::tests::ModularWidget;
+    use crate::theme;
+    use crate::widget::{Flex, Label, WidgetExt};
+    use crate::{Selector, Widget, WidgetId};
+
+    /// Counts the commands with `selector` that reach it.
+    struct CountingDelegate {
+        selector: Selector,
+        seen: Rc<Cell<usize>>,
+    }
+
+    impl<T: Data> AppDelegate<T> for CountingDelegate {
+        fn command(
+            &mut self,
+            _ctx: &mut DelegateCtx,
+            _target: &Target,
+            cmd: &Command,
+            _data: &mut T,
+            _env: &Env,
+        ) -> bool {
+            if cmd.is(self.selector) {
+                self.seen.set(self.seen.get() + 1);
+                return false;
+            }
+            true
+        }
+    }
+
+    /// A root widget that counts the commands with `selector` it receives.
+    fn counting_root(selector: Selector, seen: Rc<Cell<usize>>) -> impl Widget<()> {
+        ModularWidget::new(seen).event_fn(move |seen, _ctx, event, _data, _env| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(selector) {
+                    seen.set(seen.get() + 1);
+                }
+            }
+        })
+    }
+
+    /// An app with a single connected window, whose handle is not backed
+    /// by a platform window.
+    fn app_with_window<T: Data>(
+        data: T,
+        root: impl Widget<T> + 'static,
+        delegate: Option<Box<dyn AppDelegate<T>>>,
+    ) -> (AppState<T>, WindowId) {
+        let state = AppState::new(data, theme::init(), delegate, ExtEventHost::new());
+        let desc = WindowDesc::new(move || root);
+        let id = desc.id;
+        state.add_window(id, desc);
+        DruidHandler::new_shared(state.clone(), id).connect(&WindowHandle::default());
+        (state, id)
+    }
+
+    #[test]
+    fn delegate_can_intercept_open_file() {
+        let (to_delegate, to_window) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let delegate = CountingDelegate {
+            selector: sys_cmd::OPEN_FILE,
+            seen: to_delegate.clone(),
+        };
+        let root = counting_root(sys_cmd::OPEN_FILE, to_window.clone());
+        let (mut state, id) = app_with_window((), root, Some(Box::new(delegate)));
+
+        // the open panel delivers its result the same way.
+        state.handle_cmd(id.into(), Command::new(sys_cmd::OPEN_FILE, ()));
+        assert_eq!((to_delegate.get(), to_window.get()), (1, 0));
+    }
+
+    #[test]
+    fn commands_for_missing_widgets_are_dropped() {
+        const PING: Selector = Selector::new("druid-tests.ping");
+        let ids: Vec<WidgetId> = (0..20).map(|_| WidgetId::next()).collect();
+        let mut row = Flex::row();
+        for id in &ids {
+            row.add_child(Label::new("").with_id(*id));
+        }
+        let (state, window_id) = app_with_window((), row, None);
+        let mut inner = state.inner.borrow_mut();
+        assert!(inner.dispatch_cmd(ids[3].into(), Command::new(PING, ())));
+
+        // an id the window's filter can't rule out, though it isn't there.
+        let missing = (0..10_000)
+            .map(|_| WidgetId::next())
+            .find(|id| {
+                inner
+                    .windows
+                    .get(window_id)
+                    .unwrap()
+                    .may_contain_widget(*id)
+            })
+            .expect("no false positive in the filter");
+        assert!(!inner.dispatch_cmd(missing.into(), Command::new(PING, ())));
+    }
+
+    #[test]
+    fn command_changes_are_updated_before_returning() {
+        const INCREMENT: Selector = Selector::new("druid-tests.increment");
+        let updated = Rc::new(Cell::new(0));
+        let root = ModularWidget::new(updated.clone())
+            .event_fn(|_, ctx, event, data: &mut u32, _| {
+                if let Event::Command(cmd) = event {
+                    // each command submits the next, so this takes rounds.
+                    if cmd.is(INCREMENT) && *data < 3 {
+                        *data += 1;
+                        ctx.submit_command(INCREMENT, None);
+                    }
+                }
+            })
+            .update_fn(|updated, _, _, data, _| updated.set(*data));
+        let (mut state, id) = app_with_window(0u32, root, None);
+
+        state
+            .inner
+            .borrow_mut()
+            .append_command(id.into(), Command::new(INCREMENT, ()));
+        state.process_commands_and_update();
+        assert_eq!(updated.get(), 3);
+        assert!(state.inner.borrow().command_queue.is_empty());
+    }
+
+    #[test]
+    fn resubmitted_commands_are_deferred() {
+        const AGAIN: Selector = Selector::new("druid-tests.again");
+        let root = ModularWidget::new(()).event_fn(|_, ctx, event, _: &mut (), _| {
+            if let Event::Command(cmd) = event {
+                if cmd.is(AGAIN) {
+                    ctx.submit_command(AGAIN, None);
+                }
+            }
+        });
+        let (mut state, id) = app_with_window((), root, None);
+
+        state
+            .inner
+            .borrow_mut()
+            .append_command(id.into(), Command::new(AGAIN, ()));
+        state.process_commands_and_update();
+        assert_eq!(state.inner.borrow().command_queue.len(), 1);
+    }
+
+    /// Keeps every window open, counting how often it was asked.
+    struct VetoingDelegate {
+        asked: Rc<Cell<usize>>,
+    }
+
+    impl<T: Data> AppDelegate<T> for VetoingDelegate {
+        fn window_should_close(
+            &mut self,
+            _id: WindowId,
+            _data: &mut T,
+            _env: &Env,
+            _ctx: &mut DelegateCtx,
+        ) -> bool {
+            self.asked.set(self.asked.get() + 1);
+            false
+        }
+    }
+
+    #[test]
+    fn windows_can_veto_quitting() {
+        let asked = Rc::new(Cell::new(0));
+        let delegate = VetoingDelegate {
+            asked: asked.clone(),
+        };
+        let root = counting_root(sys_cmd::QUIT_APP, Rc::new(Cell::new(0)));
+        let (mut state, id) = app_with_window((), root, Some(Box::new(delegate)));
+
+        state.handle_cmd(Target::Global, Command::new(sys_cmd::QUIT_APP, ()));
+        assert_eq!(asked.get(), 1);
+        assert!(state.inner.borrow().windows.get(id).is_some());
+    }
+
+    /// Keeps a window open the first time it is asked, then confirms the
+    /// close with `FORCE_CLOSE_WINDOW`.
+    struct ConfirmingDelegate {
+        asked: Rc<Cell<usize>>,
+    }
+
+    impl<T: Data> AppDelegate<T> for ConfirmingDelegate {
+        fn window_should_close(
+            &mut self,
+            id: WindowId,
+            _data: &mut T,
+            _env: &Env,
+            ctx: &mut DelegateCtx,
+        ) -> bool {
+            self.asked.set(self.asked.get() + 1);
+            ctx.submit_command(Command::new(sys_cmd::FORCE_CLOSE_WINDOW, id), id);
+            false
+        }
+    }
+
+    #[test]
+    fn vetoed_close_can_be_forced() {
+        let (asked, seen) = (Rc::new(Cell::new(0)), Rc::new(Cell::new(0)));
+        let delegate = ConfirmingDelegate {
+            asked: asked.clone(),
+        };
+        let root = counting_root(sys_cmd::FORCE_CLOSE_WINDOW, seen.clone());
+        let (mut state, id) = app_with_window((), root, Some(Box::new(delegate)));
+
+        state.handle_cmd(id.into(), Command::new(sys_cmd::CLOSE_WINDOW, id));
+        state.process_commands_and_update();
+        // the forced close is acted on by druid, without asking again.
+        assert_eq!(asked.get(), 1);
+        assert_eq!(seen.get(), 0);
+        assert!(state.inner.borrow().command_queue.is_empty());
+    }
+}
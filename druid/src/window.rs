@@ -14,18 +14,32 @@
 
 //! Management of multiple windows.
 
+use std::cell::RefCell;
 use std::mem;
-use std::time::Instant;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
 
-use crate::kurbo::{Insets, Point, Rect, Size};
-use crate::piet::{Piet, RenderContext};
+use crate::kurbo::{Insets, Point, Rect, Size, Vec2};
+use crate::piet::{Color, Piet, RenderContext};
 use crate::shell::{Counter, Cursor, WindowHandle};
 
+use crate::app::clamp_window_size;
+use crate::clock::Clock;
+use crate::command::sys as sys_cmd;
 use crate::core::{BaseState, CommandQueue, FocusChange};
+use crate::debug_overlay::{self, DebugBox};
+use crate::inspect::{TreeCell, WidgetTree};
+use crate::recording::{EventRecorder, EventReplay};
+use crate::stats::{self, FrameStats};
+use crate::task::TaskHost;
+use crate::theme;
+use crate::toast::ToastLayer;
 use crate::win_handler::RUN_COMMANDS_TOKEN;
 use crate::{
-    BoxConstraints, Command, Data, Env, Event, EventCtx, LayoutCtx, LifeCycle, LifeCycleCtx,
-    LocalizedString, MenuDesc, PaintCtx, UpdateCtx, Widget, WidgetId, WidgetPod, WindowDesc,
+    BoxConstraints, Command, Data, Env, Event, EventCtx, Gesture, GesturePinch, HotKey, KeyCode,
+    KeyEvent, KeyOrValue, LayoutCtx, LifeCycle, LifeCycleCtx, LocalizedString, MenuDesc,
+    MouseGesture, PaintCtx, SysMods, Target, TimerToken, Toast, UpdateCtx, WheelEvent, Widget,
+    WidgetId, WidgetPod, WindowDesc,
 };
 
 /// A unique identifier for a window.
@@ -37,35 +51,140 @@ pub struct Window<T> {
     pub(crate) id: WindowId,
     pub(crate) root: WidgetPod<T, Box<dyn Widget<T>>>,
     pub(crate) title: LocalizedString<T>,
-    size: Size,
+    pub(crate) size: Size,
+    /// The limits on `size`, from the `WindowDesc`.
+    pub(crate) min_size: Option<Size>,
+    pub(crate) max_size: Option<Size>,
+    /// Set when the window should be resized to fit the root widget at the
+    /// next layout.
+    pub(crate) size_to_content: bool,
+    /// The size the platform window should be given, once we are done painting.
+    pub(crate) pending_resize: Option<Size>,
     pub(crate) menu: Option<MenuDesc<T>>,
+    /// Overrides the theme's window background.
+    pub(crate) background: Option<KeyOrValue<Color>>,
     pub(crate) context_menu: Option<MenuDesc<T>>,
     pub(crate) last_anim: Option<Instant>,
+    pub(crate) last_frame: Option<Instant>,
+    pub(crate) frame_stats: FrameStats,
     pub(crate) focus: Option<WidgetId>,
     pub(crate) handle: WindowHandle,
+    pub(crate) toasts: ToastLayer,
+    /// `false` while the window is minimized or occluded.
+    pub(crate) visible: bool,
+    /// The number of pixels per display point.
+    pub(crate) scale: f64,
+    /// Set if the debug overlay was toggled with its hotkey, which inverts
+    /// `Env::DEBUG_PAINT` for the root widget.
+    debug_overlay_toggled: bool,
+    /// Set while alt is held, to underline access keys.
+    show_access_keys: bool,
+    /// The shortest time between animation frames, if they are capped.
+    min_frame_interval: Option<Duration>,
+    /// The timer that will wake us for the next capped animation frame.
+    anim_timer: Option<TimerToken>,
+    /// Where and when the mouse button was pressed, while it is held.
+    press: Option<Press>,
+    /// The last position of the mouse, in window coordinates.
+    mouse_pos: Option<Point>,
+    /// Set by the test harness, to intercept timer requests.
+    pub(crate) clock: Option<Rc<dyn Clock>>,
+    /// Set by the test harness, to keep the widgets painted by the last
+    /// paint, with their bounds in window coordinates.
+    pub(crate) paint_log: Option<Vec<DebugBox>>,
+    /// Set by the test harness, to keep copied text off the system clipboard.
+    pub(crate) mock_clipboard: Option<RefCell<Option<String>>>,
+    /// The tasks started with `EventCtx::spawn`, which are cancelled when
+    /// this window is dropped.
+    pub(crate) tasks: TaskHost,
+    pub(crate) recorder: Option<EventRecorder>,
+    replay: Option<EventReplay>,
+    /// Set if the last event was a command for a widget, and that widget is
+    /// in this window.
+    pub(crate) reached_target: bool,
+    /// A snapshot of the widgets for `EventCtx::widget_id_at`, taken before
+    /// the first event after each layout or paint.
+    tree_snapshot: Option<Rc<WidgetTree>>,
     // delegate?
 }
 
 impl<T> Window<T> {
     pub(crate) fn new(id: WindowId, handle: WindowHandle, desc: WindowDesc<T>) -> Window<T> {
+        let recorder = desc.record_path.and_then(|path| {
+            EventRecorder::to_file(&path)
+                .map_err(|e| {
+                    log::error!(
+                        target: "druid::window",
+                        "failed to record to {}: {}",
+                        path.display(),
+                        e
+                    )
+                })
+                .ok()
+        });
         Window {
             id,
             root: WidgetPod::new(desc.root),
             size: Size::ZERO,
+            min_size: desc.min_size,
+            max_size: desc.max_size,
+            size_to_content: desc.auto_size,
+            pending_resize: None,
             title: desc.title,
             menu: desc.menu,
+            background: desc.background,
             context_menu: None,
             last_anim: None,
+            last_frame: None,
+            frame_stats: FrameStats::default(),
             focus: None,
             handle,
+            toasts: ToastLayer::default(),
+            visible: true,
+            scale: 1.0,
+            debug_overlay_toggled: false,
+            show_access_keys: false,
+            press: None,
+            mouse_pos: None,
+            min_frame_interval: None,
+            anim_timer: None,
+            clock: None,
+            paint_log: None,
+            mock_clipboard: None,
+            tasks: TaskHost::default(),
+            recorder,
+            replay: desc.replay.map(EventReplay::new),
+            reached_target: false,
+            tree_snapshot: None,
         }
     }
 }
 
 impl<T: Data> Window<T> {
-    /// `true` iff any child requested an animation frame during the last `AnimFrame` event.
-    pub(crate) fn wants_animation_frame(&self) -> bool {
-        self.last_anim.is_some()
+    /// `true` iff any child requested an animation frame during the last
+    /// `AnimFrame` event, and it should be delivered as soon as possible.
+    ///
+    /// If the frame rate is capped, the next frame is instead scheduled
+    /// with a timer, and this returns `false`.
+    pub(crate) fn wants_animation_frame(&mut self) -> bool {
+        let last = match self.last_anim {
+            Some(last) => last,
+            None => return false,
+        };
+        match self.min_frame_interval {
+            Some(interval) => {
+                if self.anim_timer.is_none() {
+                    self.anim_timer = Some(self.request_timer(last + interval));
+                }
+                false
+            }
+            None => true,
+        }
+    }
+
+    /// Limit animation frames to `fps` per second, or remove the limit.
+    pub(crate) fn set_max_fps(&mut self, fps: Option<f64>) {
+        self.min_frame_interval = fps.map(|fps| Duration::from_secs_f64(1.0 / fps));
     }
 
     pub(crate) fn focus_chain(&self) -> &[WidgetId] {
@@ -110,49 +229,122 @@ impl<T: Data> Window<T> {
         data: &mut T,
         env: &Env,
     ) -> bool {
+        enter_span!(DEBUG, "event", window = ?self.id);
+        self.reached_target = false;
+        let now = self.now();
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record(now, &event);
+        }
+
         let mut cursor = match event {
             Event::MouseMoved(..) => Some(Cursor::Arrow),
             _ => None,
         };
 
-        let event = match event {
+        let mut event = match event {
             Event::Size(size) => {
                 let dpi = f64::from(self.handle.get_dpi());
                 let scale = 96.0 / dpi;
-                self.size = Size::new(size.width * scale, size.height * scale);
+                let size = Size::new(size.width * scale, size.height * scale);
+                // not every platform enforces the limits, e.g. when tiling.
+                self.size = clamp_window_size(size, self.min_size, self.max_size);
                 Event::Size(self.size)
             }
             other => other,
         };
+        self.recognize_gesture(&mut event, now, env);
 
         if let Event::WindowConnected = event {
             self.lifecycle(queue, &LifeCycle::RouteWidgetAdded, data, env);
+            let scale = f64::from(self.handle.get_dpi()) / 96.0;
+            self.set_scale(scale, queue, data, env);
+            let now = self.now();
+            if let Some(replay) = self.replay.as_mut() {
+                replay.start(now);
+                self.schedule_replay();
+            }
+        }
+
+        if self.toast_event(&event, env) {
+            return true;
+        }
+
+        match &event {
+            Event::Command(cmd) | Event::TargetedCommand(Target::Window(_), cmd)
+                if cmd.is(sys_cmd::DUMP_WIDGET_TREE) =>
+            {
+                let tree = self.widget_tree(queue, data, env);
+                log::info!(target: "druid::window", "widget tree of {:?}:\n{}", self.id, tree);
+                return true;
+            }
+            Event::Command(cmd)
+            | Event::TargetedCommand(Target::Window(_), cmd)
+            | Event::TargetedCommand(Target::Global, cmd)
+                if cmd.is(sys_cmd::REQUEST_PAINT) =>
+            {
+                self.handle.invalidate();
+                return true;
+            }
+            Event::Command(cmd)
+            | Event::TargetedCommand(Target::Window(_), cmd)
+            | Event::TargetedCommand(Target::Global, cmd)
+                if cmd.is(sys_cmd::REQUEST_LAYOUT) =>
+            {
+                self.lifecycle(queue, &LifeCycle::RouteRequestLayout, data, env);
+                self.handle.invalidate();
+                return true;
+            }
+            Event::Command(cmd) | Event::TargetedCommand(Target::Window(_), cmd)
+                if cmd.is(sys_cmd::SIZE_WINDOW_TO_CONTENT) =>
+            {
+                self.size_to_content = true;
+                self.handle.invalidate();
+                return true;
+            }
+            _ => (),
+        }
+
+        if let Some(show) = shows_access_keys(&event) {
+            if show != self.show_access_keys {
+                self.show_access_keys = show;
+                self.handle.invalidate();
+            }
+        }
+
+        #[cfg(debug_assertions)]
+        {
+            if let Event::KeyDown(key) = &event {
+                if debug_overlay::is_toggle_key(key) {
+                    self.debug_overlay_toggled = !self.debug_overlay_toggled;
+                    self.handle.invalidate();
+                    return true;
+                }
+            }
+        }
+
+        if let Event::Timer(token) = event {
+            if self.anim_timer == Some(token) {
+                self.anim_timer = None;
+                self.handle.invalidate();
+                return true;
+            }
+            if self.replay.as_ref().and_then(|r| r.timer) == Some(token) {
+                self.replay_due_events(queue, data, env);
+                return true;
+            }
         }
 
+        // the snapshot is as of the last layout and paint, so it is only
+        // collected once for all the events in between.
+        if self.tree_snapshot.is_none() {
+            self.tree_snapshot = Some(Rc::new(self.widget_tree(queue, data, env)));
+        }
         let mut base_state = BaseState::new(self.root.id());
-        let is_handled = {
-            let mut ctx = EventCtx {
-                cursor: &mut cursor,
-                command_queue: queue,
-                base_state: &mut base_state,
-                is_handled: false,
-                is_root: true,
-                had_active: self.root.has_active(),
-                window: &self.handle,
-                window_id: self.id,
-                focus_widget: self.focus,
-            };
-
-            self.root.event(&mut ctx, &event, data, env);
-            ctx.is_handled
-        };
+        let is_handled = self.root_event(queue, &event, data, env, &mut base_state, &mut cursor);
 
         if let Some(focus_req) = base_state.request_focus.take() {
-            let old = self.focus;
             let new = self.widget_for_focus_request(focus_req);
-            let event = LifeCycle::RouteFocusChanged { old, new };
-            self.lifecycle(queue, &event, data, env);
-            self.focus = new;
+            self.set_focus(queue, new, data, env);
         }
 
         if let Some(cursor) = cursor {
@@ -165,9 +357,189 @@ impl<T: Data> Window<T> {
             self.lifecycle(queue, &LifeCycle::RouteWidgetAdded, data, env);
         }
 
+        if !is_handled {
+            if let Event::KeyDown(key) = &event {
+                // with nothing focused, no widget is there to move the focus on.
+                if let Some(change) = tab_focus_change(key).filter(|_| self.focus.is_none()) {
+                    let new = self.widget_for_focus_request(change);
+                    self.set_focus(queue, new, data, env);
+                    return new.is_some();
+                }
+                // without an Edit menu, nothing else turns the hotkey into the command.
+                if let Some(focus) = self.focus.filter(|_| is_copy_hotkey(key)) {
+                    let copy = Event::TargetedCommand(focus.into(), sys_cmd::COPY.into());
+                    return self.event(queue, copy, data, env);
+                }
+                if let Some(access_key) = access_key(key) {
+                    return self.activate_access_key(queue, access_key, data, env);
+                }
+            }
+            if let Event::Wheel(wheel) = &event {
+                if let Some(pinch) = wheel_pinch(wheel, self.mouse_pos) {
+                    // the wheel event was recorded; replaying it pinches again.
+                    let recorder = self.recorder.take();
+                    let is_handled = self.event(queue, Event::Gesture(pinch), data, env);
+                    self.recorder = recorder;
+                    return is_handled;
+                }
+            }
+        }
+
+        is_handled
+    }
+
+    /// Send `event` to the root widget, with none of the window's own
+    /// handling.
+    fn root_event(
+        &mut self,
+        queue: &mut CommandQueue,
+        event: &Event,
+        data: &mut T,
+        env: &Env,
+        base_state: &mut BaseState,
+        cursor: &mut Option<Cursor>,
+    ) -> bool {
+        let is_broadcast = matches!(
+            event,
+            Event::TargetedCommand(Target::Global, _) | Event::TargetedCommand(Target::Type(_), _)
+        );
+        let tree = self.tree_snapshot.clone();
+        let mut ctx = EventCtx {
+            cursor,
+            command_queue: queue,
+            base_state,
+            is_handled: false,
+            scroll_remainder: None,
+            is_broadcast,
+            reached_target: false,
+            is_root: true,
+            had_active: self.root.has_active(),
+            window: &self.handle,
+            clock: self.clock.as_deref(),
+            mock_clipboard: self.mock_clipboard.as_ref(),
+            tasks: &mut self.tasks,
+            window_id: self.id,
+            focus_widget: self.focus,
+            window_visible: self.visible,
+            widget_tree: tree.as_deref(),
+        };
+        self.root.event(&mut ctx, event, data, env);
+        let is_handled = ctx.is_handled;
+        self.reached_target = ctx.reached_target;
         is_handled
     }
 
+    /// Send the replayed events that are due, and wait for the next one.
+    fn replay_due_events(&mut self, queue: &mut CommandQueue, data: &mut T, env: &Env) {
+        let now = self.now();
+        let due = match self.replay.as_mut() {
+            Some(replay) => replay.take_due(now),
+            None => return,
+        };
+        for event in due {
+            self.event(queue, event, data, env);
+        }
+        self.schedule_replay();
+    }
+
+    fn schedule_replay(&mut self) {
+        let deadline = self.replay.as_ref().and_then(|r| r.next_deadline());
+        let timer = deadline.map(|deadline| self.request_timer(deadline));
+        match (self.replay.as_mut(), timer) {
+            (Some(replay), Some(timer)) => replay.timer = Some(timer),
+            _ => self.replay = None,
+        }
+    }
+
+    /// Fill in the gesture of a mouse event, from the movement since the
+    /// button was pressed.
+    fn recognize_gesture(&mut self, event: &mut Event, now: Instant, env: &Env) {
+        let mouse = match event {
+            Event::MouseDown(mouse) => {
+                let pos = mouse.window_pos;
+                self.press.get_or_insert(Press {
+                    pos,
+                    time: now,
+                    is_drag: false,
+                });
+                mouse
+            }
+            Event::MouseMoved(mouse) | Event::MouseUp(mouse) => mouse,
+            _ => return,
+        };
+        self.mouse_pos = Some(mouse.window_pos);
+        mouse.gesture = match self.press.as_mut() {
+            Some(press) => {
+                if !press.is_drag {
+                    let delay = Duration::from_millis(env.get(theme::DRAG_DELAY));
+                    let threshold = if now - press.time >= delay {
+                        0.0
+                    } else {
+                        env.get(theme::DRAG_THRESHOLD)
+                    };
+                    press.is_drag = (mouse.window_pos - press.pos).hypot() > threshold;
+                }
+                if press.is_drag {
+                    MouseGesture::Drag
+                } else {
+                    MouseGesture::Press
+                }
+            }
+            None => MouseGesture::Hover,
+        };
+        if let Event::MouseUp(_) = event {
+            self.press = None;
+        }
+    }
+
+    fn set_focus(&mut self, queue: &mut CommandQueue, new: Option<WidgetId>, data: &T, env: &Env) {
+        let old = self.focus;
+        let event = LifeCycle::RouteFocusChanged { old, new };
+        self.lifecycle(queue, &event, data, env);
+        self.focus = new;
+    }
+
+    /// Focus and activate the widget with the access key `key`. If several
+    /// widgets have it, focus moves to the next of them instead, and none is
+    /// activated.
+    ///
+    /// Returns `false` if no widget has the key.
+    fn activate_access_key(
+        &mut self,
+        queue: &mut CommandQueue,
+        key: char,
+        data: &mut T,
+        env: &Env,
+    ) -> bool {
+        let matches: Vec<WidgetId> = self
+            .root
+            .state()
+            .access_keys
+            .iter()
+            .filter(|(k, _)| *k == key)
+            .map(|(_, id)| *id)
+            .collect();
+        if matches.is_empty() {
+            return false;
+        }
+
+        let next = self
+            .focus
+            .and_then(|focus| matches.iter().position(|id| *id == focus))
+            .map(|idx| (idx + 1) % matches.len())
+            .unwrap_or(0);
+        let target = matches[next];
+        if self.focus != Some(target) {
+            self.set_focus(queue, Some(target), data, env);
+        }
+        if matches.len() == 1 {
+            let cmd = Command::from(sys_cmd::ACTIVATE_ACCESS_KEY);
+            let event = Event::TargetedCommand(Target::Widget(target), cmd);
+            self.event(queue, event, data, env);
+        }
+        true
+    }
+
     pub(crate) fn lifecycle(
         &mut self,
         queue: &mut CommandQueue,
@@ -175,11 +547,15 @@ impl<T: Data> Window<T> {
         data: &T,
         env: &Env,
     ) {
+        enter_span!(DEBUG, "lifecycle", window = ?self.id);
         let mut base_state = BaseState::new(self.root.id());
+        let tree = self.tree_snapshot.clone();
         let mut ctx = LifeCycleCtx {
             command_queue: queue,
             window_id: self.id,
             base_state: &mut base_state,
+            window_visible: self.visible,
+            widget_tree: tree.as_deref(),
         };
 
         if let LifeCycle::AnimFrame(_) = event {
@@ -195,25 +571,85 @@ impl<T: Data> Window<T> {
         // potentially has jitter.
         //
         // See https://github.com/xi-editor/druid/issues/85 for discussion.
-        let now = Instant::now();
+        let now = self.now();
         let last = self.last_anim.take();
         let elapsed_ns = last.map(|t| now.duration_since(t).as_nanos()).unwrap_or(0) as u64;
 
         let event = LifeCycle::AnimFrame(elapsed_ns);
         self.root.lifecycle(ctx, &event, data, env);
-        if ctx.base_state.request_anim {
+        if ctx.base_state.request_anim || self.toasts.is_animating(now) {
             self.last_anim = Some(now);
         }
     }
 
-    pub(crate) fn update(&mut self, data: &T, env: &Env) {
+    /// Handle `event` if it is for this window's toasts, returning `true` if
+    /// it was.
+    fn toast_event(&mut self, event: &Event, env: &Env) -> bool {
+        let now = self.now();
+        match event {
+            Event::Command(cmd)
+            | Event::TargetedCommand(Target::Window(_), cmd)
+            | Event::TargetedCommand(Target::Global, cmd)
+                if cmd.is(sys_cmd::SHOW_TOAST) =>
+            {
+                match cmd.get_object::<Toast>() {
+                    Ok(toast) => {
+                        for deadline in self.toasts.show(toast.clone(), now, env) {
+                            let token = self.request_timer(deadline);
+                            self.toasts.add_timer(token);
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!(target: "druid::command", "show-toast object error: '{}'", e)
+                    }
+                }
+            }
+            Event::Timer(token) if self.toasts.timer_fired(*token, now) => (),
+            _ => return false,
+        }
+        self.handle.invalidate();
+        true
+    }
+
+    /// Collect a snapshot of the widget tree.
+    pub(crate) fn widget_tree(
+        &mut self,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) -> WidgetTree {
+        let cell = TreeCell::default();
+        self.lifecycle(queue, &LifeCycle::RouteInspectTree(cell.clone()), data, env);
+        cell.take()
+    }
+
+    fn request_timer(&self, deadline: Instant) -> TimerToken {
+        match self.clock.as_ref() {
+            Some(clock) => clock.request_timer(deadline),
+            None => self.handle.request_timer(deadline),
+        }
+    }
+
+    /// The current time, which is virtual when running in the test harness.
+    fn now(&self) -> Instant {
+        match self.clock.as_ref() {
+            Some(clock) => clock.now(),
+            None => Instant::now(),
+        }
+    }
+
+    pub(crate) fn update(&mut self, queue: &mut CommandQueue, data: &T, env: &Env) {
+        enter_span!(DEBUG, "update", window = ?self.id);
         self.update_title(data, env);
 
         let mut base_state = BaseState::new(self.root.id());
         let mut update_ctx = UpdateCtx {
+            command_queue: queue,
             base_state: &mut base_state,
             window: &self.handle,
+            clock: self.clock.as_deref(),
             window_id: self.id,
+            window_visible: self.visible,
         };
 
         self.root.update(&mut update_ctx, data, env);
@@ -228,29 +664,110 @@ impl<T: Data> Window<T> {
         if self.root.state().children_changed {
             self.lifecycle(queue, &LifeCycle::RouteWidgetAdded, data, env);
         }
-        if self.root.state().needs_inval {
+        let state = self.root.state();
+        if state.needs_inval {
+            // after a layout pass widgets may have moved, so we can't trust
+            // the invalid region.
+            if state.needs_layout || state.invalid.is_empty() {
+                self.handle.invalidate();
+            } else {
+                self.handle.invalidate_rect(state.invalid.to_rect());
+            }
+        }
+    }
+
+    /// Record whether the window can be seen, telling the widgets if this
+    /// has changed.
+    ///
+    /// Painting and animation stop while the window is hidden; when it is
+    /// shown again it is repainted, and animation resumes with an interval
+    /// of 0, as if it were starting.
+    pub(crate) fn set_visible(
+        &mut self,
+        visible: bool,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) {
+        if visible == self.visible {
+            return;
+        }
+        self.visible = visible;
+        if visible {
             self.handle.invalidate();
+        } else {
+            self.last_anim = None;
         }
+        self.lifecycle(
+            queue,
+            &LifeCycle::WindowVisibilityChanged(visible),
+            data,
+            env,
+        );
+    }
+
+    /// Record the window's scale factor, telling the widgets and relaying
+    /// out if it has changed.
+    pub(crate) fn set_scale(&mut self, scale: f64, queue: &mut CommandQueue, data: &T, env: &Env) {
+        if scale == self.scale {
+            return;
+        }
+        self.scale = scale;
+        self.lifecycle(queue, &LifeCycle::ScaleChanged(scale), data, env);
+        self.handle.invalidate();
     }
 
     /// Do all the stuff we do in response to a paint call from the system:
     /// layout, send an `AnimFrame` event, and then actually paint.
+    ///
+    /// Only the area inside `invalid_rect` is repainted.
     pub(crate) fn do_paint(
         &mut self,
         piet: &mut Piet,
+        invalid_rect: Rect,
         queue: &mut CommandQueue,
         data: &T,
         env: &Env,
     ) {
+        // the platform may still ask us to paint a hidden window.
+        if !self.visible {
+            return;
+        }
+        let frame_start = Instant::now();
+        let frame_interval = self.last_frame.map(|last| frame_start.duration_since(last));
+        self.last_frame = Some(frame_start);
+
         // FIXME: only do AnimFrame if root has requested_anim?
-        self.lifecycle(queue, &LifeCycle::AnimFrame(0), data, env);
+        // when the frame rate is capped, the next frame is due once its
+        // timer fires, even if we are painting for some other reason.
+        if self.anim_timer.is_none() {
+            self.lifecycle(queue, &LifeCycle::AnimFrame(0), data, env);
+        }
 
-        if self.root.state().needs_layout {
-            self.layout(piet, data, env);
+        let layout_start = Instant::now();
+        if self.root.state().needs_layout || self.size_to_content {
+            self.layout(piet, queue, data, env);
+            // anything may have moved; make sure we paint everything soon.
+            let window_rect = self.size.to_rect();
+            if invalid_rect.intersect(window_rect).area() < window_rect.area() {
+                self.handle.invalidate();
+            }
         }
 
-        piet.clear(env.get(crate::theme::WINDOW_BACKGROUND_COLOR));
-        self.paint(piet, data, env);
+        let layout_time = layout_start.elapsed();
+
+        let paint_start = Instant::now();
+        self.paint(piet, invalid_rect, data, env);
+        let paint_time = paint_start.elapsed();
+
+        self.frame_stats = FrameStats {
+            frame_time: frame_start.elapsed(),
+            layout_time,
+            paint_time,
+            frame_interval,
+            widget_count: self.root.state().descendant_count + 1,
+        };
+        stats::record(self.id, self.frame_stats);
 
         // If commands were submitted during anim frame, ask the handler
         // to call us back on idle so we can process them in a new event/update pass.
@@ -258,41 +775,128 @@ impl<T: Data> Window<T> {
             if let Some(mut handle) = self.handle.get_idle_handle() {
                 handle.schedule_idle(RUN_COMMANDS_TOKEN);
             } else {
-                log::error!("failed to get idle handle");
+                log::error!(target: "druid::window", "failed to get idle handle");
             }
         }
     }
 
-    fn layout(&mut self, piet: &mut Piet, data: &T, env: &Env) {
+    fn layout(&mut self, piet: &mut Piet, queue: &mut CommandQueue, data: &T, env: &Env) {
+        enter_span!(DEBUG, "layout", window = ?self.id);
+        if self.size_to_content {
+            self.size_to_content = false;
+            self.fit_to_content(piet, data, env);
+        }
+        let bc = BoxConstraints::tight(self.size);
+        self.layout_with_constraints(piet, &bc, data, env);
+        if self.root.state().viewport_changed {
+            self.lifecycle(queue, &LifeCycle::RouteViewportChanged, data, env);
+        }
+    }
+
+    /// Take the size the root widget prefers, and ask the platform to resize
+    /// the window to match.
+    fn fit_to_content(&mut self, piet: &mut Piet, data: &T, env: &Env) {
+        let min = self.min_size.unwrap_or(Size::ZERO);
+        let max = self
+            .max_size
+            .unwrap_or_else(|| Size::new(f64::INFINITY, f64::INFINITY));
+        let bc = BoxConstraints::new(min, max);
+        let size = self.layout_with_constraints(piet, &bc, data, env);
+        if !(size.width.is_finite() && size.height.is_finite()) {
+            log::warn!(
+                target: "druid::layout",
+                "can't size {:?} to its content, which is unbounded",
+                self.id
+            );
+            return;
+        }
+        if size != self.size {
+            self.size = size;
+            self.pending_resize = Some(size);
+        }
+    }
+
+    /// Lay out the root widget with arbitrary constraints, returning its size.
+    ///
+    /// Normally the constraints are the window's size; this is exposed for
+    /// the test harness.
+    pub(crate) fn layout_with_constraints(
+        &mut self,
+        piet: &mut Piet,
+        bc: &BoxConstraints,
+        data: &T,
+        env: &Env,
+    ) -> Size {
+        self.tree_snapshot = None;
         let mut layout_ctx = LayoutCtx {
             text_factory: piet.text(),
             window_id: self.id,
             paint_insets: Insets::ZERO,
+            cache_layout: true,
+            viewport_changed: false,
+            scroll_shift: Vec2::ZERO,
+            window_size: self.size,
+            probed_layout: false,
         };
-        let bc = BoxConstraints::tight(self.size);
-        let size = self.root.layout(&mut layout_ctx, &bc, data, env);
+        let size = self.root.layout(&mut layout_ctx, bc, data, env);
         self.root
             .set_layout_rect(Rect::from_origin_size(Point::ORIGIN, size));
+        size
     }
 
-    /// only expose `layout` for testing; normally it is called as part of `do_paint`
-    #[cfg(test)]
-    pub(crate) fn just_layout(&mut self, piet: &mut Piet, data: &T, env: &Env) {
-        self.layout(piet, data, env)
+    /// only expose `layout` for testing and benchmarking; normally it is
+    /// called as part of `do_paint`
+    #[cfg(any(test, feature = "test-harness"))]
+    pub(crate) fn just_layout(
+        &mut self,
+        piet: &mut Piet,
+        queue: &mut CommandQueue,
+        data: &T,
+        env: &Env,
+    ) {
+        self.layout(piet, queue, data, env)
     }
 
-    fn paint(&mut self, piet: &mut Piet, data: &T, env: &Env) {
+    fn paint(&mut self, piet: &mut Piet, invalid_rect: Rect, data: &T, env: &Env) {
+        enter_span!(DEBUG, "paint", window = ?self.id);
+        self.tree_snapshot = None;
+        let visible = invalid_rect.intersect(self.size.to_rect());
+        if let Err(e) = piet.save() {
+            log::error!(target: "druid::paint", "saving render context failed: {:?}", e);
+            return;
+        }
+        piet.clip(visible);
+        let background = match &self.background {
+            Some(color) => color.resolve(env),
+            None => env.get(theme::WINDOW_BACKGROUND_COLOR),
+        };
+        piet.clear(background);
+
+        let overlay_env;
+        let root_env = if self.debug_overlay_toggled {
+            overlay_env = env
+                .clone()
+                .adding(Env::DEBUG_PAINT, !env.get(Env::DEBUG_PAINT));
+            &overlay_env
+        } else {
+            env
+        };
+        let window_transform = piet.current_transform();
+
         let base_state = BaseState::new(self.root.id());
         let mut ctx = PaintCtx {
             render_ctx: piet,
             base_state: &base_state,
             window_id: self.id,
             z_ops: Vec::new(),
+            debug_boxes: Vec::new(),
+            record_paint: self.paint_log.is_some(),
             focus_widget: self.focus,
+            show_access_keys: self.show_access_keys,
             region: Rect::ZERO.into(),
+            window_scale: self.scale,
         };
-        let visible = Rect::from_origin_size(Point::ZERO, self.size);
-        ctx.with_child_ctx(visible, |ctx| self.root.paint(ctx, data, env));
+        ctx.with_child_ctx(visible, |ctx| self.root.paint(ctx, data, root_env));
 
         let mut z_ops = mem::take(&mut ctx.z_ops);
         z_ops.sort_by_key(|k| k.z_index);
@@ -305,6 +909,35 @@ impl<T: Data> Window<T> {
                 });
             });
         }
+
+        if let Some(log) = self.paint_log.as_mut() {
+            let to_window = window_transform.inverse();
+            *log = ctx
+                .debug_boxes
+                .iter()
+                .map(|debug_box| DebugBox {
+                    rect: to_window.transform_rect_bbox(debug_box.rect),
+                    id: debug_box.id,
+                    type_name: debug_box.type_name,
+                    outlined: debug_box.outlined,
+                })
+                .collect();
+        }
+
+        self.toasts
+            .paint(ctx.render_ctx, self.size, self.now(), env);
+
+        if ctx.debug_boxes.iter().any(|debug_box| debug_box.outlined) {
+            debug_overlay::paint(ctx.render_ctx, &ctx.debug_boxes, window_transform, env);
+        }
+
+        if env.get(Env::SHOW_FRAME_STATS) {
+            stats::paint_overlay(ctx.render_ctx, &self.frame_stats, env);
+        }
+
+        if let Err(e) = ctx.render_ctx.restore() {
+            log::error!(target: "druid::paint", "restoring render context failed: {:?}", e);
+        }
     }
 
     pub(crate) fn update_title(&mut self, data: &T, env: &Env) {
@@ -324,25 +957,32 @@ impl<T: Data> Window<T> {
         match focus {
             FocusChange::Resign => None,
             FocusChange::Focus(id) => Some(id),
-            FocusChange::Next => self
-                .focus
-                .and_then(|id| self.focus_chain().iter().position(|i| i == &id))
-                .map(|idx| {
+            // from nothing, tab goes to the first widget and back-tab to the last.
+            FocusChange::Next => match self.focus {
+                None => self.focus_chain().first().copied(),
+                Some(id) => self.focus_chain().iter().position(|i| i == &id).map(|idx| {
                     let next_idx = (idx + 1) % self.focus_chain().len();
                     self.focus_chain()[next_idx]
                 }),
-            FocusChange::Previous => self
-                .focus
-                .and_then(|id| self.focus_chain().iter().position(|i| i == &id))
-                .map(|idx| {
+            },
+            FocusChange::Previous => match self.focus {
+                None => self.focus_chain().last().copied(),
+                Some(id) => self.focus_chain().iter().position(|i| i == &id).map(|idx| {
                     let len = self.focus_chain().len();
                     let prev_idx = (idx + len - 1) % len;
                     self.focus_chain()[prev_idx]
                 }),
+            },
         }
     }
 }
 
+impl<T> Drop for Window<T> {
+    fn drop(&mut self) {
+        stats::remove(self.id);
+    }
+}
+
 impl WindowId {
     /// Allocate a new, unique window id.
     pub fn next() -> WindowId {
@@ -350,3 +990,68 @@ impl WindowId {
         WindowId(WINDOW_COUNTER.next())
     }
 }
+
+/// Whether access keys should be underlined after `event`, if it is a
+/// key event.
+fn shows_access_keys(event: &Event) -> Option<bool> {
+    if cfg!(target_os = "macos") {
+        return None;
+    }
+    match event {
+        Event::KeyDown(key) if is_alt(key.key_code) => Some(true),
+        Event::KeyUp(key) if is_alt(key.key_code) => Some(false),
+        Event::KeyDown(key) | Event::KeyUp(key) => Some(key.mods.alt),
+        _ => None,
+    }
+}
+
+/// The access key pressed by `key`, if it is one; access keys are pressed
+/// with alt, and no other modifiers except shift.
+fn access_key(key: &KeyEvent) -> Option<char> {
+    if cfg!(target_os = "macos") || !key.mods.alt || key.mods.ctrl || key.mods.meta {
+        return None;
+    }
+    if key.is_repeat {
+        return None;
+    }
+    let c = key.unmod_text()?.chars().next()?;
+    c.to_lowercase().next()
+}
+
+fn is_alt(key_code: KeyCode) -> bool {
+    matches!(key_code, KeyCode::LeftAlt | KeyCode::RightAlt)
+}
+
+/// The pinch that turning the wheel with ctrl held stands in for, on
+/// devices that can't pinch.
+fn wheel_pinch(wheel: &WheelEvent, mouse_pos: Option<Point>) -> Option<Gesture> {
+    if !wheel.mods.ctrl || wheel.delta.y == 0.0 {
+        return None;
+    }
+    // one notch of the wheel, 120 units, zooms by 10%.
+    let scale = 1.1f64.powf(-wheel.delta.y / 120.0);
+    mouse_pos.map(|center| Gesture::Pinch(GesturePinch { scale, center }))
+}
+
+/// A held mouse button, for telling clicks from drags.
+struct Press {
+    pos: Point,
+    time: Instant,
+    is_drag: bool,
+}
+
+/// `true` if `key` is the hotkey of the Edit menu's Copy item.
+/// The focus change tab or shift-tab asks for, if `key` is one of them.
+fn tab_focus_change(key: &KeyEvent) -> Option<FocusChange> {
+    if HotKey::new(None, KeyCode::Tab).matches(key) {
+        Some(FocusChange::Next)
+    } else if HotKey::new(SysMods::Shift, KeyCode::Tab).matches(key) {
+        Some(FocusChange::Previous)
+    } else {
+        None
+    }
+}
+
+fn is_copy_hotkey(key: &KeyEvent) -> bool {
+    !key.is_repeat && HotKey::new(SysMods::Cmd, "c").matches(key)
+}